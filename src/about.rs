@@ -0,0 +1,74 @@
+//! About-screen data embedded in the overlay
+//!
+//! The packer writes everything the shell needs for a standard About screen
+//! under the reserved `__about__/` overlay prefix:
+//!
+//! ```text
+//! __about__/about.json              - AboutData (metadata + paths below)
+//! __about__/<changelog file name>   - Changelog (optional)
+//! __about__/<license file name>     - Application license text (optional)
+//! __about__/THIRD_PARTY_NOTICES.txt - Generated from the bundle SBOM
+//! ```
+
+use crate::sbom::SbomPackage;
+use serde::{Deserialize, Serialize};
+
+/// Reserved overlay path prefix for About-screen data
+pub const ABOUT_PREFIX: &str = "__about__/";
+
+/// Overlay path of the About-screen descriptor
+pub const ABOUT_JSON_PATH: &str = "__about__/about.json";
+
+/// Overlay path of the generated third-party notices
+pub const THIRD_PARTY_NOTICES_PATH: &str = "__about__/THIRD_PARTY_NOTICES.txt";
+
+/// Application metadata shown on the About screen
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AboutInfo {
+    /// Application name
+    pub name: String,
+
+    /// Application version
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// Short description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Authors
+    #[serde(default)]
+    pub authors: Vec<String>,
+
+    /// Homepage URL
+    #[serde(default)]
+    pub homepage: Option<String>,
+
+    /// Application license (SPDX identifier)
+    #[serde(default)]
+    pub license: Option<String>,
+}
+
+/// Contents of `__about__/about.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AboutData {
+    /// Application metadata
+    #[serde(flatten)]
+    pub info: AboutInfo,
+
+    /// Overlay path of the changelog
+    #[serde(default)]
+    pub changelog: Option<String>,
+
+    /// Overlay path of the application license text
+    #[serde(default)]
+    pub license_text: Option<String>,
+
+    /// Overlay path of the third-party notices
+    #[serde(default)]
+    pub third_party_notices: Option<String>,
+
+    /// Bundled third-party packages
+    #[serde(default)]
+    pub third_party: Vec<SbomPackage>,
+}
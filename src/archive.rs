@@ -0,0 +1,446 @@
+//! Hardened extraction of downloaded archives
+//!
+//! Python distributions and `[[downloads]]` archives may come from
+//! user-configured mirrors, so their entries are untrusted. Every entry path
+//! goes through [`sanitize_entry_path`] before anything is written:
+//!
+//! - absolute paths and `..` components are rejected
+//! - symlinks and hard links must point inside the destination, resolved
+//!   from the real directory they are created in
+//! - device nodes and FIFOs are rejected
+//! - nothing is created or written through a symlink that leaves the
+//!   destination
+//!
+//! A rejected entry fails the whole extraction with
+//! [`PackError::UnsafeArchive`]; archives are not partially trusted.
+
+use crate::{PackError, PackResult};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Relative path an archive entry is extracted to
+///
+/// Strips `.` components and the first `strip_components` components.
+/// Returns `None` for entries that vanish by stripping (e.g. the top-level
+/// directory), and an error for absolute paths and `..` components.
+pub(crate) fn sanitize_entry_path(
+    path: &Path,
+    strip_components: usize,
+) -> PackResult<Option<PathBuf>> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(unsafe_entry(path, "contains '..'"));
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(unsafe_entry(path, "is absolute"));
+            }
+        }
+    }
+    if parts.len() <= strip_components {
+        return Ok(None);
+    }
+    Ok(Some(parts[strip_components..].iter().collect()))
+}
+
+/// Check that a symlink at `entry` (relative to the destination) pointing
+/// to `target` stays inside the destination
+///
+/// `root` is the canonical destination and `parent` the canonical
+/// directory the link is created in (see [`create_dir_inside`]), so links
+/// extracted earlier cannot make the link resolve elsewhere than checked.
+/// `..` components are only allowed at the start of `target`; existing
+/// paths the target descends through must resolve inside `root`.
+pub(crate) fn check_link_target(
+    root: &Path,
+    parent: &Path,
+    entry: &Path,
+    target: &Path,
+) -> PackResult<()> {
+    let outside = || {
+        unsafe_entry(
+            entry,
+            &format!("links outside the destination ({})", target.display()),
+        )
+    };
+    let mut resolved = parent.to_path_buf();
+    let mut descended = false;
+    for component in target.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if !descended => {
+                // `parent` is canonical: its ancestors are real directories
+                if resolved == root || !resolved.pop() || !resolved.starts_with(root) {
+                    return Err(outside());
+                }
+            }
+            Component::ParentDir => {
+                return Err(unsafe_entry(
+                    entry,
+                    &format!("links through '..' after a name ({})", target.display()),
+                ));
+            }
+            Component::Normal(part) => {
+                descended = true;
+                resolved.push(part);
+                if fs::symlink_metadata(&resolved).is_ok() {
+                    resolved = fs::canonicalize(&resolved).map_err(|_| outside())?;
+                    if !resolved.starts_with(root) {
+                        return Err(outside());
+                    }
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(unsafe_entry(
+                    entry,
+                    &format!("links to an absolute path ({})", target.display()),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Canonical directory `relative` below `root` (canonical), created if
+/// missing
+///
+/// Every existing component is resolved and checked to lie inside `root`
+/// before anything below it is created, so nothing is created or written
+/// through a link leaving the destination.
+pub(crate) fn create_dir_inside(root: &Path, relative: &Path, entry: &Path) -> PackResult<PathBuf> {
+    let mut current = root.to_path_buf();
+    for component in relative.components() {
+        let Component::Normal(part) = component else {
+            return Err(unsafe_entry(entry, "is not a plain relative path"));
+        };
+        let next = current.join(part);
+        match fs::symlink_metadata(&next) {
+            Ok(_) => {
+                current = fs::canonicalize(&next)
+                    .map_err(|_| unsafe_entry(entry, "is written through a dangling link"))?;
+                if !current.starts_with(root) {
+                    return Err(unsafe_entry(
+                        entry,
+                        "is written through a link outside the destination",
+                    ));
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                fs::create_dir(&next)?;
+                current = next;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(current)
+}
+
+/// Path of the file `relative` below `root` (canonical), in a canonical
+/// parent directory created with [`create_dir_inside`]
+///
+/// An existing link at the path is removed, so the file replaces it
+/// instead of being written through it.
+pub(crate) fn file_path_inside(root: &Path, relative: &Path) -> PackResult<(PathBuf, PathBuf)> {
+    let parent = create_dir_inside(root, relative.parent().unwrap_or(Path::new("")), relative)?;
+    let name = relative
+        .file_name()
+        .ok_or_else(|| unsafe_entry(relative, "has no file name"))?;
+    let path = parent.join(name);
+    if fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink()) {
+        fs::remove_file(&path)?;
+    }
+    Ok((parent, path))
+}
+
+fn unsafe_entry(path: &Path, reason: &str) -> PackError {
+    PackError::UnsafeArchive(format!("entry {} {}", path.display(), reason))
+}
+
+/// Unpack a tar stream into `dest`, enforcing the rules of this module
+///
+/// Returns the number of entries written. Directory modes are applied
+/// once every entry is written, so read-only directories can be filled.
+pub(crate) fn unpack_tar<R: Read>(
+    reader: R,
+    dest: &Path,
+    strip_components: usize,
+) -> PackResult<usize> {
+    fs::create_dir_all(dest)?;
+    let root = fs::canonicalize(dest)?;
+    let mut archive = tar::Archive::new(reader);
+    let mut count = 0;
+    let mut directory_modes = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let Some(relative) = sanitize_entry_path(&path, strip_components)? else {
+            continue;
+        };
+
+        let entry_type = entry.header().entry_type();
+        match entry_type {
+            tar::EntryType::Directory => {
+                let dir = create_dir_inside(&root, &relative, &relative)?;
+                if let Ok(mode) = entry.header().mode() {
+                    directory_modes.push((dir, mode));
+                }
+                continue;
+            }
+            tar::EntryType::Regular | tar::EntryType::Continuous | tar::EntryType::GNUSparse => {
+                let (_, full_path) = file_path_inside(&root, &relative)?;
+                entry.unpack(&full_path)?;
+            }
+            tar::EntryType::Symlink => {
+                let target = entry
+                    .link_name()?
+                    .ok_or_else(|| unsafe_entry(&relative, "is a symlink without a target"))?;
+                let (parent, full_path) = file_path_inside(&root, &relative)?;
+                check_link_target(&root, &parent, &relative, &target)?;
+                let _ = fs::remove_file(&full_path);
+                entry.unpack(&full_path)?;
+            }
+            tar::EntryType::Link => {
+                let target = entry
+                    .link_name()?
+                    .ok_or_else(|| unsafe_entry(&relative, "is a hard link without a target"))?;
+                let Some(target) = sanitize_entry_path(&target, strip_components)? else {
+                    return Err(unsafe_entry(
+                        &relative,
+                        "is a hard link to a stripped entry",
+                    ));
+                };
+                let (_, full_path) = file_path_inside(&root, &relative)?;
+                let source_parent =
+                    create_dir_inside(&root, target.parent().unwrap_or(Path::new("")), &relative)?;
+                let source = source_parent.join(target.file_name().unwrap_or_default());
+                let _ = fs::remove_file(&full_path);
+                fs::hard_link(&source, &full_path)?;
+            }
+            tar::EntryType::Char | tar::EntryType::Block | tar::EntryType::Fifo => {
+                return Err(unsafe_entry(&relative, "is a device node or FIFO"));
+            }
+            other => {
+                tracing::debug!("Skipping {:?} archive entry {}", other, relative.display());
+                continue;
+            }
+        }
+        count += 1;
+    }
+
+    // Deepest first, so a read-only parent does not block its children
+    directory_modes.sort_by_key(|(dir, _)| std::cmp::Reverse(dir.components().count()));
+    for (dir, mode) in directory_modes {
+        set_dir_mode(&dir, mode)?;
+    }
+    Ok(count)
+}
+
+#[cfg(unix)]
+fn set_dir_mode(dir: &Path, mode: u32) -> PackResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(dir, fs::Permissions::from_mode(mode & 0o777))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_dir_mode(dir: &Path, mode: u32) -> PackResult<()> {
+    let mut permissions = fs::metadata(dir)?.permissions();
+    permissions.set_readonly(mode & 0o222 == 0);
+    fs::set_permissions(dir, permissions)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_entry_path() {
+        let sanitize = |p: &str, n| sanitize_entry_path(Path::new(p), n);
+        assert_eq!(
+            sanitize("./python/bin/python3", 0).unwrap(),
+            Some(PathBuf::from("python/bin/python3"))
+        );
+        assert_eq!(
+            sanitize("pkg-1.0/bin/tool", 1).unwrap(),
+            Some(PathBuf::from("bin/tool"))
+        );
+        assert_eq!(sanitize("pkg-1.0/", 1).unwrap(), None);
+        assert!(sanitize("../etc/passwd", 0).is_err());
+        assert!(sanitize("pkg/../../x", 1).is_err());
+        assert!(sanitize("/etc/passwd", 0).is_err());
+    }
+
+    #[test]
+    fn test_check_link_target() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = fs::canonicalize(temp.path()).unwrap();
+        let check = |entry: &str, target: &str| {
+            let entry = Path::new(entry);
+            let parent = create_dir_inside(&root, entry.parent().unwrap(), entry).unwrap();
+            check_link_target(&root, &parent, entry, Path::new(target))
+        };
+        assert!(check("python/bin/python3", "python3.11").is_ok());
+        assert!(check("python/lib/libpython.so", "../bin/x").is_ok());
+        assert!(check("python/bin/python3", "../../python/bin/x").is_ok());
+        assert!(check("python/bin/python3", "../../../etc/passwd").is_err());
+        assert!(check("python/bin/python3", "x/../../../y").is_err());
+        assert!(check("link", "..").is_err());
+        assert!(check("link", "/etc/passwd").is_err());
+    }
+
+    /// Tar with raw entries (bypassing the builder's own path checks)
+    fn tar(entries: &[(&str, tar::EntryType, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, kind, data) in entries {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+            header.set_entry_type(*kind);
+            header.set_mode(0o644);
+            let content = match kind {
+                tar::EntryType::Symlink | tar::EntryType::Link => {
+                    header.as_old_mut().linkname[..data.len()].copy_from_slice(data.as_bytes());
+                    &b""[..]
+                }
+                _ => data.as_bytes(),
+            };
+            header.set_size(content.len() as u64);
+            header.set_cksum();
+            builder.append(&header, content).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn unpack(
+        entries: &[(&str, tar::EntryType, &str)],
+        strip: usize,
+    ) -> (tempfile::TempDir, PackResult<usize>) {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dest = temp.path().join("dest");
+        let result = unpack_tar(&tar(entries)[..], &dest, strip);
+        (temp, result)
+    }
+
+    #[test]
+    fn test_unpack_tar_extracts_safe_archive() {
+        use tar::EntryType::*;
+        let (temp, result) = unpack(
+            &[
+                ("pkg/", Directory, ""),
+                ("pkg/bin/tool", Regular, "tool"),
+                ("pkg/bin/alias", Symlink, "tool"),
+                ("pkg/lib/tool", Link, "pkg/bin/tool"),
+            ],
+            1,
+        );
+        assert_eq!(result.unwrap(), 3);
+        let dest = temp.path().join("dest");
+        assert_eq!(fs::read_to_string(dest.join("bin/tool")).unwrap(), "tool");
+        assert_eq!(fs::read_to_string(dest.join("lib/tool")).unwrap(), "tool");
+        #[cfg(unix)]
+        assert_eq!(fs::read_to_string(dest.join("bin/alias")).unwrap(), "tool");
+    }
+
+    #[test]
+    fn test_unpack_tar_rejects_malicious_entries() {
+        use tar::EntryType::*;
+        let cases: &[&[(&str, tar::EntryType, &str)]] = &[
+            &[("../evil", Regular, "x")],
+            &[("/tmp/evil", Regular, "x")],
+            &[("escape", Symlink, "../../outside")],
+            &[("a", Symlink, "."), ("a/b", Symlink, "..")],
+            &[("a", Symlink, "."), ("a/a/b", Symlink, "../evil")],
+            &[("abs", Symlink, "/etc/passwd")],
+            &[("hard", Link, "../outside")],
+            &[("dev", Char, "")],
+            &[("fifo", Fifo, "")],
+        ];
+        for entries in cases {
+            let (temp, result) = unpack(entries, 0);
+            assert!(
+                matches!(result, Err(PackError::UnsafeArchive(_))),
+                "{:?}: {:?}",
+                entries,
+                result
+            );
+            assert!(!temp.path().join("evil").exists());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unpack_tar_rejects_writes_through_outside_links() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dest = temp.path().join("dest");
+        let outside = temp.path().join("outside");
+        fs::create_dir_all(&dest).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        // A link left behind by an earlier extraction
+        std::os::unix::fs::symlink(&outside, dest.join("lib")).unwrap();
+
+        let archive = tar(&[("lib/evil.so", tar::EntryType::Regular, "x")]);
+        let result = unpack_tar(&archive[..], &dest, 0);
+        assert!(
+            matches!(result, Err(PackError::UnsafeArchive(_))),
+            "{:?}",
+            result
+        );
+        assert!(!outside.join("evil.so").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unpack_tar_creates_nothing_through_outside_links() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dest = temp.path().join("dest");
+        let outside = temp.path().join("outside");
+        fs::create_dir_all(&dest).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, dest.join("lib")).unwrap();
+
+        for entries in [
+            &[("lib/sub/", tar::EntryType::Directory, "")][..],
+            &[("lib/sub/file", tar::EntryType::Regular, "x")][..],
+            &[("lib/sub/hard", tar::EntryType::Link, "other")][..],
+        ] {
+            let result = unpack_tar(&tar(entries)[..], &dest, 0);
+            assert!(
+                matches!(result, Err(PackError::UnsafeArchive(_))),
+                "{:?}",
+                result
+            );
+            assert!(!outside.join("sub").exists());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unpack_tar_restores_directory_modes() {
+        use std::os::unix::fs::PermissionsExt;
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_mode(0o555);
+        header.set_size(0);
+        builder.append_data(&mut header, "ro/", &b""[..]).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(0o644);
+        header.set_size(1);
+        builder
+            .append_data(&mut header, "ro/file", &b"x"[..])
+            .unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let dest = temp.path().join("dest");
+        assert_eq!(unpack_tar(&archive[..], &dest, 0).unwrap(), 1);
+        let mode = fs::metadata(dest.join("ro")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o555);
+        assert_eq!(fs::read_to_string(dest.join("ro/file")).unwrap(), "x");
+        fs::set_permissions(dest.join("ro"), fs::Permissions::from_mode(0o755)).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+//! Pack-time HTML branding rewrite
+//!
+//! Rewrites `<title>` and branding `<meta>` tags (`theme-color`,
+//! `description`) of the bundled `index.html` so the in-app document matches
+//! the window branding without rebuilding the frontend.
+
+use crate::bundle::AssetBundle;
+
+/// Branding values applied to the bundled `index.html`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlBranding {
+    /// Document title (`<title>`)
+    pub title: Option<String>,
+
+    /// Brand color (`<meta name="theme-color">`)
+    pub theme_color: Option<String>,
+
+    /// Description (`<meta name="description">`)
+    pub description: Option<String>,
+}
+
+impl HtmlBranding {
+    /// Check if there is nothing to rewrite
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.theme_color.is_none() && self.description.is_none()
+    }
+
+    /// Rewrite an HTML document
+    pub fn apply(&self, html: &str) -> String {
+        let mut html = html.to_string();
+
+        if let Some(ref title) = self.title {
+            html = set_title(&html, title);
+        }
+        if let Some(ref color) = self.theme_color {
+            html = set_meta(&html, "theme-color", color);
+        }
+        if let Some(ref description) = self.description {
+            html = set_meta(&html, "description", description);
+        }
+
+        html
+    }
+
+    /// Rewrite `index.html` in a bundle
+    ///
+    /// Returns `true` if the document was rewritten. Non-UTF-8 documents are
+    /// left untouched.
+    pub fn apply_to_bundle(&self, bundle: &mut AssetBundle) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let Some(content) = bundle.get("index.html") else {
+            return false;
+        };
+        let Ok(html) = std::str::from_utf8(content) else {
+            tracing::warn!("index.html is not valid UTF-8, skipping branding rewrite");
+            return false;
+        };
+
+        let rewritten = self.apply(html);
+        if rewritten == html {
+            return false;
+        }
+
+        tracing::info!("Applied branding to index.html");
+        bundle.replace("index.html", rewritten.into_bytes())
+    }
+}
+
+/// Replace the `<title>` content, or insert a title into `<head>`
+fn set_title(html: &str, title: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    let escaped = escape_html(title);
+
+    if let Some(open) = lower.find("<title") {
+        if let Some(open_end) = lower[open..].find('>').map(|i| open + i + 1) {
+            if let Some(close) = lower[open_end..].find("</title>").map(|i| open_end + i) {
+                return format!("{}{}{}", &html[..open_end], escaped, &html[close..]);
+            }
+        }
+    }
+
+    insert_into_head(html, &format!("<title>{}</title>", escaped))
+}
+
+/// Replace `<meta name="...">`, or insert it into `<head>`
+fn set_meta(html: &str, name: &str, content: &str) -> String {
+    let tag = format!(
+        "<meta name=\"{}\" content=\"{}\">",
+        name,
+        escape_html(content)
+    );
+    let lower = html.to_ascii_lowercase();
+
+    let mut search_from = 0;
+    while let Some(start) = lower[search_from..].find("<meta").map(|i| search_from + i) {
+        let Some(end) = lower[start..].find('>').map(|i| start + i + 1) else {
+            break;
+        };
+        if attribute_value(&lower[start..end], "name").as_deref() == Some(name) {
+            return format!("{}{}{}", &html[..start], tag, &html[end..]);
+        }
+        search_from = end;
+    }
+
+    insert_into_head(html, &tag)
+}
+
+/// Insert a tag at the start of `<head>` (or the document if there is none)
+fn insert_into_head(html: &str, tag: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    let head = lower
+        .match_indices("<head")
+        .find(|(i, _)| {
+            // Skip <header>
+            matches!(
+                lower.as_bytes().get(i + 5),
+                Some(b'>') | Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r')
+            )
+        })
+        .and_then(|(i, _)| lower[i..].find('>').map(|j| i + j + 1));
+
+    match head {
+        Some(pos) => format!("{}\n    {}{}", &html[..pos], tag, &html[pos..]),
+        None => format!("{}\n{}", tag, html),
+    }
+}
+
+/// Extract an attribute value from a (lowercased) tag
+fn attribute_value(tag: &str, attr: &str) -> Option<String> {
+    let pattern = format!("{}=", attr);
+    let mut search_from = 0;
+    while let Some(pos) = tag[search_from..].find(&pattern).map(|i| search_from + i) {
+        search_from = pos + pattern.len();
+        // Require an attribute boundary (avoid matching e.g. "data-name=")
+        if !tag[..pos].ends_with(|c: char| c.is_ascii_whitespace()) {
+            continue;
+        }
+        let rest = &tag[search_from..];
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => rest[1..].split(quote).next().unwrap_or_default(),
+            _ => rest
+                .split(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+                .next()
+                .unwrap_or_default(),
+        };
+        return Some(value.trim().to_string());
+    }
+    None
+}
+
+/// Escape text for use in HTML content and attribute values
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_existing_title_and_meta() {
+        let html = r##"<html><head><TITLE>Vite App</TITLE><meta name="theme-color" content="#fff"></head></html>"##;
+        let branding = HtmlBranding {
+            title: Some("My App".to_string()),
+            theme_color: Some("#1e88e5".to_string()),
+            description: None,
+        };
+
+        let out = branding.apply(html);
+        assert!(out.contains("<TITLE>My App</TITLE>"));
+        assert!(out.contains(r##"<meta name="theme-color" content="#1e88e5">"##));
+        assert!(!out.contains("#fff"));
+    }
+
+    #[test]
+    fn test_insert_missing_tags() {
+        let html = "<html>\n<head lang=\"en\">\n</head><body><header></header></body></html>";
+        let branding = HtmlBranding {
+            title: Some("A & B".to_string()),
+            theme_color: None,
+            description: Some("Tool".to_string()),
+        };
+
+        let out = branding.apply(html);
+        let head = out.find("<head lang").unwrap();
+        let title = out.find("<title>A &amp; B</title>").unwrap();
+        let meta = out
+            .find(r#"<meta name="description" content="Tool">"#)
+            .unwrap();
+        assert!(head < title && head < meta);
+        assert!(title < out.find("</head>").unwrap());
+    }
+
+    #[test]
+    fn test_attribute_boundary() {
+        let html = r#"<head><meta data-name="theme-color" content="x"></head>"#;
+        let branding = HtmlBranding {
+            theme_color: Some("#000".to_string()),
+            ..Default::default()
+        };
+
+        let out = branding.apply(html);
+        assert!(out.contains(r#"data-name="theme-color" content="x""#));
+        assert!(out.contains(r##"<meta name="theme-color" content="#000">"##));
+    }
+}
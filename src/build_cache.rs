@@ -0,0 +1,415 @@
+//! Incremental packing
+//!
+//! Compressing assets at high zstd levels dominates repeated packs of the
+//! same app. With `[build.incremental]`, every compressed asset is kept in a
+//! build cache keyed by its content hash, codec and level, so the next pack
+//! only compresses what changed (frontend files, Python files, downloads
+//! alike):
+//!
+//! ```text
+//! <cache>/AuroraView/build-cache/<app>/
+//! ├── hashes.json                  - FileHashCache: overlay path -> content hash
+//! ├── files.json                   - Source file size and mtime -> content hash
+//! ├── blobs.lock                   - Shared by packs using the cache
+//! └── blobs/<hash>.<codec>.<level> - Compressed asset data
+//! ```
+//!
+//! Source files whose size and modification time are unchanged are not
+//! hashed again. Blobs are checked against their content hash before reuse.
+//! Once the cache outgrows `max_size_mb`, the least recently used blobs not
+//! needed by the latest pack are evicted, so packs of several profiles
+//! sharing a cache keep each other's blobs. Every pack holds `blobs.lock`
+//! shared while it uses the cache; eviction only runs when no other pack
+//! does.
+
+use crate::cache_lock::{write_atomic, CacheLock};
+use crate::codec::Codec;
+use crate::context::unix_nanos;
+use crate::deps_collector::FileHashCache;
+use crate::hashing::{digest_file, Digests};
+use crate::identity::AppIdentity;
+use crate::overlay::AssetIndexEntry;
+use crate::PackResult;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// File of the asset hashes of the latest pack
+const HASHES_FILE: &str = "hashes.json";
+
+/// File of the content hashes of source files
+const FILES_FILE: &str = "files.json";
+
+/// Directory of the compressed blobs
+const BLOBS_DIR: &str = "blobs";
+
+/// Default size limit of the blobs
+const DEFAULT_MAX_SIZE_MB: u64 = 1024;
+
+/// Files modified this recently may change again within the timestamp
+/// granularity of the file system, so their hashes are not kept
+const RACY_MTIME: Duration = Duration::from_secs(2);
+
+/// Incremental packing configuration
+///
+/// Located at `[build.incremental]` in TOML. Pack-time only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IncrementalConfig {
+    /// Reuse compressed assets of earlier packs
+    pub enabled: bool,
+
+    /// Build cache directory (default: `<cache>/AuroraView/build-cache/<app>`)
+    pub dir: Option<PathBuf>,
+
+    /// Evict least recently used blobs beyond this size (default: 1024)
+    pub max_size_mb: Option<u64>,
+}
+
+impl Default for IncrementalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: None,
+            max_size_mb: Some(DEFAULT_MAX_SIZE_MB),
+        }
+    }
+}
+
+impl IncrementalConfig {
+    /// Build cache directory of an app
+    pub fn cache_dir(&self, identity: &AppIdentity) -> PathBuf {
+        self.dir.clone().unwrap_or_else(|| {
+            dirs::cache_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("AuroraView")
+                .join("build-cache")
+                .join(identity.cache_key())
+        })
+    }
+}
+
+/// Asset reuse of one pack
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncrementalStats {
+    /// Assets taken from the build cache
+    pub reused: usize,
+    /// Assets compressed in this pack
+    pub compressed: usize,
+    /// Uncompressed bytes of the reused assets
+    pub reused_bytes: u64,
+    /// Overlay paths whose content changed since the previous pack
+    /// (including new paths)
+    pub changed: usize,
+}
+
+/// Content hash of a source file, valid while its size and modification
+/// time are unchanged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStat {
+    size: u64,
+    /// Nanoseconds since the Unix epoch
+    modified: u128,
+    hash: String,
+    /// SHA-256 of the content (empty in caches written before it was kept)
+    #[serde(default)]
+    sha256: String,
+}
+
+impl FileStat {
+    /// Size and modification time of a file
+    fn of(metadata: &fs::Metadata) -> Option<(u64, u128)> {
+        Some((metadata.len(), unix_nanos(metadata.modified().ok()?)))
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    previous: FileHashCache,
+    files: HashMap<PathBuf, FileStat>,
+    stats: IncrementalStats,
+    /// Shared lock keeping other packs from evicting blobs in use
+    in_use: Option<CacheLock>,
+}
+
+/// Build cache of compressed assets
+///
+/// Cloning shares the cache: the packer keeps one handle and gives a clone
+/// to the overlay writer.
+#[derive(Debug, Clone)]
+pub struct BuildCache {
+    dir: PathBuf,
+    max_size: Option<u64>,
+    state: Arc<Mutex<State>>,
+}
+
+impl BuildCache {
+    /// Open (or create) the build cache at `dir`
+    pub fn open(dir: impl Into<PathBuf>) -> PackResult<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join(BLOBS_DIR))?;
+        let in_use = CacheLock::acquire_shared(&dir.join(BLOBS_DIR))?;
+        let previous = FileHashCache::load(&dir.join(HASHES_FILE)).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable build cache index: {}", e);
+            FileHashCache::new()
+        });
+        let files = fs::read(dir.join(FILES_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+        Ok(Self {
+            dir,
+            max_size: Some(DEFAULT_MAX_SIZE_MB * 1024 * 1024),
+            state: Arc::new(Mutex::new(State {
+                previous,
+                files,
+                stats: IncrementalStats::default(),
+                in_use: Some(in_use),
+            })),
+        })
+    }
+
+    /// Evict least recently used blobs beyond `max_size` bytes (`None`:
+    /// keep every blob)
+    pub fn with_max_size(mut self, max_size: Option<u64>) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Cache directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Asset reuse so far
+    pub fn stats(&self) -> IncrementalStats {
+        self.lock().stats
+    }
+
+    /// Start counting a new overlay write
+    pub(crate) fn reset_stats(&self) {
+        self.lock().stats = IncrementalStats::default();
+    }
+
+    /// Content hashes and size of a source file, hashing it only if its
+    /// size or modification time changed since it was last hashed
+    pub(crate) fn digest_file(&self, file: &Path) -> PackResult<Digests> {
+        let stat = FileStat::of(&fs::metadata(file)?);
+        if let Some((size, modified)) = stat {
+            let state = self.lock();
+            if let Some(known) = state.files.get(file) {
+                if known.size == size && known.modified == modified && !known.sha256.is_empty() {
+                    return Ok(Digests {
+                        blake3: known.hash.clone(),
+                        sha256: known.sha256.clone(),
+                        size,
+                    });
+                }
+            }
+        }
+
+        let digests = digest_file(file)?;
+        if let Some((stat_size, modified)) = stat {
+            let settled = SystemTime::UNIX_EPOCH
+                .elapsed()
+                .is_ok_and(|now| now.as_nanos() >= modified + RACY_MTIME.as_nanos());
+            if settled && stat_size == digests.size {
+                self.lock().files.insert(
+                    file.to_path_buf(),
+                    FileStat {
+                        size: digests.size,
+                        modified,
+                        hash: digests.blake3.clone(),
+                        sha256: digests.sha256.clone(),
+                    },
+                );
+            }
+        }
+        Ok(digests)
+    }
+
+    /// Verified compressed data of a content, if cached
+    pub(crate) fn load(&self, hash: &str, size: u64, codec: Codec, level: i32) -> Option<Vec<u8>> {
+        let path = self.blob_path(hash, codec, level);
+        let stored = fs::read(&path).ok()?;
+        let valid = self.verified(&path, &stored[..], hash, size, codec);
+        valid.then(|| {
+            touch(&path);
+            stored
+        })
+    }
+
+    /// Verified compressed data of a content, if cached, opened at its start
+    ///
+    /// The open blob stays readable even if it is evicted afterwards.
+    pub(crate) fn find(&self, hash: &str, size: u64, codec: Codec, level: i32) -> Option<File> {
+        let path = self.blob_path(hash, codec, level);
+        let mut file = File::open(&path).ok()?;
+        if !self.verified(&path, BufReader::new(&file), hash, size, codec) {
+            return None;
+        }
+        file.seek(SeekFrom::Start(0)).ok()?;
+        touch(&path);
+        Some(file)
+    }
+
+    /// Store compressed data of a content
+    pub(crate) fn store(&self, hash: &str, codec: Codec, level: i32, stored: &[u8]) {
+        if let Err(e) = write_atomic(&self.blob_path(hash, codec, level), stored) {
+            tracing::warn!("Failed to update build cache: {}", e);
+        }
+    }
+
+    /// Compress a file into the cache and return the open blob
+    pub(crate) fn compress_file(
+        &self,
+        file: &Path,
+        hash: &str,
+        codec: Codec,
+        level: i32,
+    ) -> PackResult<File> {
+        let path = self.blob_path(hash, codec, level);
+        let tmp = crate::cache_lock::temp_sibling(&path)?;
+        {
+            let mut writer = std::io::BufWriter::new(File::create(&tmp)?);
+            codec.encode(level, &mut BufReader::new(File::open(file)?), &mut writer)?;
+            writer.flush()?;
+        }
+        tmp.persist(&path).map_err(|e| e.error)?;
+        Ok(File::open(&path)?)
+    }
+
+    /// Count an asset taken from the cache
+    pub(crate) fn note_reused(&self, size: u64) {
+        let mut state = self.lock();
+        state.stats.reused += 1;
+        state.stats.reused_bytes += size;
+    }
+
+    /// Count an asset compressed in this pack
+    pub(crate) fn note_compressed(&self) {
+        self.lock().stats.compressed += 1;
+    }
+
+    /// Record the assets of a finished overlay and evict blobs beyond the
+    /// size limit
+    pub(crate) fn commit(&self, index: &[AssetIndexEntry]) -> PackResult<()> {
+        let mut state = self.lock();
+        let mut current = FileHashCache::new();
+        for entry in index {
+            current
+                .hashes
+                .insert(entry.path.clone(), entry.hash.clone());
+        }
+        state.stats.changed = current
+            .hashes
+            .iter()
+            .filter(|(path, hash)| state.previous.hashes.get(*path) != Some(*hash))
+            .count();
+        current.save(&self.dir.join(HASHES_FILE))?;
+        state.files.retain(|path, _| path.is_file());
+        let saved = serde_json::to_vec(&state.files)
+            .map_err(Into::into)
+            .and_then(|content| write_atomic(&self.dir.join(FILES_FILE), &content));
+        if let Err(e) = saved {
+            tracing::warn!("Failed to save build cache file hashes: {}", e);
+        }
+
+        // Give up this pack's share so eviction can lock out other packs
+        let used: HashSet<&str> = current.hashes.values().map(String::as_str).collect();
+        state.in_use = None;
+        let evicted = self.evict(&used);
+        state.in_use = Some(CacheLock::acquire_shared(&self.dir.join(BLOBS_DIR))?);
+        evicted?;
+        state.previous = current;
+        Ok(())
+    }
+
+    /// Remove the least recently used blobs not in `used` until the blobs
+    /// fit in the size limit
+    fn evict(&self, used: &HashSet<&str>) -> PackResult<()> {
+        let Some(max_size) = self.max_size else {
+            return Ok(());
+        };
+        let Some(_lock) = CacheLock::try_acquire(&self.dir.join(BLOBS_DIR))? else {
+            tracing::debug!("Build cache in use by another pack; not evicting");
+            return Ok(());
+        };
+
+        let mut blobs = Vec::new();
+        for blob in fs::read_dir(self.dir.join(BLOBS_DIR))? {
+            let blob = blob?;
+            let metadata = blob.metadata()?;
+            let name = blob.file_name();
+            let hash = name.to_str().and_then(|n| n.split('.').next());
+            let last_used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            let keep = hash.is_some_and(|h| used.contains(h));
+            blobs.push((last_used, metadata.len(), keep, blob.path()));
+        }
+        let mut total: u64 = blobs.iter().map(|b| b.1).sum();
+        blobs.sort_by_key(|b| b.0);
+
+        let mut evicted = 0;
+        for (_, size, keep, path) in blobs {
+            if total <= max_size {
+                break;
+            }
+            if !keep && fs::remove_file(&path).is_ok() {
+                total -= size;
+                evicted += 1;
+            }
+        }
+        if evicted > 0 {
+            tracing::debug!("Evicted {} least recently used build cache blobs", evicted);
+        }
+        Ok(())
+    }
+
+    fn blob_path(&self, hash: &str, codec: Codec, level: i32) -> PathBuf {
+        self.dir
+            .join(BLOBS_DIR)
+            .join(format!("{}.{}.{}", hash, codec.as_str(), level))
+    }
+
+    /// Whether `stored` decompresses to the content `hash`; removes the
+    /// blob at `path` if not
+    fn verified(
+        &self,
+        path: &Path,
+        stored: impl Read + Send,
+        hash: &str,
+        size: u64,
+        codec: Codec,
+    ) -> bool {
+        let check = || -> PackResult<bool> {
+            let mut hasher = blake3::Hasher::new();
+            let copied = std::io::copy(&mut codec.decoder(BufReader::new(stored))?, &mut hasher)?;
+            Ok(copied == size && hasher.finalize().to_hex().as_str() == hash)
+        };
+        let valid = check().unwrap_or(false);
+        if !valid {
+            tracing::warn!("Discarding corrupted build cache blob {}", path.display());
+            let _ = fs::remove_file(path);
+        }
+        valid
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Mark a blob as used now, for eviction
+fn touch(path: &Path) {
+    let result = File::options()
+        .write(true)
+        .open(path)
+        .and_then(|f| f.set_modified(SystemTime::now()));
+    if let Err(e) = result {
+        tracing::debug!("Failed to touch {}: {}", path.display(), e);
+    }
+}
@@ -1,8 +1,19 @@
 //! Asset bundling for frontend mode
+//!
+//! `[frontend] include` / `exclude` globs select the bundled files. A
+//! pattern without `/` matches a file or directory name at any depth
+//! (`*.mp4`, `videos`); a pattern with `/` matches the path from the
+//! frontend root (`assets/video/**`). When `include` is set, only files
+//! matching one of its patterns are bundled, and those are kept even if a
+//! built-in exclude (`*.map`, `.DS_Store`, ...) would skip them. `exclude`
+//! always wins.
 
-use crate::{PackError, PackResult};
+use crate::parallel::WorkerPool;
+use crate::{AssetTransform, PackError, PackResult};
+use rayon::prelude::*;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use walkdir::WalkDir;
 
 /// Collection of assets to be embedded
@@ -27,6 +38,26 @@ impl AssetBundle {
         self.total_size += content_len;
     }
 
+    /// Get the content of an asset by path
+    pub fn get(&self, path: &str) -> Option<&[u8]> {
+        self.assets
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, content)| content.as_slice())
+    }
+
+    /// Replace the content of an existing asset
+    ///
+    /// Returns `false` if no asset exists at `path`.
+    pub fn replace(&mut self, path: &str, content: Vec<u8>) -> bool {
+        let Some(entry) = self.assets.iter_mut().find(|(p, _)| p == path) else {
+            return false;
+        };
+        self.total_size = self.total_size - entry.1.len() as u64 + content.len() as u64;
+        entry.1 = content;
+        true
+    }
+
     /// Get all assets
     pub fn assets(&self) -> &[(String, Vec<u8>)] {
         &self.assets
@@ -61,6 +92,14 @@ pub struct BundleBuilder {
     extensions: Vec<String>,
     /// Patterns to exclude
     exclude_patterns: Vec<String>,
+    /// Globs of files to include (empty = all)
+    include_globs: Vec<String>,
+    /// Globs of files and directories to exclude
+    exclude_globs: Vec<String>,
+    /// Threads reading files (`None` = all cores)
+    threads: Option<usize>,
+    /// Transform applied to every file read (`[frontend.transform]`)
+    transform: Option<Arc<dyn AssetTransform>>,
 }
 
 impl BundleBuilder {
@@ -76,6 +115,10 @@ impl BundleBuilder {
                 "Thumbs.db".to_string(),
                 "*.map".to_string(),
             ],
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            threads: None,
+            transform: None,
         }
     }
 
@@ -92,26 +135,112 @@ impl BundleBuilder {
         self
     }
 
+    /// Only bundle files matching one of these globs (`[frontend] include`)
+    pub fn include_globs(mut self, patterns: &[String]) -> Self {
+        self.include_globs.extend(patterns.iter().cloned());
+        self
+    }
+
+    /// Skip files and directories matching these globs
+    /// (`[frontend] exclude`)
+    pub fn exclude_globs(mut self, patterns: &[String]) -> Self {
+        self.exclude_globs.extend(patterns.iter().cloned());
+        self
+    }
+
+    /// Read files with at most `threads` threads
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Transform every file as it is read (minify, optimize images, ...)
+    pub fn with_transform(mut self, transform: impl AssetTransform + 'static) -> Self {
+        self.transform = Some(Arc::new(transform));
+        self
+    }
+
     /// Build the asset bundle
+    ///
+    /// Files are read (and transformed) in parallel; assets keep the order
+    /// of [`Self::list`].
     pub fn build(&self) -> PackResult<AssetBundle> {
-        if !self.root.exists() {
-            return Err(PackError::FrontendNotFound(self.root.clone()));
+        let files = self.list()?;
+        let contents = WorkerPool::new(self.threads)?.install(|| {
+            files
+                .par_iter()
+                .map(|(relative, path)| {
+                    let content = fs::read(path)?;
+                    let Some(ref transform) = self.transform else {
+                        return Ok((content, 0));
+                    };
+                    Ok(match transform.transform(relative, &content)? {
+                        Some(transformed) => {
+                            let saved = content.len() as i64 - transformed.len() as i64;
+                            (transformed, saved)
+                        }
+                        None => (content, 0),
+                    })
+                })
+                .collect::<PackResult<Vec<_>>>()
+        })?;
+
+        let transformed = contents.iter().filter(|(_, saved)| *saved != 0).count();
+        if transformed > 0 {
+            tracing::info!(
+                "Transformed {} assets, saving {} bytes",
+                transformed,
+                contents.iter().map(|(_, saved)| saved).sum::<i64>()
+            );
         }
+        let contents = contents.into_iter().map(|(content, _)| content);
 
         let mut bundle = AssetBundle::new();
+        for ((relative, _), content) in files.into_iter().zip(contents) {
+            tracing::debug!("Adding asset: {} ({} bytes)", relative, content.len());
+            bundle.add(relative, content);
+        }
+
+        tracing::info!(
+            "Bundle created: {} files, {} bytes total",
+            bundle.len(),
+            bundle.total_size()
+        );
+
+        Ok(bundle)
+    }
+
+    /// List the files the bundle would contain as (relative_path, source)
+    /// pairs, without reading them
+    pub fn list(&self) -> PackResult<Vec<(String, PathBuf)>> {
+        if !self.root.exists() {
+            return Err(PackError::FrontendNotFound(self.root.clone()));
+        }
 
         // If root is a file, just add it as index.html
         if self.root.is_file() {
-            let content = fs::read(&self.root)?;
-            bundle.add("index.html", content);
-            return Ok(bundle);
+            return Ok(vec![("index.html".to_string(), self.root.clone())]);
         }
 
+        let include = compile_globs(&self.include_globs, "include")?;
+        let exclude = compile_globs(&self.exclude_globs, "exclude")?;
+        let mut files = Vec::new();
+
         // Walk directory
+        // Sorted, so bundles do not depend on directory order
         for entry in WalkDir::new(&self.root)
             .follow_links(true)
+            .sort_by_file_name()
             .into_iter()
-            .filter_entry(|e| !self.should_exclude(e))
+            .filter_entry(|e| {
+                let relative = self.relative_path(e.path());
+                if e.depth() > 0 && matches_any(&exclude, &relative) {
+                    return false;
+                }
+                // Included files bypass the built-in excludes
+                let included = e.file_type().is_file() && matches_any(&include, &relative);
+                included || !self.should_exclude(e)
+            })
         {
             let entry = entry.map_err(|e| PackError::Bundle(e.to_string()))?;
 
@@ -129,35 +258,29 @@ impl BundleBuilder {
                 }
             }
 
-            // Get relative path
-            let relative = path
-                .strip_prefix(&self.root)
-                .map_err(|e| PackError::Bundle(e.to_string()))?;
-
-            // Normalize path separators to forward slashes
-            let relative_str = relative.to_string_lossy().replace('\\', "/");
-
-            // Read content
-            let content = fs::read(path)?;
-
-            tracing::debug!("Adding asset: {} ({} bytes)", relative_str, content.len());
-            bundle.add(relative_str, content);
+            let relative = self.relative_path(path);
+            if !include.is_empty() && !matches_any(&include, &relative) {
+                continue;
+            }
+            files.push((relative, path.to_path_buf()));
         }
 
-        if bundle.is_empty() {
+        if files.is_empty() {
             return Err(PackError::Bundle(format!(
                 "No assets found in: {}",
                 self.root.display()
             )));
         }
 
-        tracing::info!(
-            "Bundle created: {} files, {} bytes total",
-            bundle.len(),
-            bundle.total_size()
-        );
+        Ok(files)
+    }
 
-        Ok(bundle)
+    /// Path of `path` from the root, with forward slashes
+    fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
     }
 
     /// Check if an entry should be excluded
@@ -178,3 +301,42 @@ impl BundleBuilder {
         false
     }
 }
+
+/// Compile `[frontend]` globs
+fn compile_globs(patterns: &[String], list: &str) -> PackResult<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            glob::Pattern::new(pattern).map_err(|e| {
+                PackError::Config(format!(
+                    "Invalid {} pattern '{}' in [frontend]: {}",
+                    list, pattern, e
+                ))
+            })
+        })
+        .collect()
+}
+
+/// `relative` matches one of `patterns`: by name for patterns without
+/// `/`, by path from the root otherwise
+fn matches_any(patterns: &[glob::Pattern], relative: &str) -> bool {
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+    let name = relative.rsplit('/').next().unwrap_or(relative);
+    patterns.iter().any(|pattern| {
+        if pattern.as_str().contains('/') {
+            pattern.matches_with(relative, options)
+        } else {
+            pattern.matches_with(name, options)
+        }
+    })
+}
+
+/// Check the `[frontend] include` and `exclude` globs
+pub(crate) fn validate_globs(include: &[String], exclude: &[String]) -> PackResult<()> {
+    compile_globs(include, "include")?;
+    compile_globs(exclude, "exclude")?;
+    Ok(())
+}
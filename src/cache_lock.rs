@@ -0,0 +1,141 @@
+//! Advisory locks and atomic writes for shared caches
+//!
+//! The download, Python distribution/runtime, toolchain and tool caches are
+//! shared by every pack (and packed app) on a machine, e.g. parallel CI jobs
+//! on one runner. Writers take an exclusive advisory lock on a sibling
+//! `<entry>.lock` file, re-check the cache under the lock, and publish
+//! entries by renaming a fully written temporary file or directory into
+//! place, so readers never observe partial content.
+
+use crate::PackResult;
+use std::fs::{self, File, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Advisory lock on a cache entry, released on drop
+#[derive(Debug)]
+pub struct CacheLock {
+    path: PathBuf,
+    _file: File,
+}
+
+impl CacheLock {
+    /// Lock the cache entry at `entry`, waiting for other holders
+    ///
+    /// The lock file is `<entry>.lock`; the entry itself does not need to exist.
+    pub fn acquire(entry: &Path) -> PackResult<Self> {
+        let (path, file) = open_lock_file(entry)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                tracing::info!("Waiting for cache lock: {}", path.display());
+                file.lock()?;
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+        tracing::debug!("Acquired cache lock: {}", path.display());
+        Ok(Self { path, _file: file })
+    }
+
+    /// Try to lock the cache entry at `entry` without waiting
+    ///
+    /// Returns `None` if another process holds the lock.
+    pub fn try_acquire(entry: &Path) -> PackResult<Option<Self>> {
+        let (path, file) = open_lock_file(entry)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { path, _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e.into()),
+        }
+    }
+
+    /// Share the lock of the cache entry at `entry`, waiting for an
+    /// exclusive holder
+    ///
+    /// Any number of shared holders (e.g. running apps using the entry) keep
+    /// exclusive lockers out until all of them are dropped.
+    pub fn acquire_shared(entry: &Path) -> PackResult<Self> {
+        let (path, file) = open_lock_file(entry)?;
+        file.lock_shared()?;
+        Ok(Self { path, _file: file })
+    }
+
+    /// Path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Open (creating) the lock file of a cache entry
+fn open_lock_file(entry: &Path) -> PackResult<(PathBuf, File)> {
+    let path = lock_path(entry);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)?;
+    Ok((path, file))
+}
+
+/// Lock file path of a cache entry
+fn lock_path(entry: &Path) -> PathBuf {
+    let mut name = entry.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    entry.with_file_name(name)
+}
+
+/// Write a file by renaming a complete temporary sibling into place
+pub fn write_atomic(path: &Path, content: &[u8]) -> PackResult<()> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)?;
+    let mut tmp = tempfile::Builder::new()
+        .prefix(".tmp-")
+        .tempfile_in(parent)?;
+    tmp.write_all(content)?;
+    tmp.as_file().sync_all()?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Temporary sibling of a cache entry, for producing it before the
+/// final rename (removed on drop unless persisted)
+pub(crate) fn temp_sibling(path: &Path) -> PackResult<tempfile::TempPath> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)?;
+    Ok(tempfile::Builder::new()
+        .prefix(".tmp-")
+        .tempfile_in(parent)?
+        .into_temp_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let temp = tempfile::tempdir().unwrap();
+        let entry = temp.path().join("cache").join("artifact.tar.gz");
+
+        let lock = CacheLock::acquire(&entry).unwrap();
+        assert!(lock.path().ends_with("artifact.tar.gz.lock"));
+        assert!(CacheLock::try_acquire(&entry).unwrap().is_none());
+
+        drop(lock);
+        assert!(CacheLock::try_acquire(&entry).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_write_atomic_replaces() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("nested").join("file.bin");
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        // No temporary files left behind
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,286 @@
+//! Host capability probing
+//!
+//! Reports which bundle strategies, protection methods, signing tools and
+//! installer formats are usable on this machine, and what is missing for
+//! the others, so frontends can disable unsupported options up front:
+//!
+//! ```rust,ignore
+//! let caps = auroraview_pack::probe_capabilities();
+//! if !caps.is_available(CapabilityKind::Strategy, "pyoxidizer") {
+//!     // gray out the option, show caps.get(..).reason
+//! }
+//! println!("{}", serde_json::to_string_pretty(&caps)?);
+//! ```
+//!
+//! Probing runs `--version` of the relevant tools and looks them up in
+//! `PATH`; it does not touch the network.
+
+use crate::common::BundleStrategy;
+use crate::protection::ProtectionMethodConfig;
+use crate::python_standalone::PythonTarget;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Category of a capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilityKind {
+    /// Python bundle strategy (`[python] strategy`)
+    Strategy,
+    /// Code protection method (`[python.protection] method`)
+    Protection,
+    /// Code signing
+    Signing,
+    /// Installer / package format
+    Installer,
+}
+
+/// Whether one option is usable on this host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capability {
+    /// Category
+    pub kind: CapabilityKind,
+    /// Option name as used in the manifest (e.g., "pyoxidizer", "py2pyd")
+    pub name: String,
+    /// Usable on this host
+    pub available: bool,
+    /// Tools or features that are missing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<String>,
+    /// Why the option is unavailable, or what it relies on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Capabilities of the host, see [`probe_capabilities`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostCapabilities {
+    /// Host operating system (e.g., "windows")
+    pub os: String,
+    /// Host architecture (e.g., "x86_64")
+    pub arch: String,
+    /// All probed options
+    pub capabilities: Vec<Capability>,
+}
+
+impl HostCapabilities {
+    /// Find a capability
+    pub fn get(&self, kind: CapabilityKind, name: &str) -> Option<&Capability> {
+        self.capabilities
+            .iter()
+            .find(|c| c.kind == kind && c.name == name)
+    }
+
+    /// Whether an option is usable (unknown options are not)
+    pub fn is_available(&self, kind: CapabilityKind, name: &str) -> bool {
+        self.get(kind, name).is_some_and(|c| c.available)
+    }
+
+    /// Capability of a bundle strategy
+    pub fn strategy(&self, strategy: BundleStrategy) -> Option<&Capability> {
+        self.get(CapabilityKind::Strategy, strategy.as_str())
+    }
+
+    /// Capabilities of one category
+    pub fn of_kind(&self, kind: CapabilityKind) -> impl Iterator<Item = &Capability> {
+        self.capabilities.iter().filter(move |c| c.kind == kind)
+    }
+}
+
+/// Probe which options are usable on this host
+pub fn probe_capabilities() -> HostCapabilities {
+    let mut probe = Probe::default();
+    let mut capabilities = Vec::new();
+
+    // ------------------------------------------------------------------------
+    // Bundle strategies
+    // ------------------------------------------------------------------------
+
+    for strategy in [BundleStrategy::Standalone, BundleStrategy::Portable] {
+        capabilities.push(match PythonTarget::current() {
+            Ok(target) => Capability::available(
+                CapabilityKind::Strategy,
+                strategy.as_str(),
+                format!("downloads python-build-standalone ({})", target.triple()),
+            ),
+            Err(e) => Capability::unavailable(
+                CapabilityKind::Strategy,
+                strategy.as_str(),
+                Vec::new(),
+                e.to_string(),
+            ),
+        });
+    }
+
+    capabilities.push(match crate::pyoxidizer::check_pyoxidizer() {
+        Ok(version) => Capability::available(
+            CapabilityKind::Strategy,
+            BundleStrategy::PyOxidizer.as_str(),
+            version,
+        ),
+        Err(_) => Capability::unavailable(
+            CapabilityKind::Strategy,
+            BundleStrategy::PyOxidizer.as_str(),
+            vec!["pyoxidizer".to_string()],
+            "install with: cargo install pyoxidizer",
+        ),
+    });
+
+    let python = probe.python();
+    for strategy in [BundleStrategy::Embedded, BundleStrategy::System] {
+        capabilities.push(match python {
+            Some(ref version) => Capability::available(
+                CapabilityKind::Strategy,
+                strategy.as_str(),
+                format!("uses the host {}", version),
+            ),
+            None => Capability::unavailable(
+                CapabilityKind::Strategy,
+                strategy.as_str(),
+                vec!["python".to_string()],
+                "requires a Python interpreter in PATH",
+            ),
+        });
+    }
+
+    // ------------------------------------------------------------------------
+    // Protection methods
+    // ------------------------------------------------------------------------
+
+    for (name, method) in [
+        ("bytecode", ProtectionMethodConfig::Bytecode),
+        ("py2pyd", ProtectionMethodConfig::Py2Pyd),
+    ] {
+        capabilities.push(if !crate::protection::is_protection_available() {
+            Capability::unavailable(
+                CapabilityKind::Protection,
+                name,
+                vec!["code-protection feature".to_string()],
+                "auroraview-pack was built without the code-protection feature",
+            )
+        } else {
+            match crate::protection::check_build_tools_available(method) {
+                Ok(()) => Capability::available(CapabilityKind::Protection, name, "ready"),
+                Err(e) => Capability::unavailable(
+                    CapabilityKind::Protection,
+                    name,
+                    vec!["C compiler".to_string()],
+                    e.to_string(),
+                ),
+            }
+        });
+    }
+
+    // ------------------------------------------------------------------------
+    // Signing and installers
+    // ------------------------------------------------------------------------
+
+    let authenticode_tool = if cfg!(target_os = "windows") {
+        "signtool"
+    } else {
+        "osslsigncode"
+    };
+    let tools: [(CapabilityKind, &str, Option<&str>, &str); 7] = [
+        (
+            CapabilityKind::Signing,
+            "authenticode",
+            None,
+            authenticode_tool,
+        ),
+        (
+            CapabilityKind::Signing,
+            "codesign",
+            Some("macos"),
+            "codesign",
+        ),
+        (
+            CapabilityKind::Signing,
+            "notarization",
+            Some("macos"),
+            "xcrun",
+        ),
+        (CapabilityKind::Installer, "dmg", Some("macos"), "hdiutil"),
+        (
+            CapabilityKind::Installer,
+            "appimage",
+            Some("linux"),
+            "appimagetool",
+        ),
+        (CapabilityKind::Installer, "deb", None, "dpkg-deb"),
+        (CapabilityKind::Installer, "rpm", None, "rpmbuild"),
+    ];
+    for (kind, name, required_os, tool) in tools {
+        capabilities.push(match required_os {
+            Some(os) if os != std::env::consts::OS => {
+                Capability::unavailable(kind, name, Vec::new(), format!("requires a {} host", os))
+            }
+            _ => match probe.find(tool) {
+                Some(path) => Capability::available(kind, name, path.display().to_string()),
+                None => Capability::unavailable(
+                    kind,
+                    name,
+                    vec![tool.to_string()],
+                    format!("{} not found in PATH", tool),
+                ),
+            },
+        });
+    }
+
+    HostCapabilities {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        capabilities,
+    }
+}
+
+impl Capability {
+    fn available(kind: CapabilityKind, name: &str, reason: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            available: true,
+            missing: Vec::new(),
+            reason: Some(reason.into()),
+        }
+    }
+
+    fn unavailable(
+        kind: CapabilityKind,
+        name: &str,
+        missing: Vec<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            available: false,
+            missing,
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+/// Tool lookups, memoized for one probe run
+#[derive(Default)]
+struct Probe {
+    programs: HashMap<String, Option<PathBuf>>,
+}
+
+impl Probe {
+    /// Version line of the host Python
+    fn python(&mut self) -> Option<String> {
+        ["python", "python3", "py"].iter().find_map(|cmd| {
+            self.find(cmd)?;
+            crate::doctor::tool_version(cmd, &["--version"])
+        })
+    }
+
+    /// Locate a program in `PATH`
+    fn find(&mut self, program: &str) -> Option<PathBuf> {
+        self.programs
+            .entry(program.to_string())
+            .or_insert_with(|| crate::hooks::find_program(program))
+            .clone()
+    }
+}
@@ -0,0 +1,208 @@
+//! CDP-based end-to-end test support for packed executables
+//!
+//! When `[debug.test]` is enabled, the packer writes a test-run descriptor
+//! (`<exe>.test.json`) next to the packed executable. CI jobs can load it
+//! and call [`launch_for_test`] to start the packed app, wait for the Chrome
+//! DevTools Protocol endpoint to come up and get its websocket URL.
+
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Interval between CDP / health endpoint polls
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Test-run descriptor emitted at pack time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestRunDescriptor {
+    /// Path to the packed executable
+    pub executable: PathBuf,
+
+    /// Remote debugging port the app listens on
+    pub port: u16,
+
+    /// Maximum time to wait for startup, in seconds
+    pub startup_timeout: u64,
+
+    /// Optional health URL polled after CDP is available
+    #[serde(default)]
+    pub health_url: Option<String>,
+}
+
+impl TestRunDescriptor {
+    /// Get the descriptor path for a packed executable (`<exe>.test.json`)
+    pub fn path_for(executable: &Path) -> PathBuf {
+        let mut name = executable
+            .file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_default();
+        name.push(".test.json");
+        executable.with_file_name(name)
+    }
+
+    /// Load a descriptor from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> PackResult<Self> {
+        let content = fs::read_to_string(path.as_ref())?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the descriptor as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> PackResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path.as_ref(), content)?;
+        Ok(())
+    }
+
+    /// CDP version endpoint (`http://127.0.0.1:<port>/json/version`)
+    pub fn version_url(&self) -> String {
+        format!("http://127.0.0.1:{}/json/version", self.port)
+    }
+}
+
+/// A running packed executable under test
+///
+/// The process is killed when this value is dropped.
+#[derive(Debug)]
+pub struct TestRun {
+    child: Child,
+    websocket_url: String,
+}
+
+impl TestRun {
+    /// Websocket URL of the browser CDP endpoint
+    pub fn websocket_url(&self) -> &str {
+        &self.websocket_url
+    }
+
+    /// Process ID of the packed executable
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Stop the packed executable
+    pub fn shutdown(mut self) -> PackResult<()> {
+        self.kill()
+    }
+
+    fn kill(&mut self) -> PackResult<()> {
+        if self.child.try_wait()?.is_none() {
+            self.child.kill()?;
+            self.child.wait()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TestRun {
+    fn drop(&mut self) {
+        if let Err(e) = self.kill() {
+            tracing::warn!("Failed to stop packed executable: {}", e);
+        }
+    }
+}
+
+/// Launch a packed executable and wait until its CDP endpoint is available
+pub fn launch_for_test(descriptor: &TestRunDescriptor) -> PackResult<TestRun> {
+    if !descriptor.executable.exists() {
+        return Err(PackError::AssetNotFound(descriptor.executable.clone()));
+    }
+
+    tracing::info!(
+        "Launching {} for testing (CDP port {})",
+        descriptor.executable.display(),
+        descriptor.port
+    );
+
+    // The app's output must not reach the caller's terminal
+    let child = Command::new(&descriptor.executable)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            PackError::TestRun(format!(
+                "Failed to launch {}: {}",
+                descriptor.executable.display(),
+                e
+            ))
+        })?;
+
+    let deadline = Instant::now() + Duration::from_secs(descriptor.startup_timeout);
+    // Wrap immediately so the process is cleaned up on any error below
+    let mut run = TestRun {
+        child,
+        websocket_url: String::new(),
+    };
+
+    let version_url = descriptor.version_url();
+    run.websocket_url = poll_until(deadline, Some(&mut run.child), &version_url, || {
+        fetch_websocket_url(&version_url)
+    })?;
+
+    if let Some(ref health_url) = descriptor.health_url {
+        poll_until(deadline, Some(&mut run.child), health_url, || {
+            ureq::get(health_url)
+                .timeout(Duration::from_secs(2))
+                .call()
+                .ok()
+                .map(|_| ())
+        })?;
+    }
+
+    tracing::info!("CDP available at {}", run.websocket_url);
+    Ok(run)
+}
+
+/// Wait for a CDP endpoint on `port` and return its websocket URL
+pub fn wait_for_cdp(port: u16, timeout: Duration) -> PackResult<String> {
+    let url = format!("http://127.0.0.1:{}/json/version", port);
+    poll_until(Instant::now() + timeout, None, &url, || {
+        fetch_websocket_url(&url)
+    })
+}
+
+/// Poll `probe` until it yields a value, the deadline passes or the child exits
+fn poll_until<T>(
+    deadline: Instant,
+    mut child: Option<&mut Child>,
+    what: &str,
+    mut probe: impl FnMut() -> Option<T>,
+) -> PackResult<T> {
+    loop {
+        if let Some(value) = probe() {
+            return Ok(value);
+        }
+        if let Some(ref mut child) = child {
+            if let Some(status) = child.try_wait()? {
+                return Err(PackError::TestRun(format!(
+                    "Packed executable exited during startup ({}) while waiting for {}",
+                    status, what
+                )));
+            }
+        }
+        if Instant::now() >= deadline {
+            return Err(PackError::TestRun(format!(
+                "Timed out waiting for {}",
+                what
+            )));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Query the CDP version endpoint and extract `webSocketDebuggerUrl`
+fn fetch_websocket_url(url: &str) -> Option<String> {
+    let body = ureq::get(url)
+        .timeout(Duration::from_secs(2))
+        .call()
+        .ok()?
+        .into_string()
+        .ok()?;
+    let json: serde_json::Value = serde_json::from_str(&body).ok()?;
+    json.get("webSocketDebuggerUrl")?
+        .as_str()
+        .map(|s| s.to_string())
+}
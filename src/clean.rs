@@ -0,0 +1,233 @@
+//! Pack cache and work directory cleanup
+//!
+//! Backs [`Packer::clean`](crate::Packer::clean). Each [`CleanScope`] maps to
+//! the directories a pack run leaves behind; a dry run only reports what
+//! would be removed and how much space it would reclaim.
+
+use crate::frontend_deps::get_node_modules_cache_dir;
+use crate::python_standalone::get_distribution_cache_dir;
+use crate::resource_editor::ResourceEditor;
+use crate::staging::STAGING_PREFIX;
+use crate::store::ArtifactStore;
+use crate::toolchain::get_toolchains_cache_dir;
+use crate::{PackConfig, PackContext, PackError, PackResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix of dependency collection directories left by older versions
+const LEGACY_DEPS_PREFIX: &str = "auroraview-deps-";
+
+/// Which caches to clean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CleanScope {
+    /// Everything below
+    All,
+    /// Legacy vx / downloads artifact cache (`[vx] cache_dir`)
+    Vx,
+    /// Downloaded python-build-standalone distributions and the extracted
+    /// runtime of this app
+    PythonRuntime,
+    /// PyOxidizer work directory in the output directory
+    PyOxidizer,
+    /// rcedit downloaded by versions before the artifact store
+    Rcedit,
+    /// `node_modules` snapshots cached by versions before the artifact store
+    NodeModules,
+    /// Provisioned Go/Rust toolchains
+    Toolchains,
+    /// Content-addressed artifact store (`[build.store]`)
+    Store,
+    /// Staging directories left behind by interrupted pack runs
+    ///
+    /// Do not clean this scope while another pack is running.
+    Staging,
+}
+
+impl CleanScope {
+    /// All individual scopes
+    pub const ALL: [CleanScope; 8] = [
+        CleanScope::Vx,
+        CleanScope::PythonRuntime,
+        CleanScope::PyOxidizer,
+        CleanScope::Rcedit,
+        CleanScope::NodeModules,
+        CleanScope::Toolchains,
+        CleanScope::Store,
+        CleanScope::Staging,
+    ];
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "all" => Some(Self::All),
+            "vx" => Some(Self::Vx),
+            "python" | "python-runtime" => Some(Self::PythonRuntime),
+            "pyoxidizer" => Some(Self::PyOxidizer),
+            "rcedit" => Some(Self::Rcedit),
+            "node-modules" | "node" => Some(Self::NodeModules),
+            "toolchains" | "toolchain" => Some(Self::Toolchains),
+            "store" => Some(Self::Store),
+            "staging" | "deps" => Some(Self::Staging),
+            _ => None,
+        }
+    }
+
+    /// Get string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Vx => "vx",
+            Self::PythonRuntime => "python-runtime",
+            Self::PyOxidizer => "pyoxidizer",
+            Self::Rcedit => "rcedit",
+            Self::NodeModules => "node-modules",
+            Self::Toolchains => "toolchains",
+            Self::Store => "store",
+            Self::Staging => "staging",
+        }
+    }
+
+    /// Expand `All` into the individual scopes
+    pub fn expand(&self) -> Vec<CleanScope> {
+        match self {
+            Self::All => Self::ALL.to_vec(),
+            scope => vec![*scope],
+        }
+    }
+}
+
+/// A directory removed (or to be removed) by a clean
+#[derive(Debug, Clone)]
+pub struct CleanEntry {
+    /// Scope the directory belongs to
+    pub scope: CleanScope,
+    /// Directory path
+    pub path: PathBuf,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// Result of [`Packer::clean`](crate::Packer::clean)
+#[derive(Debug, Clone, Default)]
+pub struct CleanReport {
+    /// Directories removed, or that would be removed in a dry run
+    pub entries: Vec<CleanEntry>,
+    /// Whether this was a dry run
+    pub dry_run: bool,
+}
+
+impl CleanReport {
+    /// Total reclaimed (or reclaimable) bytes
+    pub fn reclaimed(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+}
+
+/// Collect existing cache directories for a scope
+fn clean_targets(
+    config: &PackConfig,
+    context: &PackContext,
+    scope: CleanScope,
+) -> Vec<(CleanScope, PathBuf)> {
+    let mut targets = Vec::new();
+
+    for scope in scope.expand() {
+        let paths = match scope {
+            CleanScope::All => unreachable!("expanded above"),
+            CleanScope::Vx => config
+                .vx
+                .as_ref()
+                .map(|vx| vec![context.vx_cache_dir(vx)])
+                .unwrap_or_default(),
+            CleanScope::PythonRuntime => vec![
+                get_distribution_cache_dir(),
+                config.identity().runtime_cache_dir(),
+            ],
+            CleanScope::PyOxidizer => vec![config.output_dir.join(".pyoxidizer-build")],
+            CleanScope::Rcedit => vec![
+                ResourceEditor::tools_cache_dir(),
+                context.temp_root().join("rcedit-download.exe"),
+            ],
+            CleanScope::NodeModules => vec![get_node_modules_cache_dir()],
+            CleanScope::Toolchains => vec![get_toolchains_cache_dir()],
+            CleanScope::Store => vec![ArtifactStore::open(&config.store).root().to_path_buf()],
+            CleanScope::Staging => staging_dirs(&context.staging_root(config)),
+        };
+
+        targets.extend(paths.into_iter().filter(|p| p.exists()).map(|p| (scope, p)));
+    }
+
+    targets
+}
+
+/// Remove (or measure, in a dry run) the cache directories for a scope
+pub(crate) fn clean(
+    config: &PackConfig,
+    context: &PackContext,
+    scope: CleanScope,
+    dry_run: bool,
+) -> PackResult<CleanReport> {
+    let mut report = CleanReport {
+        entries: Vec::new(),
+        dry_run,
+    };
+
+    for (scope, path) in clean_targets(config, context, scope) {
+        let size = path_size(&path);
+
+        if dry_run {
+            tracing::info!(
+                "Would remove {} ({:.2} MB)",
+                path.display(),
+                size as f64 / (1024.0 * 1024.0)
+            );
+        } else {
+            let result = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            result.map_err(|e| {
+                PackError::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("Failed to remove {}: {}", path.display(), e),
+                ))
+            })?;
+            tracing::info!(
+                "Removed {} ({:.2} MB)",
+                path.display(),
+                size as f64 / (1024.0 * 1024.0)
+            );
+        }
+
+        report.entries.push(CleanEntry { scope, path, size });
+    }
+
+    Ok(report)
+}
+
+/// Find staging directories in the staging root
+fn staging_dirs(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_name()
+                .to_str()
+                .is_some_and(|n| n.starts_with(STAGING_PREFIX) || n.starts_with(LEGACY_DEPS_PREFIX))
+        })
+        .map(|e| e.path())
+        .collect()
+}
+
+/// Size of a file or directory tree in bytes
+pub(crate) fn path_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.metadata().map(|m| m.len()).unwrap_or(0))
+        .sum()
+}
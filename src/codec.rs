@@ -0,0 +1,209 @@
+//! Compression codecs of overlay assets
+//!
+//! Each asset in the overlay index records the codec it was stored with, so
+//! the packer can pick one per asset class in `[build.codecs]`:
+//!
+//! ```toml
+//! [build.codecs]
+//! default = "zstd"            # Everything not listed below
+//! frontend = "brotli"         # HTML/JS/CSS: best ratio on text
+//! python = "zstd"             # Python sources and packages
+//! python_runtime = "store"    # Already gzipped; recompressing wastes time
+//! ```
+//!
+//! `lz4` trades ratio for the fastest decompression, for assets read on
+//! every launch.
+
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Read, Write};
+
+/// Compression codec of a stored asset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Codec {
+    /// Zstandard at the configured compression level
+    #[default]
+    Zstd,
+    /// LZ4 frame (fast decompression, lower ratio)
+    Lz4,
+    /// Brotli (quality follows the compression level, up to 11)
+    Brotli,
+    /// Stored uncompressed
+    Store,
+}
+
+impl Codec {
+    /// Whether this is the default codec (not written to the index)
+    pub fn is_zstd(&self) -> bool {
+        *self == Self::Zstd
+    }
+
+    /// Name of the codec as written in TOML
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Zstd => "zstd",
+            Self::Lz4 => "lz4",
+            Self::Brotli => "brotli",
+            Self::Store => "store",
+        }
+    }
+
+    /// Compress `reader` to its end into `writer`
+    ///
+    /// `level` is the zstd level (1-22); brotli maps it to its quality.
+    pub(crate) fn encode(
+        self,
+        level: i32,
+        reader: &mut impl Read,
+        writer: &mut impl Write,
+    ) -> PackResult<()> {
+        let compression = |e: io::Error| PackError::Compression(e.to_string());
+        match self {
+            Self::Zstd => {
+                let mut encoder = zstd::Encoder::new(writer, level).map_err(compression)?;
+                io::copy(reader, &mut encoder)?;
+                encoder.finish().map_err(compression)?;
+            }
+            Self::Lz4 => {
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(writer);
+                io::copy(reader, &mut encoder)?;
+                encoder
+                    .finish()
+                    .map_err(|e| PackError::Compression(e.to_string()))?;
+            }
+            Self::Brotli => {
+                let params = brotli::enc::BrotliEncoderParams {
+                    quality: level.clamp(0, 11),
+                    lgwin: 22,
+                    ..Default::default()
+                };
+                brotli::BrotliCompress(reader, writer, &params).map_err(compression)?;
+            }
+            Self::Store => {
+                io::copy(reader, writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compress a buffer
+    pub(crate) fn encode_all(self, level: i32, content: &[u8]) -> PackResult<Vec<u8>> {
+        let mut out = Vec::new();
+        self.encode(level, &mut &content[..], &mut out)?;
+        Ok(out)
+    }
+
+    /// Reader that decompresses `reader`
+    pub(crate) fn decoder<'a, R: BufRead + Send + 'a>(
+        self,
+        reader: R,
+    ) -> PackResult<Box<dyn Read + Send + 'a>> {
+        Ok(match self {
+            Self::Zstd => Box::new(
+                zstd::Decoder::with_buffer(reader)
+                    .map_err(|e| PackError::Compression(e.to_string()))?,
+            ),
+            Self::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(reader)),
+            Self::Brotli => Box::new(brotli::Decompressor::new(reader, 64 * 1024)),
+            Self::Store => Box::new(reader),
+        })
+    }
+}
+
+/// Asset classes with their own codec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetClass {
+    /// Web assets (`frontend/...`, or HTML/JS/CSS/SVG/WASM files)
+    Frontend,
+    /// Python code and packages (`python/...`, `lib/...`)
+    Python,
+    /// The bundled Python runtime archive
+    PythonRuntime,
+    /// Everything else
+    Other,
+}
+
+impl AssetClass {
+    /// Class of an overlay path
+    pub fn of(path: &str) -> Self {
+        const WEB_EXTENSIONS: &[&str] = &[
+            ".html", ".htm", ".js", ".mjs", ".css", ".svg", ".map", ".wasm",
+        ];
+        if path == "python_runtime.tar.gz" {
+            Self::PythonRuntime
+        } else if path.starts_with("python/") || path.starts_with("lib/") {
+            Self::Python
+        } else if path.starts_with("frontend/") || {
+            let lower = path.to_ascii_lowercase();
+            WEB_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+        } {
+            Self::Frontend
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Codec per asset class
+///
+/// Located at `[build.codecs]` in TOML. Pack-time only; the overlay index
+/// records each asset's codec.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodecConfig {
+    /// Codec of assets without a class-specific setting
+    pub default: Codec,
+    /// Web assets
+    pub frontend: Option<Codec>,
+    /// Python code and packages
+    pub python: Option<Codec>,
+    /// Python runtime archive
+    pub python_runtime: Option<Codec>,
+}
+
+impl CodecConfig {
+    /// Codec of an overlay path
+    pub fn codec_for(&self, path: &str) -> Codec {
+        let class = match AssetClass::of(path) {
+            AssetClass::Frontend => self.frontend,
+            AssetClass::Python => self.python,
+            AssetClass::PythonRuntime => self.python_runtime,
+            AssetClass::Other => None,
+        };
+        class.unwrap_or(self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codec_roundtrip() {
+        let content = b"print('hello')\n".repeat(200);
+        for codec in [Codec::Zstd, Codec::Lz4, Codec::Brotli, Codec::Store] {
+            let stored = codec.encode_all(19, &content).unwrap();
+            let mut decoded = Vec::new();
+            codec
+                .decoder(&stored[..])
+                .unwrap()
+                .read_to_end(&mut decoded)
+                .unwrap();
+            assert_eq!(decoded, content, "{:?}", codec);
+        }
+    }
+
+    #[test]
+    fn test_asset_class() {
+        assert_eq!(
+            AssetClass::of("python_runtime.tar.gz"),
+            AssetClass::PythonRuntime
+        );
+        assert_eq!(AssetClass::of("python/app/main.py"), AssetClass::Python);
+        assert_eq!(AssetClass::of("lib/numpy/core.so"), AssetClass::Python);
+        assert_eq!(AssetClass::of("frontend/logo.png"), AssetClass::Frontend);
+        assert_eq!(AssetClass::of("assets/index.JS"), AssetClass::Frontend);
+        assert_eq!(AssetClass::of("shortcuts/0/logs.ico"), AssetClass::Other);
+    }
+}
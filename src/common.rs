@@ -16,6 +16,9 @@
 //! [backend.node]      - Node.js backend settings
 //! [backend.process]   - Common process settings
 //! [window]            - WindowConfig: Runtime window behavior
+//! [window.dpi]        - DpiConfig: DPI awareness and scale factor
+//! [window.theme]      - WindowThemeConfig: Theme and colors shown before first paint
+//! [window.content]    - WindowContentConfig: Zoom and content scaling policy
 //! [bundle]            - BundleConfig: General bundling settings
 //! [bundle.windows]    - Windows-specific bundling
 //! [bundle.macos]      - macOS-specific bundling
@@ -23,11 +26,29 @@
 //! [build]             - BuildConfig: Build hooks and resources
 //! [hooks]             - HooksConfig: File collection hooks
 //! [runtime]           - RuntimeConfig: Runtime environment
+//! [runtime.kiosk]     - KioskConfig: Kiosk / digital-signage lock-down
+//! [[runtime.schedule]] - ScheduleEntry: Scheduled reload/restart policies
+//! [runtime.storage]   - StorageConfig: Cookie / session persistence
+//! [runtime.print]     - PrintConfig: Print and PDF export policy
+//! [runtime.versioning] - VersioningConfig: Multi-version coexistence
+//! [runtime.rollback]  - RollbackPolicy: Self-update slot rollback
+//! [runtime.update]    - UpdatePolicy: Update channel and version constraints
 //! [debug]             - DebugConfig: Debug settings
+//! [[shortcuts.tasks]] - ShortcutTask: Jump-list / dock menu quick actions
+//! [network]           - NetworkConfig: Runtime network settings (CAs, client certs)
+//! [about]             - AboutConfig: Changelog / About-screen data
 //! [license]           - LicenseConfig: License validation
 //! [inject]            - InjectConfig: JS/CSS injection
 //! ```
 
+use crate::about::AboutInfo;
+use crate::error::{PackError, PackResult};
+use crate::eula::EulaConfig;
+use crate::print_policy::PrintConfig;
+use crate::slots::RollbackPolicy;
+use crate::update_policy::UpdatePolicy;
+use crate::versioning::VersioningConfig;
+use crate::watermark::PreviewConfig;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -211,6 +232,18 @@ pub struct WindowConfig {
     /// Visible on start
     #[serde(default = "default_true")]
     pub visible: bool,
+
+    /// DPI awareness and scale factor
+    #[serde(default)]
+    pub dpi: Option<DpiConfig>,
+
+    /// Theme and window colors
+    #[serde(default)]
+    pub theme: Option<WindowThemeConfig>,
+
+    /// Zoom and content scaling policy
+    #[serde(default)]
+    pub content: Option<WindowContentConfig>,
 }
 
 impl Default for WindowConfig {
@@ -231,6 +264,9 @@ impl Default for WindowConfig {
             fullscreen: false,
             maximized: false,
             visible: true,
+            dpi: None,
+            theme: None,
+            content: None,
         }
     }
 }
@@ -269,6 +305,235 @@ impl WindowConfig {
         self.always_on_top = always_on_top;
         self
     }
+
+    /// Set DPI awareness and scale factor
+    pub fn with_dpi(mut self, dpi: DpiConfig) -> Self {
+        self.dpi = Some(dpi);
+        self
+    }
+
+    /// Set the theme and window colors
+    pub fn with_theme(mut self, theme: WindowThemeConfig) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Set the zoom and content scaling policy
+    pub fn with_content(mut self, content: WindowContentConfig) -> Self {
+        self.content = Some(content);
+        self
+    }
+}
+
+/// How the process reacts to display scaling
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DpiAwareness {
+    /// Bitmap-stretched by the OS (blurry on high-DPI displays)
+    Unaware,
+    /// Scaled for the primary monitor at startup
+    System,
+    /// Rescaled when moved between monitors
+    PerMonitor,
+    /// Per-monitor, including non-client areas and dialogs (Windows 10 1703+)
+    #[default]
+    PerMonitorV2,
+}
+
+impl DpiAwareness {
+    /// `<dpiAware>` value of the Windows application manifest (pre-1607)
+    pub fn manifest_dpi_aware(&self) -> &'static str {
+        match self {
+            Self::Unaware => "false",
+            Self::System => "true",
+            Self::PerMonitor | Self::PerMonitorV2 => "true/pm",
+        }
+    }
+
+    /// `<dpiAwareness>` value of the Windows application manifest (1607+)
+    ///
+    /// Falls back to per-monitor where V2 is not supported.
+    pub fn manifest_dpi_awareness(&self) -> &'static str {
+        match self {
+            Self::Unaware => "unaware",
+            Self::System => "system",
+            Self::PerMonitor => "PerMonitor",
+            Self::PerMonitorV2 => "PerMonitorV2, PerMonitor",
+        }
+    }
+}
+
+/// DPI configuration
+///
+/// Located at `[window.dpi]` in TOML. Awareness goes into the Windows
+/// application manifest; the scale factors are applied by the runtime to
+/// the WebView zoom.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DpiConfig {
+    /// Per-monitor awareness
+    pub awareness: DpiAwareness,
+
+    /// Use this scale factor instead of the monitor's (e.g., 1.0)
+    pub scale_factor: Option<f64>,
+
+    /// Never scale below this factor (e.g., 1.25 for small text on 100% displays)
+    pub min_scale_factor: Option<f64>,
+}
+
+impl DpiConfig {
+    /// Validate the scale factors
+    pub fn validate(&self) -> PackResult<()> {
+        for (name, value) in [
+            ("scale_factor", self.scale_factor),
+            ("min_scale_factor", self.min_scale_factor),
+        ] {
+            if let Some(value) = value {
+                if !(0.25..=5.0).contains(&value) {
+                    return Err(PackError::Config(format!(
+                        "[window.dpi] {} must be between 0.25 and 5.0, got {}",
+                        name, value
+                    )));
+                }
+            }
+        }
+        if let (Some(scale), Some(min)) = (self.scale_factor, self.min_scale_factor) {
+            if min > scale {
+                return Err(PackError::Config(format!(
+                    "[window.dpi] min_scale_factor {} is above scale_factor {}",
+                    min, scale
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Light/dark appearance of the window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    /// Follow the OS setting
+    #[default]
+    System,
+    /// Always light
+    Light,
+    /// Always dark
+    Dark,
+}
+
+/// Window theme configuration
+///
+/// Located at `[window.theme]` in TOML. The runtime applies it when it
+/// creates the window, before the WebView paints, so the window does not
+/// flash white on launch. The titlebar colors style the native caption
+/// (Windows 11, macOS) and the custom titlebar of frameless windows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowThemeConfig {
+    /// Light/dark appearance of native controls and the titlebar
+    pub mode: ThemeMode,
+
+    /// Titlebar background (hex, e.g., "#1e1e1e")
+    pub titlebar_color: Option<String>,
+
+    /// Titlebar text (hex)
+    pub titlebar_text_color: Option<String>,
+
+    /// Window background until the first paint (hex)
+    pub background_color: Option<String>,
+}
+
+impl WindowThemeConfig {
+    /// Validate the colors
+    pub fn validate(&self) -> PackResult<()> {
+        for (name, color) in [
+            ("titlebar_color", &self.titlebar_color),
+            ("titlebar_text_color", &self.titlebar_text_color),
+            ("background_color", &self.background_color),
+        ] {
+            if let Some(color) = color {
+                if !is_hex_color(color) {
+                    return Err(PackError::Config(format!(
+                        "[window.theme] {} must be a hex color (e.g., \"#1e1e1e\"): {}",
+                        name, color
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Zoom and content scaling policy
+///
+/// Located at `[window.content]` in TOML. Kiosks typically lock zoom
+/// (`user_zoom = false`, `pinch_zoom = false`); accessibility deployments
+/// start zoomed in and keep user zoom within bounds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowContentConfig {
+    /// Zoom level the page opens at (1.0 = 100%)
+    pub zoom: f64,
+
+    /// Allow Ctrl+wheel and Ctrl+plus/minus/0 zooming
+    pub user_zoom: bool,
+
+    /// Allow touchpad and touchscreen pinch zooming
+    pub pinch_zoom: bool,
+
+    /// Lowest zoom level users can reach
+    pub min_zoom: Option<f64>,
+
+    /// Highest zoom level users can reach
+    pub max_zoom: Option<f64>,
+}
+
+impl Default for WindowContentConfig {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            user_zoom: true,
+            pinch_zoom: true,
+            min_zoom: None,
+            max_zoom: None,
+        }
+    }
+}
+
+impl WindowContentConfig {
+    /// Validate the zoom levels
+    pub fn validate(&self) -> PackResult<()> {
+        for (name, value) in [
+            ("zoom", Some(self.zoom)),
+            ("min_zoom", self.min_zoom),
+            ("max_zoom", self.max_zoom),
+        ] {
+            if let Some(value) = value {
+                if !(0.25..=5.0).contains(&value) {
+                    return Err(PackError::Config(format!(
+                        "[window.content] {} must be between 0.25 and 5.0, got {}",
+                        name, value
+                    )));
+                }
+            }
+        }
+        let min = self.min_zoom.unwrap_or(0.25);
+        let max = self.max_zoom.unwrap_or(5.0);
+        if !(min..=max).contains(&self.zoom) {
+            return Err(PackError::Config(format!(
+                "[window.content] zoom {} is outside min_zoom {} and max_zoom {}",
+                self.zoom, min, max
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Whether a string is a `#rgb`, `#rrggbb` or `#rrggbbaa` color
+pub(crate) fn is_hex_color(color: &str) -> bool {
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
 }
 
 // ============================================================================
@@ -324,6 +589,10 @@ pub struct WindowsPlatformConfig {
     /// Timestamp server URL for code signing
     #[serde(default)]
     pub timestamp_url: Option<String>,
+
+    /// Shell identity for notifications and taskbar grouping/pinning
+    #[serde(default)]
+    pub app_user_model: Option<AppUserModelConfig>,
 }
 
 impl WindowsPlatformConfig {
@@ -336,6 +605,82 @@ impl WindowsPlatformConfig {
             || self.product_name.is_some()
             || self.company_name.is_some()
             || self.copyright.is_some()
+            || self.app_user_model.as_ref().is_some_and(|m| m.id.is_some())
+    }
+}
+
+/// Windows shell identity of the app
+///
+/// Located at `[bundle.windows.app_user_model]` in TOML.
+/// Windows groups taskbar buttons, pins windows and attributes toast
+/// notifications by AppUserModelID; without an explicit one it derives an ID
+/// from the executable path, which changes between installs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppUserModelConfig {
+    /// Explicit AppUserModelID (e.g., "Acme.PhotoTool.Main")
+    pub id: Option<String>,
+
+    /// Command that relaunches a pinned window (default: the executable)
+    pub relaunch_command: Option<String>,
+
+    /// Display name of pinned windows (requires `relaunch_command`)
+    pub relaunch_display_name: Option<String>,
+
+    /// Icon resource of pinned windows (e.g., "app.exe,0")
+    pub relaunch_icon: Option<String>,
+
+    /// Prevent pinning windows to the taskbar
+    pub prevent_pinning: bool,
+
+    /// CLSID of the COM activator for toast notifications
+    pub toast_activator_clsid: Option<String>,
+}
+
+impl AppUserModelConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        let invalid =
+            |msg: String| PackError::Config(format!("[bundle.windows.app_user_model] {}", msg));
+
+        if let Some(ref id) = self.id {
+            // Shell limits: at most 128 characters, no spaces
+            if id.is_empty() || id.len() > 128 {
+                return Err(invalid(format!(
+                    "id must be 1-128 characters, got {}",
+                    id.len()
+                )));
+            }
+            if id.chars().any(char::is_whitespace) || id.split('.').any(str::is_empty) {
+                return Err(invalid(format!(
+                    "id '{}' must be dot-separated sections without spaces \
+                     (e.g., \"Company.Product.SubProduct\")",
+                    id
+                )));
+            }
+        }
+
+        if self.relaunch_display_name.is_some() && self.relaunch_command.is_none() {
+            return Err(invalid(
+                "relaunch_display_name requires relaunch_command".to_string(),
+            ));
+        }
+
+        if let Some(ref clsid) = self.toast_activator_clsid {
+            let hex = clsid.trim_start_matches('{').trim_end_matches('}');
+            let groups: Vec<usize> = hex.split('-').map(str::len).collect();
+            let well_formed = groups == [8, 4, 4, 4, 12]
+                && hex.chars().all(|c| c == '-' || c.is_ascii_hexdigit());
+            if !well_formed {
+                return Err(invalid(format!(
+                    "toast_activator_clsid '{}' is not a CLSID \
+                     (e.g., \"{{2A7B9C4D-1E3F-4A5B-8C6D-7E8F9A0B1C2D}}\")",
+                    clsid
+                )));
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -485,6 +830,38 @@ impl BundleStrategy {
             BundleStrategy::Standalone | BundleStrategy::PyOxidizer | BundleStrategy::Portable
         )
     }
+
+    /// Check if this strategy ships the python-build-standalone runtime
+    pub fn bundles_standalone(&self) -> bool {
+        matches!(self, BundleStrategy::Standalone | BundleStrategy::Portable)
+    }
+
+    /// Check if this strategy always writes an output directory
+    pub fn is_directory(&self) -> bool {
+        matches!(self, BundleStrategy::Portable | BundleStrategy::System)
+    }
+}
+
+/// Shape of the pack output (`[build] layout`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputLayout {
+    /// One self-contained executable; assets are extracted on first run
+    #[default]
+    Onefile,
+    /// `<name>/` directory with the executable and its assets as plain
+    /// files, read in place (no extraction at startup)
+    Onedir,
+}
+
+impl OutputLayout {
+    /// Convert to string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputLayout::Onefile => "onefile",
+            OutputLayout::Onedir => "onedir",
+        }
+    }
 }
 
 /// Python process configuration
@@ -599,6 +976,55 @@ impl IsolationConfig {
     pub fn default_inherit_env() -> Vec<String> {
         default_inherit_env()
     }
+
+    /// Validate `extra_path` / `extra_pythonpath` entries
+    ///
+    /// Entries are joined with the platform list separator, so they must not
+    /// contain one; relative entries resolve against the app directory and
+    /// must stay inside it.
+    pub fn validate(&self, section: &str) -> PackResult<()> {
+        for (key, entries) in [
+            ("extra_path", &self.extra_path),
+            ("extra_pythonpath", &self.extra_pythonpath),
+        ] {
+            for entry in entries {
+                let invalid = |reason: &str| {
+                    PackError::Config(format!(
+                        "Invalid {} entry '{}' in [{}]: {}",
+                        key, entry, section, reason
+                    ))
+                };
+                if entry.trim().is_empty() {
+                    return Err(invalid("must not be empty"));
+                }
+                // "C:\tools" is fine, "a:b" would split into two entries
+                let without_drive = match entry.as_bytes() {
+                    [drive, b':', ..] if drive.is_ascii_alphabetic() => &entry[2..],
+                    _ => entry.as_str(),
+                };
+                if entry.contains(['\0', ';']) || without_drive.contains(':') {
+                    return Err(invalid("contains a path list separator"));
+                }
+                let path = std::path::Path::new(entry);
+                let escapes = path.is_relative()
+                    && without_drive == entry
+                    && path
+                        .components()
+                        .try_fold(0usize, |depth, c| match c {
+                            std::path::Component::ParentDir => depth.checked_sub(1),
+                            std::path::Component::Normal(_) => Some(depth + 1),
+                            _ => Some(depth),
+                        })
+                        .is_none();
+                if escapes {
+                    return Err(invalid(
+                        "relative entries must stay inside the app directory",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 /// PyOxidizer-specific configuration
@@ -677,11 +1103,17 @@ pub struct HooksConfig {
     #[serde(default)]
     pub before_collect: Vec<String>,
 
+    /// Commands to run after collection, before the output is written
+    #[serde(default)]
+    pub before_pack: Vec<String>,
+
     /// Additional file patterns to collect
     #[serde(default)]
     pub collect: Vec<CollectPattern>,
 
-    /// Commands to run after packing
+    /// Commands to run after packing (`${OUTPUT_EXE}`, `${OUTPUT_DIR}`,
+    /// `${PACKAGE_NAME}`, `${VERSION}` and `${TARGET}` are substituted in
+    /// the commands of every stage)
     #[serde(default)]
     pub after_pack: Vec<String>,
 
@@ -692,6 +1124,129 @@ pub struct HooksConfig {
     /// Vx-specific hook commands
     #[serde(default)]
     pub vx: VxHooksConfig,
+
+    /// Argv-based hook commands (`[[hooks.run]]`), run without a shell
+    #[serde(default)]
+    pub run: Vec<HookCommand>,
+
+    /// Kill a hook after this many seconds (default: no limit)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// Kill a hook after this many seconds without output (default: no limit)
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+
+    /// Log a "still running" line this often in seconds (default: 30, 0 disables)
+    #[serde(default)]
+    pub heartbeat_secs: Option<u64>,
+
+    /// Run hooks, frontend installs and backend builds in a controlled
+    /// environment (`[hooks.isolation]`) instead of the packer's own
+    #[serde(default)]
+    pub isolation: Option<IsolationConfig>,
+}
+
+/// Hook command given as program and arguments
+///
+/// Located at `[[hooks.run]]` in TOML. Unlike the string form, arguments are
+/// passed to the program as-is, so paths with spaces, quotes or non-ASCII
+/// characters need no quoting.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HookCommand {
+    /// Program to run (looked up in PATH)
+    pub program: String,
+
+    /// Arguments passed verbatim
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Stage to run at (`before_collect`, `before_pack` or `after_pack`)
+    #[serde(default)]
+    pub stage: crate::DownloadStage,
+
+    /// Working directory (relative paths resolve against the manifest)
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+
+    /// Extra environment variables
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    /// Run the program via vx
+    #[serde(default)]
+    pub use_vx: bool,
+
+    /// Timeout in seconds (overrides `[hooks] timeout_secs`)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// Idle-output timeout in seconds (overrides `[hooks] idle_timeout_secs`)
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+}
+
+impl HookCommand {
+    /// Create a hook command for the `before_collect` stage
+    pub fn new<I, S>(program: impl Into<String>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            stage: crate::DownloadStage::BeforeCollect,
+            cwd: None,
+            env: HashMap::new(),
+            use_vx: false,
+            timeout_secs: None,
+            idle_timeout_secs: None,
+        }
+    }
+
+    /// Set the stage to run at
+    pub fn with_stage(mut self, stage: crate::DownloadStage) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    /// Set the timeout in seconds
+    pub fn with_timeout(mut self, secs: u64) -> Self {
+        self.timeout_secs = Some(secs);
+        self
+    }
+
+    /// Set the idle-output timeout in seconds
+    pub fn with_idle_timeout(mut self, secs: u64) -> Self {
+        self.idle_timeout_secs = Some(secs);
+        self
+    }
+
+    /// Validate the command
+    pub fn validate(&self) -> PackResult<()> {
+        if self.program.trim().is_empty() {
+            return Err(PackError::Config(
+                "[[hooks.run]] requires a non-empty 'program'".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Render the command line for logs (arguments with spaces are quoted)
+    pub fn display(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|a| {
+                if a.is_empty() || a.contains(char::is_whitespace) || a.contains('"') {
+                    format!("\"{}\"", a.replace('"', "\\\""))
+                } else {
+                    a.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
 /// Vx-specific hook configuration
@@ -701,6 +1256,10 @@ pub struct VxHooksConfig {
     #[serde(default)]
     pub before_collect: Vec<String>,
 
+    /// Commands to run before the output is written using vx
+    #[serde(default)]
+    pub before_pack: Vec<String>,
+
     /// Commands to run after packing using vx
     #[serde(default)]
     pub after_pack: Vec<String>,
@@ -767,6 +1326,14 @@ pub struct DebugConfig {
     /// Remote debugging port for CDP connections
     #[serde(default)]
     pub remote_debugging_port: Option<u16>,
+
+    /// CDP test-run descriptor settings
+    #[serde(default)]
+    pub test: Option<CdpTestConfig>,
+
+    /// Post-pack smoke test settings
+    #[serde(default)]
+    pub smoke_test: Option<SmokeTestConfig>,
 }
 
 impl DebugConfig {
@@ -777,6 +1344,8 @@ impl DebugConfig {
             devtools: true,
             verbose: false,
             remote_debugging_port: None,
+            test: None,
+            smoke_test: None,
         }
     }
 
@@ -792,6 +1361,78 @@ impl DebugConfig {
     }
 }
 
+/// CDP test-run configuration
+///
+/// Located at `[debug.test]` in TOML. When enabled, a `<exe>.test.json`
+/// descriptor is written next to the packed executable for CI test runners.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdpTestConfig {
+    /// Emit the test-run descriptor
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Maximum time to wait for the app to start, in seconds
+    #[serde(default = "default_startup_timeout")]
+    pub startup_timeout: u64,
+
+    /// Health URL polled after the CDP endpoint is available
+    #[serde(default)]
+    pub health_url: Option<String>,
+}
+
+fn default_startup_timeout() -> u64 {
+    30
+}
+
+impl Default for CdpTestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            startup_timeout: default_startup_timeout(),
+            health_url: None,
+        }
+    }
+}
+
+/// Post-pack smoke test
+///
+/// Located at `[debug.smoke_test]` in TOML. After signing, the packed
+/// executable is run headless with `--self-check` and must report that its
+/// overlay, extraction and backend work (see [`crate::SmokeTestReport`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmokeTestConfig {
+    /// Run the smoke test
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Kill the executable after this many seconds
+    #[serde(default = "default_smoke_test_timeout")]
+    pub timeout: u64,
+
+    /// Fail the pack when the smoke test fails (else only report it)
+    #[serde(default = "default_true")]
+    pub required: bool,
+
+    /// Extra arguments passed after `--self-check`
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+fn default_smoke_test_timeout() -> u64 {
+    60
+}
+
+impl Default for SmokeTestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout: default_smoke_test_timeout(),
+            required: true,
+            args: Vec::new(),
+        }
+    }
+}
+
 // ============================================================================
 // Runtime Environment Configuration
 // ============================================================================
@@ -812,6 +1453,34 @@ pub struct RuntimeConfig {
     /// Working directory override
     #[serde(default)]
     pub working_dir: Option<PathBuf>,
+
+    /// Kiosk / digital-signage settings
+    #[serde(default)]
+    pub kiosk: Option<KioskConfig>,
+
+    /// Scheduled reload/restart policies
+    #[serde(default)]
+    pub schedule: Vec<ScheduleEntry>,
+
+    /// Cookie / localStorage persistence policy
+    #[serde(default)]
+    pub storage: Option<StorageConfig>,
+
+    /// Print and PDF export policy
+    #[serde(default)]
+    pub print: Option<PrintConfig>,
+
+    /// Multi-version coexistence policy
+    #[serde(default)]
+    pub versioning: Option<VersioningConfig>,
+
+    /// Self-update rollback policy
+    #[serde(default)]
+    pub rollback: Option<RollbackPolicy>,
+
+    /// Update channel and auto-update constraints
+    #[serde(default)]
+    pub update: Option<UpdatePolicy>,
 }
 
 impl RuntimeConfig {
@@ -829,6 +1498,604 @@ impl RuntimeConfig {
     }
 }
 
+/// Kiosk mode configuration for unattended displays
+///
+/// Located at `[runtime.kiosk]` in TOML. Lock-down options default to enabled
+/// once the section is present.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KioskConfig {
+    /// Run fullscreen and prevent leaving fullscreen
+    #[serde(default = "default_true")]
+    pub fullscreen_locked: bool,
+
+    /// Disable the context menu
+    #[serde(default = "default_true")]
+    pub disable_context_menu: bool,
+
+    /// Disable DevTools (overrides debug settings)
+    #[serde(default = "default_true")]
+    pub disable_devtools: bool,
+
+    /// Restart the app automatically if it crashes
+    #[serde(default)]
+    pub restart_on_crash: bool,
+
+    /// Reload the page after N minutes without user input
+    #[serde(default)]
+    pub idle_reload_minutes: Option<u32>,
+}
+
+impl Default for KioskConfig {
+    fn default() -> Self {
+        Self {
+            fullscreen_locked: true,
+            disable_context_menu: true,
+            disable_devtools: true,
+            restart_on_crash: false,
+            idle_reload_minutes: None,
+        }
+    }
+}
+
+impl KioskConfig {
+    /// Apply kiosk window settings (fullscreen, non-resizable, frameless)
+    pub fn apply_to_window(&self, window: &mut WindowConfig) {
+        if self.fullscreen_locked {
+            window.fullscreen = true;
+            window.resizable = false;
+            window.frameless = true;
+        }
+    }
+}
+
+/// Action performed by a scheduled policy
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleAction {
+    /// Reload the current page
+    Reload,
+    /// Restart the whole application (including backend)
+    Restart,
+}
+
+/// Scheduled reload/restart policy
+///
+/// Located at `[[runtime.schedule]]` in TOML. `cron` uses the five-field
+/// cron syntax (minute hour day month weekday), e.g. `"0 4 * * *"`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScheduleEntry {
+    /// Action to perform
+    pub action: ScheduleAction,
+
+    /// Cron-like schedule spec
+    pub cron: String,
+}
+
+impl ScheduleEntry {
+    /// Create a periodic page reload policy
+    pub fn reload(cron: impl Into<String>) -> Self {
+        Self {
+            action: ScheduleAction::Reload,
+            cron: cron.into(),
+        }
+    }
+
+    /// Create a periodic app restart policy
+    pub fn restart(cron: impl Into<String>) -> Self {
+        Self {
+            action: ScheduleAction::Restart,
+            cron: cron.into(),
+        }
+    }
+}
+
+/// Where WebView profile data (cookies, localStorage, cache) is stored
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageLocation {
+    /// Per-user application data directory (default)
+    #[default]
+    AppData,
+    /// `data` directory next to the executable
+    Portable,
+    /// Per-run temporary directory
+    Temp,
+    /// Explicit directory given by `path`
+    Custom,
+}
+
+/// Cookie / session persistence policy
+///
+/// Located at `[runtime.storage]` in TOML. Use `persist = false` or
+/// `clear_on_exit = true` on shared workstations so sessions do not leak
+/// between users.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageConfig {
+    /// Keep cookies and localStorage across runs
+    #[serde(default = "default_true")]
+    pub persist: bool,
+
+    /// Storage location for the WebView profile
+    #[serde(default)]
+    pub location: StorageLocation,
+
+    /// Directory for `location = "custom"` (may contain `$VAR`/`%VAR%`)
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Delete the WebView profile when the app exits
+    #[serde(default)]
+    pub clear_on_exit: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            persist: true,
+            location: StorageLocation::default(),
+            path: None,
+            clear_on_exit: false,
+        }
+    }
+}
+
+impl StorageConfig {
+    /// Ephemeral storage: nothing survives the process
+    pub fn ephemeral() -> Self {
+        Self {
+            persist: false,
+            location: StorageLocation::Temp,
+            path: None,
+            clear_on_exit: true,
+        }
+    }
+
+    /// Store the WebView profile in a custom directory
+    pub fn custom(path: impl Into<String>) -> Self {
+        Self {
+            location: StorageLocation::Custom,
+            path: Some(path.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Validate location/path consistency
+    pub fn validate(&self) -> PackResult<()> {
+        match (self.location, self.path.as_deref()) {
+            (StorageLocation::Custom, None) => Err(PackError::Config(
+                "[runtime.storage] location = \"custom\" requires 'path'".to_string(),
+            )),
+            (StorageLocation::Custom, Some(path)) if path.trim().is_empty() => Err(
+                PackError::Config("'path' in [runtime.storage] must not be empty".to_string()),
+            ),
+            (StorageLocation::Custom, Some(_)) | (_, None) => Ok(()),
+            (_, Some(_)) => Err(PackError::Config(
+                "'path' in [runtime.storage] is only used with location = \"custom\"".to_string(),
+            )),
+        }
+    }
+}
+
+// ============================================================================
+// Shortcuts Configuration
+// ============================================================================
+
+/// Quick actions of the app
+///
+/// Located at `[shortcuts]` in TOML. The tasks are stored in the overlay
+/// config; the runtime registers them as Windows jump-list tasks and macOS
+/// dock menu items, and installers read them from there.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutsConfig {
+    /// Tasks in menu order (`[[shortcuts.tasks]]`)
+    pub tasks: Vec<ShortcutTask>,
+}
+
+/// One quick action: the app started again with extra arguments
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShortcutTask {
+    /// Menu label (e.g., "Open logs folder")
+    pub name: String,
+
+    /// Arguments passed to the app
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Icon (.ico or .png); embedded under `shortcuts/` in the overlay
+    #[serde(default)]
+    pub icon: Option<PathBuf>,
+
+    /// Tooltip
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ShortcutTask {
+    /// Create a task
+    pub fn new(name: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            args,
+            icon: None,
+            description: None,
+        }
+    }
+
+    /// Set the icon
+    pub fn with_icon(mut self, icon: impl Into<PathBuf>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+}
+
+impl ShortcutsConfig {
+    /// Validate task names and icons
+    pub fn validate(&self) -> PackResult<()> {
+        let mut names = std::collections::HashSet::new();
+        for task in &self.tasks {
+            let name = task.name.trim();
+            // Jump-list titles are limited to MAX_PATH characters
+            if name.is_empty() || name.chars().count() > 260 {
+                return Err(PackError::Config(format!(
+                    "[[shortcuts.tasks]] name must be 1-260 characters, got '{}'",
+                    task.name
+                )));
+            }
+            if !names.insert(name.to_lowercase()) {
+                return Err(PackError::Config(format!(
+                    "Duplicate [[shortcuts.tasks]] name '{}'",
+                    task.name
+                )));
+            }
+            if task.args.iter().any(|arg| arg.contains('\0')) {
+                return Err(PackError::Config(format!(
+                    "[[shortcuts.tasks]] '{}' has an argument containing a NUL byte",
+                    task.name
+                )));
+            }
+            if let Some(ref icon) = task.icon {
+                let ext = icon
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .map(str::to_ascii_lowercase);
+                if !matches!(ext.as_deref(), Some("ico" | "png")) {
+                    return Err(PackError::Config(format!(
+                        "[[shortcuts.tasks]] '{}' icon must be .ico or .png: {}",
+                        task.name,
+                        icon.display()
+                    )));
+                }
+                if !icon.is_file() {
+                    return Err(PackError::AssetNotFound(icon.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Network Configuration
+// ============================================================================
+
+/// Network configuration
+///
+/// Located at `[network]` in TOML.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NetworkConfig {
+    /// Settings applied by the packed app at runtime
+    #[serde(default)]
+    pub runtime: NetworkRuntimeConfig,
+}
+
+/// Runtime network settings embedded in the overlay
+///
+/// Located at `[network.runtime]` in TOML. Certificate files are embedded
+/// under `certs/` and their paths rewritten to overlay asset paths.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct NetworkRuntimeConfig {
+    /// Additional trusted root CA bundles (PEM)
+    #[serde(default)]
+    pub ca_certificates: Vec<PathBuf>,
+
+    /// Client certificates for mutual TLS
+    #[serde(default)]
+    pub client_certificates: Vec<ClientCertificateConfig>,
+
+    /// HTTP headers injected into requests to configured origins
+    #[serde(default)]
+    pub headers: Vec<HeaderRule>,
+}
+
+impl NetworkRuntimeConfig {
+    /// Check if any runtime network settings are configured
+    pub fn is_empty(&self) -> bool {
+        self.ca_certificates.is_empty()
+            && self.client_certificates.is_empty()
+            && self.headers.is_empty()
+    }
+
+    /// Validate origins and header rules
+    pub fn validate(&self) -> PackResult<()> {
+        for cert in &self.client_certificates {
+            for origin in &cert.origins {
+                validate_origin(origin).map_err(|e| {
+                    PackError::Config(format!(
+                        "Invalid origin '{}' for client certificate {}: {}",
+                        origin,
+                        cert.path.display(),
+                        e
+                    ))
+                })?;
+            }
+        }
+
+        for header in &self.headers {
+            header.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// HTTP header injected into requests to specific origins
+///
+/// Located at `[[network.runtime.headers]]` in TOML. Exactly one of `value`
+/// or `value_env` must be set; use `value_env` for secrets such as auth
+/// tokens so they are read from the environment at runtime.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HeaderRule {
+    /// Header name (e.g., "X-Tenant-Id")
+    pub name: String,
+
+    /// Literal header value (stored in the overlay)
+    #[serde(default)]
+    pub value: Option<String>,
+
+    /// Environment variable providing the value at runtime
+    #[serde(default)]
+    pub value_env: Option<String>,
+
+    /// Origins the header is sent to (required, e.g. "https://internal.example.com")
+    pub origins: Vec<String>,
+}
+
+impl HeaderRule {
+    /// Create a header rule with a literal value
+    pub fn new(name: impl Into<String>, value: impl Into<String>, origins: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: Some(value.into()),
+            value_env: None,
+            origins,
+        }
+    }
+
+    /// Validate header name, value and origins
+    pub fn validate(&self) -> PackResult<()> {
+        let valid_name = !self.name.is_empty()
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c));
+        if !valid_name {
+            return Err(PackError::Config(format!(
+                "Invalid header name: '{}'",
+                self.name
+            )));
+        }
+
+        match (&self.value, &self.value_env) {
+            (Some(value), None) => {
+                if value.contains('\r') || value.contains('\n') {
+                    return Err(PackError::Config(format!(
+                        "Header '{}' value must not contain line breaks",
+                        self.name
+                    )));
+                }
+            }
+            (None, Some(_)) => {}
+            _ => {
+                return Err(PackError::Config(format!(
+                    "Header '{}' requires exactly one of 'value' or 'value_env'",
+                    self.name
+                )));
+            }
+        }
+
+        if self.origins.is_empty() {
+            return Err(PackError::Config(format!(
+                "Header '{}' requires at least one origin",
+                self.name
+            )));
+        }
+        for origin in &self.origins {
+            validate_origin(origin).map_err(|e| {
+                PackError::Config(format!(
+                    "Invalid origin '{}' for header '{}': {}",
+                    origin, self.name, e
+                ))
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Validate that an origin is an http(s) URL
+fn validate_origin(origin: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(origin).map_err(|e| e.to_string())?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(()),
+        scheme => Err(format!("unsupported scheme '{}'", scheme)),
+    }
+}
+
+/// Client certificate for mutual TLS
+///
+/// Located at `[[network.runtime.client_certificates]]` in TOML.
+///
+/// Passwords are never embedded: prefer a password-protected PKCS#12 file
+/// (`.p12`/`.pfx`) and supply the password through `password_env` at runtime.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ClientCertificateConfig {
+    /// Certificate file (PEM, or PKCS#12 `.p12`/`.pfx`)
+    pub path: PathBuf,
+
+    /// Private key file (PEM, when not contained in the certificate file)
+    #[serde(default)]
+    pub key: Option<PathBuf>,
+
+    /// Environment variable holding the certificate/key password at runtime
+    #[serde(default)]
+    pub password_env: Option<String>,
+
+    /// Origins the certificate is presented to (empty = all origins)
+    #[serde(default)]
+    pub origins: Vec<String>,
+}
+
+impl ClientCertificateConfig {
+    /// Check if the certificate is a PKCS#12 archive
+    pub fn is_pkcs12(&self) -> bool {
+        self.path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("p12") || e.eq_ignore_ascii_case("pfx"))
+            .unwrap_or(false)
+    }
+}
+
+// ============================================================================
+// License Policy Configuration
+// ============================================================================
+
+/// License compatibility policy for bundled third-party packages
+///
+/// Located at `[build.license_policy]` in TOML. Entries are SPDX identifiers
+/// matched case-insensitively; a trailing `*` matches a prefix (e.g.
+/// `"GPL-*"`). Denied prefixes also match licenses that only start with
+/// their name, so `"GPL-*"` denies a bare `GPL`. For `OR` expressions, a
+/// package passes if any alternative is acceptable.
+///
+/// Only the `standalone` and `portable` Python strategies keep the package
+/// metadata the policy is checked against; other strategies bundling
+/// packages are rejected while a policy is set.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct LicensePolicy {
+    /// Allowed licenses (empty = anything not denied)
+    #[serde(default)]
+    pub allowed: Vec<String>,
+
+    /// Denied licenses
+    #[serde(default)]
+    pub denied: Vec<String>,
+
+    /// Fail on packages without license metadata (default: warn)
+    #[serde(default)]
+    pub deny_unknown: bool,
+
+    /// Package names exempt from the policy
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+impl LicensePolicy {
+    /// Validate the policy itself
+    pub fn validate(&self) -> PackResult<()> {
+        for id in self.allowed.iter().chain(&self.denied) {
+            if id.trim().is_empty() {
+                return Err(PackError::Config(
+                    "[build.license_policy] entries must not be empty".to_string(),
+                ));
+            }
+        }
+        if let Some(id) = self
+            .allowed
+            .iter()
+            .find(|a| self.denied.iter().any(|d| d.eq_ignore_ascii_case(a)))
+        {
+            return Err(PackError::Config(format!(
+                "'{}' is both allowed and denied in [build.license_policy]",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check whether an SPDX license expression is acceptable
+    pub fn permits(&self, expression: &str) -> bool {
+        let expression = expression.replace(['(', ')'], " ");
+        expression.split(" OR ").any(|alternative| {
+            alternative.split(" AND ").all(|term| {
+                // "GPL-2.0-only WITH Classpath-exception-2.0" -> "GPL-2.0-only"
+                let id = term.split(" WITH ").next().unwrap_or_default().trim();
+                self.permits_id(id)
+            })
+        })
+    }
+
+    /// Check a single SPDX identifier
+    fn permits_id(&self, id: &str) -> bool {
+        let id = id.to_ascii_lowercase();
+        let matches = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => id.starts_with(&prefix.to_ascii_lowercase()),
+            None => id.eq_ignore_ascii_case(pattern),
+        };
+        // Free-form names from package metadata are not normalized: "GPL-*"
+        // must also deny "GPL" and "GPLv3"
+        let denies = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => id.starts_with(
+                &prefix
+                    .trim_end_matches(['-', '.', ' '])
+                    .to_ascii_lowercase(),
+            ),
+            None => matches(pattern),
+        };
+        !self.denied.iter().any(denies)
+            && (self.allowed.is_empty() || self.allowed.iter().any(matches))
+    }
+}
+
+// ============================================================================
+// About Configuration
+// ============================================================================
+
+/// About-screen data embedded in the overlay
+///
+/// Located at `[about]` in TOML. Application metadata is taken from
+/// `[package]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AboutConfig {
+    /// Changelog file (e.g., "CHANGELOG.md")
+    #[serde(default)]
+    pub changelog: Option<PathBuf>,
+
+    /// Application license text file (e.g., "LICENSE")
+    #[serde(default)]
+    pub license_file: Option<PathBuf>,
+
+    /// Generate third-party notices from bundled package metadata
+    #[serde(default = "default_true")]
+    pub third_party_notices: bool,
+
+    /// Application metadata (filled from `[package]`)
+    #[serde(skip)]
+    pub info: AboutInfo,
+}
+
+impl Default for AboutConfig {
+    fn default() -> Self {
+        Self {
+            changelog: None,
+            license_file: None,
+            third_party_notices: true,
+            info: AboutInfo::default(),
+        }
+    }
+}
+
 // ============================================================================
 // License Configuration
 // ============================================================================
@@ -869,6 +2136,14 @@ pub struct LicenseConfig {
     /// Custom expiration message
     #[serde(default)]
     pub expiration_message: Option<String>,
+
+    /// EULA shown before the first launch (`[license.eula]`)
+    #[serde(default)]
+    pub eula: Option<EulaConfig>,
+
+    /// Watermarked preview build (`[license.preview]`)
+    #[serde(default)]
+    pub preview: Option<PreviewConfig>,
 }
 
 impl LicenseConfig {
@@ -900,6 +2175,14 @@ impl LicenseConfig {
         }
     }
 
+    /// Create an expiring, watermarked preview build license
+    pub fn preview(expires_at: impl Into<String>) -> Self {
+        Self {
+            preview: Some(PreviewConfig::default()),
+            ..Self::time_limited(expires_at)
+        }
+    }
+
     /// Check if license validation is active
     pub fn is_active(&self) -> bool {
         self.enabled && (self.expires_at.is_some() || self.require_token)
@@ -975,6 +2258,153 @@ impl TargetPlatform {
     }
 }
 
+// ============================================================================
+// Frontend Dependencies Configuration
+// ============================================================================
+
+/// JavaScript package manager
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    /// npm (`package-lock.json`)
+    Npm,
+    /// Yarn classic or Berry (`yarn.lock`)
+    Yarn,
+    /// pnpm (`pnpm-lock.yaml`)
+    Pnpm,
+}
+
+impl PackageManager {
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "npm" => Some(Self::Npm),
+            "yarn" => Some(Self::Yarn),
+            "pnpm" => Some(Self::Pnpm),
+            _ => None,
+        }
+    }
+
+    /// Get string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Yarn => "yarn",
+            Self::Pnpm => "pnpm",
+        }
+    }
+
+    /// Lockfile name
+    pub fn lockfile(&self) -> &'static str {
+        match self {
+            Self::Npm => "package-lock.json",
+            Self::Yarn => "yarn.lock",
+            Self::Pnpm => "pnpm-lock.yaml",
+        }
+    }
+
+    /// Detect the package manager from the lockfile in `dir`
+    pub fn detect(dir: &std::path::Path) -> Option<Self> {
+        [Self::Pnpm, Self::Yarn, Self::Npm]
+            .into_iter()
+            .find(|pm| dir.join(pm.lockfile()).is_file())
+    }
+}
+
+/// Declarative frontend dependency install
+///
+/// Located at `[frontend.dependencies]` in TOML. Runs the package manager's
+/// install before hooks, so `[hooks]` build commands find `node_modules`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrontendDependencies {
+    /// Package manager (default: detected from the lockfile)
+    #[serde(default)]
+    pub manager: Option<PackageManager>,
+
+    /// Directory containing `package.json` (default: manifest directory)
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+
+    /// Install exactly what the lockfile pins (`npm ci`, `--frozen-lockfile`)
+    #[serde(default = "default_true")]
+    pub frozen_lockfile: bool,
+
+    /// Expected SHA-256 of the lockfile; packing fails if it differs
+    #[serde(default)]
+    pub lockfile_sha256: Option<String>,
+
+    /// Cache `node_modules` in the pack cache, keyed by lockfile hash
+    #[serde(default = "default_true")]
+    pub cache: bool,
+
+    /// Package script to run after installing (e.g., "build")
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+impl Default for FrontendDependencies {
+    fn default() -> Self {
+        Self {
+            manager: None,
+            dir: None,
+            frozen_lockfile: true,
+            lockfile_sha256: None,
+            cache: true,
+            script: None,
+        }
+    }
+}
+
+impl FrontendDependencies {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        if let Some(ref hash) = self.lockfile_sha256 {
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(PackError::Config(format!(
+                    "Invalid 'lockfile_sha256' in [frontend.dependencies]: {}",
+                    hash
+                )));
+            }
+        }
+        if self.script.as_deref().is_some_and(|s| s.trim().is_empty()) {
+            return Err(PackError::Config(
+                "'script' in [frontend.dependencies] must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Build Profile
+// ============================================================================
+
+/// Build profile for the packed executable
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildProfile {
+    /// Release build: frontend assets are embedded
+    #[default]
+    Release,
+    /// Development build: WebView loads a live dev server, backend is still bundled
+    Dev,
+}
+
+impl BuildProfile {
+    /// Convert to string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuildProfile::Release => "release",
+            BuildProfile::Dev => "dev",
+        }
+    }
+
+    /// Check if this is a development profile
+    pub fn is_dev(&self) -> bool {
+        matches!(self, BuildProfile::Dev)
+    }
+}
+
 // ============================================================================
 // Backward Compatibility Aliases
 // ============================================================================
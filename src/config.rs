@@ -3,17 +3,50 @@
 //! This module provides runtime configuration types for the packer.
 //! Common types are re-exported from the `common` module for consistency.
 
+use crate::branding::HtmlBranding;
+use crate::build_cache::IncrementalConfig;
+use crate::codec::CodecConfig;
 use crate::common::{
     default_module_search_paths, default_optimize, default_python_version, HooksConfig,
 };
+use crate::cuda::CudaConfig;
+use crate::history::HistoryConfig;
+use crate::http::HttpConfig;
+use crate::identity::AppIdentity;
+use crate::optimize::OptimizeConfig;
+use crate::output_path::sanitize_output_name;
+use crate::overlay::OverlayPlacement;
+use crate::permissions::PermissionsConfig;
+use crate::print_policy::PrintConfig;
 use crate::protection::ProtectionConfig;
+use crate::provenance::ProvenanceConfig;
+use crate::python_abi::EmbeddedPythonConfig;
+use crate::resume::ResumeConfig;
+use crate::retry::RetryPolicy;
+use crate::slots::RollbackPolicy;
+use crate::store::StoreConfig;
+use crate::symbols::SymbolsConfig;
+use crate::system_launcher::SystemPythonConfig;
+use crate::targets::BuildTarget;
+use crate::toolchain::ToolchainConfig;
+use crate::uninstall::UninstallConfig;
+use crate::update_feed::UpdateFeedConfig;
+use crate::update_policy::UpdatePolicy;
+use crate::versioning::VersioningConfig;
+use crate::PackResult;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
+use crate::signing::SigningConfig;
+use crate::transform::TransformConfig;
 // Re-export common types
 pub use crate::common::{
-    BundleStrategy, DebugConfig, IsolationConfig, LicenseConfig, TargetPlatform, WindowConfig,
+    AboutConfig, AppUserModelConfig, BuildProfile, BundleStrategy, CdpTestConfig,
+    ClientCertificateConfig, DebugConfig, FrontendDependencies, HeaderRule, IsolationConfig,
+    KioskConfig, LicenseConfig, LicensePolicy, NetworkRuntimeConfig, OutputLayout, ScheduleEntry,
+    ShortcutTask, ShortcutsConfig, SmokeTestConfig, StorageConfig, TargetPlatform, WindowConfig,
     WindowsPlatformConfig,
 };
 
@@ -44,6 +77,14 @@ pub enum PackMode {
         /// Python configuration (boxed to reduce enum size)
         python: Box<PythonBundleConfig>,
     },
+    /// Process mode: Frontend + prebuilt backend binary
+    Process {
+        /// Path to the frontend directory
+        #[serde(skip)]
+        frontend_path: PathBuf,
+        /// Launch specification for the bundled binary (boxed to reduce enum size)
+        launch: Box<LaunchSpec>,
+    },
 }
 
 impl PackMode {
@@ -53,12 +94,16 @@ impl PackMode {
             PackMode::Url { .. } => "url",
             PackMode::Frontend { .. } => "frontend",
             PackMode::FullStack { .. } => "fullstack",
+            PackMode::Process { .. } => "process",
         }
     }
 
     /// Check if this mode embeds assets
     pub fn embeds_assets(&self) -> bool {
-        matches!(self, PackMode::Frontend { .. } | PackMode::FullStack { .. })
+        matches!(
+            self,
+            PackMode::Frontend { .. } | PackMode::FullStack { .. } | PackMode::Process { .. }
+        )
     }
 
     /// Check if this mode includes Python backend
@@ -71,6 +116,7 @@ impl PackMode {
         match self {
             PackMode::Frontend { path } => Some(path),
             PackMode::FullStack { frontend_path, .. } => Some(frontend_path),
+            PackMode::Process { frontend_path, .. } => Some(frontend_path),
             PackMode::Url { .. } => None,
         }
     }
@@ -90,6 +136,90 @@ impl PackMode {
             _ => None,
         }
     }
+
+    /// Get the launch spec if applicable
+    pub fn launch_spec(&self) -> Option<&LaunchSpec> {
+        match self {
+            PackMode::Process { launch, .. } => Some(launch),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Launch Specification
+// ============================================================================
+
+/// Launch specification for a prebuilt backend binary (Process mode)
+///
+/// The binary is bundled into the overlay under `bin/` and the runtime
+/// starts it using the command, arguments and environment recorded here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchSpec {
+    /// Path to the binary on disk (pack time only)
+    #[serde(skip)]
+    pub binary: PathBuf,
+
+    /// Command to run, relative to the extract directory (e.g., "bin/server")
+    pub command: String,
+
+    /// Command line arguments
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Environment variables for the process
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    /// Working directory (relative to the extract directory)
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+
+    /// Show console window (Windows only)
+    #[serde(default)]
+    pub console: bool,
+
+    /// Health check configuration
+    #[serde(default)]
+    pub health_check: Option<crate::manifest::HealthCheckConfig>,
+
+    /// Restart the process if it crashes
+    #[serde(default)]
+    pub restart_on_crash: bool,
+
+    /// Maximum restart attempts
+    #[serde(default)]
+    pub max_restarts: u32,
+}
+
+impl LaunchSpec {
+    /// Create a launch spec for a binary, bundled as `bin/<file name>`
+    pub fn new(binary: impl Into<PathBuf>) -> Self {
+        let binary = binary.into();
+        let name = binary
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("backend")
+            .to_string();
+
+        Self {
+            binary,
+            command: format!("bin/{}", name),
+            args: Vec::new(),
+            env: HashMap::new(),
+            working_dir: None,
+            console: false,
+            health_check: None,
+            restart_on_crash: false,
+            max_restarts: 0,
+        }
+    }
+
+    /// Set command line arguments
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
 }
 
 // ============================================================================
@@ -174,6 +304,23 @@ pub struct PythonBundleConfig {
     /// Code protection configuration (py2pyd compilation)
     #[serde(default)]
     pub protection: ProtectionConfig,
+
+    /// Prebuilt environment archive (conda-pack or venv tarball) used as the
+    /// runtime instead of downloading Python and installing packages
+    #[serde(default)]
+    pub env_archive: Option<PathBuf>,
+
+    /// CUDA/cuDNN library handling (exclusion and runtime provisioning)
+    #[serde(default)]
+    pub cuda: Option<CudaConfig>,
+
+    /// System Python launcher (strategy = "system")
+    #[serde(default)]
+    pub system: Option<SystemPythonConfig>,
+
+    /// Host Python check of the embedded strategy
+    #[serde(default)]
+    pub embedded: Option<EmbeddedPythonConfig>,
 }
 
 fn default_true() -> bool {
@@ -202,6 +349,10 @@ impl Default for PythonBundleConfig {
             show_console: false,
             isolation: IsolationConfig::default(),
             protection: ProtectionConfig::default(),
+            env_archive: None,
+            cuda: None,
+            system: None,
+            embedded: None,
         }
     }
 }
@@ -238,6 +389,30 @@ impl PythonBundleConfig {
         self.isolation = isolation;
         self
     }
+
+    /// Use a prebuilt environment archive as the Python runtime
+    pub fn with_env_archive(mut self, path: impl Into<PathBuf>) -> Self {
+        self.env_archive = Some(path.into());
+        self
+    }
+
+    /// Set CUDA/cuDNN library handling
+    pub fn with_cuda(mut self, cuda: CudaConfig) -> Self {
+        self.cuda = Some(cuda);
+        self
+    }
+
+    /// Set the system Python launcher configuration
+    pub fn with_system(mut self, system: SystemPythonConfig) -> Self {
+        self.system = Some(system);
+        self
+    }
+
+    /// Set the embedded strategy's host Python check
+    pub fn with_embedded(mut self, embedded: EmbeddedPythonConfig) -> Self {
+        self.embedded = Some(embedded);
+        self
+    }
 }
 
 // ============================================================================
@@ -247,12 +422,21 @@ impl PythonBundleConfig {
 /// Complete pack configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackConfig {
-    /// Pack mode (URL, Frontend, or FullStack)
+    /// Pack mode (URL, Frontend, FullStack, or Process)
     pub mode: PackMode,
 
     /// Output executable name (without extension)
     pub output_name: String,
 
+    /// Application identifier (e.g., "com.example.app"), the source of the
+    /// platform identities returned by [`PackConfig::identity`]
+    #[serde(default)]
+    pub identifier: Option<String>,
+
+    /// Application version (`[package] version`)
+    #[serde(default)]
+    pub app_version: Option<String>,
+
     /// Output directory
     #[serde(skip)]
     pub output_dir: PathBuf,
@@ -272,7 +456,7 @@ pub struct PackConfig {
     #[serde(default)]
     pub allow_new_window: bool,
 
-    /// Custom user agent
+    /// Custom user agent (`{default}` is replaced with the WebView's own)
     #[serde(default)]
     pub user_agent: Option<String>,
 
@@ -313,6 +497,40 @@ pub struct PackConfig {
     #[serde(skip)]
     pub windows_resource: WindowsPlatformConfig,
 
+    /// Windows shell identity the runtime applies to the process and windows
+    #[serde(default)]
+    pub app_user_model: Option<AppUserModelConfig>,
+
+    /// Jump-list / dock menu quick actions
+    #[serde(default)]
+    pub shortcuts: Option<ShortcutsConfig>,
+
+    /// Overlay signing key (pack-time only)
+    #[serde(skip)]
+    pub signing: Option<SigningConfig>,
+
+    /// Download and file dialog permissions
+    #[serde(default)]
+    pub permissions: Option<PermissionsConfig>,
+
+    /// Uninstall helpers (scripts and the runtime's `--uninstall` flag)
+    #[serde(default)]
+    pub uninstall: Option<UninstallConfig>,
+
+    /// Append the overlay to the executable or write a sidecar (pack time only)
+    #[serde(skip)]
+    pub overlay_placement: OverlayPlacement,
+
+    /// Single executable or `<name>/` directory (`None`: the mode's own
+    /// layout, see [`layout`](Self::layout)); with `onedir` the runtime
+    /// reads assets from the directory instead of extracting them
+    #[serde(default)]
+    pub layout: Option<OutputLayout>,
+
+    /// Update feed written next to the executable (pack time only)
+    #[serde(skip)]
+    pub update_feed: Option<UpdateFeedConfig>,
+
     /// Vx configuration for dependency bootstrap
     #[serde(default)]
     pub vx: Option<crate::manifest::VxConfig>,
@@ -326,6 +544,173 @@ pub struct PackConfig {
     /// Recommended: 19 for release, 3 for development
     #[serde(default = "default_compression_level")]
     pub compression_level: i32,
+
+    /// Compression codec per asset class (pack time only; the overlay index
+    /// records each asset's codec)
+    #[serde(skip)]
+    pub codecs: CodecConfig,
+
+    /// Reuse compressed assets of earlier packs (pack time only)
+    #[serde(skip)]
+    pub incremental: Option<IncrementalConfig>,
+
+    /// Resume failed packs from the failed stage (pack time only)
+    #[serde(skip)]
+    pub resume: Option<ResumeConfig>,
+
+    /// Return the previous output when nothing changed (pack time only)
+    #[serde(skip)]
+    pub skip_unchanged: bool,
+
+    /// Threads reading and compressing assets (pack time only; `None` uses
+    /// all cores)
+    #[serde(skip)]
+    pub threads: Option<usize>,
+
+    /// Signed provenance written next to the executable (pack time only)
+    #[serde(skip)]
+    pub provenance: Option<ProvenanceConfig>,
+
+    /// SHA-256 of the manifest this configuration was loaded from (pack
+    /// time only; recorded in the provenance)
+    #[serde(skip)]
+    pub manifest_sha256: Option<String>,
+
+    /// Build matrix (pack time only; empty packs for the current platform)
+    #[serde(skip)]
+    pub targets: Vec<BuildTarget>,
+
+    /// Launcher stub per target name (pack time only)
+    #[serde(skip)]
+    pub stubs: BTreeMap<String, PathBuf>,
+
+    /// Target of this pack within the build matrix (pack time only)
+    #[serde(skip)]
+    pub build_target: Option<BuildTarget>,
+
+    /// Build metadata stored in the overlay (build ID, git commit, channel)
+    #[serde(skip)]
+    pub build_metadata: BTreeMap<String, String>,
+
+    /// Build profile (release or dev)
+    #[serde(default)]
+    pub profile: BuildProfile,
+
+    /// Live dev server URL loaded instead of embedded frontend assets (dev profile)
+    #[serde(default)]
+    pub dev_server_url: Option<String>,
+
+    /// CDP test-run descriptor settings (pack time only)
+    #[serde(skip)]
+    pub test_run: Option<CdpTestConfig>,
+
+    /// Post-pack smoke test (pack time only)
+    #[serde(skip)]
+    pub smoke_test: Option<SmokeTestConfig>,
+
+    /// Globs of frontend files to bundle, empty for all (pack time only)
+    #[serde(skip)]
+    pub frontend_include: Vec<String>,
+
+    /// Globs of frontend files and directories to skip (pack time only)
+    #[serde(skip)]
+    pub frontend_exclude: Vec<String>,
+
+    /// Transforms applied to frontend assets while bundling (pack time only)
+    #[serde(skip)]
+    pub frontend_transform: Option<TransformConfig>,
+
+    /// Branding rewrite for the bundled index.html (pack time only)
+    #[serde(skip)]
+    pub html_branding: Option<HtmlBranding>,
+
+    /// About-screen data to embed (pack time only)
+    #[serde(skip)]
+    pub about: Option<AboutConfig>,
+
+    /// Frontend dependency install run before hooks (pack time only)
+    #[serde(skip)]
+    pub frontend_dependencies: Option<FrontendDependencies>,
+
+    /// Pinned Go toolchain provisioned for hooks (pack time only)
+    #[serde(skip)]
+    pub go_toolchain: Option<ToolchainConfig>,
+
+    /// Pinned Rust toolchain provisioned for hooks (pack time only)
+    #[serde(skip)]
+    pub rust_toolchain: Option<ToolchainConfig>,
+
+    /// License policy for bundled third-party packages (pack time only)
+    #[serde(skip)]
+    pub license_policy: Option<LicensePolicy>,
+
+    /// Staging directory for intermediate files (default: system temp dir)
+    #[serde(skip)]
+    pub staging_dir: Option<PathBuf>,
+
+    /// Retry policy for modifying the output executable (pack time only)
+    #[serde(skip)]
+    pub file_retry: RetryPolicy,
+
+    /// Debug symbol stripping and collection (pack time only)
+    #[serde(skip)]
+    pub symbols: Option<SymbolsConfig>,
+
+    /// Size optimization of bundled native binaries (pack time only)
+    #[serde(skip)]
+    pub optimize: Option<OptimizeConfig>,
+
+    /// Artifact store location and size limits (pack time only)
+    #[serde(skip)]
+    pub store: StoreConfig,
+
+    /// Proxy, timeouts and certificate pins of downloads (pack time only)
+    #[serde(skip)]
+    pub http: HttpConfig,
+
+    /// Pack statistics history and regression detection (pack time only)
+    #[serde(skip)]
+    pub history: Option<HistoryConfig>,
+
+    /// Kiosk / digital-signage settings
+    #[serde(default)]
+    pub kiosk: Option<KioskConfig>,
+
+    /// Scheduled reload/restart policies
+    #[serde(default)]
+    pub schedule: Vec<ScheduleEntry>,
+
+    /// Cookie / localStorage persistence policy
+    #[serde(default)]
+    pub storage: Option<StorageConfig>,
+
+    /// Print and PDF export policy
+    #[serde(default)]
+    pub print: Option<PrintConfig>,
+
+    /// Multi-version coexistence policy
+    #[serde(default)]
+    pub versioning: Option<VersioningConfig>,
+
+    /// Self-update rollback policy (blue/green asset slots)
+    #[serde(default)]
+    pub rollback: Option<RollbackPolicy>,
+
+    /// Update channel and auto-update constraints
+    #[serde(default)]
+    pub update: Option<UpdatePolicy>,
+
+    /// Runtime network settings (CA bundles, client certificates)
+    #[serde(default)]
+    pub network: NetworkRuntimeConfig,
+
+    /// Top-level fields this release does not know, kept as read
+    ///
+    /// Set when reading a config written by a newer release; written back
+    /// unchanged, so repacking or patching the overlay does not drop them.
+    /// See [`SchemaReport`](crate::SchemaReport).
+    #[serde(flatten, default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub unknown_fields: BTreeMap<String, serde_json::Value>,
 }
 
 /// Default compression level (19 = high compression, good for releases)
@@ -364,21 +749,16 @@ mod serde_bytes_base64 {
 }
 
 impl PackConfig {
-    /// Create a URL mode configuration
-    pub fn url(url: impl Into<String>) -> Self {
-        let url = url.into();
-        let output_name = url
-            .replace("https://", "")
-            .replace("http://", "")
-            .replace("www.", "")
-            .split('.')
-            .next()
-            .unwrap_or("app")
-            .to_string();
-
+    /// Create a configuration for the given mode with default settings
+    ///
+    /// The output name is derived from a URL or path (e.g. `localhost:3000`),
+    /// so it is sanitized into a valid file name.
+    fn from_mode(mode: PackMode, output_name: String) -> Self {
         Self {
-            mode: PackMode::Url { url },
-            output_name,
+            mode,
+            output_name: sanitize_output_name(&output_name),
+            identifier: None,
+            app_version: None,
             output_dir: PathBuf::from("."),
             window: WindowConfig::default(),
             target_platform: TargetPlatform::Current,
@@ -394,12 +774,75 @@ impl PackConfig {
             hooks: None,
             remote_debugging_port: None,
             windows_resource: WindowsPlatformConfig::default(),
+            app_user_model: None,
+            shortcuts: None,
+            signing: None,
+            permissions: None,
+            uninstall: None,
+            overlay_placement: OverlayPlacement::default(),
+            layout: None,
+            update_feed: None,
             vx: None,
             downloads: vec![],
             compression_level: default_compression_level(),
+            codecs: CodecConfig::default(),
+            incremental: None,
+            resume: None,
+            skip_unchanged: false,
+            threads: None,
+            provenance: None,
+            manifest_sha256: None,
+            targets: Vec::new(),
+            stubs: BTreeMap::new(),
+            build_target: None,
+            build_metadata: BTreeMap::new(),
+            profile: BuildProfile::default(),
+            dev_server_url: None,
+            test_run: None,
+            smoke_test: None,
+            frontend_include: Vec::new(),
+            frontend_exclude: Vec::new(),
+            frontend_transform: None,
+            html_branding: None,
+            about: None,
+            frontend_dependencies: None,
+            go_toolchain: None,
+            rust_toolchain: None,
+            license_policy: None,
+            staging_dir: None,
+            file_retry: RetryPolicy::default(),
+            symbols: None,
+            optimize: None,
+            store: StoreConfig::default(),
+            http: HttpConfig::default(),
+            history: None,
+            kiosk: None,
+            schedule: Vec::new(),
+            storage: None,
+            print: None,
+            versioning: None,
+            rollback: None,
+            update: None,
+            network: NetworkRuntimeConfig::default(),
+            unknown_fields: BTreeMap::new(),
         }
     }
 
+    /// Create a URL mode configuration
+    pub fn url(url: impl Into<String>) -> Self {
+        let url = url.into();
+        let output_name = url
+            .replace("https://", "")
+            .replace("http://", "")
+            .replace("www.", "")
+            .split('.')
+            .next()
+            .unwrap_or("app")
+            .to_string();
+
+        Self::from_mode(PackMode::Url { url }, output_name)
+    }
+
     /// Create a frontend mode configuration
     pub fn frontend(path: impl Into<PathBuf>) -> Self {
         let path = path.into();
@@ -409,28 +852,7 @@ impl PackConfig {
             .unwrap_or("app")
             .to_string();
 
-        Self {
-            mode: PackMode::Frontend { path },
-            output_name,
-            output_dir: PathBuf::from("."),
-            window: WindowConfig::default(),
-            target_platform: TargetPlatform::Current,
-            debug: false,
-            allow_new_window: false,
-            user_agent: None,
-            inject_js: None,
-            inject_css: None,
-            icon_path: None,
-            window_icon: None,
-            env: HashMap::new(),
-            license: None,
-            hooks: None,
-            remote_debugging_port: None,
-            windows_resource: WindowsPlatformConfig::default(),
-            vx: None,
-            downloads: vec![],
-            compression_level: default_compression_level(),
-        }
+        Self::from_mode(PackMode::Frontend { path }, output_name)
     }
 
     /// Create a fullstack mode configuration (frontend + Python backend)
@@ -442,31 +864,13 @@ impl PackConfig {
             .unwrap_or("app")
             .to_string();
 
-        Self {
-            mode: PackMode::FullStack {
+        Self::from_mode(
+            PackMode::FullStack {
                 frontend_path,
                 python: Box::new(PythonBundleConfig::new(entry_point)),
             },
             output_name,
-            output_dir: PathBuf::from("."),
-            window: WindowConfig::default(),
-            target_platform: TargetPlatform::Current,
-            debug: false,
-            allow_new_window: false,
-            user_agent: None,
-            inject_js: None,
-            inject_css: None,
-            icon_path: None,
-            window_icon: None,
-            env: HashMap::new(),
-            license: None,
-            hooks: None,
-            remote_debugging_port: None,
-            windows_resource: WindowsPlatformConfig::default(),
-            vx: None,
-            downloads: vec![],
-            compression_level: default_compression_level(),
-        }
+        )
     }
 
     /// Create a fullstack mode configuration with full Python config
@@ -481,31 +885,31 @@ impl PackConfig {
             .unwrap_or("app")
             .to_string();
 
-        Self {
-            mode: PackMode::FullStack {
+        Self::from_mode(
+            PackMode::FullStack {
                 frontend_path,
                 python: Box::new(python),
             },
             output_name,
-            output_dir: PathBuf::from("."),
-            window: WindowConfig::default(),
-            target_platform: TargetPlatform::Current,
-            debug: false,
-            allow_new_window: false,
-            user_agent: None,
-            inject_js: None,
-            inject_css: None,
-            icon_path: None,
-            window_icon: None,
-            env: HashMap::new(),
-            license: None,
-            hooks: None,
-            remote_debugging_port: None,
-            windows_resource: WindowsPlatformConfig::default(),
-            vx: None,
-            downloads: vec![],
-            compression_level: default_compression_level(),
-        }
+        )
+    }
+
+    /// Create a process mode configuration (frontend + prebuilt backend binary)
+    pub fn process(frontend_path: impl Into<PathBuf>, launch: LaunchSpec) -> Self {
+        let frontend_path = frontend_path.into();
+        let output_name = frontend_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("app")
+            .to_string();
+
+        Self::from_mode(
+            PackMode::Process {
+                frontend_path,
+                launch: Box::new(launch),
+            },
+            output_name,
+        )
     }
 
     /// Set the output name
@@ -526,6 +930,23 @@ impl PackConfig {
         self
     }
 
+    /// Set the application identifier
+    pub fn with_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+
+    /// Set the application version
+    pub fn with_app_version(mut self, version: impl Into<String>) -> Self {
+        self.app_version = Some(version.into());
+        self
+    }
+
+    /// Platform identities derived from the identifier (or the output name)
+    pub fn identity(&self) -> AppIdentity {
+        AppIdentity::new(self.identifier.as_deref(), &self.output_name)
+    }
+
     /// Set the window size
     pub fn with_size(mut self, width: u32, height: u32) -> Self {
         self.window.width = width;
@@ -599,6 +1020,17 @@ impl PackConfig {
         self
     }
 
+    /// Mark this as a watermarked preview build expiring at `expires_at`
+    /// (enables license)
+    pub fn with_preview_build(mut self, expires_at: impl Into<String>) -> Self {
+        let mut license = self.license.unwrap_or_default();
+        license.enabled = true;
+        license.expires_at = Some(expires_at.into());
+        license.preview.get_or_insert_with(Default::default);
+        self.license = Some(license);
+        self
+    }
+
     /// Require token for authorization
     pub fn with_token_required(mut self) -> Self {
         let mut license = self.license.unwrap_or_default();
@@ -614,13 +1046,376 @@ impl PackConfig {
         self
     }
 
+    /// Point the WebView at a live dev server instead of embedding the frontend
+    ///
+    /// Switches to the dev profile; the backend is still bundled as usual.
+    pub fn with_dev_server(mut self, url: impl Into<String>) -> Self {
+        self.profile = BuildProfile::Dev;
+        self.dev_server_url = Some(url.into());
+        self
+    }
+
+    /// Check if the frontend is served by a dev server instead of being embedded
+    pub fn uses_dev_server(&self) -> bool {
+        self.profile.is_dev() && self.dev_server_url.is_some()
+    }
+
+    /// Emit a CDP test-run descriptor next to the packed executable
+    ///
+    /// Requires a remote debugging port.
+    pub fn with_test_run(mut self, test_run: CdpTestConfig) -> Self {
+        self.test_run = Some(test_run);
+        self
+    }
+
+    /// Run the packed executable with `--self-check` after packing
+    pub fn with_smoke_test(mut self, smoke_test: SmokeTestConfig) -> Self {
+        self.smoke_test = Some(smoke_test);
+        self
+    }
+
+    /// Bundle only the frontend files matching one of `patterns`
+    /// (`[frontend] include`)
+    pub fn with_frontend_include(mut self, patterns: Vec<String>) -> Self {
+        self.frontend_include = patterns;
+        self
+    }
+
+    /// Skip the frontend files and directories matching one of `patterns`
+    /// (`[frontend] exclude`)
+    pub fn with_frontend_exclude(mut self, patterns: Vec<String>) -> Self {
+        self.frontend_exclude = patterns;
+        self
+    }
+
+    /// Minify and optimize frontend assets while bundling
+    /// (`[frontend.transform]`)
+    pub fn with_frontend_transform(mut self, transform: TransformConfig) -> Self {
+        self.frontend_transform = Some(transform);
+        self
+    }
+
+    /// Rewrite `<title>` and branding meta tags of the bundled index.html
+    pub fn with_html_branding(mut self, branding: HtmlBranding) -> Self {
+        self.html_branding = Some(branding);
+        self
+    }
+
+    /// Embed changelog, license text and third-party notices for an About screen
+    pub fn with_about(mut self, about: AboutConfig) -> Self {
+        self.about = Some(about);
+        self
+    }
+
+    /// Pin the Go toolchain (downloaded when missing)
+    pub fn with_go_toolchain(mut self, toolchain: ToolchainConfig) -> Self {
+        self.go_toolchain = Some(toolchain);
+        self
+    }
+
+    /// Pin the Rust toolchain (installed via rustup when missing)
+    pub fn with_rust_toolchain(mut self, toolchain: ToolchainConfig) -> Self {
+        self.rust_toolchain = Some(toolchain);
+        self
+    }
+
+    /// Install frontend dependencies before running hooks
+    pub fn with_frontend_dependencies(mut self, deps: FrontendDependencies) -> Self {
+        self.frontend_dependencies = Some(deps);
+        self
+    }
+
+    /// Set the staging directory for intermediate files
+    pub fn with_staging_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.staging_dir = Some(dir.into());
+        self
+    }
+
+    /// Set the retry policy for file-lock errors on the output executable
+    pub fn with_file_retry(mut self, policy: RetryPolicy) -> Self {
+        self.file_retry = policy;
+        self
+    }
+
+    /// Strip bundled binaries and collect their debug symbols
+    pub fn with_symbols(mut self, symbols: SymbolsConfig) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    /// Strip (and optionally UPX-compress) bundled native binaries
+    pub fn with_optimize(mut self, optimize: OptimizeConfig) -> Self {
+        self.optimize = Some(optimize);
+        self
+    }
+
+    /// Configure the artifact store shared by the pack caches
+    pub fn with_store(mut self, store: StoreConfig) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Configure proxy, timeouts and certificate pins of downloads
+    pub fn with_http(mut self, http: HttpConfig) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Record pack statistics and flag size/time regressions
+    pub fn with_history(mut self, history: HistoryConfig) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Fail packing when a bundled package violates the license policy
+    pub fn with_license_policy(mut self, policy: LicensePolicy) -> Self {
+        self.license_policy = Some(policy);
+        self
+    }
+
+    /// Enable kiosk mode (applies fullscreen lock to the window config)
+    pub fn with_kiosk(mut self, kiosk: KioskConfig) -> Self {
+        kiosk.apply_to_window(&mut self.window);
+        self.kiosk = Some(kiosk);
+        self
+    }
+
+    /// Add a scheduled reload/restart policy
+    pub fn with_schedule(mut self, entry: ScheduleEntry) -> Self {
+        self.schedule.push(entry);
+        self
+    }
+
+    /// Set the cookie / localStorage persistence policy
+    pub fn with_storage(mut self, storage: StorageConfig) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Emit uninstall helpers
+    pub fn with_uninstall(mut self, uninstall: UninstallConfig) -> Self {
+        self.uninstall = Some(uninstall);
+        self
+    }
+
+    /// Set the multi-version coexistence policy
+    pub fn with_versioning(mut self, versioning: VersioningConfig) -> Self {
+        self.versioning = Some(versioning);
+        self
+    }
+
+    /// Set the self-update rollback policy
+    pub fn with_rollback(mut self, rollback: RollbackPolicy) -> Self {
+        self.rollback = Some(rollback);
+        self
+    }
+
+    /// Set the update channel and auto-update constraints
+    pub fn with_update_policy(mut self, update: UpdatePolicy) -> Self {
+        self.update = Some(update);
+        self
+    }
+
+    /// Set the print and PDF export policy
+    pub fn with_print(mut self, print: PrintConfig) -> Self {
+        self.print = Some(print);
+        self
+    }
+
+    /// Set the Windows AppUserModelID and taskbar metadata
+    pub fn with_app_user_model(mut self, app_user_model: AppUserModelConfig) -> Self {
+        self.app_user_model = Some(app_user_model);
+        self
+    }
+
+    /// Sign the overlay with an Ed25519 key
+    pub fn with_signing(mut self, signing: SigningConfig) -> Self {
+        self.signing = Some(signing);
+        self
+    }
+
+    /// Add a build metadata entry stored in the overlay
+    pub fn with_build_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.build_metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the compression codec per asset class
+    pub fn with_codecs(mut self, codecs: CodecConfig) -> Self {
+        self.codecs = codecs;
+        self
+    }
+
+    /// Reuse compressed assets of earlier packs from a build cache
+    pub fn with_incremental(mut self, incremental: IncrementalConfig) -> Self {
+        self.incremental = Some(incremental);
+        self
+    }
+
+    /// Resume a failed pack from the stage it failed in
+    pub fn with_resume(mut self, resume: ResumeConfig) -> Self {
+        self.resume = Some(resume);
+        self
+    }
+
+    /// Return the previous output instead of packing when nothing changed
+    ///
+    /// [`Packer::with_force`](crate::Packer::with_force) packs anyway.
+    pub fn with_skip_unchanged(mut self, skip: bool) -> Self {
+        self.skip_unchanged = skip;
+        self
+    }
+
+    /// Read and compress assets with at most `threads` threads
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Write a signed SLSA provenance statement next to the executable
+    /// (requires [`signing`](Self::signing))
+    pub fn with_provenance(mut self, provenance: ProvenanceConfig) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Pack one executable per target (see [`Packer::pack_targets`])
+    ///
+    /// [`Packer::pack_targets`]: crate::Packer::pack_targets
+    pub fn with_targets(mut self, targets: Vec<BuildTarget>) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    /// Use `stub` as the launcher executable of `target`
+    pub fn with_stub(mut self, target: BuildTarget, stub: impl Into<PathBuf>) -> Self {
+        self.stubs.insert(target.to_string(), stub.into());
+        self
+    }
+
+    /// Launcher stub configured for `target`
+    pub fn stub(&self, target: &BuildTarget) -> Option<&PathBuf> {
+        self.stubs.get(&target.to_string())
+    }
+
+    /// This configuration narrowed to one target of the build matrix
+    ///
+    /// The output is named `<name>-<arch>-<os>` so the targets of one
+    /// matrix do not overwrite each other.
+    pub fn for_target(&self, target: BuildTarget) -> Self {
+        let mut config = self.clone();
+        config.output_name = format!("{}-{}", self.output_name, target);
+        config.target_platform = target.platform;
+        config.build_target = Some(target);
+        config.targets = Vec::new();
+        config
+    }
+
+    /// Write a single executable or an output directory
+    pub fn with_layout(mut self, layout: OutputLayout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    /// Hash of the settings that determine the packed output (hex SHA-256)
+    ///
+    /// Settings that only apply once the output is written (signing, update
+    /// feed, provenance, history) are left out, so fixing one of them keeps
+    /// the hash.
+    pub fn config_hash(&self) -> PackResult<String> {
+        let pack_time = serde_json::json!({
+            "config": self,
+            "output_dir": self.output_dir,
+            "icon_path": self.icon_path,
+            "windows_resource": self.windows_resource,
+            "overlay_placement": self.overlay_placement,
+            "codecs": self.codecs,
+            "stubs": self.stubs,
+            "build_target": self.build_target.map(|target| target.to_string()),
+            "build_metadata": self.build_metadata,
+            "html_branding": format!("{:?}", self.html_branding),
+            "about": self.about,
+            "frontend_dependencies": self.frontend_dependencies,
+            "go_toolchain": self.go_toolchain,
+            "rust_toolchain": self.rust_toolchain,
+            "license_policy": self.license_policy,
+            "symbols": self.symbols,
+            "optimize": self.optimize,
+        });
+        Ok(format!(
+            "{:x}",
+            Sha256::digest(serde_json::to_vec(&pack_time)?)
+        ))
+    }
+
+    /// Effective output layout: the configured one, else `onedir` for the
+    /// Python strategies that always write a directory and `onefile` for
+    /// everything else
+    pub fn layout(&self) -> OutputLayout {
+        match (self.layout, &self.mode) {
+            (Some(layout), _) => layout,
+            (None, PackMode::FullStack { python, .. }) if python.strategy.is_directory() => {
+                OutputLayout::Onedir
+            }
+            (None, _) => OutputLayout::Onefile,
+        }
+    }
+
+    /// Write the overlay to `<name>.avpk` next to the executable
+    pub fn with_sidecar_overlay(mut self) -> Self {
+        self.overlay_placement = OverlayPlacement::Sidecar;
+        self
+    }
+
+    /// Write an update feed (`latest.json`) for the packed executable
+    pub fn with_update_feed(mut self, update_feed: UpdateFeedConfig) -> Self {
+        self.update_feed = Some(update_feed);
+        self
+    }
+
+    /// Set the download and file dialog permissions
+    pub fn with_permissions(mut self, permissions: PermissionsConfig) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    /// Add a jump-list / dock menu task
+    pub fn with_shortcut_task(mut self, task: ShortcutTask) -> Self {
+        self.shortcuts
+            .get_or_insert_with(Default::default)
+            .tasks
+            .push(task);
+        self
+    }
+
+    /// Trust an additional root CA bundle (PEM) at runtime
+    pub fn with_ca_certificate(mut self, path: impl Into<PathBuf>) -> Self {
+        self.network.ca_certificates.push(path.into());
+        self
+    }
+
+    /// Add a client certificate for mutual TLS
+    pub fn with_client_certificate(mut self, cert: ClientCertificateConfig) -> Self {
+        self.network.client_certificates.push(cert);
+        self
+    }
+
+    /// Inject an HTTP header into requests to the given origins
+    pub fn with_header(mut self, header: HeaderRule) -> Self {
+        self.network.headers.push(header);
+        self
+    }
+
     /// Get debug configuration
     pub fn debug_config(&self) -> DebugConfig {
+        let devtools_locked = self.kiosk.as_ref().is_some_and(|k| k.disable_devtools);
         DebugConfig {
             enabled: self.debug,
-            devtools: self.debug,
+            devtools: self.debug && !devtools_locked,
             verbose: false,
             remote_debugging_port: self.remote_debugging_port,
+            test: self.test_run.clone(),
+            smoke_test: self.smoke_test.clone(),
         }
     }
 }
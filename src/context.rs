@@ -0,0 +1,276 @@
+//! Per-pack execution context
+//!
+//! A pack run takes a few things from its environment: the directory
+//! relative paths resolve against, the cache and temp roots, the platform it
+//! runs on, its own executable (the launcher) and the time. [`PackContext`]
+//! captures them once per [`Packer`](crate::Packer) instead of reading
+//! process-global state during the run, so a service can run several packs
+//! in one process, each with its own directories:
+//!
+//! ```rust,ignore
+//! let context = PackContext::new("/srv/jobs/42")
+//!     .with_cache_root("/srv/cache")
+//!     .with_temp_root("/srv/jobs/42/tmp");
+//! let output = Packer::new(config).with_context(context).pack()?;
+//! ```
+
+use crate::config::PackMode;
+use crate::manifest::{default_vx_cache_dir, VxConfig};
+use crate::targets::BuildTarget;
+use crate::PackConfig;
+use rand::Rng;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds since the Unix epoch of `time` (0 before it)
+pub(crate) fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Nanoseconds since the Unix epoch of `time` (0 before it)
+pub(crate) fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    /// Current time
+    fn now(&self) -> SystemTime;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock standing still at one time (reproducible builds and tests)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub SystemTime);
+
+impl FixedClock {
+    /// Clock at `secs` seconds since the Unix epoch
+    pub fn from_unix(secs: u64) -> Self {
+        Self(UNIX_EPOCH + std::time::Duration::from_secs(secs))
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// Directories, platform and clock of a pack run
+#[derive(Clone)]
+pub struct PackContext {
+    base_dir: PathBuf,
+    cache_root: Option<PathBuf>,
+    temp_root: PathBuf,
+    target: Option<BuildTarget>,
+    launcher: Option<PathBuf>,
+    clock: Arc<dyn Clock>,
+}
+
+impl PackContext {
+    /// Context of the running process: its working directory, the system
+    /// temp directory and cache locations, its own executable and the
+    /// system clock
+    pub fn from_process() -> Self {
+        Self {
+            base_dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            cache_root: None,
+            temp_root: std::env::temp_dir(),
+            target: BuildTarget::current().ok(),
+            launcher: std::env::current_exe().ok(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Process context with relative paths resolved against `base_dir`
+    pub fn new(base_dir: impl Into<PathBuf>) -> Self {
+        Self::from_process().with_base_dir(base_dir)
+    }
+
+    /// Resolve relative paths against `base_dir`
+    pub fn with_base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = base_dir.into();
+        self
+    }
+
+    /// Keep the artifact store, build cache, checkpoints and pack history
+    /// under `cache_root` unless configured explicitly
+    pub fn with_cache_root(mut self, cache_root: impl Into<PathBuf>) -> Self {
+        self.cache_root = Some(cache_root.into());
+        self
+    }
+
+    /// Stage intermediate files under `temp_root` unless `[build]
+    /// staging_dir` is set
+    pub fn with_temp_root(mut self, temp_root: impl Into<PathBuf>) -> Self {
+        self.temp_root = temp_root.into();
+        self
+    }
+
+    /// Platform the packer runs on (`None`: only stubbed targets are packed)
+    pub fn with_target(mut self, target: Option<BuildTarget>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Launcher copied into executables for [`PackContext::target`]
+    pub fn with_launcher(mut self, launcher: impl Into<PathBuf>) -> Self {
+        self.launcher = Some(launcher.into());
+        self
+    }
+
+    /// Take timestamps and run IDs from `clock`
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Directory relative paths resolve against
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// Root of the pack caches (`None`: the crate defaults)
+    pub fn cache_root(&self) -> Option<&Path> {
+        self.cache_root.as_deref()
+    }
+
+    /// Default staging root
+    pub fn temp_root(&self) -> &Path {
+        &self.temp_root
+    }
+
+    /// Platform the packer runs on
+    pub fn target(&self) -> Option<BuildTarget> {
+        self.target
+    }
+
+    /// Launcher of [`PackContext::target`]
+    pub fn launcher(&self) -> Option<&Path> {
+        self.launcher.as_deref()
+    }
+
+    /// Current time
+    pub fn now(&self) -> SystemTime {
+        self.clock.now()
+    }
+
+    /// Current time in seconds since the Unix epoch
+    pub fn unix_time(&self) -> u64 {
+        unix_seconds(self.now())
+    }
+
+    /// `path`, joined to the base directory if relative
+    pub fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.base_dir.join(path)
+        }
+    }
+
+    /// Whether executables for `target` run on this platform
+    pub fn is_current(&self, target: &BuildTarget) -> bool {
+        self.target.as_ref() == Some(target)
+    }
+
+    /// Generate a unique run ID (`<unix seconds>-<random hex>`)
+    pub fn new_run_id(&self) -> String {
+        format!(
+            "{}-{:08x}",
+            self.unix_time(),
+            rand::thread_rng().gen::<u32>()
+        )
+    }
+
+    /// Staging root of `config`: `[build] staging_dir` or the temp root
+    pub(crate) fn staging_root(&self, config: &PackConfig) -> PathBuf {
+        match config.staging_dir {
+            Some(ref dir) => self.resolve(dir),
+            None => self.temp_root.clone(),
+        }
+    }
+
+    /// `configured`, else `<cache root>/<kind>` (`None`: the crate default)
+    pub(crate) fn cache_dir(&self, configured: Option<&Path>, kind: &str) -> Option<PathBuf> {
+        match configured {
+            Some(dir) => Some(self.resolve(dir)),
+            None => self.cache_root.as_ref().map(|root| root.join(kind)),
+        }
+    }
+
+    /// `[vx] cache_dir` resolved against the base directory; the default
+    /// goes under the cache root if one is set
+    pub(crate) fn vx_cache_dir(&self, vx: &VxConfig) -> PathBuf {
+        let dir = self.resolve(&vx.cache_dir);
+        match self.cache_root {
+            Some(ref root) if dir == self.resolve(&default_vx_cache_dir()) => root.join("vx"),
+            _ => dir,
+        }
+    }
+
+    /// Resolve the relative paths of `config` against the base directory
+    pub(crate) fn rebase(&self, config: &mut PackConfig) {
+        let resolve = |path: &mut PathBuf| *path = self.resolve(path);
+        resolve(&mut config.output_dir);
+        if let Some(ref mut dir) = config.staging_dir {
+            resolve(dir);
+        }
+        match config.mode {
+            PackMode::Url { .. } => {}
+            PackMode::Frontend { ref mut path } => resolve(path),
+            PackMode::FullStack {
+                ref mut frontend_path,
+                ref mut python,
+            } => {
+                resolve(frontend_path);
+                python.include_paths.iter_mut().for_each(resolve);
+            }
+            PackMode::Process {
+                ref mut frontend_path,
+                ref mut launch,
+            } => {
+                resolve(frontend_path);
+                resolve(&mut launch.binary);
+            }
+        }
+        config.stubs.values_mut().for_each(resolve);
+        if let Some(ref mut vx) = config.vx {
+            resolve(&mut vx.cache_dir);
+        }
+    }
+}
+
+impl Default for PackContext {
+    fn default() -> Self {
+        Self::from_process()
+    }
+}
+
+impl fmt::Debug for PackContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PackContext")
+            .field("base_dir", &self.base_dir)
+            .field("cache_root", &self.cache_root)
+            .field("temp_root", &self.temp_root)
+            .field("target", &self.target)
+            .field("launcher", &self.launcher)
+            .field("now", &self.now())
+            .finish()
+    }
+}
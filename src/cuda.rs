@@ -0,0 +1,314 @@
+//! CUDA/cuDNN shared library handling
+//!
+//! GPU wheels (torch, jax, the `nvidia-*` packages) carry gigabytes of
+//! platform-specific CUDA libraries. With `[backend.python.cuda]` they are
+//! left out of the bundle and declared in the overlay instead, either as a
+//! system requirement or as a component the runtime downloads:
+//!
+//! ```toml
+//! [backend.python.cuda]
+//! exclude = true                 # default
+//! provide = "download"           # "system" | "download"
+//! version = "12.1"
+//! url = "https://example.com/cuda-libs-12.1-linux-x64.tar.gz"
+//! sha256 = "..."
+//! ```
+//!
+//! The declaration is written to [`CUDA_REQUIREMENTS_PATH`]. Downloaded
+//! archives are extracted into the extraction root and must contain the
+//! listed library paths. Packs without the section still report bundled
+//! CUDA libraries and their size.
+
+use crate::overlay::OverlayData;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+
+/// Overlay path of the CUDA requirements declaration
+pub const CUDA_REQUIREMENTS_PATH: &str = "cuda_requirements.json";
+
+/// File name prefixes of CUDA toolkit, cuDNN and NCCL shared libraries
+/// (Linux `lib*.so*`, Windows `*64_*.dll`, and the `nvidia-*` wheels)
+const CUDA_LIBRARY_PREFIXES: &[&str] = &[
+    "cudart",
+    "cublas",
+    "cudnn",
+    "cufft",
+    "cufile",
+    "curand",
+    "cusolver",
+    "cusparse",
+    "nccl",
+    "nvrtc",
+    "nvjitlink",
+    "nvtoolsext",
+    "cupti",
+    "nvperf",
+    "nvblas",
+];
+
+/// Only libraries at least this large are listed in the size report
+const REPORT_MIN_SIZE: u64 = 1024 * 1024;
+
+/// How excluded CUDA libraries are provided on target machines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CudaProvision {
+    /// Installed on the system (CUDA toolkit / driver), checked at runtime
+    #[default]
+    System,
+    /// Downloaded by the runtime from `url` on first run
+    Download,
+}
+
+/// CUDA/cuDNN library configuration
+///
+/// Located at `[backend.python.cuda]` in TOML.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct CudaConfig {
+    /// Leave CUDA/cuDNN shared libraries out of the bundle
+    pub exclude: bool,
+
+    /// How excluded libraries are provided
+    pub provide: CudaProvision,
+
+    /// Required CUDA version (e.g., "12.1")
+    pub version: Option<String>,
+
+    /// Archive with the excluded libraries (`provide = "download"`)
+    pub url: Option<String>,
+
+    /// SHA-256 of the archive at `url`
+    pub sha256: Option<String>,
+
+    /// Additional glob patterns (overlay paths) treated as CUDA libraries
+    pub patterns: Vec<String>,
+}
+
+impl Default for CudaConfig {
+    fn default() -> Self {
+        Self {
+            exclude: true,
+            provide: CudaProvision::default(),
+            version: None,
+            url: None,
+            sha256: None,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+impl CudaConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        if self.provide == CudaProvision::Download {
+            if self.url.as_deref().is_none_or(str::is_empty) {
+                return Err(PackError::Config(
+                    "[backend.python.cuda] provide = \"download\" requires 'url'".to_string(),
+                ));
+            }
+            if self.sha256.is_none() {
+                return Err(PackError::Config(
+                    "[backend.python.cuda] provide = \"download\" requires 'sha256'".to_string(),
+                ));
+            }
+        }
+        if let Some(ref hash) = self.sha256 {
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(PackError::Config(format!(
+                    "Invalid 'sha256' in [backend.python.cuda]: {}",
+                    hash
+                )));
+            }
+        }
+        for pattern in &self.patterns {
+            glob::Pattern::new(pattern).map_err(|e| {
+                PackError::Config(format!(
+                    "Invalid pattern '{}' in [backend.python.cuda]: {}",
+                    pattern, e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// CUDA requirements declared in the overlay for the runtime
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CudaRequirements {
+    /// How the excluded libraries are provided
+    pub provide: CudaProvision,
+    /// Required CUDA version
+    pub version: Option<String>,
+    /// Download URL (`provide = "download"`)
+    pub url: Option<String>,
+    /// SHA-256 of the download
+    pub sha256: Option<String>,
+    /// Overlay paths of the excluded libraries
+    pub libraries: Vec<String>,
+    /// Total size of the excluded libraries in bytes
+    pub excluded_size: u64,
+}
+
+/// Check whether a path names a CUDA/cuDNN shared library
+pub fn is_cuda_library(path: &str) -> bool {
+    let name = path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(path)
+        .to_ascii_lowercase();
+    let shared = name.ends_with(".dll") || name.ends_with(".dylib") || name.contains(".so");
+    let stem = name.strip_prefix("lib").unwrap_or(&name);
+    shared
+        && CUDA_LIBRARY_PREFIXES
+            .iter()
+            .any(|prefix| stem.starts_with(prefix))
+}
+
+/// Sorts package files into bundled and excluded CUDA libraries
+pub(crate) struct CudaLibraryFilter<'a> {
+    config: Option<&'a CudaConfig>,
+    patterns: Vec<glob::Pattern>,
+    excluded: Vec<(String, u64)>,
+    bundled: Vec<(String, u64)>,
+}
+
+impl<'a> CudaLibraryFilter<'a> {
+    /// Create a filter for `[backend.python.cuda]` (`None` only reports)
+    pub fn new(config: Option<&'a CudaConfig>) -> Self {
+        let patterns = config
+            .map(|c| {
+                c.patterns
+                    .iter()
+                    .filter_map(|p| glob::Pattern::new(p).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            config,
+            patterns,
+            excluded: Vec::new(),
+            bundled: Vec::new(),
+        }
+    }
+
+    /// Record a package file; returns `true` if it must be left out
+    pub fn exclude(&mut self, overlay_path: &str, size: u64) -> bool {
+        let is_cuda =
+            is_cuda_library(overlay_path) || self.patterns.iter().any(|p| p.matches(overlay_path));
+        if !is_cuda {
+            return false;
+        }
+
+        let entry = (overlay_path.to_string(), size);
+        if self.config.is_some_and(|c| c.exclude) {
+            self.excluded.push(entry);
+            true
+        } else {
+            self.bundled.push(entry);
+            false
+        }
+    }
+
+    /// Report CUDA library sizes and declare excluded libraries in the overlay
+    pub fn finish(mut self, overlay: &mut OverlayData) -> PackResult<()> {
+        if !self.bundled.is_empty() {
+            let total: u64 = self.bundled.iter().map(|(_, size)| size).sum();
+            tracing::warn!(
+                "Bundle contains {} CUDA/cuDNN libraries ({:.2} MB); set [backend.python.cuda] \
+                 exclude = true to provide them from the system or a download instead",
+                self.bundled.len(),
+                total as f64 / (1024.0 * 1024.0)
+            );
+            report_largest(&mut self.bundled);
+        }
+
+        let Some(config) = self.config.filter(|_| !self.excluded.is_empty()) else {
+            return Ok(());
+        };
+
+        let excluded_size: u64 = self.excluded.iter().map(|(_, size)| size).sum();
+        tracing::info!(
+            "Excluded {} CUDA/cuDNN libraries ({:.2} MB), provided by {}",
+            self.excluded.len(),
+            excluded_size as f64 / (1024.0 * 1024.0),
+            match config.provide {
+                CudaProvision::System => "the system",
+                CudaProvision::Download => "runtime download",
+            }
+        );
+        report_largest(&mut self.excluded);
+
+        let requirements = CudaRequirements {
+            provide: config.provide,
+            version: config.version.clone(),
+            url: config.url.clone(),
+            sha256: config.sha256.clone(),
+            libraries: self.excluded.into_iter().map(|(path, _)| path).collect(),
+            excluded_size,
+        };
+        overlay.add_asset(
+            CUDA_REQUIREMENTS_PATH.to_string(),
+            serde_json::to_vec_pretty(&requirements)?,
+        );
+        Ok(())
+    }
+}
+
+/// Log the largest libraries of a group
+fn report_largest(libraries: &mut [(String, u64)]) {
+    libraries.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    for (path, size) in libraries.iter().take(5) {
+        if *size < REPORT_MIN_SIZE {
+            break;
+        }
+        tracing::info!("  {:>9.2} MB  {}", *size as f64 / (1024.0 * 1024.0), path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_cuda_library() {
+        assert!(is_cuda_library("lib/nvidia/cudnn/lib/libcudnn.so.8"));
+        assert!(is_cuda_library("lib/torch/lib/libcublasLt.so.12"));
+        assert!(is_cuda_library("lib/torch/lib/cudnn_ops_infer64_8.dll"));
+        assert!(is_cuda_library(r"lib\torch\lib\cudart64_12.dll"));
+        assert!(is_cuda_library("lib/nvidia/nccl/lib/libnccl.so.2"));
+        assert!(!is_cuda_library("lib/torch/lib/libtorch_cpu.so"));
+        assert!(!is_cuda_library("lib/nvidia/cudnn/__init__.py"));
+        assert!(!is_cuda_library("lib/cublas_helpers.py"));
+    }
+
+    #[test]
+    fn test_filter_records_requirements() {
+        let config = CudaConfig {
+            version: Some("12.1".to_string()),
+            patterns: vec!["lib/torch/lib/libc10_cuda.so".to_string()],
+            ..Default::default()
+        };
+        let mut filter = CudaLibraryFilter::new(Some(&config));
+        assert!(filter.exclude("lib/torch/lib/libcudnn.so.8", 700));
+        assert!(filter.exclude("lib/torch/lib/libc10_cuda.so", 300));
+        assert!(!filter.exclude("lib/torch/lib/libtorch_cpu.so", 500));
+
+        let mut overlay = OverlayData::new(crate::PackConfig::url("https://example.com"));
+        filter.finish(&mut overlay).unwrap();
+        let (_, json) = overlay
+            .assets
+            .iter()
+            .find(|(path, _)| path == CUDA_REQUIREMENTS_PATH)
+            .unwrap();
+        let requirements: CudaRequirements = serde_json::from_slice(json).unwrap();
+        assert_eq!(requirements.provide, CudaProvision::System);
+        assert_eq!(requirements.version.as_deref(), Some("12.1"));
+        assert_eq!(requirements.libraries.len(), 2);
+        assert_eq!(requirements.excluded_size, 1000);
+
+        // Without configuration, libraries are bundled and only reported
+        let mut filter = CudaLibraryFilter::new(None);
+        assert!(!filter.exclude("lib/torch/lib/libcudnn.so.8", 700));
+    }
+}
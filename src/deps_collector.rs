@@ -4,11 +4,13 @@
 //! This module analyzes Python source files to find imports and collects the
 //! corresponding packages from the current Python environment.
 
+use crate::subprocess::{system_runner, CommandRunner};
 use crate::{PackError, PackResult};
 use std::collections::{HashMap, HashSet};
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 
 /// Collected dependency information
 #[derive(Debug, Clone)]
@@ -119,6 +121,8 @@ pub struct DepsCollector {
     exclude_packages: HashSet<String>,
     /// Additional packages to include
     include_packages: HashSet<String>,
+    /// Runs Python and pip
+    runner: Arc<dyn CommandRunner>,
 }
 
 impl DepsCollector {
@@ -128,6 +132,7 @@ impl DepsCollector {
             python_exe: Self::find_python_executable(),
             exclude_packages: default_excludes(),
             include_packages: HashSet::new(),
+            runner: system_runner(),
         }
     }
 
@@ -149,12 +154,18 @@ impl DepsCollector {
 
     /// Check if Python is available
     pub fn is_python_available(&self) -> bool {
-        Command::new(&self.python_exe)
-            .args(["--version"])
-            .output()
+        self.runner
+            .output(Command::new(&self.python_exe).args(["--version"]))
             .is_ok_and(|o| o.status.success())
     }
 
+    /// Run Python and pip through `runner` (the executable is still
+    /// detected on the system by [`new`](Self::new))
+    pub fn with_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
+
     /// Set the Python executable to use
     pub fn python_exe(mut self, path: impl Into<PathBuf>) -> Self {
         self.python_exe = path.into();
@@ -182,7 +193,10 @@ impl DepsCollector {
         tracing::info!("Python executable: {}", self.python_exe.display());
 
         // Get Python version
-        match Command::new(&self.python_exe).args(["--version"]).output() {
+        match self
+            .runner
+            .output(Command::new(&self.python_exe).args(["--version"]))
+        {
             Ok(output) if output.status.success() => {
                 let version = String::from_utf8_lossy(&output.stdout);
                 tracing::info!("Python version: {}", version.trim());
@@ -208,7 +222,10 @@ print(f"Executable: {sys.executable}")
 print(f"Prefix: {sys.prefix}")
 print(f"Site-packages: {site.getsitepackages()}")
 "#;
-        match Command::new(&self.python_exe).args(["-c", script]).output() {
+        match self
+            .runner
+            .output(Command::new(&self.python_exe).args(["-c", script]))
+        {
             Ok(output) if output.status.success() => {
                 for line in String::from_utf8_lossy(&output.stdout).lines() {
                     tracing::info!("  {}", line);
@@ -276,9 +293,13 @@ for imp in get_imports(file_path):
     print(imp)
 "#;
 
-        let output = Command::new(&self.python_exe)
-            .args(["-c", script, file_path.to_str().unwrap_or("")])
-            .output()
+        let output = self
+            .runner
+            .output(Command::new(&self.python_exe).args([
+                "-c",
+                script,
+                file_path.to_str().unwrap_or(""),
+            ]))
             .map_err(|e| PackError::Config(format!("Failed to run Python: {}", e)))?;
 
         if !output.status.success() {
@@ -319,9 +340,9 @@ elif spec and spec.submodule_search_locations:
             package_name
         );
 
-        let output = Command::new(&self.python_exe)
-            .args(["-c", &script])
-            .output()
+        let output = self
+            .runner
+            .output(Command::new(&self.python_exe).args(["-c", &script]))
             .map_err(|e| PackError::Config(format!("Failed to run Python: {}", e)))?;
 
         if !output.status.success() {
@@ -470,18 +491,21 @@ elif spec and spec.submodule_search_locations:
         tracing::info!("Installing {} packages with pip...", packages.len());
 
         // Use pip to install packages to dest_dir
-        let status = Command::new(&self.python_exe)
-            .args([
-                "-m",
-                "pip",
-                "install",
-                "--target",
-                dest_dir.to_str().unwrap_or("."),
-                "--no-compile",
-                "--no-deps",
-            ])
-            .args(packages)
-            .status()
+        let status = self
+            .runner
+            .status(
+                Command::new(&self.python_exe)
+                    .args([
+                        "-m",
+                        "pip",
+                        "install",
+                        "--target",
+                        dest_dir.to_str().unwrap_or("."),
+                        "--no-compile",
+                        "--no-deps",
+                    ])
+                    .args(packages),
+            )
             .map_err(|e| PackError::Config(format!("Failed to run pip: {}", e)))?;
 
         if !status.success() {
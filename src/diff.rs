@@ -0,0 +1,266 @@
+//! Comparing two packed executables
+//!
+//! Backs [`Packer::diff`](crate::Packer::diff): what changed between two
+//! releases, read from their overlays. Assets are matched by path and
+//! compared by content hash; the runtime configuration, build metadata
+//! and Python runtime are compared key by key, with nested configuration
+//! keys joined by dots (e.g. `window.width`).
+
+use crate::inspect::{inspect, InspectReport};
+use crate::PackResult;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// How an asset changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Only in the new executable
+    Added,
+    /// Only in the old executable
+    Removed,
+    /// In both, with different content
+    Changed,
+}
+
+impl ChangeKind {
+    /// Marker used in reports (`+`, `-`, `~`)
+    pub fn marker(&self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Removed => '-',
+            Self::Changed => '~',
+        }
+    }
+}
+
+/// Asset added, removed or changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetChange {
+    /// Overlay path
+    pub path: String,
+    /// Kind of change
+    pub kind: ChangeKind,
+    /// Uncompressed size in the old executable
+    pub old_size: Option<u64>,
+    /// Uncompressed size in the new executable
+    pub new_size: Option<u64>,
+}
+
+impl AssetChange {
+    /// Change of the uncompressed size in bytes
+    pub fn size_delta(&self) -> i64 {
+        self.new_size.unwrap_or(0) as i64 - self.old_size.unwrap_or(0) as i64
+    }
+}
+
+/// Configuration, metadata or runtime value that differs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueChange {
+    /// Dotted key (e.g. `window.title`)
+    pub key: String,
+    /// Value in the old executable (`None`: not set)
+    pub old: Option<Value>,
+    /// Value in the new executable (`None`: not set)
+    pub new: Option<Value>,
+}
+
+/// Result of [`Packer::diff`](crate::Packer::diff)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackDiff {
+    /// Old executable
+    pub old: PathBuf,
+    /// New executable
+    pub new: PathBuf,
+    /// Changed assets, by path
+    pub assets: Vec<AssetChange>,
+    /// Changed runtime configuration keys
+    pub config: Vec<ValueChange>,
+    /// Changed build metadata
+    pub metadata: Vec<ValueChange>,
+    /// Changed Python runtime keys (`version`, `target`, `archive_size`)
+    pub python_runtime: Vec<ValueChange>,
+}
+
+impl PackDiff {
+    /// Compare two inspected executables
+    pub fn between(old: &InspectReport, new: &InspectReport) -> PackResult<Self> {
+        let metadata = |report: &InspectReport| {
+            report
+                .metadata
+                .iter()
+                .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                .collect::<Map<_, _>>()
+        };
+        Ok(Self {
+            old: old.executable.clone(),
+            new: new.executable.clone(),
+            assets: asset_changes(old, new),
+            config: value_changes(
+                &serde_json::to_value(&old.config)?,
+                &serde_json::to_value(&new.config)?,
+            ),
+            metadata: value_changes(&Value::Object(metadata(old)), &Value::Object(metadata(new))),
+            python_runtime: value_changes(
+                &serde_json::to_value(&old.python_runtime)?,
+                &serde_json::to_value(&new.python_runtime)?,
+            ),
+        })
+    }
+
+    /// Nothing differs
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+            && self.config.is_empty()
+            && self.metadata.is_empty()
+            && self.python_runtime.is_empty()
+    }
+
+    /// Change of the total uncompressed asset size in bytes
+    pub fn size_delta(&self) -> i64 {
+        self.assets.iter().map(AssetChange::size_delta).sum()
+    }
+
+    /// Assets of one kind of change
+    pub fn assets_of(&self, kind: ChangeKind) -> impl Iterator<Item = &AssetChange> {
+        self.assets.iter().filter(move |a| a.kind == kind)
+    }
+
+    /// Change of an asset
+    pub fn asset(&self, path: &str) -> Option<&AssetChange> {
+        self.assets.iter().find(|a| a.path == path)
+    }
+
+    /// Change of a configuration key
+    pub fn config_change(&self, key: &str) -> Option<&ValueChange> {
+        self.config.iter().find(|c| c.key == key)
+    }
+}
+
+impl fmt::Display for PackDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Old:         {}", self.old.display())?;
+        writeln!(f, "New:         {}", self.new.display())?;
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+        writeln!(
+            f,
+            "Assets:      {} added, {} removed, {} changed ({:+} bytes)",
+            self.assets_of(ChangeKind::Added).count(),
+            self.assets_of(ChangeKind::Removed).count(),
+            self.assets_of(ChangeKind::Changed).count(),
+            self.size_delta()
+        )?;
+        for asset in &self.assets {
+            writeln!(
+                f,
+                "  {} {:>+12}  {}",
+                asset.kind.marker(),
+                asset.size_delta(),
+                asset.path
+            )?;
+        }
+        for (section, changes) in [
+            ("Config", &self.config),
+            ("Metadata", &self.metadata),
+            ("Python", &self.python_runtime),
+        ] {
+            for change in changes {
+                writeln!(
+                    f,
+                    "{:<12} {}: {} -> {}",
+                    format!("{}:", section),
+                    change.key,
+                    display_value(&change.old),
+                    display_value(&change.new)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare two packed executables
+pub(crate) fn diff(old: &Path, new: &Path) -> PackResult<PackDiff> {
+    PackDiff::between(&inspect(old)?, &inspect(new)?)
+}
+
+fn asset_changes(old: &InspectReport, new: &InspectReport) -> Vec<AssetChange> {
+    let old_assets: BTreeMap<_, _> = old.assets.iter().map(|a| (&a.path, a)).collect();
+    let new_assets: BTreeMap<_, _> = new.assets.iter().map(|a| (&a.path, a)).collect();
+    let mut paths: Vec<_> = old_assets.keys().chain(new_assets.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let (old, new) = (old_assets.get(path), new_assets.get(path));
+            let kind = match (old, new) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(old), Some(new)) if old.hash != new.hash => ChangeKind::Changed,
+                _ => return None,
+            };
+            Some(AssetChange {
+                path: path.to_string(),
+                kind,
+                old_size: old.map(|a| a.size),
+                new_size: new.map(|a| a.size),
+            })
+        })
+        .collect()
+}
+
+/// Differing leaves of two JSON values, objects flattened to dotted keys
+fn value_changes(old: &Value, new: &Value) -> Vec<ValueChange> {
+    let (mut old_leaves, mut new_leaves) = (BTreeMap::new(), BTreeMap::new());
+    flatten("", old, &mut old_leaves);
+    flatten("", new, &mut new_leaves);
+    let mut keys: Vec<_> = old_leaves
+        .keys()
+        .chain(new_leaves.keys())
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = old_leaves.remove(&key);
+            let new = new_leaves.remove(&key);
+            (old != new).then_some(ValueChange { key, old, new })
+        })
+        .collect()
+}
+
+/// Collect the leaves of `value` (`null` counts as not set)
+fn flatten(prefix: &str, value: &Value, leaves: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Null => {}
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, leaves);
+            }
+        }
+        _ => {
+            leaves.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+fn display_value(value: &Option<Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "(unset)".to_string(),
+    }
+}
@@ -0,0 +1,547 @@
+//! Preflight environment checks
+//!
+//! Backs [`Packer::doctor`](crate::Packer::doctor). Only what the current
+//! configuration needs is checked: Python/uv for the chosen bundle strategy,
+//! a C toolchain for py2pyd, PyOxidizer, package managers, signing
+//! certificates, reachability of the hosts downloads come from, free disk
+//! space and WebView2 for CDP test runs. Nothing is installed or downloaded.
+
+use crate::common::{BundleStrategy, PackageManager};
+use crate::protection::ProtectionMethodConfig;
+use crate::staging::{available_space, estimate_required_space};
+use crate::{PackConfig, PackContext, PackMode, PackResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Timeout of a network reachability probe
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Host of the python-build-standalone releases
+const PYTHON_STANDALONE_HOST: &str = "https://github.com";
+
+/// Host of the Python package index
+const PYPI_HOST: &str = "https://pypi.org";
+
+/// Host of the npm registry
+const NPM_HOST: &str = "https://registry.npmjs.org";
+
+/// Outcome of a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Requirement met
+    Pass,
+    /// Packing may still succeed, but something looks off
+    Warn,
+    /// Packing will fail
+    Fail,
+    /// Not checkable on this machine
+    Skip,
+}
+
+impl CheckStatus {
+    /// Short label (e.g., "ok", "FAIL")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "ok",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Skip => "skip",
+        }
+    }
+}
+
+/// One item of the checklist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DoctorCheck {
+    /// What was checked (e.g., "python", "network: github.com")
+    pub name: String,
+    /// Outcome
+    pub status: CheckStatus,
+    /// What was found
+    pub detail: String,
+    /// How to fix a failure or warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Check from a result (`Fail` with the error message on error)
+    pub(crate) fn from_result(name: &str, result: PackResult<()>, ok: &str) -> Self {
+        match result {
+            Ok(()) => Self::new(name, CheckStatus::Pass, ok),
+            Err(e) => Self::new(name, CheckStatus::Fail, e.to_string()),
+        }
+    }
+}
+
+/// Result of [`Packer::doctor`](crate::Packer::doctor)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DoctorReport {
+    /// Checks in the order they ran
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// Whether no check failed
+    pub fn is_ok(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Failed checks
+    pub fn failures(&self) -> impl Iterator<Item = &DoctorCheck> {
+        self.with_status(CheckStatus::Fail)
+    }
+
+    /// Checks with warnings
+    pub fn warnings(&self) -> impl Iterator<Item = &DoctorCheck> {
+        self.with_status(CheckStatus::Warn)
+    }
+
+    /// Find a check by name
+    pub fn get(&self, name: &str) -> Option<&DoctorCheck> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+
+    fn with_status(&self, status: CheckStatus) -> impl Iterator<Item = &DoctorCheck> {
+        self.checks.iter().filter(move |c| c.status == status)
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(
+                f,
+                "[{:>4}] {}: {}",
+                check.status.as_str(),
+                check.name,
+                check.detail
+            )?;
+            if let Some(ref hint) = check.hint {
+                writeln!(f, "       -> {}", hint)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run the environment checks for a configuration
+pub(crate) fn diagnose(config: &PackConfig, context: &PackContext) -> DoctorReport {
+    let mut checks = Vec::new();
+    let mut hosts = BTreeSet::new();
+
+    if let PackMode::FullStack { ref python, .. } = config.mode {
+        checks.push(check_python(python.strategy, &python.version));
+
+        let has_packages = !python.packages.is_empty() || python.requirements.is_some();
+        if has_packages {
+            checks.push(check_uv(python.strategy));
+            hosts.insert(PYPI_HOST.to_string());
+        }
+        if python.strategy.bundles_standalone() {
+            hosts.insert(PYTHON_STANDALONE_HOST.to_string());
+        }
+        if python.protection.enabled {
+            checks.push(check_protection(python.protection.method));
+        }
+    }
+
+    if let Some(ref deps) = config.frontend_dependencies {
+        let dir = deps.dir.as_deref().unwrap_or(Path::new("."));
+        let manager = deps
+            .manager
+            .or_else(|| PackageManager::detect(dir))
+            .unwrap_or(PackageManager::Npm);
+        checks.push(check_package_manager(manager));
+        hosts.insert(NPM_HOST.to_string());
+    }
+
+    checks.extend(check_signing(config));
+
+    // Hosts downloads come from
+    if let Some(url) = config.vx.as_ref().and_then(|vx| vx.runtime_url.clone()) {
+        hosts.insert(url);
+    }
+    hosts.extend(config.downloads.iter().map(|d| d.url.clone()));
+    if let Some(ref go) = config.go_toolchain {
+        hosts.insert(
+            go.url
+                .clone()
+                .unwrap_or_else(|| "https://go.dev".to_string()),
+        );
+    }
+    if let Some(ref rust) = config.rust_toolchain {
+        hosts.insert(
+            rust.url
+                .clone()
+                .unwrap_or_else(|| "https://static.rust-lang.org".to_string()),
+        );
+    }
+    if let Some(ref remote) = config.store.remote {
+        hosts.insert(remote.url.clone());
+    }
+    if let Some(ref url) = config.windows_resource.timestamp_url {
+        hosts.insert(url.clone());
+    }
+    checks.extend(check_network(&hosts));
+
+    checks.extend(check_disk_space(config, context));
+
+    if config.test_run.as_ref().is_some_and(|t| t.enabled) {
+        checks.push(check_webview2());
+    }
+
+    DoctorReport { checks }
+}
+
+// ============================================================================
+// Tools
+// ============================================================================
+
+fn check_python(strategy: BundleStrategy, version: &str) -> DoctorCheck {
+    match strategy {
+        // The runtime is downloaded; no host Python is involved
+        BundleStrategy::Standalone | BundleStrategy::Portable => DoctorCheck::new(
+            "python",
+            CheckStatus::Pass,
+            format!("python-build-standalone {} is downloaded", version),
+        ),
+        BundleStrategy::PyOxidizer => match crate::pyoxidizer::check_pyoxidizer() {
+            Ok(found) => DoctorCheck::new("pyoxidizer", CheckStatus::Pass, found),
+            Err(e) => DoctorCheck::new("pyoxidizer", CheckStatus::Fail, e.to_string())
+                .with_hint("Install with: cargo install pyoxidizer"),
+        },
+        // Packages are installed with (and the app runs on) the host Python
+        BundleStrategy::Embedded | BundleStrategy::System => {
+            let found = ["python", "python3", "py"]
+                .iter()
+                .find_map(|cmd| tool_version(cmd, &["--version"]));
+            match found {
+                Some(found) if found_version_matches(&found, version) => {
+                    DoctorCheck::new("python", CheckStatus::Pass, found)
+                }
+                Some(found) => DoctorCheck::new(
+                    "python",
+                    CheckStatus::Warn,
+                    format!("{} found, {} configured", found, version),
+                )
+                .with_hint(format!(
+                    "Install Python {} or update [python] version",
+                    version
+                )),
+                None => DoctorCheck::new(
+                    "python",
+                    CheckStatus::Fail,
+                    format!(
+                        "not found (the {} strategy uses the host Python)",
+                        strategy.as_str()
+                    ),
+                )
+                .with_hint(format!(
+                    "Install Python {} or use strategy = \"standalone\"",
+                    version
+                )),
+            }
+        }
+    }
+}
+
+/// Whether `Python 3.12.1` satisfies a configured `3.12`
+fn found_version_matches(found: &str, version: &str) -> bool {
+    let found = found.trim_start_matches("Python").trim();
+    found == version || found.starts_with(&format!("{}.", version))
+}
+
+fn check_uv(strategy: BundleStrategy) -> DoctorCheck {
+    match tool_version("uv", &["--version"]) {
+        Some(found) => DoctorCheck::new("uv", CheckStatus::Pass, found),
+        // Standalone and portable install with the bundled pip; the others
+        // fall back to uv
+        None if strategy.bundles_standalone() => DoctorCheck::new(
+            "uv",
+            CheckStatus::Pass,
+            "not found (packages are installed with the bundled pip)",
+        ),
+        None => DoctorCheck::new(
+            "uv",
+            CheckStatus::Warn,
+            "not found (used when pip is unavailable)",
+        )
+        .with_hint("Install with: curl -LsSf https://astral.sh/uv/install.sh | sh"),
+    }
+}
+
+fn check_protection(method: ProtectionMethodConfig) -> DoctorCheck {
+    let name = "protection";
+    match crate::protection::check_build_tools_available(method) {
+        Ok(()) => DoctorCheck::new(name, CheckStatus::Pass, "build tools available"),
+        Err(e) => {
+            let check = DoctorCheck::new(name, CheckStatus::Fail, e.to_string());
+            if !crate::protection::is_protection_available() {
+                check.with_hint("Rebuild with --features code-protection")
+            } else if cfg!(windows) {
+                check.with_hint("Install Visual Studio Build Tools (C++ workload)")
+            } else {
+                check.with_hint("Install a C compiler (gcc or clang) and Python headers")
+            }
+        }
+    }
+}
+
+fn check_package_manager(manager: PackageManager) -> DoctorCheck {
+    let program = manager.as_str();
+    let program = if cfg!(windows) {
+        format!("{}.cmd", program)
+    } else {
+        program.to_string()
+    };
+    match tool_version(&program, &["--version"]) {
+        Some(found) => DoctorCheck::new(manager.as_str(), CheckStatus::Pass, found),
+        None => DoctorCheck::new(manager.as_str(), CheckStatus::Fail, "not found")
+            .with_hint("Install Node.js from https://nodejs.org/"),
+    }
+}
+
+/// First output line of `program args`, if it runs successfully
+pub(crate) fn tool_version(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Older Pythons print their version to stderr
+    [&output.stdout, &output.stderr]
+        .iter()
+        .filter_map(|out| {
+            String::from_utf8_lossy(out)
+                .lines()
+                .next()
+                .map(String::from)
+        })
+        .map(|line| line.trim().to_string())
+        .find(|line| !line.is_empty())
+}
+
+// ============================================================================
+// Signing
+// ============================================================================
+
+fn check_signing(config: &PackConfig) -> Vec<DoctorCheck> {
+    let resource = &config.windows_resource;
+    let Some(ref certificate) = resource.certificate else {
+        return Vec::new();
+    };
+
+    let mut checks = vec![if certificate.is_file() {
+        DoctorCheck::new(
+            "signing certificate",
+            CheckStatus::Pass,
+            certificate.display().to_string(),
+        )
+    } else {
+        DoctorCheck::new(
+            "signing certificate",
+            CheckStatus::Fail,
+            format!("{} not found", certificate.display()),
+        )
+        .with_hint("Fix [bundle.platform.windows] certificate")
+    }];
+
+    // The password may name an environment variable
+    if let Some(ref password) = resource.certificate_password {
+        let is_env_name = password
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        if is_env_name && std::env::var_os(password).is_none() {
+            checks.push(
+                DoctorCheck::new(
+                    "signing password",
+                    CheckStatus::Warn,
+                    format!("environment variable {} is not set", password),
+                )
+                .with_hint(format!("Export {} before packing", password)),
+            );
+        }
+    }
+    checks
+}
+
+// ============================================================================
+// Network
+// ============================================================================
+
+fn check_network(urls: &BTreeSet<String>) -> Vec<DoctorCheck> {
+    let mut endpoints = BTreeSet::new();
+    for url in urls {
+        let Ok(parsed) = url::Url::parse(url) else {
+            continue;
+        };
+        // s3:// and gs:// caches resolve to their providers' HTTPS endpoints
+        let (host, port) = match parsed.scheme() {
+            "s3" => ("s3.amazonaws.com".to_string(), 443),
+            "gs" => ("storage.googleapis.com".to_string(), 443),
+            "http" | "https" => match (parsed.host_str(), parsed.port_or_known_default()) {
+                (Some(host), Some(port)) => (host.to_string(), port),
+                _ => continue,
+            },
+            _ => continue,
+        };
+        endpoints.insert((host, port));
+    }
+
+    endpoints
+        .into_iter()
+        .map(|(host, port)| {
+            let name = format!("network: {}", host);
+            match probe(&host, port) {
+                Ok(()) => DoctorCheck::new(name, CheckStatus::Pass, format!("{}:{}", host, port)),
+                Err(e) => DoctorCheck::new(
+                    name,
+                    CheckStatus::Warn,
+                    format!("{}:{} unreachable: {}", host, port, e),
+                )
+                .with_hint("Check proxy/firewall settings; cached artifacts may still suffice"),
+            }
+        })
+        .collect()
+}
+
+/// Open a TCP connection to `host:port`
+fn probe(host: &str, port: u16) -> std::io::Result<()> {
+    let mut last_error = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| std::io::Error::other("no addresses")))
+}
+
+// ============================================================================
+// Disk space
+// ============================================================================
+
+fn check_disk_space(config: &PackConfig, context: &PackContext) -> Vec<DoctorCheck> {
+    let estimate = estimate_required_space(config, context);
+    let staging_root = context.staging_root(config);
+    let mut checks = Vec::new();
+    for (what, path, required) in [
+        ("output", config.output_dir.as_path(), estimate.output),
+        ("staging", staging_root.as_path(), estimate.staging),
+    ] {
+        let name = format!("disk: {}", what);
+        let Some(available) = available_space(path) else {
+            checks.push(DoctorCheck::new(
+                name,
+                CheckStatus::Skip,
+                format!("free space of {} unknown", path.display()),
+            ));
+            continue;
+        };
+        // Same 10% headroom as the pack-time preflight
+        let required = required + required / 10;
+        let detail = format!(
+            "{}: {:.1} MB free, ~{:.1} MB needed",
+            path.display(),
+            available as f64 / (1024.0 * 1024.0),
+            required as f64 / (1024.0 * 1024.0)
+        );
+        checks.push(if available >= required {
+            DoctorCheck::new(name, CheckStatus::Pass, detail)
+        } else {
+            DoctorCheck::new(name, CheckStatus::Fail, detail)
+                .with_hint("Free up space or set [build] staging_dir")
+        });
+    }
+    checks
+}
+
+// ============================================================================
+// WebView2
+// ============================================================================
+
+/// WebView2 runtime client key of EdgeUpdate
+const WEBVIEW2_CLIENT: &str =
+    r"Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}";
+
+fn check_webview2() -> DoctorCheck {
+    let name = "webview2";
+    if !cfg!(windows) {
+        return DoctorCheck::new(name, CheckStatus::Skip, "only required on Windows");
+    }
+
+    let keys = [
+        format!(r"HKLM\SOFTWARE\WOW6432Node\{}", WEBVIEW2_CLIENT),
+        format!(r"HKLM\SOFTWARE\{}", WEBVIEW2_CLIENT),
+        format!(r"HKCU\SOFTWARE\{}", WEBVIEW2_CLIENT),
+    ];
+    let version = keys.iter().find_map(|key| {
+        let output = Command::new("reg")
+            .args(["query", key, "/v", "pv"])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find(|line| line.trim_start().starts_with("pv"))
+            .and_then(|line| line.split_whitespace().last().map(String::from))
+            .filter(|v| v != "0.0.0.0")
+    });
+
+    match version {
+        Some(version) => DoctorCheck::new(name, CheckStatus::Pass, format!("runtime {}", version)),
+        None => DoctorCheck::new(
+            name,
+            CheckStatus::Fail,
+            "runtime not installed (required by the CDP test run)",
+        )
+        .with_hint("Install from https://developer.microsoft.com/microsoft-edge/webview2/"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_found_version_matches() {
+        assert!(found_version_matches("Python 3.12.1", "3.12"));
+        assert!(found_version_matches("Python 3.12.1", "3.12.1"));
+        assert!(!found_version_matches("Python 3.1.2", "3.12"));
+        assert!(!found_version_matches("Python 3.11.9", "3.12"));
+    }
+
+    #[test]
+    fn test_network_endpoints_are_deduplicated() {
+        let urls: BTreeSet<String> = [
+            "http://127.0.0.1:9/a.tar.gz",
+            "http://127.0.0.1:9/b.tar.gz",
+            "not a url",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let checks = check_network(&urls);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].name, "network: 127.0.0.1");
+    }
+}
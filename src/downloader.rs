@@ -7,13 +7,20 @@
 //! - Security controls (domain whitelist, HTTPS enforcement)
 //! - Extraction (zip, tar.gz) with strip_components support
 
+use crate::cache_lock::{write_atomic, CacheLock};
 use crate::error::{PackError, PackResult};
+use crate::http::HttpClient;
+use crate::progress::ProgressReader;
+use crate::store::{missing_object, ArtifactStore, ObjectKind};
 use sha2::{Digest, Sha256, Sha512};
 use std::fs;
-use std::io::{self, Read, Write};
+use std::io;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
+/// Artifact store namespace of downloads
+const DOWNLOADS_NAMESPACE: &str = "downloads";
+
 /// Download manager for external dependencies
 pub struct Downloader {
     /// Cache directory for downloaded artifacts
@@ -28,6 +35,10 @@ pub struct Downloader {
     require_checksum: bool,
     /// Offline mode (only use cache)
     offline: bool,
+    /// Artifact store replacing `cache_dir`
+    store: Option<ArtifactStore>,
+    /// HTTP client (`[build.http]`)
+    http: HttpClient,
 }
 
 impl Downloader {
@@ -42,9 +53,26 @@ impl Downloader {
             offline: std::env::var("AURORAVIEW_OFFLINE")
                 .map(|v| v == "1" || v.to_lowercase() == "true")
                 .unwrap_or(false),
+            store: None,
+            http: HttpClient::default(),
         }
     }
 
+    /// Keep downloads in the artifact store instead of `cache_dir`
+    ///
+    /// Downloads use the store's HTTP client.
+    pub fn with_store(mut self, store: ArtifactStore) -> Self {
+        self.http = store.http().clone();
+        self.store = Some(store);
+        self
+    }
+
+    /// Set the HTTP client
+    pub fn with_http(mut self, http: HttpClient) -> Self {
+        self.http = http;
+        self
+    }
+
     /// Set insecure mode
     pub fn allow_insecure(mut self, allow: bool) -> Self {
         self.allow_insecure = allow;
@@ -105,6 +133,22 @@ impl Downloader {
             return Ok(cached);
         }
 
+        // Serialize concurrent downloads of the same artifact; another
+        // process may have finished it while we waited
+        let _lock = match self.store {
+            Some(ref store) => store.lock(DOWNLOADS_NAMESPACE, name)?,
+            None => CacheLock::acquire(&self.cache_dir.join(name))?,
+        };
+        if let Ok(cached) = self.get_from_cache(name, checksum) {
+            info!(
+                target: "auroraview::vx::download",
+                name = %name,
+                path = %cached.display(),
+                "Using artifact cached by a concurrent download"
+            );
+            return Ok(cached);
+        }
+
         // Download the file
         info!(
             target: "auroraview::vx::download",
@@ -137,11 +181,28 @@ impl Downloader {
             warn!("No checksum provided for {}, skipping verification", name);
         }
 
-        // Save to cache
-        self.save_to_cache(name, &content)?;
+        // Save to cache and return the cached path
+        self.save_to_cache(name, &content)
+    }
 
-        // Return cached path
-        self.get_cache_path(name)
+    /// Check a download without fetching it
+    ///
+    /// Applies the URL and checksum policies and returns the cached
+    /// artifact, if there is one.
+    pub fn resolve(
+        &self,
+        name: &str,
+        url: &str,
+        checksum: Option<&str>,
+    ) -> PackResult<Option<PathBuf>> {
+        self.validate_url(url)?;
+        if checksum.is_none() && self.require_checksum {
+            return Err(PackError::Config(format!(
+                "Checksum required but not provided for {}",
+                name
+            )));
+        }
+        Ok(self.get_from_cache(name, checksum).ok())
     }
 
     /// Extract an archive to a destination
@@ -248,16 +309,7 @@ impl Downloader {
 
     /// Fetch URL content
     fn fetch_url(&self, url: &str) -> PackResult<Vec<u8>> {
-        let response = ureq::get(url)
-            .call()
-            .map_err(|e| PackError::Config(format!("Failed to download {}: {}", url, e)))?;
-
-        let mut buffer = Vec::new();
-        response
-            .into_reader()
-            .read_to_end(&mut buffer)
-            .map_err(|e| PackError::Config(format!("Failed to read response: {}", e)))?;
-
+        let buffer = self.http.get_bytes(url)?;
         debug!("Downloaded {} bytes from {}", buffer.len(), url);
         Ok(buffer)
     }
@@ -293,24 +345,13 @@ impl Downloader {
         Ok(())
     }
 
-    /// Get cache path for a named artifact
-    fn get_cache_path(&self, name: &str) -> PackResult<PathBuf> {
-        let path = self.cache_dir.join(name);
-        if !path.exists() {
-            return Err(PackError::Config(format!(
-                "Artifact not found in cache: {}",
-                name
-            )));
-        }
-        Ok(path)
-    }
-
     /// Get artifact from cache (with optional checksum verification)
     fn get_from_cache(&self, name: &str, checksum: Option<&str>) -> PackResult<PathBuf> {
-        let path = self.cache_dir.join(name);
-        if !path.exists() {
-            return Err(PackError::Config(format!("Cache miss: {}", name)));
+        let path = match self.store {
+            Some(ref store) => store.resolve(DOWNLOADS_NAMESPACE, name),
+            None => Some(self.cache_dir.join(name)).filter(|p| p.exists()),
         }
+        .ok_or_else(|| PackError::Config(format!("Cache miss: {}", name)))?;
 
         // Verify checksum if provided
         if let Some(expected) = checksum {
@@ -321,14 +362,24 @@ impl Downloader {
         Ok(path)
     }
 
-    /// Save content to cache
-    fn save_to_cache(&self, name: &str, content: &[u8]) -> PackResult<()> {
-        fs::create_dir_all(&self.cache_dir)?;
-        let path = self.cache_dir.join(name);
-        let mut file = fs::File::create(&path)?;
-        file.write_all(content)?;
+    /// Save content to cache (atomically, so readers never see partial files)
+    fn save_to_cache(&self, name: &str, content: &[u8]) -> PackResult<PathBuf> {
+        let path = match self.store {
+            Some(ref store) => {
+                let digest = store.insert(name, content)?;
+                store.link(DOWNLOADS_NAMESPACE, name, ObjectKind::Blob, &digest)?;
+                store
+                    .blob(&digest)
+                    .ok_or_else(|| missing_object(DOWNLOADS_NAMESPACE, name))?
+            }
+            None => {
+                let path = self.cache_dir.join(name);
+                write_atomic(&path, content)?;
+                path
+            }
+        };
         info!("Saved to cache: {} ({} bytes)", name, content.len());
-        Ok(())
+        Ok(path)
     }
 
     /// Extract zip archive
@@ -339,26 +390,28 @@ impl Downloader {
         strip_components: usize,
     ) -> PackResult<()> {
         let file = fs::File::open(archive_path)?;
+        let progress = self
+            .http
+            .extract_tracker(&archive_name(archive_path), archive_path);
         let mut archive = zip::ZipArchive::new(file)
             .map_err(|e| PackError::Config(format!("Failed to read zip: {}", e)))?;
 
+        fs::create_dir_all(dest)?;
+        let root = fs::canonicalize(dest)?;
         for i in 0..archive.len() {
             let mut file = archive
                 .by_index(i)
                 .map_err(|e| PackError::Config(format!("Failed to read zip entry: {}", e)))?;
+            let compressed_size = file.compressed_size();
 
-            let file_path = file.mangled_name();
-            let stripped = self.strip_path_components(&file_path, strip_components);
+            let file_path = PathBuf::from(file.name());
+            let stripped = self.strip_path_components(&file_path, strip_components)?;
 
             if let Some(output_path) = stripped {
-                let full_path = dest.join(output_path);
-
                 if file.is_dir() {
-                    fs::create_dir_all(&full_path)?;
+                    crate::archive::create_dir_inside(&root, &output_path, &output_path)?;
                 } else {
-                    if let Some(parent) = full_path.parent() {
-                        fs::create_dir_all(parent)?;
-                    }
+                    let (_, full_path) = crate::archive::file_path_inside(&root, &output_path)?;
                     let mut outfile = fs::File::create(&full_path)?;
                     io::copy(&mut file, &mut outfile)?;
 
@@ -376,6 +429,9 @@ impl Downloader {
                     }
                 }
             }
+            if let Some(ref progress) = progress {
+                progress.advance(compressed_size);
+            }
         }
 
         Ok(())
@@ -388,21 +444,9 @@ impl Downloader {
         dest: &Path,
         strip_components: usize,
     ) -> PackResult<()> {
-        let file = fs::File::open(archive_path)?;
+        let file = self.open_archive(archive_path)?;
         let decoder = flate2::read::GzDecoder::new(file);
-        let mut archive = tar::Archive::new(decoder);
-
-        for entry in archive.entries()? {
-            let mut entry = entry?;
-            let path = entry.path()?;
-            let stripped = self.strip_path_components(&path, strip_components);
-
-            if let Some(output_path) = stripped {
-                let full_path = dest.join(output_path);
-                entry.unpack(&full_path)?;
-            }
-        }
-
+        crate::archive::unpack_tar(decoder, dest, strip_components)?;
         Ok(())
     }
 
@@ -413,38 +457,40 @@ impl Downloader {
         dest: &Path,
         strip_components: usize,
     ) -> PackResult<()> {
-        let file = fs::File::open(archive_path)?;
-        let mut archive = tar::Archive::new(file);
-
-        for entry in archive.entries()? {
-            let mut entry = entry?;
-            let path = entry.path()?;
-            let stripped = self.strip_path_components(&path, strip_components);
-
-            if let Some(output_path) = stripped {
-                let full_path = dest.join(output_path);
-                entry.unpack(&full_path)?;
-            }
-        }
-
+        let file = self.open_archive(archive_path)?;
+        crate::archive::unpack_tar(file, dest, strip_components)?;
         Ok(())
     }
 
-    /// Strip N path components from the beginning
-    fn strip_path_components(&self, path: &Path, n: usize) -> Option<PathBuf> {
-        if n == 0 {
-            return Some(path.to_path_buf());
-        }
+    /// Open a tar archive, counting its bytes towards the progress observer
+    fn open_archive(&self, archive_path: &Path) -> PackResult<Box<dyn io::Read>> {
+        let file = fs::File::open(archive_path)?;
+        Ok(
+            match self
+                .http
+                .extract_tracker(&archive_name(archive_path), archive_path)
+            {
+                Some(tracker) => Box::new(ProgressReader::new(file, tracker)),
+                None => Box::new(file),
+            },
+        )
+    }
 
-        let components: Vec<_> = path.components().skip(n).collect();
-        if components.is_empty() {
-            None
-        } else {
-            Some(components.iter().collect())
-        }
+    /// Strip N path components from the beginning (rejecting absolute
+    /// paths and `..`)
+    fn strip_path_components(&self, path: &Path, n: usize) -> PackResult<Option<PathBuf>> {
+        crate::archive::sanitize_entry_path(path, n)
     }
 }
 
+/// File name of an archive, for progress events
+fn archive_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -505,21 +551,24 @@ mod tests {
         let path = Path::new("a/b/c/file.txt");
 
         assert_eq!(
-            downloader.strip_path_components(path, 0),
+            downloader.strip_path_components(path, 0).unwrap(),
             Some(PathBuf::from("a/b/c/file.txt"))
         );
         assert_eq!(
-            downloader.strip_path_components(path, 1),
+            downloader.strip_path_components(path, 1).unwrap(),
             Some(PathBuf::from("b/c/file.txt"))
         );
         assert_eq!(
-            downloader.strip_path_components(path, 2),
+            downloader.strip_path_components(path, 2).unwrap(),
             Some(PathBuf::from("c/file.txt"))
         );
         assert_eq!(
-            downloader.strip_path_components(path, 3),
+            downloader.strip_path_components(path, 3).unwrap(),
             Some(PathBuf::from("file.txt"))
         );
-        assert_eq!(downloader.strip_path_components(path, 4), None);
+        assert_eq!(downloader.strip_path_components(path, 4).unwrap(), None);
+        assert!(downloader
+            .strip_path_components(Path::new("a/../../file.txt"), 1)
+            .is_err());
     }
 }
@@ -0,0 +1,302 @@
+//! Pack-time check of the Python entry point
+//!
+//! Before any runtime is downloaded, the entry point (`module:function`,
+//! `module` or a `script.py`) is parsed and its module looked up in the
+//! `include_paths`, laid out as they are bundled: the contents of a
+//! directory, or a single file by name. A module found nowhere fails the
+//! pack with the modules that do exist as candidates.
+//!
+//! Modules provided by packages cannot be resolved before the packages
+//! are installed: a module named like one of the `packages` (or a
+//! requirement) is accepted, and a missing module is only a warning when
+//! packages, an environment archive or the system Python could provide it.
+
+use crate::{BundleStrategy, PackError, PackResult, PythonBundleConfig};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Candidate modules listed in errors, at most
+const MAX_CANDIDATES: usize = 10;
+
+/// Depth of packages searched for candidate modules
+const CANDIDATE_DEPTH: usize = 3;
+
+/// File extensions of importable modules besides `.py`
+const MODULE_EXTENSIONS: &[&str] = &["pyc", "pyd", "so"];
+
+/// Parsed Python entry point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryPoint {
+    /// `package.module:function` (the function may be a dotted attribute),
+    /// or `package.module` run as `__main__`
+    Module {
+        /// Dotted module name
+        module: String,
+        /// Attribute called, if any
+        function: Option<String>,
+    },
+    /// Script run by path (`main.py`, `app/main.py`)
+    Script(PathBuf),
+}
+
+impl EntryPoint {
+    /// Parse an entry point
+    pub fn parse(entry_point: &str) -> PackResult<Self> {
+        let entry_point = entry_point.trim();
+        let invalid = |reason: &str| {
+            PackError::Config(format!(
+                "Invalid Python entry_point '{}': {} (expected \"module:function\", \
+                 \"package.module\" or \"script.py\")",
+                entry_point, reason
+            ))
+        };
+        if entry_point.is_empty() {
+            return Err(invalid("empty"));
+        }
+        if !entry_point.contains(':') && entry_point.ends_with(".py") {
+            return Ok(Self::Script(PathBuf::from(entry_point)));
+        }
+
+        let (module, function) = match entry_point.split_once(':') {
+            Some((module, function)) => (module, Some(function)),
+            None => (entry_point, None),
+        };
+        if !is_dotted_name(module) {
+            return Err(invalid(&format!("'{}' is not a module name", module)));
+        }
+        if let Some(function) = function {
+            if !is_dotted_name(function) {
+                return Err(invalid(&format!("'{}' is not a function name", function)));
+            }
+        }
+        Ok(Self::Module {
+            module: module.to_string(),
+            function: function.map(str::to_string),
+        })
+    }
+
+    /// Top-level package (or module) of a module entry point
+    pub fn top_level(&self) -> Option<&str> {
+        match self {
+            Self::Module { module, .. } => module.split('.').next(),
+            Self::Script(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for EntryPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Module {
+                module,
+                function: Some(function),
+            } => write!(f, "{}:{}", module, function),
+            Self::Module { module, .. } => f.write_str(module),
+            Self::Script(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Check that the entry point of `python` will be importable
+pub(crate) fn check_entry_point(python: &PythonBundleConfig) -> PackResult<()> {
+    let entry_point = EntryPoint::parse(&python.entry_point)?;
+    let found = match entry_point {
+        EntryPoint::Module { ref module, .. } => python
+            .include_paths
+            .iter()
+            .any(|root| module_exists(root, module)),
+        EntryPoint::Script(ref script) => python
+            .include_paths
+            .iter()
+            .any(|root| script_exists(root, script)),
+    };
+    if found {
+        return Ok(());
+    }
+
+    let packages = package_names(python);
+    if let Some(top_level) = entry_point.top_level() {
+        if packages.contains(&normalize(top_level)) {
+            return Ok(());
+        }
+    }
+
+    let what = match entry_point {
+        EntryPoint::Module { ref module, .. } => format!("module '{}'", module),
+        EntryPoint::Script(ref script) => format!("script '{}'", script.display()),
+    };
+    let mut message = format!(
+        "Python entry_point '{}': {} not found in include_paths",
+        entry_point, what
+    );
+    let candidates = candidate_modules(python, &entry_point);
+    if candidates.is_empty() {
+        message.push_str(" (no Python modules found there)");
+    } else {
+        message.push_str(&format!(" (candidates: {})", candidates.join(", ")));
+    }
+
+    if !packages.is_empty()
+        || python.env_archive.is_some()
+        || python.strategy == BundleStrategy::System
+    {
+        // A package or the system Python may still provide it
+        tracing::warn!("{}", message);
+        return Ok(());
+    }
+    Err(PackError::Config(message))
+}
+
+/// `name` is a dotted sequence of Python identifiers
+fn is_dotted_name(name: &str) -> bool {
+    name.split('.').all(|part| {
+        let mut chars = part.chars();
+        chars.next().is_some_and(|c| c == '_' || c.is_alphabetic())
+            && chars.all(|c| c == '_' || c.is_alphanumeric())
+    })
+}
+
+/// `module` is importable from the bundled `root`
+fn module_exists(root: &Path, module: &str) -> bool {
+    let parts: Vec<&str> = module.split('.').collect();
+    if root.is_file() {
+        // A single file is bundled by name: a top-level module only
+        return parts.len() == 1 && file_module_name(root).as_deref() == Some(parts[0]);
+    }
+    if !root.is_dir() {
+        return false;
+    }
+    let (name, packages) = parts.split_last().expect("module name is not empty");
+    let dir = packages
+        .iter()
+        .fold(root.to_path_buf(), |dir, p| dir.join(p));
+    if !dir.is_dir() {
+        return false;
+    }
+    // A package (regular or namespace) or a module file
+    dir.join(name).is_dir() || module_file(&dir, name)
+}
+
+/// `dir` holds a module file for `name` (`name.py`, `name.pyd`,
+/// `name.cpython-311-x86_64-linux-gnu.so`, ...)
+fn module_file(dir: &Path, name: &str) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.filter_map(|e| e.ok()).any(|entry| {
+        entry.path().is_file() && file_module_name(&entry.path()).as_deref() == Some(name)
+    })
+}
+
+/// Module name of a Python file (`None` for other files)
+fn file_module_name(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let (stem, extension) = name.rsplit_once('.')?;
+    if extension != "py" && !MODULE_EXTENSIONS.contains(&extension) {
+        return None;
+    }
+    // Extension modules carry an ABI tag: `name.cpython-311-...`
+    let module = stem.split('.').next()?;
+    is_dotted_name(module).then(|| module.to_string())
+}
+
+/// `script` is bundled from `root`
+fn script_exists(root: &Path, script: &Path) -> bool {
+    if root.is_file() {
+        return script.file_name().is_some() && root.file_name() == script.file_name();
+    }
+    root.join(script).is_file()
+}
+
+/// Normalized names of the packages and requirements
+fn package_names(python: &PythonBundleConfig) -> BTreeSet<String> {
+    let requirements = python
+        .requirements
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    python
+        .packages
+        .iter()
+        .map(String::as_str)
+        .chain(requirements.lines())
+        .filter_map(|requirement| {
+            let requirement = requirement.split('#').next()?.trim();
+            if requirement.is_empty() || requirement.starts_with('-') {
+                return None;
+            }
+            let end = requirement
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .unwrap_or(requirement.len());
+            let name = &requirement[..end];
+            (!name.is_empty()).then(|| normalize(name))
+        })
+        .collect()
+}
+
+/// Package name as a module name (`PyYAML-x` -> `pyyaml_x`)
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace(['-', '.'], "_")
+}
+
+/// Modules found in the include paths, those sharing the entry point's
+/// last name component first
+fn candidate_modules(python: &PythonBundleConfig, entry_point: &EntryPoint) -> Vec<String> {
+    let mut modules = BTreeSet::new();
+    for root in &python.include_paths {
+        if root.is_file() {
+            if let Some(name) = file_module_name(root) {
+                modules.insert(name);
+            }
+        } else if root.is_dir() {
+            collect_modules(root, "", CANDIDATE_DEPTH, &mut modules);
+        }
+    }
+
+    let wanted = match entry_point {
+        EntryPoint::Module { module, .. } => module.rsplit('.').next().unwrap_or(module),
+        EntryPoint::Script(script) => script
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default(),
+    };
+    let (mut close, rest): (Vec<_>, Vec<_>) = modules
+        .into_iter()
+        .partition(|m| m.rsplit('.').next() == Some(wanted));
+    close.extend(rest);
+    close.truncate(MAX_CANDIDATES);
+    close
+}
+
+fn collect_modules(dir: &Path, prefix: &str, depth: usize, modules: &mut BTreeSet<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let (name, is_package) = if path.is_dir() {
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !is_dotted_name(name) || name.contains('.') || !path.join("__init__.py").is_file() {
+                continue;
+            }
+            (name.to_string(), true)
+        } else {
+            match file_module_name(&path) {
+                Some(name) if name != "__init__" => (name, false),
+                _ => continue,
+            }
+        };
+        let module = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        if is_package && depth > 1 {
+            collect_modules(&path, &module, depth - 1, modules);
+        }
+        modules.insert(module);
+    }
+}
@@ -0,0 +1,288 @@
+//! Import of prebuilt Python environment archives
+//!
+//! `[backend.python] env_archive` points at a conda-pack or venv tarball
+//! produced by a separate, validated pipeline. Instead of downloading
+//! python-build-standalone and installing packages, the archive is unpacked
+//! to staging, checked for an interpreter, and re-archived with the
+//! `python/` prefix that runtime extraction expects.
+//!
+//! conda-pack archives carry `bin/conda-unpack`, which
+//! [`extract_runtime`](crate::extract_runtime) runs after the first
+//! extraction to rewrite the build machine's prefixes.
+
+use crate::cuda::is_cuda_library;
+use crate::downloader::Downloader;
+use crate::{PackError, PackResult};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Layout of an imported environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EnvArchiveKind {
+    /// conda-pack export (has `conda-meta/`)
+    CondaPack,
+    /// venv (has `pyvenv.cfg`; links to its base interpreter)
+    Venv,
+    /// Plain Python prefix
+    Prefix,
+}
+
+/// An environment archive prepared for embedding
+#[derive(Debug)]
+pub(crate) struct ImportedEnv {
+    /// Detected environment layout
+    pub kind: EnvArchiveKind,
+    /// Python version (`major.minor`) detected from the environment
+    pub version: Option<String>,
+    /// site-packages directory inside the unpacked environment
+    pub site_packages: Option<PathBuf>,
+    /// Re-packed `.tar.gz` with a top-level `python/` directory
+    pub archive: Vec<u8>,
+}
+
+/// Unpack, check and re-pack an environment archive
+///
+/// `staging` must outlive the returned `site_packages` path.
+pub(crate) fn import(archive: &Path, staging: &Path) -> PackResult<ImportedEnv> {
+    if !archive.is_file() {
+        return Err(PackError::AssetNotFound(archive.to_path_buf()));
+    }
+
+    tracing::info!(
+        "Importing Python environment archive: {}",
+        archive.display()
+    );
+    let unpacked = staging.join("env");
+    Downloader::new(staging.join("downloads")).extract(archive, &unpacked, 0)?;
+    let root = env_root(&unpacked);
+
+    let kind = if root.join("conda-meta").is_dir() {
+        EnvArchiveKind::CondaPack
+    } else if root.join("pyvenv.cfg").is_file() {
+        EnvArchiveKind::Venv
+    } else {
+        EnvArchiveKind::Prefix
+    };
+
+    let python_exe = root.join(python_exe_relative());
+    // symlink_metadata: venv interpreters are (possibly dangling) symlinks
+    if fs::symlink_metadata(&python_exe).is_err() {
+        return Err(PackError::Config(format!(
+            "Environment archive {} has no {} at its root{}",
+            archive.display(),
+            python_exe_relative().display(),
+            if kind == EnvArchiveKind::Venv && cfg!(windows) {
+                " (Windows venvs keep python.exe in Scripts/; export the environment with conda-pack)"
+            } else {
+                ""
+            }
+        )));
+    }
+
+    if kind == EnvArchiveKind::Venv {
+        let home = read_pyvenv_cfg(&root, "home").unwrap_or_else(|| "?".to_string());
+        tracing::warn!(
+            "{} is a venv: it links to its base interpreter ({}), which must exist at the \
+             same path on target machines; prefer conda-pack for self-contained environments",
+            archive.display(),
+            home
+        );
+    }
+
+    let version = detect_version(&root);
+    let site_packages = find_site_packages(&root);
+    tracing::info!(
+        "Environment: {:?}, Python {}, site-packages: {}",
+        kind,
+        version.as_deref().unwrap_or("unknown"),
+        site_packages
+            .as_deref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "none".to_string())
+    );
+
+    // Environment archives are embedded as-is; flag GPU libraries by size
+    let (cuda_count, cuda_size) = walkdir::WalkDir::new(&root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_cuda_library(&e.path().to_string_lossy()))
+        .fold((0usize, 0u64), |(count, size), e| {
+            (
+                count + 1,
+                size + e.metadata().map(|m| m.len()).unwrap_or_default(),
+            )
+        });
+    if cuda_count > 0 {
+        tracing::warn!(
+            "Environment archive contains {} CUDA/cuDNN libraries ({:.2} MB); remove them from \
+             the exported environment to ship a CPU-only or system-CUDA build",
+            cuda_count,
+            cuda_size as f64 / (1024.0 * 1024.0)
+        );
+    }
+
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    builder.follow_symlinks(false);
+    builder.append_dir_all("python", &root)?;
+    let archive = builder.into_inner()?.finish()?;
+
+    Ok(ImportedEnv {
+        kind,
+        version,
+        site_packages,
+        archive,
+    })
+}
+
+/// Interpreter path relative to the environment root, as expected by
+/// runtime extraction
+fn python_exe_relative() -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from("python.exe")
+    } else {
+        PathBuf::from("bin").join("python3")
+    }
+}
+
+/// Find the environment root (archives often wrap it in a single directory)
+fn env_root(unpacked: &Path) -> PathBuf {
+    let is_root = |dir: &Path| {
+        dir.join("conda-meta").is_dir()
+            || dir.join("pyvenv.cfg").is_file()
+            || fs::symlink_metadata(dir.join(python_exe_relative())).is_ok()
+    };
+    if is_root(unpacked) {
+        return unpacked.to_path_buf();
+    }
+
+    let entries: Vec<PathBuf> = fs::read_dir(unpacked)
+        .map(|rd| rd.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    match entries.as_slice() {
+        [single] if single.is_dir() && is_root(single) => single.clone(),
+        _ => unpacked.to_path_buf(),
+    }
+}
+
+/// Read a key from `pyvenv.cfg`
+fn read_pyvenv_cfg(root: &Path, key: &str) -> Option<String> {
+    let content = fs::read_to_string(root.join("pyvenv.cfg")).ok()?;
+    content.lines().find_map(|line| {
+        let (k, v) = line.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().to_string())
+    })
+}
+
+/// Detect the Python `major.minor` version of an environment
+fn detect_version(root: &Path) -> Option<String> {
+    let full = read_pyvenv_cfg(root, "version")
+        .or_else(|| read_pyvenv_cfg(root, "version_info"))
+        .or_else(|| {
+            // conda-meta/python-3.11.9-h955ad1f_0.json
+            fs::read_dir(root.join("conda-meta"))
+                .ok()?
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .find_map(|name| {
+                    let rest = name.strip_prefix("python-")?;
+                    rest.starts_with(|c: char| c.is_ascii_digit())
+                        .then(|| rest.split('-').next().unwrap_or(rest).to_string())
+                })
+        })
+        .or_else(|| {
+            // lib/python3.11
+            fs::read_dir(root.join("lib"))
+                .ok()?
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .find_map(|name| {
+                    let rest = name.strip_prefix("python")?;
+                    rest.starts_with(|c: char| c.is_ascii_digit())
+                        .then(|| rest.to_string())
+                })
+        })?;
+
+    let mut parts = full.split('.');
+    match (parts.next(), parts.next()) {
+        (Some(major), Some(minor)) => Some(format!("{}.{}", major, minor)),
+        _ => None,
+    }
+}
+
+/// Locate site-packages (`Lib/site-packages` or `lib/pythonX.Y/site-packages`)
+fn find_site_packages(root: &Path) -> Option<PathBuf> {
+    let windows = root.join("Lib").join("site-packages");
+    if windows.is_dir() {
+        return Some(windows);
+    }
+    fs::read_dir(root.join("lib"))
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().join("site-packages"))
+        .find(|p| p.is_dir())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_wrapped_conda_env() {
+        let temp = tempfile::tempdir().unwrap();
+        let env = temp.path().join("src").join("myenv");
+        fs::create_dir_all(env.join("bin")).unwrap();
+        fs::create_dir_all(env.join("conda-meta")).unwrap();
+        fs::create_dir_all(env.join("lib/python3.11/site-packages/pkg")).unwrap();
+        fs::write(env.join("bin/python3"), "#!/bin/sh\n").unwrap();
+        fs::write(env.join("conda-meta/python-3.11.9-h0_0.json"), "{}").unwrap();
+
+        let archive_path = temp.path().join("env.tar.gz");
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            fs::File::create(&archive_path).unwrap(),
+            Compression::fast(),
+        ));
+        builder.append_dir_all("myenv", &env).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let staging = temp.path().join("staging");
+        let imported = import(&archive_path, &staging).unwrap();
+        assert_eq!(imported.kind, EnvArchiveKind::CondaPack);
+        assert_eq!(imported.version.as_deref(), Some("3.11"));
+        assert!(imported
+            .site_packages
+            .unwrap()
+            .ends_with("lib/python3.11/site-packages"));
+
+        let mut repacked = tar::Archive::new(flate2::read::GzDecoder::new(&imported.archive[..]));
+        let paths: Vec<PathBuf> = repacked
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().into_owned())
+            .collect();
+        assert!(paths.iter().any(|p| p == Path::new("python/bin/python3")));
+        assert!(paths.iter().all(|p| p.starts_with("python")));
+    }
+
+    #[test]
+    fn test_import_requires_interpreter() {
+        let temp = tempfile::tempdir().unwrap();
+        let env = temp.path().join("env");
+        fs::create_dir_all(env.join("lib")).unwrap();
+        fs::write(
+            env.join("pyvenv.cfg"),
+            "home = /usr/bin\nversion = 3.12.1\n",
+        )
+        .unwrap();
+        assert_eq!(detect_version(&env).as_deref(), Some("3.12"));
+
+        let archive_path = temp.path().join("env.tar");
+        let mut builder = tar::Builder::new(fs::File::create(&archive_path).unwrap());
+        builder.append_dir_all(".", &env).unwrap();
+        builder.finish().unwrap();
+
+        let err = import(&archive_path, &temp.path().join("staging")).unwrap_err();
+        assert!(err.to_string().contains("bin/python3"));
+    }
+}
@@ -41,6 +41,20 @@ pub enum PackError {
     #[error("Invalid overlay format: {0}")]
     InvalidOverlay(String),
 
+    /// Overlay written for a newer reader, or in a format this reader
+    /// does not support
+    #[error("Incompatible overlay: {0}")]
+    IncompatibleOverlay(String),
+
+    /// Archive entry escapes the extraction directory or is not a plain
+    /// file, directory or link
+    #[error("Unsafe archive: {0}")]
+    UnsafeArchive(String),
+
+    /// Overlay signature missing, untrusted or not matching
+    #[error("Overlay signature error: {0}")]
+    Signature(String),
+
     /// Asset not found
     #[error("Asset not found: {0}")]
     AssetNotFound(PathBuf),
@@ -69,6 +83,34 @@ pub enum PackError {
     #[error("Resource edit error: {0}")]
     ResourceEdit(String),
 
+    /// Test run error (launching packed executable, CDP wait)
+    #[error("Test run error: {0}")]
+    TestRun(String),
+
+    /// Not enough disk space for the pack run
+    #[error("Insufficient disk space: {0}")]
+    DiskSpace(String),
+
+    /// Hook command exceeded its timeout or idle-output timeout
+    #[error("Hook timed out: {0}")]
+    HookTimeout(String),
+
+    /// Bundled dependency violates the license policy
+    #[error("License policy violation: {0}")]
+    LicensePolicy(String),
+
+    /// Pack metrics regressed beyond the `[build.history]` thresholds
+    #[error("Pack regression: {0}")]
+    Regression(String),
+
+    /// Exporting telemetry failed
+    #[error("Telemetry export error: {0}")]
+    Telemetry(String),
+
+    /// A packer plugin failed
+    #[error("Plugin error: {0}")]
+    Plugin(String),
+
     /// vx.ensure validation failed
     #[error("vx.ensure validation failed: {0}")]
     VxEnsureFailed(String),
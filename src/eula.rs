@@ -0,0 +1,163 @@
+//! First-run EULA / consent gate
+//!
+//! `[license.eula]` bundles an end-user license agreement into the overlay;
+//! the runtime shows it before the first launch and records acceptance in
+//! the app's data directory, so no custom frontend page is needed:
+//!
+//! ```toml
+//! [license.eula]
+//! path = "./EULA.html"          # Text, Markdown or HTML
+//! title = "License Agreement"
+//! require_acceptance = true     # Quit unless accepted
+//! revision = "2026-01"          # Ask again when this changes
+//! ```
+//!
+//! Without `revision`, the packer uses the hash of the EULA text, so every
+//! change to the text asks for acceptance again.
+
+use crate::context::unix_seconds;
+use crate::identity::AppIdentity;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Reserved overlay path prefix of the EULA text
+pub const EULA_PREFIX: &str = "__eula__/";
+
+/// File in the app's data directory recording acceptance
+pub const EULA_ACCEPTANCE_FILE: &str = "eula-accepted.json";
+
+/// EULA extensions the runtime can display
+const EULA_EXTENSIONS: &[&str] = &["txt", "md", "html", "htm"];
+
+/// EULA configuration
+///
+/// Located at `[license.eula]` in TOML. Stored in the overlay config with
+/// `path` pointing at the embedded text and `revision` filled in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EulaConfig {
+    /// EULA file (overlay path once packed)
+    pub path: PathBuf,
+
+    /// Dialog title (default: "License Agreement")
+    pub title: Option<String>,
+
+    /// Quit the app unless the EULA is accepted; `false` only shows it once
+    pub require_acceptance: bool,
+
+    /// Revision of the EULA; acceptance of another revision does not count
+    pub revision: Option<String>,
+}
+
+impl Default for EulaConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::new(),
+            title: None,
+            require_acceptance: true,
+            revision: None,
+        }
+    }
+}
+
+impl EulaConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        let extension = self
+            .path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        if !extension.is_some_and(|e| EULA_EXTENSIONS.contains(&e.as_str())) {
+            return Err(PackError::Config(format!(
+                "[license.eula] path '{}' must be a .txt, .md or .html file",
+                self.path.display()
+            )));
+        }
+        if self
+            .revision
+            .as_deref()
+            .is_some_and(|r| r.trim().is_empty())
+        {
+            return Err(PackError::Config(
+                "[license.eula] revision must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the EULA is HTML (otherwise plain text or Markdown)
+    pub fn is_html(&self) -> bool {
+        self.path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm"))
+    }
+
+    /// Read the EULA file and return the overlay asset path, its content and
+    /// the config to store in the overlay
+    pub(crate) fn embed(&self) -> PackResult<(String, Vec<u8>, EulaConfig)> {
+        let content = fs::read(&self.path).map_err(|e| {
+            PackError::Config(format!(
+                "Failed to read EULA {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        if String::from_utf8_lossy(&content).trim().is_empty() {
+            return Err(PackError::Config(format!(
+                "EULA {} is empty",
+                self.path.display()
+            )));
+        }
+        let name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "EULA.txt".to_string());
+        let asset = format!("{}{}", EULA_PREFIX, name);
+
+        let mut embedded = self.clone();
+        embedded.path = PathBuf::from(&asset);
+        if embedded.revision.is_none() {
+            let hash = format!("{:x}", Sha256::digest(&content));
+            embedded.revision = Some(hash[..16].to_string());
+        }
+        Ok((asset, content, embedded))
+    }
+}
+
+/// Recorded acceptance of an EULA
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EulaAcceptance {
+    /// Accepted revision
+    pub revision: String,
+    /// Unix timestamp (seconds) of the acceptance
+    pub accepted_at: u64,
+}
+
+/// Path of the acceptance record of an app on this machine
+pub fn eula_acceptance_path(identity: &AppIdentity) -> PathBuf {
+    crate::uninstall::user_data_dir(identity).join(EULA_ACCEPTANCE_FILE)
+}
+
+/// Whether `revision` has been accepted according to the record at `path`
+pub fn is_eula_accepted(path: &Path, revision: &str) -> bool {
+    fs::read(path)
+        .ok()
+        .and_then(|content| serde_json::from_slice::<EulaAcceptance>(&content).ok())
+        .is_some_and(|record| record.revision == revision)
+}
+
+/// Record acceptance of `revision` at `path`
+pub fn record_eula_acceptance(path: &Path, revision: &str) -> PackResult<()> {
+    let record = EulaAcceptance {
+        revision: revision.to_string(),
+        accepted_at: unix_seconds(SystemTime::now()),
+    };
+    crate::cache_lock::write_atomic(path, &serde_json::to_vec_pretty(&record)?)
+}
@@ -0,0 +1,171 @@
+//! C ABI (feature `ffi`)
+//!
+//! A minimal C-compatible API, so build systems that are not written in
+//! Rust (Python scripts, C# launchers) can pack without running the CLI.
+//! Build the shared library with
+//! `cargo rustc --release --features ffi --crate-type cdylib`.
+//!
+//! ```c
+//! typedef struct AvPackConfig AvPackConfig;
+//!
+//! /* NULL on error; *error then holds the message (free it) */
+//! AvPackConfig *avpack_config_from_toml(const char *toml, const char *base_dir,
+//!                                       char **error);
+//! void avpack_config_free(AvPackConfig *config);
+//!
+//! /* {"ok": true, "output": {...}} or {"ok": false, "error": "..."} */
+//! char *avpack_pack(const AvPackConfig *config);
+//!
+//! void avpack_string_free(char *s);
+//! const char *avpack_version(void);
+//! ```
+//!
+//! Strings are UTF-8. Strings returned by the library are owned by the
+//! caller and freed with `avpack_string_free`. A config handle may be used
+//! from any thread; packs of one handle run one at a time.
+
+use crate::{Manifest, PackConfig, PackError, PackResult, Packer};
+use serde_json::json;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::ptr;
+
+/// Version of the library, NUL-terminated
+static VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
+
+/// Pack configuration handle
+pub struct AvPackConfig {
+    packer: Packer,
+}
+
+/// Create a configuration from a manifest (`auroraview.pack.toml`) string
+///
+/// Relative paths in the manifest resolve against `base_dir` (the current
+/// directory if NULL). Returns NULL on error and, if `error` is not NULL,
+/// stores the message there.
+///
+/// # Safety
+///
+/// `toml` and `base_dir` must be NULL or NUL-terminated strings; `error`
+/// must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn avpack_config_from_toml(
+    toml: *const c_char,
+    base_dir: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut AvPackConfig {
+    if !error.is_null() {
+        *error = ptr::null_mut();
+    }
+    let result = guard(|| {
+        let toml = required_str(toml, "toml")?;
+        let base_dir = match optional_str(base_dir, "base_dir")? {
+            Some(dir) => Path::new(dir).to_path_buf(),
+            None => std::env::current_dir()?,
+        };
+        let manifest = Manifest::parse(toml)?;
+        manifest.validate()?;
+        let config = PackConfig::from_manifest(&manifest, &base_dir)?;
+        Ok(AvPackConfig {
+            packer: Packer::new(config),
+        })
+    });
+    match result {
+        Ok(config) => Box::into_raw(Box::new(config)),
+        Err(e) => {
+            if !error.is_null() {
+                *error = into_c_string(e.to_string());
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Free a configuration handle (NULL is ignored)
+///
+/// # Safety
+///
+/// `config` must be NULL or a handle from [`avpack_config_from_toml`] that
+/// was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn avpack_config_free(config: *mut AvPackConfig) {
+    if !config.is_null() {
+        drop(Box::from_raw(config));
+    }
+}
+
+/// Pack `config` and return the result as JSON
+///
+/// `{"ok": true, "output": {...}}` carries the build report
+/// ([`PackOutput`](crate::PackOutput)), `{"ok": false, "error": "..."}` the
+/// error.
+///
+/// # Safety
+///
+/// `config` must be NULL or a live handle from [`avpack_config_from_toml`].
+#[no_mangle]
+pub unsafe extern "C" fn avpack_pack(config: *const AvPackConfig) -> *mut c_char {
+    let result = match config.as_ref() {
+        Some(config) => guard(|| config.packer.pack()),
+        None => Err(PackError::Config("config is NULL".to_string())),
+    };
+    let json = match result {
+        Ok(output) => json!({ "ok": true, "output": output }),
+        Err(e) => json!({ "ok": false, "error": e.to_string() }),
+    };
+    into_c_string(json.to_string())
+}
+
+/// Free a string returned by the library (NULL is ignored)
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library that was not
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn avpack_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Version of the library (static, not to be freed)
+#[no_mangle]
+pub extern "C" fn avpack_version() -> *const c_char {
+    VERSION.as_ptr().cast()
+}
+
+/// Run `op`, turning a panic into an error so it does not unwind into C
+fn guard<T>(op: impl FnOnce() -> PackResult<T>) -> PackResult<T> {
+    panic::catch_unwind(AssertUnwindSafe(op)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(PackError::Build(format!("packer panicked: {}", message)))
+    })
+}
+
+/// `s` as UTF-8, failing if NULL
+unsafe fn required_str<'a>(s: *const c_char, name: &str) -> PackResult<&'a str> {
+    optional_str(s, name)?.ok_or_else(|| PackError::Config(format!("{} is NULL", name)))
+}
+
+/// `s` as UTF-8 (`None`: NULL)
+unsafe fn optional_str<'a>(s: *const c_char, name: &str) -> PackResult<Option<&'a str>> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map(Some)
+        .map_err(|e| PackError::Config(format!("{} is not UTF-8: {}", name, e)))
+}
+
+/// Hand `s` to the caller (interior NULs are dropped)
+fn into_c_string(s: String) -> *mut c_char {
+    let s = CString::new(s.replace('\0', "")).unwrap_or_default();
+    s.into_raw()
+}
@@ -0,0 +1,200 @@
+//! Frontend dependency install for `[frontend.dependencies]`
+//!
+//! Runs the project's package manager with a frozen lockfile before hooks,
+//! verifies the lockfile hash and caches `node_modules` as a tree in the
+//! [artifact store](crate::ArtifactStore), referenced as
+//! `node-modules/<manager>-<lockfile sha256>`.
+//!
+//! A restored or freshly installed `node_modules` carries a stamp file with
+//! the cache key, so unchanged lockfiles skip the install entirely.
+
+use crate::common::{FrontendDependencies, PackageManager};
+use crate::hooks::{run_hook, HookEnv, HookLimits};
+use crate::store::{ArtifactStore, ObjectKind};
+use crate::{PackError, PackResult};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Stamp file inside `node_modules` recording the installed cache key
+const STAMP_FILE: &str = ".auroraview-lock-hash";
+
+/// Artifact store namespace of `node_modules` snapshots
+const NODE_MODULES_NAMESPACE: &str = "node-modules";
+
+/// Get the cache directory for `node_modules` snapshots of older versions
+pub fn get_node_modules_cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("AuroraView")
+        .join("node-modules")
+}
+
+/// Compute the SHA-256 of a lockfile (lowercase hex)
+pub fn lockfile_hash(path: &Path) -> PackResult<String> {
+    let content = fs::read(path)?;
+    Ok(format!("{:x}", Sha256::digest(&content)))
+}
+
+/// Install frontend dependencies (and run the configured script)
+pub(crate) fn install(
+    deps: &FrontendDependencies,
+    limits: HookLimits,
+    env: &HookEnv,
+    store: &ArtifactStore,
+) -> PackResult<()> {
+    let dir = deps.dir.as_deref().unwrap_or(Path::new("."));
+    if !dir.join("package.json").is_file() {
+        return Err(PackError::AssetNotFound(dir.join("package.json")));
+    }
+
+    let manager = deps
+        .manager
+        .or_else(|| PackageManager::detect(dir))
+        .unwrap_or(PackageManager::Npm);
+    let lockfile = dir.join(manager.lockfile());
+
+    let hash = if lockfile.is_file() {
+        Some(lockfile_hash(&lockfile)?)
+    } else if deps.frozen_lockfile || deps.lockfile_sha256.is_some() {
+        return Err(PackError::Config(format!(
+            "[frontend.dependencies] requires {} (run `{} install` and commit the lockfile, \
+             or set frozen_lockfile = false)",
+            lockfile.display(),
+            manager.as_str()
+        )));
+    } else {
+        None
+    };
+
+    if let (Some(expected), Some(actual)) = (&deps.lockfile_sha256, &hash) {
+        if !expected.eq_ignore_ascii_case(actual) {
+            return Err(PackError::Config(format!(
+                "Lockfile {} has changed: expected sha256 {}, found {}",
+                lockfile.display(),
+                expected,
+                actual
+            )));
+        }
+    }
+
+    let key = hash.map(|h| format!("{}-{}", manager.as_str(), h));
+    let node_modules = dir.join("node_modules");
+    let stamp = node_modules.join(STAMP_FILE);
+    // pnpm links node_modules into its own content-addressed store
+    let cacheable = deps.cache && manager != PackageManager::Pnpm;
+    let cache_key = key.as_deref().filter(|_| cacheable);
+
+    let installed = key
+        .as_deref()
+        .is_some_and(|k| fs::read_to_string(&stamp).is_ok_and(|s| s.trim() == k));
+
+    if installed {
+        tracing::info!("node_modules is up to date with {}", lockfile.display());
+    } else if let Some(cached) = cache_key.and_then(|k| store.resolve(NODE_MODULES_NAMESPACE, k)) {
+        tracing::info!("Restoring node_modules from cache: {}", cached.display());
+        if node_modules.exists() {
+            fs::remove_dir_all(&node_modules)?;
+        }
+        copy_tree(&cached, &node_modules)?;
+    } else {
+        tracing::info!(
+            "Installing frontend dependencies with {} in {}",
+            manager.as_str(),
+            dir.display()
+        );
+        let mut command = package_manager_command(manager);
+        command.args(install_args(manager, dir, deps.frozen_lockfile));
+        command.current_dir(dir);
+        env.apply(&mut command);
+        run_hook(command, &format!("{} install", manager.as_str()), limits)?;
+
+        if let Some(ref key) = key {
+            fs::write(&stamp, key)?;
+        }
+        if let Some(cache_key) = cache_key {
+            store_in_cache(&node_modules, store, cache_key);
+        }
+    }
+
+    if let Some(ref script) = deps.script {
+        tracing::info!("Running {} run {}", manager.as_str(), script);
+        let mut command = package_manager_command(manager);
+        command.args(["run", script]).current_dir(dir);
+        env.apply(&mut command);
+        run_hook(
+            command,
+            &format!("{} run {}", manager.as_str(), script),
+            limits,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Command for a package manager (batch shims on Windows)
+fn package_manager_command(manager: PackageManager) -> Command {
+    if cfg!(windows) {
+        Command::new(format!("{}.cmd", manager.as_str()))
+    } else {
+        Command::new(manager.as_str())
+    }
+}
+
+/// Install arguments for a package manager
+fn install_args(manager: PackageManager, dir: &Path, frozen: bool) -> Vec<&'static str> {
+    match (manager, frozen) {
+        (PackageManager::Npm, true) => vec!["ci"],
+        (PackageManager::Npm, false) => vec!["install"],
+        // Yarn Berry projects carry a .yarnrc.yml and renamed the flag
+        (PackageManager::Yarn, true) if dir.join(".yarnrc.yml").is_file() => {
+            vec!["install", "--immutable"]
+        }
+        (PackageManager::Yarn, true) | (PackageManager::Pnpm, true) => {
+            vec!["install", "--frozen-lockfile"]
+        }
+        (_, false) => vec!["install"],
+    }
+}
+
+/// Copy `node_modules` into the artifact store (best effort)
+///
+/// The store moves the copy into place once complete, so concurrent packs
+/// never see a partial snapshot.
+fn store_in_cache(node_modules: &Path, store: &ArtifactStore, key: &str) {
+    let id = ArtifactStore::tree_id(&["node_modules", key]);
+    let result = store
+        .insert_tree(&id, |dir| copy_tree(node_modules, dir))
+        .and_then(|cached| {
+            store.link(NODE_MODULES_NAMESPACE, key, ObjectKind::Tree, &id)?;
+            Ok(cached)
+        });
+
+    match result {
+        Ok(cached) => tracing::info!("Cached node_modules: {}", cached.display()),
+        Err(e) => tracing::warn!("Failed to cache node_modules: {}", e),
+    }
+}
+
+/// Recursively copy a directory, preserving symlinks on Unix
+fn copy_tree(src: &Path, dest: &Path) -> PackResult<()> {
+    for entry in walkdir::WalkDir::new(src) {
+        let entry = entry.map_err(|e| PackError::Io(e.into()))?;
+        let rel = entry.path().strip_prefix(src).unwrap_or(entry.path());
+        let target = dest.join(rel);
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target)?;
+            #[cfg(not(unix))]
+            fs::copy(entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Content hashes of files and buffers
+//!
+//! BLAKE3 keys the overlay index and the build cache; SHA-256 is the digest
+//! published to others (integrity manifest, provenance, update feeds).
+//! [`Digester`] computes both in one pass, so assets are read once.
+
+use crate::PackResult;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+
+/// BLAKE3 and SHA-256 (hex) and size of a content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Digests {
+    pub blake3: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl Digests {
+    /// Digests of an in-memory content
+    pub fn of(content: &[u8]) -> Self {
+        let mut digester = Digester::default();
+        digester.update(content);
+        digester.finalize()
+    }
+}
+
+/// Computes [`Digests`] of the bytes written to it
+#[derive(Default)]
+pub(crate) struct Digester {
+    blake3: blake3::Hasher,
+    sha256: Sha256,
+    size: u64,
+}
+
+impl Digester {
+    pub fn update(&mut self, data: &[u8]) {
+        self.blake3.update(data);
+        self.sha256.update(data);
+        self.size += data.len() as u64;
+    }
+
+    pub fn finalize(self) -> Digests {
+        Digests {
+            blake3: self.blake3.finalize().to_hex().to_string(),
+            sha256: format!("{:x}", self.sha256.finalize()),
+            size: self.size,
+        }
+    }
+}
+
+impl Write for Digester {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// BLAKE3 and SHA-256 of a file, in one read
+pub(crate) fn digest_file(path: &Path) -> PackResult<Digests> {
+    let mut digester = Digester::default();
+    io::copy(&mut BufReader::new(File::open(path)?), &mut digester)?;
+    Ok(digester.finalize())
+}
+
+/// SHA-256 (hex) and size of a file
+pub(crate) fn sha256_file(path: &Path) -> PackResult<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut BufReader::new(File::open(path)?), &mut hasher)?;
+    Ok((format!("{:x}", hasher.finalize()), size))
+}
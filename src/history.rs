@@ -0,0 +1,410 @@
+//! Pack statistics history and regression detection
+//!
+//! With `[build.history]`, every pack run appends its metrics (output size,
+//! durations, asset counts) to a per-app history in the pack cache:
+//!
+//! ```text
+//! <cache>/AuroraView/pack-history/
+//! ├── <name>.jsonl            - One PackStats per line, oldest first
+//! └── <name>.baseline.json    - Pinned baseline (see PackHistory::set_baseline)
+//! ```
+//!
+//! Each run is compared against the previous run or the pinned baseline;
+//! size or time increases beyond the configured thresholds are reported as
+//! [`Regression`]s and can fail the pack for CI gating.
+
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Metrics of one pack run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackStats {
+    /// Pack run ID
+    pub run_id: String,
+    /// Unix timestamp (seconds) of the run
+    pub timestamp: u64,
+    /// Pack mode (e.g., "fullstack-standalone")
+    pub mode: String,
+    /// Output executable size in bytes
+    pub size: u64,
+    /// Number of embedded assets
+    pub asset_count: usize,
+    /// Number of bundled Python files
+    pub python_file_count: usize,
+    /// Total pack duration in milliseconds
+    pub duration_ms: u64,
+    /// Phase durations in milliseconds
+    #[serde(default)]
+    pub phases: BTreeMap<String, u64>,
+}
+
+/// What a run is compared against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareTo {
+    /// The previous recorded run
+    #[default]
+    Previous,
+    /// The pinned baseline (falls back to the previous run if none is set)
+    Baseline,
+}
+
+/// Pack history configuration
+///
+/// Located at `[build.history]` in TOML.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// History directory (default: `<cache>/AuroraView/pack-history`)
+    pub dir: Option<PathBuf>,
+
+    /// Number of runs kept per app
+    pub keep: usize,
+
+    /// Reference run for regression detection
+    pub compare_to: CompareTo,
+
+    /// Size increase (percent) reported as a regression
+    pub size_threshold_percent: f64,
+
+    /// Duration increase (percent) reported as a regression
+    pub duration_threshold_percent: f64,
+
+    /// Ignore duration changes of runs faster than this (noise)
+    pub min_duration_ms: u64,
+
+    /// Fail the pack on regressions (CI gating)
+    pub fail_on_regression: bool,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            keep: 100,
+            compare_to: CompareTo::default(),
+            size_threshold_percent: 5.0,
+            duration_threshold_percent: 50.0,
+            min_duration_ms: 5000,
+            fail_on_regression: false,
+        }
+    }
+}
+
+impl HistoryConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        if self.size_threshold_percent < 0.0 || self.duration_threshold_percent < 0.0 {
+            return Err(PackError::Config(
+                "[build.history] thresholds must not be negative".to_string(),
+            ));
+        }
+        if self.dir.as_deref().is_some_and(Path::is_file) {
+            return Err(PackError::Config(format!(
+                "[build.history] dir is a file: {}",
+                self.dir.as_deref().unwrap_or(Path::new("")).display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A metric that got worse beyond its threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Regression {
+    /// Metric name ("size" or "duration")
+    pub metric: String,
+    /// Reference value
+    pub previous: u64,
+    /// Current value
+    pub current: u64,
+    /// Relative change in percent
+    pub change_percent: f64,
+    /// Threshold that was exceeded, in percent
+    pub threshold_percent: f64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = if self.metric == "size" { "bytes" } else { "ms" };
+        write!(
+            f,
+            "{} grew {:.1}% ({} -> {} {}, threshold {:.1}%)",
+            self.metric,
+            self.change_percent,
+            self.previous,
+            self.current,
+            unit,
+            self.threshold_percent
+        )
+    }
+}
+
+/// Get the default pack history directory
+pub fn get_history_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("AuroraView")
+        .join("pack-history")
+}
+
+/// Compare a run against a reference run
+pub fn compare_stats(
+    previous: &PackStats,
+    current: &PackStats,
+    config: &HistoryConfig,
+) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+    let mut check = |metric: &str, before: u64, after: u64, threshold: f64| {
+        if before == 0 || after <= before {
+            return;
+        }
+        let change = (after - before) as f64 * 100.0 / before as f64;
+        if change > threshold {
+            regressions.push(Regression {
+                metric: metric.to_string(),
+                previous: before,
+                current: after,
+                change_percent: change,
+                threshold_percent: threshold,
+            });
+        }
+    };
+
+    check(
+        "size",
+        previous.size,
+        current.size,
+        config.size_threshold_percent,
+    );
+    if previous.duration_ms.max(current.duration_ms) >= config.min_duration_ms {
+        check(
+            "duration",
+            previous.duration_ms,
+            current.duration_ms,
+            config.duration_threshold_percent,
+        );
+    }
+    regressions
+}
+
+/// Recorded pack runs of one app
+#[derive(Debug, Clone)]
+pub struct PackHistory {
+    dir: PathBuf,
+    name: String,
+}
+
+impl PackHistory {
+    /// History of an app in the default history directory
+    pub fn open(name: impl Into<String>) -> Self {
+        Self::in_dir(get_history_dir(), name)
+    }
+
+    /// History of an app in a custom directory
+    pub fn in_dir(dir: impl Into<PathBuf>, name: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            name: name.into(),
+        }
+    }
+
+    /// Path of the history file
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", self.name))
+    }
+
+    fn baseline_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.baseline.json", self.name))
+    }
+
+    /// All recorded runs, oldest first (unreadable lines are skipped)
+    pub fn entries(&self) -> PackResult<Vec<PackStats>> {
+        let content = match fs::read_to_string(self.path()) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// The most recent recorded run
+    pub fn previous(&self) -> PackResult<Option<PackStats>> {
+        Ok(self.entries()?.pop())
+    }
+
+    /// The pinned baseline, if any
+    pub fn baseline(&self) -> PackResult<Option<PackStats>> {
+        match fs::read(self.baseline_path()) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Pin a run as the baseline
+    pub fn set_baseline(&self, stats: &PackStats) -> PackResult<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.baseline_path(), serde_json::to_vec_pretty(stats)?)?;
+        Ok(())
+    }
+
+    /// Append a run, keeping at most `keep` runs
+    pub fn record(&self, stats: &PackStats, keep: usize) -> PackResult<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut entries = self.entries()?;
+        entries.push(stats.clone());
+
+        if entries.len() > keep.max(1) {
+            let excess = entries.len() - keep.max(1);
+            entries.drain(..excess);
+            let mut content = Vec::new();
+            for entry in &entries {
+                serde_json::to_writer(&mut content, entry)?;
+                content.push(b'\n');
+            }
+            fs::write(self.path(), content)?;
+        } else {
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.path())?;
+            let mut line = serde_json::to_vec(stats)?;
+            line.push(b'\n');
+            file.write_all(&line)?;
+        }
+        Ok(())
+    }
+
+    /// Compare a run against the configured reference run
+    ///
+    /// Returns no regressions when there is nothing to compare against.
+    pub fn compare(
+        &self,
+        current: &PackStats,
+        config: &HistoryConfig,
+    ) -> PackResult<Vec<Regression>> {
+        let reference = match config.compare_to {
+            CompareTo::Baseline => match self.baseline()? {
+                Some(baseline) => Some(baseline),
+                None => self.previous()?,
+            },
+            CompareTo::Previous => self.previous()?,
+        };
+        Ok(reference
+            .map(|reference| compare_stats(&reference, current, config))
+            .unwrap_or_default())
+    }
+}
+
+/// Compare a finished run, enforce `fail_on_regression` and record it
+///
+/// A run failing on its regressions is not recorded, so it never becomes
+/// the previous run later packs are compared against.
+pub(crate) fn record_run(
+    name: &str,
+    stats: &PackStats,
+    config: &HistoryConfig,
+) -> PackResult<Vec<Regression>> {
+    let history = match config.dir {
+        Some(ref dir) => PackHistory::in_dir(dir, name),
+        None => PackHistory::open(name),
+    };
+
+    let regressions = history.compare(stats, config)?;
+    for regression in &regressions {
+        tracing::warn!("Pack regression: {}", regression);
+    }
+    if config.fail_on_regression && !regressions.is_empty() {
+        let details: Vec<String> = regressions.iter().map(ToString::to_string).collect();
+        return Err(PackError::Regression(details.join("; ")));
+    }
+
+    history.record(stats, config.keep)?;
+    tracing::debug!("Recorded pack stats in {}", history.path().display());
+    Ok(regressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(size: u64, duration_ms: u64) -> PackStats {
+        PackStats {
+            size,
+            duration_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compare_stats_thresholds() {
+        let config = HistoryConfig::default();
+
+        let regressions = compare_stats(&stats(1000, 10_000), &stats(1100, 12_000), &config);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].metric, "size");
+        assert!((regressions[0].change_percent - 10.0).abs() < 1e-9);
+
+        // Within thresholds, or shrinking
+        assert!(compare_stats(&stats(1000, 10_000), &stats(1040, 14_000), &config).is_empty());
+        assert!(compare_stats(&stats(1000, 10_000), &stats(500, 5_000), &config).is_empty());
+
+        // Fast runs are too noisy for duration checks
+        assert!(compare_stats(&stats(1000, 100), &stats(1000, 900), &config).is_empty());
+        let slow = compare_stats(&stats(1000, 4_000), &stats(1000, 9_000), &config);
+        assert_eq!(slow[0].metric, "duration");
+    }
+
+    #[test]
+    fn test_history_record_and_baseline() {
+        let temp = tempfile::tempdir().unwrap();
+        let history = PackHistory::in_dir(temp.path(), "app");
+        assert!(history.previous().unwrap().is_none());
+
+        for size in [100, 200, 300] {
+            history.record(&stats(size, 0), 2).unwrap();
+        }
+        let sizes: Vec<u64> = history.entries().unwrap().iter().map(|s| s.size).collect();
+        assert_eq!(sizes, vec![200, 300]);
+
+        let config = HistoryConfig {
+            compare_to: CompareTo::Baseline,
+            ..Default::default()
+        };
+        // No baseline yet: compared against the previous run
+        assert!(history.compare(&stats(310, 0), &config).unwrap().is_empty());
+
+        history.set_baseline(&stats(100, 0)).unwrap();
+        let regressions = history.compare(&stats(310, 0), &config).unwrap();
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].previous, 100);
+    }
+
+    #[test]
+    fn test_failing_run_is_not_recorded() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = HistoryConfig {
+            dir: Some(temp.path().to_path_buf()),
+            fail_on_regression: true,
+            ..Default::default()
+        };
+        record_run("app", &stats(1000, 0), &config).unwrap();
+        let err = record_run("app", &stats(2000, 0), &config).unwrap_err();
+        assert!(matches!(err, PackError::Regression(_)));
+
+        // The regressed run did not become the previous run
+        let history = PackHistory::in_dir(temp.path(), "app");
+        assert_eq!(history.previous().unwrap().unwrap().size, 1000);
+        assert!(record_run("app", &stats(2000, 0), &config).is_err());
+    }
+}
@@ -0,0 +1,423 @@
+//! Hook command execution with timeouts and heartbeat
+//!
+//! Hook output is streamed to the log line by line while the packer watches
+//! the process: a heartbeat is logged while it runs, and it is killed
+//! (including child processes) when it exceeds its total or idle-output
+//! timeout. Failures include the last lines of output.
+//!
+//! With `[hooks.isolation]`, hooks, frontend installs and backend builds run
+//! in a controlled environment (see [`HookEnv::isolated`]) instead of the
+//! packer's own, so builds cannot pick up whatever happens to be installed
+//! on the build machine.
+//!
+//! Hook commands, arguments and environment values can reference build
+//! variables (see [`HookVars`]), substituted before the hook
+//! runs:
+//!
+//! ```toml
+//! [hooks]
+//! after_pack = ["signtool sign /a \"${OUTPUT_EXE}\""]
+//! ```
+
+use crate::common::IsolationConfig;
+use crate::{PackError, PackResult};
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Default heartbeat interval in seconds
+const DEFAULT_HEARTBEAT_SECS: u64 = 30;
+
+/// Number of output lines kept for error messages
+const CAPTURED_LINES: usize = 40;
+
+/// Time limits for one hook command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct HookLimits {
+    /// Kill the hook after this long
+    pub timeout: Option<Duration>,
+    /// Kill the hook after this long without output
+    pub idle_timeout: Option<Duration>,
+    /// Log a progress line this often (`None` disables)
+    pub heartbeat: Option<Duration>,
+}
+
+impl HookLimits {
+    /// Build limits from `[hooks]` settings (seconds; heartbeat 0 disables)
+    pub fn from_secs(
+        timeout: Option<u64>,
+        idle_timeout: Option<u64>,
+        heartbeat: Option<u64>,
+    ) -> Self {
+        Self {
+            timeout: timeout.map(Duration::from_secs),
+            idle_timeout: idle_timeout.map(Duration::from_secs),
+            heartbeat: match heartbeat.unwrap_or(DEFAULT_HEARTBEAT_SECS) {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+        }
+    }
+}
+
+/// Build variables of hook commands, referenced as `${NAME}`
+///
+/// `OUTPUT_DIR`, `OUTPUT_EXE`, `PACKAGE_NAME`, `VERSION` and `TARGET`
+/// (`<arch>-<os>`, e.g. `x86_64-windows`). Other `${...}` references, such
+/// as shell variables, are left for the shell.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct HookVars {
+    pub output_dir: PathBuf,
+    pub output_exe: PathBuf,
+    pub package_name: String,
+    pub version: String,
+    pub target: String,
+}
+
+impl HookVars {
+    fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "OUTPUT_DIR" => Some(self.output_dir.display().to_string()),
+            "OUTPUT_EXE" => Some(self.output_exe.display().to_string()),
+            "PACKAGE_NAME" => Some(self.package_name.clone()),
+            "VERSION" => Some(self.version.clone()),
+            "TARGET" => Some(self.target.clone()),
+            _ => None,
+        }
+    }
+
+    /// Substitute the build variables referenced in `template`
+    pub fn expand(&self, template: &str) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after
+                .find('}')
+                .and_then(|end| Some((self.get(&after[..end])?, end)))
+            {
+                Some((value, end)) => {
+                    out.push_str(&value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push_str("${");
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Variables Windows programs need to start at all (cmd.exe, PATHEXT lookup)
+#[cfg(windows)]
+const PLATFORM_ENV: &[&str] = &["COMSPEC", "PATHEXT", "SYSTEMROOT", "WINDIR"];
+#[cfg(not(windows))]
+const PLATFORM_ENV: &[&str] = &[];
+
+/// Environment hook-like commands run with
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct HookEnv {
+    /// Start from an empty environment instead of the packer's
+    clear: bool,
+    /// Variables set for the command
+    vars: Vec<(String, OsString)>,
+}
+
+impl HookEnv {
+    /// The packer's environment with `vars` on top
+    pub fn inherit(vars: Vec<(String, OsString)>) -> Self {
+        Self { clear: false, vars }
+    }
+
+    /// A controlled environment described by `isolation`
+    ///
+    /// Only `inherit_env` (plus variables the platform needs to start
+    /// programs) is taken from the host. PATH is `tool_dirs` (pinned
+    /// toolchains from the pack cache and the tools the configuration
+    /// names), then `extra_path`, then `system_path`; with `path = false`
+    /// the host PATH replaces the last two. PYTHONPATH is `extra_pythonpath`
+    /// unless `pythonpath = false`. `vars` (e.g., RUSTUP_HOME) are set on
+    /// top and `clear_env` is removed last.
+    pub fn isolated(
+        isolation: &IsolationConfig,
+        tool_dirs: &[PathBuf],
+        vars: Vec<(String, OsString)>,
+    ) -> PackResult<Self> {
+        let mut env: Vec<(String, OsString)> = isolation
+            .inherit_env
+            .iter()
+            .map(String::as_str)
+            .chain(PLATFORM_ENV.iter().copied())
+            .filter_map(|name| std::env::var_os(name).map(|v| (name.to_string(), v)))
+            .collect();
+
+        let mut path: Vec<PathBuf> = tool_dirs.to_vec();
+        if isolation.path {
+            path.extend(isolation.extra_path.iter().map(PathBuf::from));
+            path.extend(isolation.system_path.iter().map(PathBuf::from));
+        } else if let Some(host) = std::env::var_os("PATH") {
+            path.extend(std::env::split_paths(&host));
+        }
+        env.push(("PATH".to_string(), join_paths(&path)?));
+
+        if !isolation.pythonpath {
+            if let Some(host) = std::env::var_os("PYTHONPATH") {
+                env.push(("PYTHONPATH".to_string(), host));
+            }
+        } else if !isolation.extra_pythonpath.is_empty() {
+            let paths: Vec<PathBuf> = isolation
+                .extra_pythonpath
+                .iter()
+                .map(PathBuf::from)
+                .collect();
+            env.push(("PYTHONPATH".to_string(), join_paths(&paths)?));
+        }
+
+        env.extend(vars.into_iter().filter(|(name, _)| name != "PATH"));
+        env.retain(|(name, _)| !isolation.clear_env.contains(name));
+        Ok(Self {
+            clear: true,
+            vars: env,
+        })
+    }
+
+    /// Variables set for the command
+    pub fn vars(&self) -> &[(String, OsString)] {
+        &self.vars
+    }
+
+    /// Apply the environment to a command
+    pub fn apply(&self, command: &mut Command) {
+        if self.clear {
+            command.env_clear();
+        }
+        command.envs(self.vars.iter().map(|(k, v)| (k, v)));
+    }
+}
+
+fn join_paths(paths: &[PathBuf]) -> PackResult<OsString> {
+    std::env::join_paths(paths).map_err(|e| PackError::Config(format!("Invalid PATH: {}", e)))
+}
+
+/// Locate a program in the host `PATH` (honoring `PATHEXT` on Windows)
+pub(crate) fn find_program(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    let extensions: Vec<String> = if cfg!(target_os = "windows") {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT".to_string())
+            .split(';')
+            .map(|ext| ext.to_ascii_lowercase())
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+
+    std::env::split_paths(&path).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", program, ext)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Directory of a program in the host `PATH`
+pub(crate) fn program_dir(program: &str) -> Option<PathBuf> {
+    find_program(program)?.parent().map(Path::to_path_buf)
+}
+
+/// Build a platform shell invocation for a string hook command
+pub(crate) fn shell_command(cmd: &str) -> Command {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        // cmd.exe does not understand the MSVC argument escaping Rust applies,
+        // which mangles embedded quotes. With /S, cmd strips exactly the outer
+        // quotes and runs the rest verbatim.
+        let mut command = Command::new("cmd");
+        command.raw_arg("/S /C").raw_arg(format!("\"{}\"", cmd));
+        command
+    }
+    #[cfg(not(windows))]
+    {
+        let mut command = Command::new("sh");
+        command.args(["-c", cmd]);
+        command
+    }
+}
+
+/// Run a hook command to completion, enforcing `limits`
+///
+/// `command_line` is the command line used in logs and errors.
+pub(crate) fn run_hook(
+    mut command: Command,
+    command_line: &str,
+    limits: HookLimits,
+) -> PackResult<()> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Own process group, so a timeout also kills the shell's children
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    let mut child = command.spawn().map_err(|e| {
+        PackError::Config(format!(
+            "Failed to run hook command '{}': {}",
+            command_line, e
+        ))
+    })?;
+
+    let (tx, rx) = mpsc::channel::<String>();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, tx.clone());
+    }
+    drop(tx);
+
+    let started = Instant::now();
+    let mut last_output = started;
+    let mut last_heartbeat = started;
+    let mut captured = VecDeque::with_capacity(CAPTURED_LINES);
+    let poll = Duration::from_millis(100);
+
+    let status = loop {
+        match rx.recv_timeout(poll) {
+            // Fall through: a hook printing without pause must still time out
+            Ok(line) => {
+                record_line(&mut captured, line);
+                last_output = Instant::now();
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            // Both streams closed; keep polling the process without spinning
+            Err(mpsc::RecvTimeoutError::Disconnected) => std::thread::sleep(poll),
+        }
+
+        if let Some(status) = child.try_wait()? {
+            // Drain output that arrived after the last poll
+            while let Ok(line) = rx.recv_timeout(Duration::from_millis(50)) {
+                record_line(&mut captured, line);
+            }
+            break status;
+        }
+
+        let now = Instant::now();
+        let exceeded = match (limits.timeout, limits.idle_timeout) {
+            (Some(timeout), _) if now - started >= timeout => {
+                Some(format!("exceeded timeout of {}s", timeout.as_secs()))
+            }
+            (_, Some(idle)) if now - last_output >= idle => {
+                Some(format!("produced no output for {}s", idle.as_secs()))
+            }
+            _ => None,
+        };
+        if let Some(reason) = exceeded {
+            kill_tree(&mut child);
+            return Err(PackError::HookTimeout(format!(
+                "'{}' {}{}",
+                command_line,
+                reason,
+                format_captured(&captured)
+            )));
+        }
+
+        if let Some(heartbeat) = limits.heartbeat {
+            if now - last_heartbeat >= heartbeat {
+                tracing::info!(
+                    "Hook still running ({}s elapsed, last output {}s ago): {}",
+                    (now - started).as_secs(),
+                    (now - last_output).as_secs(),
+                    command_line
+                );
+                last_heartbeat = now;
+            }
+        }
+    };
+
+    if !status.success() {
+        return Err(PackError::Config(format!(
+            "Hook command failed (exit code {:?}): {}{}",
+            status.code(),
+            command_line,
+            format_captured(&captured)
+        )));
+    }
+
+    tracing::debug!(
+        "Hook finished in {:.1}s: {}",
+        started.elapsed().as_secs_f64(),
+        command_line
+    );
+    Ok(())
+}
+
+/// Log a line of hook output and keep it for error messages
+fn record_line(captured: &mut VecDeque<String>, line: String) {
+    tracing::info!("[hook] {}", line);
+    if captured.len() == CAPTURED_LINES {
+        captured.pop_front();
+    }
+    captured.push_back(line);
+}
+
+/// Forward lines of a child output stream to the channel
+///
+/// Output is decoded lossily, so hooks printing in a non-UTF-8 locale
+/// encoding cannot break the reader.
+fn forward_lines(stream: impl Read + Send + 'static, tx: mpsc::Sender<String>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).split(b'\n') {
+            let Ok(line) = line else { break };
+            let line = String::from_utf8_lossy(&line).trim_end().to_string();
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Kill a hook process and its children
+fn kill_tree(child: &mut Child) {
+    let pid = child.id().to_string();
+
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", pid)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    #[cfg(windows)]
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Format captured output for an error message
+fn format_captured(captured: &VecDeque<String>) -> String {
+    if captured.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<&str> = captured.iter().map(String::as_str).collect();
+    format!(
+        "\n--- last {} line(s) of output ---\n{}",
+        lines.len(),
+        lines.join("\n")
+    )
+}
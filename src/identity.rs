@@ -0,0 +1,163 @@
+//! Application identity across platforms
+//!
+//! The application identifier (`[package] identifier` or
+//! `[bundle] identifier`, reverse-DNS like `com.acme.viewer`) is the one
+//! name every platform-specific identity is derived from, so two apps of the
+//! same vendor never share a cache directory, taskbar group or instance lock:
+//!
+//! | Use | Derived name |
+//! |-----|--------------|
+//! | Extraction cache | `<cache>/AuroraView/runtime/com.acme.viewer` |
+//! | Windows AppUserModelID | `com.acme.viewer` |
+//! | macOS bundle id | `com.acme.viewer` |
+//! | Linux desktop entry | `com.acme.viewer.desktop` |
+//! | Single-instance lock | `AuroraView.com.acme.viewer` |
+//!
+//! Without an identifier the output name is used, as before.
+
+use crate::{PackError, PackResult};
+use std::path::PathBuf;
+
+/// Maximum length of an identifier (the AppUserModelID limit)
+const MAX_IDENTIFIER_LEN: usize = 128;
+
+/// Names of one app on every platform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppIdentity {
+    identifier: String,
+}
+
+impl AppIdentity {
+    /// Identity of an app with an explicit identifier, or its output name
+    pub fn new(identifier: Option<&str>, output_name: &str) -> Self {
+        Self {
+            identifier: identifier.unwrap_or(output_name).to_string(),
+        }
+    }
+
+    /// The identifier (or output name) as given
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    /// Directory name of the runtime extraction cache
+    pub fn cache_key(&self) -> String {
+        crate::output_path::sanitize_output_name(&self.identifier)
+    }
+
+    /// Runtime extraction cache directory
+    pub fn runtime_cache_dir(&self) -> PathBuf {
+        crate::python_standalone::get_runtime_cache_dir(&self.cache_key())
+    }
+
+    /// Windows AppUserModelID (dot-separated, no spaces, at most 128 chars)
+    pub fn app_user_model_id(&self) -> String {
+        let id = self.dotted(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        id.chars().take(MAX_IDENTIFIER_LEN).collect()
+    }
+
+    /// macOS `CFBundleIdentifier` (alphanumerics, `-` and `.`)
+    pub fn macos_bundle_id(&self) -> String {
+        self.dotted(|c| c.is_ascii_alphanumeric() || c == '-')
+    }
+
+    /// Linux desktop entry file name (freedesktop app ID + `.desktop`)
+    ///
+    /// Elements of an app ID may not start with a digit or contain `-`.
+    pub fn linux_desktop_file(&self) -> String {
+        let id = self.dotted(|c| c.is_ascii_alphanumeric() || c == '_');
+        let elements: Vec<String> = id
+            .split('.')
+            .map(|element| {
+                if element.starts_with(|c: char| c.is_ascii_digit()) {
+                    format!("_{}", element)
+                } else {
+                    element.to_string()
+                }
+            })
+            .collect();
+        format!("{}.desktop", elements.join("."))
+    }
+
+    /// Name of the single-instance lock (Windows mutex, Unix lock file)
+    pub fn single_instance_name(&self) -> String {
+        format!("AuroraView.{}", self.app_user_model_id())
+    }
+
+    /// Identifier with characters outside `allowed` replaced by `_` and
+    /// empty sections dropped
+    fn dotted(&self, allowed: impl Fn(char) -> bool) -> String {
+        let id: Vec<String> = self
+            .identifier
+            .split('.')
+            .filter(|section| !section.trim().is_empty())
+            .map(|section| {
+                section
+                    .trim()
+                    .chars()
+                    .map(|c| if allowed(c) { c } else { '_' })
+                    .collect()
+            })
+            .collect();
+        if id.is_empty() {
+            "app".to_string()
+        } else {
+            id.join(".")
+        }
+    }
+}
+
+/// Check an application identifier
+///
+/// Identifiers are reverse-DNS: at least two dot-separated sections of
+/// ASCII letters, digits, `-` and `_`.
+pub fn validate_identifier(identifier: &str) -> PackResult<()> {
+    let sections: Vec<&str> = identifier.split('.').collect();
+    let valid_section = |section: &&str| {
+        !section.is_empty()
+            && section
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    if identifier.len() > MAX_IDENTIFIER_LEN
+        || sections.len() < 2
+        || !sections.iter().all(valid_section)
+    {
+        return Err(PackError::Config(format!(
+            "identifier '{}' must be reverse-DNS like \"com.example.app\" \
+             (letters, digits, '-' and '_', at most {} characters)",
+            identifier, MAX_IDENTIFIER_LEN
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_names() {
+        let identity = AppIdentity::new(Some("com.acme.3d-viewer"), "viewer");
+        assert_eq!(identity.cache_key(), "com.acme.3d-viewer");
+        assert_eq!(identity.app_user_model_id(), "com.acme.3d-viewer");
+        assert_eq!(identity.macos_bundle_id(), "com.acme.3d-viewer");
+        assert_eq!(identity.linux_desktop_file(), "com.acme._3d_viewer.desktop");
+        assert_eq!(
+            identity.single_instance_name(),
+            "AuroraView.com.acme.3d-viewer"
+        );
+
+        let fallback = AppIdentity::new(None, "My App");
+        assert_eq!(fallback.identifier(), "My App");
+        assert_eq!(fallback.app_user_model_id(), "My_App");
+    }
+
+    #[test]
+    fn test_validate_identifier() {
+        assert!(validate_identifier("com.example.app").is_ok());
+        assert!(validate_identifier("app").is_err());
+        assert!(validate_identifier("com..app").is_err());
+        assert!(validate_identifier("com.example.my app").is_err());
+    }
+}
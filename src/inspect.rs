@@ -0,0 +1,171 @@
+//! Inspecting and unpacking packed executables
+//!
+//! Backs [`Packer::inspect`](crate::Packer::inspect) and
+//! [`Packer::unpack`](crate::Packer::unpack): what actually got bundled,
+//! without running the app. `unpack` writes:
+//!
+//! ```text
+//! <dir>/overlay.json    - InspectReport (config, metadata, asset index)
+//! <dir>/assets/...      - Every asset, with modes and symlinks restored
+//! ```
+
+use crate::overlay::{AssetIndexEntry, OverlayArchive, OverlayReader};
+use crate::python_standalone::PythonRuntimeMeta;
+use crate::signing::OverlaySignature;
+use crate::{PackConfig, PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Overlay path of the Python runtime metadata
+const PYTHON_RUNTIME_META_PATH: &str = "python_runtime.json";
+
+/// Name of the report written by [`unpack`]
+pub const UNPACK_REPORT_FILE: &str = "overlay.json";
+
+/// Directory the assets are extracted to by [`unpack`]
+pub const UNPACK_ASSETS_DIR: &str = "assets";
+
+/// Contents of a packed executable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectReport {
+    /// Inspected executable
+    pub executable: PathBuf,
+    /// File holding the overlay (the executable, or its sidecar)
+    pub overlay_file: PathBuf,
+    /// Size of the executable without the overlay (`None` for sidecars)
+    pub original_size: Option<u64>,
+    /// Overlay format version
+    pub format_version: u32,
+    /// Content hash of all assets
+    pub content_hash: String,
+    /// Signature, if the overlay is signed
+    pub signature: Option<OverlaySignature>,
+    /// Build metadata
+    pub metadata: BTreeMap<String, String>,
+    /// Embedded Python runtime, if any
+    pub python_runtime: Option<PythonRuntimeMeta>,
+    /// Runtime configuration stored in the overlay
+    pub config: PackConfig,
+    /// Asset index
+    pub assets: Vec<AssetIndexEntry>,
+}
+
+impl InspectReport {
+    /// Total uncompressed size of all assets
+    pub fn total_size(&self) -> u64 {
+        self.assets.iter().map(|a| a.size).sum()
+    }
+
+    /// Total stored (compressed) size of all assets
+    pub fn stored_size(&self) -> u64 {
+        self.assets.iter().map(|a| a.length).sum()
+    }
+
+    /// Index entry of an asset
+    pub fn asset(&self, path: &str) -> Option<&AssetIndexEntry> {
+        self.assets.iter().find(|a| a.path == path)
+    }
+}
+
+impl fmt::Display for InspectReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Executable:  {}", self.executable.display())?;
+        if self.overlay_file != self.executable {
+            writeln!(f, "Overlay:     {}", self.overlay_file.display())?;
+        }
+        writeln!(
+            f,
+            "Format:      v{} (content hash {})",
+            self.format_version, self.content_hash
+        )?;
+        writeln!(f, "Mode:        {}", self.config.mode.name())?;
+        match self.signature {
+            Some(ref signature) => writeln!(
+                f,
+                "Signed:      {} ({})",
+                signature.algorithm, signature.public_key
+            )?,
+            None => writeln!(f, "Signed:      no")?,
+        }
+        if let Some(ref python) = self.python_runtime {
+            writeln!(f, "Python:      {} ({})", python.version, python.target)?;
+        }
+        for (key, value) in &self.metadata {
+            writeln!(f, "Metadata:    {} = {}", key, value)?;
+        }
+        writeln!(
+            f,
+            "Assets:      {} ({} bytes, {} stored)",
+            self.assets.len(),
+            self.total_size(),
+            self.stored_size()
+        )?;
+        for asset in &self.assets {
+            writeln!(
+                f,
+                "  {:>12}  {:>12}  {:<6}  {}",
+                asset.size,
+                asset.length,
+                asset.codec.as_str(),
+                asset.path
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the overlay of a packed executable
+pub(crate) fn inspect(executable: &Path) -> PackResult<InspectReport> {
+    open(executable).map(|(report, _)| report)
+}
+
+/// Extract the overlay of a packed executable into `dir`
+pub(crate) fn unpack(executable: &Path, dir: &Path) -> PackResult<InspectReport> {
+    let (report, mut archive) = open(executable)?;
+    let count = archive.extract_to(&dir.join(UNPACK_ASSETS_DIR))?;
+    fs::write(
+        dir.join(UNPACK_REPORT_FILE),
+        serde_json::to_vec_pretty(&report)?,
+    )?;
+    tracing::info!("Unpacked {} assets to {}", count, dir.display());
+    Ok(report)
+}
+
+fn open(executable: &Path) -> PackResult<(InspectReport, OverlayArchive)> {
+    let overlay_file =
+        OverlayReader::overlay_file(executable)?.ok_or_else(|| no_overlay(executable))?;
+    let mut archive = OverlayReader::open(executable)?.ok_or_else(|| no_overlay(executable))?;
+    let original_size = if overlay_file == executable {
+        OverlayReader::get_original_size(executable)?
+    } else {
+        None
+    };
+    let python_runtime = match archive.read_asset(PYTHON_RUNTIME_META_PATH)? {
+        Some(json) => Some(serde_json::from_slice(&json)?),
+        None => None,
+    };
+
+    let report = InspectReport {
+        executable: executable.to_path_buf(),
+        overlay_file,
+        original_size,
+        format_version: archive.version,
+        content_hash: archive.content_hash.clone(),
+        signature: archive.signature.clone(),
+        metadata: archive.metadata.clone(),
+        python_runtime,
+        config: archive.config.clone(),
+        assets: archive.entries().to_vec(),
+    };
+    Ok((report, archive))
+}
+
+fn no_overlay(executable: &Path) -> PackError {
+    PackError::InvalidOverlay(format!(
+        "{} is not a packed executable (no overlay found)",
+        executable.display()
+    ))
+}
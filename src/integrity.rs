@@ -0,0 +1,137 @@
+//! Per-file integrity manifest
+//!
+//! Every pack embeds [`INTEGRITY_MANIFEST_PATH`]: the SHA-256 and size of
+//! each asset. The runtime checks the files it extracted to the cache
+//! directory against it at startup and re-extracts the damaged ones (disk
+//! errors, antivirus quarantine, users editing the cache) instead of failing
+//! with an obscure import error:
+//!
+//! ```json
+//! { "files": [ { "path": "python/app/main.py", "sha256": "9f2c...", "size": 1042, "blake3": "41d7..." } ] }
+//! ```
+//!
+//! The overlay writer computes each SHA-256 in the same read as the BLAKE3
+//! hash of its index, which entries also carry
+//! ([`OverlayStreamWriter::integrity_manifest`](crate::OverlayStreamWriter::integrity_manifest)).
+//! Onedir packs hash their asset files while copying them next to the
+//! executable.
+
+use crate::hashing::{digest_file, sha256_file, Digests};
+use crate::PackResult;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Overlay path of the integrity manifest
+pub const INTEGRITY_MANIFEST_PATH: &str = "integrity.json";
+
+/// Hash and size of one asset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityEntry {
+    /// Asset path (relative to the extraction directory)
+    pub path: String,
+    /// SHA-256 of the content (hex)
+    pub sha256: String,
+    /// Size in bytes
+    pub size: u64,
+    /// BLAKE3 hash of the content (hex), as in the overlay index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blake3: Option<String>,
+}
+
+impl IntegrityEntry {
+    pub(crate) fn new(path: impl Into<String>, digests: Digests) -> Self {
+        Self {
+            path: path.into(),
+            sha256: digests.sha256,
+            size: digests.size,
+            blake3: Some(digests.blake3),
+        }
+    }
+}
+
+/// Hashes of all assets of an overlay
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityManifest {
+    /// Entries in asset order
+    pub files: Vec<IntegrityEntry>,
+}
+
+/// Files of an extraction directory that do not match the manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Files that are not there
+    pub missing: Vec<String>,
+    /// Files whose size or hash differs
+    pub corrupted: Vec<String>,
+}
+
+impl IntegrityReport {
+    /// Whether every file matched
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty()
+    }
+
+    /// Paths to extract again
+    pub fn damaged(&self) -> impl Iterator<Item = &str> {
+        self.missing
+            .iter()
+            .chain(&self.corrupted)
+            .map(String::as_str)
+    }
+}
+
+impl IntegrityManifest {
+    /// Add an in-memory asset
+    pub fn add(&mut self, path: impl Into<String>, content: &[u8]) {
+        self.files
+            .push(IntegrityEntry::new(path, Digests::of(content)));
+    }
+
+    /// Add an asset stored in a file
+    pub fn add_file(&mut self, path: impl Into<String>, file: &Path) -> PackResult<()> {
+        self.files
+            .push(IntegrityEntry::new(path, digest_file(file)?));
+        Ok(())
+    }
+
+    /// Manifest of in-memory assets
+    pub fn from_assets(assets: &[(String, Vec<u8>)]) -> Self {
+        let mut manifest = Self::default();
+        for (path, content) in assets {
+            manifest.add(path.clone(), content);
+        }
+        manifest
+    }
+
+    /// Entry of an asset
+    pub fn entry(&self, path: &str) -> Option<&IntegrityEntry> {
+        self.files.iter().find(|e| e.path == path)
+    }
+
+    /// Check the files extracted to `dir`
+    ///
+    /// Sizes are compared first, so only files of the right size are hashed.
+    pub fn verify_dir(&self, dir: &Path) -> IntegrityReport {
+        let mut report = IntegrityReport::default();
+        for entry in &self.files {
+            let path = dir.join(&entry.path);
+            match std::fs::metadata(&path) {
+                Err(_) => report.missing.push(entry.path.clone()),
+                Ok(meta) if meta.len() != entry.size => report.corrupted.push(entry.path.clone()),
+                Ok(_) => match sha256_file(&path) {
+                    Ok((sha256, _)) if sha256 == entry.sha256 => {}
+                    _ => report.corrupted.push(entry.path.clone()),
+                },
+            }
+        }
+        if !report.is_ok() {
+            tracing::warn!(
+                "Integrity check of {}: {} missing, {} corrupted",
+                dir.display(),
+                report.missing.len(),
+                report.corrupted.len()
+            );
+        }
+        report
+    }
+}
@@ -0,0 +1,198 @@
+//! Environment contract of packed Python backends
+//!
+//! `[python.isolation]` describes the environment the launcher gives the
+//! Python backend, for every bundle strategy:
+//!
+//! - `PYTHONPATH`: the bundled code directories, then `extra_pythonpath`;
+//!   the host value is dropped unless `pythonpath = false`
+//! - `PATH`: the bundled runtime (portable), `extra_path`, then
+//!   `system_path`; the host value is dropped unless `path = false`
+//! - Host variables listed in `inherit_env` are kept, `clear_env` is removed
+//! - Relative entries resolve against the app directory
+//!
+//! Single-file strategies carry the contract in the overlay configuration.
+//! Directory layouts (portable, system) also get it as
+//! [`ISOLATION_ENV_FILE`] next to the launcher, plus `env.sh` / `env.cmd`
+//! wrappers that reproduce it for running the backend by hand:
+//!
+//! ```text
+//! <app>/
+//! ├── isolation.json   - IsolationEnv
+//! ├── env.sh           - `. ./env.sh && python backend/main.py`
+//! └── env.cmd          - `call env.cmd && python backend\main.py`
+//! ```
+
+use crate::common::{BundleStrategy, IsolationConfig};
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// File name of the serialized contract in directory layouts
+pub const ISOLATION_ENV_FILE: &str = "isolation.json";
+
+/// Resolved environment contract of a packed backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IsolationEnv {
+    /// Bundle strategy the layout belongs to
+    pub strategy: BundleStrategy,
+    /// Replace the host PATH
+    pub isolate_path: bool,
+    /// Replace the host PYTHONPATH
+    pub isolate_pythonpath: bool,
+    /// PATH entries in order (relative to the app directory unless absolute)
+    pub path: Vec<String>,
+    /// PYTHONPATH entries in order (relative to the app directory unless absolute)
+    pub pythonpath: Vec<String>,
+    /// Host variables passed through
+    pub inherit_env: Vec<String>,
+    /// Variables removed
+    pub clear_env: Vec<String>,
+}
+
+impl IsolationEnv {
+    /// Resolve the contract of a strategy's layout
+    pub fn new(strategy: BundleStrategy, isolation: &IsolationConfig) -> Self {
+        let code_dirs: &[&str] = match strategy {
+            BundleStrategy::Portable => &["backend", "lib"],
+            BundleStrategy::System => &["backend"],
+            // Single-file strategies extract their code at runtime
+            _ => &[],
+        };
+        // The portable runtime's interpreter and scripts come first
+        let mut path: Vec<String> = match strategy {
+            BundleStrategy::Portable if cfg!(windows) => vec!["python".to_string()],
+            BundleStrategy::Portable => vec!["python/bin".to_string()],
+            _ => Vec::new(),
+        };
+        path.extend(isolation.extra_path.iter().cloned());
+        if isolation.path {
+            path.extend(isolation.system_path.iter().cloned());
+        }
+
+        Self {
+            strategy,
+            isolate_path: isolation.path,
+            isolate_pythonpath: isolation.pythonpath,
+            path,
+            pythonpath: code_dirs
+                .iter()
+                .map(|d| d.to_string())
+                .chain(isolation.extra_pythonpath.iter().cloned())
+                .collect(),
+            inherit_env: isolation.inherit_env.clone(),
+            clear_env: isolation.clear_env.clone(),
+        }
+    }
+
+    /// Write the contract and the shell wrappers into an app directory
+    pub fn write(&self, app_dir: &Path) -> PackResult<()> {
+        fs::write(
+            app_dir.join(ISOLATION_ENV_FILE),
+            serde_json::to_vec_pretty(self)?,
+        )?;
+        fs::write(app_dir.join("env.sh"), self.shell_script())?;
+        fs::write(app_dir.join("env.cmd"), self.cmd_script()?)?;
+        tracing::debug!("Wrote isolation contract to {}", app_dir.display());
+        Ok(())
+    }
+
+    fn shell_script(&self) -> String {
+        let entry = |e: &String| {
+            if Path::new(e).is_absolute() {
+                sh_escape(e)
+            } else {
+                format!("$APP_DIR/{}", sh_escape(e))
+            }
+        };
+        let join = |entries: &[String], host: &str, isolate: bool| {
+            let mut parts: Vec<String> = entries.iter().map(entry).collect();
+            if !isolate {
+                parts.push(format!("${}", host));
+            }
+            parts.join(":")
+        };
+
+        let mut script = String::from(
+            "# Generated by auroraview-pack: environment the launcher gives the backend\n\
+             APP_DIR=\"$(cd \"$(dirname \"$0\")\" && pwd)\"\n",
+        );
+        script.push_str(&format!(
+            "export PATH=\"{}\"\n",
+            join(&self.path, "PATH", self.isolate_path)
+        ));
+        script.push_str(&format!(
+            "export PYTHONPATH=\"{}\"\n",
+            join(&self.pythonpath, "PYTHONPATH", self.isolate_pythonpath)
+        ));
+        for name in &self.clear_env {
+            script.push_str(&format!("unset \"{}\"\n", sh_escape(name)));
+        }
+        script
+    }
+
+    fn cmd_script(&self) -> PackResult<String> {
+        let values = self
+            .path
+            .iter()
+            .chain(&self.pythonpath)
+            .chain(&self.clear_env);
+        if let Some(value) = values.clone().find(|v| v.contains('"')) {
+            return Err(PackError::Config(format!(
+                "Isolation entry '{}' contains '\"', which env.cmd cannot quote",
+                value
+            )));
+        }
+
+        let entry = |e: &String| {
+            if Path::new(e).is_absolute() || e.get(1..2) == Some(":") {
+                cmd_escape(e)
+            } else {
+                format!("%APP_DIR%\\{}", cmd_escape(&e.replace('/', "\\")))
+            }
+        };
+        let join = |entries: &[String], host: &str, isolate: bool| {
+            let mut parts: Vec<String> = entries.iter().map(entry).collect();
+            if !isolate {
+                parts.push(format!("%{}%", host));
+            }
+            parts.join(";")
+        };
+
+        let mut script = String::from(
+            "@rem Generated by auroraview-pack: environment the launcher gives the backend\r\n\
+             @set \"APP_DIR=%~dp0\"\r\n\
+             @set \"APP_DIR=%APP_DIR:~0,-1%\"\r\n",
+        );
+        script.push_str(&format!(
+            "@set \"PATH={}\"\r\n",
+            join(&self.path, "PATH", self.isolate_path)
+        ));
+        script.push_str(&format!(
+            "@set \"PYTHONPATH={}\"\r\n",
+            join(&self.pythonpath, "PYTHONPATH", self.isolate_pythonpath)
+        ));
+        for name in &self.clear_env {
+            script.push_str(&format!("@set \"{}=\"\r\n", cmd_escape(name)));
+        }
+        Ok(script)
+    }
+}
+
+/// Escape a value for a double-quoted POSIX shell string
+fn sh_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '$' | '`' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape a value for a quoted `set` in a batch file (`"` is rejected
+/// beforehand: it cannot be escaped inside the quotes)
+fn cmd_escape(value: &str) -> String {
+    value.replace('%', "%%")
+}
@@ -0,0 +1,639 @@
+//! Key management for signing
+//!
+//! One key, configured once in `[signing]`, signs the overlay, the update
+//! feed and license tokens. Keys can be generated and kept in a keystore
+//! instead of loose PKCS#8 files:
+//!
+//! ```text
+//! <config>/AuroraView/keys/
+//! └── <key_id>.json   - StoredKey: algorithm, public key and the private
+//!                       key encrypted with a passphrase (or a pointer to
+//!                       the OS keychain entry holding it)
+//! ```
+//!
+//! ```toml
+//! [signing]
+//! key_id = "release"                        # Key in the keystore
+//! # keystore = "./keys"                     # Default: <config>/AuroraView/keys
+//! # passphrase_env = "AURORAVIEW_KEY_PASSPHRASE"
+//! ```
+//!
+//! Passphrase-protected keys are encrypted with ChaCha20-Poly1305 under a
+//! PBKDF2-HMAC-SHA256 derived key. Keychain-protected keys live in the macOS
+//! Keychain (`security`), the Secret Service (`secret-tool`) on Linux or the
+//! Windows Credential Manager.
+//!
+//! Keys are generated in-process: Ed25519, or RSA (3072-bit, for license
+//! servers that require them).
+
+use crate::cache_lock::write_atomic;
+use crate::context::unix_seconds;
+use crate::{PackError, PackResult};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{
+    Ed25519KeyPair, KeyPair as _, RsaKeyPair, UnparsedPublicKey, ED25519,
+    RSA_PKCS1_2048_8192_SHA256, RSA_PKCS1_SHA256,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Environment variable holding the keystore passphrase
+pub const KEY_PASSPHRASE_ENV: &str = "AURORAVIEW_KEY_PASSPHRASE";
+
+/// Keychain service name of keychain-protected keys
+const KEYCHAIN_SERVICE: &str = "auroraview-pack";
+
+/// PBKDF2 iterations for newly encrypted keys
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+/// RSA key size generated by [`KeyPair::generate`]
+const RSA_BITS: usize = 3072;
+
+/// Signature algorithm of a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAlgorithm {
+    /// Ed25519
+    Ed25519,
+    /// RSA with PKCS#1 v1.5 SHA-256 signatures
+    Rsa,
+}
+
+impl KeyAlgorithm {
+    /// Name recorded in signatures ("ed25519", "rsa-pkcs1-sha256")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ed25519 => "ed25519",
+            Self::Rsa => "rsa-pkcs1-sha256",
+        }
+    }
+
+    /// Algorithm of a signature name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ed25519" => Some(Self::Ed25519),
+            "rsa-pkcs1-sha256" | "rsa" => Some(Self::Rsa),
+            _ => None,
+        }
+    }
+
+    /// Algorithm of a public key (Ed25519 keys are 32 raw bytes, RSA keys
+    /// DER `RSAPublicKey`s)
+    pub fn of_public_key(public_key: &[u8]) -> Self {
+        if public_key.len() == 32 {
+            Self::Ed25519
+        } else {
+            Self::Rsa
+        }
+    }
+}
+
+enum Inner {
+    Ed25519(Ed25519KeyPair),
+    Rsa(RsaKeyPair),
+}
+
+/// Private key that can sign overlays, feeds and license tokens
+pub struct KeyPair {
+    pkcs8: Vec<u8>,
+    inner: Inner,
+}
+
+impl std::fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("algorithm", &self.algorithm())
+            .field("public_key", &self.public_key_base64())
+            .finish()
+    }
+}
+
+impl KeyPair {
+    /// Generate a new key
+    pub fn generate(algorithm: KeyAlgorithm) -> PackResult<Self> {
+        let der = match algorithm {
+            KeyAlgorithm::Ed25519 => {
+                let document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+                    .map_err(|_| PackError::Config("Failed to generate an Ed25519 key".into()))?;
+                document.as_ref().to_vec()
+            }
+            KeyAlgorithm::Rsa => generate_rsa_pkcs8()?,
+        };
+        Self::from_pkcs8(&der)
+    }
+
+    /// Load a PKCS#8 key (DER or PEM); the algorithm is detected
+    pub fn from_pkcs8(key: &[u8]) -> PackResult<Self> {
+        let der = match std::str::from_utf8(key) {
+            Ok(text) if text.contains("-----BEGIN") => decode_pem(text)?,
+            _ => key.to_vec(),
+        };
+        let inner = if let Ok(key_pair) = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&der) {
+            Inner::Ed25519(key_pair)
+        } else {
+            let key_pair = RsaKeyPair::from_pkcs8(&der).map_err(|e| {
+                PackError::Config(format!(
+                    "[signing] key is not a PKCS#8 Ed25519 or RSA key: {}",
+                    e
+                ))
+            })?;
+            Inner::Rsa(key_pair)
+        };
+        Ok(Self { pkcs8: der, inner })
+    }
+
+    /// Algorithm of the key
+    pub fn algorithm(&self) -> KeyAlgorithm {
+        match self.inner {
+            Inner::Ed25519(_) => KeyAlgorithm::Ed25519,
+            Inner::Rsa(_) => KeyAlgorithm::Rsa,
+        }
+    }
+
+    /// PKCS#8 DER of the private key
+    pub fn pkcs8_der(&self) -> &[u8] {
+        &self.pkcs8
+    }
+
+    /// Public key (base64; raw for Ed25519, DER `RSAPublicKey` for RSA)
+    pub fn public_key_base64(&self) -> String {
+        match self.inner {
+            Inner::Ed25519(ref key_pair) => STANDARD.encode(key_pair.public_key().as_ref()),
+            Inner::Rsa(ref key_pair) => STANDARD.encode(key_pair.public_key().as_ref()),
+        }
+    }
+
+    /// Sign a message
+    pub fn sign(&self, message: &[u8]) -> PackResult<Vec<u8>> {
+        match self.inner {
+            Inner::Ed25519(ref key_pair) => Ok(key_pair.sign(message).as_ref().to_vec()),
+            Inner::Rsa(ref key_pair) => {
+                let mut signature = vec![0; key_pair.public().modulus_len()];
+                key_pair
+                    .sign(
+                        &RSA_PKCS1_SHA256,
+                        &SystemRandom::new(),
+                        message,
+                        &mut signature,
+                    )
+                    .map_err(|_| PackError::Signature("RSA signing failed".to_string()))?;
+                Ok(signature)
+            }
+        }
+    }
+}
+
+/// Check a signature made by [`KeyPair::sign`]
+pub fn verify_signature(
+    algorithm: KeyAlgorithm,
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> PackResult<()> {
+    let result = match algorithm {
+        KeyAlgorithm::Ed25519 => {
+            UnparsedPublicKey::new(&ED25519, public_key).verify(message, signature)
+        }
+        KeyAlgorithm::Rsa => UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, public_key)
+            .verify(message, signature),
+    };
+    result.map_err(|_| PackError::Signature("signature does not match".to_string()))
+}
+
+/// How a stored private key is protected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyProtection {
+    /// Encrypted in the key file with a passphrase
+    Passphrase(String),
+    /// Kept in the OS keychain
+    Keychain,
+}
+
+/// Private key encrypted with a passphrase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedKey {
+    /// Key derivation ("pbkdf2-sha256")
+    pub kdf: String,
+    /// PBKDF2 iterations
+    pub iterations: u32,
+    /// Salt (base64)
+    pub salt: String,
+    /// Cipher ("chacha20-poly1305")
+    pub cipher: String,
+    /// Nonce (base64)
+    pub nonce: String,
+    /// Encrypted PKCS#8 DER with its tag (base64)
+    pub ciphertext: String,
+}
+
+impl EncryptedKey {
+    /// Encrypt a PKCS#8 key with a passphrase
+    pub fn seal(pkcs8: &[u8], passphrase: &str) -> PackResult<Self> {
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt)
+            .and_then(|_| rng.fill(&mut nonce))
+            .map_err(|_| PackError::Config("Failed to gather randomness".to_string()))?;
+
+        let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+        let mut data = pkcs8.to_vec();
+        key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+            .map_err(|_| PackError::Config("Failed to encrypt the key".to_string()))?;
+        Ok(Self {
+            kdf: "pbkdf2-sha256".to_string(),
+            iterations: PBKDF2_ITERATIONS,
+            salt: STANDARD.encode(salt),
+            cipher: "chacha20-poly1305".to_string(),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(data),
+        })
+    }
+
+    /// Decrypt the PKCS#8 key
+    pub fn open(&self, passphrase: &str) -> PackResult<Vec<u8>> {
+        if self.kdf != "pbkdf2-sha256" || self.cipher != "chacha20-poly1305" {
+            return Err(PackError::Config(format!(
+                "Unsupported key encryption {}/{}",
+                self.kdf, self.cipher
+            )));
+        }
+        let decode = |value: &str| {
+            STANDARD
+                .decode(value)
+                .map_err(|e| PackError::Config(format!("Corrupted key file: {}", e)))
+        };
+        let nonce = Nonce::try_assume_unique_for_key(&decode(&self.nonce)?)
+            .map_err(|_| PackError::Config("Corrupted key file: bad nonce".to_string()))?;
+        let key = derive_key(passphrase, &decode(&self.salt)?, self.iterations)?;
+        let mut data = decode(&self.ciphertext)?;
+        let plain = key
+            .open_in_place(nonce, Aad::empty(), &mut data)
+            .map_err(|_| PackError::Config("Wrong passphrase for the key".to_string()))?;
+        Ok(plain.to_vec())
+    }
+}
+
+/// Key file in a [`KeyStore`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredKey {
+    /// Key id
+    pub id: String,
+    /// Algorithm
+    pub algorithm: KeyAlgorithm,
+    /// Public key (base64)
+    pub public_key: String,
+    /// Creation time (RFC 3339)
+    pub created_at: String,
+    /// Passphrase-encrypted private key; `None` if it is in the OS keychain
+    pub encrypted: Option<EncryptedKey>,
+}
+
+impl StoredKey {
+    /// Whether the private key is in the OS keychain
+    pub fn in_keychain(&self) -> bool {
+        self.encrypted.is_none()
+    }
+}
+
+/// Directory of stored keys
+#[derive(Debug, Clone)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    /// Default keystore directory (`<config>/AuroraView/keys`)
+    pub fn default_dir() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("AuroraView")
+            .join("keys")
+    }
+
+    /// Open the keystore at `dir` (created on first write)
+    pub fn open(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Keystore directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Generate and store a new key
+    pub fn generate(
+        &self,
+        id: &str,
+        algorithm: KeyAlgorithm,
+        protection: &KeyProtection,
+    ) -> PackResult<StoredKey> {
+        self.import(id, &KeyPair::generate(algorithm)?, protection)
+    }
+
+    /// Store an existing key
+    ///
+    /// Refuses to overwrite a key with the same id.
+    pub fn import(
+        &self,
+        id: &str,
+        key_pair: &KeyPair,
+        protection: &KeyProtection,
+    ) -> PackResult<StoredKey> {
+        validate_key_id(id)?;
+        let path = self.key_path(id);
+        if path.exists() {
+            return Err(PackError::Config(format!(
+                "Key '{}' already exists in {}",
+                id,
+                self.dir.display()
+            )));
+        }
+
+        let encrypted = match protection {
+            KeyProtection::Passphrase(passphrase) => {
+                if passphrase.is_empty() {
+                    return Err(PackError::Config("Key passphrase is empty".to_string()));
+                }
+                Some(EncryptedKey::seal(key_pair.pkcs8_der(), passphrase)?)
+            }
+            KeyProtection::Keychain => {
+                keychain_store(id, &STANDARD.encode(key_pair.pkcs8_der()))?;
+                None
+            }
+        };
+        let now = unix_seconds(SystemTime::now());
+        let stored = StoredKey {
+            id: id.to_string(),
+            algorithm: key_pair.algorithm(),
+            public_key: key_pair.public_key_base64(),
+            created_at: crate::update_feed::rfc3339(now),
+            encrypted,
+        };
+        fs::create_dir_all(&self.dir)?;
+        write_atomic(&path, &serde_json::to_vec_pretty(&stored)?)?;
+        restrict_permissions(&path)?;
+        tracing::info!(
+            "Stored {} key '{}' in {}",
+            stored.algorithm.as_str(),
+            id,
+            self.dir.display()
+        );
+        Ok(stored)
+    }
+
+    /// Key file of a key, without decrypting it
+    pub fn info(&self, id: &str) -> PackResult<StoredKey> {
+        validate_key_id(id)?;
+        let path = self.key_path(id);
+        if !path.is_file() {
+            return Err(PackError::Config(format!(
+                "Key '{}' not found in {}",
+                id,
+                self.dir.display()
+            )));
+        }
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Load a private key
+    ///
+    /// `passphrase` is required for passphrase-protected keys and ignored
+    /// for keychain-protected ones.
+    pub fn load(&self, id: &str, passphrase: Option<&str>) -> PackResult<KeyPair> {
+        let stored = self.info(id)?;
+        let der = match stored.encrypted {
+            Some(ref encrypted) => {
+                let passphrase = passphrase
+                    .ok_or_else(|| PackError::Config(format!("Key '{}' needs a passphrase", id)))?;
+                encrypted.open(passphrase)?
+            }
+            None => STANDARD
+                .decode(keychain_load(id)?.trim())
+                .map_err(|e| PackError::Config(format!("Corrupted keychain entry: {}", e)))?,
+        };
+        let key_pair = KeyPair::from_pkcs8(&der)?;
+        if key_pair.public_key_base64() != stored.public_key {
+            return Err(PackError::Config(format!(
+                "Key '{}' does not match its recorded public key",
+                id
+            )));
+        }
+        Ok(key_pair)
+    }
+
+    /// All stored keys, by id
+    pub fn list(&self) -> PackResult<Vec<StoredKey>> {
+        let mut keys = Vec::new();
+        if !self.dir.is_dir() {
+            return Ok(keys);
+        }
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                match serde_json::from_slice::<StoredKey>(&fs::read(&path)?) {
+                    Ok(key) => keys.push(key),
+                    Err(e) => tracing::warn!("Skipping {}: {}", path.display(), e),
+                }
+            }
+        }
+        keys.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(keys)
+    }
+
+    fn key_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
+
+fn validate_key_id(id: &str) -> PackResult<()> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !id.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(PackError::Config(format!(
+            "Key id '{}' must be letters, digits, '-', '_' or '.'",
+            id
+        )))
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> PackResult<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| PackError::Config("Corrupted key file: zero iterations".to_string()))?;
+    let mut key = [0u8; 32];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&CHACHA20_POLY1305, &key)
+        .map_err(|_| PackError::Config("Failed to derive the key".to_string()))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn generate_rsa_pkcs8() -> PackResult<Vec<u8>> {
+    use rsa::pkcs8::EncodePrivateKey;
+
+    let error = |e: &dyn std::fmt::Display| {
+        PackError::Config(format!("Failed to generate an RSA key: {}", e))
+    };
+    let key = rsa::RsaPrivateKey::new(&mut rand::rngs::OsRng, RSA_BITS).map_err(|e| error(&e))?;
+    let der = key.to_pkcs8_der().map_err(|e| error(&e))?;
+    Ok(der.as_bytes().to_vec())
+}
+
+/// DER contents of a PEM document
+pub(crate) fn decode_pem(text: &str) -> PackResult<Vec<u8>> {
+    let body: String = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+        .collect();
+    STANDARD
+        .decode(body)
+        .map_err(|e| PackError::Config(format!("[signing] key is not valid PEM: {}", e)))
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> PackResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> PackResult<()> {
+    Ok(())
+}
+
+/// Run a keychain tool, feeding `input` on stdin so secrets stay out of
+/// the process list
+#[cfg(unix)]
+fn run_keychain_tool(program: &str, args: &[&str], input: &str) -> PackResult<String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| PackError::Config(format!("{} (OS keychain): {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(PackError::Config(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "macos")]
+fn keychain_store(id: &str, secret: &str) -> PackResult<()> {
+    let command = format!(
+        "add-generic-password -U -s {} -a {} -w {}\n",
+        KEYCHAIN_SERVICE, id, secret
+    );
+    run_keychain_tool("security", &["-i"], &command).map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn keychain_load(id: &str) -> PackResult<String> {
+    run_keychain_tool(
+        "security",
+        &[
+            "find-generic-password",
+            "-s",
+            KEYCHAIN_SERVICE,
+            "-a",
+            id,
+            "-w",
+        ],
+        "",
+    )
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn keychain_store(id: &str, secret: &str) -> PackResult<()> {
+    let label = format!("AuroraView signing key {}", id);
+    run_keychain_tool(
+        "secret-tool",
+        &[
+            "store",
+            "--label",
+            &label,
+            "service",
+            KEYCHAIN_SERVICE,
+            "key",
+            id,
+        ],
+        secret,
+    )
+    .map(|_| ())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn keychain_load(id: &str) -> PackResult<String> {
+    run_keychain_tool(
+        "secret-tool",
+        &["lookup", "service", KEYCHAIN_SERVICE, "key", id],
+        "",
+    )
+}
+
+/// Windows Credential Manager entry of a key
+#[cfg(windows)]
+fn credential(id: &str) -> PackResult<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, id).map_err(credential_error)
+}
+
+#[cfg(windows)]
+fn credential_error(e: keyring::Error) -> PackError {
+    PackError::Config(format!("Windows Credential Manager (OS keychain): {}", e))
+}
+
+#[cfg(windows)]
+fn keychain_store(id: &str, secret: &str) -> PackResult<()> {
+    credential(id)?
+        .set_password(secret)
+        .map_err(credential_error)
+}
+
+#[cfg(windows)]
+fn keychain_load(id: &str) -> PackResult<String> {
+    credential(id)?.get_password().map_err(credential_error)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn keychain_store(_id: &str, _secret: &str) -> PackResult<()> {
+    Err(PackError::Config(format!(
+        "OS keychain storage ({}) is not supported on this platform; use a passphrase",
+        KEYCHAIN_SERVICE
+    )))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn keychain_load(id: &str) -> PackResult<String> {
+    Err(PackError::Config(format!(
+        "Key '{}' is in an OS keychain, which is not supported on this platform",
+        id
+    )))
+}
@@ -20,6 +20,7 @@
 //! - **URL Mode**: Wrap any website into a desktop app
 //! - **Frontend Mode**: Bundle local HTML/CSS/JS into a standalone app
 //! - **FullStack Mode**: Bundle frontend + backend (Python/Go/Rust/Node.js)
+//! - **Process Mode**: Bundle frontend + a prebuilt backend binary
 //! - **Manifest Support**: Declarative configuration via `auroraview.pack.toml`
 //! - **Zero Dependencies**: No build tools required on user's machine
 //!
@@ -51,7 +52,7 @@
 //! # url = "https://example.com"
 //!
 //! [backend]
-//! type = "python"  # or "go", "rust", "node", "none"
+//! type = "python"  # or "go", "rust", "node", "process", "none"
 //!
 //! [backend.python]
 //! version = "3.11"
@@ -83,45 +84,194 @@
 //!   - Overlay Offset: u64 (8 bytes)
 //!   - Magic: "AVPK" (4 bytes)
 //! ```
+//!
+//! ## Compatibility
+//!
+//! The config JSON records the release that wrote it ([`PACKER_VERSION`])
+//! and the oldest release able to read it ([`MIN_READER_VERSION`]).
+//! [`OverlayReader::version`] reads both without decoding the config, and
+//! [`OverlayVersion::check`] turns a mismatch into
+//! [`PackError::IncompatibleOverlay`] ("built with auroraview-pack X, needs
+//! a shell built with auroraview-pack >= Y") instead of a deserialization
+//! error. Readable configs from other releases are reported by
+//! [`SchemaReport`]: unknown fields are kept in
+//! [`PackConfig::unknown_fields`], missing ones take this release's defaults.
+//!
+//! ## Logging
+//!
+//! The crate logs through `tracing` only and never writes to stdout or
+//! stderr itself: the output of pip, uv, rustup, PyOxidizer and hooks is
+//! captured and logged. Every event's target is the module emitting it, so
+//! `auroraview_pack` ([`LOG_TARGET`]) covers the crate and narrower targets
+//! select a stage: `auroraview_pack::packer` (stages, pip installs),
+//! `::downloader` (Python distributions), `::deps_collector`,
+//! `::overlay`, `::hooks` (hook output), `::signing` and so on.
+//!
+//! Applications install the subscriber, e.g. with an `EnvFilter` built from
+//! [`PackOptions::filter_directive`]. [`Packer::with_options`] (or the
+//! [`LOG_LEVEL_ENV`] and [`QUIET_ENV`] variables) additionally caps the
+//! crate's events during a pack; quiet (CI) mode keeps warnings and errors
+//! and hides [`PackProgress`] bars. The cap covers the thread running the
+//! pack and its `[build] threads` workers, not rayon's global pool.
+//!
+//! ## Thread safety
+//!
+//! [`Packer`], [`PackConfig`], [`Manifest`], [`PackOutput`] and the other
+//! configuration and result types are `Send + Sync`. Packers with their
+//! own [`PackContext`] pack concurrently in one process; a single packer
+//! shared between threads runs its packs one at a time. The caches packs
+//! share (the artifact store, the build cache) write their entries
+//! atomically.
+//!
+//! ## C API
+//!
+//! With the `ffi` feature, the `ffi` module exports C functions that
+//! create a configuration from a manifest string and pack it, returning the
+//! build report or the error as JSON.
 
+mod about;
+mod archive;
+mod branding;
+mod build_cache;
 mod bundle;
+mod cache_lock;
+mod capabilities;
+mod cdp;
+mod clean;
+mod codec;
 pub mod common;
 mod config;
+mod context;
+mod cuda;
 mod deps_collector;
+mod diff;
+mod doctor;
 mod downloader;
+mod entry_point;
+mod env_archive;
 mod error;
+mod eula;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod frontend_deps;
+mod hashing;
+mod history;
+mod hooks;
+mod http;
 pub mod icon;
+mod identity;
+mod inspect;
+mod integrity;
+mod isolation;
+mod keys;
 mod license;
+mod logging;
 mod manifest;
 mod metrics;
+mod mmap;
+mod optimize;
+mod output_path;
 mod overlay;
 mod packer;
+mod parallel;
+mod permissions;
+mod plan;
+mod plugin;
+mod print_policy;
 pub mod progress;
 mod protection;
+mod provenance;
 mod pyoxidizer;
+mod python_abi;
 mod python_standalone;
+mod remote_cache;
+mod repack;
 mod resource_editor;
+mod resume;
+mod retry;
+mod runtime_cache;
+mod sbom;
+mod schedule;
+mod signer_backend;
+mod signing;
+mod slots;
+mod smoke_test;
+mod staging;
+mod store;
+mod subprocess;
+mod symbols;
+mod system_launcher;
+mod targets;
+mod telemetry;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+mod toolchain;
+mod transform;
+mod uninstall;
+mod update_feed;
+mod update_policy;
+mod user_agent;
+mod versioning;
+mod watch;
+mod watermark;
 
 // Re-export public API
+pub use about::{AboutData, AboutInfo, ABOUT_JSON_PATH, ABOUT_PREFIX, THIRD_PARTY_NOTICES_PATH};
+pub use branding::HtmlBranding;
+pub use build_cache::{BuildCache, IncrementalConfig, IncrementalStats};
 pub use bundle::{AssetBundle, BundleBuilder};
+pub use cache_lock::{write_atomic, CacheLock};
+pub use cdp::{launch_for_test, wait_for_cdp, TestRun, TestRunDescriptor};
+pub use clean::{CleanEntry, CleanReport, CleanScope};
 
 // Re-export common types (unified configuration types)
 pub use common::{
-    BundleStrategy, CollectPattern, DebugConfig, HooksConfig, IsolationConfig, LicenseConfig,
-    LinuxPlatformConfig, MacOSPlatformConfig, NotarizationConfig, PlatformConfig, ProcessConfig,
-    ProtectionConfig as CommonProtectionConfig, PyOxidizerConfig as CommonPyOxidizerConfig,
-    RuntimeConfig, TargetPlatform, VxHooksConfig, WindowConfig, WindowStartPosition,
-    WindowsPlatformConfig, WindowsResourceConfig,
+    AboutConfig, AppUserModelConfig, BuildProfile, BundleStrategy, CdpTestConfig,
+    ClientCertificateConfig, CollectPattern, DebugConfig, DpiAwareness, DpiConfig,
+    FrontendDependencies, HeaderRule, HookCommand, HooksConfig, IsolationConfig, KioskConfig,
+    LicenseConfig, LicensePolicy, LinuxPlatformConfig, MacOSPlatformConfig, NetworkConfig,
+    NetworkRuntimeConfig, NotarizationConfig, OutputLayout, PackageManager, PlatformConfig,
+    ProcessConfig, ProtectionConfig as CommonProtectionConfig,
+    PyOxidizerConfig as CommonPyOxidizerConfig, RuntimeConfig, ScheduleAction, ScheduleEntry,
+    ShortcutTask, ShortcutsConfig, SmokeTestConfig, StorageConfig, StorageLocation, TargetPlatform,
+    ThemeMode, VxHooksConfig, WindowConfig, WindowContentConfig, WindowStartPosition,
+    WindowThemeConfig, WindowsPlatformConfig, WindowsResourceConfig,
 };
 
 // Re-export config types (runtime configuration)
-pub use config::{PackConfig, PackMode, PythonBundleConfig};
+pub use config::{LaunchSpec, PackConfig, PackMode, PythonBundleConfig};
+pub use context::{Clock, FixedClock, PackContext, SystemClock};
+pub use cuda::{
+    is_cuda_library, CudaConfig, CudaProvision, CudaRequirements, CUDA_REQUIREMENTS_PATH,
+};
 
+pub use capabilities::{probe_capabilities, Capability, CapabilityKind, HostCapabilities};
 pub use deps_collector::{CollectedDeps, DepsCollector, FileHashCache};
+pub use doctor::{CheckStatus, DoctorCheck, DoctorReport};
 pub use downloader::Downloader;
+pub use entry_point::EntryPoint;
 pub use error::{PackError, PackResult};
+pub use eula::{
+    eula_acceptance_path, is_eula_accepted, record_eula_acceptance, EulaAcceptance, EulaConfig,
+    EULA_ACCEPTANCE_FILE, EULA_PREFIX,
+};
+pub use frontend_deps::{get_node_modules_cache_dir, lockfile_hash};
+pub use history::{
+    compare_stats, get_history_dir, CompareTo, HistoryConfig, PackHistory, PackStats, Regression,
+};
+pub use http::{parse_fingerprint, HttpClient, HttpConfig, HTTP_USER_AGENT};
 pub use icon::{convert_icon_data, load_icon, IconData, IconFormat};
-pub use license::{get_machine_id, LicenseReason, LicenseStatus, LicenseValidator};
+pub use identity::{validate_identifier, AppIdentity};
+pub use isolation::{IsolationEnv, ISOLATION_ENV_FILE};
+pub use keys::{
+    verify_signature, EncryptedKey, KeyAlgorithm, KeyPair, KeyProtection, KeyStore, StoredKey,
+    KEY_PASSPHRASE_ENV,
+};
+pub use license::{
+    get_machine_id, issue_license_token, verify_license_token, LicenseClaims, LicenseReason,
+    LicenseStatus, LicenseValidator,
+};
+pub use logging::{LogLevel, PackOptions, LOG_LEVEL_ENV, LOG_TARGET, QUIET_ENV};
 
 // Re-export manifest types (TOML parsing)
 pub use manifest::{
@@ -138,23 +288,97 @@ pub use manifest::{LinuxBundleConfig, MacOSBundleConfig, WindowsBundleConfig};
 // Re-export InjectConfig from common
 pub use common::InjectConfig;
 
+pub use codec::{AssetClass, Codec, CodecConfig};
+pub use diff::{AssetChange, ChangeKind, PackDiff, ValueChange};
+pub use inspect::{InspectReport, UNPACK_ASSETS_DIR, UNPACK_REPORT_FILE};
+pub use integrity::{IntegrityEntry, IntegrityManifest, IntegrityReport, INTEGRITY_MANIFEST_PATH};
 pub use metrics::PackedMetrics;
-pub use overlay::{OverlayData, OverlayReader, OverlayWriter, OVERLAY_MAGIC, OVERLAY_VERSION};
-pub use packer::Packer;
-pub use progress::{progress_bar, spinner, PackProgress, ProgressExt, ProgressStyles};
+pub use optimize::OptimizeConfig;
+pub use output_path::{sanitize_output_name, validate_output_name};
+pub use overlay::{
+    migrate, sidecar_path, AssetAttributes, AssetIndexEntry, AssetReader, Compatibility,
+    DedupStats, OverlayArchive, OverlayData, OverlayPlacement, OverlayReader, OverlayStreamWriter,
+    OverlayVersion, OverlayWriter, SchemaReport, MIN_READER_VERSION, OVERLAY_MAGIC,
+    OVERLAY_VERSION, PACKER_VERSION, SIDECAR_EXTENSION, SUPPORTED_OVERLAY_VERSIONS,
+};
+pub use packer::{PackOutput, Packer};
+pub use permissions::{
+    expand_dir_template, ClipboardPermission, DownloadsPermission, DragDropPermission,
+    FileDialogPermission, PermissionsConfig,
+};
+pub use plan::{AssetSource, PackPlan, PlannedAsset, PlannedDownload, PlannedTool};
+pub use plugin::{PackPlugin, PluginContext};
+pub use print_policy::PrintConfig;
+pub use progress::{
+    progress_bar, spinner, ByteProgress, PackProgress, PackProgressObserver, PackStage,
+    ProgressExt, ProgressStyles,
+};
 pub use protection::{
     check_build_tools_available, is_protection_available, protect_python_code,
     EncryptionConfigPack, ProtectionConfig, ProtectionMethodConfig, ProtectionResult,
 };
+pub use provenance::{
+    provenance_path, sign_statement, verify_provenance, BuildDefinition, ExternalParameters,
+    Provenance, ProvenanceBuilder, ProvenanceConfig, ProvenanceEnvelope, ProvenanceMetadata,
+    ProvenanceStatement, ResourceDescriptor, RunDetails, IN_TOTO_PAYLOAD_TYPE,
+    IN_TOTO_STATEMENT_V1, PACK_BUILD_TYPE, SLSA_PROVENANCE_V1,
+};
 pub use pyoxidizer::{
     check_pyoxidizer, installation_instructions, DistributionFlavor, ExternalBinary,
     PyOxidizerBuilder, PyOxidizerConfig as PyOxidizerBuilderConfig, ResourceFile,
 };
+pub use python_abi::{
+    AbiMismatchAction, EmbeddedPythonConfig, HostPython, PythonRequirement, PYTHON_REQUIREMENT_PATH,
+};
 pub use python_standalone::{
-    extract_runtime, get_runtime_cache_dir, PythonRuntimeMeta, PythonStandalone,
-    PythonStandaloneConfig, PythonTarget,
+    extract_runtime, get_distribution_cache_dir, get_runtime_cache_dir, DistributionProvider,
+    LocalArchive, PythonRuntimeMeta, PythonStandalone, PythonStandaloneConfig, PythonTarget,
+};
+pub use remote_cache::RemoteCacheConfig;
+pub use repack::{repack, RepackReport};
+pub use resource_editor::{application_manifest, ResourceConfig, ResourceEditor};
+pub use resume::ResumeConfig;
+pub use retry::{is_lock_error, locking_processes, RetryPolicy};
+pub use runtime_cache::{CacheEntry, CacheManifest, RuntimeCache, CACHE_MANIFEST_FILE};
+pub use sbom::{Sbom, SbomPackage};
+pub use schedule::CronSpec;
+pub use signer_backend::{ExternalProvider, ExternalSigner, ExternalSignerConfig, SignerBackend};
+pub use signing::{
+    verify_artifact, OverlaySignature, OverlaySigner, SigningConfig, TRUSTED_OVERLAY_KEY,
+};
+pub use slots::{
+    AssetSlots, RollbackPolicy, Slot, SlotContent, SlotLaunch, SlotPointer, SlotTrial,
+    SLOT_POINTER_FILE,
+};
+pub use smoke_test::{SmokeTestReport, HEADLESS_ENV, SELF_CHECK_FLAG, SELF_CHECK_TIMEOUT_ENV};
+pub use staging::{available_space, estimate_required_space, new_run_id, SpaceEstimate};
+pub use store::{
+    get_store_dir, ArtifactStore, ObjectKind, StoreConfig, StoreGcReport, StoreObject, StoreRef,
+    STORE_DIR_ENV,
+};
+pub use subprocess::{CommandRunner, MockOutcome, RecordedCommand, RecordingRunner, SystemRunner};
+pub use symbols::{
+    read_build_id, BinaryFormat, BuildId, SymbolEntry, SymbolIndex, SymbolsConfig,
+    SYMBOLS_INFO_PATH,
+};
+pub use system_launcher::{
+    SystemPythonConfig, LAUNCH_SCRIPT_CMD, LAUNCH_SCRIPT_SH, SYSTEM_PYTHON_ENV,
+};
+pub use targets::{BuildTarget, TargetArch};
+pub use telemetry::{OtlpConfig, OtlpExporter};
+pub use toolchain::{
+    ensure_go, ensure_rust, get_toolchains_cache_dir, ProvisionedToolchain, ToolchainConfig,
+};
+pub use transform::{AssetTransform, TransformConfig, TransformStep};
+pub use uninstall::{user_data_dir, UninstallConfig, UNINSTALL_FLAG};
+pub use update_feed::{platform_key, FeedArtifact, UpdateFeed, UpdateFeedConfig};
+pub use update_policy::{compare_versions, UpdateDecision, UpdatePolicy, DEFAULT_UPDATE_CHANNEL};
+pub use user_agent::{
+    expand_user_agent, validate_user_agent, UserAgentVars, DEFAULT_USER_AGENT_TOKEN,
 };
-pub use resource_editor::{ResourceConfig, ResourceEditor};
+pub use versioning::{VersionIsolation, VersioningConfig};
+pub use watch::{WatchEvent, WatchOptions};
+pub use watermark::{PreviewConfig, WatermarkPosition};
 
 /// Alias for backward compatibility with CLI
 pub type PackGenerator = Packer;
@@ -163,12 +387,27 @@ pub type PackGenerator = Packer;
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Check if the current executable has overlay data (is a packed app)
+///
+/// A shell built with [`TRUSTED_OVERLAY_KEY`] only counts overlays signed
+/// by that key.
 pub fn is_packed() -> bool {
     let exe_path = match std::env::current_exe() {
         Ok(p) => p,
         Err(_) => return false,
     };
-    OverlayReader::has_overlay(&exe_path).unwrap_or(false)
+    if !OverlayReader::has_overlay(&exe_path).unwrap_or(false) {
+        return false;
+    }
+    if TRUSTED_OVERLAY_KEY.is_none() {
+        return true;
+    }
+    match OverlayReader::verify(&exe_path, TRUSTED_OVERLAY_KEY) {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::error!("Refusing overlay of {}: {}", exe_path.display(), e);
+            false
+        }
+    }
 }
 
 /// Read overlay data from the current executable
@@ -5,10 +5,22 @@
 //! - Token validation (offline and online)
 //! - Machine ID binding
 //! - Grace period handling
+//! - Signed license tokens
+//!
+//! Signed tokens are issued with the `[signing]` key
+//! ([`issue_license_token`]) and checked against the public key the overlay
+//! is signed with ([`LicenseValidator::with_public_key`]), so there is no
+//! second key to distribute.
 
 use crate::config::LicenseConfig;
+use crate::context::unix_seconds;
+use crate::keys::{verify_signature, KeyAlgorithm};
+use crate::signing::OverlaySigner;
+use crate::{PackError, PackResult};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::SystemTime;
 
 /// License validation result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,15 +61,93 @@ pub enum LicenseReason {
     ConfigError,
 }
 
+/// Domain separator of signed license tokens
+const TOKEN_CONTEXT: &[u8] = b"auroraview-license-token-v1\0";
+
+/// Claims of a signed license token
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LicenseClaims {
+    /// Licensee (user, company, order id, ...)
+    pub subject: String,
+    /// Token expiration date (YYYY-MM-DD)
+    pub expires_at: Option<String>,
+    /// Machine IDs the token is bound to (empty: any machine)
+    pub machines: Vec<String>,
+}
+
+/// Issue a signed license token (`<claims>.<signature>`, base64url)
+pub fn issue_license_token(signer: &OverlaySigner, claims: &LicenseClaims) -> PackResult<String> {
+    if let Some(ref expires_at) = claims.expires_at {
+        if parse_expiry_date(expires_at).is_none() {
+            return Err(PackError::Config(format!(
+                "Invalid token expiration '{}' (expected YYYY-MM-DD)",
+                expires_at
+            )));
+        }
+    }
+    let payload = serde_json::to_vec(claims)?;
+    let signature = signer.sign_bytes(&token_message(&payload))?;
+    Ok(format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(payload),
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+/// Check a signed license token against a public key (base64) and return
+/// its claims
+pub fn verify_license_token(public_key: &str, token: &str) -> PackResult<LicenseClaims> {
+    let invalid = |what: &str| PackError::Signature(format!("invalid license token: {}", what));
+    let (payload, signature) = token
+        .trim()
+        .split_once('.')
+        .ok_or_else(|| invalid("format"))?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| invalid("claims"))?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| invalid("signature"))?;
+    let public_key = STANDARD
+        .decode(public_key.trim())
+        .map_err(|_| invalid("public key"))?;
+    verify_signature(
+        KeyAlgorithm::of_public_key(&public_key),
+        &public_key,
+        &token_message(&payload),
+        &signature,
+    )
+    .map_err(|_| invalid("signature does not match"))?;
+    serde_json::from_slice(&payload).map_err(|_| invalid("claims"))
+}
+
+fn token_message(payload: &[u8]) -> Vec<u8> {
+    let mut message = TOKEN_CONTEXT.to_vec();
+    message.extend_from_slice(payload);
+    message
+}
+
 /// License validator
 pub struct LicenseValidator {
     config: LicenseConfig,
+    public_key: Option<String>,
 }
 
 impl LicenseValidator {
     /// Create a new license validator
     pub fn new(config: LicenseConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            public_key: None,
+        }
+    }
+
+    /// Require tokens signed by this key (base64), usually the overlay's
+    /// signing key
+    pub fn with_public_key(mut self, public_key: impl Into<String>) -> Self {
+        self.public_key = Some(public_key.into());
+        self
     }
 
     /// Validate the license
@@ -97,6 +187,10 @@ impl LicenseValidator {
                         message: Some("Invalid authorization token".to_string()),
                     };
                 }
+
+                if let Some(status) = self.check_signed_token(t) {
+                    return status;
+                }
             }
         }
 
@@ -179,29 +273,55 @@ impl LicenseValidator {
 
     /// Validate token format (basic check)
     fn validate_token_format(&self, token: &str) -> bool {
-        // Token should be non-empty and have reasonable length
-        !token.is_empty() && token.len() >= 8 && token.len() <= 512
+        // Token should be non-empty and have reasonable length; signed
+        // tokens carry an RSA signature and can be longer
+        let max_len = if self.public_key.is_some() { 4096 } else { 512 };
+        !token.is_empty() && token.len() >= 8 && token.len() <= max_len
+    }
+
+    /// Check a token's signature and claims, if a public key is set
+    ///
+    /// Returns the failing status, or `None` if the token is acceptable.
+    fn check_signed_token(&self, token: &str) -> Option<LicenseStatus> {
+        let public_key = self.public_key.as_deref()?;
+        let failed = |reason: LicenseReason, message: &str| {
+            Some(LicenseStatus {
+                valid: false,
+                reason,
+                days_remaining: None,
+                in_grace_period: false,
+                message: Some(message.to_string()),
+            })
+        };
+
+        let Ok(claims) = verify_license_token(public_key, token) else {
+            return failed(LicenseReason::InvalidToken, "Invalid authorization token");
+        };
+        if !claims.machines.is_empty() && !claims.machines.contains(&get_machine_id()) {
+            return failed(
+                LicenseReason::MachineNotAllowed,
+                "This machine is not authorized",
+            );
+        }
+        match claims
+            .expires_at
+            .as_deref()
+            .map(|e| self.check_expiration(e))
+        {
+            Some(ExpirationCheck::Expired) => {
+                failed(LicenseReason::Expired, "Authorization token has expired")
+            }
+            Some(ExpirationCheck::ParseError) => {
+                failed(LicenseReason::InvalidToken, "Invalid authorization token")
+            }
+            _ => None,
+        }
     }
 
     /// Check expiration date
     fn check_expiration(&self, expires_at: &str) -> ExpirationCheck {
-        // Parse date in YYYY-MM-DD format
-        let parts: Vec<&str> = expires_at.split('-').collect();
-        if parts.len() != 3 {
+        let Some((year, month, day)) = parse_expiry_date(expires_at) else {
             return ExpirationCheck::ParseError;
-        }
-
-        let year: i32 = match parts[0].parse() {
-            Ok(y) => y,
-            Err(_) => return ExpirationCheck::ParseError,
-        };
-        let month: u32 = match parts[1].parse() {
-            Ok(m) => m,
-            Err(_) => return ExpirationCheck::ParseError,
-        };
-        let day: u32 = match parts[2].parse() {
-            Ok(d) => d,
-            Err(_) => return ExpirationCheck::ParseError,
         };
 
         // Calculate expiration timestamp (end of day in UTC)
@@ -237,12 +357,30 @@ enum ExpirationCheck {
     ParseError,
 }
 
+/// Parse an expiration date in YYYY-MM-DD format
+pub(crate) fn parse_expiry_date(expires_at: &str) -> Option<(i32, u32, u32)> {
+    let parts: Vec<&str> = expires_at.split('-').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let year: i32 = parts[0].parse().ok()?;
+    let month: u32 = parts[1].parse().ok()?;
+    let day: u32 = parts[2].parse().ok()?;
+    ((1970..=9999).contains(&year) && (1..=12).contains(&month) && (1..=31).contains(&day))
+        .then_some((year, month, day))
+}
+
+/// Unix timestamp (seconds) at which a license stops working: the end of
+/// the expiration day (UTC) plus the grace period
+pub(crate) fn expiry_cutoff(expires_at: &str, grace_period_days: u32) -> Option<u64> {
+    let (year, month, day) = parse_expiry_date(expires_at)?;
+    let days = days_since_epoch(year, month, day) + grace_period_days as i64;
+    Some(days as u64 * 86400)
+}
+
 /// Get current days since Unix epoch
 fn current_days_since_epoch() -> i64 {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or(Duration::ZERO);
-    (now.as_secs() / 86400) as i64
+    (unix_seconds(SystemTime::now()) / 86400) as i64
 }
 
 /// Calculate days since Unix epoch for a given date
@@ -0,0 +1,240 @@
+//! Log level and quiet mode of a pack run
+//!
+//! See the crate documentation ("Logging") for the target hierarchy.
+//! [`PackOptions`] caps the crate's events with a scoped dispatcher layered
+//! over the current one, so the application's subscriber still formats
+//! and routes them.
+
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use tracing::level_filters::LevelFilter;
+use tracing::subscriber::Interest;
+use tracing::{span, Dispatch, Event, Metadata, Subscriber};
+
+/// Target prefix of every event the crate logs
+pub const LOG_TARGET: &str = "auroraview_pack";
+
+/// Environment variable with the log level (`error` ... `trace`)
+pub const LOG_LEVEL_ENV: &str = "AURORAVIEW_PACK_LOG";
+
+/// Environment variable enabling quiet mode (`1`, `true`)
+pub const QUIET_ENV: &str = "AURORAVIEW_PACK_QUIET";
+
+/// Most verbose level logged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Errors only
+    Error,
+    /// Warnings and errors
+    Warn,
+    /// Stage progress (default)
+    #[default]
+    Info,
+    /// Per-file and subprocess detail
+    Debug,
+    /// Everything
+    Trace,
+}
+
+impl LogLevel {
+    /// Name as used in filters (`info`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+
+    /// Level filter of `tracing`
+    pub fn level_filter(&self) -> LevelFilter {
+        match self {
+            Self::Error => LevelFilter::ERROR,
+            Self::Warn => LevelFilter::WARN,
+            Self::Info => LevelFilter::INFO,
+            Self::Debug => LevelFilter::DEBUG,
+            Self::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = PackError;
+
+    fn from_str(s: &str) -> PackResult<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" | "warning" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            other => Err(PackError::Config(format!(
+                "Invalid log level '{}' (expected error, warn, info, debug or trace)",
+                other
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Output settings of a pack run
+///
+/// The default leaves filtering to the subscriber.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackOptions {
+    /// Most verbose level the crate logs (`None`: as the subscriber allows)
+    pub log_level: Option<LogLevel>,
+    /// Quiet (CI) mode: warnings and errors only, no progress bars
+    pub quiet: bool,
+}
+
+impl PackOptions {
+    /// Options from [`LOG_LEVEL_ENV`] and [`QUIET_ENV`]
+    ///
+    /// An invalid level is ignored with a warning.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Options from variables looked up by `lookup`
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let log_level = lookup(LOG_LEVEL_ENV).and_then(|level| {
+            level
+                .parse()
+                .map_err(|e| tracing::warn!("Ignoring {}: {}", LOG_LEVEL_ENV, e))
+                .ok()
+        });
+        let quiet = lookup(QUIET_ENV)
+            .is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"));
+        Self { log_level, quiet }
+    }
+
+    /// Log at most at `level`
+    pub fn with_log_level(mut self, log_level: LogLevel) -> Self {
+        self.log_level = Some(log_level);
+        self
+    }
+
+    /// Quiet (CI) mode
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Level the crate's events are capped at: quiet mode caps it at
+    /// `warn` (`None`: no cap)
+    pub fn effective_level(&self) -> Option<LogLevel> {
+        match (self.log_level, self.quiet) {
+            (Some(level), true) => Some(level.min(LogLevel::Warn)),
+            (None, true) => Some(LogLevel::Warn),
+            (level, false) => level,
+        }
+    }
+
+    /// Filter directive for the crate's events (`auroraview_pack=info`),
+    /// for `EnvFilter` and `RUST_LOG`
+    pub fn filter_directive(&self) -> String {
+        format!(
+            "{}={}",
+            LOG_TARGET,
+            self.effective_level().unwrap_or_default()
+        )
+    }
+
+    /// Whether the crate logs an event of `level`
+    pub fn enabled(&self, level: &tracing::Level) -> bool {
+        self.effective_level()
+            .is_none_or(|max| max.level_filter() >= *level)
+    }
+
+    /// Dispatcher capping the crate's events on top of the current one
+    /// (`None`: no cap)
+    pub(crate) fn dispatch(&self) -> Option<Dispatch> {
+        let max = self.effective_level()?;
+        let inner = tracing::dispatcher::get_default(Dispatch::clone);
+        Some(Dispatch::new(LevelCap {
+            inner,
+            max: max.level_filter(),
+        }))
+    }
+}
+
+/// Run `op` with the crate's events capped by `dispatch`
+pub(crate) fn scoped<R>(dispatch: Option<&Dispatch>, op: impl FnOnce() -> R) -> R {
+    match dispatch {
+        Some(dispatch) => tracing::dispatcher::with_default(dispatch, op),
+        None => op(),
+    }
+}
+
+/// Subscriber dropping the crate's events above `max`, forwarding the rest
+struct LevelCap {
+    inner: Dispatch,
+    max: LevelFilter,
+}
+
+impl LevelCap {
+    fn capped(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target().starts_with(LOG_TARGET) && *metadata.level() > self.max
+    }
+}
+
+impl Subscriber for LevelCap {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        let interest = self.inner.register_callsite(metadata);
+        if interest.is_never() || !metadata.target().starts_with(LOG_TARGET) {
+            interest
+        } else {
+            // Decided per event: other dispatchers may want the callsite
+            Interest::sometimes()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        !self.capped(metadata) && self.inner.enabled(metadata)
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        self.inner.new_span(span)
+    }
+
+    fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+        self.inner.record(span, values)
+    }
+
+    fn record_follows_from(&self, span: &span::Id, follows: &span::Id) {
+        self.inner.record_follows_from(span, follows)
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        if !self.capped(event.metadata()) {
+            self.inner.event(event)
+        }
+    }
+
+    fn enter(&self, span: &span::Id) {
+        self.inner.enter(span)
+    }
+
+    fn exit(&self, span: &span::Id) {
+        self.inner.exit(span)
+    }
+
+    fn clone_span(&self, id: &span::Id) -> span::Id {
+        self.inner.clone_span(id)
+    }
+
+    fn try_close(&self, id: span::Id) -> bool {
+        self.inner.try_close(id)
+    }
+}
@@ -11,32 +11,65 @@
 //! version = "1.0.0"
 //! title = "My App"
 //! identifier = "com.example.app"
+//! brand_color = "#1e88e5"       # theme-color for frontend.rewrite_html
 //!
 //! [frontend]                   # Frontend configuration
 //! path = "./dist"              # Local frontend assets
 //! # url = "https://example.com" # OR remote URL (mutually exclusive)
+//! # dev_url = "http://localhost:5173" # Dev server (used when build.profile = "dev")
+//! # rewrite_html = true        # Rewrite index.html <title>/meta from package branding
+//! # include = ["**"]           # Globs of files to bundle (keeps built-in excludes like *.map)
+//! # exclude = ["*.mp4", "docs/**"] # Globs of files to skip
+//!
+//! [frontend.transform.extensions] # Transforms while bundling (optional)
+//! js = ["minify"]              # "minify" | "strip_comments" (js, css)
+//! html = ["strip_comments"]
+//! png = ["optimize"]           # Lossless; jpg is re-encoded at jpeg_quality
+//!
+//! [frontend.dependencies]      # npm/yarn/pnpm install with a frozen lockfile (optional)
+//! dir = "./web"                # Directory containing package.json
+//! script = "build"             # Run after installing
+//! # lockfile_sha256 = "..."    # Fail if the lockfile changed
 //!
 //! [backend]                    # Backend abstraction layer (optional)
-//! type = "python"              # "python" | "go" | "rust" | "node" | "none"
+//! type = "python"              # "python" | "go" | "rust" | "node" | "process" | "none"
 //!
 //! [backend.python]             # Python-specific config (when type = "python")
 //! version = "3.11"
 //! entry_point = "main:run"
 //! packages = ["flask"]
+//! # env_archive = "./dist/env.tar.gz"  # conda-pack/venv tarball instead of packages
+//!
+//! [backend.python.cuda]        # Leave CUDA/cuDNN libraries out of the bundle
+//! provide = "system"           # "system" | "download" (with url + sha256)
+//! version = "12.1"
+//!
+//! [backend.python.system]      # launch.sh/launch.cmd for strategy = "system"
+//! venv = true                  # Create .venv from requirements.txt on first run
+//!
+//! [backend.python.embedded]    # Host Python check for strategy = "embedded"
+//! on_mismatch = "error"        # "error" | "warn"
 //!
 //! [backend.go]                 # Go-specific config (when type = "go")
 //! module = "github.com/user/app"
 //! entry_point = "./cmd/server"
 //!
+//! [backend.go.toolchain]       # Download Go 1.22.5 if missing (checksum verified)
+//! version = "1.22.5"
+//!
 //! [backend.rust]               # Rust-specific config (when type = "rust")
 //! manifest = "./backend/Cargo.toml"
 //! binary = "server"
 //!
+//! [backend.rust.toolchain]     # Install via rustup into the pack cache if missing
+//! version = "1.79.0"
+//!
 //! [backend.node]               # Node.js-specific config (when type = "node")
 //! version = "20"
 //! entry_point = "./server/index.js"
 //!
 //! [backend.process]            # Common process settings (all backend types)
+//! # command = "./bin/server"    # Prebuilt binary (required when type = "process")
 //! args = []
 //! env = {}
 //! health_check = { url = "http://localhost:8080/health", timeout = 30 }
@@ -45,6 +78,16 @@
 //! width = 1280
 //! height = 720
 //!
+//! [window.dpi]                 # Scaling on mixed-DPI setups (optional)
+//! awareness = "per_monitor_v2" # unaware, system, per_monitor, per_monitor_v2
+//! # scale_factor = 1.0         # Ignore the monitor's scale
+//! # min_scale_factor = 1.25
+//!
+//! [window.theme]               # Colors before first paint (optional)
+//! mode = "dark"                # system, light, dark
+//! titlebar_color = "#1e1e1e"
+//! background_color = "#121212"
+//!
 //! [bundle]                     # General bundling settings
 //! icon = "./assets/icon.png"
 //! copyright = "Copyright 2025"
@@ -53,46 +96,222 @@
 //! icon = "./assets/icon.ico"
 //! console = false
 //!
+//! [bundle.windows.app_user_model] # Taskbar grouping/pinning and notifications
+//! id = "Acme.PhotoTool.Main"
+//! # prevent_pinning = true
+//!
 //! [bundle.macos]               # macOS-specific
 //! icon = "./assets/icon.icns"
 //!
 //! [bundle.linux]               # Linux-specific
 //! categories = ["Development"]
 //!
+//! [bundle.update_feed]         # latest.json (+ Sparkle appcast) for the updater
+//! base_url = "https://downloads.example.com/{version}/"
+//! # minimum_version = "1.2.0"
+//!
 //! [build]                      # Build hooks
 //! before = ["npm run build"]
+//! # profile = "dev"            # "release" (default) | "dev"
+//! # staging_dir = "D:/pack-staging" # Intermediate files (default: system temp)
+//! # sanitize_name = true         # Fix invalid characters in the output name
+//! # skip_unchanged = true      # Return the previous output if nothing changed
+//! # threads = 8                # Threads reading/compressing assets (default: all cores)
+//! # layout = "onedir"          # "onefile" | "onedir" (assets next to the exe, no extraction)
+//! # targets = ["x86_64-windows", "aarch64-macos"] # One executable per target
+//!
+//! [build.stubs]                # Launcher executable per non-host target
+//! "aarch64-macos" = "./stubs/auroraview-aarch64-macos"
+//!
+//! [build.retry]                # Retries when AV scanners lock the output exe
+//! attempts = 5
+//! initial_delay_ms = 100
+//!
+//! [build.provenance]           # Signed SLSA provenance (<exe>.intoto.jsonl)
+//! # builder_id = "https://ci.example.com/release"
+//!
+//! [build.symbols]              # Strip binaries, collect PDB/dSYM/DWARF by build id
+//! strip = true
+//! symbol_server = "https://symbols.example.com"
+//!
+//! [build.optimize]             # Shrink bundled native binaries
+//! strip = true
+//! # upx = true                 # Smaller, but often flagged by antivirus
+//!
+//! [build.store]                # Shared download/toolchain/node_modules cache
+//! # dir = "./.pack-cache/store"
+//! max_size_mb = 10240
+//!
+//! [build.store.remote]         # Share the store between CI runners
+//! url = "s3://ci-cache/auroraview"   # Or gs://bucket/prefix, https://...
+//! # push = false               # Read-only runners
+//!
+//! [build.http]                 # All downloads (Python, [[downloads]], toolchains, rcedit)
+//! # proxy = "http://proxy.corp.example:3128" # Default: HTTPS_PROXY / HTTP_PROXY
+//! # no_proxy = ["artifacts.corp.example"]
+//! # rate_limit_retries = 3     # 429/503 retries, honoring Retry-After
+//!
+//! [build.http.pins]            # SHA-256 certificate fingerprints per host
+//! # "artifacts.corp.example" = ["9f:86:d0:..."]
+//!
+//! [build.incremental]          # Only recompress changed assets on repacks
+//! # dir = "./.pack-cache/build"
+//! # max_size_mb = 1024         # Evict least recently used blobs beyond this
+//!
+//! [build.resume]               # Rerun a failed pack from the failed stage
+//! # dir = "./.pack-cache/resume"
+//!
+//! [build.history]              # Record pack metrics, flag regressions in CI
+//! size_threshold_percent = 5.0
+//! # fail_on_regression = true
+//!
+//! [build.license_policy]       # Fail on incompatible dependency licenses
+//! denied = ["GPL-*", "AGPL-*"]
 //!
 //! [hooks]                      # File collection
+//! # timeout_secs = 1800          # Kill hooks that run too long
+//! # idle_timeout_secs = 300      # ...or stay silent too long
+//! # before_pack = ["python scripts/gen_version.py"] # After collection, before writing
+//! # after_pack = ["gpg --detach-sign \"${OUTPUT_EXE}\""] # Also ${OUTPUT_DIR}, ${PACKAGE_NAME}, ${VERSION}, ${TARGET}
+//! # [hooks.isolation]            # Clean PATH/env for hooks and builds
+//! # extra_path = ["./tools/bin"]
 //! [[hooks.collect]]
 //! source = "./examples/*.py"
 //! dest = "resources/examples"
+//! [[hooks.run]]                # Argv form, no shell quoting needed
+//! program = "npm"
+//! args = ["run", "build", "--", "--out-dir", "My Output"]
+//! cwd = "./web"
 //!
 //! [runtime]                    # Runtime environment
 //! [runtime.env]
 //! APP_ENV = "production"
 //!
+//! [runtime.kiosk]              # Kiosk / digital signage (optional)
+//! idle_reload_minutes = 30
+//!
+//! [[runtime.schedule]]         # Scheduled reload/restart (cron-like)
+//! action = "restart"
+//! cron = "0 4 * * *"
+//!
+//! [runtime.storage]            # Cookie / session persistence
+//! persist = true
+//! location = "app_data"        # app_data, portable, temp, custom
+//! clear_on_exit = false
+//!
+//! [runtime.print]              # Print / PDF export policy
+//! allowed = false
+//! strip_print_css = true       # Remove print styles from the bundled frontend
+//!
+//! [runtime.update]             # Update channel and auto-update constraints
+//! channel = "stable"
+//! feed_url = "https://downloads.example.com/{channel}/latest.json"
+//! # maximum_version = "1.99.99"
+//!
+//! [[shortcuts.tasks]]          # Jump-list / dock menu quick actions
+//! name = "Open logs folder"
+//! args = ["--open-logs"]
+//! icon = "./assets/logs.ico"
+//!
+//! [signing]                    # Overlay, update feed and license signing (optional)
+//! key = "./keys/overlay.pk8"   # or key_env = "AURORAVIEW_SIGNING_KEY"
+//! # key_id = "release"         # or a key from the keystore (AURORAVIEW_KEY_PASSPHRASE)
+//!
+//! # [signing.external]         # or a key in an HSM / cloud KMS (instead of key)
+//! # provider = "aws-kms"       # aws-kms, azure-key-vault, pkcs11, command
+//! # key = "alias/release-signing"
+//!
+//! [permissions.downloads]      # Download policy (optional)
+//! directory = "{downloads}/{app}"
+//! extensions = ["pdf", "csv"]
+//!
+//! [permissions.file_dialogs]   # Open/save dialog scope (optional)
+//! roots = ["{documents}"]
+//!
+//! [permissions.clipboard]      # Clipboard access (optional)
+//! read = false
+//!
+//! [permissions.drag_drop]      # External drag-drop (optional)
+//! files = false
+//!
 //! [debug]                      # Debug settings
 //! enabled = false
 //!
+//! [debug.test]                 # CDP test-run descriptor (requires remote_debugging_port)
+//! startup_timeout = 30
+//!
+//! [debug.smoke_test]           # Run the packed executable with --self-check after packing
+//! timeout = 60
+//! required = true
+//!
+//! [about]                      # About screen data (optional)
+//! changelog = "./CHANGELOG.md"
+//! license_file = "./LICENSE"
+//! third_party_notices = true   # Generated from bundled package metadata
+//!
 //! [license]                    # License validation
 //! enabled = false
 //!
+//! [license.eula]               # EULA accepted before the first launch
+//! path = "./EULA.html"
+//!
+//! [license.preview]            # Expiry watermark (requires expires_at)
+//! label = "Preview · expires {expires}"
+//!
 //! [inject]                     # JS/CSS injection
 //! js_code = "console.log('hello');"
+//!
+//! [network.runtime]            # Runtime network settings
+//! ca_certificates = ["./certs/internal-ca.pem"]
+//!
+//! [[network.runtime.client_certificates]]
+//! path = "./certs/client.p12"
+//! password_env = "APP_CLIENT_CERT_PASSWORD"
+//! origins = ["https://internal.example.com"]
+//!
+//! [[network.runtime.headers]]
+//! name = "X-Tenant-Id"
+//! value = "acme"                # or value_env = "APP_TENANT_ID"
+//! origins = ["https://internal.example.com"]
 //! ```
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Component, Path, PathBuf};
 
+use crate::about::AboutInfo;
+use crate::branding::HtmlBranding;
+use crate::build_cache::IncrementalConfig;
+use crate::codec::CodecConfig;
 use crate::common::{
-    default_module_search_paths, default_optimize, default_python_version, BundleStrategy,
-    CollectPattern, DebugConfig, HooksConfig, IsolationConfig, LicenseConfig, LinuxPlatformConfig,
-    MacOSPlatformConfig, ProcessConfig, PyOxidizerConfig, RuntimeConfig, VxHooksConfig,
-    WindowConfig, WindowStartPosition, WindowsPlatformConfig,
+    default_module_search_paths, default_optimize, default_python_version, AboutConfig,
+    BuildProfile, BundleStrategy, CollectPattern, DebugConfig, DpiConfig, FrontendDependencies,
+    HookCommand, HooksConfig, IsolationConfig, LicenseConfig, LicensePolicy, LinuxPlatformConfig,
+    MacOSPlatformConfig, NetworkConfig, OutputLayout, ProcessConfig, PyOxidizerConfig,
+    RuntimeConfig, ShortcutsConfig, VxHooksConfig, WindowConfig, WindowContentConfig,
+    WindowStartPosition, WindowThemeConfig, WindowsPlatformConfig,
 };
-use crate::config::PythonBundleConfig;
+use crate::config::{LaunchSpec, PythonBundleConfig};
+use crate::cuda::CudaConfig;
 use crate::error::{PackError, PackResult};
+use crate::history::HistoryConfig;
+use crate::http::HttpConfig;
+use crate::optimize::OptimizeConfig;
+use crate::overlay::OverlayPlacement;
+use crate::permissions::PermissionsConfig;
+use crate::provenance::ProvenanceConfig;
+use crate::python_abi::EmbeddedPythonConfig;
+use crate::resume::ResumeConfig;
+use crate::retry::RetryPolicy;
+use crate::schedule::CronSpec;
+use crate::signing::SigningConfig;
+use crate::store::StoreConfig;
+use crate::symbols::SymbolsConfig;
+use crate::system_launcher::SystemPythonConfig;
+use crate::toolchain::ToolchainConfig;
+use crate::transform::TransformConfig;
+use crate::uninstall::UninstallConfig;
+use crate::update_feed::UpdateFeedConfig;
 
 // Re-export common types for convenience
 pub use crate::common::InjectConfig;
@@ -159,6 +378,10 @@ pub struct Manifest {
     #[serde(default)]
     pub debug: DebugConfig,
 
+    /// About-screen data (changelog, license text, third-party notices)
+    #[serde(default)]
+    pub about: Option<AboutConfig>,
+
     /// License/authorization settings
     #[serde(default)]
     pub license: Option<LicenseConfig>,
@@ -167,6 +390,10 @@ pub struct Manifest {
     #[serde(default)]
     pub inject: Option<InjectConfig>,
 
+    /// Network configuration (CA bundles, client certificates)
+    #[serde(default)]
+    pub network: Option<NetworkConfig>,
+
     /// Vx configuration for dependency bootstrap
     #[serde(default)]
     pub vx: Option<VxConfig>,
@@ -174,6 +401,22 @@ pub struct Manifest {
     /// Downloads configuration for embedding external dependencies
     #[serde(default)]
     pub downloads: Vec<DownloadEntry>,
+
+    /// Jump-list / dock menu quick actions
+    #[serde(default)]
+    pub shortcuts: Option<ShortcutsConfig>,
+
+    /// Overlay signing
+    #[serde(default)]
+    pub signing: Option<SigningConfig>,
+
+    /// Download and file dialog permissions
+    #[serde(default)]
+    pub permissions: Option<PermissionsConfig>,
+
+    /// Uninstall helpers (`[uninstall]`)
+    #[serde(default)]
+    pub uninstall: Option<UninstallConfig>,
 }
 
 // ============================================================================
@@ -218,7 +461,14 @@ pub struct PackageConfig {
     #[serde(default)]
     pub repository: Option<String>,
 
+    /// Brand color (e.g., "#1e88e5"), used for the `theme-color` meta tag
+    #[serde(default)]
+    pub brand_color: Option<String>,
+
     /// Custom user agent
+    ///
+    /// May contain `{app_name}`, `{version}`, `{os}` and `{arch}` (expanded
+    /// at pack time) and `{default}` (the WebView's default user agent).
     #[serde(default)]
     pub user_agent: Option<String>,
 
@@ -248,6 +498,31 @@ pub struct FrontendConfig {
     /// Remote URL to load (mutually exclusive with path)
     #[serde(default)]
     pub url: Option<String>,
+
+    /// Dev server URL (e.g., "http://localhost:5173") loaded when `[build] profile = "dev"`
+    #[serde(default)]
+    pub dev_url: Option<String>,
+
+    /// Rewrite `<title>` and branding meta tags of index.html at pack time
+    #[serde(default)]
+    pub rewrite_html: bool,
+
+    /// Globs of files to bundle (default: all)
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Globs of files and directories to skip, on top of the built-in
+    /// excludes (`.git`, `.DS_Store`, `*.map`, ...)
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Minify and optimize assets while bundling
+    #[serde(default)]
+    pub transform: Option<TransformConfig>,
+
+    /// Package manager install run before hooks
+    #[serde(default)]
+    pub dependencies: Option<FrontendDependencies>,
 }
 
 // ============================================================================
@@ -269,6 +544,8 @@ pub enum BackendType {
     Rust,
     /// Node.js backend
     Node,
+    /// Prebuilt binary configured by [backend.process]
+    Process,
 }
 
 impl BackendType {
@@ -279,6 +556,7 @@ impl BackendType {
             "go" | "golang" => BackendType::Go,
             "rust" => BackendType::Rust,
             "node" | "nodejs" | "node.js" => BackendType::Node,
+            "process" => BackendType::Process,
             "none" | "" => BackendType::None,
             _ => BackendType::None,
         }
@@ -288,7 +566,7 @@ impl BackendType {
 /// Backend configuration (abstraction layer for multiple backend types)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct BackendConfig {
-    /// Backend type: "python" | "go" | "rust" | "node" | "none"
+    /// Backend type: "python" | "go" | "rust" | "node" | "process" | "none"
     #[serde(default, rename = "type")]
     pub backend_type: BackendType,
 
@@ -383,6 +661,25 @@ pub struct BackendPythonConfig {
     /// Code protection configuration
     #[serde(default)]
     pub protection: Option<ProtectionManifestConfig>,
+
+    /// Prebuilt environment archive (conda-pack or venv tarball, .tar.gz/.tar/.zip)
+    ///
+    /// Embedded as the runtime instead of python-build-standalone plus
+    /// `packages`/`requirements`. Requires the standalone strategy.
+    #[serde(default)]
+    pub env_archive: Option<PathBuf>,
+
+    /// CUDA/cuDNN library handling (under [backend.python.cuda])
+    #[serde(default)]
+    pub cuda: Option<CudaConfig>,
+
+    /// System Python launcher (under [backend.python.system])
+    #[serde(default)]
+    pub system: Option<SystemPythonConfig>,
+
+    /// Host Python check of the embedded strategy (under [backend.python.embedded])
+    #[serde(default)]
+    pub embedded: Option<EmbeddedPythonConfig>,
 }
 
 impl Default for BackendPythonConfig {
@@ -405,6 +702,10 @@ impl Default for BackendPythonConfig {
             isolation: None,
             pyoxidizer: None,
             protection: Some(ProtectionManifestConfig::default()),
+            env_archive: None,
+            cuda: None,
+            system: None,
+            embedded: None,
         }
     }
 }
@@ -452,6 +753,10 @@ impl BackendPythonConfig {
                 .as_ref()
                 .map(|p| p.to_protection_config())
                 .unwrap_or_default(),
+            env_archive: self.env_archive.as_ref().map(resolve_path),
+            cuda: self.cuda.clone(),
+            system: self.system.clone(),
+            embedded: self.embedded.clone(),
         }
     }
 }
@@ -486,6 +791,10 @@ pub struct BackendGoConfig {
     /// Environment variables for build
     #[serde(default)]
     pub env: HashMap<String, String>,
+
+    /// Pinned Go toolchain, downloaded when missing (`[backend.go.toolchain]`)
+    #[serde(default)]
+    pub toolchain: Option<ToolchainConfig>,
 }
 
 /// Rust backend configuration (under [backend.rust])
@@ -518,6 +827,11 @@ pub struct BackendRustConfig {
     /// Whether to disable default features
     #[serde(default)]
     pub no_default_features: bool,
+
+    /// Pinned Rust toolchain, installed via rustup when missing
+    /// (`[backend.rust.toolchain]`)
+    #[serde(default)]
+    pub toolchain: Option<ToolchainConfig>,
 }
 
 fn default_release_profile() -> String {
@@ -563,6 +877,10 @@ fn default_node_bundle_strategy() -> String {
 /// Common backend process configuration (under [backend.process])
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct BackendProcessConfig {
+    /// Prebuilt binary to bundle and launch (required for `type = "process"`)
+    #[serde(default)]
+    pub command: Option<PathBuf>,
+
     /// Command line arguments
     #[serde(default)]
     pub args: Vec<String>,
@@ -596,6 +914,27 @@ fn default_max_restarts() -> u32 {
     3
 }
 
+impl BackendProcessConfig {
+    /// Convert to a LaunchSpec, resolving the command relative to base_dir
+    pub fn to_launch_spec(&self, base_dir: &Path) -> Option<LaunchSpec> {
+        let command = self.command.as_ref()?;
+        let binary = if command.is_absolute() {
+            command.clone()
+        } else {
+            normalize_path(&base_dir.join(command))
+        };
+
+        let mut spec = LaunchSpec::new(binary).with_args(self.args.clone());
+        spec.env = self.env.clone();
+        spec.working_dir = self.working_dir.clone();
+        spec.console = self.console;
+        spec.health_check = self.health_check.clone();
+        spec.restart_on_crash = self.restart_on_crash;
+        spec.max_restarts = self.max_restarts;
+        Some(spec)
+    }
+}
+
 /// Health check configuration for backend process
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct HealthCheckConfig {
@@ -690,6 +1029,18 @@ pub struct ManifestWindowConfig {
     /// Visible on start
     #[serde(default = "default_true")]
     pub visible: bool,
+
+    /// DPI awareness and scale factor (`[window.dpi]`)
+    #[serde(default)]
+    pub dpi: Option<DpiConfig>,
+
+    /// Theme and window colors (`[window.theme]`)
+    #[serde(default)]
+    pub theme: Option<WindowThemeConfig>,
+
+    /// Zoom and content scaling policy (`[window.content]`)
+    #[serde(default)]
+    pub content: Option<WindowContentConfig>,
 }
 
 fn default_width() -> u32 {
@@ -721,6 +1072,9 @@ impl Default for ManifestWindowConfig {
             fullscreen: false,
             maximized: false,
             visible: true,
+            dpi: None,
+            theme: None,
+            content: None,
         }
     }
 }
@@ -743,6 +1097,9 @@ impl From<ManifestWindowConfig> for WindowConfig {
             fullscreen: manifest.fullscreen,
             maximized: manifest.maximized,
             visible: manifest.visible,
+            dpi: manifest.dpi,
+            theme: manifest.theme,
+            content: manifest.content,
         }
     }
 }
@@ -828,6 +1185,15 @@ pub struct BundleConfig {
     #[serde(default)]
     pub resources: Vec<PathBuf>,
 
+    /// Overlay placement: "embedded" (appended to the exe) or "sidecar"
+    /// (`<name>.avpk` next to it)
+    #[serde(default)]
+    pub overlay: OverlayPlacement,
+
+    /// Update feed written next to the executable ([bundle.update_feed])
+    #[serde(default)]
+    pub update_feed: Option<UpdateFeedConfig>,
+
     /// Windows-specific configuration ([bundle.windows])
     #[serde(default)]
     pub windows: Option<WindowsPlatformConfig>,
@@ -1092,10 +1458,16 @@ pub struct BuildConfig {
     #[serde(default)]
     pub out_dir: Option<PathBuf>,
 
-    /// Target platforms to build for
+    /// Target platforms to build for (`x86_64-windows`, `aarch64-macos`,
+    /// ...); each is packed into `<name>-<arch>-<os>[.exe]`
     #[serde(default)]
     pub targets: Vec<String>,
 
+    /// Launcher stub per target (`[build.stubs]`, target -> executable);
+    /// required for every target other than the packer's own platform
+    #[serde(default)]
+    pub stubs: BTreeMap<String, PathBuf>,
+
     /// Enable release mode
     #[serde(default = "default_true")]
     pub release: bool,
@@ -1109,6 +1481,82 @@ pub struct BuildConfig {
     /// Recommended: 19 for release, 3 for development
     #[serde(default = "default_compression_level")]
     pub compression_level: i32,
+
+    /// Compression codec per asset class (`[build.codecs]`)
+    #[serde(default)]
+    pub codecs: CodecConfig,
+
+    /// Threads reading and compressing assets (default: all cores)
+    #[serde(default)]
+    pub threads: Option<usize>,
+
+    /// "onefile" (one executable) or "onedir" (`<name>/` directory with
+    /// the assets as plain files); default depends on the mode
+    #[serde(default)]
+    pub layout: Option<OutputLayout>,
+
+    /// Incremental packing from a build cache (`[build.incremental]`)
+    #[serde(default)]
+    pub incremental: Option<IncrementalConfig>,
+
+    /// Resume failed packs from the failed stage (`[build.resume]`)
+    #[serde(default)]
+    pub resume: Option<ResumeConfig>,
+
+    /// Signed SLSA provenance next to the executable (`[build.provenance]`)
+    #[serde(default)]
+    pub provenance: Option<ProvenanceConfig>,
+
+    /// Build metadata stored in the overlay (`[build.metadata]`, e.g.
+    /// `channel = "beta"`)
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+
+    /// Build profile: "release" (default) or "dev"
+    #[serde(default)]
+    pub profile: BuildProfile,
+
+    /// License policy for bundled third-party packages
+    #[serde(default)]
+    pub license_policy: Option<LicensePolicy>,
+
+    /// Staging directory for intermediate files (default: system temp dir)
+    #[serde(default)]
+    pub staging_dir: Option<PathBuf>,
+
+    /// Retry policy for file-lock errors on the output executable
+    #[serde(default)]
+    pub retry: RetryPolicy,
+
+    /// Sanitize `[package] name` into a valid output file name instead of
+    /// failing validation
+    #[serde(default)]
+    pub sanitize_name: bool,
+
+    /// Return the previous output instead of packing when the manifest,
+    /// launcher and inputs are unchanged
+    #[serde(default)]
+    pub skip_unchanged: bool,
+
+    /// Debug symbol stripping and collection
+    #[serde(default)]
+    pub symbols: Option<SymbolsConfig>,
+
+    /// Size optimization of bundled native binaries
+    #[serde(default)]
+    pub optimize: Option<OptimizeConfig>,
+
+    /// Pack statistics history and regression detection
+    #[serde(default)]
+    pub history: Option<HistoryConfig>,
+
+    /// Artifact store shared by the pack caches
+    #[serde(default)]
+    pub store: StoreConfig,
+
+    /// Proxy, timeouts and certificate pins of all downloads
+    #[serde(default)]
+    pub http: HttpConfig,
 }
 
 fn default_compression_level() -> i32 {
@@ -1126,11 +1574,16 @@ pub struct HooksManifestConfig {
     #[serde(default)]
     pub before_collect: Vec<String>,
 
+    /// Commands to run after collection, before the output is written
+    #[serde(default)]
+    pub before_pack: Vec<String>,
+
     /// Additional file patterns to collect
     #[serde(default)]
     pub collect: Vec<CollectEntry>,
 
-    /// Commands to run after packing
+    /// Commands to run after packing (build variables such as
+    /// `${OUTPUT_EXE}` are substituted)
     #[serde(default)]
     pub after_pack: Vec<String>,
 
@@ -1141,6 +1594,26 @@ pub struct HooksManifestConfig {
     /// Vx-specific hook commands
     #[serde(default)]
     pub vx: VxHooksConfig,
+
+    /// Argv-based hook commands (`[[hooks.run]]`)
+    #[serde(default)]
+    pub run: Vec<HookCommand>,
+
+    /// Kill a hook after this many seconds
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// Kill a hook after this many seconds without output
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+
+    /// Heartbeat log interval in seconds (default: 30, 0 disables)
+    #[serde(default)]
+    pub heartbeat_secs: Option<u64>,
+
+    /// Controlled environment for hook commands (`[hooks.isolation]`)
+    #[serde(default)]
+    pub isolation: Option<IsolationManifestConfig>,
 }
 
 impl HooksManifestConfig {
@@ -1148,6 +1621,7 @@ impl HooksManifestConfig {
     pub fn to_hooks_config(&self, base_dir: &Path) -> HooksConfig {
         HooksConfig {
             before_collect: self.before_collect.clone(),
+            before_pack: self.before_pack.clone(),
             collect: self
                 .collect
                 .iter()
@@ -1170,6 +1644,32 @@ impl HooksManifestConfig {
             after_pack: self.after_pack.clone(),
             use_vx: self.use_vx,
             vx: self.vx.clone(),
+            run: self
+                .run
+                .iter()
+                .map(|h| HookCommand {
+                    cwd: h
+                        .cwd
+                        .as_ref()
+                        .map(|cwd| normalize_path(&base_dir.join(cwd))),
+                    ..h.clone()
+                })
+                .collect(),
+            timeout_secs: self.timeout_secs,
+            idle_timeout_secs: self.idle_timeout_secs,
+            heartbeat_secs: self.heartbeat_secs,
+            isolation: self.isolation.as_ref().map(|i| {
+                let mut isolation = i.to_isolation_config();
+                // Relative tool directories resolve against the manifest
+                for entry in &mut isolation.extra_path {
+                    if Path::new(entry.as_str()).is_relative() {
+                        *entry = normalize_path(&base_dir.join(&*entry))
+                            .to_string_lossy()
+                            .to_string();
+                    }
+                }
+                isolation
+            }),
         }
     }
 }
@@ -1178,10 +1678,16 @@ impl From<HooksConfig> for HooksManifestConfig {
     fn from(config: HooksConfig) -> Self {
         Self {
             before_collect: config.before_collect,
+            before_pack: config.before_pack,
             collect: config.collect.into_iter().map(CollectEntry::from).collect(),
             after_pack: config.after_pack,
             use_vx: config.use_vx,
             vx: config.vx,
+            run: config.run,
+            timeout_secs: config.timeout_secs,
+            idle_timeout_secs: config.idle_timeout_secs,
+            heartbeat_secs: config.heartbeat_secs,
+            isolation: config.isolation.map(IsolationManifestConfig::from),
         }
     }
 }
@@ -1294,6 +1800,136 @@ impl Manifest {
             ));
         }
 
+        // Validate dev server URL
+        let dev_url = self.frontend.as_ref().and_then(|f| f.dev_url.as_ref());
+        if self.build.profile.is_dev() && dev_url.is_none() {
+            return Err(PackError::Config(
+                "build.profile = \"dev\" requires 'dev_url' in [frontend]".to_string(),
+            ));
+        }
+        if let Some(dev_url) = dev_url {
+            let parsed = url::Url::parse(dev_url).map_err(|e| {
+                PackError::Config(format!("Invalid 'dev_url' in [frontend]: {}", e))
+            })?;
+            if parsed.scheme() != "http" && parsed.scheme() != "https" {
+                return Err(PackError::Config(format!(
+                    "'dev_url' in [frontend] must be an http(s) URL: {}",
+                    dev_url
+                )));
+            }
+        }
+
+        // Validate brand color
+        if let Some(ref color) = self.package.brand_color {
+            if !crate::common::is_hex_color(color) {
+                return Err(PackError::Config(format!(
+                    "'brand_color' in [package] must be a hex color (e.g., \"#1e88e5\"): {}",
+                    color
+                )));
+            }
+        }
+
+        // Validate kiosk configuration
+        if let Some(kiosk) = self.runtime.as_ref().and_then(|r| r.kiosk.as_ref()) {
+            if kiosk.idle_reload_minutes == Some(0) {
+                return Err(PackError::Config(
+                    "'idle_reload_minutes' in [runtime.kiosk] must be greater than 0".to_string(),
+                ));
+            }
+        }
+
+        // Validate scheduled reload/restart policies
+        if let Some(ref runtime) = self.runtime {
+            for entry in &runtime.schedule {
+                CronSpec::parse(&entry.cron)?;
+            }
+        }
+
+        // Validate the EULA and preview watermark
+        if let Some(ref license) = self.license {
+            if let Some(ref eula) = license.eula {
+                eula.validate()?;
+            }
+            if let Some(ref preview) = license.preview {
+                preview.validate(license)?;
+            }
+        }
+
+        // Validate license policy
+        if let Some(ref policy) = self.build.license_policy {
+            policy.validate()?;
+        }
+
+        if self.build.threads == Some(0) {
+            return Err(PackError::Config(
+                "[build] threads must be at least 1".to_string(),
+            ));
+        }
+
+        self.build.http.validate()?;
+
+        if let Some(ref provenance) = self.build.provenance {
+            provenance.validate()?;
+            if self.signing.is_none() {
+                return Err(PackError::Config(
+                    "[build.provenance] requires [signing]".to_string(),
+                ));
+            }
+        }
+
+        // Validate the build matrix
+        for target in self.build.targets.iter().chain(self.build.stubs.keys()) {
+            crate::BuildTarget::parse(target)?;
+        }
+
+        // Validate storage persistence policy
+        if let Some(storage) = self.runtime.as_ref().and_then(|r| r.storage.as_ref()) {
+            storage.validate()?;
+        }
+
+        // Validate print policy
+        if let Some(print) = self.runtime.as_ref().and_then(|r| r.print.as_ref()) {
+            print.validate()?;
+        }
+
+        // Validate multi-version coexistence
+        if let Some(versioning) = self.runtime.as_ref().and_then(|r| r.versioning.as_ref()) {
+            versioning.validate(
+                Some(&self.package.version),
+                self.get_backend_type() == BackendType::Python,
+            )?;
+        }
+
+        // Validate the self-update rollback policy
+        if let Some(rollback) = self.runtime.as_ref().and_then(|r| r.rollback.as_ref()) {
+            rollback.validate()?;
+        }
+
+        // Validate the update channel and version constraints
+        if let Some(update) = self.runtime.as_ref().and_then(|r| r.update.as_ref()) {
+            update.validate(Some(&self.package.version))?;
+        }
+
+        // Validate file system permissions
+        if let Some(ref permissions) = self.permissions {
+            permissions.validate()?;
+        }
+
+        // Validate uninstall helpers
+        if let Some(ref uninstall) = self.uninstall {
+            uninstall.validate()?;
+        }
+
+        // Validate the update feed
+        if let Some(ref update_feed) = self.bundle.update_feed {
+            update_feed.validate()?;
+        }
+
+        // Validate runtime network settings
+        if let Some(ref network) = self.network {
+            network.runtime.validate()?;
+        }
+
         // Validate backend configuration
         if let Some(ref backend) = self.backend {
             match backend.backend_type {
@@ -1312,6 +1948,9 @@ impl Manifest {
                                 "Python optimize level must be 0, 1, or 2".to_string(),
                             ));
                         }
+                        if let Some(ref cuda) = py.cuda {
+                            cuda.validate()?;
+                        }
                     }
                 }
                 BackendType::Go => {
@@ -1321,10 +1960,18 @@ impl Manifest {
                                 "Go backend requires either 'entry_point' or 'module'".to_string(),
                             ));
                         }
+                        if let Some(ref toolchain) = go.toolchain {
+                            toolchain.validate("backend.go.toolchain")?;
+                        }
                     }
                 }
                 BackendType::Rust => {
                     // Rust config is optional, defaults work
+                    if let Some(toolchain) =
+                        backend.rust.as_ref().and_then(|r| r.toolchain.as_ref())
+                    {
+                        toolchain.validate("backend.rust.toolchain")?;
+                    }
                 }
                 BackendType::Node => {
                     if let Some(ref node) = backend.node {
@@ -1336,6 +1983,23 @@ impl Manifest {
                         }
                     }
                 }
+                BackendType::Process => {
+                    let has_command = backend
+                        .process
+                        .as_ref()
+                        .map(|p| p.command.is_some())
+                        .unwrap_or(false);
+                    if !has_command {
+                        return Err(PackError::Config(
+                            "Process backend requires 'command' in [backend.process]".to_string(),
+                        ));
+                    }
+                    if self.get_frontend_path().is_none() {
+                        return Err(PackError::Config(
+                            "Process backend requires 'path' in [frontend]".to_string(),
+                        ));
+                    }
+                }
                 BackendType::None => {
                     // No backend, nothing to validate
                 }
@@ -1411,12 +2075,60 @@ impl Manifest {
             .unwrap_or_else(|| self.package.name.clone())
     }
 
+    /// Get the frontend dependency install with `dir` resolved against `base_dir`
+    pub fn get_frontend_dependencies(&self, base_dir: &Path) -> Option<FrontendDependencies> {
+        let deps = self.frontend.as_ref()?.dependencies.as_ref()?;
+        let dir = match deps.dir {
+            Some(ref dir) if dir.is_absolute() => dir.clone(),
+            Some(ref dir) => normalize_path(&base_dir.join(dir)),
+            None => base_dir.to_path_buf(),
+        };
+        Some(FrontendDependencies {
+            dir: Some(dir),
+            ..deps.clone()
+        })
+    }
+
+    /// Get the branding applied to index.html (when `frontend.rewrite_html` is set)
+    pub fn get_html_branding(&self) -> Option<HtmlBranding> {
+        if !self.frontend.as_ref().is_some_and(|f| f.rewrite_html) {
+            return None;
+        }
+        Some(HtmlBranding {
+            title: Some(self.get_title()),
+            theme_color: self.package.brand_color.clone(),
+            description: self.package.description.clone(),
+        })
+    }
+
+    /// Get the About-screen config with metadata filled from `[package]`
+    pub fn get_about_config(&self, base_dir: &Path) -> Option<AboutConfig> {
+        let mut about = self.about.clone()?;
+        about.changelog = about.changelog.map(|p| base_dir.join(p));
+        about.license_file = about.license_file.map(|p| base_dir.join(p));
+        about.info = AboutInfo {
+            name: self.get_title(),
+            version: Some(self.package.version.clone()),
+            description: self.package.description.clone(),
+            authors: self.package.authors.clone(),
+            homepage: self.package.homepage.clone(),
+            license: self.package.license.clone(),
+        };
+        Some(about)
+    }
+
     /// Get the effective identifier
     pub fn get_identifier(&self) -> Option<String> {
         self.package
             .identifier
             .clone()
             .or_else(|| self.bundle.identifier.clone())
+            .or_else(|| {
+                self.bundle
+                    .macos
+                    .as_ref()
+                    .and_then(|m| m.bundle_identifier.clone())
+            })
     }
 
     /// Get the frontend path
@@ -1429,6 +2141,15 @@ impl Manifest {
         self.frontend.as_ref().and_then(|f| f.url.clone())
     }
 
+    /// Get the dev server URL (only when the dev profile is active)
+    pub fn get_dev_server_url(&self) -> Option<String> {
+        if self.build.profile.is_dev() {
+            self.frontend.as_ref().and_then(|f| f.dev_url.clone())
+        } else {
+            None
+        }
+    }
+
     /// Get the user agent
     pub fn get_user_agent(&self) -> Option<String> {
         self.package.user_agent.clone()
@@ -1478,6 +2199,17 @@ impl Manifest {
         self.get_windows_platform_config()
     }
 
+    /// Get launch spec from backend.process (when type = "process")
+    pub fn get_launch_spec(&self, base_dir: &Path) -> Option<LaunchSpec> {
+        self.backend.as_ref().and_then(|b| {
+            if b.backend_type == BackendType::Process {
+                b.process.as_ref().and_then(|p| p.to_launch_spec(base_dir))
+            } else {
+                None
+            }
+        })
+    }
+
     /// Get Python bundle config from backend.python
     pub fn get_python_bundle_config(&self, base_dir: &Path) -> Option<PythonBundleConfig> {
         self.backend.as_ref().and_then(|b| {
@@ -1509,7 +2241,9 @@ pub struct VxConfig {
     #[serde(default)]
     pub runtime_checksum: Option<String>,
 
-    /// Local cache directory for downloaded artifacts
+    /// Local download cache of older versions; downloads are now kept in
+    /// the artifact store (`[build.store]`). Relative to the base
+    /// directory; the default goes under the context's cache root if set
     #[serde(default = "default_vx_cache_dir")]
     pub cache_dir: PathBuf,
 
@@ -1550,7 +2284,7 @@ impl Default for VxConfig {
     }
 }
 
-fn default_vx_cache_dir() -> PathBuf {
+pub(crate) fn default_vx_cache_dir() -> PathBuf {
     PathBuf::from("./.pack-cache/vx")
 }
 
@@ -138,6 +138,31 @@ impl PackedMetrics {
         self.start.elapsed()
     }
 
+    /// Main phases marked so far, in startup order, with their time since
+    /// start
+    pub fn marks(&self) -> Vec<(&'static str, Duration)> {
+        [
+            ("overlay_read", self.overlay_read),
+            ("config_decompress", self.config_decompress),
+            ("assets_decompress", self.assets_decompress),
+            ("tar_extract", self.tar_extract),
+            ("python_runtime_extract", self.python_runtime_extract),
+            ("python_files_extract", self.python_files_extract),
+            ("resources_extract", self.resources_extract),
+            ("python_start", self.python_start),
+            ("window_created", self.window_created),
+            ("webview_created", self.webview_created),
+        ]
+        .into_iter()
+        .filter_map(|(name, mark)| mark.map(|mark| (name, mark)))
+        .collect()
+    }
+
+    /// Custom phase timings, in the order recorded
+    pub fn phases(&self) -> &[(String, Duration)] {
+        &self.phases
+    }
+
     /// Format a duration for display
     fn format_duration(d: Duration) -> String {
         let ms = d.as_secs_f64() * 1000.0;
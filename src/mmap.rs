@@ -0,0 +1,56 @@
+//! Read-only memory maps of overlay files
+//!
+//! Used by [`OverlayArchive::asset_reader`](crate::OverlayArchive::asset_reader)
+//! to decompress assets straight from the mapped executable. Mapping is
+//! best-effort: when it fails, callers fall back to positional reads of the
+//! file.
+//!
+//! A mapped file must not shrink while mapped: touching pages past the new
+//! end is a `SIGBUS` on Unix. The overlay writers that rewrite packed files
+//! ([`OverlayWriter::patch`](crate::OverlayWriter::patch),
+//! [`migrate`](crate::migrate)) never truncate them; they rename a new file
+//! into place. Every map also holds a shared advisory lock on its file, and
+//! those writers refuse to run while any map of the file is alive. Tools
+//! that ignore the lock must not truncate mapped files.
+
+use memmap2::Mmap;
+use std::fs::{File, TryLockError};
+use std::io;
+
+/// A file mapped read-only into memory
+pub(crate) struct MappedFile {
+    map: Mmap,
+    /// Handle holding the shared lock for as long as the map lives
+    _lock: File,
+}
+
+impl MappedFile {
+    /// Map a whole file
+    ///
+    /// Fails if a writer holds the exclusive lock of the file.
+    pub(crate) fn map(file: &File) -> io::Result<Self> {
+        let lock = file.try_clone()?;
+        match lock.try_lock_shared() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Err(io::Error::other("file is being rewritten"));
+            }
+            Err(TryLockError::Error(e)) => return Err(e),
+        }
+        if file.metadata()?.len() == 0 {
+            return Err(io::Error::other("cannot map an empty file"));
+        }
+
+        // SAFETY: the map is read-only and private to this value; this
+        // crate's writers never truncate packed files, and the shared lock
+        // held until it is dropped keeps them off the file entirely (see the
+        // module docs).
+        let map = unsafe { Mmap::map(file)? };
+        Ok(Self { map, _lock: lock })
+    }
+
+    /// The mapped bytes
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.map
+    }
+}
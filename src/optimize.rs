@@ -0,0 +1,308 @@
+//! Size optimization of bundled native binaries (`[build.optimize]`)
+//!
+//! Runs after all assets are collected and before the overlay is written:
+//! native binaries in the overlay (extension modules, shared libraries,
+//! external and backend executables) are stripped of symbols and optionally
+//! compressed with UPX. Each binary is processed in a staging copy and only
+//! replaced when it got smaller; per-binary savings are reported.
+//!
+//! Stripping uses the host `strip`, so it only touches binaries native to
+//! the packing machine (ELF on Linux, Mach-O on macOS, re-signed ad hoc).
+
+use crate::symbols::{detect_format, BinaryFormat};
+use crate::{PackError, PackResult};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Binary size optimization configuration
+///
+/// Located at `[build.optimize]` in TOML.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct OptimizeConfig {
+    /// Strip symbols from bundled native binaries
+    pub strip: bool,
+
+    /// Compress bundled binaries with UPX (often flagged by antivirus scanners)
+    pub upx: bool,
+
+    /// UPX executable (default: `upx` on PATH)
+    pub upx_path: Option<PathBuf>,
+
+    /// UPX arguments
+    pub upx_args: Vec<String>,
+
+    /// Skip binaries smaller than this many bytes
+    pub min_size: u64,
+
+    /// Glob patterns (overlay paths) of binaries to leave untouched
+    pub exclude: Vec<String>,
+}
+
+impl Default for OptimizeConfig {
+    fn default() -> Self {
+        Self {
+            strip: true,
+            upx: false,
+            upx_path: None,
+            upx_args: vec!["--best".to_string()],
+            min_size: 64 * 1024,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl OptimizeConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        for pattern in &self.exclude {
+            glob::Pattern::new(pattern).map_err(|e| {
+                PackError::Config(format!(
+                    "Invalid exclude pattern '{}' in [build.optimize]: {}",
+                    pattern, e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Size change of one optimized binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BinarySavings {
+    /// Overlay path
+    pub path: String,
+    /// Size before optimization
+    pub before: u64,
+    /// Size after optimization
+    pub after: u64,
+    /// Applied steps ("strip", "upx")
+    pub steps: Vec<&'static str>,
+}
+
+/// Optimize native binaries among the overlay assets in place
+pub(crate) fn optimize_assets(
+    config: &OptimizeConfig,
+    assets: &mut [(String, Vec<u8>)],
+    staging: &Path,
+) -> PackResult<Vec<BinarySavings>> {
+    let exclude: Vec<glob::Pattern> = config
+        .exclude
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+
+    let strip = config.strip && tool_available(Command::new("strip").arg("--version"));
+    if config.strip && !strip {
+        tracing::warn!("`strip` not found; [build.optimize] skips symbol stripping");
+    }
+
+    let upx = if config.upx {
+        let upx = config
+            .upx_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("upx"));
+        if !tool_available(Command::new(&upx).arg("--version")) {
+            return Err(PackError::Config(format!(
+                "UPX not found at '{}' (install UPX, set [build.optimize] upx_path, or upx = false)",
+                upx.display()
+            )));
+        }
+        tracing::warn!(
+            "UPX-compressed binaries are frequently flagged by antivirus scanners; \
+             sign the output and test with common AV products before shipping"
+        );
+        Some(upx)
+    } else {
+        None
+    };
+
+    let mut savings: Vec<BinarySavings> = assets
+        .par_iter_mut()
+        .enumerate()
+        .filter(|(_, (path, content))| {
+            content.len() as u64 >= config.min_size
+                && detect_format(content) != BinaryFormat::Unknown
+                && !exclude.iter().any(|p| p.matches(path))
+        })
+        .map(|(index, (path, content))| {
+            optimize_one(config, strip, upx.as_deref(), staging, index, path, content)
+        })
+        .collect::<PackResult<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    savings.sort_by_key(|s| std::cmp::Reverse(s.before - s.after));
+    for s in &savings {
+        tracing::info!(
+            "Optimized {} ({}): {:.2} MB -> {:.2} MB (-{:.1}%)",
+            s.path,
+            s.steps.join("+"),
+            s.before as f64 / (1024.0 * 1024.0),
+            s.after as f64 / (1024.0 * 1024.0),
+            (s.before - s.after) as f64 * 100.0 / s.before as f64
+        );
+    }
+    let saved: u64 = savings.iter().map(|s| s.before - s.after).sum();
+    tracing::info!(
+        "Binary optimization saved {:.2} MB across {} binaries",
+        saved as f64 / (1024.0 * 1024.0),
+        savings.len()
+    );
+
+    Ok(savings)
+}
+
+/// Optimize one binary; `None` when nothing made it smaller
+fn optimize_one(
+    config: &OptimizeConfig,
+    strip: bool,
+    upx: Option<&Path>,
+    staging: &Path,
+    index: usize,
+    path: &str,
+    content: &mut Vec<u8>,
+) -> PackResult<Option<BinarySavings>> {
+    let format = detect_format(content);
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let file = staging.join(format!("{}-{}", index, name));
+    fs::write(&file, &*content)?;
+
+    let mut steps = Vec::new();
+    if strip && strip_binary(format, &file) {
+        steps.push("strip");
+    }
+    // UPX does not support current macOS binaries
+    if let Some(upx) = upx.filter(|_| format != BinaryFormat::MachO) {
+        if run_upx(upx, &config.upx_args, &file) {
+            steps.push("upx");
+        }
+    }
+
+    let optimized = fs::read(&file)?;
+    fs::remove_file(&file)?;
+    if steps.is_empty() || optimized.len() >= content.len() {
+        return Ok(None);
+    }
+
+    let before = content.len() as u64;
+    *content = optimized;
+    Ok(Some(BinarySavings {
+        path: path.to_string(),
+        before,
+        after: content.len() as u64,
+        steps,
+    }))
+}
+
+/// Strip symbols from a host-native binary; failures leave it unchanged
+fn strip_binary(format: BinaryFormat, file: &Path) -> bool {
+    let mut command = Command::new("strip");
+    match format {
+        BinaryFormat::Elf if cfg!(target_os = "linux") => {
+            command.arg("--strip-unneeded");
+        }
+        BinaryFormat::MachO if cfg!(target_os = "macos") => {
+            command.arg("-x");
+        }
+        _ => return false,
+    }
+    if !run_quiet(command.arg(file)) {
+        tracing::debug!("strip skipped {}", file.display());
+        return false;
+    }
+
+    // Stripping invalidates Mach-O code signatures
+    if format == BinaryFormat::MachO
+        && !run_quiet(
+            Command::new("codesign")
+                .args(["--force", "--sign", "-"])
+                .arg(file),
+        )
+    {
+        tracing::warn!("Failed to re-sign {} after stripping", file.display());
+    }
+    true
+}
+
+/// Compress a binary with UPX; failures (already packed, CFG-protected
+/// DLLs, ...) leave it unchanged
+fn run_upx(upx: &Path, args: &[String], file: &Path) -> bool {
+    let ok = run_quiet(Command::new(upx).args(args).arg("-q").arg(file));
+    if !ok {
+        tracing::debug!("UPX skipped {}", file.display());
+    }
+    ok
+}
+
+/// Run a command without output; `true` on success
+fn run_quiet(command: &mut Command) -> bool {
+    command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Check that a tool can be started (its exit code does not matter)
+fn tool_available(command: &mut Command) -> bool {
+    command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_binaries_untouched() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = OptimizeConfig {
+            min_size: 0,
+            ..Default::default()
+        };
+        let mut assets = vec![
+            ("frontend/index.html".to_string(), b"<html></html>".to_vec()),
+            ("lib/data.bin".to_string(), vec![0u8; 1024]),
+        ];
+        let original = assets.clone();
+        let savings = optimize_assets(&config, &mut assets, temp.path()).unwrap();
+        assert!(savings.is_empty());
+        assert_eq!(assets, original);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_strip_elf_binary() {
+        if !tool_available(Command::new("strip").arg("--version")) {
+            return;
+        }
+        // Test binaries carry debug info
+        let exe = fs::read(std::env::current_exe().unwrap()).unwrap();
+        let temp = tempfile::tempdir().unwrap();
+        let mut assets = vec![("python/bin/tool".to_string(), exe.clone())];
+
+        let excluded = OptimizeConfig {
+            exclude: vec!["python/bin/*".to_string()],
+            ..Default::default()
+        };
+        assert!(optimize_assets(&excluded, &mut assets, temp.path())
+            .unwrap()
+            .is_empty());
+
+        let savings =
+            optimize_assets(&OptimizeConfig::default(), &mut assets, temp.path()).unwrap();
+        assert_eq!(savings.len(), 1);
+        assert_eq!(savings[0].steps, vec!["strip"]);
+        assert_eq!(savings[0].before, exe.len() as u64);
+        assert!(assets[0].1.len() < exe.len());
+        assert_eq!(detect_format(&assets[0].1), BinaryFormat::Elf);
+    }
+}
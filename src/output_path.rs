@@ -0,0 +1,237 @@
+//! Output name and path validation
+//!
+//! The output name ends up in the executable file name, the onedir folder
+//! and the runtime extraction cache on users' machines. Names that are
+//! invalid on any desktop platform, and (for Windows targets) extraction
+//! paths that exceed the `MAX_PATH` limit, are rejected before packing
+//! instead of failing during extraction. [`sanitize_output_name`] (or `[build] sanitize_name`)
+//! turns an invalid name into a portable one.
+
+use crate::config::PackMode;
+use crate::{PackConfig, PackError, PackResult, TargetPlatform};
+use std::path::Path;
+
+/// Characters that are invalid in Windows file names
+const INVALID_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Device names reserved by Windows (with or without an extension)
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Maximum length of a single path component on common file systems
+const MAX_COMPONENT_LEN: usize = 255;
+
+/// Windows `MAX_PATH` (without long path support, which users rarely enable)
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// Headroom for longer user profile paths on target machines than on the
+/// packing machine (the extraction cache lives below the user profile)
+const PROFILE_HEADROOM: usize = 32;
+
+/// Find the first problem with an output name, if any
+fn output_name_problem(name: &str) -> Option<String> {
+    if name.trim().is_empty() {
+        return Some("output name is empty".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| INVALID_CHARS.contains(c) || c.is_control())
+    {
+        return Some(format!("output name contains invalid character {:?}", c));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Some("output name must not end with a dot or space".to_string());
+    }
+    if name.starts_with(' ') {
+        return Some("output name must not start with a space".to_string());
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        return Some(format!("output name uses reserved device name '{}'", stem));
+    }
+    // Leave room for the ".exe" extension
+    if name.len() + 4 > MAX_COMPONENT_LEN {
+        return Some(format!(
+            "output name is {} bytes long (max {})",
+            name.len(),
+            MAX_COMPONENT_LEN - 4
+        ));
+    }
+    None
+}
+
+/// Turn an arbitrary name into a valid, portable output name
+///
+/// Invalid and control characters become `-`, leading/trailing dots and
+/// spaces are trimmed, reserved device names get a `_` suffix and overlong
+/// names are truncated. Returns `"app"` if nothing usable remains.
+pub fn sanitize_output_name(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| {
+            if INVALID_CHARS.contains(&c) || c.is_control() {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let mut sanitized = replaced
+        .trim_matches(|c: char| c == '.' || c.is_whitespace())
+        .to_string();
+
+    let limit = MAX_COMPONENT_LEN - 4;
+    if sanitized.len() > limit {
+        let mut end = limit;
+        while !sanitized.is_char_boundary(end) {
+            end -= 1;
+        }
+        sanitized.truncate(end);
+        sanitized = sanitized.trim_end_matches(['.', ' ']).to_string();
+    }
+
+    if sanitized.is_empty() {
+        return "app".to_string();
+    }
+
+    let stem = sanitized.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        sanitized.insert(stem.len(), '_');
+    }
+
+    sanitized
+}
+
+/// Validate an output name against platform file name constraints
+///
+/// The error suggests the sanitized name.
+pub fn validate_output_name(name: &str) -> PackResult<()> {
+    match output_name_problem(name) {
+        None => Ok(()),
+        Some(problem) => Err(PackError::Config(format!(
+            "Invalid output name '{}': {} (use '{}' or set [build] sanitize_name = true)",
+            name,
+            problem,
+            sanitize_output_name(name)
+        ))),
+    }
+}
+
+/// Validate the output name and the paths generated from it
+///
+/// For Windows targets, checks the output executable, the onedir layout
+/// and the runtime extraction cache (including the deepest bundled Python
+/// file) against the path length limit, so packs built on Linux/macOS for
+/// Windows users fail here too.
+pub(crate) fn validate_output_paths(config: &PackConfig) -> PackResult<()> {
+    validate_output_name(&config.output_name)?;
+    if !targets_windows(config) {
+        return Ok(());
+    }
+
+    let exe_name = format!("{}.exe", config.output_name);
+    check_path_length("Output executable", &config.output_dir.join(&exe_name), 0)?;
+
+    if let PackMode::FullStack { python, .. } = &config.mode {
+        let deepest = python
+            .include_paths
+            .iter()
+            .map(|p| deepest_relative_path(p))
+            .max()
+            .unwrap_or_default();
+
+        // Onedir layout: <output_dir>/<name>/<name>.exe + python/<files>
+        let onedir = config.output_dir.join(&config.output_name);
+        check_path_length("Onedir output", &onedir.join("python"), deepest + 1)?;
+
+        // Extraction cache on the user's machine
+        let cache = config.identity().runtime_cache_dir().join("python");
+        check_path_length(
+            "Runtime extraction path",
+            &cache,
+            deepest + 1 + PROFILE_HEADROOM,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Whether the packed executable runs on Windows
+fn targets_windows(config: &PackConfig) -> bool {
+    let platform = match config.build_target {
+        Some(target) => target.platform,
+        None => config.target_platform,
+    };
+    match platform {
+        TargetPlatform::Current => cfg!(target_os = "windows"),
+        platform => platform == TargetPlatform::Windows,
+    }
+}
+
+/// Check that `base` plus `extra` characters fits within `MAX_PATH`
+fn check_path_length(what: &str, base: &Path, extra: usize) -> PackResult<()> {
+    let len = base.as_os_str().len() + extra;
+    if len >= WINDOWS_MAX_PATH {
+        return Err(PackError::Config(format!(
+            "{} would reach {} characters below {} (Windows limit is {}); \
+             use a shorter output name, output directory or bundled file paths",
+            what,
+            len,
+            base.display(),
+            WINDOWS_MAX_PATH - 1
+        )));
+    }
+    Ok(())
+}
+
+/// Length of the longest file path relative to `root` (include paths are
+/// bundled by their contents)
+fn deepest_relative_path(root: &Path) -> usize {
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            e.path()
+                .strip_prefix(root)
+                .ok()
+                .map(|p| p.as_os_str().len())
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_output_name() {
+        assert_eq!(sanitize_output_name("My App: v2?"), "My App- v2-");
+        assert_eq!(sanitize_output_name("  .hidden. "), "hidden");
+        assert_eq!(sanitize_output_name("con"), "con_");
+        assert_eq!(sanitize_output_name("nul.tar"), "nul_.tar");
+        assert_eq!(sanitize_output_name("///"), "---");
+        assert_eq!(sanitize_output_name(""), "app");
+        assert_eq!(sanitize_output_name(&"x".repeat(300)).len(), 251);
+    }
+
+    #[test]
+    fn test_validate_output_name() {
+        assert!(validate_output_name("my-app").is_ok());
+        assert!(validate_output_name("My App 2.0").is_ok());
+        assert!(validate_output_name("console").is_ok());
+
+        let err = validate_output_name("a/b").unwrap_err().to_string();
+        assert!(err.contains("'a-b'"));
+        assert!(validate_output_name("LPT1").is_err());
+        assert!(validate_output_name("app.").is_err());
+        assert!(validate_output_name("tab\there").is_err());
+
+        // Sanitized names are always valid
+        for name in ["a/b", "LPT1", "app.", " x ", "", "COM3.txt"] {
+            assert!(validate_output_name(&sanitize_output_name(name)).is_ok());
+        }
+    }
+}
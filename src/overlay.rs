@@ -11,14 +11,38 @@
 //!   - Magic: "AVPK" (4 bytes)
 //!   - Version: u32 LE (4 bytes)
 //!   - Config Length: u64 LE (8 bytes)
-//!   - Assets Length: u64 LE (8 bytes)
+//!   - Index Length: u64 LE (8 bytes)
 //! [Config Data] (JSON, zstd compressed)
-//! [Assets Data] (tar archive, zstd compressed)
+//! [Asset Index] (JSON, zstd compressed)
+//!   - Per asset: path, offset, compressed length, size, BLAKE3 hash, codec
+//! [Asset Data] (each asset compressed on its own, zstd unless the index
+//!   entry names another codec)
 //! [Footer]
 //!   - Overlay Start Offset: u64 LE (8 bytes)
 //!   - Magic: "AVPK" (4 bytes)
 //! ```
 //!
+//! The index lets [`OverlayReader::open`] seek to and decompress a single
+//! asset without touching the others. Assets with identical content (empty
+//! `__init__.py` files, DLLs shipped by several packages) share one stored
+//! copy: their index entries point at the same offset. Version 2 overlays
+//! (zstd only, no codec in the index) read as is. Version 1 overlays (one
+//! tar archive of all assets, zstd compressed, in place of index and data)
+//! are still read; their assets are loaded all at once, and [`migrate`]
+//! upgrades them in place.
+//!
+//! [`OverlayWriter::begin`] writes the same format incrementally, for assets
+//! too large to hold in memory (e.g., the Python runtime archive).
+//! [`OverlayWriter::patch`] rewrites only the assets of an existing overlay.
+//!
+//! ## Sidecar
+//!
+//! With [`OverlayPlacement::Sidecar`] the same overlay (starting at offset 0)
+//! is written to `<name>.avpk` next to the executable instead of being
+//! appended to it, for antivirus scanners and code signing pipelines that
+//! reject large self-appended executables. [`OverlayReader`] falls back to
+//! the sidecar when the executable has no embedded overlay.
+//!
 //! ## Content Hash
 //!
 //! The overlay includes a content hash (BLAKE3) computed from all assets.
@@ -27,27 +51,73 @@
 //! - Conflict avoidance: Different content → different hash → new directory
 //! - Multi-version support: Multiple versions can coexist
 
+use crate::build_cache::BuildCache;
+use crate::codec::{Codec, CodecConfig};
+use crate::hashing::{digest_file, Digester, Digests};
+use crate::integrity::{IntegrityEntry, IntegrityManifest, INTEGRITY_MANIFEST_PATH};
 use crate::metrics::PackedMetrics;
+use crate::mmap::MappedFile;
+use crate::parallel::WorkerPool;
+use crate::progress::ProgressTracker;
+use crate::signing::{OverlaySignature, OverlaySigner, TRUSTED_OVERLAY_KEY};
 use crate::{PackConfig, PackError, PackResult};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
-use std::path::Path;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 /// Magic bytes for overlay identification
 pub const OVERLAY_MAGIC: &[u8; 4] = b"AVPK";
 
 /// Current overlay format version
-pub const OVERLAY_VERSION: u32 = 1;
+pub const OVERLAY_VERSION: u32 = 3;
+
+/// Overlay format with a single tar.zstd asset blob
+const OVERLAY_VERSION_V1: u32 = 1;
+
+/// Overlay format versions this crate reads
+pub const SUPPORTED_OVERLAY_VERSIONS: RangeInclusive<u32> = OVERLAY_VERSION_V1..=OVERLAY_VERSION;
+
+/// Release of this crate, recorded in the overlays it writes
+pub const PACKER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Oldest release of this crate whose reader (the shell) understands the
+/// overlays this release writes
+///
+/// Raise it whenever the config changes in a way older readers would
+/// misread; they then refuse the overlay with a precise error.
+pub const MIN_READER_VERSION: &str = "0.1.0";
 
 /// Footer size in bytes (offset: 8 + magic: 4)
 const FOOTER_SIZE: u64 = 12;
 
-/// Header size in bytes (magic: 4 + version: 4 + config_len: 8 + assets_len: 8)
-#[allow(dead_code)]
+/// Header size in bytes (magic: 4 + version: 4 + config_len: 8 + index_len: 8)
 const HEADER_SIZE: u64 = 24;
 
+/// Extension of sidecar overlay files
+pub const SIDECAR_EXTENSION: &str = "avpk";
+
+/// Where the packer puts the overlay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayPlacement {
+    /// Appended to the executable
+    #[default]
+    Embedded,
+    /// In `<name>.avpk` next to the executable
+    Sidecar,
+}
+
+/// Sidecar overlay file of an executable (`myapp.exe` -> `myapp.avpk`)
+pub fn sidecar_path(exe_path: &Path) -> PathBuf {
+    exe_path.with_extension(SIDECAR_EXTENSION)
+}
+
 /// Overlay data containing configuration and assets
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OverlayData {
@@ -56,9 +126,18 @@ pub struct OverlayData {
     /// Content hash (BLAKE3) of all assets - used as cache key
     /// Format: 16 hex chars (first 64 bits of BLAKE3 hash)
     pub content_hash: String,
+    /// Free-form build metadata (build ID, git commit, channel, CI run)
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
     /// Embedded assets (file path -> content)
     #[serde(skip)]
     pub assets: Vec<(String, Vec<u8>)>,
+    /// Unix attributes of assets that are not plain files (path -> attributes)
+    #[serde(skip)]
+    pub attributes: HashMap<String, AssetAttributes>,
+    /// How the config matched this release's schema (set when read)
+    #[serde(skip)]
+    pub schema: SchemaReport,
 }
 
 impl OverlayData {
@@ -67,8 +146,70 @@ impl OverlayData {
         Self {
             config,
             content_hash: String::new(),
+            metadata: BTreeMap::new(),
             assets: Vec::new(),
+            attributes: HashMap::new(),
+            schema: SchemaReport::default(),
+        }
+    }
+
+    /// Add a file from disk, keeping its executable bits and symlinks
+    ///
+    /// Symlinks are stored as links when their target is relative and stays
+    /// inside the overlay; other symlinks are followed.
+    pub fn add_file_asset(&mut self, path: impl Into<String>, file: &Path) -> PackResult<()> {
+        let path = path.into();
+        if let Some(target) = link_target(&path, file) {
+            return self.add_symlink(path, target);
+        }
+        let attributes = AssetAttributes::of_file(file)?;
+        self.add_asset(path.clone(), std::fs::read(file)?);
+        self.set_attributes(&path, attributes)
+    }
+
+    /// Add a symlink to `target` (relative to the link's directory)
+    pub fn add_symlink(
+        &mut self,
+        path: impl Into<String>,
+        target: impl Into<String>,
+    ) -> PackResult<()> {
+        let (path, target) = (path.into(), target.into());
+        validate_symlink_target(&path, &target)?;
+        self.add_asset(path.clone(), target.into_bytes());
+        self.attributes.insert(path, AssetAttributes::symlink());
+        Ok(())
+    }
+
+    /// Set the permission bits of an asset (e.g., `0o755` for executables)
+    pub fn set_mode(&mut self, path: &str, mode: u32) -> PackResult<()> {
+        self.set_attributes(path, AssetAttributes::with_mode(mode))
+    }
+
+    fn set_attributes(&mut self, path: &str, attributes: AssetAttributes) -> PackResult<()> {
+        if !self.assets.iter().any(|(p, _)| p == path) {
+            return Err(PackError::Config(format!(
+                "No asset {} in the overlay",
+                path
+            )));
         }
+        if attributes.is_default() {
+            self.attributes.remove(path);
+        } else {
+            self.attributes.insert(path.to_string(), attributes);
+        }
+        Ok(())
+    }
+
+    /// Set a metadata entry, stored in the overlay config section
+    ///
+    /// Replaces any previous value of `key`.
+    pub fn set_metadata(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.metadata.insert(key.into(), value.into());
+    }
+
+    /// Value of a metadata entry
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
     }
 
     /// Add an asset to the overlay
@@ -95,6 +236,9 @@ impl OverlayData {
             hasher.update(&(content.len() as u64).to_le_bytes());
             // Hash the content
             hasher.update(content);
+            if let Some(attributes) = self.attributes.get(path.as_str()) {
+                hasher.update(&attributes.digest_bytes());
+            }
         }
 
         // Use first 64 bits (16 hex chars) for shorter, still-unique cache keys
@@ -115,6 +259,28 @@ impl OverlayData {
         }
         self.content_hash.clone()
     }
+
+    /// Assets whose content is stored once for several paths when written
+    pub fn dedup_stats(&self) -> DedupStats {
+        let mut seen = HashSet::new();
+        let mut stats = DedupStats::default();
+        for (_, content) in &self.assets {
+            if !seen.insert(blake3::hash(content)) {
+                stats.duplicate_assets += 1;
+                stats.saved_bytes += content.len() as u64;
+            }
+        }
+        stats
+    }
+}
+
+/// Space saved by storing identical asset contents once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupStats {
+    /// Assets that share the stored data of an identical earlier asset
+    pub duplicate_assets: usize,
+    /// Uncompressed bytes not stored again
+    pub saved_bytes: u64,
 }
 
 /// Metadata stored in the overlay (config + content hash)
@@ -128,6 +294,374 @@ struct OverlayMetadata {
     config: PackConfig,
     /// Content hash (BLAKE3) of all assets
     content_hash: String,
+    /// Free-form build metadata
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
+    /// Signature of the metadata and asset index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<OverlaySignature>,
+    /// Release of this crate that wrote the overlay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    packer_version: Option<String>,
+    /// Oldest reader release able to read the overlay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_reader_version: Option<String>,
+    /// How the config matched this release's schema (set when read)
+    #[serde(skip)]
+    schema: SchemaReport,
+}
+
+/// Version fields of the config section, read without the config itself
+#[derive(Debug, Default, Deserialize)]
+struct VersionFields {
+    #[serde(default)]
+    packer_version: Option<String>,
+    #[serde(default)]
+    min_reader_version: Option<String>,
+}
+
+/// Versions an overlay was written with (see [`OverlayReader::version`])
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayVersion {
+    /// Overlay format version
+    pub format: u32,
+    /// Release of this crate that wrote the overlay (`None` for overlays
+    /// written before releases were recorded, or unreadable configs)
+    pub packer_version: Option<String>,
+    /// Oldest reader release able to read the overlay
+    pub min_reader_version: Option<String>,
+}
+
+/// Whether a reader can read an overlay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    /// The reader supports the format and is recent enough
+    Compatible,
+    /// The format is newer (or older) than the reader supports
+    UnsupportedFormat {
+        /// Overlay format version
+        format: u32,
+    },
+    /// The overlay needs a newer reader
+    ReaderTooOld {
+        /// Oldest reader release able to read the overlay
+        required: String,
+    },
+}
+
+impl OverlayVersion {
+    /// Compatibility with this crate's reader
+    pub fn compatibility(&self) -> Compatibility {
+        self.compatibility_with(PACKER_VERSION, SUPPORTED_OVERLAY_VERSIONS)
+    }
+
+    /// Compatibility with a reader of release `reader_version` supporting
+    /// the format versions `formats`
+    pub fn compatibility_with(
+        &self,
+        reader_version: &str,
+        formats: RangeInclusive<u32>,
+    ) -> Compatibility {
+        if !formats.contains(&self.format) {
+            return Compatibility::UnsupportedFormat {
+                format: self.format,
+            };
+        }
+        match self.min_reader_version {
+            Some(ref required)
+                if crate::compare_versions(reader_version, required)
+                    == Some(std::cmp::Ordering::Less) =>
+            {
+                Compatibility::ReaderTooOld {
+                    required: required.clone(),
+                }
+            }
+            _ => Compatibility::Compatible,
+        }
+    }
+
+    /// Fail with [`PackError::IncompatibleOverlay`] unless this crate's
+    /// reader can read the overlay
+    pub fn check(&self) -> PackResult<()> {
+        let built_with = match self.packer_version {
+            Some(ref version) => format!("auroraview-pack {}", version),
+            None => "an unknown auroraview-pack release".to_string(),
+        };
+        let message = match self.compatibility() {
+            Compatibility::Compatible => return Ok(()),
+            Compatibility::UnsupportedFormat { format } => format!(
+                "overlay format {} (built with {}) is not supported by this shell \
+                 (auroraview-pack {} reads formats {} to {})",
+                format,
+                built_with,
+                PACKER_VERSION,
+                SUPPORTED_OVERLAY_VERSIONS.start(),
+                SUPPORTED_OVERLAY_VERSIONS.end()
+            ),
+            Compatibility::ReaderTooOld { required } => format!(
+                "built with {}, needs a shell built with auroraview-pack >= {} \
+                 (this shell: {})",
+                built_with, required, PACKER_VERSION
+            ),
+        };
+        Err(PackError::IncompatibleOverlay(message))
+    }
+}
+
+/// How an overlay's config matched the schema of this release
+///
+/// A config written by a newer release may carry fields this release does
+/// not know: they are kept in [`PackConfig::unknown_fields`] rather than
+/// dropped. A config written by an older release lacks the fields added
+/// since: they take this release's defaults, which may not be what the
+/// older release meant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaReport {
+    /// Versions the overlay was written with (`None` until read)
+    pub version: Option<OverlayVersion>,
+    /// Top-level config fields this release does not know
+    pub unknown_fields: Vec<String>,
+    /// Top-level config fields missing from the overlay, set to this
+    /// release's (non-null) defaults
+    pub defaulted_fields: Vec<String>,
+}
+
+impl SchemaReport {
+    /// Compare a config as stored (`raw`) with the config read from it
+    fn new(version: OverlayVersion, raw: &serde_json::Value, config: &PackConfig) -> Self {
+        let stored = |key: &String| raw.get(key).is_some();
+        let defaulted_fields = match serde_json::to_value(config) {
+            Ok(serde_json::Value::Object(read)) => read
+                .into_iter()
+                .filter(|(key, value)| !value.is_null() && !stored(key))
+                .map(|(key, _)| key)
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            version: Some(version),
+            unknown_fields: config.unknown_fields.keys().cloned().collect(),
+            defaulted_fields,
+        }
+    }
+
+    /// The config matched this release's schema field for field
+    pub fn is_exact(&self) -> bool {
+        self.unknown_fields.is_empty() && self.defaulted_fields.is_empty()
+    }
+
+    /// Describe the mismatches (`None` if exact)
+    pub fn describe(&self) -> Option<String> {
+        if self.is_exact() {
+            return None;
+        }
+        let built_with = self
+            .version
+            .as_ref()
+            .and_then(|v| v.packer_version.as_deref())
+            .unwrap_or("an unknown release");
+        let mut parts = Vec::new();
+        if !self.unknown_fields.is_empty() {
+            parts.push(format!(
+                "unknown fields kept as read: {}",
+                self.unknown_fields.join(", ")
+            ));
+        }
+        if !self.defaulted_fields.is_empty() {
+            parts.push(format!(
+                "missing fields set to defaults: {}",
+                self.defaulted_fields.join(", ")
+            ));
+        }
+        Some(format!(
+            "Overlay config built with auroraview-pack {} read by {} ({})",
+            built_with,
+            PACKER_VERSION,
+            parts.join("; ")
+        ))
+    }
+}
+
+impl fmt::Display for OverlayVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "format {}", self.format)?;
+        if let Some(ref version) = self.packer_version {
+            write!(f, ", auroraview-pack {}", version)?;
+        }
+        if let Some(ref version) = self.min_reader_version {
+            write!(f, ", needs reader >= {}", version)?;
+        }
+        Ok(())
+    }
+}
+
+/// Index entry of one asset in a v2 overlay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetIndexEntry {
+    /// Asset path
+    pub path: String,
+    /// Offset of the compressed asset from the start of the asset data
+    pub offset: u64,
+    /// Compressed length in bytes
+    pub length: u64,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    /// BLAKE3 hash of the uncompressed content (hex)
+    pub hash: String,
+    /// Codec of the stored data
+    #[serde(default, skip_serializing_if = "Codec::is_zstd")]
+    pub codec: Codec,
+    /// Permission bits and symlink flag
+    #[serde(flatten)]
+    pub attributes: AssetAttributes,
+}
+
+/// Unix file attributes of an asset
+///
+/// Restored by [`OverlayArchive::extract_to`]. Assets without attributes
+/// are extracted as regular files with default permissions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetAttributes {
+    /// Permission bits (e.g., `0o755`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+    /// The asset is a symlink; its content is the relative link target
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub symlink: bool,
+}
+
+impl AssetAttributes {
+    /// Attributes of a symlink
+    pub fn symlink() -> Self {
+        Self {
+            mode: None,
+            symlink: true,
+        }
+    }
+
+    /// Attributes with permission bits
+    pub fn with_mode(mode: u32) -> Self {
+        Self {
+            mode: Some(mode & 0o7777),
+            symlink: false,
+        }
+    }
+
+    /// Whether these are the attributes of a plain file
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Bytes added to content hashes and signatures for non-default
+    /// attributes (none for plain files, so their hashes are unchanged)
+    pub(crate) fn digest_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if let Some(mode) = self.mode {
+            bytes.extend_from_slice(b"\0mode");
+            bytes.extend_from_slice(&mode.to_le_bytes());
+        }
+        if self.symlink {
+            bytes.extend_from_slice(b"\0symlink");
+        }
+        bytes
+    }
+
+    /// Attributes of a regular file on disk
+    ///
+    /// Only executable permission bits are recorded; other files extract
+    /// with the default permissions of the target system.
+    #[cfg(unix)]
+    fn of_file(file: &Path) -> std::io::Result<Self> {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(file)?.permissions().mode();
+        Ok(if mode & 0o111 != 0 {
+            Self::with_mode(mode)
+        } else {
+            Self::default()
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn of_file(_file: &Path) -> std::io::Result<Self> {
+        Ok(Self::default())
+    }
+}
+
+/// Target of a symlink that can be stored as a link
+///
+/// `None` for regular files and for links pointing outside the overlay,
+/// which are stored as copies of their targets.
+fn link_target(path: &str, file: &Path) -> Option<String> {
+    let target = std::fs::read_link(file)
+        .ok()?
+        .to_string_lossy()
+        .replace('\\', "/");
+    validate_symlink_target(path, &target).ok()?;
+    Some(target)
+}
+
+/// Check that a symlink target is relative and stays inside the overlay
+fn validate_symlink_target(path: &str, target: &str) -> PackResult<()> {
+    let depth = path.split('/').count() - 1;
+    let mut level = depth as isize;
+    let escapes = Path::new(target).has_root()
+        || target.contains(':')
+        || target.split(['/', '\\']).any(|part| {
+            match part {
+                "" | "." => {}
+                ".." => level -= 1,
+                _ => level += 1,
+            }
+            level < 0
+        });
+    if escapes {
+        return Err(PackError::InvalidOverlay(format!(
+            "Symlink {} -> {} points outside the overlay",
+            path, target
+        )));
+    }
+    Ok(())
+}
+
+impl AssetIndexEntry {
+    /// End of the stored bytes, from the start of the asset data
+    fn end(&self) -> PackResult<u64> {
+        self.offset
+            .checked_add(self.length)
+            .ok_or_else(|| self.outside_overlay())
+    }
+
+    fn outside_overlay(&self) -> PackError {
+        PackError::InvalidOverlay(format!("Asset {} lies outside the overlay", self.path))
+    }
+
+    /// Decompress and verify this asset's stored bytes
+    fn decode(&self, compressed: &[u8]) -> PackResult<Vec<u8>> {
+        // The size is not trusted until the hash matches
+        let mut content = Vec::with_capacity(preallocation(self.size));
+        self.codec
+            .decoder(compressed)?
+            .take(self.size.saturating_add(1))
+            .read_to_end(&mut content)
+            .map_err(|e| PackError::Compression(e.to_string()))?;
+        if content.len() as u64 != self.size
+            || blake3::hash(&content).to_hex().as_str() != self.hash
+        {
+            return Err(PackError::InvalidOverlay(format!(
+                "Asset {} is corrupted (hash mismatch)",
+                self.path
+            )));
+        }
+        Ok(content)
+    }
+}
+
+/// Bytes to reserve for content of a claimed `size`
+///
+/// Sizes come from the overlay, so a corrupted one must not allocate
+/// gigabytes up front; larger content grows the buffer as it is read.
+fn preallocation(size: u64) -> usize {
+    size.min(64 * 1024 * 1024) as usize
 }
 
 /// Writer for appending overlay data to executables
@@ -153,234 +687,1226 @@ impl OverlayWriter {
     /// - 16-19: High compression (recommended for release)
     /// - 20-22: Ultra compression (very slow, marginal improvement)
     pub fn write_with_level(exe_path: &Path, data: &OverlayData, level: i32) -> PackResult<()> {
+        Self::write_inner(exe_path, data, level, None)
+    }
+
+    /// Write overlay data signed with an Ed25519 key
+    pub fn write_signed(
+        exe_path: &Path,
+        data: &OverlayData,
+        signer: &OverlaySigner,
+    ) -> PackResult<()> {
+        Self::write_inner(exe_path, data, data.config.compression_level, Some(signer))
+    }
+
+    fn write_inner(
+        exe_path: &Path,
+        data: &OverlayData,
+        level: i32,
+        signer: Option<&OverlaySigner>,
+    ) -> PackResult<()> {
         // Clone and compute hash if needed
         let mut data = data.clone();
         let content_hash = data.get_content_hash();
 
-        let file = File::options().append(true).open(exe_path)?;
-        let mut writer = BufWriter::new(file);
-
-        // Get the current end of file (where overlay starts)
-        let overlay_start = writer.seek(SeekFrom::End(0))?;
+        let mut stream = Self::begin(exe_path, level)?;
+        stream.signer = signer.cloned();
+        stream.metadata = data.metadata.clone();
+        stream.append_assets(&data.assets)?;
+        stream.apply_attributes(&data.attributes);
+        stream.finish_with_hash(&data.config, Some(content_hash))?;
+        Ok(())
+    }
 
-        // Clamp level to valid range (1-22)
-        let level = level.clamp(1, 22);
+    /// Replace, add and remove assets of a packed executable
+    ///
+    /// Only the asset section (of the executable or its sidecar) is
+    /// rewritten: `added` assets are compressed and replace existing assets
+    /// with the same path, `removed` paths are dropped, and all other assets
+    /// are copied as stored, without decompressing them. The configuration is kept. This turns a small
+    /// frontend fix into seconds of work instead of a full repack (Python
+    /// download, dependency collection).
+    ///
+    /// Returns the new content hash. Signed overlays must be re-signed with
+    /// [`OverlayWriter::patch_signed`].
+    pub fn patch(
+        exe_path: &Path,
+        added: Vec<(String, Vec<u8>)>,
+        removed: Vec<String>,
+    ) -> PackResult<String> {
+        Self::rewrite(exe_path, None, added, removed, None)
+    }
 
-        // Create a metadata object that includes the hash
-        let metadata = OverlayMetadata {
-            config: data.config.clone(),
-            content_hash: content_hash.clone(),
-        };
-        let metadata_json = serde_json::to_vec(&metadata)?;
+    /// Patch the assets of a packed executable and sign the result
+    pub fn patch_signed(
+        exe_path: &Path,
+        added: Vec<(String, Vec<u8>)>,
+        removed: Vec<String>,
+        signer: &OverlaySigner,
+    ) -> PackResult<String> {
+        Self::rewrite(exe_path, None, added, removed, Some(signer))
+    }
 
-        // Compress config with zstd (use level 3 for small metadata)
-        let config_compressed = zstd::encode_all(&metadata_json[..], 3)
-            .map_err(|e| PackError::Compression(e.to_string()))?;
+    /// Rewrite the overlay of a packed executable, copying the assets that
+    /// are kept as stored
+    ///
+    /// `config` replaces the packed configuration (see
+    /// [`repack`](crate::repack)); `None` keeps it.
+    pub(crate) fn rewrite(
+        exe_path: &Path,
+        config: Option<&PackConfig>,
+        added: Vec<(String, Vec<u8>)>,
+        removed: Vec<String>,
+        signer: Option<&OverlaySigner>,
+    ) -> PackResult<String> {
+        let started = Instant::now();
+        let exe_path = &OverlayReader::overlay_file(exe_path)?.ok_or_else(|| {
+            PackError::InvalidOverlay(format!("{} has no overlay", exe_path.display()))
+        })?;
+        let mut archive = OverlayReader::open(exe_path)?.ok_or_else(|| {
+            PackError::InvalidOverlay(format!("{} has no overlay", exe_path.display()))
+        })?;
+        if archive.signature.is_some() && signer.is_none() {
+            return Err(PackError::Signature(
+                "overlay is signed; patch it with OverlayWriter::patch_signed".to_string(),
+            ));
+        }
+        if let Some(path) = removed.iter().find(|p| archive.entry(p).is_none()) {
+            return Err(PackError::Config(format!(
+                "Cannot remove asset {}: not in the overlay",
+                path
+            )));
+        }
+        let original_size = OverlayReader::get_original_size(exe_path)?.unwrap_or_default();
+
+        let replaced: HashSet<&str> = added
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .chain(removed.iter().map(String::as_str))
+            .collect();
+        let kept: Vec<AssetIndexEntry> = archive
+            .entries()
+            .iter()
+            .filter(|e| !replaced.contains(e.path.as_str()))
+            .cloned()
+            .collect();
+
+        let mut stream = Self::begin(exe_path, archive.config.compression_level)?;
+        stream.signer = signer.cloned();
+        stream.metadata = archive.metadata.clone();
+        if archive.version == OVERLAY_VERSION_V1 {
+            // No stored per-asset data to copy; recompress
+            for entry in &kept {
+                let content = archive.read_asset(&entry.path)?.unwrap_or_default();
+                stream.append_asset(entry.path.clone(), &content)?;
+            }
+        } else {
+            for entry in &kept {
+                stream.append_stored(entry, &mut archive)?;
+            }
+        }
+        stream.append_assets(&added)?;
 
-        // Create tar archive for assets
-        let assets_tar = Self::create_assets_archive(&data.assets)?;
-        let uncompressed_size = assets_tar.len();
+        // The archive maps the executable; release it before replacing it
+        let config = config.unwrap_or(&archive.config).clone();
+        drop(archive);
+        let content_hash = replace_overlay(stream, original_size, |stream| stream.finish(&config))?;
 
-        // Compress assets with zstd at specified level
         tracing::info!(
-            "Compressing {:.2} MB of assets with zstd level {}...",
-            uncompressed_size as f64 / (1024.0 * 1024.0),
-            level
+            "Rewrote overlay of {}: {} added/replaced, {} removed, {} kept in {:.1}s",
+            exe_path.display(),
+            added.len(),
+            removed.len(),
+            kept.len(),
+            started.elapsed().as_secs_f64()
         );
-        let compress_start = std::time::Instant::now();
-        let assets_compressed = zstd::encode_all(&assets_tar[..], level)
-            .map_err(|e| PackError::Compression(e.to_string()))?;
-        let compress_time = compress_start.elapsed();
+        Ok(content_hash)
+    }
 
-        let compression_ratio = uncompressed_size as f64 / assets_compressed.len() as f64;
-        tracing::info!(
-            "Compression complete: {:.2} MB -> {:.2} MB ({:.1}x ratio) in {:.1}s",
-            uncompressed_size as f64 / (1024.0 * 1024.0),
-            assets_compressed.len() as f64 / (1024.0 * 1024.0),
-            compression_ratio,
-            compress_time.as_secs_f64()
-        );
+    /// Start writing an overlay incrementally
+    ///
+    /// Assets are compressed one at a time into a spool file next to the
+    /// executable, so memory stays bounded by the zstd buffers regardless of
+    /// asset size. The executable is not touched until
+    /// [`OverlayStreamWriter::finish`], which appends the same format as
+    /// [`OverlayWriter::write`].
+    pub fn begin(exe_path: &Path, level: i32) -> PackResult<OverlayStreamWriter> {
+        let dir = exe_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let spool = tempfile::tempfile_in(dir)?;
+
+        Ok(OverlayStreamWriter {
+            exe_path: exe_path.to_path_buf(),
+            // Clamp level to valid range (1-22)
+            level: level.clamp(1, 22),
+            spool: BufWriter::new(spool),
+            index: Vec::new(),
+            data_len: 0,
+            started: Instant::now(),
+            signer: None,
+            metadata: BTreeMap::new(),
+            codecs: CodecConfig::default(),
+            stored: HashMap::new(),
+            sha256: HashMap::new(),
+            dedup: DedupStats::default(),
+            build_cache: None,
+            pool: WorkerPool::default(),
+            temp_dir: dir.to_path_buf(),
+            progress: None,
+        })
+    }
+}
 
-        // Write header
-        writer.write_all(OVERLAY_MAGIC)?;
-        writer.write_all(&OVERLAY_VERSION.to_le_bytes())?;
-        writer.write_all(&(config_compressed.len() as u64).to_le_bytes())?;
-        writer.write_all(&(assets_compressed.len() as u64).to_le_bytes())?;
+/// Overlay being written asset by asset, see [`OverlayWriter::begin`]
+pub struct OverlayStreamWriter {
+    exe_path: PathBuf,
+    level: i32,
+    /// Compressed asset data, in index order
+    spool: BufWriter<File>,
+    index: Vec<AssetIndexEntry>,
+    data_len: u64,
+    started: Instant,
+    signer: Option<OverlaySigner>,
+    metadata: BTreeMap<String, String>,
+    codecs: CodecConfig,
+    /// Location and codec of each stored content, by hash
+    stored: HashMap<String, (u64, u64, Codec)>,
+    /// SHA-256 of each content read by this writer, by hash (for the
+    /// integrity manifest)
+    sha256: HashMap<String, String>,
+    dedup: DedupStats,
+    build_cache: Option<BuildCache>,
+    pool: WorkerPool,
+    /// Directory of the spool, for per-asset temporary files
+    temp_dir: PathBuf,
+    /// Receives an event per compressed asset
+    progress: Option<ProgressTracker>,
+}
 
-        // Write data
-        writer.write_all(&config_compressed)?;
-        writer.write_all(&assets_compressed)?;
+/// File asset prepared by [`OverlayStreamWriter::append_assets_from_files`]
+enum PreparedFile {
+    Link(String),
+    File {
+        hash: String,
+        sha256: String,
+        size: u64,
+        attributes: AssetAttributes,
+    },
+}
 
-        // Write footer
-        writer.write_all(&overlay_start.to_le_bytes())?;
-        writer.write_all(OVERLAY_MAGIC)?;
+/// Compressed data of a file asset
+enum CompressedFile {
+    /// Build cache blob, and whether it was reused
+    Blob(File, bool),
+    /// Temporary file holding the data
+    Temp(File),
+}
 
-        writer.flush()?;
+impl OverlayStreamWriter {
+    /// Sign the overlay with an Ed25519 key
+    pub fn with_signer(mut self, signer: OverlaySigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
 
-        // Explicitly drop writer and sync to ensure file is fully written
-        // This is important on Windows before other tools (like rcedit) modify the file
-        let file = writer
-            .into_inner()
-            .map_err(|e| std::io::Error::other(e.to_string()))?;
-        file.sync_all()?;
-        drop(file);
+    /// Set a metadata entry (see [`OverlayData::set_metadata`])
+    pub fn set_metadata(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.metadata.insert(key.into(), value.into());
+    }
 
-        tracing::info!(
-            "Overlay written: config={} bytes, assets={} bytes, hash={}, title={}",
-            config_compressed.len(),
-            assets_compressed.len(),
-            content_hash,
-            data.config.window.title
-        );
+    /// Choose the codec of each asset by its class (default: zstd for all)
+    pub fn with_codecs(mut self, codecs: CodecConfig) -> Self {
+        self.codecs = codecs;
+        self
+    }
 
-        Ok(())
+    /// Read and compress assets with at most `threads` threads (default:
+    /// all cores)
+    pub fn with_threads(mut self, threads: usize) -> PackResult<Self> {
+        self.pool = WorkerPool::new(Some(threads))?;
+        Ok(self)
+    }
+
+    /// Reuse compressed assets from a build cache, and store new ones in it
+    ///
+    /// The cache records this overlay's assets when it is finished.
+    pub fn with_build_cache(mut self, cache: BuildCache) -> Self {
+        cache.reset_stats();
+        self.build_cache = Some(cache);
+        self
     }
 
-    /// Create a tar archive from assets
-    fn create_assets_archive(assets: &[(String, Vec<u8>)]) -> PackResult<Vec<u8>> {
-        let mut archive = tar::Builder::new(Vec::new());
+    /// Report each compressed asset to `progress`
+    pub(crate) fn with_progress(mut self, progress: ProgressTracker) -> Self {
+        self.progress = Some(progress);
+        self
+    }
 
-        for (path, content) in assets {
-            let mut header = tar::Header::new_gnu();
-            header.set_path(path)?;
-            header.set_size(content.len() as u64);
-            header.set_mode(0o644);
-            header.set_cksum();
-            archive.append(&header, &content[..])?;
+    fn report(&self, path: &str, size: u64) {
+        if let Some(ref progress) = self.progress {
+            progress.file_written(path, size);
         }
+    }
 
-        archive
-            .into_inner()
-            .map_err(|e| PackError::Bundle(e.to_string()))
+    /// Compress and append an in-memory asset
+    pub fn append_asset(&mut self, path: impl Into<String>, content: &[u8]) -> PackResult<()> {
+        self.append_asset_from_reader(path, content)?;
+        Ok(())
     }
-}
 
-/// Reader for extracting overlay data from executables
-pub struct OverlayReader;
+    /// Compress and append an asset read to its end from `reader`
+    ///
+    /// Returns the uncompressed size.
+    pub fn append_asset_from_reader(
+        &mut self,
+        path: impl Into<String>,
+        reader: impl Read,
+    ) -> PackResult<u64> {
+        let path = path.into();
+        let codec = self.codecs.codec_for(&path);
+        let mut reader = DigestingReader {
+            inner: reader,
+            digester: Digester::default(),
+        };
+        codec.encode(self.level, &mut reader, &mut self.spool)?;
+
+        let length = self.spool.stream_position()? - self.data_len;
+        let Digests {
+            blake3,
+            sha256,
+            size,
+        } = reader.digester.finalize();
+        let hash = blake3;
+        self.sha256.insert(hash.clone(), sha256);
+        self.report(&path, size);
+        if let Some(&stored) = self.stored.get(&hash) {
+            // Already stored: drop the copy just written
+            self.spool.flush()?;
+            self.spool.get_mut().set_len(self.data_len)?;
+            self.spool.seek(SeekFrom::Start(self.data_len))?;
+            self.push_duplicate(path, stored, size, hash);
+            return Ok(size);
+        }
 
-impl OverlayReader {
-    /// Check if a file has overlay data
-    pub fn has_overlay(path: &Path) -> PackResult<bool> {
-        let file = File::open(path)?;
-        let file_len = file.metadata()?.len();
+        self.stored
+            .insert(hash.clone(), (self.data_len, length, codec));
+        self.index.push(AssetIndexEntry {
+            path,
+            offset: self.data_len,
+            length,
+            size,
+            hash,
+            codec,
+            attributes: AssetAttributes::default(),
+        });
+        self.data_len += length;
+        Ok(size)
+    }
 
-        if file_len < FOOTER_SIZE {
-            return Ok(false);
+    /// Point an asset at data stored for an earlier, identical one
+    fn push_duplicate(
+        &mut self,
+        path: String,
+        (offset, length, codec): (u64, u64, Codec),
+        size: u64,
+        hash: String,
+    ) {
+        self.dedup.duplicate_assets += 1;
+        self.dedup.saved_bytes += size;
+        self.index.push(AssetIndexEntry {
+            path,
+            offset,
+            length,
+            size,
+            hash,
+            codec,
+            attributes: AssetAttributes::default(),
+        });
+    }
+
+    /// Copy an asset's stored (compressed) data from an opened v2 overlay
+    fn append_stored(
+        &mut self,
+        entry: &AssetIndexEntry,
+        archive: &mut OverlayArchive,
+    ) -> PackResult<()> {
+        if let Some(&stored) = self.stored.get(&entry.hash) {
+            self.push_duplicate(entry.path.clone(), stored, entry.size, entry.hash.clone());
+            self.apply_attributes(&HashMap::from([(entry.path.clone(), entry.attributes)]));
+            return Ok(());
+        }
+        let AssetSource::Indexed {
+            ref mut file,
+            data_start,
+            ..
+        } = archive.source
+        else {
+            return Err(PackError::InvalidOverlay(
+                "Overlay has no stored asset data".to_string(),
+            ));
+        };
+        file.seek(SeekFrom::Start(data_start + entry.offset))?;
+        let copied = std::io::copy(&mut (&mut *file).take(entry.length), &mut self.spool)?;
+        if copied != entry.length {
+            return Err(PackError::InvalidOverlay(format!(
+                "Asset {} lies outside the overlay",
+                entry.path
+            )));
         }
 
-        let mut reader = BufReader::new(file);
-        reader.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
+        self.stored.insert(
+            entry.hash.clone(),
+            (self.data_len, entry.length, entry.codec),
+        );
+        self.index.push(AssetIndexEntry {
+            offset: self.data_len,
+            ..entry.clone()
+        });
+        self.data_len += entry.length;
+        Ok(())
+    }
 
-        // Read footer
-        let mut offset_bytes = [0u8; 8];
-        let mut magic = [0u8; 4];
-        reader.read_exact(&mut offset_bytes)?;
-        reader.read_exact(&mut magic)?;
+    /// Assets stored once for several paths so far
+    pub fn dedup_stats(&self) -> DedupStats {
+        self.dedup
+    }
 
-        Ok(&magic == OVERLAY_MAGIC)
+    /// Integrity manifest of the assets appended so far
+    ///
+    /// The SHA-256 of each asset is computed in the same read as its index
+    /// hash, so no asset is read again. Symlinks and assets copied from
+    /// another overlay (whose content this writer never read) are left out.
+    pub fn integrity_manifest(&self) -> IntegrityManifest {
+        let files = self
+            .index
+            .iter()
+            .filter(|entry| !entry.attributes.symlink)
+            .filter_map(|entry| {
+                Some(IntegrityEntry {
+                    path: entry.path.clone(),
+                    sha256: self.sha256.get(&entry.hash)?.clone(),
+                    size: entry.size,
+                    blake3: Some(entry.hash.clone()),
+                })
+            })
+            .collect();
+        IntegrityManifest { files }
     }
 
-    /// Read overlay data from a file
-    pub fn read(path: &Path) -> PackResult<Option<OverlayData>> {
-        Self::read_with_metrics(path, None)
+    /// Append `manifest` as [`INTEGRITY_MANIFEST_PATH`]
+    ///
+    /// Not reported as progress: it is not one of the packed assets.
+    pub fn append_integrity_manifest(&mut self, manifest: &IntegrityManifest) -> PackResult<()> {
+        let json = serde_json::to_vec(manifest)?;
+        let progress = self.progress.take();
+        let result = self.append_asset(INTEGRITY_MANIFEST_PATH, &json);
+        self.progress = progress;
+        result
     }
 
-    /// Read overlay data from a file with performance metrics
-    pub fn read_with_metrics(
-        path: &Path,
-        mut metrics: Option<&mut PackedMetrics>,
-    ) -> PackResult<Option<OverlayData>> {
-        let file = File::open(path)?;
-        let file_len = file.metadata()?.len();
+    /// Compress and append the contents of a file
+    ///
+    /// Executable bits are kept, and symlinks inside the overlay are stored
+    /// as links (see [`OverlayData::add_file_asset`]). Returns the
+    /// uncompressed size.
+    pub fn append_asset_from_file(
+        &mut self,
+        path: impl Into<String>,
+        file: &Path,
+    ) -> PackResult<u64> {
+        let path = path.into();
+        if let Some(target) = link_target(&path, file) {
+            return self.append_symlink(path, &target);
+        }
+        let reader = File::open(file).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => PackError::AssetNotFound(file.to_path_buf()),
+            _ => e.into(),
+        })?;
+        let attributes = AssetAttributes::of_file(file)?;
+        let size = match self.build_cache.clone() {
+            Some(cache) => self.append_cached_file(path.clone(), file, &cache)?,
+            None => self.append_asset_from_reader(path.clone(), BufReader::new(reader))?,
+        };
+        self.apply_attributes(&HashMap::from([(path, attributes)]));
+        Ok(size)
+    }
 
-        if file_len < FOOTER_SIZE {
-            return Ok(None);
+    /// Append a file through the build cache, compressing it only if the
+    /// cache has no copy of its content
+    fn append_cached_file(
+        &mut self,
+        path: String,
+        file: &Path,
+        cache: &BuildCache,
+    ) -> PackResult<u64> {
+        let Digests {
+            blake3,
+            sha256,
+            size,
+        } = cache.digest_file(file)?;
+        let hash = blake3;
+        self.sha256.insert(hash.clone(), sha256);
+        if let Some(&stored) = self.stored.get(&hash) {
+            self.report(&path, size);
+            self.push_duplicate(path, stored, size, hash);
+            return Ok(size);
         }
+        let codec = self.codecs.codec_for(&path);
+        let mut blob = match cache.find(&hash, size, codec, self.level) {
+            Some(blob) => {
+                cache.note_reused(size);
+                blob
+            }
+            None => {
+                cache.note_compressed();
+                cache.compress_file(file, &hash, codec, self.level)?
+            }
+        };
+        let length = std::io::copy(&mut blob, &mut self.spool)?;
+        self.report(&path, size);
+
+        self.stored
+            .insert(hash.clone(), (self.data_len, length, codec));
+        self.index.push(AssetIndexEntry {
+            path,
+            offset: self.data_len,
+            length,
+            size,
+            hash,
+            codec,
+            attributes: AssetAttributes::default(),
+        });
+        self.data_len += length;
+        Ok(size)
+    }
 
-        let mut reader = BufReader::with_capacity(64 * 1024, file); // 64KB buffer
+    /// Append a symlink to `target` (relative to the link's directory)
+    ///
+    /// Returns the size of the stored target.
+    pub fn append_symlink(&mut self, path: impl Into<String>, target: &str) -> PackResult<u64> {
+        let path = path.into();
+        validate_symlink_target(&path, target)?;
+        let size = self.append_asset_from_reader(path.clone(), target.as_bytes())?;
+        self.apply_attributes(&HashMap::from([(path, AssetAttributes::symlink())]));
+        Ok(size)
+    }
 
-        // Read footer
-        reader.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
+    /// Set the attributes of appended assets (latest entry of each path)
+    fn apply_attributes(&mut self, attributes: &HashMap<String, AssetAttributes>) {
+        if attributes.is_empty() {
+            return;
+        }
+        for entry in self.index.iter_mut().rev() {
+            if let Some(&attrs) = attributes.get(&entry.path) {
+                entry.attributes = attrs;
+            }
+        }
+    }
+
+    /// Compress in-memory assets in parallel and append them in order
+    ///
+    /// Identical contents are compressed and stored once.
+    pub fn append_assets(&mut self, assets: &[(String, Vec<u8>)]) -> PackResult<()> {
+        let digests: Vec<Digests> = self
+            .pool
+            .install(|| assets.par_iter().map(|(_, c)| Digests::of(c)).collect());
+        let hashes: Vec<String> = digests
+            .into_iter()
+            .map(|d| {
+                self.sha256.insert(d.blake3.clone(), d.sha256);
+                d.blake3
+            })
+            .collect();
+
+        // Compress only the first occurrence of each content
+        let mut seen = HashSet::new();
+        let first: Vec<bool> = hashes
+            .iter()
+            .map(|hash| !self.stored.contains_key(hash) && seen.insert(hash))
+            .collect();
+        let compressed = self.pool.install(|| {
+            assets
+                .par_iter()
+                .zip(&hashes)
+                .zip(&first)
+                .map(|(((path, content), hash), &first)| {
+                    if !first {
+                        self.report(path, content.len() as u64);
+                        return Ok(None);
+                    }
+                    let codec = self.codecs.codec_for(path);
+                    let cached = self.build_cache.as_ref().and_then(|cache| {
+                        cache.load(hash, content.len() as u64, codec, self.level)
+                    });
+                    let compressed = match cached {
+                        Some(stored) => (codec, stored, true),
+                        None => (codec, codec.encode_all(self.level, content)?, false),
+                    };
+                    self.report(path, content.len() as u64);
+                    Ok(Some(compressed))
+                })
+                .collect::<PackResult<Vec<_>>>()
+        })?;
+
+        for (((path, content), hash), stored) in assets.iter().zip(hashes).zip(compressed) {
+            let size = content.len() as u64;
+            let Some((codec, stored, cached)) = stored else {
+                self.push_duplicate(path.clone(), self.stored[&hash], size, hash);
+                continue;
+            };
+            if let Some(ref cache) = self.build_cache {
+                if cached {
+                    cache.note_reused(size);
+                } else {
+                    cache.note_compressed();
+                    cache.store(&hash, codec, self.level, &stored);
+                }
+            }
+            let length = stored.len() as u64;
+            self.stored
+                .insert(hash.clone(), (self.data_len, length, codec));
+            self.index.push(AssetIndexEntry {
+                path: path.clone(),
+                offset: self.data_len,
+                length,
+                size,
+                hash,
+                codec,
+                attributes: AssetAttributes::default(),
+            });
+            self.spool.write_all(&stored)?;
+            self.data_len += length;
+        }
+        Ok(())
+    }
+
+    /// Compress files in parallel and append them in order
+    ///
+    /// Same result as [`Self::append_asset_from_file`] for each file, but
+    /// the files are hashed and compressed on the worker threads (into
+    /// temporary files, or through the build cache) before they are copied
+    /// into the overlay in input order. Identical contents are compressed
+    /// and stored once. Returns the total uncompressed size.
+    pub fn append_assets_from_files(&mut self, files: &[(&str, &Path)]) -> PackResult<u64> {
+        let prepared = self.pool.install(|| {
+            files
+                .par_iter()
+                .map(|&(path, file)| {
+                    if let Some(target) = link_target(path, file) {
+                        return Ok(PreparedFile::Link(target));
+                    }
+                    let attributes =
+                        AssetAttributes::of_file(file).map_err(|e| match e.kind() {
+                            std::io::ErrorKind::NotFound => {
+                                PackError::AssetNotFound(file.to_path_buf())
+                            }
+                            _ => e.into(),
+                        })?;
+                    let digests = match self.build_cache {
+                        Some(ref cache) => cache.digest_file(file)?,
+                        None => digest_file(file)?,
+                    };
+                    Ok(PreparedFile::File {
+                        hash: digests.blake3,
+                        sha256: digests.sha256,
+                        size: digests.size,
+                        attributes,
+                    })
+                })
+                .collect::<PackResult<Vec<_>>>()
+        })?;
+
+        for prepared in &prepared {
+            if let PreparedFile::File { hash, sha256, .. } = prepared {
+                self.sha256.insert(hash.clone(), sha256.clone());
+            }
+        }
+
+        // Compress only the first occurrence of each content
+        let mut seen = HashSet::new();
+        let first: Vec<bool> = prepared
+            .iter()
+            .map(|prepared| match prepared {
+                PreparedFile::File { hash, .. } => {
+                    !self.stored.contains_key(hash) && seen.insert(hash.as_str())
+                }
+                PreparedFile::Link(_) => false,
+            })
+            .collect();
+        let compressed = self.pool.install(|| {
+            files
+                .par_iter()
+                .zip(&prepared)
+                .zip(&first)
+                .map(|((&(path, file), prepared), &first)| {
+                    let PreparedFile::File { hash, size, .. } = prepared else {
+                        return Ok(None);
+                    };
+                    if !first {
+                        self.report(path, *size);
+                        return Ok(None);
+                    }
+                    let codec = self.codecs.codec_for(path);
+                    if let Some(ref cache) = self.build_cache {
+                        let blob = match cache.find(hash, *size, codec, self.level) {
+                            Some(blob) => CompressedFile::Blob(blob, true),
+                            None => CompressedFile::Blob(
+                                cache.compress_file(file, hash, codec, self.level)?,
+                                false,
+                            ),
+                        };
+                        self.report(path, *size);
+                        return Ok(Some((codec, blob)));
+                    }
+                    let mut temp = tempfile::tempfile_in(&self.temp_dir)?;
+                    {
+                        let mut writer = BufWriter::new(&mut temp);
+                        codec.encode(
+                            self.level,
+                            &mut BufReader::new(File::open(file)?),
+                            &mut writer,
+                        )?;
+                        writer.flush()?;
+                    }
+                    temp.seek(SeekFrom::Start(0))?;
+                    self.report(path, *size);
+                    Ok(Some((codec, CompressedFile::Temp(temp))))
+                })
+                .collect::<PackResult<Vec<_>>>()
+        })?;
+
+        let mut total = 0;
+        let mut attributes = HashMap::new();
+        for ((&(path, _), prepared), compressed) in files.iter().zip(prepared).zip(compressed) {
+            let (hash, size, attrs) = match prepared {
+                PreparedFile::Link(target) => {
+                    total += self.append_symlink(path, &target)?;
+                    continue;
+                }
+                PreparedFile::File {
+                    hash,
+                    size,
+                    attributes,
+                    ..
+                } => (hash, size, attributes),
+            };
+            total += size;
+            attributes.insert(path.to_string(), attrs);
+            let Some((codec, compressed)) = compressed else {
+                self.push_duplicate(path.to_string(), self.stored[&hash], size, hash);
+                continue;
+            };
+
+            let length = match compressed {
+                CompressedFile::Blob(mut blob, reused) => {
+                    if let Some(ref cache) = self.build_cache {
+                        if reused {
+                            cache.note_reused(size);
+                        } else {
+                            cache.note_compressed();
+                        }
+                    }
+                    std::io::copy(&mut blob, &mut self.spool)?
+                }
+                CompressedFile::Temp(mut temp) => std::io::copy(&mut temp, &mut self.spool)?,
+            };
+            self.stored
+                .insert(hash.clone(), (self.data_len, length, codec));
+            self.index.push(AssetIndexEntry {
+                path: path.to_string(),
+                offset: self.data_len,
+                length,
+                size,
+                hash,
+                codec,
+                attributes: AssetAttributes::default(),
+            });
+            self.data_len += length;
+        }
+        self.apply_attributes(&attributes);
+        Ok(total)
+    }
+
+    /// Append the overlay to the executable
+    ///
+    /// `config` is stored as the overlay configuration. Returns the content
+    /// hash, computed from the appended assets like
+    /// [`OverlayData::compute_content_hash`].
+    pub fn finish(self, config: &PackConfig) -> PackResult<String> {
+        self.finish_with_hash(config, None)
+    }
+
+    fn finish_with_hash(
+        self,
+        config: &PackConfig,
+        content_hash: Option<String>,
+    ) -> PackResult<String> {
+        let mut spool = self
+            .spool
+            .into_inner()
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+
+        let uncompressed_size: u64 = self.index.iter().map(|e| e.size).sum();
+        if self.dedup.duplicate_assets > 0 {
+            tracing::info!(
+                "Deduplicated {} assets ({:.2} MB stored once)",
+                self.dedup.duplicate_assets,
+                self.dedup.saved_bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+        tracing::info!(
+            "Compression complete: {:.2} MB -> {:.2} MB ({:.1}x ratio, zstd level {}) in {:.1}s",
+            uncompressed_size as f64 / (1024.0 * 1024.0),
+            self.data_len as f64 / (1024.0 * 1024.0),
+            uncompressed_size as f64 / self.data_len.max(1) as f64,
+            self.level,
+            self.started.elapsed().as_secs_f64()
+        );
+
+        let content_hash = match content_hash {
+            Some(hash) => hash,
+            None => Self::content_hash(&self.index, &mut spool)?,
+        };
+
+        // Create a metadata object that includes the hash; the signature
+        // covers its canonical JSON (sorted keys) without the signature
+        let mut metadata = serde_json::to_value(OverlayMetadata {
+            config: config.clone(),
+            content_hash: content_hash.clone(),
+            metadata: self.metadata,
+            signature: None,
+            packer_version: Some(PACKER_VERSION.to_string()),
+            min_reader_version: Some(MIN_READER_VERSION.to_string()),
+            schema: SchemaReport::default(),
+        })?;
+        if let Some(ref signer) = self.signer {
+            let signature = signer.sign(&serde_json::to_vec(&metadata)?, &self.index)?;
+            metadata["signature"] = serde_json::to_value(signature)?;
+            tracing::info!("Signed overlay with key {}", signer.public_key_base64());
+        }
+        let metadata_json = serde_json::to_vec(&metadata)?;
+
+        // Compress config with zstd (use level 3 for small metadata)
+        let config_compressed = zstd::encode_all(&metadata_json[..], 3)
+            .map_err(|e| PackError::Compression(e.to_string()))?;
+        let index_compressed = zstd::encode_all(&serde_json::to_vec(&self.index)?[..], 3)
+            .map_err(|e| PackError::Compression(e.to_string()))?;
+
+        let file = File::options().append(true).open(&self.exe_path)?;
+        let mut writer = BufWriter::new(file);
+
+        // Get the current end of file (where overlay starts)
+        let overlay_start = writer.seek(SeekFrom::End(0))?;
+
+        // Write header
+        writer.write_all(OVERLAY_MAGIC)?;
+        writer.write_all(&OVERLAY_VERSION.to_le_bytes())?;
+        writer.write_all(&(config_compressed.len() as u64).to_le_bytes())?;
+        writer.write_all(&(index_compressed.len() as u64).to_le_bytes())?;
+
+        // Write data
+        writer.write_all(&config_compressed)?;
+        writer.write_all(&index_compressed)?;
+        spool.seek(SeekFrom::Start(0))?;
+        let copied = std::io::copy(&mut spool, &mut writer)?;
+        if copied != self.data_len {
+            return Err(PackError::InvalidOverlay(format!(
+                "Spooled asset data is {} bytes, expected {}",
+                copied, self.data_len
+            )));
+        }
+
+        // Write footer
+        writer.write_all(&overlay_start.to_le_bytes())?;
+        writer.write_all(OVERLAY_MAGIC)?;
+
+        writer.flush()?;
+
+        // Explicitly drop writer and sync to ensure file is fully written
+        // This is important on Windows before other tools (like rcedit) modify the file
+        let file = writer
+            .into_inner()
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        file.sync_all()?;
+        drop(file);
+
+        tracing::info!(
+            "Overlay written: config={} bytes, index={} bytes, assets={} bytes, hash={}, title={}",
+            config_compressed.len(),
+            index_compressed.len(),
+            self.data_len,
+            content_hash,
+            config.window.title
+        );
+
+        if let Some(ref cache) = self.build_cache {
+            cache.commit(&self.index)?;
+        }
+        Ok(content_hash)
+    }
+
+    /// Content hash of the spooled assets
+    ///
+    /// Matches [`OverlayData::compute_content_hash`]: assets are hashed in
+    /// path order, decompressed from the spool one at a time.
+    fn content_hash(index: &[AssetIndexEntry], spool: &mut File) -> PackResult<String> {
+        let mut sorted: Vec<_> = index.iter().collect();
+        sorted.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut hasher = blake3::Hasher::new();
+        for entry in sorted {
+            hasher.update(entry.path.as_bytes());
+            hasher.update(&[0]);
+            hasher.update(&entry.size.to_le_bytes());
+
+            spool.seek(SeekFrom::Start(entry.offset))?;
+            let mut decoder = entry
+                .codec
+                .decoder(BufReader::new((&mut *spool).take(entry.length)))?;
+            std::io::copy(&mut decoder, &mut hasher)?;
+            hasher.update(&entry.attributes.digest_bytes());
+        }
+
+        let hash = hasher.finalize();
+        Ok(format!(
+            "{:016x}",
+            u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap())
+        ))
+    }
+}
+
+/// Upgrade the overlay of a packed executable (or its sidecar) to
+/// `target_version` in place
+///
+/// Apps packed by older versions of this crate can then be inspected,
+/// patched and signed by current tooling. The configuration, assets and
+/// content hash (the runtime cache key) are kept; only the layout changes.
+/// Only [`OVERLAY_VERSION`] can be targeted.
+///
+/// Returns the version the overlay had; nothing is written if it already
+/// was `target_version`.
+pub fn migrate(exe_path: &Path, target_version: u32) -> PackResult<u32> {
+    if target_version != OVERLAY_VERSION {
+        return Err(PackError::InvalidOverlay(format!(
+            "Cannot migrate to overlay version {} (only {} is written)",
+            target_version, OVERLAY_VERSION
+        )));
+    }
+    let exe_path = &OverlayReader::overlay_file(exe_path)?.ok_or_else(|| {
+        PackError::InvalidOverlay(format!("{} has no overlay", exe_path.display()))
+    })?;
+    let version = OverlayReader::detect_version(exe_path)?.ok_or_else(|| {
+        PackError::InvalidOverlay(format!("{} has no overlay", exe_path.display()))
+    })?;
+    if version == target_version {
+        return Ok(version);
+    }
+    if version > target_version {
+        return Err(PackError::InvalidOverlay(format!(
+            "Overlay version {} is newer than {}; update auroraview-pack",
+            version, target_version
+        )));
+    }
+
+    let mut data = OverlayReader::read(exe_path)?.ok_or_else(|| {
+        PackError::InvalidOverlay(format!("{} has no overlay", exe_path.display()))
+    })?;
+    let original_size = OverlayReader::get_original_size(exe_path)?.unwrap_or_default();
+    let content_hash = data.get_content_hash();
+
+    let mut stream = OverlayWriter::begin(exe_path, data.config.compression_level)?;
+    stream.metadata = std::mem::take(&mut data.metadata);
+    stream.append_assets(&data.assets)?;
+    stream.apply_attributes(&data.attributes);
+    replace_overlay(stream, original_size, |stream| {
+        stream.finish_with_hash(&data.config, Some(content_hash))
+    })?;
+
+    tracing::info!(
+        "Migrated overlay of {} from version {} to {}",
+        exe_path.display(),
+        version,
+        target_version
+    );
+    Ok(version)
+}
+
+/// Replace the overlay of the file `stream` was begun for with the one
+/// `finish` writes
+///
+/// The first `original_size` bytes of the file (the executable) are copied
+/// to a temporary sibling, the new overlay is appended to the copy, and the
+/// copy is renamed over the file; a failure at any step leaves the file and
+/// its old overlay untouched. Fails while an [`OverlayArchive`] (here or in a
+/// running app) maps the file.
+fn replace_overlay(
+    mut stream: OverlayStreamWriter,
+    original_size: u64,
+    finish: impl FnOnce(OverlayStreamWriter) -> PackResult<String>,
+) -> PackResult<String> {
+    let path = stream.exe_path.clone();
+    check_not_mapped(&path)?;
+    let staged = crate::cache_lock::temp_sibling(&path)?;
+    let copied = std::io::copy(
+        &mut File::open(&path)?.take(original_size),
+        &mut File::create(&staged)?,
+    )?;
+    if copied != original_size {
+        return Err(PackError::InvalidOverlay(format!(
+            "{} is shorter than the start of its overlay",
+            path.display()
+        )));
+    }
+
+    stream.exe_path = staged.to_path_buf();
+    let content_hash = finish(stream)?;
+    std::fs::set_permissions(&staged, std::fs::metadata(&path)?.permissions())?;
+    staged.persist(&path).map_err(|e| e.error)?;
+    Ok(content_hash)
+}
+
+/// Fail if an [`OverlayArchive`] maps `path` (see [`crate::mmap`])
+fn check_not_mapped(path: &Path) -> PackResult<()> {
+    let file = File::options().write(true).open(path)?;
+    match file.try_lock() {
+        Ok(()) => Ok(()),
+        Err(std::fs::TryLockError::WouldBlock) => Err(PackError::Build(format!(
+            "{} is in use by an open overlay archive or a running app",
+            path.display()
+        ))),
+        Err(std::fs::TryLockError::Error(e)) => Err(e.into()),
+    }
+}
+
+/// Reader computing the [`Digests`] of what passes through it
+struct DigestingReader<R> {
+    inner: R,
+    digester: Digester,
+}
+
+impl<R: Read> Read for DigestingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.digester.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Reader that hashes and counts what passes through it
+struct HashingReader<R> {
+    inner: R,
+    hasher: blake3::Hasher,
+    size: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            size: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.size += n as u64;
+        Ok(n)
+    }
+}
+
+/// Overlay header fields
+struct OverlayHeader {
+    /// Offset of the overlay in the file
+    start: u64,
+    /// Format version
+    version: u32,
+    /// Compressed config length
+    config_len: u64,
+    /// Compressed index length (v2) or assets length (v1)
+    section_len: u64,
+}
+
+impl OverlayHeader {
+    /// Offset of the section after the config
+    fn section_start(&self) -> u64 {
+        self.start + HEADER_SIZE + self.config_len
+    }
+
+    /// Offset of the v2 asset data
+    fn data_start(&self) -> u64 {
+        self.section_start() + self.section_len
+    }
+}
+
+/// Reader for extracting overlay data from executables
+pub struct OverlayReader;
+
+impl OverlayReader {
+    /// Check if a file has overlay data, embedded or in its sidecar
+    pub fn has_overlay(path: &Path) -> PackResult<bool> {
+        Ok(Self::overlay_file(path)?.is_some())
+    }
+
+    /// File holding a file's overlay
+    ///
+    /// The file itself if the overlay is embedded, else its sidecar
+    /// ([`sidecar_path`]) if that holds one, else `None`.
+    pub fn overlay_file(path: &Path) -> PackResult<Option<PathBuf>> {
+        if Self::has_embedded_overlay(path)? {
+            return Ok(Some(path.to_path_buf()));
+        }
+        let sidecar = sidecar_path(path);
+        if sidecar != path && sidecar.is_file() && Self::has_embedded_overlay(&sidecar)? {
+            tracing::debug!("Using sidecar overlay {}", sidecar.display());
+            return Ok(Some(sidecar));
+        }
+        Ok(None)
+    }
+
+    /// Check if a file itself ends with an overlay
+    fn has_embedded_overlay(path: &Path) -> PackResult<bool> {
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+
+        if file_len < FOOTER_SIZE {
+            return Ok(false);
+        }
+
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
+
+        // Read footer
         let mut offset_bytes = [0u8; 8];
         let mut magic = [0u8; 4];
         reader.read_exact(&mut offset_bytes)?;
         reader.read_exact(&mut magic)?;
 
-        if &magic != OVERLAY_MAGIC {
-            return Ok(None);
-        }
+        Ok(&magic == OVERLAY_MAGIC)
+    }
 
-        let overlay_start = u64::from_le_bytes(offset_bytes);
+    /// Format version of a file's overlay
+    ///
+    /// Returns `None` if the file has no overlay. Versions this crate cannot
+    /// read are reported too, so tools can explain why an overlay is
+    /// rejected.
+    pub fn detect_version(path: &Path) -> PackResult<Option<u32>> {
+        let Some(path) = Self::overlay_file(path)? else {
+            return Ok(None);
+        };
+        let Some(overlay_start) = Self::get_original_size(&path)? else {
+            return Ok(None);
+        };
 
-        // Seek to overlay start and read header
+        let mut reader = BufReader::new(File::open(&path)?);
         reader.seek(SeekFrom::Start(overlay_start))?;
-
-        let mut header_magic = [0u8; 4];
-        let mut version_bytes = [0u8; 4];
-        let mut config_len_bytes = [0u8; 8];
-        let mut assets_len_bytes = [0u8; 8];
-
-        reader.read_exact(&mut header_magic)?;
-        reader.read_exact(&mut version_bytes)?;
-        reader.read_exact(&mut config_len_bytes)?;
-        reader.read_exact(&mut assets_len_bytes)?;
-
-        if &header_magic != OVERLAY_MAGIC {
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != OVERLAY_MAGIC {
             return Err(PackError::InvalidOverlay(
                 "Invalid header magic".to_string(),
             ));
         }
+        Ok(Some(u32::from_le_bytes(header[4..].try_into().unwrap())))
+    }
 
-        let version = u32::from_le_bytes(version_bytes);
-        if version != OVERLAY_VERSION {
-            return Err(PackError::InvalidOverlay(format!(
-                "Unsupported version: {} (expected {})",
-                version, OVERLAY_VERSION
-            )));
-        }
+    /// Versions a file's overlay was written with
+    ///
+    /// Returns `None` if the file has no overlay. Works for overlays this
+    /// crate cannot read, so a shell can explain why it rejects one (see
+    /// [`OverlayVersion::check`]).
+    pub fn version(path: &Path) -> PackResult<Option<OverlayVersion>> {
+        let Some(path) = Self::overlay_file(path)? else {
+            return Ok(None);
+        };
+        let mut reader = BufReader::new(File::open(path)?);
+        let Some(header) = Self::read_raw_header(&mut reader)? else {
+            return Ok(None);
+        };
+        Ok(Some(Self::read_version(&mut reader, &header)))
+    }
+
+    /// Read overlay data from a file
+    pub fn read(path: &Path) -> PackResult<Option<OverlayData>> {
+        Self::read_with_metrics(path, None)
+    }
 
-        let config_len = u64::from_le_bytes(config_len_bytes) as usize;
-        let assets_len = u64::from_le_bytes(assets_len_bytes) as usize;
+    /// Read overlay data from a file with performance metrics
+    pub fn read_with_metrics(
+        path: &Path,
+        mut metrics: Option<&mut PackedMetrics>,
+    ) -> PackResult<Option<OverlayData>> {
+        let Some(path) = Self::overlay_file(path)? else {
+            return Ok(None);
+        };
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::with_capacity(64 * 1024, file); // 64KB buffer
+        let Some(header) = Self::read_header(&mut reader)? else {
+            return Ok(None);
+        };
 
         // Read config data
         let read_start = Instant::now();
-        let mut config_compressed = vec![0u8; config_len];
-        reader.read_exact(&mut config_compressed)?;
+        let (metadata, signed) = Self::read_metadata(&mut reader, &header)?;
 
-        // Decompress config
-        let config_json = zstd::decode_all(&config_compressed[..])
-            .map_err(|e| PackError::Compression(e.to_string()))?;
-
-        // Parse overlay metadata
-        let metadata: OverlayMetadata = serde_json::from_slice(&config_json)?;
-        let config = metadata.config;
-        let content_hash = metadata.content_hash;
-
-        tracing::debug!("Overlay content hash: {}", content_hash);
+        tracing::debug!("Overlay content hash: {}", metadata.content_hash);
 
         if let Some(ref mut m) = metrics {
             m.add_phase("config_read_decompress", read_start.elapsed());
             m.mark_config_decompress();
         }
 
-        tracing::debug!(
-            "Config: {} bytes compressed -> {} bytes",
-            config_len,
-            config_json.len()
-        );
-
         // Read assets data
         let assets_start = Instant::now();
-        let mut assets_compressed = vec![0u8; assets_len];
-        reader.read_exact(&mut assets_compressed)?;
+        let (index, section) = match header.version {
+            OVERLAY_VERSION_V1 => (None, Self::read_section(&mut reader, header.section_len)?),
+            _ => {
+                let index = Self::read_index(&mut reader, &header)?;
+                let data_len = Self::check_entries(&index, &header, file_len)?;
+                (Some(index), Self::read_section(&mut reader, data_len)?)
+            }
+        };
+        // Version 1 overlays have no index to sign
+        crate::signing::verify(
+            index.as_ref().and(metadata.signature.as_ref()),
+            &signed,
+            index.as_deref().unwrap_or_default(),
+            TRUSTED_OVERLAY_KEY,
+        )?;
+        let config = metadata.config;
+        let content_hash = metadata.content_hash;
+        let build_metadata = metadata.metadata;
 
         if let Some(ref mut m) = metrics {
             m.add_phase("assets_read", assets_start.elapsed());
         }
 
-        // Use streaming decompression + tar extraction (avoids double memory allocation)
+        let attributes: HashMap<String, AssetAttributes> = index
+            .iter()
+            .flatten()
+            .filter(|entry| !entry.attributes.is_default())
+            .map(|entry| (entry.path.clone(), entry.attributes))
+            .collect();
+
+        // v1: streaming decompression + tar extraction (avoids double memory
+        // allocation); v2: decompress assets in parallel
         let decompress_start = Instant::now();
-        let assets = Self::extract_assets_streaming(&assets_compressed)?;
+        let assets = match index {
+            None => Self::extract_assets_streaming(&section)?,
+            Some(index) => index
+                .par_iter()
+                .map(|entry| {
+                    let range = entry.offset as usize..entry.end()? as usize;
+                    Ok((entry.path.clone(), entry.decode(&section[range])?))
+                })
+                .collect::<PackResult<Vec<_>>>()?,
+        };
 
         if let Some(ref mut m) = metrics {
             m.add_phase("assets_decompress_and_extract", decompress_start.elapsed());
@@ -391,37 +1917,283 @@ impl OverlayReader {
 
         tracing::debug!(
             "Assets: {} bytes compressed -> {} files extracted",
-            assets_len,
+            section.len(),
             assets.len()
         );
 
         Ok(Some(OverlayData {
             config,
             content_hash,
+            metadata: build_metadata,
             assets,
+            attributes,
+            schema: metadata.schema,
         }))
     }
 
-    /// Extract assets from a tar archive (parallel version)
+    /// Open an overlay for reading single assets on demand
     ///
-    /// First pass: collect entry metadata and offsets
-    /// Second pass: parallel read of file contents
-    #[allow(dead_code)]
-    fn extract_assets_archive(data: &[u8]) -> PackResult<Vec<(String, Vec<u8>)>> {
-        let mut archive = tar::Archive::new(data);
+    /// Only the header, config and asset index are read; assets are read and
+    /// decompressed by [`OverlayArchive::read_asset`]. Version 1 overlays
+    /// have no index, so their assets are loaded here.
+    pub fn open(path: &Path) -> PackResult<Option<OverlayArchive>> {
+        let Some(path) = Self::overlay_file(path)? else {
+            return Ok(None);
+        };
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+        let Some(header) = Self::read_header(&mut reader)? else {
+            return Ok(None);
+        };
+        let (metadata, signed) = Self::read_metadata(&mut reader, &header)?;
+
+        let (entries, source) = match header.version {
+            OVERLAY_VERSION_V1 => {
+                crate::signing::verify(None, &signed, &[], TRUSTED_OVERLAY_KEY)?;
+                let section = Self::read_section(&mut reader, header.section_len)?;
+                let assets = Self::extract_assets_streaming(&section)?;
+                let entries = assets
+                    .iter()
+                    .map(|(path, content)| AssetIndexEntry {
+                        path: path.clone(),
+                        offset: 0,
+                        length: content.len() as u64,
+                        size: content.len() as u64,
+                        hash: blake3::hash(content).to_hex().to_string(),
+                        codec: Codec::Store,
+                        attributes: AssetAttributes::default(),
+                    })
+                    .collect();
+                (entries, AssetSource::Loaded(assets))
+            }
+            _ => {
+                let entries = Self::read_index(&mut reader, &header)?;
+                let data_start = header.data_start();
+                Self::check_entries(&entries, &header, file_len)?;
+                crate::signing::verify(
+                    metadata.signature.as_ref(),
+                    &signed,
+                    &entries,
+                    TRUSTED_OVERLAY_KEY,
+                )?;
+                let file = reader.into_inner();
+                let map = MappedFile::map(&file)
+                    .map_err(|e| tracing::debug!("Reading overlay assets without mmap: {}", e))
+                    .ok();
+                (
+                    entries,
+                    AssetSource::Indexed {
+                        file,
+                        data_start,
+                        map,
+                    },
+                )
+            }
+        };
 
-        // First pass: collect entries sequentially (tar requires sequential read)
-        let mut entries_data: Vec<(String, Vec<u8>)> = Vec::new();
+        Ok(Some(OverlayArchive {
+            config: metadata.config,
+            content_hash: metadata.content_hash,
+            metadata: metadata.metadata,
+            version: header.version,
+            signature: metadata.signature,
+            schema: metadata.schema,
+            entries,
+            source,
+        }))
+    }
 
-        for entry in archive.entries()? {
-            let mut entry = entry?;
-            let path = entry.path()?.to_string_lossy().to_string();
-            let mut content = Vec::with_capacity(entry.size() as usize);
-            entry.read_to_end(&mut content)?;
-            entries_data.push((path, content));
+    /// Check an overlay's signature without reading its assets
+    ///
+    /// Signed overlays must verify against their own key; with a `trusted`
+    /// key (base64), the overlay must be signed by that key. Returns the
+    /// signature, if any. Asset contents are checked against the signed
+    /// index hashes as they are read.
+    pub fn verify(path: &Path, trusted: Option<&str>) -> PackResult<Option<OverlaySignature>> {
+        let file = File::open(Self::overlay_file(path)?.as_deref().unwrap_or(path))?;
+        let mut reader = BufReader::new(file);
+        let Some(header) = Self::read_header(&mut reader)? else {
+            return Err(PackError::InvalidOverlay(format!(
+                "{} has no overlay",
+                path.display()
+            )));
+        };
+        let (metadata, signed) = Self::read_metadata(&mut reader, &header)?;
+        let (signature, index) = match header.version {
+            OVERLAY_VERSION_V1 => (None, Vec::new()),
+            _ => (metadata.signature, Self::read_index(&mut reader, &header)?),
+        };
+        crate::signing::verify(signature.as_ref(), &signed, &index, trusted)?;
+        Ok(signature)
+    }
+
+    /// Read and check the footer and header
+    fn read_header<R: Read + Seek>(reader: &mut R) -> PackResult<Option<OverlayHeader>> {
+        let Some(header) = Self::read_raw_header(reader)? else {
+            return Ok(None);
+        };
+        if !SUPPORTED_OVERLAY_VERSIONS.contains(&header.version) {
+            Self::read_version(reader, &header).check()?;
         }
+        Ok(Some(header))
+    }
 
-        Ok(entries_data)
+    /// Versions of an overlay (the reader is positioned after the header)
+    ///
+    /// Best effort: the release fields are `None` if the config section
+    /// cannot be read.
+    fn read_version<R: Read>(reader: &mut R, header: &OverlayHeader) -> OverlayVersion {
+        let mut config = Vec::new();
+        let fields = reader
+            .take(header.config_len)
+            .read_to_end(&mut config)
+            .ok()
+            .and_then(|_| zstd::decode_all(&config[..]).ok())
+            .and_then(|json| serde_json::from_slice::<VersionFields>(&json).ok())
+            .unwrap_or_default();
+        OverlayVersion {
+            format: header.version,
+            packer_version: fields.packer_version,
+            min_reader_version: fields.min_reader_version,
+        }
+    }
+
+    /// Read the footer and header, whatever the format version
+    fn read_raw_header<R: Read + Seek>(reader: &mut R) -> PackResult<Option<OverlayHeader>> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        if file_len < FOOTER_SIZE {
+            return Ok(None);
+        }
+
+        // Read footer
+        reader.seek(SeekFrom::End(-(FOOTER_SIZE as i64)))?;
+        let mut offset_bytes = [0u8; 8];
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut offset_bytes)?;
+        reader.read_exact(&mut magic)?;
+
+        if &magic != OVERLAY_MAGIC {
+            return Ok(None);
+        }
+
+        let overlay_start = u64::from_le_bytes(offset_bytes);
+
+        // Seek to overlay start and read header
+        reader.seek(SeekFrom::Start(overlay_start))?;
+
+        let mut header_magic = [0u8; 4];
+        let mut version_bytes = [0u8; 4];
+        let mut config_len_bytes = [0u8; 8];
+        let mut section_len_bytes = [0u8; 8];
+
+        reader.read_exact(&mut header_magic)?;
+        reader.read_exact(&mut version_bytes)?;
+        reader.read_exact(&mut config_len_bytes)?;
+        reader.read_exact(&mut section_len_bytes)?;
+
+        if &header_magic != OVERLAY_MAGIC {
+            return Err(PackError::InvalidOverlay(
+                "Invalid header magic".to_string(),
+            ));
+        }
+
+        Ok(Some(OverlayHeader {
+            start: overlay_start,
+            version: u32::from_le_bytes(version_bytes),
+            config_len: u64::from_le_bytes(config_len_bytes),
+            section_len: u64::from_le_bytes(section_len_bytes),
+        }))
+    }
+
+    /// Read the config section (the reader is positioned after the header)
+    ///
+    /// Also returns the canonical JSON the signature covers.
+    fn read_metadata<R: Read>(
+        reader: &mut R,
+        header: &OverlayHeader,
+    ) -> PackResult<(OverlayMetadata, Vec<u8>)> {
+        let config_compressed = Self::read_section(reader, header.config_len)?;
+
+        // Decompress config
+        let config_json = zstd::decode_all(&config_compressed[..])
+            .map_err(|e| PackError::Compression(e.to_string()))?;
+
+        tracing::debug!(
+            "Config: {} bytes compressed -> {} bytes",
+            header.config_len,
+            config_json.len()
+        );
+
+        let mut value: serde_json::Value = serde_json::from_slice(&config_json)?;
+        // Refuse configs written for newer readers before misreading them
+        let fields = VersionFields::deserialize(&value).unwrap_or_default();
+        let version = OverlayVersion {
+            format: header.version,
+            packer_version: fields.packer_version,
+            min_reader_version: fields.min_reader_version,
+        };
+        version.check()?;
+        let signature = value
+            .as_object_mut()
+            .and_then(|object| object.remove("signature"));
+        let signed = serde_json::to_vec(&value)?;
+        let mut metadata: OverlayMetadata = serde_json::from_value(value.clone())?;
+        metadata.signature = signature.map(serde_json::from_value).transpose()?;
+
+        metadata.schema = SchemaReport::new(version, &value, &metadata.config);
+        if !metadata.schema.unknown_fields.is_empty() {
+            tracing::warn!("{}", metadata.schema.describe().unwrap_or_default());
+        } else if let Some(mismatch) = metadata.schema.describe() {
+            tracing::debug!("{}", mismatch);
+        }
+        Ok((metadata, signed))
+    }
+
+    /// Read the v2 asset index (the reader is positioned after the config)
+    fn read_index<R: Read + Seek>(
+        reader: &mut R,
+        header: &OverlayHeader,
+    ) -> PackResult<Vec<AssetIndexEntry>> {
+        reader.seek(SeekFrom::Start(header.section_start()))?;
+        let index_compressed = Self::read_section(reader, header.section_len)?;
+        let index_json = zstd::decode_all(&index_compressed[..])
+            .map_err(|e| PackError::Compression(e.to_string()))?;
+        Ok(serde_json::from_slice(&index_json)?)
+    }
+
+    /// Check that all assets of `entries` lie inside the file; returns the
+    /// length of the asset data they use
+    fn check_entries(
+        entries: &[AssetIndexEntry],
+        header: &OverlayHeader,
+        file_len: u64,
+    ) -> PackResult<u64> {
+        let available = file_len.saturating_sub(header.data_start().saturating_add(FOOTER_SIZE));
+        entries.iter().try_fold(0, |data_len, entry| {
+            let end = entry.end()?;
+            if end > available {
+                return Err(entry.outside_overlay());
+            }
+            Ok(data_len.max(end))
+        })
+    }
+
+    /// Read `len` bytes
+    ///
+    /// Lengths come from the overlay: the buffer only grows with the bytes
+    /// actually read, so a corrupted length fails instead of allocating it.
+    fn read_section<R: Read>(reader: &mut R, len: u64) -> PackResult<Vec<u8>> {
+        let mut section = Vec::with_capacity(preallocation(len));
+        reader.take(len).read_to_end(&mut section)?;
+        if (section.len() as u64) < len {
+            return Err(PackError::InvalidOverlay(format!(
+                "Overlay section of {} bytes is truncated to {}",
+                len,
+                section.len()
+            )));
+        }
+        Ok(section)
     }
 
     /// Extract assets from a tar archive using streaming zstd decoder
@@ -438,7 +2210,7 @@ impl OverlayReader {
         for entry in archive.entries()? {
             let mut entry = entry?;
             let path = entry.path()?.to_string_lossy().to_string();
-            let mut content = Vec::with_capacity(entry.size() as usize);
+            let mut content = Vec::with_capacity(preallocation(entry.size()));
             entry.read_to_end(&mut content)?;
             entries_data.push((path, content));
         }
@@ -470,3 +2242,278 @@ impl OverlayReader {
         Ok(Some(u64::from_le_bytes(offset_bytes)))
     }
 }
+
+/// Where an opened overlay's assets come from
+enum AssetSource {
+    /// v2: read from the file by index, through a memory map if possible
+    Indexed {
+        file: File,
+        data_start: u64,
+        map: Option<MappedFile>,
+    },
+    /// v1: loaded when opened
+    Loaded(Vec<(String, Vec<u8>)>),
+}
+
+/// An opened overlay with random access to its assets
+///
+/// Created by [`OverlayReader::open`].
+pub struct OverlayArchive {
+    /// Pack configuration
+    pub config: PackConfig,
+    /// Content hash (BLAKE3) of all assets
+    pub content_hash: String,
+    /// Free-form build metadata (see [`OverlayData::set_metadata`])
+    pub metadata: BTreeMap<String, String>,
+    /// Format version of the overlay
+    pub version: u32,
+    /// Signature of the overlay, if it was signed
+    pub signature: Option<OverlaySignature>,
+    /// How the config matched this release's schema
+    pub schema: SchemaReport,
+    entries: Vec<AssetIndexEntry>,
+    source: AssetSource,
+}
+
+impl OverlayArchive {
+    /// Index of all assets
+    pub fn entries(&self) -> &[AssetIndexEntry] {
+        &self.entries
+    }
+
+    /// Index entry of an asset
+    pub fn entry(&self, path: &str) -> Option<&AssetIndexEntry> {
+        self.entries.iter().find(|e| e.path == path)
+    }
+
+    /// Read and decompress one asset
+    ///
+    /// Returns `None` if the overlay has no such asset. The content is
+    /// checked against the index hash.
+    pub fn read_asset(&mut self, path: &str) -> PackResult<Option<Vec<u8>>> {
+        let Some(entry) = self.entries.iter().find(|e| e.path == path) else {
+            return Ok(None);
+        };
+        match self.source {
+            AssetSource::Indexed {
+                ref mut file,
+                data_start,
+                ..
+            } => {
+                file.seek(SeekFrom::Start(data_start + entry.offset))?;
+                let compressed = OverlayReader::read_section(file, entry.length)?;
+                entry.decode(&compressed).map(Some)
+            }
+            AssetSource::Loaded(ref assets) => Ok(assets
+                .iter()
+                .find(|(p, _)| p == path)
+                .map(|(_, content)| content.clone())),
+        }
+    }
+
+    /// Stream one asset without loading it whole
+    ///
+    /// v2 assets are decompressed straight from the memory-mapped overlay
+    /// (positional file reads where mapping is unavailable), so serving a
+    /// large frontend file or unpacking the Python archive does not allocate
+    /// a copy of the asset. The content is checked against the index hash
+    /// when the reader reaches the end; a mismatch is an `InvalidData` error.
+    pub fn asset_reader(&self, path: &str) -> PackResult<Option<AssetReader<'_>>> {
+        let Some(entry) = self.entries.iter().find(|e| e.path == path) else {
+            return Ok(None);
+        };
+        let inner: Box<dyn Read + Send + '_> = match self.source {
+            AssetSource::Indexed {
+                ref map,
+                ref file,
+                data_start,
+            } => {
+                let start = data_start + entry.offset;
+                let end = data_start + entry.end()?;
+                match map {
+                    Some(map) => {
+                        let compressed = map
+                            .as_slice()
+                            .get(start as usize..end as usize)
+                            .ok_or_else(|| entry.outside_overlay())?;
+                        entry.codec.decoder(compressed)?
+                    }
+                    None => entry.codec.decoder(BufReader::new(PositionalReader {
+                        file,
+                        pos: start,
+                        end,
+                    }))?,
+                }
+            }
+            AssetSource::Loaded(ref assets) => {
+                let content = assets
+                    .iter()
+                    .find(|(p, _)| p == path)
+                    .map(|(_, content)| content.as_slice())
+                    .unwrap_or_default();
+                Box::new(content)
+            }
+        };
+
+        Ok(Some(AssetReader {
+            inner: HashingReader::new(inner),
+            entry,
+        }))
+    }
+
+    /// Extract all assets into `dir`
+    ///
+    /// Permission bits are restored and symlinks recreated on Unix; on
+    /// other systems symlinks become copies of their targets. Returns the
+    /// number of extracted assets.
+    pub fn extract_to(&mut self, dir: &Path) -> PackResult<usize> {
+        let entries = self.entries.clone();
+        let mut links = Vec::new();
+        for entry in &entries {
+            let dest = extraction_path(dir, &entry.path)?;
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            if entry.attributes.symlink {
+                let target = String::from_utf8(self.read_asset(&entry.path)?.unwrap_or_default())
+                    .map_err(|_| {
+                    PackError::InvalidOverlay(format!("Symlink {} is not UTF-8", entry.path))
+                })?;
+                validate_symlink_target(&entry.path, &target)?;
+                links.push((dest, target));
+                continue;
+            }
+
+            let mut reader = self
+                .asset_reader(&entry.path)?
+                .ok_or_else(|| PackError::InvalidOverlay(format!("Missing {}", entry.path)))?;
+            let _ = std::fs::remove_file(&dest);
+            std::io::copy(&mut reader, &mut BufWriter::new(File::create(&dest)?))?;
+            #[cfg(unix)]
+            if let Some(mode) = entry.attributes.mode {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&dest, std::fs::Permissions::from_mode(mode))?;
+            }
+        }
+
+        // Links last, so copies on non-Unix systems find their targets
+        for (dest, target) in links {
+            let _ = std::fs::remove_file(&dest);
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&target, &dest)?;
+            #[cfg(not(unix))]
+            {
+                let source = dest.parent().unwrap_or(dir).join(&target);
+                if source.is_file() {
+                    std::fs::copy(&source, &dest)?;
+                } else {
+                    tracing::warn!("Skipping symlink {} -> {}", dest.display(), target);
+                }
+            }
+        }
+        Ok(entries.len())
+    }
+}
+
+/// Destination of an asset below an extraction directory
+pub(crate) fn extraction_path(dir: &Path, asset: &str) -> PackResult<PathBuf> {
+    let relative = Path::new(asset);
+    let safe = !asset.is_empty()
+        && relative
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+    if !safe {
+        return Err(PackError::InvalidOverlay(format!(
+            "Asset path {} escapes the extraction directory",
+            asset
+        )));
+    }
+    Ok(dir.join(relative))
+}
+
+/// Streaming reader of one asset, see [`OverlayArchive::asset_reader`]
+pub struct AssetReader<'a> {
+    inner: HashingReader<Box<dyn Read + Send + 'a>>,
+    entry: &'a AssetIndexEntry,
+}
+
+impl AssetReader<'_> {
+    /// Uncompressed size of the asset
+    pub fn size(&self) -> u64 {
+        self.entry.size
+    }
+}
+
+impl Read for AssetReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n == 0
+            && !buf.is_empty()
+            && (self.inner.size != self.entry.size
+                || self.inner.hasher.finalize().to_hex().as_str() != self.entry.hash)
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Asset {} is corrupted (hash mismatch)", self.entry.path),
+            ));
+        }
+        Ok(n)
+    }
+}
+
+/// Reads a byte range of a file without moving a shared cursor
+struct PositionalReader<'a> {
+    file: &'a File,
+    pos: u64,
+    end: u64,
+}
+
+impl Read for PositionalReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = (self.end - self.pos).min(buf.len() as u64) as usize;
+        if remaining == 0 {
+            return Ok(0);
+        }
+        #[cfg(unix)]
+        let n = std::os::unix::fs::FileExt::read_at(self.file, &mut buf[..remaining], self.pos)?;
+        #[cfg(windows)]
+        let n =
+            std::os::windows::fs::FileExt::seek_read(self.file, &mut buf[..remaining], self.pos)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_overlay_keeps_file_on_failure() {
+        let temp = tempfile::tempdir().unwrap();
+        let exe = temp.path().join("app.exe");
+        std::fs::write(&exe, b"exe").unwrap();
+        let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+        data.add_asset("index.html", b"<html>old</html>".to_vec());
+        OverlayWriter::write(&exe, &data).unwrap();
+        let packed = std::fs::read(&exe).unwrap();
+
+        let mut stream = OverlayWriter::begin(&exe, 3).unwrap();
+        stream
+            .append_asset("index.html", b"<html>new</html>")
+            .unwrap();
+        let result = replace_overlay(stream, 3, |_| {
+            Err(PackError::Build("disk full".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(std::fs::read(&exe).unwrap(), packed);
+        // The staged copy is removed
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 1);
+
+        let stream = OverlayWriter::begin(&exe, 3).unwrap();
+        replace_overlay(stream, 3, |stream| stream.finish(&data.config)).unwrap();
+        let archive = OverlayReader::open(&exe).unwrap().unwrap();
+        assert!(archive.entries().is_empty());
+        assert!(std::fs::read(&exe).unwrap().starts_with(b"exe"));
+    }
+}
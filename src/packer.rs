@@ -1,17 +1,65 @@
 //! Main packer implementation
 
-use crate::bundle::BundleBuilder;
+use crate::about::{AboutData, ABOUT_JSON_PATH, ABOUT_PREFIX, THIRD_PARTY_NOTICES_PATH};
+use crate::build_cache::{BuildCache, IncrementalConfig, IncrementalStats};
+use crate::bundle::{AssetBundle, BundleBuilder};
+use crate::clean::{path_size, CleanReport, CleanScope};
 use crate::config::BundleStrategy;
+use crate::context::PackContext;
+use crate::cuda::CudaLibraryFilter;
 use crate::deps_collector::DepsCollector;
-use crate::overlay::{OverlayData, OverlayWriter};
-use crate::python_standalone::{PythonRuntimeMeta, PythonStandalone, PythonStandaloneConfig};
+use crate::diff::PackDiff;
+use crate::doctor::{DoctorCheck, DoctorReport};
+use crate::hashing::Digester;
+use crate::history::{HistoryConfig, PackStats, Regression};
+use crate::hooks::{HookEnv, HookLimits, HookVars};
+use crate::inspect::InspectReport;
+use crate::integrity::{IntegrityEntry, IntegrityManifest};
+use crate::isolation::IsolationEnv;
+use crate::overlay::{
+    sidecar_path, AssetIndexEntry, DedupStats, OverlayData, OverlayPlacement, OverlayReader,
+    OverlayWriter,
+};
+use crate::plan::{AssetSource, PackPlan, PlannedDownload};
+use crate::plugin::{PackPlugin, PluginContext};
+use crate::progress::{PackProgressObserver, PackStage, ProgressTracker};
+use crate::provenance::{ExternalParameters, ProvenanceConfig, ProvenanceRun, ResourceDescriptor};
+use crate::python_abi::{PythonRequirement, PYTHON_REQUIREMENT_PATH};
+use crate::python_standalone::{
+    DistributionProvider, PythonRuntimeMeta, PythonStandalone, PythonStandaloneConfig, PythonTarget,
+};
 use crate::resource_editor::ResourceConfig;
 #[cfg(target_os = "windows")]
 use crate::resource_editor::ResourceEditor;
-use crate::{Manifest, PackConfig, PackError, PackMode, PackResult, PythonBundleConfig};
+use crate::resume::{Checkpoint, ResumeConfig, UpToDate};
+use crate::sbom::Sbom;
+use crate::schedule::CronSpec;
+use crate::signing::OverlaySigner;
+use crate::smoke_test::SmokeTestReport;
+use crate::store::{ArtifactStore, StoreConfig};
+use crate::subprocess::CommandRunner;
+use crate::symbols::{SymbolEntry, SymbolIndex, SYMBOLS_INFO_PATH};
+use crate::targets::BuildTarget;
+use crate::watch::{Snapshot, WatchEvent, WatchOptions};
+use crate::{
+    BackendType, LaunchSpec, Manifest, OtlpExporter, OutputLayout, PackConfig, PackError, PackMode,
+    PackOptions, PackResult, PythonBundleConfig, TargetPlatform, TestRunDescriptor,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::ControlFlow;
 use std::path::{Component, Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Milliseconds elapsed since `start`
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
 
 /// Normalize a path by removing `.` and resolving `..` components
 fn normalize_path(path: &Path) -> PathBuf {
@@ -34,7 +82,10 @@ fn normalize_path(path: &Path) -> PathBuf {
 }
 
 /// Result of a pack operation
-#[derive(Debug)]
+///
+/// Doubles as the build report: [`to_json`](Self::to_json) gives CI and
+/// release tooling the sizes, timings, tools and downloads of the run.
+#[derive(Debug, Serialize)]
 pub struct PackOutput {
     /// Path to the generated executable or directory
     pub executable: PathBuf,
@@ -46,17 +97,241 @@ pub struct PackOutput {
     pub python_file_count: usize,
     /// Pack mode used
     pub mode: String,
+    /// Size/time regressions against the pack history (`[build.history]`)
+    pub regressions: Vec<Regression>,
+    /// Space saved by storing identical assets once
+    pub dedup: DedupStats,
+    /// Assets reused from the build cache (`[build.incremental]`)
+    pub incremental: Option<IncrementalStats>,
+    /// Update feed the executable was added to (`[bundle.update_feed]`)
+    pub update_feed: Option<PathBuf>,
+    /// Signed provenance of the executable (`[build.provenance]`)
+    pub provenance: Option<PathBuf>,
+    /// Stage this run resumed from (`[build.resume]`)
+    pub resumed_from: Option<PackStage>,
+    /// Nothing changed since the previous pack, whose output this is
+    /// (`[build] skip_unchanged`)
+    pub up_to_date: bool,
+    /// Overlay index: compressed and uncompressed size of every asset
+    pub assets: Vec<AssetIndexEntry>,
+    /// Duration of each stage run, in milliseconds
+    pub stage_timings: BTreeMap<String, u64>,
+    /// Duration of the whole run, in milliseconds
+    pub duration_ms: u64,
+    /// Versions of the tools used (the packer, Python, pinned toolchains)
+    pub tools: BTreeMap<String, String>,
+    /// Downloads the output was built from, with their SHA-256 digests
+    pub downloads: Vec<ResourceDescriptor>,
+    /// Result of the post-pack smoke test (`[debug.smoke_test]`)
+    pub smoke_test: Option<SmokeTestReport>,
+}
+
+impl PackOutput {
+    /// Build report as pretty-printed JSON
+    pub fn to_json(&self) -> PackResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
 }
 
 /// Main packer for creating standalone executables
+///
+/// `Packer` is `Send + Sync`: it can be shared between threads (e.g. in an
+/// `Arc` held by a packing service). Packs of one packer run one at a time,
+/// since they share its run state; separate packers (one per job or per
+/// target) pack concurrently.
 pub struct Packer {
     config: PackConfig,
+    run_id: String,
+    /// Held for the duration of a pack (the state below is per run)
+    run_lock: Mutex<()>,
+    /// Binaries processed by `[build.symbols]` during the current run
+    symbol_entries: Mutex<Vec<SymbolEntry>>,
+    /// Build cache reuse of the last overlay written
+    incremental_stats: Mutex<Option<IncrementalStats>>,
+    /// Client of all downloads (`[build.http]`)
+    http: crate::HttpClient,
+    /// Receives progress events (see [`Packer::with_progress`])
+    progress: Option<Arc<dyn PackProgressObserver>>,
+    /// Pack even if nothing changed (see [`Packer::with_force`])
+    force: bool,
+    /// Supplies Python distributions (see [`Packer::with_python_provider`])
+    python_provider: Option<Arc<dyn DistributionProvider>>,
+    /// Tool versions used during the current run
+    tools: Mutex<BTreeMap<String, String>>,
+    /// Downloads made during the current run
+    downloads: Mutex<Vec<ResourceDescriptor>>,
+    /// Runs Python, pip, uv, rcedit and tool checks
+    /// (see [`Packer::with_command_runner`])
+    runner: Arc<dyn CommandRunner>,
+    /// Exports the timings of every run (see [`Packer::with_telemetry`])
+    telemetry: Option<OtlpExporter>,
+    /// Log level and quiet mode (see [`Packer::with_options`])
+    options: PackOptions,
+    /// Directories, platform and clock (see [`Packer::with_context`])
+    context: PackContext,
+    /// Called at every pack stage (see [`Packer::with_plugin`])
+    plugins: Vec<Arc<dyn PackPlugin>>,
 }
 
 impl Packer {
     /// Create a new packer with configuration
     pub fn new(config: PackConfig) -> Self {
-        Self { config }
+        // Invalid [build.http] settings fail validate() before any download
+        let http = crate::HttpClient::new(&config.http).unwrap_or_default();
+        let telemetry = OtlpExporter::from_env().map(|exporter| exporter.with_http(http.clone()));
+        let context = PackContext::from_process();
+        Self {
+            http,
+            config,
+            run_id: context.new_run_id(),
+            run_lock: Mutex::new(()),
+            symbol_entries: Mutex::new(Vec::new()),
+            incremental_stats: Mutex::new(None),
+            progress: None,
+            force: false,
+            python_provider: None,
+            tools: Mutex::new(BTreeMap::new()),
+            downloads: Mutex::new(Vec::new()),
+            runner: crate::subprocess::system_runner(),
+            telemetry,
+            options: PackOptions::from_env(),
+            context,
+            plugins: Vec::new(),
+        }
+    }
+
+    /// Packer for `config` with this packer's runner, Python provider,
+    /// telemetry, HTTP client, progress observer, options, context and
+    /// plugins, under a new run id
+    fn derive(&self, config: PackConfig) -> Packer {
+        Packer {
+            config,
+            run_id: self.context.new_run_id(),
+            run_lock: Mutex::new(()),
+            symbol_entries: Mutex::new(Vec::new()),
+            incremental_stats: Mutex::new(None),
+            http: self.http.clone(),
+            progress: self.progress.clone(),
+            force: self.force,
+            python_provider: self.python_provider.clone(),
+            tools: Mutex::new(BTreeMap::new()),
+            downloads: Mutex::new(Vec::new()),
+            runner: self.runner.clone(),
+            telemetry: self.telemetry.clone(),
+            options: self.options,
+            context: self.context.clone(),
+            plugins: self.plugins.clone(),
+        }
+    }
+
+    /// Run in `context` instead of the process environment
+    ///
+    /// Relative paths of the configuration are resolved against the
+    /// context's base directory.
+    pub fn with_context(mut self, context: PackContext) -> Self {
+        context.rebase(&mut self.config);
+        self.run_id = context.new_run_id();
+        self.context = context;
+        self
+    }
+
+    /// Directories, platform and clock of this packer
+    pub fn context(&self) -> &PackContext {
+        &self.context
+    }
+
+    /// Register `plugin`; plugins are called in registration order
+    pub fn with_plugin(mut self, plugin: impl PackPlugin + 'static) -> Self {
+        self.plugins.push(Arc::new(plugin));
+        self
+    }
+
+    /// Names of the registered plugins
+    pub fn plugins(&self) -> Vec<&str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    /// Pack even if nothing changed since the previous pack
+    /// (`[build] skip_unchanged`)
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Take Python distributions from `provider` instead of downloading
+    /// them (offline builds and tests)
+    pub fn with_python_provider(mut self, provider: impl DistributionProvider + 'static) -> Self {
+        self.python_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Run Python, pip, uv, rcedit and tool version checks through
+    /// `runner` instead of spawning them directly (hooks still run as
+    /// configured)
+    pub fn with_command_runner(mut self, runner: impl CommandRunner + 'static) -> Self {
+        self.runner = Arc::new(runner);
+        self
+    }
+
+    /// Export the trace and metrics of every run through `exporter`
+    /// instead of the one configured by the `OTEL_*` variables
+    pub fn with_telemetry(mut self, exporter: OtlpExporter) -> Self {
+        self.telemetry = Some(exporter);
+        self
+    }
+
+    /// Log level and quiet mode of every run, instead of the ones set by
+    /// `AURORAVIEW_PACK_LOG` and `AURORAVIEW_PACK_QUIET`
+    pub fn with_options(mut self, options: PackOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Output settings of this packer
+    pub fn options(&self) -> &PackOptions {
+        &self.options
+    }
+
+    /// Report stage, per-file and byte progress to `observer`
+    ///
+    /// Byte progress covers writing the assets, every download (Python
+    /// distributions, `[[downloads]]`, toolchains) and extracting them.
+    pub fn with_progress(mut self, observer: impl PackProgressObserver + 'static) -> Self {
+        let observer: Arc<dyn PackProgressObserver> = Arc::new(observer);
+        self.http = self.http.clone().with_progress(observer.clone());
+        self.progress = Some(observer);
+        self
+    }
+
+    fn stage_started(&self, stage: PackStage) -> Instant {
+        if let Some(ref progress) = self.progress {
+            progress.stage_started(stage);
+        }
+        Instant::now()
+    }
+
+    fn stage_resumed(&self, stage: PackStage) {
+        if let Some(ref progress) = self.progress {
+            progress.stage_resumed(stage);
+        }
+    }
+
+    /// Record the duration of `stage` in `phases` and report it
+    fn stage_finished(
+        &self,
+        stage: PackStage,
+        started: Instant,
+        phases: &mut BTreeMap<String, u64>,
+    ) {
+        phases.insert(stage.as_str().to_string(), elapsed_ms(started));
+        if let Some(ref progress) = self.progress {
+            progress.stage_finished(stage, started.elapsed());
+        }
+    }
+
+    /// Unique ID of this packer's runs (used to name staging directories)
+    pub fn run_id(&self) -> &str {
+        &self.run_id
     }
 
     /// Create a packer from a manifest file
@@ -73,19 +348,432 @@ impl Packer {
         Ok(output.executable)
     }
 
+    /// Pack once per target of the build matrix (`[build] targets`)
+    ///
+    /// Each target is packed into `<name>-<arch>-<os>[.exe]` from its own
+    /// launcher stub and Python distribution. Without targets this is a
+    /// single [`pack`](Self::pack) for the current platform.
+    pub fn pack_targets(&self) -> PackResult<Vec<(BuildTarget, PackOutput)>> {
+        if self.config.targets.is_empty() {
+            let target = match self.context.target() {
+                Some(target) => target,
+                None => BuildTarget::current()?,
+            };
+            return Ok(vec![(target, self.pack()?)]);
+        }
+        // Fail before the first target is packed if a later one lacks a stub
+        self.validate()?;
+        let mut outputs = Vec::with_capacity(self.config.targets.len());
+        for target in &self.config.targets {
+            tracing::info!("Packing target {}", target);
+            let packer = self.derive(self.config.for_target(*target));
+            outputs.push((*target, packer.pack()?));
+        }
+        Ok(outputs)
+    }
+
+    /// Pack, then repack whenever the frontend or the Python
+    /// `include_paths` change
+    ///
+    /// Every pack (the initial one included) is reported to `on_event`;
+    /// watching stops when it returns [`ControlFlow::Break`]. A failed pack
+    /// is reported and watching continues. Packs are incremental: without
+    /// `[build.incremental]` the default build cache is used.
+    pub fn watch<F>(&self, options: &WatchOptions, mut on_event: F) -> PackResult<()>
+    where
+        F: FnMut(WatchEvent) -> ControlFlow<()>,
+    {
+        let mut roots: Vec<PathBuf> = Vec::new();
+        if let Some(frontend) = self.config.mode.frontend_path() {
+            if !self.config.uses_dev_server() {
+                roots.push(frontend.clone());
+            }
+        }
+        if let Some(python) = self.config.mode.python_config() {
+            roots.extend(python.include_paths.iter().cloned());
+        }
+        if roots.is_empty() {
+            return Err(PackError::Config(
+                "Nothing to watch: no frontend directory or Python include_paths".to_string(),
+            ));
+        }
+
+        let mut config = self.config.clone();
+        config
+            .incremental
+            .get_or_insert_with(IncrementalConfig::default);
+        let packer = self.derive(config);
+
+        let ignore = self.config.output_dir.clone();
+        let mut changed = Vec::new();
+        loop {
+            let started = Instant::now();
+            let event = match packer.pack() {
+                Ok(output) => WatchEvent::Packed {
+                    output: Box::new(output),
+                    changed,
+                    elapsed: started.elapsed(),
+                },
+                Err(error) => WatchEvent::Failed {
+                    error,
+                    changed,
+                    elapsed: started.elapsed(),
+                },
+            };
+            if on_event(event).is_break() {
+                return Ok(());
+            }
+
+            // Files written by the pack itself (e.g. by hooks) are not changes
+            let snapshot = Snapshot::scan(&roots, &ignore);
+            changed = loop {
+                std::thread::sleep(options.poll_interval);
+                let mut current = Snapshot::scan(&roots, &ignore);
+                if current.changes(&snapshot).is_empty() {
+                    continue;
+                }
+                // Wait until the tree is quiet
+                loop {
+                    std::thread::sleep(options.debounce);
+                    let next = Snapshot::scan(&roots, &ignore);
+                    if next == current {
+                        break;
+                    }
+                    current = next;
+                }
+                let changes = current.changes(&snapshot);
+                if !changes.is_empty() {
+                    break changes;
+                }
+            };
+            tracing::info!("{} watched files changed, repacking", changed.len());
+            let event = WatchEvent::Changed {
+                paths: changed.clone(),
+            };
+            if on_event(event).is_break() {
+                return Ok(());
+            }
+        }
+    }
+
     /// Pack the application into a standalone executable
     ///
     /// This copies the current auroraview executable and appends
     /// configuration and assets as overlay data.
     pub fn pack(&self) -> PackResult<PackOutput> {
+        let _run = self.run_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let dispatch = self.options.dispatch();
+        crate::logging::scoped(dispatch.as_ref(), || self.pack_once())
+    }
+
+    fn pack_once(&self) -> PackResult<PackOutput> {
+        let started = self.stage_started(PackStage::Prepare);
+        let started_on = self.context.unix_time();
+        let mut phases = BTreeMap::new();
+
         // Validate configuration
         self.validate()?;
 
+        // Fail early if the output or staging volume is too small
+        let staging_root = self.context.staging_root(&self.config);
+        crate::staging::preflight_disk_space(
+            &self.config,
+            &staging_root,
+            self.launcher_path().ok().as_deref(),
+        )?;
+        tracing::info!(
+            "Pack run {} (staging in {})",
+            self.run_id,
+            staging_root.display()
+        );
+
         // Ensure output directory exists
-        fs::create_dir_all(&self.config.output_dir)?;
+        fs::create_dir_all(self.exe_dir())?;
+        self.lock_symbol_entries().clear();
+        self.lock_tools().clear();
+        self.lock_downloads().clear();
+
+        // Download pinned Go/Rust toolchains missing on this machine
+        let hook_env = self.provision_toolchains()?;
+
+        // Stages finished by an earlier, failed run of this configuration
+        let mut checkpoint = self.open_checkpoint()?;
+        let mut resume_from = checkpoint.as_ref().and_then(Checkpoint::resume_from);
+        let packed = match (resume_from, &checkpoint) {
+            (Some(PackStage::AfterPack), Some(checkpoint)) => checkpoint.packed_output(),
+            _ => None,
+        };
+        if resume_from == Some(PackStage::AfterPack) && packed.is_none() {
+            resume_from = Some(PackStage::Pack);
+        }
+        if let Some(stage) = resume_from {
+            tracing::info!("Resuming failed pack from the {} stage", stage.as_str());
+            self.stage_resumed(PackStage::Prepare);
+        } else {
+            self.prepare(&hook_env)?;
+            if let Some(ref mut checkpoint) = checkpoint {
+                checkpoint.complete(PackStage::Prepare, None)?;
+            }
+        }
+        self.stage_finished(PackStage::Prepare, started, &mut phases);
+
+        // Nothing changed since the previous pack: return its output
+        let up_to_date = self.up_to_date_record()?;
+        if !self.force {
+            if let Some(previous) = up_to_date.as_ref().and_then(UpToDate::previous) {
+                tracing::info!(
+                    "{} is up to date, skipping pack",
+                    previous.executable.display()
+                );
+                self.stage_resumed(PackStage::Pack);
+                self.stage_resumed(PackStage::AfterPack);
+                if let Some(checkpoint) = checkpoint {
+                    checkpoint.finish()?;
+                }
+                let mut previous = previous;
+                self.complete_report(&mut previous, &phases, started)?;
+                return Ok(previous);
+            }
+        }
+
+        let mut result = match packed {
+            Some(result) => {
+                self.stage_resumed(PackStage::Pack);
+                result
+            }
+            None => {
+                let pack_started = self.stage_started(PackStage::Pack);
+                let mut result = match &self.config.mode {
+                    PackMode::Url { .. } | PackMode::Frontend { .. } => self.pack_simple(),
+                    PackMode::FullStack {
+                        frontend_path,
+                        python,
+                    } => self.pack_fullstack(frontend_path, python),
+                    PackMode::Process {
+                        frontend_path,
+                        launch,
+                    } => self.pack_process(frontend_path, launch),
+                }?;
+
+                self.stage_finished(PackStage::Pack, pack_started, &mut phases);
+
+                // A onedir output is as large as its directory
+                if self.writes_asset_files() {
+                    if let Some(dir) = result.executable.parent() {
+                        result.size = path_size(dir);
+                    }
+                }
+                if let Some(ref mut checkpoint) = checkpoint {
+                    // Keep the report of the pack stage for a resumed run
+                    result.stage_timings = phases.clone();
+                    result.tools = std::mem::take(&mut *self.lock_tools());
+                    result.downloads = std::mem::take(&mut *self.lock_downloads());
+                    checkpoint.complete(PackStage::Pack, Some(&result))?;
+                }
+                result.resumed_from = resume_from;
+                result
+            }
+        };
+
+        let after_started = self.stage_started(PackStage::AfterPack);
+
+        // Authenticode-sign the Windows executable through the signing backend
+        if let Some(ref signing) = self.config.signing {
+            if signing.signs_executables() && self.config.target_platform.exe_extension() == ".exe"
+            {
+                OverlaySigner::from_config(signing)?.sign_executable(&result.executable)?;
+            }
+        }
+
+        // Launch the signed executable with --self-check
+        result.smoke_test = self.run_smoke_test(&result.executable)?;
+
+        // Emit CDP test-run descriptor
+        self.write_test_descriptor(&result.executable)?;
+
+        // Emit uninstall scripts
+        if let Some(ref uninstall) = self.config.uninstall {
+            if uninstall.script {
+                crate::uninstall::write_scripts(&result.executable, &self.config, uninstall)?;
+            }
+        }
+
+        // After pack stage downloads and hooks
+        if let Some(ref vx_config) = self.config.vx {
+            if vx_config.enabled {
+                self.process_downloads_for_stage(vx_config, crate::DownloadStage::AfterPack)?;
+            }
+        }
+
+        // Run after_pack hooks (vx-aware)
+        self.run_hooks(crate::DownloadStage::AfterPack, &hook_env)?;
+
+        // Let plugins post-process the packed executable
+        let executable = result.executable.clone();
+        let cx = self.plugin_context(&executable);
+        for plugin in &self.plugins {
+            plugin
+                .after_pack(&cx, &mut result)
+                .map_err(|e| crate::plugin::plugin_error(plugin.as_ref(), "after_pack", e))?;
+        }
+
+        // Add the final executable to the update feed
+        if let Some(ref update_feed) = self.config.update_feed {
+            let signer = match self.config.signing {
+                Some(ref signing) => Some(OverlaySigner::from_config(signing)?),
+                None => None,
+            };
+            result.update_feed = Some(crate::update_feed::write(
+                &self.config,
+                update_feed,
+                &result.executable,
+                signer.as_ref(),
+                self.context.unix_time(),
+            )?);
+        }
+
+        // Sign a provenance statement over the final artifacts
+        if let Some(ref provenance) = self.config.provenance {
+            result.provenance = Some(self.write_provenance(provenance, &result, started_on)?);
+        }
+        self.stage_finished(PackStage::AfterPack, after_started, &mut phases);
+        if let Some(checkpoint) = checkpoint {
+            checkpoint.finish()?;
+        }
+
+        // Keep the artifact store within its configured limits
+        let store = self.store();
+        if store.has_limits() {
+            store.gc()?;
+        }
+
+        self.complete_report(&mut result, &phases, started)?;
+        if let Some(up_to_date) = up_to_date {
+            up_to_date.record(&result)?;
+        }
+
+        // Record pack statistics and compare against previous runs
+        if let Some(ref history) = self.config.history {
+            let stats = PackStats {
+                run_id: self.run_id.clone(),
+                timestamp: self.context.unix_time(),
+                mode: result.mode.clone(),
+                size: result.size,
+                asset_count: result.asset_count,
+                python_file_count: result.python_file_count,
+                duration_ms: elapsed_ms(started),
+                phases,
+            };
+            let history = HistoryConfig {
+                dir: self
+                    .context
+                    .cache_dir(history.dir.as_deref(), "pack-history"),
+                ..history.clone()
+            };
+            result.regressions =
+                crate::history::record_run(&self.config.output_name, &stats, &history)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Fill in the build report of `output`: asset index, stage timings,
+    /// tools and downloads of this run (added to those recorded for an
+    /// output restored from an earlier run); then export its telemetry
+    fn complete_report(
+        &self,
+        output: &mut PackOutput,
+        phases: &BTreeMap<String, u64>,
+        started: Instant,
+    ) -> PackResult<()> {
+        if let Some(archive) = OverlayReader::open(&output.executable)? {
+            output.assets = archive.entries().to_vec();
+        }
+        output.stage_timings.extend(phases.clone());
+        output.duration_ms = elapsed_ms(started);
+        output.tools.append(&mut self.lock_tools());
+        output.tools.insert(
+            "auroraview-pack".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        );
+        output.downloads.append(&mut self.lock_downloads());
+
+        if let Some(ref telemetry) = self.telemetry {
+            if let Err(e) = telemetry.export_pack(output) {
+                tracing::warn!("Failed to export pack telemetry: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Smoke-test the packed executable (`[debug.smoke_test]`)
+    ///
+    /// Skipped when the executable cannot run on this machine. A failure
+    /// fails the pack unless the test is not `required`.
+    fn run_smoke_test(&self, executable: &Path) -> PackResult<Option<SmokeTestReport>> {
+        let Some(ref smoke_test) = self.config.smoke_test else {
+            return Ok(None);
+        };
+        if !smoke_test.enabled {
+            return Ok(None);
+        }
+        let runs_here = match self.config.build_target {
+            Some(target) => self.context.is_current(&target),
+            None => self.config.target_platform == TargetPlatform::current(),
+        };
+        if !runs_here || !executable.is_file() {
+            tracing::warn!(
+                "Skipping smoke test: {} cannot run on this machine",
+                executable.display()
+            );
+            return Ok(None);
+        }
+
+        tracing::info!("Smoke-testing {}", executable.display());
+        let report = crate::smoke_test::run(executable, smoke_test, self.runner.as_ref());
+        if !report.passed {
+            let message = format!("Smoke test of {} failed: {}", executable.display(), report);
+            if smoke_test.required {
+                return Err(PackError::TestRun(message));
+            }
+            tracing::warn!("{}", message);
+        }
+        Ok(Some(report))
+    }
+
+    fn lock_tools(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, String>> {
+        self.tools.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_downloads(&self) -> std::sync::MutexGuard<'_, Vec<ResourceDescriptor>> {
+        self.downloads.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record the version of a tool used for the build report
+    fn record_tool(&self, name: &str, version: &str) {
+        self.lock_tools()
+            .insert(name.to_string(), version.to_string());
+    }
+
+    /// Record a downloaded file for the build report
+    fn record_download(&self, name: &str, uri: &str, path: &Path) -> PackResult<()> {
+        let download = ResourceDescriptor::from_file(name, path)?.with_uri(uri);
+        self.lock_downloads().push(download);
+        Ok(())
+    }
+
+    /// Prepare stage: frontend dependencies, `before_collect` and
+    /// `before_pack` hooks and downloads
+    fn prepare(&self, hook_env: &HookEnv) -> PackResult<()> {
+        // Install frontend dependencies (not needed when a dev server serves the frontend)
+        if let Some(ref deps) = self.config.frontend_dependencies {
+            if !self.config.uses_dev_server() {
+                crate::frontend_deps::install(deps, self.hook_limits(), hook_env, &self.store())?;
+            }
+        }
 
         // Run before_collect hooks (vx-aware)
-        self.run_hooks(crate::DownloadStage::BeforeCollect)?;
+        self.run_hooks(crate::DownloadStage::BeforeCollect, hook_env)?;
 
         // Process downloads if vx is enabled
         if let Some(ref vx_config) = self.config.vx {
@@ -98,25 +786,151 @@ impl Packer {
             }
         }
 
-        let result = match &self.config.mode {
-            PackMode::Url { .. } | PackMode::Frontend { .. } => self.pack_simple(),
-            PackMode::FullStack {
-                frontend_path,
-                python,
-            } => self.pack_fullstack(frontend_path, python),
-        }?;
+        // Run before_pack hooks (vx-aware), after the downloads they may use
+        self.run_hooks(crate::DownloadStage::BeforePack, hook_env)
+    }
 
-        // After pack stage downloads and hooks
-        if let Some(ref vx_config) = self.config.vx {
-            if vx_config.enabled {
-                self.process_downloads_for_stage(vx_config, crate::DownloadStage::AfterPack)?;
+    /// Checkpoint of this configuration, with `[build.resume]`
+    fn open_checkpoint(&self) -> PackResult<Option<Checkpoint>> {
+        let Some(ref resume) = self.config.resume else {
+            return Ok(None);
+        };
+        if !resume.enabled {
+            return Ok(None);
+        }
+        let inputs = crate::resume::input_stamp(&self.plan()?);
+        Ok(Some(Checkpoint::open(
+            &self.checkpoint_dir(resume),
+            &self.config.config_hash()?,
+            inputs,
+        )))
+    }
+
+    /// Up-to-date record of the executable, with `[build] skip_unchanged`
+    fn up_to_date_record(&self) -> PackResult<Option<UpToDate>> {
+        if !self.config.skip_unchanged {
+            return Ok(None);
+        }
+        let build_hash =
+            crate::resume::build_hash(&self.config, &self.plan()?, &self.launcher_path()?)?;
+        let dir = self.checkpoint_dir(&self.config.resume.clone().unwrap_or_default());
+        Ok(Some(UpToDate::new(
+            &dir,
+            &self.exe_dir().join(self.get_exe_name()),
+            build_hash,
+        )))
+    }
+
+    /// Write the signed provenance of a finished pack run
+    fn write_provenance(
+        &self,
+        provenance: &ProvenanceConfig,
+        output: &PackOutput,
+        started_on: u64,
+    ) -> PackResult<PathBuf> {
+        let signing = self.config.signing.as_ref().ok_or_else(|| {
+            PackError::Config("[build.provenance] requires [signing]".to_string())
+        })?;
+
+        let name = |path: &Path| {
+            path.file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let mut subjects = vec![(name(&output.executable), output.executable.clone())];
+        if self.config.overlay_placement == OverlayPlacement::Sidecar {
+            let sidecar = sidecar_path(&output.executable);
+            subjects.push((name(&sidecar), sidecar));
+        }
+        // Every file of a onedir output ships with the executable
+        if let (OutputLayout::Onedir, Some(dir)) =
+            (self.config.layout(), output.executable.parent())
+        {
+            for entry in walkdir::WalkDir::new(dir)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+            {
+                let path = entry.into_path();
+                let relative = path
+                    .strip_prefix(dir)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let known = subjects.iter().any(|(_, subject)| *subject == path);
+                if !known && !relative.ends_with(".intoto.jsonl") {
+                    subjects.push((relative, path));
+                }
             }
         }
 
-        // Run after_pack hooks (vx-aware)
-        self.run_hooks(crate::DownloadStage::AfterPack)?;
+        let launcher = self.launcher_path()?;
+        let mut inputs = vec![ResourceDescriptor::from_file("launcher", &launcher)?
+            .with_uri(launcher.display().to_string())];
+        let plan = self.plan()?;
+        for asset in &plan.assets {
+            inputs.push(
+                ResourceDescriptor::from_file(&asset.path, &asset.source_path)?
+                    .with_uri(asset.source_path.display().to_string()),
+            );
+        }
+        for download in &plan.downloads {
+            if let Some(ref cached) = download.cached {
+                inputs.push(
+                    ResourceDescriptor::from_file(&download.name, cached)?
+                        .with_uri(download.url.clone()),
+                );
+            }
+        }
 
-        Ok(result)
+        let target = match self.config.build_target {
+            Some(target) => target.to_string(),
+            None => BuildTarget::current()
+                .map(|t| t.to_string())
+                .unwrap_or_else(|_| format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)),
+        };
+        let run = ProvenanceRun {
+            run_id: &self.run_id,
+            started_on,
+            finished_on: self.context.unix_time(),
+            parameters: ExternalParameters {
+                mode: output.mode.clone(),
+                output_name: self.config.output_name.clone(),
+                app_version: self.config.app_version.clone(),
+                target,
+                manifest_sha256: self.config.manifest_sha256.clone(),
+            },
+            subjects,
+            inputs,
+        };
+        crate::provenance::write(provenance, run, &OverlaySigner::from_config(signing)?)
+    }
+
+    /// Write the CDP test-run descriptor next to the packed executable
+    fn write_test_descriptor(&self, executable: &Path) -> PackResult<()> {
+        let Some(ref test_run) = self.config.test_run else {
+            return Ok(());
+        };
+        if !test_run.enabled {
+            return Ok(());
+        }
+
+        let port = self.config.remote_debugging_port.ok_or_else(|| {
+            PackError::Config("Test run descriptor requires remote_debugging_port".to_string())
+        })?;
+
+        let descriptor = TestRunDescriptor {
+            executable: executable.to_path_buf(),
+            port,
+            startup_timeout: test_run.startup_timeout,
+            health_url: test_run.health_url.clone(),
+        };
+        let path = TestRunDescriptor::path_for(executable);
+        descriptor.save(&path)?;
+
+        tracing::info!("Wrote test-run descriptor: {}", path.display());
+        Ok(())
     }
 
     /// Process downloads for a specific stage
@@ -125,20 +939,13 @@ impl Packer {
         vx_config: &crate::VxConfig,
         stage: crate::DownloadStage,
     ) -> PackResult<()> {
-        use crate::Downloader;
-
         let entries = self.build_download_entries();
         if entries.is_empty() {
             tracing::debug!("No downloads configured");
             return Ok(());
         }
 
-        let downloader = Downloader::new(&vx_config.cache_dir)
-            .allow_insecure(vx_config.allow_insecure)
-            .allowed_domains(vx_config.allowed_domains.clone())
-            .block_unknown_domains(vx_config.block_unknown_domains)
-            .require_checksum(vx_config.require_checksum);
-
+        let downloader = self.downloader(vx_config);
         for entry in entries.iter().filter(|d| d.stage == stage) {
             self.process_download_entry(&downloader, entry)?;
         }
@@ -146,6 +953,16 @@ impl Packer {
         Ok(())
     }
 
+    /// Downloader applying the `[vx]` security policy
+    fn downloader(&self, vx_config: &crate::VxConfig) -> crate::Downloader {
+        crate::Downloader::new(self.context.vx_cache_dir(vx_config))
+            .with_store(self.store())
+            .allow_insecure(vx_config.allow_insecure)
+            .allowed_domains(vx_config.allowed_domains.clone())
+            .block_unknown_domains(vx_config.block_unknown_domains)
+            .require_checksum(vx_config.require_checksum)
+    }
+
     /// Process a single download entry
     fn process_download_entry(
         &self,
@@ -157,6 +974,7 @@ impl Packer {
         // Download the file
         let downloaded_path =
             downloader.download(&entry.name, &entry.url, entry.checksum.as_deref())?;
+        self.record_download(&entry.name, &entry.url, &downloaded_path)?;
 
         // Extract if needed
         if entry.extract {
@@ -197,84 +1015,433 @@ impl Packer {
     }
 
     /// Run hook commands for a given stage
-    fn run_hooks(&self, stage: crate::DownloadStage) -> PackResult<()> {
+    ///
+    /// `env` is applied to every command (e.g., provisioned toolchains).
+    fn run_hooks(&self, stage: crate::DownloadStage, env: &HookEnv) -> PackResult<()> {
         let hooks = match &self.config.hooks {
             Some(h) => h,
             None => return Ok(()),
         };
 
-        let mut commands: Vec<String> = match stage {
-            crate::DownloadStage::BeforeCollect => hooks.before_collect.clone(),
-            crate::DownloadStage::AfterPack => hooks.after_pack.clone(),
-            crate::DownloadStage::BeforePack => Vec::new(),
-        };
+        let commands = shell_hook_commands(hooks, stage);
+        let argv_hooks: Vec<&crate::HookCommand> =
+            hooks.run.iter().filter(|h| h.stage == stage).collect();
 
-        let vx_stage_cmds: Vec<String> = match stage {
-            crate::DownloadStage::BeforeCollect => hooks.vx.before_collect.clone(),
-            crate::DownloadStage::AfterPack => hooks.vx.after_pack.clone(),
-            crate::DownloadStage::BeforePack => Vec::new(),
-        };
+        if commands.is_empty() && argv_hooks.is_empty() {
+            return Ok(());
+        }
 
-        let use_vx = hooks.use_vx || !vx_stage_cmds.is_empty();
+        tracing::info!(
+            "Running {} hook command(s) for stage {:?}",
+            commands.len() + argv_hooks.len(),
+            stage
+        );
 
-        if use_vx {
-            commands = commands.into_iter().map(|c| format!("vx {}", c)).collect();
+        let limits = self.hook_limits();
+        let vars = self.hook_vars();
+
+        for cmd in commands {
+            let cmd = vars.expand(&cmd);
+            let mut command = crate::hooks::shell_command(&cmd);
+            command.current_dir(self.context.base_dir());
+            env.apply(&mut command);
+            crate::hooks::run_hook(command, &cmd, limits)?;
         }
 
-        for cmd in vx_stage_cmds {
-            commands.push(format!("vx {}", cmd));
+        for hook in argv_hooks {
+            let limits = HookLimits {
+                timeout: hook
+                    .timeout_secs
+                    .map(Duration::from_secs)
+                    .or(limits.timeout),
+                idle_timeout: hook
+                    .idle_timeout_secs
+                    .map(Duration::from_secs)
+                    .or(limits.idle_timeout),
+                ..limits
+            };
+            self.run_hook_command(&expand_hook_command(hook, &vars), hooks.use_vx, limits, env)?;
         }
 
-        if commands.is_empty() {
-            return Ok(());
+        Ok(())
+    }
+
+    /// Build variables hook commands can reference (`${OUTPUT_EXE}`, ...)
+    fn hook_vars(&self) -> HookVars {
+        let target = match self.config.build_target {
+            Some(target) => target.to_string(),
+            None => crate::BuildTarget::current()
+                .map(|target| target.to_string())
+                .unwrap_or_else(|_| format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)),
+        };
+        // Matrix targets suffix the output name with the target
+        let package_name = self
+            .config
+            .output_name
+            .strip_suffix(&format!("-{}", target))
+            .filter(|_| self.config.build_target.is_some())
+            .unwrap_or(&self.config.output_name)
+            .to_string();
+        HookVars {
+            output_dir: self.config.output_dir.clone(),
+            output_exe: self.exe_dir().join(self.get_exe_name()),
+            package_name,
+            version: self.config.app_version.clone().unwrap_or_default(),
+            target,
+        }
+    }
+
+    /// Provision pinned Go/Rust toolchains that are missing on this machine
+    ///
+    /// Returns the environment hooks run with: PATH with the toolchain bin
+    /// directories prepended, plus rustup variables, on top of the packer's
+    /// environment or, with `[hooks.isolation]`, of a controlled one.
+    fn provision_toolchains(&self) -> PackResult<HookEnv> {
+        let mut provisioned = Vec::new();
+        if let Some(ref go) = self.config.go_toolchain {
+            provisioned.push(("go", crate::toolchain::ensure_go(go, &self.store())?));
+            self.record_tool("go", &go.version);
         }
+        if let Some(ref rust) = self.config.rust_toolchain {
+            provisioned.push(("cargo", crate::toolchain::ensure_rust(rust, &self.store())?));
+            self.record_tool("rust", &rust.version);
+        }
+
+        let isolation = self
+            .config
+            .hooks
+            .as_ref()
+            .and_then(|h| h.isolation.as_ref());
+        let Some(isolation) = isolation else {
+            let bin_dirs: Vec<&Path> = provisioned
+                .iter()
+                .filter_map(|(_, t)| t.bin_dir.as_deref())
+                .collect();
+            if bin_dirs.is_empty() {
+                return Ok(HookEnv::default());
+            }
+            let mut env = vec![("PATH".to_string(), crate::toolchain::path_with(&bin_dirs)?)];
+            env.extend(provisioned.into_iter().flat_map(|(_, t)| t.env));
+            return Ok(HookEnv::inherit(env));
+        };
 
+        // Pinned toolchains first (system ones where the pin is satisfied),
+        // then the host tools the configuration itself relies on
+        let mut tool_dirs: Vec<PathBuf> = Vec::new();
+        for (program, toolchain) in &provisioned {
+            tool_dirs.extend(
+                toolchain
+                    .bin_dir
+                    .clone()
+                    .or_else(|| crate::hooks::program_dir(program)),
+            );
+        }
+        tool_dirs.extend(
+            self.hook_host_tools()
+                .into_iter()
+                .filter_map(crate::hooks::program_dir),
+        );
+        let mut seen = std::collections::HashSet::new();
+        tool_dirs.retain(|dir| seen.insert(dir.clone()));
+
+        let env = provisioned.into_iter().flat_map(|(_, t)| t.env).collect();
+        let hook_env = HookEnv::isolated(isolation, &tool_dirs, env)?;
         tracing::info!(
-            "Running {} hook command(s) for stage {:?}",
-            commands.len(),
-            stage
+            "Running hooks in an isolated environment ({} variables)",
+            hook_env.vars().len()
         );
+        Ok(hook_env)
+    }
 
-        for cmd in commands {
-            self.run_shell_command(&cmd)?;
+    /// Host tools isolated hook commands still need (vx, package managers)
+    fn hook_host_tools(&self) -> Vec<&'static str> {
+        let mut tools = Vec::new();
+        if let Some(ref hooks) = self.config.hooks {
+            let uses_vx = hooks.use_vx
+                || !hooks.vx.before_collect.is_empty()
+                || !hooks.vx.before_pack.is_empty()
+                || !hooks.vx.after_pack.is_empty()
+                || hooks.run.iter().any(|h| h.use_vx);
+            if uses_vx {
+                tools.push("vx");
+            }
+        }
+        if let Some(ref deps) = self.config.frontend_dependencies {
+            let dir = deps.dir.as_deref().unwrap_or(Path::new("."));
+            let manager = deps
+                .manager
+                .or_else(|| crate::PackageManager::detect(dir))
+                .unwrap_or(crate::PackageManager::Npm);
+            tools.extend(["node", manager.as_str()]);
         }
+        tools
+    }
 
-        Ok(())
+    /// Time limits for hook-like commands from `[hooks]`
+    fn hook_limits(&self) -> HookLimits {
+        match self.config.hooks {
+            Some(ref hooks) => HookLimits::from_secs(
+                hooks.timeout_secs,
+                hooks.idle_timeout_secs,
+                hooks.heartbeat_secs,
+            ),
+            None => HookLimits::from_secs(None, None, None),
+        }
     }
 
-    /// Run a shell command with platform-specific shell
-    fn run_shell_command(&self, cmd: &str) -> PackResult<()> {
-        let status = if cfg!(windows) {
-            Command::new("cmd").args(["/C", cmd]).status()
+    /// Run an argv-based hook command without a shell
+    fn run_hook_command(
+        &self,
+        hook: &crate::HookCommand,
+        use_vx: bool,
+        limits: HookLimits,
+        env: &HookEnv,
+    ) -> PackResult<()> {
+        let mut command = if use_vx || hook.use_vx {
+            let mut command = Command::new("vx");
+            command.arg(&hook.program);
+            command
         } else {
-            Command::new("sh").args(["-c", cmd]).status()
+            Command::new(&hook.program)
+        };
+        command.args(&hook.args);
+        env.apply(&mut command);
+        command.envs(&hook.env);
+        match hook.cwd {
+            Some(ref cwd) => command.current_dir(self.context.resolve(cwd)),
+            None => command.current_dir(self.context.base_dir()),
+        };
+
+        crate::hooks::run_hook(command, &hook.display(), limits)
+    }
+
+    /// Remove pack caches and work directories for `scope`
+    ///
+    /// With `dry_run`, nothing is deleted; the report lists what would be
+    /// removed and the reclaimable size.
+    pub fn clean(&self, scope: CleanScope, dry_run: bool) -> PackResult<CleanReport> {
+        let report = crate::clean::clean(&self.config, &self.context, scope, dry_run)?;
+        tracing::info!(
+            "{} {} cache entries ({:.2} MB)",
+            if dry_run { "Found" } else { "Cleaned" },
+            report.entries.len(),
+            report.reclaimed() as f64 / (1024.0 * 1024.0)
+        );
+        Ok(report)
+    }
+
+    /// Read the overlay of a packed executable: config, build metadata,
+    /// Python runtime and the asset index with sizes
+    pub fn inspect(executable: &Path) -> PackResult<InspectReport> {
+        crate::inspect::inspect(executable)
+    }
+
+    /// Compare two packed executables: added, removed and changed assets
+    /// with size deltas, and configuration, metadata and Python runtime
+    /// differences
+    pub fn diff(old: &Path, new: &Path) -> PackResult<PackDiff> {
+        crate::diff::diff(old, new)
+    }
+
+    /// Extract everything a packed executable bundles into `dir`
+    ///
+    /// Assets go to `dir/assets/`; the [`InspectReport`] is written to
+    /// `dir/overlay.json`.
+    pub fn unpack(executable: &Path, dir: &Path) -> PackResult<InspectReport> {
+        crate::inspect::unpack(executable, dir)
+    }
+
+    /// Remove this app's runtime extractions beyond the `keep_latest_n`
+    /// most recently used (see [`RuntimeCache`](crate::RuntimeCache))
+    pub fn clean_stale_caches(&self, keep_latest_n: usize) -> PackResult<CleanReport> {
+        crate::RuntimeCache::for_app(&self.config.identity()).clean_stale_caches(keep_latest_n)
+    }
+
+    /// Check the environment for everything this configuration needs
+    ///
+    /// Returns a checklist instead of failing on the first problem, so all
+    /// missing tools, certificates and unreachable hosts show up at once.
+    pub fn doctor(&self) -> DoctorReport {
+        let mut report = crate::doctor::diagnose(&self.config, &self.context);
+        report.checks.insert(
+            0,
+            DoctorCheck::from_result("config", self.validate(), "configuration is valid"),
+        );
+        report
+    }
+
+    /// Work out what [`pack`](Self::pack) would do, without writing anything
+    ///
+    /// Validates the configuration, checks downloads against the `[vx]`
+    /// policy and the local cache (nothing is fetched), and enumerates the
+    /// assets to embed. Hook-collected files are not known before the hooks
+    /// run and are not listed.
+    pub fn plan(&self) -> PackResult<PackPlan> {
+        self.validate()?;
+
+        let estimate = crate::staging::estimate_required_space(&self.config, &self.context);
+        let mut plan = PackPlan {
+            mode: self.config.mode.name().to_string(),
+            executable: self.exe_dir().join(self.get_exe_name()),
+            estimated_output_size: estimate.output,
+            estimated_staging_size: estimate.staging,
+            ..Default::default()
+        };
+
+        // Frontend assets (at the overlay root in frontend mode)
+        if let Some(frontend_path) = self.config.mode.frontend_path() {
+            if !self.config.uses_dev_server() {
+                let prefix = match self.config.mode {
+                    PackMode::Frontend { .. } => "",
+                    _ => "frontend/",
+                };
+                for (path, source) in self.frontend_builder(frontend_path).list()? {
+                    plan.push(
+                        format!("{}{}", prefix, path),
+                        &source,
+                        AssetSource::Frontend,
+                    )?;
+                }
+            }
+        }
+
+        match self.config.mode {
+            PackMode::FullStack { ref python, .. } => {
+                for include_path in &python.include_paths {
+                    plan.add_files(include_path, "python/", AssetSource::Python, |path| {
+                        let is_code = [".py", ".pyd", ".so"].iter().any(|ext| path.ends_with(ext));
+                        is_code && !python_path_excluded(path, &python.exclude)
+                    })?;
+                }
+                for bin_path in python.external_bin.iter().filter(|p| p.exists()) {
+                    plan.add_files(bin_path, "python/bin/", AssetSource::Resource, |_| true)?;
+                }
+
+                plan.python_packages = python.packages.clone();
+                if let Some(ref requirements) = python.requirements {
+                    let content = fs::read_to_string(requirements)?;
+                    plan.python_packages.extend(
+                        content
+                            .lines()
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                            .map(str::to_string),
+                    );
+                }
+
+                match python.strategy {
+                    BundleStrategy::PyOxidizer => {
+                        plan.require_tool("pyoxidizer", "strategy = \"pyoxidizer\"")
+                    }
+                    BundleStrategy::Embedded | BundleStrategy::System => plan.require_tool(
+                        format!("python@{}", python.version),
+                        format!(
+                            "strategy = \"{}\" uses the host Python",
+                            python.strategy.as_str()
+                        ),
+                    ),
+                    _ => {}
+                }
+                if !plan.python_packages.is_empty() && !python.strategy.bundles_standalone() {
+                    plan.require_tool("uv", "installs Python packages");
+                }
+                if python.protection.enabled {
+                    plan.require_tool("C compiler", "code protection compiles Python sources");
+                }
+            }
+            PackMode::Process { ref launch, .. } => {
+                plan.push(launch.command.clone(), &launch.binary, AssetSource::Process)?;
+            }
+            _ => {}
         }
-        .map_err(|e| PackError::Config(format!("Failed to run hook command '{}': {}", cmd, e)))?;
 
-        if !status.success() {
-            return Err(PackError::Config(format!(
-                "Hook command failed (exit code {:?}): {}",
-                status.code(),
-                cmd
-            )));
+        if let Some(ref deps) = self.config.frontend_dependencies {
+            if !self.config.uses_dev_server() {
+                let dir = deps.dir.as_deref().unwrap_or(Path::new("."));
+                let manager = deps
+                    .manager
+                    .or_else(|| crate::PackageManager::detect(dir))
+                    .unwrap_or(crate::PackageManager::Npm);
+                plan.require_tool(manager.as_str(), "installs frontend dependencies");
+            }
         }
 
-        Ok(())
+        // Downloads are only processed with vx enabled
+        if let Some(ref vx_config) = self.config.vx {
+            if vx_config.enabled {
+                for tool in &vx_config.ensure {
+                    plan.require_tool(tool.clone(), "[vx] ensure");
+                }
+                let downloader = self.downloader(vx_config);
+                for entry in self.build_download_entries() {
+                    let cached =
+                        downloader.resolve(&entry.name, &entry.url, entry.checksum.as_deref())?;
+                    plan.downloads.push(PlannedDownload {
+                        verified: entry.checksum.is_some(),
+                        name: entry.name,
+                        url: entry.url,
+                        stage: entry.stage,
+                        dest: entry.dest,
+                        cached,
+                    });
+                }
+            }
+        }
+
+        if let Some(ref hooks) = self.config.hooks {
+            let vars = self.hook_vars();
+            for stage in [
+                crate::DownloadStage::BeforeCollect,
+                crate::DownloadStage::BeforePack,
+                crate::DownloadStage::AfterPack,
+            ] {
+                plan.hooks.extend(
+                    shell_hook_commands(hooks, stage)
+                        .iter()
+                        .map(|cmd| vars.expand(cmd)),
+                );
+                for hook in hooks.run.iter().filter(|h| h.stage == stage) {
+                    let hook = expand_hook_command(hook, &vars);
+                    let vx = if hook.use_vx || hooks.use_vx {
+                        "vx "
+                    } else {
+                        ""
+                    };
+                    plan.hooks.push(
+                        format!("{}{} {}", vx, hook.program, hook.args.join(" "))
+                            .trim_end()
+                            .to_string(),
+                    );
+                }
+            }
+            if plan.hooks.iter().any(|h| h.starts_with("vx ")) {
+                plan.require_tool("vx", "hooks run via vx");
+            }
+        }
+
+        tracing::info!(
+            "Pack plan: {} assets ({} bytes), {} downloads, {} hooks",
+            plan.assets.len(),
+            plan.asset_size(),
+            plan.downloads.len(),
+            plan.hooks.len()
+        );
+        Ok(plan)
     }
 
     /// Pack URL or Frontend mode (simple overlay approach)
     fn pack_simple(&self) -> PackResult<PackOutput> {
         // Determine output path
         let exe_name = self.get_exe_name();
-        let output_path = self.config.output_dir.join(&exe_name);
+        let output_path = self.exe_dir().join(&exe_name);
 
         tracing::info!("Packing to: {}", output_path.display());
 
-        // Get the current executable
-        let current_exe = std::env::current_exe()?;
+        // Get the launcher executable
+        let current_exe = self.launcher_path()?;
 
         // Copy executable to output
-        fs::copy(&current_exe, &output_path)?;
+        self.copy_launcher(&current_exe, &output_path)?;
 
         // Build download entries (includes synthetic vx runtime if configured)
         let download_entries = self.build_download_entries();
@@ -285,37 +1452,501 @@ impl Packer {
 
         // Bundle assets if in frontend mode
         let asset_count = if let PackMode::Frontend { ref path } = self.config.mode {
-            let bundle = BundleBuilder::new(path).build()?;
+            let bundle = self.build_frontend_bundle(path)?;
             let count = bundle.len();
 
-            for (path, content) in bundle.into_assets() {
-                overlay.add_asset(path, content);
-            }
+            for (path, content) in bundle.into_assets() {
+                overlay.add_asset(path, content);
+            }
+
+            count
+        } else {
+            0
+        };
+
+        // Embed downloaded artifacts into overlay
+        self.embed_downloads_into_overlay(&mut overlay, &download_entries)?;
+
+        // Embed About-screen data (changelog, license, third-party notices)
+        self.embed_about(&mut overlay, &Sbom::default())?;
+        self.embed_eula(&mut overlay)?;
+        self.inject_preview_watermark(&mut overlay);
+
+        // Embed CA bundles and client certificates
+        self.embed_network_certificates(&mut overlay)?;
+
+        // Embed quick action icons
+        self.embed_shortcut_icons(&mut overlay)?;
+
+        // Apply Windows resource modifications BEFORE writing overlay
+
+        // rcedit cannot handle executables with overlay data appended
+        #[cfg(target_os = "windows")]
+        self.apply_windows_resources(&output_path)?;
+
+        // Shrink bundled native binaries
+        self.optimize_binaries(&mut overlay)?;
+
+        // Embed the debug symbol index
+        self.embed_symbol_index(&mut overlay)?;
+
+        // Write overlay to executable (must be after rcedit modifications)
+        let dedup = self.write_overlay(&output_path, &mut overlay)?;
+
+        // Get final size
+        let size = fs::metadata(&output_path)?.len();
+
+        tracing::info!(
+            "Pack complete: {} ({:.2} MB)",
+            output_path.display(),
+            size as f64 / (1024.0 * 1024.0)
+        );
+
+        Ok(PackOutput {
+            executable: output_path,
+            size,
+            asset_count,
+            python_file_count: 0,
+            mode: self.config.mode.name().to_string(),
+            regressions: Vec::new(),
+            dedup,
+            incremental: self.take_incremental_stats(),
+            update_feed: None,
+            provenance: None,
+            resumed_from: None,
+            up_to_date: false,
+            assets: Vec::new(),
+            stage_timings: BTreeMap::new(),
+            duration_ms: 0,
+            tools: BTreeMap::new(),
+            downloads: Vec::new(),
+            smoke_test: None,
+        })
+    }
+
+    /// Copy the launcher executable to the output path (retried on file locks)
+    fn copy_launcher(&self, current_exe: &Path, dest: &Path) -> PackResult<()> {
+        self.config
+            .file_retry
+            .run("Copying launcher executable", dest, || {
+                fs::copy(current_exe, dest)?;
+                Ok(())
+            })?;
+        self.split_symbols("launcher", current_exe, dest)
+    }
+
+    /// Strip a bundled binary and collect its debug symbols (`[build.symbols]`)
+    ///
+    /// `source` is the binary as built, `bundled` the copy that ships.
+    fn split_symbols(&self, name: &str, source: &Path, bundled: &Path) -> PackResult<()> {
+        let Some(ref symbols) = self.config.symbols else {
+            return Ok(());
+        };
+        let entry =
+            crate::symbols::split_symbols(symbols, &self.symbols_dir(), name, source, bundled)?;
+        self.lock_symbol_entries().push(entry);
+        Ok(())
+    }
+
+    /// Directory of the symbols artifact
+    fn symbols_dir(&self) -> PathBuf {
+        self.config
+            .symbols
+            .as_ref()
+            .and_then(|s| s.dir.clone())
+            .unwrap_or_else(|| {
+                self.config
+                    .output_dir
+                    .join(format!("{}-symbols", self.config.output_name))
+            })
+    }
+
+    fn lock_symbol_entries(&self) -> std::sync::MutexGuard<'_, Vec<SymbolEntry>> {
+        self.symbol_entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Strip / UPX-compress native binaries among the overlay assets
+    fn optimize_binaries(&self, overlay: &mut OverlayData) -> PackResult<()> {
+        let Some(ref optimize) = self.config.optimize else {
+            return Ok(());
+        };
+        let staging = self.staging_dir("optimize")?;
+        crate::optimize::optimize_assets(optimize, &mut overlay.assets, staging.path())?;
+        Ok(())
+    }
+
+    /// Write the symbol index next to the symbols and embed it in the overlay
+    fn embed_symbol_index(&self, overlay: &mut OverlayData) -> PackResult<()> {
+        let Some(ref symbols) = self.config.symbols else {
+            return Ok(());
+        };
+        let index = SymbolIndex {
+            app: self.config.output_name.clone(),
+            symbol_server: symbols.symbol_server.clone(),
+            entries: self.lock_symbol_entries().clone(),
+        };
+        let dir = self.symbols_dir();
+        crate::symbols::write_index(&dir, &index)?;
+        tracing::info!(
+            "Wrote symbols for {} binaries to {}",
+            index.entries.len(),
+            dir.display()
+        );
+        overlay.add_asset(
+            SYMBOLS_INFO_PATH.to_string(),
+            serde_json::to_vec_pretty(&index)?,
+        );
+        Ok(())
+    }
+
+    /// Append the overlay to an executable (retried on file locks)
+    ///
+    /// A failed attempt may leave a partial overlay behind, so the file is
+    /// truncated back to its original length before each retry. Returns the
+    /// space saved by storing identical assets once.
+    fn write_overlay(&self, exe_path: &Path, overlay: &mut OverlayData) -> PackResult<DedupStats> {
+        self.write_overlay_with_files(exe_path, overlay, &[])
+    }
+
+    /// What plugins see of this run
+    fn plugin_context<'a>(&'a self, executable: &'a Path) -> PluginContext<'a> {
+        PluginContext {
+            config: &self.config,
+            context: &self.context,
+            run_id: &self.run_id,
+            executable,
+        }
+    }
+
+    /// Build cache reuse of the last overlay written, if incremental
+    fn take_incremental_stats(&self) -> Option<IncrementalStats> {
+        self.incremental_stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
+    /// Append the overlay plus assets streamed from files (retried on file locks)
+    ///
+    /// Large assets (e.g., the Python runtime archive) are compressed
+    /// straight from disk instead of being loaded into `overlay`. With
+    /// `[bundle] overlay = "sidecar"` the overlay goes to `<name>.avpk`
+    /// next to the executable instead. Plugins see the overlay first.
+    fn write_overlay_with_files(
+        &self,
+        exe_path: &Path,
+        overlay: &mut OverlayData,
+        files: &[(&str, &Path)],
+    ) -> PackResult<DedupStats> {
+        let files = if self.plugins.is_empty() {
+            files.to_vec()
+        } else {
+            let cx = self.plugin_context(exe_path);
+            let files = crate::plugin::process_assets(&self.plugins, &cx, overlay, files)?;
+            for plugin in &self.plugins {
+                plugin.before_overlay_write(&cx, overlay).map_err(|e| {
+                    crate::plugin::plugin_error(plugin.as_ref(), "before_overlay_write", e)
+                })?;
+            }
+            files
+        };
+        let overlay = &*overlay;
+        let files = files.as_slice();
+        let signer = match self.config.signing {
+            Some(ref signing) => Some(OverlaySigner::from_config(signing)?),
+            None => None,
+        };
+        // Onedir output keeps the assets as plain files next to the executable
+        let asset_dir = match self.writes_asset_files() {
+            true => exe_path.parent().map(Path::to_path_buf),
+            false => None,
+        };
+        // Progress over the uncompressed bytes of every asset
+        let tracker = || -> PackResult<Option<ProgressTracker>> {
+            let Some(ref observer) = self.progress else {
+                return Ok(None);
+            };
+            let mut total: u64 = overlay
+                .assets
+                .iter()
+                .map(|(_, c)| c.len() as u64)
+                .sum::<u64>();
+            for (_, file) in files {
+                total += fs::metadata(file)?.len();
+            }
+            Ok(Some(ProgressTracker::new(
+                observer.clone(),
+                "assets",
+                Some(total),
+            )))
+        };
+        // Hashes of the plain files, for the runtime to check them
+        let written = match asset_dir {
+            Some(ref dir) => Some(write_asset_files(dir, overlay, files, tracker()?.as_ref())?),
+            None => None,
+        };
+
+        let sidecar;
+        let exe_path = match self.config.overlay_placement {
+            OverlayPlacement::Embedded => {
+                // Drop the sidecar of an earlier pack so it cannot go stale
+                let stale = sidecar_path(exe_path);
+                if stale.is_file() {
+                    fs::remove_file(&stale)?;
+                }
+                exe_path
+            }
+            OverlayPlacement::Sidecar => {
+                sidecar = sidecar_path(exe_path);
+                fs::write(&sidecar, [])?;
+                tracing::info!("Writing overlay to sidecar {}", sidecar.display());
+                sidecar.as_path()
+            }
+        };
+        let build_cache = match self.config.incremental {
+            Some(ref incremental) if incremental.enabled => {
+                let max_size = incremental.max_size_mb.map(|mb| mb * 1024 * 1024);
+                Some(BuildCache::open(self.build_cache_dir(incremental))?.with_max_size(max_size))
+            }
+            _ => None,
+        };
+
+        let original_len = fs::metadata(exe_path)?.len();
+        let dedup = self
+            .config
+            .file_retry
+            .run("Writing overlay", exe_path, || {
+                if fs::metadata(exe_path)?.len() != original_len {
+                    fs::OpenOptions::new()
+                        .write(true)
+                        .open(exe_path)?
+                        .set_len(original_len)?;
+                }
+                let mut stream = OverlayWriter::begin(exe_path, overlay.config.compression_level)?
+                    .with_codecs(self.config.codecs);
+                if let Some(threads) = self.config.threads {
+                    stream = stream.with_threads(threads)?;
+                }
+                if let Some(ref signer) = signer {
+                    stream = stream.with_signer(signer.clone());
+                }
+                if let Some(ref cache) = build_cache {
+                    stream = stream.with_build_cache(cache.clone());
+                }
+                if asset_dir.is_none() {
+                    if let Some(tracker) = tracker()? {
+                        stream = stream.with_progress(tracker);
+                    }
+                }
+                for (key, value) in overlay.metadata.iter().chain(&self.config.build_metadata) {
+                    stream.set_metadata(key.clone(), value.clone());
+                }
+                if asset_dir.is_none() {
+                    stream.append_assets(&overlay.assets)?;
+                    stream.append_assets_from_files(files)?;
+                }
+                // Hashes of every asset, for the runtime to check its
+                // extracted copies
+                let integrity = match written {
+                    Some(ref manifest) => manifest.clone(),
+                    None => stream.integrity_manifest(),
+                };
+                if !integrity.files.is_empty() {
+                    stream.append_integrity_manifest(&integrity)?;
+                }
+                let dedup = stream.dedup_stats();
+                stream.finish(&overlay.config)?;
+                Ok(dedup)
+            })?;
+
+        if let Some(cache) = build_cache {
+            let stats = cache.stats();
+            *self
+                .incremental_stats
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = Some(stats);
+            tracing::info!(
+                "Incremental pack: {} assets reused ({:.2} MB), {} compressed, {} changed",
+                stats.reused,
+                stats.reused_bytes as f64 / (1024.0 * 1024.0),
+                stats.compressed,
+                stats.changed
+            );
+        }
+        Ok(dedup)
+    }
+
+    /// Apply Windows resource modifications to the packed executable
+    #[cfg(target_os = "windows")]
+    fn apply_windows_resources(&self, exe_path: &Path) -> PackResult<()> {
+        if self.config.target_platform.exe_extension() != ".exe" {
+            return Ok(());
+        }
+        let mut res_config = self.build_resource_config();
+
+        // Skip if no modifications needed
+        if !res_config.has_modifications() {
+            tracing::debug!("No Windows resource modifications needed");
+            return Ok(());
+        }
+
+        // rcedit takes ICO files: convert other formats in a staging
+        // directory, removed once the resources are written
+        let mut _icon_dir = None;
+        if let Some(ref icon) = res_config.icon {
+            let format = icon
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(crate::icon::IconFormat::from_extension);
+            if format != Some(crate::icon::IconFormat::Ico) {
+                let icon_data = crate::icon::load_icon(icon)?;
+                let dir = self.staging_dir("icon")?;
+                let ico = dir.path().join("icon.ico");
+                crate::icon::save_ico(&icon_data.ico_data, &ico)?;
+                tracing::info!(
+                    "Auto-generated multi-resolution ICO ({} bytes)",
+                    icon_data.ico_data.len()
+                );
+                res_config.icon = Some(ico);
+                _icon_dir = Some(dir);
+            }
+        }
+
+        tracing::info!("Applying Windows resource modifications...");
+
+        let editor = ResourceEditor::from_store(&self.store())?.with_runner(self.runner.clone());
+        // rcedit fails while AV scanners still hold the freshly written file
+        self.config
+            .file_retry
+            .run("Updating Windows resources", exe_path, || {
+                editor.apply_config(exe_path, &res_config)
+            })?;
+
+        tracing::info!("Windows resources updated successfully");
+        Ok(())
+    }
+
+    /// Build ResourceConfig from PackConfig
+    #[allow(dead_code)]
+    fn build_resource_config(&self) -> ResourceConfig {
+        let win_res = &self.config.windows_resource;
+
+        ResourceConfig {
+            icon: win_res.icon.clone(),
+            console: win_res.console,
+            file_version: win_res.file_version.clone(),
+            product_version: win_res.product_version.clone(),
+            file_description: win_res.file_description.clone(),
+            product_name: win_res.product_name.clone(),
+            company_name: win_res.company_name.clone(),
+            copyright: win_res.copyright.clone(),
+            app_user_model_id: self
+                .config
+                .app_user_model
+                .as_ref()
+                .and_then(|m| m.id.clone())
+                .or_else(|| {
+                    self.config
+                        .identifier
+                        .as_ref()
+                        .map(|_| self.config.identity().app_user_model_id())
+                }),
+            dpi_awareness: self.config.window.dpi.as_ref().map(|d| d.awareness),
+        }
+    }
+
+    /// Pack Process mode (frontend + prebuilt backend binary)
+    ///
+    /// No language-specific tooling is involved: the binary is bundled as-is
+    /// and the LaunchSpec in the overlay config tells the runtime how to start it.
+    fn pack_process(&self, frontend_path: &Path, launch: &LaunchSpec) -> PackResult<PackOutput> {
+        let exe_name = self.get_exe_name();
+        let output_path = self.exe_dir().join(&exe_name);
+
+        tracing::info!("Packing process backend to: {}", output_path.display());
+
+        // Get the launcher executable
+        let current_exe = self.launcher_path()?;
+        self.copy_launcher(&current_exe, &output_path)?;
+
+        // Build download entries (includes synthetic vx runtime if configured)
+        let download_entries = self.build_download_entries();
+        let overlay_config = self.overlay_config_with_vx_env(&self.config, &download_entries);
+
+        // Create overlay data
+        let mut overlay = OverlayData::new(overlay_config);
 
-            count
+        // Bundle frontend assets
+        let frontend_bundle = self.build_frontend_bundle(frontend_path)?;
+        let asset_count = frontend_bundle.len();
+        for (path, content) in frontend_bundle.into_assets() {
+            overlay.add_asset(format!("frontend/{}", path), content);
+        }
+
+        // Bundle the backend binary at the location recorded in the launch spec
+        // (a stripped staging copy when symbols are split)
+        let content = if self.config.symbols.is_some() {
+            let staging = self.staging_dir("symbols")?;
+            let staged = staging.path().join("backend");
+            fs::copy(&launch.binary, &staged)?;
+            self.split_symbols(&launch.command, &launch.binary, &staged)?;
+            fs::read(&staged)?
         } else {
-            0
+            fs::read(&launch.binary)?
         };
+        overlay.add_asset(launch.command.clone(), content);
+        overlay.set_mode(&launch.command, 0o755)?;
+        tracing::debug!(
+            "Bundled backend binary: {} -> {}",
+            launch.binary.display(),
+            launch.command
+        );
+
+        // Collect additional resources from hooks
+        let resource_count = self.collect_hook_resources(&mut overlay)?;
+        if resource_count > 0 {
+            tracing::info!("Collected {} resource files from hooks", resource_count);
+        }
 
         // Embed downloaded artifacts into overlay
         self.embed_downloads_into_overlay(&mut overlay, &download_entries)?;
 
+        // Embed About-screen data (changelog, license, third-party notices)
+        self.embed_about(&mut overlay, &Sbom::default())?;
+        self.embed_eula(&mut overlay)?;
+        self.inject_preview_watermark(&mut overlay);
+
+        // Embed CA bundles and client certificates
+        self.embed_network_certificates(&mut overlay)?;
+
+        // Embed quick action icons
+        self.embed_shortcut_icons(&mut overlay)?;
+
         // Apply Windows resource modifications BEFORE writing overlay
 
         // rcedit cannot handle executables with overlay data appended
         #[cfg(target_os = "windows")]
         self.apply_windows_resources(&output_path)?;
 
+        // Shrink bundled native binaries
+        self.optimize_binaries(&mut overlay)?;
+
+        // Embed the debug symbol index
+        self.embed_symbol_index(&mut overlay)?;
+
         // Write overlay to executable (must be after rcedit modifications)
-        OverlayWriter::write(&output_path, &overlay)?;
+        let dedup = self.write_overlay(&output_path, &mut overlay)?;
 
-        // Get final size
         let size = fs::metadata(&output_path)?.len();
 
         tracing::info!(
-            "Pack complete: {} ({:.2} MB)",
+            "Pack complete: {} ({:.2} MB, {} assets, {} resources)",
             output_path.display(),
-            size as f64 / (1024.0 * 1024.0)
+            size as f64 / (1024.0 * 1024.0),
+            asset_count,
+            resource_count
         );
 
         Ok(PackOutput {
@@ -324,46 +1955,22 @@ impl Packer {
             asset_count,
             python_file_count: 0,
             mode: self.config.mode.name().to_string(),
+            regressions: Vec::new(),
+            dedup,
+            incremental: self.take_incremental_stats(),
+            update_feed: None,
+            provenance: None,
+            resumed_from: None,
+            up_to_date: false,
+            assets: Vec::new(),
+            stage_timings: BTreeMap::new(),
+            duration_ms: 0,
+            tools: BTreeMap::new(),
+            downloads: Vec::new(),
+            smoke_test: None,
         })
     }
 
-    /// Apply Windows resource modifications to the packed executable
-    #[cfg(target_os = "windows")]
-    fn apply_windows_resources(&self, exe_path: &Path) -> PackResult<()> {
-        let res_config = self.build_resource_config();
-
-        // Skip if no modifications needed
-        if !res_config.has_modifications() {
-            tracing::debug!("No Windows resource modifications needed");
-            return Ok(());
-        }
-
-        tracing::info!("Applying Windows resource modifications...");
-
-        let editor = ResourceEditor::new()?;
-        editor.apply_config(exe_path, &res_config)?;
-
-        tracing::info!("Windows resources updated successfully");
-        Ok(())
-    }
-
-    /// Build ResourceConfig from PackConfig
-    #[allow(dead_code)]
-    fn build_resource_config(&self) -> ResourceConfig {
-        let win_res = &self.config.windows_resource;
-
-        ResourceConfig {
-            icon: win_res.icon.clone(),
-            console: win_res.console,
-            file_version: win_res.file_version.clone(),
-            product_version: win_res.product_version.clone(),
-            file_description: win_res.file_description.clone(),
-            product_name: win_res.product_name.clone(),
-            company_name: win_res.company_name.clone(),
-            copyright: win_res.copyright.clone(),
-        }
-    }
-
     /// Pack FullStack mode (frontend + Python backend)
     fn pack_fullstack(
         &self,
@@ -397,43 +2004,98 @@ impl Packer {
         python: &PythonBundleConfig,
     ) -> PackResult<PackOutput> {
         let exe_name = self.get_exe_name();
-        let output_path = self.config.output_dir.join(&exe_name);
+        let output_path = self.exe_dir().join(&exe_name);
 
         tracing::info!(
             "Packing fullstack (standalone) to: {}",
             output_path.display()
         );
 
-        // Download Python distribution
-        let standalone_config = PythonStandaloneConfig {
-            version: python.version.clone(),
-            release: None, // Use latest
-            target: None,  // Auto-detect
-            cache_dir: None,
-        };
+        // A prebuilt environment archive replaces the download and pip install
+        let mut standalone = None;
+        let mut env_import = None;
+        let (python_archive, python_meta) = match python.env_archive {
+            Some(ref env_archive) => {
+                if let Some(target) = self
+                    .config
+                    .build_target
+                    .filter(|t| !self.context.is_current(t))
+                {
+                    return Err(PackError::Config(format!(
+                        "python.env_archive is an environment of this platform and cannot be packed for {}",
+                        target
+                    )));
+                }
+                let staging = self.staging_dir("env")?;
+                let mut env = crate::env_archive::import(env_archive, staging.path())?;
+                let version = match env.version {
+                    Some(ref detected) if *detected != python.version => {
+                        tracing::warn!(
+                            "Environment archive contains Python {}, configured version is {}; \
+                             using {}",
+                            detected,
+                            python.version,
+                            detected
+                        );
+                        detected.clone()
+                    }
+                    Some(ref detected) => detected.clone(),
+                    None => python.version.clone(),
+                };
+                // Spill the re-packed archive to staging; it is streamed
+                // into the overlay instead of held in memory
+                let archive = staging.path().join("python_runtime.tar.gz");
+                fs::write(&archive, std::mem::take(&mut env.archive))?;
+                tracing::info!(
+                    "Embedding {:?} environment as Python {} runtime",
+                    env.kind,
+                    version
+                );
+                let meta = PythonRuntimeMeta {
+                    version,
+                    target: PythonTarget::current()?.triple().to_string(),
+                    archive_size: fs::metadata(&archive)?.len(),
+                };
+                env_import = Some((staging, env));
+                (archive, meta)
+            }
+            None => {
+                // Download Python distribution
+                let standalone_config = PythonStandaloneConfig {
+                    version: python.version.clone(),
+                    release: None, // Use latest
+                    target: self.python_target_triple()?,
+                    cache_dir: None,
+                };
 
-        let standalone = PythonStandalone::new(standalone_config)?;
-        tracing::info!(
-            "Downloading Python {} for {}...",
-            standalone.version(),
-            standalone.target().triple()
-        );
+                let downloaded = self.python_standalone(standalone_config)?;
+                tracing::info!(
+                    "Downloading Python {} for {}...",
+                    downloaded.version(),
+                    downloaded.target().triple()
+                );
 
-        let python_archive = standalone.get_distribution_bytes()?;
-        let python_meta = PythonRuntimeMeta {
-            version: python.version.clone(),
-            target: standalone.target().triple().to_string(),
-            archive_size: python_archive.len() as u64,
+                let archive = downloaded.download()?;
+                self.record_python_distribution(&downloaded, &archive)?;
+                let meta = PythonRuntimeMeta {
+                    version: python.version.clone(),
+                    target: downloaded.target().triple().to_string(),
+                    archive_size: fs::metadata(&archive)?.len(),
+                };
+                standalone = Some(downloaded);
+                (archive, meta)
+            }
         };
 
         tracing::info!(
             "Python distribution size: {:.2} MB",
-            python_archive.len() as f64 / (1024.0 * 1024.0)
+            python_meta.archive_size as f64 / (1024.0 * 1024.0)
         );
+        self.record_tool("python", &python_meta.version);
 
-        // Get the current executable
-        let current_exe = std::env::current_exe()?;
-        fs::copy(&current_exe, &output_path)?;
+        // Get the launcher executable
+        let current_exe = self.launcher_path()?;
+        self.copy_launcher(&current_exe, &output_path)?;
 
         // Build download entries (includes synthetic vx runtime if configured)
         let download_entries = self.build_download_entries();
@@ -442,16 +2104,13 @@ impl Packer {
         // Create overlay data
         let mut overlay = OverlayData::new(overlay_config);
 
-        // Add Python runtime metadata
-
+        // Add Python runtime metadata; the distribution archive itself is
+        // streamed from disk when the overlay is written
         let meta_json = serde_json::to_vec(&python_meta)?;
         overlay.add_asset("python_runtime.json".to_string(), meta_json);
 
-        // Add Python distribution archive
-        overlay.add_asset("python_runtime.tar.gz".to_string(), python_archive);
-
         // Bundle frontend assets
-        let frontend_bundle = BundleBuilder::new(frontend_path).build()?;
+        let frontend_bundle = self.build_frontend_bundle(frontend_path)?;
         let asset_count = frontend_bundle.len();
         for (path, content) in frontend_bundle.into_assets() {
             overlay.add_asset(format!("frontend/{}", path), content);
@@ -460,9 +2119,23 @@ impl Packer {
         // Bundle Python code
         let python_file_count = self.bundle_python_code(&mut overlay, python)?;
 
-        // Install Python packages (third-party dependencies)
-        let package_file_count =
-            self.install_packages_for_standalone(&mut overlay, python, &standalone)?;
+        // Install Python packages (third-party dependencies); imported
+        // environments already contain theirs
+        let (package_file_count, sbom) = match standalone {
+            Some(ref standalone) => {
+                self.install_packages_for_standalone(&mut overlay, python, standalone)?
+            }
+            None => {
+                let site_packages = env_import
+                    .as_ref()
+                    .and_then(|(_, env)| env.site_packages.as_deref());
+                let sbom = match site_packages {
+                    Some(dir) => self.collect_sbom(dir)?,
+                    None => Sbom::default(),
+                };
+                (0, sbom)
+            }
+        };
 
         // Collect additional resources from hooks
         let resource_count = self.collect_hook_resources(&mut overlay)?;
@@ -473,14 +2146,35 @@ impl Packer {
         // Embed downloaded artifacts into overlay
         self.embed_downloads_into_overlay(&mut overlay, &download_entries)?;
 
+        // Embed About-screen data (changelog, license, third-party notices)
+        self.embed_about(&mut overlay, &sbom)?;
+        self.embed_eula(&mut overlay)?;
+        self.inject_preview_watermark(&mut overlay);
+
+        // Embed CA bundles and client certificates
+        self.embed_network_certificates(&mut overlay)?;
+
+        // Embed quick action icons
+        self.embed_shortcut_icons(&mut overlay)?;
+
         // Apply Windows resource modifications BEFORE writing overlay
 
         // rcedit cannot handle executables with overlay data appended
         #[cfg(target_os = "windows")]
         self.apply_windows_resources(&output_path)?;
 
+        // Shrink bundled native binaries
+        self.optimize_binaries(&mut overlay)?;
+
+        // Embed the debug symbol index
+        self.embed_symbol_index(&mut overlay)?;
+
         // Write overlay to executable (must be after rcedit modifications)
-        OverlayWriter::write(&output_path, &overlay)?;
+        let dedup = self.write_overlay_with_files(
+            &output_path,
+            &mut overlay,
+            &[("python_runtime.tar.gz", python_archive.as_path())],
+        )?;
 
         let size = fs::metadata(&output_path)?.len();
 
@@ -500,6 +2194,19 @@ impl Packer {
             asset_count,
             python_file_count,
             mode: "fullstack-standalone".to_string(),
+            regressions: Vec::new(),
+            dedup,
+            incremental: self.take_incremental_stats(),
+            update_feed: None,
+            provenance: None,
+            resumed_from: None,
+            up_to_date: false,
+            assets: Vec::new(),
+            stage_timings: BTreeMap::new(),
+            duration_ms: 0,
+            tools: BTreeMap::new(),
+            downloads: Vec::new(),
+            smoke_test: None,
         })
     }
 
@@ -560,10 +2267,11 @@ impl Packer {
             })
             .collect();
 
-        // Build resources list (including frontend)
-        let mut resources: Vec<ResourceFile> = vec![ResourceFile {
-            source: frontend_path.to_path_buf(),
-            dest: Some("frontend".to_string()),
+        // Stage the branded frontend and the certificates as resources
+        let (staged, asset_count) = self.stage_bundle_resources(frontend_path)?;
+        let mut resources = vec![ResourceFile {
+            source: staged.path().to_path_buf(),
+            dest: None,
             pattern: None,
             exclude: Vec::new(),
         }];
@@ -602,11 +2310,8 @@ impl Packer {
             .env_vars(self.config.env.clone());
 
         // Build with PyOxidizer
-        let output_exe = builder.build(&self.config.output_dir)?;
-
-        // Get frontend asset count for reporting
-        let frontend_bundle = BundleBuilder::new(frontend_path).build()?;
-        let asset_count = frontend_bundle.len();
+        let output_exe = builder.build(&self.exe_dir())?;
+        drop(staged);
 
         // Count Python files
         let mut python_file_count = 0;
@@ -641,6 +2346,19 @@ impl Packer {
             asset_count,
             python_file_count,
             mode: "fullstack-pyoxidizer".to_string(),
+            regressions: Vec::new(),
+            dedup: DedupStats::default(),
+            incremental: self.take_incremental_stats(),
+            update_feed: None,
+            provenance: None,
+            resumed_from: None,
+            up_to_date: false,
+            assets: Vec::new(),
+            stage_timings: BTreeMap::new(),
+            duration_ms: 0,
+            tools: BTreeMap::new(),
+            downloads: Vec::new(),
+            smoke_test: None,
         })
     }
 
@@ -654,13 +2372,13 @@ impl Packer {
         python: &PythonBundleConfig,
     ) -> PackResult<PackOutput> {
         let exe_name = self.get_exe_name();
-        let output_path = self.config.output_dir.join(&exe_name);
+        let output_path = self.exe_dir().join(&exe_name);
 
         tracing::info!("Packing fullstack (embedded) to: {}", output_path.display());
 
-        // Get the current executable
-        let current_exe = std::env::current_exe()?;
-        fs::copy(&current_exe, &output_path)?;
+        // Get the launcher executable
+        let current_exe = self.launcher_path()?;
+        self.copy_launcher(&current_exe, &output_path)?;
 
         // Build download entries (includes synthetic vx runtime if configured)
         let download_entries = self.build_download_entries();
@@ -671,7 +2389,7 @@ impl Packer {
 
         // Bundle frontend assets
 
-        let frontend_bundle = BundleBuilder::new(frontend_path).build()?;
+        let frontend_bundle = self.build_frontend_bundle(frontend_path)?;
         let asset_count = frontend_bundle.len();
         for (path, content) in frontend_bundle.into_assets() {
             overlay.add_asset(format!("frontend/{}", path), content);
@@ -680,16 +2398,36 @@ impl Packer {
         // Bundle Python code
         let python_file_count = self.bundle_python_code(&mut overlay, python)?;
 
+        // Record the Python the code needs, for the launcher's startup check
+        let requirement = PythonRequirement::new(python, &overlay.assets)?;
+        requirement.preflight();
+        overlay.add_asset(
+            PYTHON_REQUIREMENT_PATH,
+            serde_json::to_vec_pretty(&requirement)?,
+        );
+
         // Embed downloaded artifacts into overlay
         self.embed_downloads_into_overlay(&mut overlay, &download_entries)?;
 
-        // Write overlay to executable
-        OverlayWriter::write(&output_path, &overlay)?;
+        // Embed About-screen data (changelog, license, third-party notices)
+        self.embed_about(&mut overlay, &Sbom::default())?;
+        self.embed_eula(&mut overlay)?;
+        self.inject_preview_watermark(&mut overlay);
 
-        // Small delay to ensure file handles are fully released on Windows
-        // before rcedit tries to modify the executable
-        #[cfg(target_os = "windows")]
-        std::thread::sleep(std::time::Duration::from_millis(100));
+        // Embed CA bundles and client certificates
+        self.embed_network_certificates(&mut overlay)?;
+
+        // Embed quick action icons
+        self.embed_shortcut_icons(&mut overlay)?;
+
+        // Shrink bundled native binaries
+        self.optimize_binaries(&mut overlay)?;
+
+        // Embed the debug symbol index
+        self.embed_symbol_index(&mut overlay)?;
+
+        // Write overlay to executable
+        let dedup = self.write_overlay(&output_path, &mut overlay)?;
 
         // Apply Windows resource modifications (icon, subsystem, etc.)
         #[cfg(target_os = "windows")]
@@ -711,6 +2449,19 @@ impl Packer {
             asset_count,
             python_file_count,
             mode: "fullstack-embedded".to_string(),
+            regressions: Vec::new(),
+            dedup,
+            incremental: self.take_incremental_stats(),
+            update_feed: None,
+            provenance: None,
+            resumed_from: None,
+            up_to_date: false,
+            assets: Vec::new(),
+            stage_timings: BTreeMap::new(),
+            duration_ms: 0,
+            tools: BTreeMap::new(),
+            downloads: Vec::new(),
+            smoke_test: None,
         })
     }
 
@@ -735,12 +2486,44 @@ impl Packer {
         // Copy launcher executable
         let exe_name = self.get_exe_name();
         let exe_path = output_dir.join(&exe_name);
-        let current_exe = std::env::current_exe()?;
-        fs::copy(&current_exe, &exe_path)?;
+        let current_exe = self.launcher_path()?;
+        self.copy_launcher(&current_exe, &exe_path)?;
+
+        // Embed the Python runtime (python/), replacing a previous pack's
+        let python_dir = output_dir.join("python");
+        if python_dir.exists() {
+            fs::remove_dir_all(&python_dir)?;
+        }
+        let standalone = self.python_standalone(PythonStandaloneConfig {
+            version: python.version.clone(),
+            target: self.python_target_triple()?,
+            ..Default::default()
+        })?;
+        tracing::info!(
+            "Embedding Python {} for {}...",
+            standalone.version(),
+            standalone.target().triple()
+        );
+        let python_exe = standalone.extract(&output_dir)?;
+        self.record_python_distribution(&standalone, &standalone.download()?)?;
+
+        // Install Python packages with the embedded runtime, so compiled
+        // wheels match the interpreter the app runs with
+        let lib_dir = output_dir.join("lib");
+        fs::create_dir_all(&lib_dir)?;
+        self.install_packages_with_python(&lib_dir, python, Some(&python_exe))?;
+        let sbom = self.collect_sbom(&lib_dir)?;
 
         // Create overlay for launcher config
-        let overlay = OverlayData::new(self.config.clone());
-        OverlayWriter::write(&exe_path, &overlay)?;
+        let mut overlay = OverlayData::new(self.config.clone());
+        self.embed_about(&mut overlay, &sbom)?;
+        self.embed_eula(&mut overlay)?;
+        self.inject_preview_watermark(&mut overlay);
+        self.embed_network_certificates(&mut overlay)?;
+        self.embed_shortcut_icons(&mut overlay)?;
+        self.optimize_binaries(&mut overlay)?;
+        self.embed_symbol_index(&mut overlay)?;
+        let dedup = self.write_overlay(&exe_path, &mut overlay)?;
 
         // Apply Windows resource modifications (icon, subsystem, etc.)
         #[cfg(target_os = "windows")]
@@ -749,7 +2532,7 @@ impl Packer {
         // Copy frontend assets
         let frontend_dir = output_dir.join("frontend");
         fs::create_dir_all(&frontend_dir)?;
-        let frontend_bundle = BundleBuilder::new(frontend_path).build()?;
+        let frontend_bundle = self.build_frontend_bundle(frontend_path)?;
         let asset_count = frontend_bundle.len();
         for (path, content) in frontend_bundle.into_assets() {
             let dest = frontend_dir.join(&path);
@@ -764,13 +2547,11 @@ impl Packer {
         fs::create_dir_all(&backend_dir)?;
         let python_file_count = self.copy_python_code(&backend_dir, python)?;
 
-        // Install Python packages
-        let lib_dir = output_dir.join("lib");
-        fs::create_dir_all(&lib_dir)?;
-        self.install_python_packages(&lib_dir, python)?;
+        // Document the backend environment next to the launcher
+        IsolationEnv::new(BundleStrategy::Portable, &python.isolation).write(&output_dir)?;
 
         // Calculate total size
-        let size = calculate_dir_size(&output_dir)?;
+        let size = path_size(&output_dir);
 
         tracing::info!(
             "Pack complete: {} ({:.2} MB, {} assets, {} python files)",
@@ -786,6 +2567,19 @@ impl Packer {
             asset_count,
             python_file_count,
             mode: "fullstack-portable".to_string(),
+            regressions: Vec::new(),
+            dedup,
+            incremental: self.take_incremental_stats(),
+            update_feed: None,
+            provenance: None,
+            resumed_from: None,
+            up_to_date: false,
+            assets: Vec::new(),
+            stage_timings: BTreeMap::new(),
+            duration_ms: 0,
+            tools: BTreeMap::new(),
+            downloads: Vec::new(),
+            smoke_test: None,
         })
     }
 
@@ -805,12 +2599,19 @@ impl Packer {
         // Copy launcher executable
         let exe_name = self.get_exe_name();
         let exe_path = output_dir.join(&exe_name);
-        let current_exe = std::env::current_exe()?;
-        fs::copy(&current_exe, &exe_path)?;
+        let current_exe = self.launcher_path()?;
+        self.copy_launcher(&current_exe, &exe_path)?;
 
         // Create overlay for launcher config
-        let overlay = OverlayData::new(self.config.clone());
-        OverlayWriter::write(&exe_path, &overlay)?;
+        let mut overlay = OverlayData::new(self.config.clone());
+        self.embed_about(&mut overlay, &Sbom::default())?;
+        self.embed_eula(&mut overlay)?;
+        self.inject_preview_watermark(&mut overlay);
+        self.embed_network_certificates(&mut overlay)?;
+        self.embed_shortcut_icons(&mut overlay)?;
+        self.optimize_binaries(&mut overlay)?;
+        self.embed_symbol_index(&mut overlay)?;
+        let dedup = self.write_overlay(&exe_path, &mut overlay)?;
 
         // Apply Windows resource modifications (icon, subsystem, etc.)
         #[cfg(target_os = "windows")]
@@ -819,7 +2620,7 @@ impl Packer {
         // Copy frontend assets
         let frontend_dir = output_dir.join("frontend");
         fs::create_dir_all(&frontend_dir)?;
-        let frontend_bundle = BundleBuilder::new(frontend_path).build()?;
+        let frontend_bundle = self.build_frontend_bundle(frontend_path)?;
         let asset_count = frontend_bundle.len();
         for (path, content) in frontend_bundle.into_assets() {
             let dest = frontend_dir.join(&path);
@@ -837,7 +2638,13 @@ impl Packer {
         // Generate requirements.txt for user to install
         self.generate_requirements_file(&output_dir, python)?;
 
-        let size = calculate_dir_size(&output_dir)?;
+        // Check the system Python (and create the venv) before starting
+        crate::system_launcher::write_scripts(&output_dir, &exe_name, python)?;
+
+        // Document the backend environment next to the launcher
+        IsolationEnv::new(BundleStrategy::System, &python.isolation).write(&output_dir)?;
+
+        let size = path_size(&output_dir);
 
         tracing::info!(
             "Pack complete: {} ({:.2} MB, {} assets, {} python files)",
@@ -853,6 +2660,19 @@ impl Packer {
             asset_count,
             python_file_count,
             mode: "fullstack-system".to_string(),
+            regressions: Vec::new(),
+            dedup,
+            incremental: self.take_incremental_stats(),
+            update_feed: None,
+            provenance: None,
+            resumed_from: None,
+            up_to_date: false,
+            assets: Vec::new(),
+            stage_timings: BTreeMap::new(),
+            duration_ms: 0,
+            tools: BTreeMap::new(),
+            downloads: Vec::new(),
+            smoke_test: None,
         })
     }
 
@@ -883,8 +2703,7 @@ impl Packer {
 
         // Create temp directory for protection if enabled
         let temp_dir = if protection_enabled {
-            let dir = tempfile::tempdir().map_err(|e| PackError::Io(std::io::Error::other(e)))?;
-            Some(dir)
+            Some(self.staging_dir("protect")?)
         } else {
             None
         };
@@ -967,17 +2786,7 @@ impl Packer {
                     .unwrap_or(entry.path());
 
                 // Check if path matches any exclude pattern
-                let path_str = rel_path.to_string_lossy();
-                let should_exclude = python.exclude.iter().any(|pattern| {
-                    if pattern.contains('*') {
-                        let pattern = pattern.replace("*", "");
-                        path_str.contains(&pattern)
-                    } else {
-                        path_str.contains(pattern)
-                    }
-                });
-
-                if should_exclude {
+                if python_path_excluded(&rel_path.to_string_lossy(), &python.exclude) {
                     continue;
                 }
 
@@ -1068,7 +2877,10 @@ elif spec and spec.origin:
         let mut python_output = None;
 
         for python_exe in python_candidates {
-            match Command::new(python_exe).args(["-c", script]).output() {
+            match self
+                .runner
+                .output(Command::new(python_exe).args(["-c", script]))
+            {
                 Ok(output) => {
                     python_output = Some(output);
                     break;
@@ -1162,8 +2974,7 @@ elif spec and spec.origin:
                     .file_name()
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown");
-                let content = fs::read(bin_path)?;
-                overlay.add_asset(format!("python/bin/{}", name), content);
+                overlay.add_file_asset(format!("python/bin/{}", name), bin_path)?;
                 tracing::debug!(
                     "Bundled external binary: {} -> python/bin/{}",
                     bin_path.display(),
@@ -1175,17 +2986,16 @@ elif spec and spec.origin:
                 for entry in walkdir::WalkDir::new(bin_path)
                     .into_iter()
                     .filter_map(|e| e.ok())
-                    .filter(|e| e.path().is_file())
+                    .filter(|e| e.path().is_file() || e.path_is_symlink() && !e.path().is_dir())
                 {
                     let rel_path = entry.path().strip_prefix(bin_path).unwrap_or(entry.path());
-                    let content = fs::read(entry.path())?;
-                    overlay.add_asset(
+                    overlay.add_file_asset(
                         format!(
                             "python/bin/{}",
                             rel_path.to_string_lossy().replace('\\', "/")
                         ),
-                        content,
-                    );
+                        entry.path(),
+                    )?;
                     count += 1;
                 }
             }
@@ -1252,11 +3062,12 @@ elif spec and spec.origin:
         tracing::info!("Collecting Python dependencies: {:?}", packages_to_collect);
 
         // Create temp directory for collecting deps
-        let temp_dir = std::env::temp_dir().join(format!("auroraview-deps-{}", std::process::id()));
-        fs::create_dir_all(&temp_dir)?;
+        let staging = self.staging_dir("deps")?;
+        let temp_dir = staging.path();
 
         // Use DepsCollector to collect packages
         let collector = DepsCollector::new()
+            .with_runner(self.runner.clone())
             .include(packages_to_collect.iter().cloned())
             .exclude(python.exclude.iter().cloned());
 
@@ -1278,7 +3089,7 @@ elif spec and spec.origin:
             collector.check_package(pkg);
         }
 
-        let collected = collector.collect(entry_files, &temp_dir)?;
+        let collected = collector.collect(entry_files, temp_dir)?;
 
         tracing::info!(
             "Collected {} packages ({} files, {:.2} MB)",
@@ -1309,26 +3120,31 @@ elif spec and spec.origin:
 
         // Add collected files to overlay under site-packages/
         let mut count = 0;
-        for entry in walkdir::WalkDir::new(&temp_dir)
+        let mut cuda = CudaLibraryFilter::new(python.cuda.as_ref());
+        for entry in walkdir::WalkDir::new(temp_dir)
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| e.path().is_file())
         {
-            let rel_path = entry.path().strip_prefix(&temp_dir).unwrap_or(entry.path());
-            let content = fs::read(entry.path())?;
+            let rel_path = entry.path().strip_prefix(temp_dir).unwrap_or(entry.path());
             // Put dependencies in python/site-packages/ for clean separation
-            overlay.add_asset(
-                format!(
-                    "python/site-packages/{}",
-                    rel_path.to_string_lossy().replace('\\', "/")
-                ),
-                content,
+            let overlay_path = format!(
+                "python/site-packages/{}",
+                rel_path.to_string_lossy().replace('\\', "/")
             );
+            let size = entry.metadata().map(|m| m.len()).unwrap_or_default();
+            if cuda.exclude(&overlay_path, size) {
+                continue;
+            }
+
+            let content = fs::read(entry.path())?;
+            overlay.add_asset(overlay_path, content);
             count += 1;
         }
+        cuda.finish(overlay)?;
 
-        // Cleanup temp directory
-        let _ = fs::remove_dir_all(&temp_dir);
+        // Cleanup staging directory
+        drop(staging);
 
         Ok(count)
     }
@@ -1401,15 +3217,6 @@ elif spec and spec.origin:
         Ok(count)
     }
 
-    /// Install Python packages using pip (for portable mode with system Python)
-    fn install_python_packages(
-        &self,
-        lib_dir: &Path,
-        python: &PythonBundleConfig,
-    ) -> PackResult<()> {
-        self.install_packages_with_python(lib_dir, python, None)
-    }
-
     /// Install Python packages using a specific Python executable
     ///
     /// If `python_exe` is None, tries system Python commands.
@@ -1455,19 +3262,24 @@ elif spec and spec.origin:
         let mut pip_success = false;
 
         for python_cmd in &python_commands {
-            let status = Command::new(python_cmd)
-                .args([
-                    "-m",
-                    "pip",
-                    "install",
-                    "--target",
-                    lib_dir.to_str().unwrap_or("."),
-                    "--upgrade",
-                ])
-                .args(&packages)
-                .status();
-
-            match status {
+            let output = self.runner.output(
+                Command::new(python_cmd)
+                    .envs(self.pip_cache_env())
+                    .args([
+                        "-m",
+                        "pip",
+                        "install",
+                        "--target",
+                        lib_dir.to_str().unwrap_or("."),
+                        "--upgrade",
+                    ])
+                    .args(&packages),
+            );
+            if let Ok(ref output) = output {
+                crate::subprocess::log_output(python_cmd, output);
+            }
+
+            match output.map(|o| o.status) {
                 Ok(s) if s.success() => {
                     tracing::info!(
                         "Python packages installed successfully using {}",
@@ -1487,22 +3299,29 @@ elif spec and spec.origin:
 
         if !pip_success {
             tracing::warn!("Failed to install Python packages with pip, trying uv...");
-            let status = Command::new("uv")
-                .args([
-                    "pip",
-                    "install",
-                    "--target",
-                    lib_dir.to_str().unwrap_or("."),
-                ])
-                .args(&packages)
-                .status();
-
-            match status {
-                Ok(s) if s.success() => {
+            let output = self.runner.output(
+                Command::new("uv")
+                    .envs(self.pip_cache_env())
+                    .args([
+                        "pip",
+                        "install",
+                        "--target",
+                        lib_dir.to_str().unwrap_or("."),
+                    ])
+                    .args(&packages),
+            );
+
+            match output {
+                Ok(o) if o.status.success() => {
+                    crate::subprocess::log_output("uv", &o);
                     tracing::info!("Python packages installed successfully using uv");
                 }
-                Ok(s) => {
-                    tracing::warn!("uv pip install exited with status: {}", s);
+                Ok(o) => {
+                    tracing::warn!(
+                        "uv pip install exited with status: {}\n{}",
+                        o.status,
+                        crate::subprocess::stderr_tail(&o)
+                    );
                 }
                 Err(e) => {
                     tracing::warn!("Failed to run uv pip: {}", e);
@@ -1524,11 +3343,12 @@ elif spec and spec.origin:
 
         // First, ensure pip is available using ensurepip (suppress output)
         tracing::info!("Ensuring pip is available...");
-        let ensurepip_status = Command::new(python_exe)
-            .args(["-m", "ensurepip", "--upgrade"])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status();
+        let ensurepip_status = self.runner.status(
+            Command::new(python_exe)
+                .args(["-m", "ensurepip", "--upgrade"])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null()),
+        );
 
         match ensurepip_status {
             Ok(s) if s.success() => {
@@ -1547,11 +3367,12 @@ elif spec and spec.origin:
 
         // Upgrade pip to latest version (suppress output)
         tracing::info!("Upgrading pip...");
-        let _ = Command::new(python_exe)
-            .args(["-m", "pip", "install", "--upgrade", "pip", "-q"])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status();
+        let _ = self.runner.status(
+            Command::new(python_exe)
+                .args(["-m", "pip", "install", "--upgrade", "pip", "-q"])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null()),
+        );
 
         // Now install packages with progress info
         tracing::info!(
@@ -1562,22 +3383,26 @@ elif spec and spec.origin:
         tracing::info!("Packages: {:?}", packages);
 
         // Use --progress-bar off and -q for quieter output, capture stderr for errors
-        let output = Command::new(python_exe)
-            .args([
-                "-m",
-                "pip",
-                "install",
-                "--target",
-                lib_dir.to_str().unwrap_or("."),
-                "--upgrade",
-                "--progress-bar",
-                "off",
-                "-q",
-            ])
-            .args(packages)
-            .stdout(Stdio::null())
-            .stderr(Stdio::piped())
-            .output()
+        let output = self
+            .runner
+            .output(
+                Command::new(python_exe)
+                    .envs(self.pip_cache_env())
+                    .args([
+                        "-m",
+                        "pip",
+                        "install",
+                        "--target",
+                        lib_dir.to_str().unwrap_or("."),
+                        "--upgrade",
+                        "--progress-bar",
+                        "off",
+                        "-q",
+                    ])
+                    .args(packages)
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::piped()),
+            )
             .map_err(|e| {
                 PackError::Config(format!("Failed to run pip with bundled Python: {}", e))
             })?;
@@ -1607,7 +3432,7 @@ elif spec and spec.origin:
         overlay: &mut OverlayData,
         python: &PythonBundleConfig,
         standalone: &PythonStandalone,
-    ) -> PackResult<usize> {
+    ) -> PackResult<(usize, Sbom)> {
         let mut packages = python.packages.clone();
 
         // Read from requirements.txt if specified
@@ -1625,7 +3450,7 @@ elif spec and spec.origin:
 
         if packages.is_empty() {
             tracing::info!("No Python packages to install");
-            return Ok(0);
+            return Ok((0, Sbom::default()));
         }
 
         tracing::info!(
@@ -1635,7 +3460,7 @@ elif spec and spec.origin:
         );
 
         // Create temp directory for extraction
-        let temp_dir = tempfile::tempdir().map_err(|e| PackError::Io(std::io::Error::other(e)))?;
+        let temp_dir = self.staging_dir("pip")?;
 
         // Extract Python to temp directory
         let python_exe = standalone.extract(temp_dir.path())?;
@@ -1647,10 +3472,12 @@ elif spec and spec.origin:
 
         // Install packages using the extracted Python
         self.pip_install_with_exe(&python_exe, &lib_dir, &packages)?;
+        let sbom = self.collect_sbom(&lib_dir)?;
 
         // Bundle the installed packages into overlay
         let mut count = 0;
         let mut skipped_size = 0u64;
+        let mut cuda = CudaLibraryFilter::new(python.cuda.as_ref());
 
         for entry in walkdir::WalkDir::new(&lib_dir)
             .into_iter()
@@ -1695,20 +3522,24 @@ elif spec and spec.origin:
                 continue;
             }
 
+            let overlay_path = format!("lib/{}", rel_path.to_string_lossy().replace('\\', "/"));
+            let size = entry.metadata().map(|m| m.len()).unwrap_or_default();
+            if cuda.exclude(&overlay_path, size) {
+                continue;
+            }
+
             let content = fs::read(entry.path())?;
-            overlay.add_asset(
-                format!("lib/{}", rel_path.to_string_lossy().replace('\\', "/")),
-                content,
-            );
+            overlay.add_asset(overlay_path, content);
             count += 1;
         }
+        cuda.finish(overlay)?;
 
         tracing::info!(
             "Bundled {} package files into overlay (skipped {:.2} MB)",
             count,
             skipped_size as f64 / (1024.0 * 1024.0)
         );
-        Ok(count)
+        Ok((count, sbom))
     }
 
     /// Collect additional resources from hooks configuration
@@ -1726,15 +3557,16 @@ elif spec and spec.origin:
                 for file in walkdir::WalkDir::new(&dest_root)
                     .into_iter()
                     .filter_map(|e| e.ok())
-                    .filter(|e| e.file_type().is_file())
+                    .filter(|e| {
+                        e.file_type().is_file() || e.path_is_symlink() && !e.path().is_dir()
+                    })
                 {
                     let rel = file
                         .path()
                         .strip_prefix(&self.config.output_dir)
                         .unwrap_or(file.path());
                     let rel_str = rel.to_string_lossy().replace('\\', "/");
-                    let content = fs::read(file.path())?;
-                    overlay.add_asset(rel_str, content);
+                    overlay.add_file_asset(rel_str, file.path())?;
                 }
             } else if dest_root.is_file() {
                 let rel = dest_root
@@ -1742,8 +3574,7 @@ elif spec and spec.origin:
                     .unwrap_or(&dest_root)
                     .to_string_lossy()
                     .replace('\\', "/");
-                let content = fs::read(&dest_root)?;
-                overlay.add_asset(rel, content);
+                overlay.add_file_asset(rel, &dest_root)?;
             } else {
                 tracing::warn!(
                     "Download destination missing, skip embedding: {}",
@@ -1823,7 +3654,7 @@ elif spec and spec.origin:
             "vx"
         };
 
-        match std::process::Command::new(vx_cmd).arg("--version").output() {
+        match self.runner.output(Command::new(vx_cmd).arg("--version")) {
             Ok(output) if output.status.success() => {
                 let version_str = String::from_utf8_lossy(&output.stdout);
                 tracing::debug!("Found vx: {}", version_str.trim());
@@ -1860,7 +3691,7 @@ elif spec and spec.origin:
     }
 
     fn validate_uv_tool(&self, version_req: Option<&str>) -> PackResult<()> {
-        match std::process::Command::new("uv").arg("--version").output() {
+        match self.runner.output(Command::new("uv").arg("--version")) {
             Ok(output) if output.status.success() => {
                 let version_str = String::from_utf8_lossy(&output.stdout);
                 tracing::debug!("Found uv: {}", version_str.trim());
@@ -1879,7 +3710,7 @@ elif spec and spec.origin:
     }
 
     fn validate_node_tool(&self, version_req: Option<&str>) -> PackResult<()> {
-        match std::process::Command::new("node").arg("--version").output() {
+        match self.runner.output(Command::new("node").arg("--version")) {
             Ok(output) if output.status.success() => {
                 let version_str = String::from_utf8_lossy(&output.stdout);
                 tracing::debug!("Found node: {}", version_str.trim());
@@ -1902,7 +3733,7 @@ elif spec and spec.origin:
     }
 
     fn validate_go_tool(&self, version_req: Option<&str>) -> PackResult<()> {
-        match std::process::Command::new("go").arg("version").output() {
+        match self.runner.output(Command::new("go").arg("version")) {
             Ok(output) if output.status.success() => {
                 let version_str = String::from_utf8_lossy(&output.stdout);
                 tracing::debug!("Found go: {}", version_str.trim());
@@ -1925,10 +3756,7 @@ elif spec and spec.origin:
     }
 
     fn validate_python_tool(&self, version_req: Option<&str>) -> PackResult<()> {
-        match std::process::Command::new("python")
-            .arg("--version")
-            .output()
-        {
+        match self.runner.output(Command::new("python").arg("--version")) {
             Ok(output) if output.status.success() => {
                 let version_str = String::from_utf8_lossy(&output.stdout);
                 tracing::debug!("Found python: {}", version_str.trim());
@@ -1988,7 +3816,233 @@ elif spec and spec.origin:
                 config.env.insert("AURORAVIEW_VX_PATH".to_string(), path);
             }
         }
-        config
+        config
+    }
+
+    /// Collect package metadata from installed packages and enforce the license policy
+    fn collect_sbom(&self, lib_dir: &Path) -> PackResult<Sbom> {
+        let sbom = Sbom::from_site_packages(lib_dir)?;
+        if let Some(ref policy) = self.config.license_policy {
+            sbom.check_license_policy(policy)?;
+            tracing::info!("License policy passed for {} packages", sbom.packages.len());
+        }
+        Ok(sbom)
+    }
+
+    /// Embed About-screen data under the reserved `__about__/` overlay prefix
+    ///
+    /// Writes the changelog, application license text and third-party notices
+    /// generated from `sbom`, plus an `about.json` descriptor pointing at them.
+    fn embed_about(&self, overlay: &mut OverlayData, sbom: &Sbom) -> PackResult<()> {
+        let Some(ref about) = self.config.about else {
+            return Ok(());
+        };
+
+        let mut data = AboutData {
+            info: about.info.clone(),
+            ..Default::default()
+        };
+        if data.info.name.is_empty() {
+            data.info.name = self.config.window.title.clone();
+        }
+
+        if let Some(ref changelog) = about.changelog {
+            let asset = format!("{}{}", ABOUT_PREFIX, file_name_of(changelog));
+            overlay.add_asset(asset.clone(), fs::read(changelog)?);
+            data.changelog = Some(asset);
+        }
+
+        if let Some(ref license_file) = about.license_file {
+            let asset = format!("{}{}", ABOUT_PREFIX, file_name_of(license_file));
+            overlay.add_asset(asset.clone(), fs::read(license_file)?);
+            data.license_text = Some(asset);
+        }
+
+        if about.third_party_notices && !sbom.is_empty() {
+            overlay.add_asset(
+                THIRD_PARTY_NOTICES_PATH.to_string(),
+                sbom.third_party_notices().into_bytes(),
+            );
+            data.third_party_notices = Some(THIRD_PARTY_NOTICES_PATH.to_string());
+            data.third_party = sbom.packages.clone();
+        }
+
+        overlay.add_asset(
+            ABOUT_JSON_PATH.to_string(),
+            serde_json::to_vec_pretty(&data)?,
+        );
+        tracing::info!(
+            "Embedded About-screen data ({} third-party packages)",
+            data.third_party.len()
+        );
+        Ok(())
+    }
+
+    /// Embed the EULA under the reserved `__eula__/` overlay prefix
+    ///
+    /// The overlay config is rewritten to reference the embedded text and
+    /// carries the revision the runtime records acceptance of.
+    fn embed_eula(&self, overlay: &mut OverlayData) -> PackResult<()> {
+        let Some(eula) = self.config.license.as_ref().and_then(|l| l.eula.as_ref()) else {
+            return Ok(());
+        };
+        let (asset, content, embedded) = eula.embed()?;
+        overlay.add_asset(asset, content);
+        tracing::info!(
+            "Embedded EULA (revision {})",
+            embedded.revision.as_deref().unwrap_or_default()
+        );
+        if let Some(ref mut license) = overlay.config.license {
+            license.eula = Some(embedded);
+        }
+        Ok(())
+    }
+
+    /// Append the preview watermark to the injected JS/CSS
+    fn inject_preview_watermark(&self, overlay: &mut OverlayData) {
+        let Some(ref license) = self.config.license else {
+            return;
+        };
+        let Some(ref preview) = license.preview else {
+            return;
+        };
+        let Some(script) = preview.script(license) else {
+            return;
+        };
+        let style = preview.style();
+
+        let config = &mut overlay.config;
+        config.inject_js = Some(match config.inject_js.take() {
+            Some(js) => format!("{}\n{}", js, script),
+            None => script,
+        });
+        config.inject_css = Some(match config.inject_css.take() {
+            Some(css) => format!("{}\n{}", css, style),
+            None => style,
+        });
+        tracing::info!(
+            "Injected preview watermark (expires {})",
+            license.expires_at.as_deref().unwrap_or_default()
+        );
+    }
+
+    /// Embed CA bundles and client certificates into the overlay
+    ///
+    /// Files are stored under `certs/` and the overlay config is rewritten to
+    /// reference the embedded asset paths instead of build machine paths.
+    fn embed_network_certificates(&self, overlay: &mut OverlayData) -> PackResult<usize> {
+        let network = &self.config.network;
+        if network.is_empty() {
+            return Ok(0);
+        }
+
+        let mut embedded = network.clone();
+        let mut count = 0;
+
+        for (i, path) in network.ca_certificates.iter().enumerate() {
+            let content = fs::read(path)?;
+            if !String::from_utf8_lossy(&content).contains("-----BEGIN CERTIFICATE-----") {
+                return Err(PackError::Config(format!(
+                    "CA bundle is not a PEM certificate file: {}",
+                    path.display()
+                )));
+            }
+            let asset = format!("certs/ca/{}/{}", i, file_name_of(path));
+            overlay.add_asset(asset.clone(), content);
+            embedded.ca_certificates[i] = PathBuf::from(asset);
+            count += 1;
+        }
+
+        for (i, cert) in network.client_certificates.iter().enumerate() {
+            let asset = format!("certs/client/{}/{}", i, file_name_of(&cert.path));
+            overlay.add_asset(asset.clone(), fs::read(&cert.path)?);
+            embedded.client_certificates[i].path = PathBuf::from(asset);
+            count += 1;
+
+            if let Some(ref key) = cert.key {
+                let content = fs::read(key)?;
+                if !String::from_utf8_lossy(&content).contains("ENCRYPTED") {
+                    tracing::warn!(
+                        "Embedding unencrypted private key {}; prefer a password-protected \
+                         PKCS#12 file with password_env, or the OS certificate store",
+                        key.display()
+                    );
+                }
+                let asset = format!("certs/client/{}/{}", i, file_name_of(key));
+                overlay.add_asset(asset.clone(), content);
+                embedded.client_certificates[i].key = Some(PathBuf::from(asset));
+                count += 1;
+            } else if cert.is_pkcs12() && cert.password_env.is_none() {
+                tracing::warn!(
+                    "PKCS#12 certificate {} has no password_env; it must not be password-protected",
+                    cert.path.display()
+                );
+            }
+        }
+
+        overlay.config.network = embedded;
+        tracing::info!("Embedded {} certificate files", count);
+        Ok(count)
+    }
+
+    /// Stage the overlay-bound files for bundlers that take plain files
+    ///
+    /// PyOxidizer builds carry no overlay, so the frontend bundle (with the
+    /// HTML branding applied) goes under `frontend/` and the certificates
+    /// under `certs/`, with the rewritten runtime network settings in
+    /// `network.json`. Returns the directory and the frontend asset count.
+    fn stage_bundle_resources(
+        &self,
+        frontend_path: &Path,
+    ) -> PackResult<(tempfile::TempDir, usize)> {
+        let staged = self.staging_dir("resources")?;
+        let frontend_bundle = self.build_frontend_bundle(frontend_path)?;
+        let asset_count = frontend_bundle.len();
+
+        let mut overlay = OverlayData::new(self.config.clone());
+        for (path, content) in frontend_bundle.into_assets() {
+            overlay.add_asset(format!("frontend/{}", path), content);
+        }
+        self.embed_network_certificates(&mut overlay)?;
+        if !overlay.config.network.is_empty() {
+            overlay.add_asset(
+                "network.json",
+                serde_json::to_vec_pretty(&overlay.config.network)?,
+            );
+        }
+
+        for (path, content) in &overlay.assets {
+            let target = staged.path().join(path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(target, content)?;
+        }
+        Ok((staged, asset_count))
+    }
+
+    /// Embed the icons of jump-list / dock menu tasks
+    fn embed_shortcut_icons(&self, overlay: &mut OverlayData) -> PackResult<usize> {
+        let Some(ref shortcuts) = self.config.shortcuts else {
+            return Ok(0);
+        };
+
+        let mut embedded = shortcuts.clone();
+        let mut count = 0;
+        for (i, task) in shortcuts.tasks.iter().enumerate() {
+            if let Some(ref icon) = task.icon {
+                let asset = format!("shortcuts/{}/{}", i, file_name_of(icon));
+                overlay.add_asset(asset.clone(), fs::read(icon)?);
+                embedded.tasks[i].icon = Some(PathBuf::from(asset));
+                count += 1;
+            }
+        }
+
+        overlay.config.shortcuts = Some(embedded);
+        if count > 0 {
+            tracing::info!("Embedded {} shortcut task icons", count);
+        }
+        Ok(count)
     }
 
     fn collect_hook_resources(&self, overlay: &mut OverlayData) -> PackResult<usize> {
@@ -2077,6 +4131,239 @@ elif spec and spec.origin:
 
     /// Validate the configuration
     fn validate(&self) -> PackResult<()> {
+        if self.config.profile.is_dev() && self.config.dev_server_url.is_none() {
+            return Err(PackError::Config(
+                "The dev profile requires a dev server URL".to_string(),
+            ));
+        }
+
+        if self.config.test_run.as_ref().is_some_and(|t| t.enabled)
+            && self.config.remote_debugging_port.is_none()
+        {
+            return Err(PackError::Config(
+                "[debug.test] requires debug.remote_debugging_port".to_string(),
+            ));
+        }
+
+        // Validate the output name and generated path lengths
+        crate::output_path::validate_output_paths(&self.config)?;
+
+        if let Some(ref identifier) = self.config.identifier {
+            crate::identity::validate_identifier(identifier)?;
+        }
+
+        // Reject user-agent templates that were never expanded
+        if let Some(ref user_agent) = self.config.user_agent {
+            crate::user_agent::validate_user_agent(user_agent)?;
+        }
+
+        // Validate pack history thresholds
+        if let Some(ref history) = self.config.history {
+            history.validate()?;
+        }
+
+        // Validate the artifact store's remote cache and the HTTP settings
+        self.config.store.validate()?;
+        self.config.http.validate()?;
+
+        // Validate binary size optimization
+        if let Some(ref optimize) = self.config.optimize {
+            optimize.validate()?;
+        }
+
+        // Validate pinned toolchains
+        if let Some(ref go) = self.config.go_toolchain {
+            go.validate("backend.go.toolchain")?;
+        }
+        if let Some(ref rust) = self.config.rust_toolchain {
+            rust.validate("backend.rust.toolchain")?;
+        }
+
+        // Validate frontend dependency install
+        if let Some(ref deps) = self.config.frontend_dependencies {
+            deps.validate()?;
+        }
+
+        // Validate the [frontend] include/exclude globs
+        crate::bundle::validate_globs(
+            &self.config.frontend_include,
+            &self.config.frontend_exclude,
+        )?;
+        if let Some(ref transform) = self.config.frontend_transform {
+            transform.validate()?;
+        }
+
+        // Validate argv-based hook commands
+        if let Some(ref hooks) = self.config.hooks {
+            for hook in &hooks.run {
+                hook.validate()?;
+            }
+            if let Some(ref isolation) = hooks.isolation {
+                isolation.validate("hooks.isolation")?;
+            }
+        }
+
+        // Validate the backend environment contract
+        if let Some(python) = self.config.mode.python_config() {
+            python.isolation.validate("python.isolation")?;
+        }
+
+        // Validate scheduled reload/restart policies
+        for entry in &self.config.schedule {
+            CronSpec::parse(&entry.cron)?;
+        }
+
+        // Validate the Windows shell identity and quick actions
+        if let Some(ref app_user_model) = self.config.app_user_model {
+            app_user_model.validate()?;
+        }
+        if let Some(ref signing) = self.config.signing {
+            signing.validate()?;
+            if let Some(ref key_id) = signing.key_id {
+                signing.keystore().info(key_id)?;
+            }
+            // Data appended after an Authenticode signature invalidates it
+            if signing.signs_executables()
+                && self.config.target_platform.exe_extension() == ".exe"
+                && self.config.overlay_placement == OverlayPlacement::Embedded
+            {
+                return Err(PackError::Config(
+                    "[signing.external] authenticode_command requires [bundle] overlay = \"sidecar\""
+                        .to_string(),
+                ));
+            }
+        }
+        // Portable and system Python always write a directory
+        if let PackMode::FullStack { ref python, .. } = self.config.mode {
+            if python.strategy.is_directory() && self.config.layout == Some(OutputLayout::Onefile) {
+                return Err(PackError::Config(format!(
+                    "[build] layout = \"onefile\" is not supported by strategy = \"{}\", which always writes a directory",
+                    python.strategy.as_str()
+                )));
+            }
+        }
+
+        if let Some(ref provenance) = self.config.provenance {
+            provenance.validate()?;
+            if self.config.signing.is_none() {
+                return Err(PackError::Config(
+                    "[build.provenance] requires [signing]".to_string(),
+                ));
+            }
+        }
+        if let Some(ref update_feed) = self.config.update_feed {
+            update_feed.validate()?;
+            if self.config.app_version.is_none() {
+                return Err(PackError::Config(
+                    "[bundle.update_feed] requires a package version".to_string(),
+                ));
+            }
+        }
+        if let Some(ref dpi) = self.config.window.dpi {
+            dpi.validate()?;
+        }
+        if let Some(ref theme) = self.config.window.theme {
+            theme.validate()?;
+        }
+        if let Some(ref content) = self.config.window.content {
+            content.validate()?;
+        }
+        if let Some(ref shortcuts) = self.config.shortcuts {
+            shortcuts.validate()?;
+        }
+
+        // Validate storage persistence policy
+        if let Some(ref storage) = self.config.storage {
+            storage.validate()?;
+        }
+
+        // Validate print policy
+        if let Some(ref print) = self.config.print {
+            print.validate()?;
+        }
+
+        // Validate multi-version coexistence
+        if let Some(ref versioning) = self.config.versioning {
+            versioning.validate(
+                self.config.app_version.as_deref(),
+                matches!(self.config.mode, PackMode::FullStack { .. }),
+            )?;
+        }
+
+        // Validate the self-update rollback policy
+        if let Some(ref rollback) = self.config.rollback {
+            rollback.validate()?;
+        }
+
+        // Validate the update channel and version constraints
+        if let Some(ref update) = self.config.update {
+            update.validate(self.config.app_version.as_deref())?;
+        }
+
+        // Validate file system permissions
+        if let Some(ref permissions) = self.config.permissions {
+            permissions.validate()?;
+        }
+
+        // Validate uninstall helpers
+        if let Some(ref uninstall) = self.config.uninstall {
+            uninstall.validate()?;
+        }
+
+        // Validate license policy
+        if let Some(ref policy) = self.config.license_policy {
+            policy.validate()?;
+        }
+
+        if self.config.threads == Some(0) {
+            return Err(PackError::Config(
+                "[build] threads must be at least 1".to_string(),
+            ));
+        }
+
+        // Every target of the build matrix needs a launcher
+        for target in &self.config.targets {
+            Packer::new(self.config.for_target(*target)).launcher_path()?;
+        }
+        self.launcher_path()?;
+
+        // Validate the EULA and preview watermark
+        if let Some(ref license) = self.config.license {
+            if let Some(ref eula) = license.eula {
+                eula.validate()?;
+                if !eula.path.is_file() {
+                    return Err(PackError::AssetNotFound(eula.path.clone()));
+                }
+            }
+            if let Some(ref preview) = license.preview {
+                preview.validate(license)?;
+            }
+        }
+
+        // Validate About-screen files
+        if let Some(ref about) = self.config.about {
+            for path in about.changelog.iter().chain(about.license_file.iter()) {
+                if !path.is_file() {
+                    return Err(PackError::AssetNotFound(path.clone()));
+                }
+            }
+        }
+
+        // Validate runtime network settings and certificate files
+        let network = &self.config.network;
+        network.validate()?;
+        let cert_files = network.ca_certificates.iter().chain(
+            network
+                .client_certificates
+                .iter()
+                .flat_map(|c| std::iter::once(&c.path).chain(c.key.as_ref())),
+        );
+        for path in cert_files {
+            if !path.is_file() {
+                return Err(PackError::AssetNotFound(path.clone()));
+            }
+        }
+
         match &self.config.mode {
             PackMode::Url { url } => {
                 if url.is_empty() {
@@ -2084,39 +4371,13 @@ elif spec and spec.origin:
                 }
             }
             PackMode::Frontend { path } => {
-                if !path.exists() {
-                    return Err(PackError::FrontendNotFound(path.clone()));
-                }
-
-                // Check for index.html
-                let index_path = if path.is_dir() {
-                    path.join("index.html")
-                } else {
-                    path.clone()
-                };
-
-                if !index_path.exists() {
-                    return Err(PackError::FrontendNotFound(index_path));
-                }
+                self.validate_frontend(path)?;
             }
             PackMode::FullStack {
                 frontend_path,
                 python,
             } => {
-                // Validate frontend
-                if !frontend_path.exists() {
-                    return Err(PackError::FrontendNotFound(frontend_path.clone()));
-                }
-
-                let index_path = if frontend_path.is_dir() {
-                    frontend_path.join("index.html")
-                } else {
-                    frontend_path.clone()
-                };
-
-                if !index_path.exists() {
-                    return Err(PackError::FrontendNotFound(index_path));
-                }
+                self.validate_frontend(frontend_path)?;
 
                 // Validate Python entry point
                 if python.entry_point.is_empty() {
@@ -2124,36 +4385,428 @@ elif spec and spec.origin:
                         "Python entry_point is required for fullstack mode".to_string(),
                     ));
                 }
+
+                // Fail before downloading runtimes if it cannot be imported
+                crate::entry_point::check_entry_point(python)?;
+
+                // Validate CUDA library handling
+                if let Some(ref cuda) = python.cuda {
+                    cuda.validate()?;
+                }
+
+                // Validate the prebuilt environment archive
+                if let Some(ref env_archive) = python.env_archive {
+                    if python.strategy != BundleStrategy::Standalone {
+                        return Err(PackError::Config(format!(
+                            "[backend.python] env_archive requires strategy = \"standalone\" \
+                             (got {:?})",
+                            python.strategy
+                        )));
+                    }
+                    if !env_archive.is_file() {
+                        return Err(PackError::AssetNotFound(env_archive.clone()));
+                    }
+                    if !python.packages.is_empty() || python.requirements.is_some() {
+                        tracing::warn!(
+                            "[backend.python] packages/requirements are ignored with env_archive \
+                             (the environment archive provides all packages)"
+                        );
+                    }
+                }
+
+                // Validate the system Python launcher
+                if let Some(ref system) = python.system {
+                    if python.strategy != BundleStrategy::System {
+                        return Err(PackError::Config(format!(
+                            "[backend.python.system] requires strategy = \"system\" (got {:?})",
+                            python.strategy
+                        )));
+                    }
+                    system.validate()?;
+                }
+
+                // Validate the embedded strategy's runtime check
+                if let Some(ref embedded) = python.embedded {
+                    if python.strategy != BundleStrategy::Embedded {
+                        return Err(PackError::Config(format!(
+                            "[backend.python.embedded] requires strategy = \"embedded\" (got {:?})",
+                            python.strategy
+                        )));
+                    }
+                    embedded.validate()?;
+                }
+
+                // The license policy is checked against the metadata of the
+                // installed packages, which only these strategies keep
+                let bundles_packages = !python.packages.is_empty() || python.requirements.is_some();
+                if self.config.license_policy.is_some()
+                    && bundles_packages
+                    && !python.strategy.bundles_standalone()
+                    && python.strategy != BundleStrategy::System
+                {
+                    return Err(PackError::LicensePolicy(format!(
+                        "cannot check the packages bundled with strategy = \"{}\"; \
+                         use \"standalone\" or \"portable\" with [build.license_policy]",
+                        python.strategy.as_str()
+                    )));
+                }
+            }
+            PackMode::Process {
+                frontend_path,
+                launch,
+            } => {
+                self.validate_frontend(frontend_path)?;
+
+                // Validate backend binary
+                if !launch.binary.is_file() {
+                    return Err(PackError::AssetNotFound(launch.binary.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the frontend path (skipped when a dev server provides the frontend)
+    fn validate_frontend(&self, path: &Path) -> PackResult<()> {
+        if self.config.uses_dev_server() {
+            let url = self.config.dev_server_url.as_deref().unwrap_or_default();
+            if url.is_empty() {
+                return Err(PackError::InvalidUrl(
+                    "Dev server URL cannot be empty".to_string(),
+                ));
             }
+            return Ok(());
+        }
+
+        if !path.exists() {
+            return Err(PackError::FrontendNotFound(path.to_path_buf()));
+        }
+
+        // Check for index.html
+        let index_path = if path.is_dir() {
+            path.join("index.html")
+        } else {
+            path.to_path_buf()
+        };
+
+        if !index_path.exists() {
+            return Err(PackError::FrontendNotFound(index_path));
         }
 
         Ok(())
     }
 
+    /// Build the frontend asset bundle
+    ///
+    /// Returns an empty bundle when a dev server provides the frontend, so
+    /// dev packs only carry the backend.
+    fn build_frontend_bundle(&self, frontend_path: &Path) -> PackResult<AssetBundle> {
+        if self.config.uses_dev_server() {
+            tracing::info!(
+                "Dev profile: loading frontend from {} (assets not embedded)",
+                self.config.dev_server_url.as_deref().unwrap_or_default()
+            );
+            return Ok(AssetBundle::new());
+        }
+        let mut bundle = self.frontend_builder(frontend_path).build()?;
+        if let Some(ref branding) = self.config.html_branding {
+            branding.apply_to_bundle(&mut bundle);
+        }
+        if let Some(ref print) = self.config.print {
+            print.apply_to_bundle(&mut bundle);
+        }
+        Ok(bundle)
+    }
+
+    /// Bundle builder applying the `[frontend]` globs
+    fn frontend_builder(&self, frontend_path: &Path) -> BundleBuilder {
+        let mut builder = BundleBuilder::new(frontend_path)
+            .include_globs(&self.config.frontend_include)
+            .exclude_globs(&self.config.frontend_exclude);
+        if let Some(threads) = self.config.threads {
+            builder = builder.with_threads(threads);
+        }
+        if let Some(ref transform) = self.config.frontend_transform {
+            builder = builder.with_transform(transform.clone());
+        }
+        builder
+    }
+
+    /// Artifact store shared by the pack caches (`[build.store]`)
+    fn store(&self) -> ArtifactStore {
+        let config = StoreConfig {
+            dir: self
+                .context
+                .cache_dir(self.config.store.dir.as_deref(), "store"),
+            ..self.config.store.clone()
+        };
+        ArtifactStore::open(&config).with_http(self.http.clone())
+    }
+
+    /// Environment pointing pip/uv at their caches in the artifact store
+    /// (unless set by the user)
+    fn pip_cache_env(&self) -> Vec<(&'static str, PathBuf)> {
+        let store = self.store();
+        [("PIP_CACHE_DIR", "pip"), ("UV_CACHE_DIR", "uv")]
+            .into_iter()
+            .filter(|(var, _)| std::env::var_os(var).is_none())
+            .map(|(var, tool)| (var, store.tool_dir(tool)))
+            .collect()
+    }
+
+    /// Create a unique staging directory for one pack step (removed on drop)
+    fn staging_dir(&self, purpose: &str) -> PackResult<tempfile::TempDir> {
+        let root = self.context.staging_root(&self.config);
+        crate::staging::create_staging_dir(&root, &self.run_id, purpose)
+    }
+
+    /// Directory the executable is written to: the output directory, or
+    /// `<output_dir>/<name>/` with `[build] layout = "onedir"`
+    fn exe_dir(&self) -> PathBuf {
+        match self.config.layout() {
+            OutputLayout::Onefile => self.config.output_dir.clone(),
+            OutputLayout::Onedir => self.config.output_dir.join(&self.config.output_name),
+        }
+    }
+
+    /// Whether overlay assets are written as files next to the executable
+    ///
+    /// True for `onedir` output of every mode except the Python strategies
+    /// that lay out their directory themselves (`portable`, `system`).
+    fn writes_asset_files(&self) -> bool {
+        let directory_strategy = matches!(
+            self.config.mode,
+            PackMode::FullStack { ref python, .. } if python.strategy.is_directory()
+        );
+        self.config.layout() == OutputLayout::Onedir && !directory_strategy
+    }
+
     /// Get the output executable name with platform extension
     fn get_exe_name(&self) -> String {
-        #[cfg(target_os = "windows")]
-        {
-            format!("{}.exe", self.config.output_name)
+        format!(
+            "{}{}",
+            self.config.output_name,
+            self.config.target_platform.exe_extension()
+        )
+    }
+
+    /// Launcher executable copied into the output
+    ///
+    /// The context's launcher (the running packer) for its own platform,
+    /// the `[build.stubs]` entry of the target otherwise.
+    fn launcher_path(&self) -> PackResult<PathBuf> {
+        let Some(target) = self.config.build_target else {
+            return self.context_launcher();
+        };
+        match self.config.stub(&target) {
+            Some(stub) if stub.is_file() => Ok(stub.clone()),
+            Some(stub) => Err(PackError::Config(format!(
+                "Launcher stub for {} not found: {}",
+                target,
+                stub.display()
+            ))),
+            None if self.context.is_current(&target) => self.context_launcher(),
+            None => Err(PackError::Config(format!(
+                "Target {} needs a launcher stub ([build.stubs] \"{}\" = \"...\")",
+                target, target
+            ))),
         }
-        #[cfg(not(target_os = "windows"))]
-        {
-            self.config.output_name.clone()
+    }
+
+    /// Launcher of the context's platform
+    fn context_launcher(&self) -> PackResult<PathBuf> {
+        self.context
+            .launcher()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| {
+                PackError::Config("No launcher: the running executable is unknown".to_string())
+            })
+    }
+
+    /// Build cache directory: `[build.incremental] dir`, under the
+    /// context's cache root, or the default
+    fn build_cache_dir(&self, incremental: &IncrementalConfig) -> PathBuf {
+        let identity = self.config.identity();
+        match (&incremental.dir, self.context.cache_root()) {
+            (None, Some(root)) => root.join("build-cache").join(identity.cache_key()),
+            _ => incremental.cache_dir(&identity),
+        }
+    }
+
+    /// Checkpoint directory: `[build.resume] dir`, under the context's
+    /// cache root, or the default
+    fn checkpoint_dir(&self, resume: &ResumeConfig) -> PathBuf {
+        let identity = self.config.identity();
+        match (&resume.dir, self.context.cache_root()) {
+            (None, Some(root)) => root.join("resume").join(identity.cache_key()),
+            _ => resume.checkpoint_dir(&identity),
+        }
+    }
+
+    /// Record the Python distribution used for the build report
+    fn record_python_distribution(
+        &self,
+        standalone: &PythonStandalone,
+        archive: &Path,
+    ) -> PackResult<()> {
+        self.record_tool("python", standalone.version());
+        let name = format!("python-{}", standalone.target().triple());
+        self.record_download(&name, &standalone.source(), archive)
+    }
+
+    /// Python distribution manager using the pack's store and provider
+    fn python_standalone(&self, config: PythonStandaloneConfig) -> PackResult<PythonStandalone> {
+        let standalone = PythonStandalone::new(config)?.with_store(self.store());
+        Ok(match self.python_provider {
+            Some(ref provider) => standalone.with_provider(provider.clone()),
+            None => standalone,
+        })
+    }
+
+    /// Python distribution triple of the target (`None`: the current platform)
+    fn python_target_triple(&self) -> PackResult<Option<String>> {
+        self.config
+            .build_target
+            .map(|target| Ok(target.python_target()?.triple().to_string()))
+            .transpose()
+    }
+}
+
+/// Get the file name of a path as a string (for asset naming)
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "cert".to_string())
+}
+
+/// `hook` with the build variables in its program, arguments and
+/// environment substituted
+fn expand_hook_command(hook: &crate::HookCommand, vars: &HookVars) -> crate::HookCommand {
+    crate::HookCommand {
+        program: vars.expand(&hook.program),
+        args: hook.args.iter().map(|arg| vars.expand(arg)).collect(),
+        env: hook
+            .env
+            .iter()
+            .map(|(name, value)| (name.clone(), vars.expand(value)))
+            .collect(),
+        ..hook.clone()
+    }
+}
+
+/// Shell hook commands of a stage (`vx`-prefixed where configured)
+fn shell_hook_commands(hooks: &crate::HooksConfig, stage: crate::DownloadStage) -> Vec<String> {
+    let mut commands: Vec<String> = match stage {
+        crate::DownloadStage::BeforeCollect => hooks.before_collect.clone(),
+        crate::DownloadStage::BeforePack => hooks.before_pack.clone(),
+        crate::DownloadStage::AfterPack => hooks.after_pack.clone(),
+    };
+
+    let vx_stage_cmds: Vec<String> = match stage {
+        crate::DownloadStage::BeforeCollect => hooks.vx.before_collect.clone(),
+        crate::DownloadStage::BeforePack => hooks.vx.before_pack.clone(),
+        crate::DownloadStage::AfterPack => hooks.vx.after_pack.clone(),
+    };
+
+    let use_vx = hooks.use_vx || !vx_stage_cmds.is_empty();
+
+    if use_vx {
+        commands = commands.into_iter().map(|c| format!("vx {}", c)).collect();
+    }
+
+    for cmd in vx_stage_cmds {
+        commands.push(format!("vx {}", cmd));
+    }
+    commands
+}
+
+/// Whether a Python source path matches a `[backend.python] exclude` pattern
+fn python_path_excluded(path: &str, exclude: &[String]) -> bool {
+    exclude.iter().any(|pattern| {
+        if pattern.contains('*') {
+            let pattern = pattern.replace("*", "");
+            path.contains(&pattern)
+        } else {
+            path.contains(pattern)
+        }
+    })
+}
+
+/// Write overlay assets as plain files below `dir` (`[build] layout = "onedir"`)
+fn write_asset_files(
+    dir: &Path,
+    overlay: &OverlayData,
+    files: &[(&str, &Path)],
+    progress: Option<&ProgressTracker>,
+) -> PackResult<IntegrityManifest> {
+    let mut manifest = IntegrityManifest::default();
+    let destination = |path: &str| -> PackResult<PathBuf> {
+        let dest = crate::overlay::extraction_path(dir, path)?;
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _ = fs::remove_file(&dest);
+        Ok(dest)
+    };
+    for (path, content) in &overlay.assets {
+        let dest = destination(path)?;
+        let attributes = overlay.attributes.get(path);
+        let written = || {
+            if let Some(progress) = progress {
+                progress.file_written(path, content.len() as u64);
+            }
+        };
+        #[cfg(unix)]
+        if attributes.is_some_and(|a| a.symlink) {
+            std::os::unix::fs::symlink(String::from_utf8_lossy(content).as_ref(), &dest)?;
+            written();
+            continue;
         }
+        fs::write(&dest, content)?;
+        manifest.add(path.clone(), content);
+        written();
+        #[cfg(unix)]
+        if let Some(mode) = attributes.and_then(|a| a.mode) {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&dest, fs::Permissions::from_mode(mode))?;
+        }
+        #[cfg(not(unix))]
+        let _ = attributes;
+    }
+    for (path, file) in files {
+        let entry = copy_hashed(path, file, &destination(path)?)?;
+        if let Some(progress) = progress {
+            progress.file_written(path, entry.size);
+        }
+        manifest.files.push(entry);
     }
+    tracing::info!(
+        "Wrote {} assets to {}",
+        overlay.assets.len() + files.len(),
+        dir.display()
+    );
+    Ok(manifest)
 }
 
-/// Calculate total size of a directory recursively
-fn calculate_dir_size(path: &Path) -> PackResult<u64> {
-    let mut total = 0;
-    for entry in walkdir::WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-    {
-        total += entry.metadata().map(|m| m.len()).unwrap_or(0);
+/// Copy the asset `path` from `file` to `dest` (with its permissions),
+/// hashing it on the way
+fn copy_hashed(path: &str, file: &Path, dest: &Path) -> PackResult<IntegrityEntry> {
+    let mut reader = BufReader::new(fs::File::open(file)?);
+    let mut writer = BufWriter::new(fs::File::create(dest)?);
+    let mut digester = Digester::default();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        digester.update(&buf[..n]);
+        writer.write_all(&buf[..n])?;
     }
-    Ok(total)
+    writer.flush()?;
+    fs::set_permissions(dest, fs::metadata(file)?.permissions())?;
+    Ok(IntegrityEntry::new(path, digester.finalize()))
 }
 
 impl PackConfig {
@@ -2179,7 +4832,19 @@ impl PackConfig {
         } else if let Some(ref frontend_path) = manifest.get_frontend_path() {
             let resolved = resolve_path(frontend_path);
 
-            if manifest.is_fullstack() {
+            if manifest.get_backend_type() == BackendType::Process {
+                // Process mode: prebuilt binary from backend.process
+                let launch = manifest.get_launch_spec(base_dir).ok_or_else(|| {
+                    PackError::Config(
+                        "'command' in [backend.process] required for process backend".to_string(),
+                    )
+                })?;
+
+                PackMode::Process {
+                    frontend_path: resolved,
+                    launch: Box::new(launch),
+                }
+            } else if manifest.is_fullstack() {
                 // FullStack mode: get Python config from backend.python
                 let python = manifest.get_python_bundle_config(base_dir).ok_or_else(|| {
                     PackError::Config("Python config required for fullstack mode".to_string())
@@ -2199,7 +4864,13 @@ impl PackConfig {
         };
 
         // Use the unified window config conversion
-        let window = manifest.get_window_config();
+        let mut window = manifest.get_window_config();
+
+        // Kiosk settings (fullscreen lock also adjusts the window)
+        let kiosk = manifest.runtime.as_ref().and_then(|r| r.kiosk.clone());
+        if let Some(ref kiosk) = kiosk {
+            kiosk.apply_to_window(&mut window);
+        }
 
         // Build environment variables from runtime config and backend.python env
         let mut env = std::collections::HashMap::new();
@@ -2216,7 +4887,10 @@ impl PackConfig {
         }
 
         // License config is already using the common type
-        let license = manifest.license.clone();
+        let mut license = manifest.license.clone();
+        if let Some(eula) = license.as_mut().and_then(|l| l.eula.as_mut()) {
+            eula.path = base_dir.join(&eula.path);
+        }
 
         // Use the conversion method from HooksManifestConfig
         let hooks = manifest.hooks.as_ref().map(|h| h.to_hooks_config(base_dir));
@@ -2247,18 +4921,8 @@ impl PackConfig {
                             icon_data.original_format
                         );
 
-                        // Save converted ICO to temp file for Windows resource editor
-                        let temp_ico_path = std::env::temp_dir()
-                            .join(format!("auroraview-icon-{}.ico", std::process::id()));
-                        if let Err(e) = crate::icon::save_ico(&icon_data.ico_data, &temp_ico_path) {
-                            tracing::warn!("Failed to save temp ICO: {}", e);
-                        } else {
-                            win_config.icon = Some(temp_ico_path);
-                            tracing::info!(
-                                "Auto-generated multi-resolution ICO ({} bytes)",
-                                icon_data.ico_data.len()
-                            );
-                        }
+                        // Converted to ICO in a staging directory when packing
+                        win_config.icon = Some(path.clone());
 
                         Some(icon_data.png_data)
                     }
@@ -2274,6 +4938,23 @@ impl PackConfig {
             (win_config, window_icon_data, effective_icon_path)
         };
 
+        // Resolve certificate paths for runtime network settings
+        let network = manifest
+            .network
+            .as_ref()
+            .map(|n| {
+                let mut runtime = n.runtime.clone();
+                for path in runtime.ca_certificates.iter_mut() {
+                    *path = resolve_path(path);
+                }
+                for cert in runtime.client_certificates.iter_mut() {
+                    cert.path = resolve_path(&cert.path);
+                    cert.key = cert.key.as_ref().map(&resolve_path);
+                }
+                runtime
+            })
+            .unwrap_or_default();
+
         // Resolve output directory
         let output_dir = manifest
             .build
@@ -2282,15 +4963,41 @@ impl PackConfig {
             .map(&resolve_path)
             .unwrap_or_else(|| base_dir.to_path_buf());
 
+        let output_name = if manifest.build.sanitize_name {
+            let sanitized = crate::output_path::sanitize_output_name(&manifest.package.name);
+            if sanitized != manifest.package.name {
+                tracing::warn!(
+                    "Output name '{}' sanitized to '{}'",
+                    manifest.package.name,
+                    sanitized
+                );
+            }
+            sanitized
+        } else {
+            manifest.package.name.clone()
+        };
+
         Ok(Self {
             mode,
-            output_name: manifest.package.name.clone(),
+            output_name,
+            identifier: manifest.get_identifier(),
+            app_version: Some(manifest.package.version.clone()),
             output_dir,
             window,
             target_platform: crate::TargetPlatform::Current,
             debug: manifest.debug.enabled,
             allow_new_window: manifest.get_allow_new_window(),
-            user_agent: manifest.get_user_agent(),
+            user_agent: manifest
+                .get_user_agent()
+                .map(|template| {
+                    let vars = crate::UserAgentVars::new(
+                        &manifest.package.name,
+                        &manifest.package.version,
+                        crate::TargetPlatform::Current,
+                    );
+                    crate::expand_user_agent(&template, &vars)
+                })
+                .transpose()?,
             inject_js: manifest.inject.as_ref().and_then(|i| i.js_code.clone()),
             inject_css: manifest.inject.as_ref().and_then(|i| i.css_code.clone()),
             icon_path,
@@ -2299,10 +5006,120 @@ impl PackConfig {
             license,
             hooks,
             remote_debugging_port: manifest.debug.remote_debugging_port,
+            app_user_model: windows_resource.app_user_model.clone(),
             windows_resource,
             vx: manifest.vx.clone(),
             downloads: manifest.downloads.clone(),
             compression_level: manifest.build.compression_level,
+            codecs: manifest.build.codecs,
+            incremental: manifest.build.incremental.clone(),
+            resume: manifest.build.resume.clone(),
+            skip_unchanged: manifest.build.skip_unchanged,
+            threads: manifest.build.threads,
+            provenance: manifest.build.provenance.clone(),
+            manifest_sha256: Some(format!(
+                "{:x}",
+                Sha256::digest(serde_json::to_vec(&serde_json::to_value(manifest)?)?)
+            )),
+            targets: manifest
+                .build
+                .targets
+                .iter()
+                .map(|target| crate::BuildTarget::parse(target))
+                .collect::<PackResult<_>>()?,
+            stubs: manifest
+                .build
+                .stubs
+                .iter()
+                .map(|(target, stub)| {
+                    Ok((
+                        crate::BuildTarget::parse(target)?.to_string(),
+                        resolve_path(stub),
+                    ))
+                })
+                .collect::<PackResult<_>>()?,
+            build_target: None,
+            build_metadata: manifest.build.metadata.clone(),
+            profile: manifest.build.profile,
+            dev_server_url: manifest.get_dev_server_url(),
+            test_run: manifest.debug.test.clone(),
+            smoke_test: manifest.debug.smoke_test.clone(),
+            frontend_include: manifest
+                .frontend
+                .as_ref()
+                .map(|f| f.include.clone())
+                .unwrap_or_default(),
+            frontend_exclude: manifest
+                .frontend
+                .as_ref()
+                .map(|f| f.exclude.clone())
+                .unwrap_or_default(),
+            frontend_transform: manifest.frontend.as_ref().and_then(|f| f.transform.clone()),
+            html_branding: manifest.get_html_branding(),
+            about: manifest.get_about_config(base_dir),
+            frontend_dependencies: manifest.get_frontend_dependencies(base_dir),
+            go_toolchain: manifest
+                .backend
+                .as_ref()
+                .and_then(|b| b.go.as_ref())
+                .and_then(|go| go.toolchain.clone()),
+            rust_toolchain: manifest
+                .backend
+                .as_ref()
+                .and_then(|b| b.rust.as_ref())
+                .and_then(|rust| rust.toolchain.clone()),
+            license_policy: manifest.build.license_policy.clone(),
+            staging_dir: manifest.build.staging_dir.as_ref().map(&resolve_path),
+            file_retry: manifest.build.retry,
+            optimize: manifest.build.optimize.clone().map(|mut optimize| {
+                optimize.upx_path = optimize.upx_path.as_ref().map(&resolve_path);
+                optimize
+            }),
+            symbols: manifest.build.symbols.clone().map(|mut symbols| {
+                symbols.dir = symbols.dir.as_ref().map(&resolve_path);
+                symbols
+            }),
+            store: StoreConfig {
+                dir: manifest.build.store.dir.as_ref().map(&resolve_path),
+                ..manifest.build.store.clone()
+            },
+            http: manifest.build.http.clone(),
+            history: manifest.build.history.clone().map(|mut history| {
+                history.dir = history.dir.as_ref().map(&resolve_path);
+                history
+            }),
+            kiosk,
+            schedule: manifest
+                .runtime
+                .as_ref()
+                .map(|r| r.schedule.clone())
+                .unwrap_or_default(),
+            storage: manifest.runtime.as_ref().and_then(|r| r.storage.clone()),
+            print: manifest.runtime.as_ref().and_then(|r| r.print.clone()),
+            versioning: manifest.runtime.as_ref().and_then(|r| r.versioning.clone()),
+            rollback: manifest.runtime.as_ref().and_then(|r| r.rollback.clone()),
+            update: manifest.runtime.as_ref().and_then(|r| r.update.clone()),
+            shortcuts: manifest.shortcuts.clone().map(|mut shortcuts| {
+                for task in &mut shortcuts.tasks {
+                    task.icon = task.icon.as_ref().map(&resolve_path);
+                }
+                shortcuts
+            }),
+            signing: manifest.signing.clone().map(|mut signing| {
+                signing.key = signing.key.as_ref().map(&resolve_path);
+                signing.keystore = signing.keystore.as_ref().map(&resolve_path);
+                if let Some(ref mut external) = signing.external {
+                    external.module = external.module.as_ref().map(&resolve_path);
+                }
+                signing
+            }),
+            permissions: manifest.permissions.clone(),
+            uninstall: manifest.uninstall.clone(),
+            overlay_placement: manifest.bundle.overlay,
+            layout: manifest.build.layout,
+            update_feed: manifest.bundle.update_feed.clone(),
+            network,
+            unknown_fields: BTreeMap::new(),
         })
     }
 }
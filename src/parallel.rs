@@ -0,0 +1,44 @@
+//! Worker threads for reading and compressing assets
+//!
+//! `[build] threads` bounds the threads the bundle builder and the overlay
+//! writer use; without it they share rayon's global pool (one thread per
+//! core). Work is split per asset and collected in input order, so the
+//! output does not depend on the thread count.
+
+use crate::{PackError, PackResult};
+use std::sync::Arc;
+
+/// Thread pool for asset work (`None` inside: the global rayon pool)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WorkerPool(Option<Arc<rayon::ThreadPool>>);
+
+impl WorkerPool {
+    /// Pool with `threads` workers (`None`: all cores)
+    pub(crate) fn new(threads: Option<usize>) -> PackResult<Self> {
+        let Some(threads) = threads else {
+            return Ok(Self::default());
+        };
+        // Workers log through the dispatcher of the pack (PackOptions)
+        let dispatch = tracing::dispatcher::get_default(tracing::Dispatch::clone);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("auroraview-pack-{}", i))
+            .start_handler(move |_| {
+                // Kept for the life of the worker thread
+                std::mem::forget(tracing::dispatcher::set_default(&dispatch));
+            })
+            .build()
+            .map_err(|e| {
+                PackError::Config(format!("Failed to start {} threads: {}", threads, e))
+            })?;
+        Ok(Self(Some(Arc::new(pool))))
+    }
+
+    /// Run `op` (and the parallel iterators it starts) on the pool
+    pub(crate) fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match self.0 {
+            Some(ref pool) => pool.install(op),
+            None => op(),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+//! Download, file dialog, clipboard and drag-drop permissions
+//!
+//! `[permissions]` limits what a wrapped web app can do with the local file
+//! system and clipboard. The policy is stored in the overlay config and
+//! enforced by the runtime, so kiosk machines cannot be used to pull
+//! arbitrary files in or push them out through a wrapped internal or
+//! third-party site:
+//!
+//! ```toml
+//! [permissions.downloads]
+//! allowed = true
+//! directory = "{downloads}/{app}"    # Where downloads are saved
+//! prompt = false                     # Save silently instead of asking
+//! extensions = ["pdf", "csv"]        # Only these file types (empty = any)
+//!
+//! [permissions.file_dialogs]
+//! allowed = true
+//! roots = ["{documents}/Reports"]    # Dialogs cannot leave these directories
+//! extensions = ["xlsx"]
+//!
+//! [permissions.clipboard]
+//! read = false                       # navigator.clipboard.read*, paste events
+//! write = true
+//!
+//! [permissions.drag_drop]
+//! files = false                      # Dropping files from the desktop
+//! ```
+//!
+//! Directory templates expand `{downloads}`, `{documents}`, `{desktop}`,
+//! `{home}`, `{temp}` and `{app}` (the package name).
+
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Placeholders of directory templates
+const DIR_PLACEHOLDERS: &[&str] = &["downloads", "documents", "desktop", "home", "temp", "app"];
+
+/// File system and clipboard permissions
+///
+/// Located at `[permissions]` in TOML.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionsConfig {
+    /// Download policy (`[permissions.downloads]`)
+    pub downloads: Option<DownloadsPermission>,
+
+    /// Open/save dialog policy (`[permissions.file_dialogs]`)
+    pub file_dialogs: Option<FileDialogPermission>,
+
+    /// Clipboard access policy (`[permissions.clipboard]`)
+    pub clipboard: Option<ClipboardPermission>,
+
+    /// External drag-drop policy (`[permissions.drag_drop]`)
+    pub drag_drop: Option<DragDropPermission>,
+}
+
+impl PermissionsConfig {
+    /// Validate all sections
+    pub fn validate(&self) -> PackResult<()> {
+        if let Some(ref downloads) = self.downloads {
+            downloads.validate()?;
+        }
+        if let Some(ref file_dialogs) = self.file_dialogs {
+            file_dialogs.validate()?;
+        }
+        if let Some(ref drag_drop) = self.drag_drop {
+            drag_drop.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Download policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadsPermission {
+    /// Allow downloads
+    pub allowed: bool,
+
+    /// Directory downloads are saved to (template)
+    pub directory: Option<String>,
+
+    /// Ask where to save each download; `false` saves into `directory`
+    pub prompt: bool,
+
+    /// Allowed file extensions without the dot (empty allows any)
+    pub extensions: Vec<String>,
+}
+
+impl Default for DownloadsPermission {
+    fn default() -> Self {
+        Self {
+            allowed: true,
+            directory: None,
+            prompt: true,
+            extensions: Vec::new(),
+        }
+    }
+}
+
+impl DownloadsPermission {
+    /// Downloads blocked entirely
+    pub fn denied() -> Self {
+        Self {
+            allowed: false,
+            ..Default::default()
+        }
+    }
+
+    /// Validate the policy
+    pub fn validate(&self) -> PackResult<()> {
+        const SECTION: &str = "[permissions.downloads]";
+        if let Some(ref directory) = self.directory {
+            validate_dir_template(SECTION, directory)?;
+        }
+        if !self.prompt && self.directory.is_none() {
+            return Err(PackError::Config(format!(
+                "{} prompt = false requires 'directory'",
+                SECTION
+            )));
+        }
+        validate_extensions(SECTION, &self.extensions)
+    }
+
+    /// Whether a file may be downloaded
+    pub fn allows(&self, file_name: &str) -> bool {
+        self.allowed && extension_allowed(&self.extensions, file_name)
+    }
+}
+
+/// Open/save dialog policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileDialogPermission {
+    /// Allow pages to open file dialogs (`<input type="file">`, save pickers)
+    pub allowed: bool,
+
+    /// Directories dialogs are confined to (templates; empty allows any)
+    pub roots: Vec<String>,
+
+    /// Selectable file extensions without the dot (empty allows any)
+    pub extensions: Vec<String>,
+}
+
+impl Default for FileDialogPermission {
+    fn default() -> Self {
+        Self {
+            allowed: true,
+            roots: Vec::new(),
+            extensions: Vec::new(),
+        }
+    }
+}
+
+impl FileDialogPermission {
+    /// Validate the policy
+    pub fn validate(&self) -> PackResult<()> {
+        const SECTION: &str = "[permissions.file_dialogs]";
+        for root in &self.roots {
+            validate_dir_template(SECTION, root)?;
+        }
+        validate_extensions(SECTION, &self.extensions)
+    }
+
+    /// Whether a file may be picked
+    pub fn allows(&self, file_name: &str) -> bool {
+        self.allowed && extension_allowed(&self.extensions, file_name)
+    }
+}
+
+/// Clipboard access policy
+///
+/// Applies to the async Clipboard API, `document.execCommand` and the
+/// clipboard shortcuts / context menu entries inside the WebView.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardPermission {
+    /// Allow pages to read (paste from) the clipboard
+    pub read: bool,
+
+    /// Allow pages to write (copy/cut to) the clipboard
+    pub write: bool,
+}
+
+impl Default for ClipboardPermission {
+    fn default() -> Self {
+        Self {
+            read: true,
+            write: true,
+        }
+    }
+}
+
+impl ClipboardPermission {
+    /// No clipboard access in either direction
+    pub fn denied() -> Self {
+        Self {
+            read: false,
+            write: false,
+        }
+    }
+}
+
+/// External drag-drop policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DragDropPermission {
+    /// Accept files dragged in from outside the app
+    pub files: bool,
+
+    /// Accept text and links dragged in from outside the app
+    pub text: bool,
+
+    /// Droppable file extensions without the dot (empty allows any)
+    pub extensions: Vec<String>,
+}
+
+impl Default for DragDropPermission {
+    fn default() -> Self {
+        Self {
+            files: true,
+            text: true,
+            extensions: Vec::new(),
+        }
+    }
+}
+
+impl DragDropPermission {
+    /// Validate the policy
+    pub fn validate(&self) -> PackResult<()> {
+        validate_extensions("[permissions.drag_drop]", &self.extensions)
+    }
+
+    /// Whether a dropped file is accepted
+    pub fn allows(&self, file_name: &str) -> bool {
+        self.files && extension_allowed(&self.extensions, file_name)
+    }
+}
+
+/// Expand a directory template for an app
+///
+/// Returns `None` if a placeholder's directory does not exist on this
+/// system (e.g., no desktop directory on a server).
+pub fn expand_dir_template(template: &str, app: &str) -> Option<PathBuf> {
+    let mut expanded = template.to_string();
+    for name in DIR_PLACEHOLDERS {
+        let placeholder = format!("{{{}}}", name);
+        if !expanded.contains(&placeholder) {
+            continue;
+        }
+        let value = match *name {
+            "downloads" => dirs::download_dir()?,
+            "documents" => dirs::document_dir()?,
+            "desktop" => dirs::desktop_dir()?,
+            "home" => dirs::home_dir()?,
+            "temp" => std::env::temp_dir(),
+            _ => PathBuf::from(app),
+        };
+        expanded = expanded.replace(&placeholder, &value.to_string_lossy());
+    }
+    Some(PathBuf::from(expanded))
+}
+
+/// Check a directory template's placeholders
+fn validate_dir_template(section: &str, template: &str) -> PackResult<()> {
+    if template.trim().is_empty() {
+        return Err(PackError::Config(format!(
+            "{} directories must not be empty",
+            section
+        )));
+    }
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+            break;
+        };
+        let name = &rest[open + 1..close];
+        if !DIR_PLACEHOLDERS.contains(&name) {
+            return Err(PackError::Config(format!(
+                "{} unknown placeholder {{{}}} in '{}' (expected one of: {})",
+                section,
+                name,
+                template,
+                DIR_PLACEHOLDERS.join(", ")
+            )));
+        }
+        rest = &rest[close + 1..];
+    }
+    Ok(())
+}
+
+/// Check an extension allow-list
+fn validate_extensions(section: &str, extensions: &[String]) -> PackResult<()> {
+    for extension in extensions {
+        let valid = !extension.is_empty()
+            && extension
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
+            && !extension.starts_with('.');
+        if !valid {
+            return Err(PackError::Config(format!(
+                "{} extension '{}' must be a bare extension like \"pdf\"",
+                section, extension
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Whether a file name matches an extension allow-list
+fn extension_allowed(extensions: &[String], file_name: &str) -> bool {
+    if extensions.is_empty() {
+        return true;
+    }
+    let lower = file_name.to_ascii_lowercase();
+    extensions
+        .iter()
+        .any(|extension| lower.ends_with(&format!(".{}", extension.to_ascii_lowercase())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_allowed() {
+        let extensions = vec!["pdf".to_string(), "tar.gz".to_string()];
+        assert!(extension_allowed(&extensions, "Report.PDF"));
+        assert!(extension_allowed(&extensions, "logs.tar.gz"));
+        assert!(!extension_allowed(&extensions, "payload.exe"));
+        assert!(!extension_allowed(&extensions, "pdf"));
+        assert!(extension_allowed(&[], "anything.bin"));
+    }
+
+    #[test]
+    fn test_expand_dir_template() {
+        let expanded = expand_dir_template("{temp}/{app}/exports", "viewer").unwrap();
+        assert_eq!(
+            expanded,
+            PathBuf::from(format!(
+                "{}/viewer/exports",
+                std::env::temp_dir().to_string_lossy()
+            ))
+        );
+        assert!(validate_dir_template("[s]", "{temp}/{app}").is_ok());
+        assert!(validate_dir_template("[s]", "{appdata}/x").is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! Dry-run build plans
+//!
+//! Backs [`Packer::plan`](crate::Packer::plan): everything a pack run would
+//! do, worked out without writing anything. Configuration is validated,
+//! downloads are checked against the `[vx]` policy and the local cache, and
+//! the assets to embed are enumerated from disk. CI can gate on the result
+//! (e.g. its JSON form) before spending time on a real pack.
+//!
+//! Hook-collected files and download contents are not known before the
+//! hooks run and the downloads are fetched, so sizes are lower bounds.
+
+use crate::{DownloadStage, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Where a planned asset comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetSource {
+    /// Frontend file
+    Frontend,
+    /// Python source file from `include_paths`
+    Python,
+    /// External binary or resource
+    Resource,
+    /// Backend process binary
+    Process,
+}
+
+impl AssetSource {
+    /// Short name used in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Frontend => "frontend",
+            Self::Python => "python",
+            Self::Resource => "resource",
+            Self::Process => "process",
+        }
+    }
+}
+
+/// Asset that would be embedded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedAsset {
+    /// Overlay path
+    pub path: String,
+    /// File it is read from
+    pub source_path: PathBuf,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    /// Kind of asset
+    pub source: AssetSource,
+}
+
+/// Download that would be fetched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedDownload {
+    /// Download name
+    pub name: String,
+    /// Source URL
+    pub url: String,
+    /// Stage it is fetched at
+    pub stage: DownloadStage,
+    /// Destination, relative to the output directory
+    pub dest: String,
+    /// A checksum is configured
+    pub verified: bool,
+    /// Cached artifact, if already downloaded (nothing is fetched)
+    pub cached: Option<PathBuf>,
+}
+
+/// External tool the pack run needs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedTool {
+    /// Tool name (with version requirement, if any)
+    pub name: String,
+    /// Why it is needed
+    pub reason: String,
+}
+
+/// Result of [`Packer::plan`](crate::Packer::plan)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackPlan {
+    /// Pack mode
+    pub mode: String,
+    /// Executable that would be written
+    pub executable: PathBuf,
+    /// Assets that would be embedded
+    pub assets: Vec<PlannedAsset>,
+    /// Downloads that would be fetched (with `[vx]` enabled)
+    pub downloads: Vec<PlannedDownload>,
+    /// Python packages that would be installed
+    pub python_packages: Vec<String>,
+    /// Hook commands that would run, in order
+    pub hooks: Vec<String>,
+    /// External tools needed
+    pub tools: Vec<PlannedTool>,
+    /// Estimated bytes written to the output directory
+    pub estimated_output_size: u64,
+    /// Estimated bytes written to the staging directory
+    pub estimated_staging_size: u64,
+}
+
+impl PackPlan {
+    /// Total uncompressed size of the planned assets
+    pub fn asset_size(&self) -> u64 {
+        self.assets.iter().map(|a| a.size).sum()
+    }
+
+    /// Planned asset by overlay path
+    pub fn asset(&self, path: &str) -> Option<&PlannedAsset> {
+        self.assets.iter().find(|a| a.path == path)
+    }
+
+    /// Downloads not in the cache yet
+    pub fn pending_downloads(&self) -> impl Iterator<Item = &PlannedDownload> {
+        self.downloads.iter().filter(|d| d.cached.is_none())
+    }
+
+    /// Add the files of `root` (a file or a directory) as assets under
+    /// `prefix`
+    pub(crate) fn add_files(
+        &mut self,
+        root: &Path,
+        prefix: &str,
+        source: AssetSource,
+        include: impl Fn(&str) -> bool,
+    ) -> PackResult<()> {
+        if root.is_file() {
+            let name = root
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            self.push(format!("{}{}", prefix, name), root, source)?;
+            return Ok(());
+        }
+        for entry in walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let relative = entry
+                .path()
+                .strip_prefix(root)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            if include(&relative) {
+                self.push(format!("{}{}", prefix, relative), entry.path(), source)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        path: String,
+        source_path: &Path,
+        source: AssetSource,
+    ) -> PackResult<()> {
+        self.assets.push(PlannedAsset {
+            path,
+            source_path: source_path.to_path_buf(),
+            size: std::fs::metadata(source_path)?.len(),
+            source,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn require_tool(&mut self, name: impl Into<String>, reason: impl Into<String>) {
+        let name = name.into();
+        if !self.tools.iter().any(|t| t.name == name) {
+            self.tools.push(PlannedTool {
+                name,
+                reason: reason.into(),
+            });
+        }
+    }
+}
+
+impl fmt::Display for PackPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Mode:        {}", self.mode)?;
+        writeln!(f, "Executable:  {}", self.executable.display())?;
+        writeln!(
+            f,
+            "Assets:      {} ({} bytes)",
+            self.assets.len(),
+            self.asset_size()
+        )?;
+        for asset in &self.assets {
+            writeln!(
+                f,
+                "  {:>12}  {:<8}  {}",
+                asset.size,
+                asset.source.as_str(),
+                asset.path
+            )?;
+        }
+        for download in &self.downloads {
+            writeln!(
+                f,
+                "Download:    {} <- {} ({}{})",
+                download.name,
+                download.url,
+                if download.cached.is_some() {
+                    "cached"
+                } else {
+                    "to fetch"
+                },
+                if download.verified { ", checksum" } else { "" }
+            )?;
+        }
+        if !self.python_packages.is_empty() {
+            writeln!(f, "Packages:    {}", self.python_packages.join(", "))?;
+        }
+        for hook in &self.hooks {
+            writeln!(f, "Hook:        {}", hook)?;
+        }
+        for tool in &self.tools {
+            writeln!(f, "Tool:        {} ({})", tool.name, tool.reason)?;
+        }
+        writeln!(
+            f,
+            "Estimate:    {} bytes output, {} bytes staging",
+            self.estimated_output_size, self.estimated_staging_size
+        )
+    }
+}
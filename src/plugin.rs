@@ -0,0 +1,143 @@
+//! Packer plugins
+//!
+//! A [`PackPlugin`] registered with
+//! [`Packer::with_plugin`](crate::Packer::with_plugin) is called at fixed
+//! points of every pack run, in registration order:
+//!
+//! 1. [`after_collect`](PackPlugin::after_collect): the overlay assets are
+//!    collected (frontend, Python code, downloads, About data); plugins may
+//!    add, remove or rename assets
+//! 2. [`filter_asset`](PackPlugin::filter_asset) and
+//!    [`transform_asset`](PackPlugin::transform_asset): per asset, after
+//!    `[frontend.transform]`
+//! 3. [`before_overlay_write`](PackPlugin::before_overlay_write): the
+//!    launcher is in place and the overlay is about to be appended
+//!    (metadata, last-minute protection)
+//! 4. [`after_pack`](PackPlugin::after_pack): after the `after_pack` hooks,
+//!    before the update feed and provenance are written
+//!
+//! A plugin error fails the pack. Assets streamed from disk (the Python
+//! runtime archive) are only filtered, not passed to the other callbacks.
+//! A pack resumed after its overlay was written (`[build.resume]`) only
+//! calls `after_pack`.
+
+use crate::context::PackContext;
+use crate::overlay::OverlayData;
+use crate::packer::PackOutput;
+use crate::{PackConfig, PackError, PackResult};
+use std::fmt;
+use std::path::Path;
+
+/// What a plugin sees of the pack run
+#[derive(Debug, Clone, Copy)]
+pub struct PluginContext<'a> {
+    /// Configuration being packed
+    pub config: &'a PackConfig,
+    /// Directories, platform and clock of the run
+    pub context: &'a PackContext,
+    /// Unique ID of the run
+    pub run_id: &'a str,
+    /// Output executable
+    pub executable: &'a Path,
+}
+
+/// Extends a pack run
+///
+/// All callbacks default to doing nothing. Packers may run on several
+/// threads, so implementations must be thread-safe.
+pub trait PackPlugin: Send + Sync {
+    /// Name used in logs and errors
+    fn name(&self) -> &str;
+
+    /// The overlay assets were collected
+    fn after_collect(
+        &self,
+        _cx: &PluginContext<'_>,
+        _assets: &mut Vec<(String, Vec<u8>)>,
+    ) -> PackResult<()> {
+        Ok(())
+    }
+
+    /// Whether the asset at `path` is packed
+    fn filter_asset(&self, _path: &str) -> bool {
+        true
+    }
+
+    /// New content of the asset at `path` (`None`: keep it unchanged)
+    fn transform_asset(&self, _path: &str, _content: &[u8]) -> PackResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// The overlay is about to be written to the executable
+    fn before_overlay_write(
+        &self,
+        _cx: &PluginContext<'_>,
+        _overlay: &mut OverlayData,
+    ) -> PackResult<()> {
+        Ok(())
+    }
+
+    /// The executable is packed
+    fn after_pack(&self, _cx: &PluginContext<'_>, _output: &mut PackOutput) -> PackResult<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for dyn PackPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PackPlugin")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+/// Attribute an error of `plugin` in `callback` to it
+pub(crate) fn plugin_error(plugin: &dyn PackPlugin, callback: &str, error: PackError) -> PackError {
+    PackError::Plugin(format!("{} ({}): {}", plugin.name(), callback, error))
+}
+
+/// Run the collect, filter and transform callbacks of `plugins` over
+/// `overlay`; returns the streamed `files` kept by the filters
+pub(crate) fn process_assets<'f>(
+    plugins: &[std::sync::Arc<dyn PackPlugin>],
+    cx: &PluginContext<'_>,
+    overlay: &mut OverlayData,
+    files: &[(&'f str, &'f Path)],
+) -> PackResult<Vec<(&'f str, &'f Path)>> {
+    for plugin in plugins {
+        plugin
+            .after_collect(cx, &mut overlay.assets)
+            .map_err(|e| plugin_error(plugin.as_ref(), "after_collect", e))?;
+    }
+
+    let keep = |path: &str| {
+        plugins.iter().all(|plugin| {
+            let keep = plugin.filter_asset(path);
+            if !keep {
+                tracing::debug!("Plugin {} dropped {}", plugin.name(), path);
+            }
+            keep
+        })
+    };
+    overlay.assets.retain(|(path, _)| keep(path));
+    overlay.attributes.retain(|path, _| keep(path));
+    let files = files
+        .iter()
+        .copied()
+        .filter(|(path, _)| keep(path))
+        .collect();
+
+    for (path, content) in overlay.assets.iter_mut() {
+        for plugin in plugins {
+            if let Some(transformed) = plugin
+                .transform_asset(path, content)
+                .map_err(|e| plugin_error(plugin.as_ref(), "transform_asset", e))?
+            {
+                *content = transformed;
+            }
+        }
+    }
+    // Assets changed after the hash was taken
+    overlay.content_hash.clear();
+    Ok(files)
+}
@@ -0,0 +1,267 @@
+//! Print and PDF export policy
+//!
+//! `[runtime.print]` controls printing in the packed app. The runtime
+//! enforces it (blocks `window.print()`, Ctrl+P and the context menu entry,
+//! and pins the "Save as PDF" destination); the packer can additionally
+//! strip print stylesheets from the bundled frontend so a disabled print
+//! path leaves nothing print-specific behind:
+//!
+//! ```toml
+//! [runtime.print]
+//! allowed = false
+//! strip_print_css = true      # Remove @media print rules from bundled CSS/HTML
+//!
+//! # Or allow PDF export into one directory only
+//! # allowed = true
+//! # pdf_dir = "$USERPROFILE/Documents/Reports"
+//! # pdf_dir_locked = true
+//! ```
+
+use crate::bundle::AssetBundle;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+
+/// Print policy configuration
+///
+/// Located at `[runtime.print]` in TOML. Stored in the overlay config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PrintConfig {
+    /// Allow printing
+    pub allowed: bool,
+
+    /// Allow "Save as PDF" (requires `allowed`)
+    pub pdf: bool,
+
+    /// Default directory of exported PDFs (may contain `$VAR`/`%VAR%`)
+    pub pdf_dir: Option<String>,
+
+    /// Only allow saving PDFs into `pdf_dir`
+    pub pdf_dir_locked: bool,
+
+    /// Remove print stylesheets from the bundled frontend (pack time only)
+    #[serde(skip_serializing)]
+    pub strip_print_css: bool,
+}
+
+impl Default for PrintConfig {
+    fn default() -> Self {
+        Self {
+            allowed: true,
+            pdf: true,
+            pdf_dir: None,
+            pdf_dir_locked: false,
+            strip_print_css: false,
+        }
+    }
+}
+
+impl PrintConfig {
+    /// Printing and PDF export disabled
+    pub fn disabled() -> Self {
+        Self {
+            allowed: false,
+            pdf: false,
+            ..Default::default()
+        }
+    }
+
+    /// Validate the policy
+    pub fn validate(&self) -> PackResult<()> {
+        if self
+            .pdf_dir
+            .as_deref()
+            .is_some_and(|dir| dir.trim().is_empty())
+        {
+            return Err(PackError::Config(
+                "'pdf_dir' in [runtime.print] must not be empty".to_string(),
+            ));
+        }
+        if self.pdf_dir_locked && self.pdf_dir.is_none() {
+            return Err(PackError::Config(
+                "[runtime.print] pdf_dir_locked requires 'pdf_dir'".to_string(),
+            ));
+        }
+        if self.strip_print_css && self.allowed {
+            return Err(PackError::Config(
+                "[runtime.print] strip_print_css only applies with allowed = false".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Strip print stylesheets from a bundle if configured
+    ///
+    /// Returns the number of rewritten files.
+    pub fn apply_to_bundle(&self, bundle: &mut AssetBundle) -> usize {
+        if !self.strip_print_css || self.allowed {
+            return 0;
+        }
+
+        let rewritten: Vec<(String, String)> = bundle
+            .assets()
+            .iter()
+            .filter_map(|(path, content)| {
+                let text = std::str::from_utf8(content).ok()?;
+                let lower = path.to_ascii_lowercase();
+                let stripped = if lower.ends_with(".css") {
+                    strip_print_rules(text)
+                } else if lower.ends_with(".html") || lower.ends_with(".htm") {
+                    strip_print_html(text)
+                } else {
+                    return None;
+                };
+                (stripped != text).then(|| (path.clone(), stripped))
+            })
+            .collect();
+
+        for (path, content) in &rewritten {
+            bundle.replace(path, content.clone().into_bytes());
+        }
+        if !rewritten.is_empty() {
+            tracing::info!("Stripped print styles from {} files", rewritten.len());
+        }
+        rewritten.len()
+    }
+}
+
+/// Remove `@media print { ... }` blocks from a stylesheet
+///
+/// Rules shared with other media (`@media print, screen`) are kept.
+pub(crate) fn strip_print_rules(css: &str) -> String {
+    let lower = css.to_ascii_lowercase();
+    let mut out = String::with_capacity(css.len());
+    let mut copied = 0;
+    let mut search_from = 0;
+
+    while let Some(start) = lower[search_from..].find("@media").map(|i| search_from + i) {
+        search_from = start + "@media".len();
+        let Some(open) = lower[start..].find('{').map(|i| start + i) else {
+            break;
+        };
+        let print_only = lower[search_from..open].split(',').all(|query| {
+            let query = query.trim();
+            query
+                .strip_prefix("only ")
+                .unwrap_or(query)
+                .trim_start()
+                .starts_with("print")
+        });
+        if !print_only {
+            continue;
+        }
+
+        // Find the matching closing brace
+        let mut depth = 0;
+        let mut end = None;
+        for (i, c) in css[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(open + i + 1);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(end) = end else {
+            break;
+        };
+
+        out.push_str(&css[copied..start]);
+        copied = end;
+        search_from = end;
+    }
+
+    out.push_str(&css[copied..]);
+    out
+}
+
+/// Remove print-only `<link>`/`<style>` elements and `@media print` rules of
+/// inline styles from an HTML document
+fn strip_print_html(html: &str) -> String {
+    let mut html = html.to_string();
+
+    for tag in ["<link", "<style"] {
+        let mut search_from = 0;
+        loop {
+            let lower = html.to_ascii_lowercase();
+            let Some(start) = lower[search_from..].find(tag).map(|i| search_from + i) else {
+                break;
+            };
+            let Some(open_end) = lower[start..].find('>').map(|i| start + i + 1) else {
+                break;
+            };
+            let media = media_attribute(&lower[start..open_end]);
+            let end = if tag == "<style" {
+                lower[open_end..]
+                    .find("</style>")
+                    .map(|i| open_end + i + "</style>".len())
+            } else {
+                Some(open_end)
+            };
+
+            match end {
+                Some(end) if media.as_deref() == Some("print") => {
+                    html.replace_range(start..end, "");
+                    search_from = start;
+                }
+                Some(end) if tag == "<style" => {
+                    let close = end - "</style>".len();
+                    let css = strip_print_rules(&html[open_end..close]);
+                    let len = css.len();
+                    html.replace_range(open_end..close, &css);
+                    search_from = open_end + len;
+                }
+                _ => search_from = open_end,
+            }
+        }
+    }
+    html
+}
+
+/// `media` attribute of a (lowercased) tag
+fn media_attribute(tag: &str) -> Option<String> {
+    let pos = tag.find(" media=")? + " media=".len();
+    let rest = &tag[pos..];
+    let value = match rest.chars().next() {
+        Some(quote @ ('"' | '\'')) => rest[1..].split(quote).next().unwrap_or_default(),
+        _ => rest
+            .split(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+            .next()
+            .unwrap_or_default(),
+    };
+    Some(value.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_print_rules() {
+        let css =
+            "body { color: red; }\n@media print { .nav { display: none; } @page { margin: 0; } }\n\
+                   @media print, screen { p { margin: 0; } }\n.x { }";
+        let stripped = strip_print_rules(css);
+        assert_eq!(
+            stripped,
+            "body { color: red; }\n\n@media print, screen { p { margin: 0; } }\n.x { }"
+        );
+
+        let css = "@MEDIA only print and (orientation: landscape) { a { b: c } }";
+        assert_eq!(strip_print_rules(css), "");
+    }
+
+    #[test]
+    fn test_strip_print_html() {
+        let html = r#"<head><link rel="stylesheet" href="print.css" media="print"><link rel="stylesheet" href="app.css"><style media="print">a{}</style><style>@media print { b {} } c {}</style></head>"#;
+        assert_eq!(
+            strip_print_html(html),
+            r#"<head><link rel="stylesheet" href="app.css"><style> c {}</style></head>"#
+        );
+    }
+}
@@ -1,9 +1,18 @@
 //! Progress bar utilities for CLI operations
 //!
 //! Provides beautiful progress indicators for long-running tasks using indicatif.
-
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use std::time::Duration;
+//!
+//! Frontends that render their own progress (GUIs, CI wrappers) pass a
+//! [`PackProgressObserver`] to [`Packer::with_progress`](crate::Packer::with_progress)
+//! instead and receive stage, per-file and byte events.
+
+use crate::PackOptions;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::fmt;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Style presets for different types of progress indicators
 pub struct ProgressStyles;
@@ -87,6 +96,19 @@ impl PackProgress {
         }
     }
 
+    /// Tracker drawing nothing in quiet (CI) mode
+    pub fn with_options(options: &PackOptions) -> Self {
+        let multi = if options.quiet {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        } else {
+            MultiProgress::new()
+        };
+        Self {
+            multi,
+            main_bar: None,
+        }
+    }
+
     /// Create a spinner for an indeterminate operation
     pub fn spinner(&self, msg: &str) -> ProgressBar {
         let pb = self.multi.add(ProgressBar::new_spinner());
@@ -227,6 +249,165 @@ pub fn progress_bar(total: u64, msg: &str) -> ProgressBar {
     pb
 }
 
+/// Stage of a pack run (the phases recorded in pack history)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackStage {
+    /// Toolchains, dependency installs, hooks and downloads
+    Prepare,
+    /// Collecting assets and writing the output
+    Pack,
+    /// Signing, `after_pack` hooks, update feed and provenance
+    AfterPack,
+}
+
+impl PackStage {
+    /// Phase name (`prepare`, `pack`, `after_pack`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Prepare => "prepare",
+            Self::Pack => "pack",
+            Self::AfterPack => "after_pack",
+        }
+    }
+}
+
+/// Byte progress of one item (the overlay being compressed, a download,
+/// an archive being extracted)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteProgress {
+    /// What is being processed (`assets`, `download <file>`, `extract <file>`)
+    pub item: String,
+    /// Bytes processed so far
+    pub done: u64,
+    /// Total bytes, if known
+    pub total: Option<u64>,
+    /// Time since the item started
+    pub elapsed: Duration,
+    /// Estimated time left at the average rate so far (needs `total`)
+    pub eta: Option<Duration>,
+}
+
+impl ByteProgress {
+    /// Fraction done in `0.0..=1.0`, if the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total.map(|total| match total {
+            0 => 1.0,
+            total => (self.done as f64 / total as f64).min(1.0),
+        })
+    }
+}
+
+/// Remaining time of `total` bytes after `done` took `elapsed`
+fn estimate_eta(done: u64, total: Option<u64>, elapsed: Duration) -> Option<Duration> {
+    let total = total?;
+    if done >= total {
+        return Some(Duration::ZERO);
+    }
+    if done == 0 {
+        return None;
+    }
+    Some(elapsed.mul_f64((total - done) as f64 / done as f64))
+}
+
+/// Receives progress events of a pack run
+///
+/// All methods default to doing nothing. Events may arrive from worker
+/// threads, so implementations must be cheap and thread-safe.
+pub trait PackProgressObserver: Send + Sync {
+    /// A stage started
+    fn stage_started(&self, _stage: PackStage) {}
+
+    /// A stage finished after `elapsed`
+    fn stage_finished(&self, _stage: PackStage, _elapsed: Duration) {}
+
+    /// A stage reuses the work of an earlier, failed run (`[build.resume]`)
+    ///
+    /// `prepare` still starts and finishes (validation, toolchains); a
+    /// resumed `pack` is not started at all.
+    fn stage_resumed(&self, _stage: PackStage) {}
+
+    /// An asset was written to the output (`size`: uncompressed bytes)
+    fn file_written(&self, _path: &str, _size: u64) {}
+
+    /// More bytes of an item were processed
+    fn bytes(&self, _progress: &ByteProgress) {}
+}
+
+impl fmt::Debug for dyn PackProgressObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PackProgressObserver")
+    }
+}
+
+/// Byte counter of one item reporting to an observer
+#[derive(Clone)]
+pub(crate) struct ProgressTracker {
+    observer: Arc<dyn PackProgressObserver>,
+    item: String,
+    total: Option<u64>,
+    done: Arc<AtomicU64>,
+    started: Instant,
+}
+
+impl ProgressTracker {
+    pub fn new(
+        observer: Arc<dyn PackProgressObserver>,
+        item: impl Into<String>,
+        total: Option<u64>,
+    ) -> Self {
+        Self {
+            observer,
+            item: item.into(),
+            total,
+            done: Arc::new(AtomicU64::new(0)),
+            started: Instant::now(),
+        }
+    }
+
+    /// Count `bytes` more processed bytes
+    pub fn advance(&self, bytes: u64) {
+        let done = self.done.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let elapsed = self.started.elapsed();
+        self.observer.bytes(&ByteProgress {
+            item: self.item.clone(),
+            done,
+            total: self.total,
+            elapsed,
+            eta: estimate_eta(done, self.total, elapsed),
+        });
+    }
+
+    /// Report a written asset and count its bytes
+    pub fn file_written(&self, path: &str, size: u64) {
+        self.observer.file_written(path, size);
+        self.advance(size);
+    }
+}
+
+/// Reader counting the bytes read through it (downloads, archives being
+/// extracted)
+pub(crate) struct ProgressReader<R> {
+    inner: R,
+    tracker: ProgressTracker,
+}
+
+impl<R> ProgressReader<R> {
+    pub fn new(inner: R, tracker: ProgressTracker) -> Self {
+        Self { inner, tracker }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read > 0 {
+            self.tracker.advance(read as u64);
+        }
+        Ok(read)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,6 +423,41 @@ mod tests {
         let _ = ProgressStyles::encrypt();
     }
 
+    #[test]
+    fn test_estimate_eta() {
+        let elapsed = Duration::from_secs(10);
+        assert_eq!(
+            estimate_eta(25, Some(100), elapsed),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(estimate_eta(100, Some(100), elapsed), Some(Duration::ZERO));
+        assert_eq!(estimate_eta(0, Some(100), elapsed), None);
+        assert_eq!(estimate_eta(25, None, elapsed), None);
+    }
+
+    #[test]
+    fn test_progress_reader_counts_bytes() {
+        #[derive(Default)]
+        struct Last(std::sync::Mutex<Option<ByteProgress>>);
+        impl PackProgressObserver for Last {
+            fn bytes(&self, progress: &ByteProgress) {
+                *self.0.lock().unwrap() = Some(progress.clone());
+            }
+        }
+
+        let observer = Arc::new(Last::default());
+        let tracker = ProgressTracker::new(observer.clone(), "download a.tar.gz", Some(11));
+        let mut reader = ProgressReader::new(&b"hello world"[..], tracker);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+
+        let last = observer.0.lock().unwrap().clone().unwrap();
+        assert_eq!(last.item, "download a.tar.gz");
+        assert_eq!(last.done, 11);
+        assert_eq!(last.fraction(), Some(1.0));
+        assert_eq!(last.eta, Some(Duration::ZERO));
+    }
+
     #[test]
     fn test_pack_progress() {
         let progress = PackProgress::new();
@@ -0,0 +1,407 @@
+//! Signed build provenance (`[build.provenance]`)
+//!
+//! With provenance enabled, every pack writes `<exe>.intoto.jsonl` next to
+//! the executable: an [in-toto] statement carrying a [SLSA v1] provenance
+//! predicate, wrapped in a DSSE envelope signed with the `[signing]` key.
+//! It records who built the artifact (builder identity, packer version),
+//! from what (the manifest hash and every input with its SHA-256 digest),
+//! where (OS, architecture, CI variables) and when (start and finish time).
+//!
+//! ```toml
+//! [build.provenance]
+//! # builder_id = "https://ci.example.com/release-pipeline"
+//! # environment = ["RELEASE_TRAIN"]   # extra variables to record
+//! ```
+//!
+//! The envelope verifies against the same public key as the overlay
+//! signature, see [`verify_provenance`].
+//!
+//! [in-toto]: https://in-toto.io/Statement/v1
+//! [SLSA v1]: https://slsa.dev/spec/v1.0/provenance
+
+use crate::keys::{verify_signature, KeyAlgorithm};
+use crate::signing::OverlaySigner;
+use crate::{PackError, PackResult};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `_type` of in-toto v1 statements
+pub const IN_TOTO_STATEMENT_V1: &str = "https://in-toto.io/Statement/v1";
+
+/// `predicateType` of SLSA v1 provenance
+pub const SLSA_PROVENANCE_V1: &str = "https://slsa.dev/provenance/v1";
+
+/// DSSE payload type of in-toto statements
+pub const IN_TOTO_PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+/// `buildType` of statements written by the packer
+pub const PACK_BUILD_TYPE: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/pack/v1");
+
+/// CI variables recorded when set (in addition to `environment`)
+const CI_VARIABLES: &[&str] = &[
+    "CI",
+    "GITHUB_REPOSITORY",
+    "GITHUB_SHA",
+    "GITHUB_REF",
+    "GITHUB_WORKFLOW",
+    "GITHUB_RUN_ID",
+    "GITHUB_RUN_ATTEMPT",
+    "GITLAB_CI",
+    "CI_PROJECT_PATH",
+    "CI_COMMIT_SHA",
+    "CI_PIPELINE_ID",
+    "BUILD_BUILDID",
+    "BUILD_SOURCEVERSION",
+];
+
+/// Provenance configuration (`[build.provenance]`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProvenanceConfig {
+    /// Builder identity (default: the CI run URL on GitHub Actions, the
+    /// packer repository otherwise)
+    pub builder_id: Option<String>,
+    /// Environment variables to record besides the common CI ones
+    pub environment: Vec<String>,
+}
+
+impl ProvenanceConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        if self
+            .builder_id
+            .as_deref()
+            .is_some_and(|id| id.trim().is_empty())
+        {
+            return Err(PackError::Config(
+                "[build.provenance] builder_id must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Builder identity recorded in the statement
+    pub fn builder_id(&self) -> String {
+        if let Some(ref id) = self.builder_id {
+            return id.clone();
+        }
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        match (
+            var("GITHUB_SERVER_URL"),
+            var("GITHUB_REPOSITORY"),
+            var("GITHUB_RUN_ID"),
+        ) {
+            (Some(server), Some(repository), Some(run)) => {
+                format!("{}/{}/actions/runs/{}", server, repository, run)
+            }
+            _ => env!("CARGO_PKG_REPOSITORY").to_string(),
+        }
+    }
+
+    /// OS, architecture and the recorded environment variables that are set
+    fn environment(&self) -> BTreeMap<String, String> {
+        let mut environment = BTreeMap::new();
+        environment.insert("os".to_string(), std::env::consts::OS.to_string());
+        environment.insert("arch".to_string(), std::env::consts::ARCH.to_string());
+        for name in CI_VARIABLES
+            .iter()
+            .copied()
+            .chain(self.environment.iter().map(String::as_str))
+        {
+            if let Ok(value) = std::env::var(name) {
+                environment.insert(name.to_string(), value);
+            }
+        }
+        environment
+    }
+}
+
+/// Artifact or input with its digests
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceDescriptor {
+    /// Name (file name of subjects, overlay or download name of inputs)
+    pub name: String,
+    /// Where it came from (file path or URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// Digests by algorithm (`sha256`)
+    pub digest: BTreeMap<String, String>,
+}
+
+impl ResourceDescriptor {
+    /// Descriptor of a file with its SHA-256 digest
+    pub fn from_file(name: impl Into<String>, path: &Path) -> PackResult<Self> {
+        let (sha256, _) = crate::hashing::sha256_file(path)?;
+        Ok(Self {
+            name: name.into(),
+            uri: None,
+            digest: BTreeMap::from([("sha256".to_string(), sha256)]),
+        })
+    }
+
+    /// Set where the resource came from
+    pub fn with_uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = Some(uri.into());
+        self
+    }
+
+    /// SHA-256 digest (hex)
+    pub fn sha256(&self) -> Option<&str> {
+        self.digest.get("sha256").map(String::as_str)
+    }
+}
+
+/// Parameters the build was invoked with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalParameters {
+    /// Pack mode
+    pub mode: String,
+    /// Output name
+    pub output_name: String,
+    /// Application version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>,
+    /// Target platform (`<arch>-<os>`)
+    pub target: String,
+    /// SHA-256 of the manifest the configuration was loaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_sha256: Option<String>,
+}
+
+/// How the artifact was built
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDefinition {
+    /// Build type URI ([`PACK_BUILD_TYPE`])
+    pub build_type: String,
+    /// Parameters the build was invoked with
+    pub external_parameters: ExternalParameters,
+    /// Build environment (OS, architecture, CI variables)
+    pub internal_parameters: BTreeMap<String, String>,
+    /// Inputs with their digests
+    pub resolved_dependencies: Vec<ResourceDescriptor>,
+}
+
+/// Builder identity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceBuilder {
+    /// Builder ID
+    pub id: String,
+    /// Component versions (`auroraview-pack`)
+    pub version: BTreeMap<String, String>,
+}
+
+/// Build invocation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceMetadata {
+    /// Pack run ID
+    pub invocation_id: String,
+    /// Start of the pack run (RFC 3339)
+    pub started_on: String,
+    /// End of the pack run (RFC 3339)
+    pub finished_on: String,
+}
+
+/// Who built the artifact, and when
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunDetails {
+    /// Builder identity
+    pub builder: ProvenanceBuilder,
+    /// Build invocation
+    pub metadata: ProvenanceMetadata,
+}
+
+/// SLSA v1 provenance predicate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provenance {
+    /// How the artifact was built
+    pub build_definition: BuildDefinition,
+    /// Who built it, and when
+    pub run_details: RunDetails,
+}
+
+/// in-toto statement about the packed artifacts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceStatement {
+    /// Statement type ([`IN_TOTO_STATEMENT_V1`])
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    /// Artifacts the statement is about
+    pub subject: Vec<ResourceDescriptor>,
+    /// Predicate type ([`SLSA_PROVENANCE_V1`])
+    pub predicate_type: String,
+    /// Provenance
+    pub predicate: Provenance,
+}
+
+/// Signature of a DSSE envelope
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvelopeSignature {
+    /// Public key of the signer (base64)
+    pub keyid: String,
+    /// Signature (base64)
+    pub sig: String,
+}
+
+/// DSSE envelope around a statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceEnvelope {
+    /// Payload type ([`IN_TOTO_PAYLOAD_TYPE`])
+    pub payload_type: String,
+    /// Statement JSON (base64)
+    pub payload: String,
+    /// Signatures over the payload
+    pub signatures: Vec<EnvelopeSignature>,
+}
+
+/// Provenance file of an executable (`<exe>.intoto.jsonl`)
+pub fn provenance_path(executable: &Path) -> PathBuf {
+    let mut name = executable.as_os_str().to_owned();
+    name.push(".intoto.jsonl");
+    PathBuf::from(name)
+}
+
+/// DSSE pre-authentication encoding of a payload
+fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut message = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    message.extend_from_slice(payload);
+    message
+}
+
+/// Sign a statement into a DSSE envelope
+pub fn sign_statement(
+    statement: &ProvenanceStatement,
+    signer: &OverlaySigner,
+) -> PackResult<ProvenanceEnvelope> {
+    let payload = serde_json::to_vec(statement)?;
+    let signature = signer.sign_bytes(&pae(IN_TOTO_PAYLOAD_TYPE, &payload))?;
+    Ok(ProvenanceEnvelope {
+        payload_type: IN_TOTO_PAYLOAD_TYPE.to_string(),
+        payload: STANDARD.encode(payload),
+        signatures: vec![EnvelopeSignature {
+            keyid: signer.public_key_base64(),
+            sig: STANDARD.encode(signature),
+        }],
+    })
+}
+
+/// Check a provenance envelope against a public key (base64) and return
+/// its statement
+///
+/// Pass the key the overlay is verified with, e.g. the `public_key` of its
+/// signature or the key pinned in the shell.
+pub fn verify_provenance(envelope: &str, public_key: &str) -> PackResult<ProvenanceStatement> {
+    let decode = |value: &str, what: &str| {
+        STANDARD
+            .decode(value.trim())
+            .map_err(|e| PackError::Signature(format!("invalid {}: {}", what, e)))
+    };
+    let envelope: ProvenanceEnvelope = serde_json::from_str(envelope.trim())?;
+    if envelope.payload_type != IN_TOTO_PAYLOAD_TYPE {
+        return Err(PackError::Signature(format!(
+            "unexpected provenance payload type: {}",
+            envelope.payload_type
+        )));
+    }
+    let payload = decode(&envelope.payload, "provenance payload")?;
+    let key = decode(public_key, "public key")?;
+    let message = pae(&envelope.payload_type, &payload);
+    let verified = envelope.signatures.iter().any(|signature| {
+        decode(&signature.sig, "signature").is_ok_and(|sig| {
+            verify_signature(KeyAlgorithm::of_public_key(&key), &key, &message, &sig).is_ok()
+        })
+    });
+    if !verified {
+        return Err(PackError::Signature(
+            "provenance is not signed by the given key".to_string(),
+        ));
+    }
+
+    let statement: ProvenanceStatement = serde_json::from_slice(&payload)?;
+    if statement.statement_type != IN_TOTO_STATEMENT_V1
+        || statement.predicate_type != SLSA_PROVENANCE_V1
+    {
+        return Err(PackError::Signature(format!(
+            "unsupported provenance statement: {} / {}",
+            statement.statement_type, statement.predicate_type
+        )));
+    }
+    Ok(statement)
+}
+
+/// Inputs and timing of one pack run, collected by the packer
+pub(crate) struct ProvenanceRun<'a> {
+    pub(crate) run_id: &'a str,
+    pub(crate) started_on: u64,
+    pub(crate) finished_on: u64,
+    pub(crate) parameters: ExternalParameters,
+    /// Artifacts (name, file); the first is the executable
+    pub(crate) subjects: Vec<(String, PathBuf)>,
+    pub(crate) inputs: Vec<ResourceDescriptor>,
+}
+
+/// Write the signed provenance of `run` next to its first subject
+pub(crate) fn write(
+    config: &ProvenanceConfig,
+    run: ProvenanceRun<'_>,
+    signer: &OverlaySigner,
+) -> PackResult<PathBuf> {
+    let subject = run
+        .subjects
+        .iter()
+        .filter(|(_, path)| path.is_file())
+        .map(|(name, path)| ResourceDescriptor::from_file(name.clone(), path))
+        .collect::<PackResult<Vec<_>>>()?;
+    let (_, executable) = run
+        .subjects
+        .first()
+        .ok_or_else(|| PackError::Config("Provenance needs at least one artifact".to_string()))?;
+
+    let statement = ProvenanceStatement {
+        statement_type: IN_TOTO_STATEMENT_V1.to_string(),
+        subject,
+        predicate_type: SLSA_PROVENANCE_V1.to_string(),
+        predicate: Provenance {
+            build_definition: BuildDefinition {
+                build_type: PACK_BUILD_TYPE.to_string(),
+                external_parameters: run.parameters,
+                internal_parameters: config.environment(),
+                resolved_dependencies: run.inputs,
+            },
+            run_details: RunDetails {
+                builder: ProvenanceBuilder {
+                    id: config.builder_id(),
+                    version: BTreeMap::from([(
+                        env!("CARGO_PKG_NAME").to_string(),
+                        env!("CARGO_PKG_VERSION").to_string(),
+                    )]),
+                },
+                metadata: ProvenanceMetadata {
+                    invocation_id: run.run_id.to_string(),
+                    started_on: crate::update_feed::rfc3339(run.started_on),
+                    finished_on: crate::update_feed::rfc3339(run.finished_on),
+                },
+            },
+        },
+    };
+
+    let envelope = sign_statement(&statement, signer)?;
+    let path = provenance_path(executable);
+    fs::write(&path, format!("{}\n", serde_json::to_string(&envelope)?))?;
+    tracing::info!("Provenance written to {}", path.display());
+    Ok(path)
+}
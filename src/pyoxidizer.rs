@@ -433,14 +433,16 @@ resolve_targets()
         }
 
         tracing::info!("Running PyOxidizer build...");
-        let status = cmd
-            .status()
+        let output = cmd
+            .output()
             .map_err(|e| PackError::Build(format!("Failed to run PyOxidizer: {}", e)))?;
+        crate::subprocess::log_output("pyoxidizer", &output);
 
-        if !status.success() {
+        if !output.status.success() {
             return Err(PackError::Build(format!(
-                "PyOxidizer build failed with status: {}",
-                status
+                "PyOxidizer build failed with status: {}\n{}",
+                output.status,
+                crate::subprocess::stderr_tail(&output)
             )));
         }
 
@@ -0,0 +1,260 @@
+//! Python version/ABI requirements of the embedded strategy
+//!
+//! `strategy = "embedded"` packs carry only the app's code and run it on the
+//! user's Python. The pack records which Python that must be under
+//! [`PYTHON_REQUIREMENT_PATH`] in the overlay, so the launcher can check it
+//! before starting the backend and explain a mismatch instead of failing
+//! with an import error:
+//!
+//! ```toml
+//! [backend.python]
+//! strategy = "embedded"
+//! version = "3.11"
+//!
+//! [backend.python.embedded]
+//! on_mismatch = "error"        # "error" | "warn"
+//! install_url = "https://www.python.org/downloads/release/python-3119/"
+//! ```
+//!
+//! Native extensions (`.pyd`/`.so`, e.g. from py2pyd protection) only load
+//! on the exact CPython minor version they were built for; pure Python code
+//! is checked against the configured version.
+
+use crate::config::PythonBundleConfig;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+
+/// Overlay path of the recorded requirement
+pub const PYTHON_REQUIREMENT_PATH: &str = "python_requirement.json";
+
+/// What the launcher does when the host Python does not match
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbiMismatchAction {
+    /// Show the error and exit
+    #[default]
+    Error,
+    /// Log a warning and start anyway
+    Warn,
+}
+
+/// Embedded strategy configuration
+///
+/// Located at `[backend.python.embedded]` in TOML.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct EmbeddedPythonConfig {
+    /// Check the host Python at startup
+    pub check: bool,
+
+    /// What to do on a mismatch
+    pub on_mismatch: AbiMismatchAction,
+
+    /// Where users get the required Python (default: python.org downloads)
+    pub install_url: Option<String>,
+
+    /// Extra text shown with the error (e.g., who to contact)
+    pub message: Option<String>,
+}
+
+impl Default for EmbeddedPythonConfig {
+    fn default() -> Self {
+        Self {
+            check: true,
+            on_mismatch: AbiMismatchAction::Error,
+            install_url: None,
+            message: None,
+        }
+    }
+}
+
+impl EmbeddedPythonConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        if let Some(ref url) = self.install_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(PackError::Config(format!(
+                    "[backend.python.embedded] install_url must be an http(s) URL, got '{}'",
+                    url
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Python an embedded pack needs at runtime
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PythonRequirement {
+    /// Required major.minor version (e.g., "3.11")
+    pub version: String,
+    /// Required CPython ABI tag (e.g., "cp311")
+    pub abi: String,
+    /// Platform the native extensions were built for (e.g., "linux-x86_64")
+    pub platform: String,
+    /// Overlay paths of bundled native extensions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub native_extensions: Vec<String>,
+    /// Launcher behavior
+    #[serde(default)]
+    pub runtime: EmbeddedPythonConfig,
+}
+
+/// A Python interpreter found on a host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostPython {
+    /// Command that runs it
+    pub program: String,
+    /// major.minor version
+    pub version: String,
+}
+
+impl HostPython {
+    /// Find the first Python in `PATH`
+    pub fn probe() -> Option<Self> {
+        ["python", "python3", "py"].iter().find_map(|program| {
+            let found = crate::doctor::tool_version(program, &["--version"])?;
+            let version = found.trim_start_matches("Python").trim();
+            let (major, minor) = major_minor(version).ok()?;
+            Some(Self {
+                program: program.to_string(),
+                version: format!("{}.{}", major, minor),
+            })
+        })
+    }
+
+    /// CPython ABI tag of this interpreter
+    pub fn abi(&self) -> String {
+        abi_tag(&self.version)
+    }
+}
+
+impl PythonRequirement {
+    /// Requirement of the code bundled into an overlay
+    pub fn new(python: &PythonBundleConfig, assets: &[(String, Vec<u8>)]) -> PackResult<Self> {
+        let (major, minor) = major_minor(&python.version)?;
+        let version = format!("{}.{}", major, minor);
+        let native_extensions = assets
+            .iter()
+            .map(|(path, _)| path)
+            .filter(|path| path.ends_with(".pyd") || path.ends_with(".so"))
+            .cloned()
+            .collect();
+
+        Ok(Self {
+            abi: abi_tag(&version),
+            version,
+            platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+            native_extensions,
+            runtime: python.embedded.clone().unwrap_or_default(),
+        })
+    }
+
+    /// Whether a host Python can run the bundled code
+    ///
+    /// The error is the message to show the user.
+    pub fn check(&self, host: Option<&HostPython>) -> Result<(), String> {
+        let problem = match host {
+            None => format!("Python {} is required but was not found.", self.version),
+            Some(host) if host.version != self.version => format!(
+                "Python {} is required, but `{}` is Python {}.",
+                self.version, host.program, host.version
+            ),
+            Some(_) => return Ok(()),
+        };
+
+        let url = self
+            .runtime
+            .install_url
+            .as_deref()
+            .unwrap_or("https://www.python.org/downloads/");
+        let mut message = format!("{}\nInstall Python {} from {}", problem, self.version, url);
+        if let Some(ref extra) = self.runtime.message {
+            message.push('\n');
+            message.push_str(extra);
+        }
+        Err(message)
+    }
+
+    /// Warn about problems visible at pack time
+    pub(crate) fn preflight(&self) {
+        for path in &self.native_extensions {
+            match extension_abi(path) {
+                Some(abi) if abi != self.abi => tracing::warn!(
+                    "Native extension {} was built for {}, but [backend.python] version \
+                     requires {}; it will fail to import at runtime",
+                    path,
+                    abi,
+                    self.abi
+                ),
+                _ => {}
+            }
+        }
+        if !self.native_extensions.is_empty() {
+            tracing::warn!(
+                "{} native extension(s) bundled; the embedded pack only runs on CPython {} ({})",
+                self.native_extensions.len(),
+                self.version,
+                self.platform
+            );
+        }
+
+        let host = HostPython::probe();
+        if let Err(message) = self.check(host.as_ref()) {
+            tracing::warn!(
+                "Embedded packs run on the user's Python: {}",
+                message.lines().next().unwrap_or_default()
+            );
+        }
+    }
+}
+
+/// Major and minor of a version string (e.g., "3.11" or "3.11.9")
+pub(crate) fn major_minor(version: &str) -> PackResult<(u32, u32)> {
+    let mut parts = version.trim().split('.').map(str::parse::<u32>);
+    match (parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor))) => Ok((major, minor)),
+        _ => Err(PackError::Config(format!(
+            "[backend.python] version '{}' must be <major>.<minor>",
+            version
+        ))),
+    }
+}
+
+/// CPython ABI tag of a major.minor version ("3.11" -> "cp311")
+fn abi_tag(version: &str) -> String {
+    format!("cp{}", version.replace('.', ""))
+}
+
+/// ABI tag in a native extension's file name, if any
+///
+/// `mod.cpython-311-x86_64-linux-gnu.so` and `mod.cp311-win_amd64.pyd` are
+/// tagged; `mod.abi3.so` and untagged `mod.pyd` are not.
+fn extension_abi(path: &str) -> Option<String> {
+    let name = path.rsplit('/').next()?;
+    let tag = name.split('.').nth(1)?;
+    let digits = tag
+        .strip_prefix("cpython-")
+        .or_else(|| tag.strip_prefix("cp"))?;
+    let digits: String = digits.chars().take_while(|c| c.is_ascii_digit()).collect();
+    (!digits.is_empty()).then(|| format!("cp{}", digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_abi() {
+        assert_eq!(
+            extension_abi("python/app/core.cpython-311-x86_64-linux-gnu.so"),
+            Some("cp311".to_string())
+        );
+        assert_eq!(
+            extension_abi("python/app/core.cp312-win_amd64.pyd"),
+            Some("cp312".to_string())
+        );
+        assert_eq!(extension_abi("python/app/core.abi3.so"), None);
+        assert_eq!(extension_abi("python/app/core.pyd"), None);
+    }
+}
@@ -15,11 +15,27 @@
 //! - Linux x86_64: `cpython-{version}+{release}-x86_64-unknown-linux-gnu-install_only.tar.gz`
 //! - macOS x86_64: `cpython-{version}+{release}-x86_64-apple-darwin-install_only.tar.gz`
 //! - macOS arm64: `cpython-{version}+{release}-aarch64-apple-darwin-install_only.tar.gz`
-
+//!
+//! # Distribution Providers
+//!
+//! A [`DistributionProvider`] set with [`PythonStandalone::with_provider`]
+//! supplies the archive instead of the GitHub download and the caches, so
+//! tests and air-gapped builds stay offline and deterministic
+//! ([`LocalArchive`] serves a file on disk).
+
+use crate::cache_lock::{temp_sibling, write_atomic, CacheLock};
+use crate::http::HttpClient;
+use crate::progress::{ProgressReader, ProgressTracker};
+use crate::store::{missing_object, ArtifactStore, ObjectKind};
 use crate::{PackError, PackResult};
+use std::fmt;
 use std::fs::{self, File};
-use std::io::BufReader;
+use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Artifact store namespace of downloaded distributions
+const PYTHON_NAMESPACE: &str = "python";
 
 /// Python standalone distribution configuration
 #[derive(Debug, Clone)]
@@ -30,7 +46,8 @@ pub struct PythonStandaloneConfig {
     pub release: Option<String>,
     /// Target platform (auto-detected if None)
     pub target: Option<String>,
-    /// Cache directory for downloaded distributions
+    /// Cache directory for downloaded distributions (default: the
+    /// artifact store)
     pub cache_dir: Option<PathBuf>,
 }
 
@@ -107,10 +124,56 @@ impl PythonTarget {
     }
 }
 
+/// Supplies Python distribution archives in place of the GitHub download
+pub trait DistributionProvider: Send + Sync + fmt::Debug {
+    /// Short description for logs (e.g. "local archive python.tar.gz")
+    fn describe(&self) -> String;
+
+    /// Path of the `install_only` archive (`.tar.gz`) of `version` for
+    /// `target`
+    fn archive(&self, version: &str, target: PythonTarget) -> PackResult<PathBuf>;
+}
+
+/// Provider serving one local archive for every version and target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalArchive {
+    path: PathBuf,
+}
+
+impl LocalArchive {
+    /// Serve the archive at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the archive
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl DistributionProvider for LocalArchive {
+    fn describe(&self) -> String {
+        format!("local archive {}", self.path.display())
+    }
+
+    fn archive(&self, _version: &str, _target: PythonTarget) -> PackResult<PathBuf> {
+        if !self.path.is_file() {
+            return Err(PackError::Config(format!(
+                "Python distribution archive not found: {}",
+                self.path.display()
+            )));
+        }
+        Ok(self.path.clone())
+    }
+}
+
 /// Python standalone distribution manager
 pub struct PythonStandalone {
     config: PythonStandaloneConfig,
     target: PythonTarget,
+    store: Option<ArtifactStore>,
+    provider: Option<Arc<dyn DistributionProvider>>,
 }
 
 impl PythonStandalone {
@@ -128,7 +191,35 @@ impl PythonStandalone {
             PythonTarget::current()?
         };
 
-        Ok(Self { config, target })
+        Ok(Self {
+            config,
+            target,
+            store: None,
+            provider: None,
+        })
+    }
+
+    /// Keep downloads in this artifact store (default: the default store)
+    pub fn with_store(mut self, store: ArtifactStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Take the distribution from `provider` instead of downloading it
+    ///
+    /// The archive is used as is: neither `cache_dir` nor the store is
+    /// consulted or filled.
+    pub fn with_provider(mut self, provider: Arc<dyn DistributionProvider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// HTTP client of the store (default settings without one)
+    fn http(&self) -> HttpClient {
+        self.store
+            .as_ref()
+            .map(|store| store.http().clone())
+            .unwrap_or_default()
     }
 
     /// Get the download URL for the Python distribution
@@ -150,28 +241,50 @@ impl PythonStandalone {
         )
     }
 
+    /// Where the distribution comes from: the provider, or the download URL
+    pub fn source(&self) -> String {
+        match self.provider {
+            Some(ref provider) => provider.describe(),
+            None => self.download_url(),
+        }
+    }
+
     /// Get the cache directory for downloaded distributions
     pub fn cache_dir(&self) -> PathBuf {
-        self.config.cache_dir.clone().unwrap_or_else(|| {
-            dirs::cache_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("AuroraView")
-                .join("python-standalone")
-        })
+        self.config
+            .cache_dir
+            .clone()
+            .unwrap_or_else(get_distribution_cache_dir)
     }
 
-    /// Get the cached distribution path
+    /// Get the cached distribution path (in `cache_dir`)
     pub fn cached_path(&self) -> PathBuf {
-        let filename = format!(
+        self.cache_dir().join(self.archive_name())
+    }
+
+    /// File name of the distribution archive
+    fn archive_name(&self) -> String {
+        format!(
             "cpython-{}-{}.tar.gz",
             self.config.version,
             self.target.triple()
-        );
-        self.cache_dir().join(filename)
+        )
     }
 
     /// Download the Python distribution if not cached
     pub fn download(&self) -> PackResult<PathBuf> {
+        if let Some(ref provider) = self.provider {
+            let path = provider.archive(&self.config.version, self.target)?;
+            tracing::info!(
+                "Using Python distribution from {}: {}",
+                provider.describe(),
+                path.display()
+            );
+            return Ok(path);
+        }
+        if self.config.cache_dir.is_none() {
+            return self.download_to_store();
+        }
         let cache_path = self.cached_path();
 
         // Check if already cached
@@ -180,19 +293,58 @@ impl PythonStandalone {
             return Ok(cache_path);
         }
 
-        // Create cache directory
-        fs::create_dir_all(self.cache_dir())?;
+        // Re-check under the lock: a concurrent pack may have downloaded it
+        let _lock = CacheLock::acquire(&cache_path)?;
+        if cache_path.exists() {
+            tracing::info!("Using cached Python distribution: {}", cache_path.display());
+            return Ok(cache_path);
+        }
 
         let url = self.download_url();
         tracing::info!("Downloading Python distribution from: {}", url);
 
-        // Download using system tools (curl/wget/powershell)
-        download_file(&url, &cache_path)?;
+        // Download next to the cache entry, then move it into place
+        let partial = temp_sibling(&cache_path)?;
+        self.http().download_to(&url, &partial)?;
+        partial.persist(&cache_path).map_err(|e| e.error)?;
 
         tracing::info!("Downloaded to: {}", cache_path.display());
         Ok(cache_path)
     }
 
+    /// Download the Python distribution into the artifact store if missing
+    fn download_to_store(&self) -> PackResult<PathBuf> {
+        let store = self
+            .store
+            .clone()
+            .unwrap_or_else(ArtifactStore::open_default);
+        let name = self.archive_name();
+        if let Some(path) = store.resolve(PYTHON_NAMESPACE, &name) {
+            tracing::info!("Using cached Python distribution: {}", path.display());
+            return Ok(path);
+        }
+
+        // Re-check under the lock: a concurrent pack may have downloaded it
+        let _lock = store.lock(PYTHON_NAMESPACE, &name)?;
+        if let Some(path) = store.resolve(PYTHON_NAMESPACE, &name) {
+            tracing::info!("Using cached Python distribution: {}", path.display());
+            return Ok(path);
+        }
+
+        let url = self.download_url();
+        tracing::info!("Downloading Python distribution from: {}", url);
+        let partial = store.temp_file()?;
+        store.http().download_to(&url, &partial)?;
+        let digest = store.insert_file(&name, &partial)?;
+        store.link(PYTHON_NAMESPACE, &name, ObjectKind::Blob, &digest)?;
+
+        let path = store
+            .blob(&digest)
+            .ok_or_else(|| missing_object(PYTHON_NAMESPACE, &name))?;
+        tracing::info!("Downloaded to: {}", path.display());
+        Ok(path)
+    }
+
     /// Extract the Python distribution to a directory
     pub fn extract(&self, dest_dir: &Path) -> PackResult<PathBuf> {
         let archive_path = self.download()?;
@@ -203,7 +355,10 @@ impl PythonStandalone {
         fs::create_dir_all(dest_dir)?;
 
         // Extract tar.gz
-        extract_tar_gz(&archive_path, dest_dir)?;
+        let progress = self
+            .http()
+            .extract_tracker(&self.archive_name(), &archive_path);
+        extract_tar_gz(&archive_path, dest_dir, progress)?;
 
         // Return path to python executable
         let python_path = dest_dir.join(self.target.python_path());
@@ -213,6 +368,7 @@ impl PythonStandalone {
                 python_path.display()
             )));
         }
+        make_executable(&dest_dir.join("python").join("bin"))?;
 
         Ok(python_path)
     }
@@ -260,82 +416,56 @@ fn get_full_python_version(short_version: &str, _release: &str) -> String {
     }
 }
 
-/// Download a file using system tools
-fn download_file(url: &str, dest: &Path) -> PackResult<()> {
-    // Try different download methods based on platform
-    #[cfg(target_os = "windows")]
-    {
-        // Use PowerShell on Windows
-        let status = std::process::Command::new("powershell")
-            .args([
-                "-NoProfile",
-                "-ExecutionPolicy",
-                "Bypass",
-                "-Command",
-                &format!(
-                    "Invoke-WebRequest -Uri '{}' -OutFile '{}' -UseBasicParsing",
-                    url,
-                    dest.display()
-                ),
-            ])
-            .status()
-            .map_err(|e| PackError::Download(format!("Failed to run PowerShell: {}", e)))?;
-
-        if !status.success() {
-            return Err(PackError::Download(format!(
-                "PowerShell download failed with status: {}",
-                status
-            )));
-        }
-    }
+/// Extract a tar.gz archive
+fn extract_tar_gz(
+    archive_path: &Path,
+    dest_dir: &Path,
+    progress: Option<ProgressTracker>,
+) -> PackResult<()> {
+    let file = File::open(archive_path)?;
+    let reader: Box<dyn Read> = match progress {
+        Some(tracker) => Box::new(ProgressReader::new(file, tracker)),
+        None => Box::new(file),
+    };
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        // Try curl first, then wget
-        let curl_result = std::process::Command::new("curl")
-            .args(["-fsSL", "-o", dest.to_str().unwrap_or("."), url])
-            .status();
-
-        match curl_result {
-            Ok(status) if status.success() => {}
-            _ => {
-                // Fallback to wget
-                let wget_status = std::process::Command::new("wget")
-                    .args(["-q", "-O", dest.to_str().unwrap_or("."), url])
-                    .status()
-                    .map_err(|e| {
-                        PackError::Download(format!("Failed to download (no curl/wget): {}", e))
-                    })?;
-
-                if !wget_status.success() {
-                    return Err(PackError::Download(format!(
-                        "wget download failed with status: {}",
-                        wget_status
-                    )));
-                }
-            }
-        }
-    }
+    // Decompress gzip and extract, rejecting entries that escape dest_dir
+    let decoder = flate2::read::GzDecoder::new(BufReader::new(reader));
+    crate::archive::unpack_tar(decoder, dest_dir, 0)?;
 
     Ok(())
 }
 
-/// Extract a tar.gz archive
-fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> PackResult<()> {
-    let file = File::open(archive_path)?;
-    let reader = BufReader::new(file);
-
-    // Decompress gzip
-    let decoder = flate2::read::GzDecoder::new(reader);
-
-    // Extract tar
-    let mut archive = tar::Archive::new(decoder);
-    archive.unpack(dest_dir)?;
+/// Mark every file in a runtime's `bin` directory executable
+///
+/// Archives repacked on Windows or by tools that drop modes lose the exec
+/// bits of `python3` and the scripts next to it.
+#[cfg(unix)]
+fn make_executable(bin_dir: &Path) -> PackResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if !bin_dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(bin_dir)? {
+        let path = entry?.path();
+        // Symlinks (python3 -> python3.11) share their target's mode
+        if fs::symlink_metadata(&path)?.is_file() {
+            let mut perms = fs::metadata(&path)?.permissions();
+            perms.set_mode(perms.mode() | 0o755);
+            fs::set_permissions(&path, perms)?;
+        }
+    }
+    Ok(())
+}
 
+#[cfg(not(unix))]
+fn make_executable(_bin_dir: &Path) -> PackResult<()> {
     Ok(())
 }
 
 /// Runtime: Extract embedded Python distribution to cache
+///
+/// `app_name` is the app's cache key ([`crate::AppIdentity::cache_key`]).
 pub fn extract_runtime(
     python_archive: &[u8],
     app_name: &str,
@@ -345,16 +475,15 @@ pub fn extract_runtime(
     let version_marker = cache_dir.join(".version");
 
     // Check if already extracted with correct version
-    if version_marker.exists() {
-        if let Ok(cached_version) = fs::read_to_string(&version_marker) {
-            if cached_version.trim() == version {
-                let python_path = get_python_exe_path(&cache_dir);
-                if python_path.exists() {
-                    tracing::debug!("Using cached Python runtime: {}", cache_dir.display());
-                    return Ok(python_path);
-                }
-            }
-        }
+    if let Some(python_path) = cached_runtime(&cache_dir, version) {
+        return Ok(python_path);
+    }
+
+    // Several instances of the app may start at once; the first extracts,
+    // the others wait and reuse its result
+    let _lock = CacheLock::acquire(&cache_dir)?;
+    if let Some(python_path) = cached_runtime(&cache_dir, version) {
+        return Ok(python_path);
     }
 
     // Clean up old extraction if exists
@@ -367,11 +496,7 @@ pub fn extract_runtime(
 
     // Decompress and extract
     let decoder = flate2::read::GzDecoder::new(python_archive);
-    let mut archive = tar::Archive::new(decoder);
-    archive.unpack(&cache_dir)?;
-
-    // Write version marker
-    fs::write(&version_marker, version)?;
+    crate::archive::unpack_tar(decoder, &cache_dir, 0)?;
 
     let python_path = get_python_exe_path(&cache_dir);
     if !python_path.exists() {
@@ -390,9 +515,64 @@ pub fn extract_runtime(
         fs::set_permissions(&python_path, perms)?;
     }
 
+    // Imported conda-pack environments need their prefixes rewritten once
+    run_conda_unpack(&cache_dir.join("python"), &python_path)?;
+
+    // Write version marker (after conda-unpack, so a failed run is retried)
+    write_atomic(&version_marker, version.as_bytes())?;
+
     Ok(python_path)
 }
 
+/// Python executable of a complete runtime extraction of `version`
+fn cached_runtime(cache_dir: &Path, version: &str) -> Option<PathBuf> {
+    let cached_version = fs::read_to_string(cache_dir.join(".version")).ok()?;
+    let python_path = get_python_exe_path(cache_dir);
+    (cached_version.trim() == version && python_path.exists()).then(|| {
+        tracing::debug!("Using cached Python runtime: {}", cache_dir.display());
+        python_path
+    })
+}
+
+/// Run `conda-unpack` from an extracted conda-pack environment, if present
+fn run_conda_unpack(prefix: &Path, python_path: &Path) -> PackResult<()> {
+    let (program, args) = if cfg!(windows) {
+        (prefix.join("Scripts").join("conda-unpack.exe"), vec![])
+    } else {
+        // The script's shebang points at the build machine's prefix
+        let script = prefix.join("bin").join("conda-unpack");
+        (python_path.to_path_buf(), vec![script])
+    };
+    let marker = args.first().unwrap_or(&program);
+    if !marker.is_file() {
+        return Ok(());
+    }
+
+    tracing::info!("Running conda-unpack in {}", prefix.display());
+    let output = std::process::Command::new(&program)
+        .args(&args)
+        .current_dir(prefix)
+        .output()?;
+    crate::subprocess::log_output("conda-unpack", &output);
+    if !output.status.success() {
+        return Err(PackError::Config(format!(
+            "conda-unpack failed in {} (exit code {:?}): {}",
+            prefix.display(),
+            output.status.code(),
+            crate::subprocess::stderr_tail(&output)
+        )));
+    }
+    Ok(())
+}
+
+/// Get the default cache directory for downloaded Python distributions
+pub fn get_distribution_cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("AuroraView")
+        .join("python-standalone")
+}
+
 /// Get the runtime cache directory for an app
 pub fn get_runtime_cache_dir(app_name: &str) -> PathBuf {
     dirs::cache_dir()
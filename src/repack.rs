@@ -0,0 +1,119 @@
+//! Repack: change the settings of a packed executable
+//!
+//! Release engineers tweak the window, environment and license of a build
+//! after it was packed. [`repack`] reads the overlay of the packed
+//! executable, replaces these three sections of its configuration with the
+//! ones of a manifest and writes the overlay back. Assets are copied as
+//! stored, so neither the source tree nor a Python download is needed, and
+//! the content hash (the runtime cache key) only changes with the EULA.
+//!
+//! A EULA (`[license.eula]`) is read relative to the manifest directory and
+//! embedded again. The preview watermark is not re-injected; change
+//! `[license.preview]` with a full pack. Overlays signed with
+//! `[signing]` need the signing key, and an Authenticode signature of the
+//! executable must be renewed after repacking.
+
+use crate::eula::EULA_PREFIX;
+use crate::overlay::{OverlayReader, OverlayWriter};
+use crate::{Manifest, OverlaySigner, PackConfig, PackError, PackResult};
+use std::path::Path;
+
+/// Sections [`repack`] replaces
+const SECTIONS: [&str; 3] = ["window", "env", "license"];
+
+/// Outcome of [`repack`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepackReport {
+    /// Sections whose settings changed (`window`, `env`, `license`)
+    pub changed: Vec<String>,
+    /// Content hash of the rewritten overlay
+    pub content_hash: String,
+}
+
+/// Apply the window, environment and license settings of `manifest` to the
+/// packed executable `exe_path`
+///
+/// `base_dir` is the manifest's directory. Signed overlays are re-signed
+/// with `signer`, which they require. Nothing is written when no setting
+/// changed.
+pub fn repack(
+    exe_path: &Path,
+    manifest: &Manifest,
+    base_dir: &Path,
+    signer: Option<&OverlaySigner>,
+) -> PackResult<RepackReport> {
+    let mut archive = OverlayReader::open(exe_path)?.ok_or_else(|| {
+        PackError::InvalidOverlay(format!("{} has no overlay", exe_path.display()))
+    })?;
+    if archive.signature.is_some() && signer.is_none() {
+        return Err(PackError::Signature(
+            "overlay is signed; repack it with the signing key".to_string(),
+        ));
+    }
+    let update = PackConfig::from_manifest(manifest, base_dir)?;
+    if let Some(eula) = update.license.as_ref().and_then(|l| l.eula.as_ref()) {
+        eula.validate()?;
+        if !eula.path.is_file() {
+            return Err(PackError::AssetNotFound(eula.path.clone()));
+        }
+    }
+
+    let mut config = archive.config.clone();
+    config.window = update.window;
+    config.env = update.env;
+    config.license = update.license;
+
+    // Embed the EULA (unless already there); drop previous ones
+    let mut added = Vec::new();
+    let mut eula_asset = None;
+    if let Some(eula) = config.license.as_mut().and_then(|l| l.eula.as_mut()) {
+        let (asset, content, embedded) = eula.embed()?;
+        if archive.read_asset(&asset)?.as_deref() != Some(&content[..]) {
+            added.push((asset.clone(), content));
+        }
+        eula_asset = Some(asset);
+        *eula = embedded;
+    }
+    let removed: Vec<String> = archive
+        .entries()
+        .iter()
+        .map(|entry| entry.path.clone())
+        .filter(|path| path.starts_with(EULA_PREFIX) && Some(path) != eula_asset.as_ref())
+        .collect();
+
+    let changed = changed_sections(&archive.config, &config)?;
+    if changed.is_empty() && added.is_empty() && removed.is_empty() {
+        tracing::info!("{} already has these settings", exe_path.display());
+        return Ok(RepackReport {
+            changed,
+            content_hash: archive.content_hash.clone(),
+        });
+    }
+    drop(archive);
+
+    let content_hash = OverlayWriter::rewrite(exe_path, Some(&config), added, removed, signer)?;
+    tracing::info!(
+        "Repacked {} ({} changed)",
+        exe_path.display(),
+        if changed.is_empty() {
+            "EULA".to_string()
+        } else {
+            changed.join(", ")
+        }
+    );
+    Ok(RepackReport {
+        changed,
+        content_hash,
+    })
+}
+
+/// Sections of `SECTIONS` that differ between two configurations
+fn changed_sections(before: &PackConfig, after: &PackConfig) -> PackResult<Vec<String>> {
+    let before = serde_json::to_value(before)?;
+    let after = serde_json::to_value(after)?;
+    Ok(SECTIONS
+        .iter()
+        .filter(|section| before.get(*section) != after.get(*section))
+        .map(|section| section.to_string())
+        .collect())
+}
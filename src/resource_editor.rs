@@ -1,15 +1,19 @@
 //! Windows executable resource editor
 //!
 //! This module provides functionality to modify Windows PE executable resources,
-//! including icons, version information, and subsystem settings.
+//! including icons, version information, the application manifest and
+//! subsystem settings.
 //!
 //! It uses rcedit (https://github.com/electron/rcedit) as the underlying tool.
 
+use crate::common::DpiAwareness;
+use crate::store::{missing_object, ArtifactStore, ObjectKind};
+use crate::subprocess::{system_runner, CommandRunner};
 use crate::{PackError, PackResult};
 use std::fs;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 
 /// rcedit release version to download
 const RCEDIT_VERSION: &str = "v2.0.0";
@@ -18,19 +22,33 @@ const RCEDIT_VERSION: &str = "v2.0.0";
 const RCEDIT_DOWNLOAD_URL: &str =
     "https://github.com/electron/rcedit/releases/download/{version}/rcedit-x64.exe";
 
+/// Artifact store namespace of downloaded tools
+const TOOLS_NAMESPACE: &str = "tools";
+
 /// Windows executable resource editor
 ///
 /// This struct wraps the rcedit tool for modifying PE resources.
 pub struct ResourceEditor {
     /// Path to the rcedit executable
     rcedit_path: PathBuf,
+    /// Runs rcedit
+    runner: Arc<dyn CommandRunner>,
 }
 
 impl ResourceEditor {
     /// Create a new ResourceEditor, downloading rcedit if necessary
     pub fn new() -> PackResult<Self> {
-        let rcedit_path = Self::ensure_rcedit()?;
-        Ok(Self { rcedit_path })
+        Self::from_store(&ArtifactStore::open_default())
+    }
+
+    /// Create a ResourceEditor with rcedit from an artifact store,
+    /// downloading it if necessary
+    pub fn from_store(store: &ArtifactStore) -> PackResult<Self> {
+        let rcedit_path = Self::ensure_rcedit(store)?;
+        Ok(Self {
+            rcedit_path,
+            runner: system_runner(),
+        })
     }
 
     /// Create a ResourceEditor with a custom rcedit path
@@ -41,46 +59,63 @@ impl ResourceEditor {
                 path.display()
             )));
         }
-        Ok(Self { rcedit_path: path })
+        Ok(Self {
+            rcedit_path: path,
+            runner: system_runner(),
+        })
     }
 
-    /// Minimum expected size for rcedit-x64.exe (should be ~1.3MB)
-    const RCEDIT_MIN_SIZE: u64 = 500_000;
+    /// Run rcedit through `runner`
+    pub fn with_runner(mut self, runner: Arc<dyn CommandRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
 
-    /// Ensure rcedit is available, downloading if necessary
-    fn ensure_rcedit() -> PackResult<PathBuf> {
-        // Check cache directory
-        let cache_dir = dirs::cache_dir()
+    /// Cache directory for downloaded tools (rcedit) used by older versions
+    pub fn tools_cache_dir() -> PathBuf {
+        dirs::cache_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("auroraview")
-            .join("tools");
-
-        fs::create_dir_all(&cache_dir)?;
+            .join("tools")
+    }
 
-        let rcedit_path = cache_dir.join("rcedit-x64.exe");
+    /// Minimum expected size for rcedit-x64.exe (should be ~1.3MB)
+    const RCEDIT_MIN_SIZE: u64 = 500_000;
 
-        // Check if already downloaded and valid
-        if rcedit_path.exists() {
+    /// Ensure rcedit is available, downloading if necessary
+    fn ensure_rcedit(store: &ArtifactStore) -> PackResult<PathBuf> {
+        let name = "rcedit-x64.exe";
+        let cached = || {
+            let path = store.resolve(TOOLS_NAMESPACE, name)?;
             // Verify file size to detect corrupted downloads
-            if let Ok(metadata) = fs::metadata(&rcedit_path) {
-                if metadata.len() >= Self::RCEDIT_MIN_SIZE {
-                    tracing::debug!("Using cached rcedit at: {}", rcedit_path.display());
-                    return Ok(rcedit_path);
-                } else {
-                    tracing::warn!(
-                        "Cached rcedit is too small ({} bytes), re-downloading...",
-                        metadata.len()
-                    );
-                    let _ = fs::remove_file(&rcedit_path);
-                }
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if size < Self::RCEDIT_MIN_SIZE {
+                tracing::warn!(
+                    "Cached rcedit is too small ({} bytes), re-downloading...",
+                    size
+                );
+                return None;
             }
+            tracing::debug!("Using cached rcedit at: {}", path.display());
+            Some(path)
+        };
+
+        if let Some(path) = cached() {
+            return Ok(path);
+        }
+        let _lock = store.lock(TOOLS_NAMESPACE, name)?;
+        if let Some(path) = cached() {
+            return Ok(path);
         }
 
         // Download rcedit
         tracing::info!("Downloading rcedit {}...", RCEDIT_VERSION);
         let url = RCEDIT_DOWNLOAD_URL.replace("{version}", RCEDIT_VERSION);
 
-        let response = Self::download_file(&url)?;
+        let response = store
+            .http()
+            .get_bytes(&url)
+            .map_err(|e| PackError::ResourceEdit(format!("Failed to download rcedit: {}", e)))?;
 
         // Validate downloaded size
         if (response.len() as u64) < Self::RCEDIT_MIN_SIZE {
@@ -92,8 +127,11 @@ impl ResourceEditor {
             )));
         }
 
-        let mut file = fs::File::create(&rcedit_path)?;
-        file.write_all(&response)?;
+        let digest = store.insert(name, &response)?;
+        store.link(TOOLS_NAMESPACE, name, ObjectKind::Blob, &digest)?;
+        let rcedit_path = store
+            .blob(&digest)
+            .ok_or_else(|| missing_object(TOOLS_NAMESPACE, name))?;
 
         tracing::info!(
             "rcedit downloaded to: {} ({} bytes)",
@@ -103,59 +141,6 @@ impl ResourceEditor {
         Ok(rcedit_path)
     }
 
-    /// Download a file from URL
-    fn download_file(url: &str) -> PackResult<Vec<u8>> {
-        // Use PowerShell to download on Windows (no extra dependencies)
-        #[cfg(target_os = "windows")]
-        {
-            // Use Invoke-WebRequest with -OutFile to download binary correctly
-            let temp_file = std::env::temp_dir().join("rcedit-download.exe");
-            let output = Command::new("powershell")
-                .args([
-                    "-NoProfile",
-                    "-NonInteractive",
-                    "-Command",
-                    &format!(
-                        "[Net.ServicePointManager]::SecurityProtocol = [Net.SecurityProtocolType]::Tls12; \
-                         Invoke-WebRequest -Uri '{}' -OutFile '{}' -UseBasicParsing",
-                        url,
-                        temp_file.display()
-                    ),
-                ])
-                .output()
-                .map_err(|e| PackError::ResourceEdit(format!("Failed to run PowerShell: {}", e)))?;
-
-            if !output.status.success() {
-                return Err(PackError::ResourceEdit(format!(
-                    "Failed to download rcedit: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
-
-            let data = fs::read(&temp_file)?;
-            let _ = fs::remove_file(&temp_file);
-            Ok(data)
-        }
-
-        #[cfg(not(target_os = "windows"))]
-        {
-            // On non-Windows, use curl
-            let output = Command::new("curl")
-                .args(["-fsSL", url])
-                .output()
-                .map_err(|e| PackError::ResourceEdit(format!("Failed to run curl: {}", e)))?;
-
-            if !output.status.success() {
-                return Err(PackError::ResourceEdit(format!(
-                    "Failed to download rcedit: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
-
-            Ok(output.stdout)
-        }
-    }
-
     /// Set the icon of an executable
     ///
     /// # Arguments
@@ -181,10 +166,13 @@ impl ResourceEditor {
         tracing::info!("Setting icon: {}", icon_path.display());
 
         // rcedit syntax: rcedit <exe> --set-icon <icon>
-        let output = Command::new(&self.rcedit_path)
-            .arg(exe_path)
-            .args(["--set-icon", &icon_path.to_string_lossy()])
-            .output()
+        let output = self
+            .runner
+            .output(
+                Command::new(&self.rcedit_path)
+                    .arg(exe_path)
+                    .args(["--set-icon", &icon_path.to_string_lossy()]),
+            )
             .map_err(|e| PackError::ResourceEdit(format!("Failed to run rcedit: {}", e)))?;
 
         if !output.status.success() {
@@ -298,10 +286,13 @@ impl ResourceEditor {
         tracing::debug!("Setting version string {}: {}", key, value);
 
         // rcedit syntax: rcedit <exe> --set-version-string <key> <value>
-        let output = Command::new(&self.rcedit_path)
-            .arg(exe_path)
-            .args(["--set-version-string", key, value])
-            .output()
+        let output = self
+            .runner
+            .output(Command::new(&self.rcedit_path).arg(exe_path).args([
+                "--set-version-string",
+                key,
+                value,
+            ]))
             .map_err(|e| PackError::ResourceEdit(format!("Failed to run rcedit: {}", e)))?;
 
         if !output.status.success() {
@@ -314,6 +305,39 @@ impl ResourceEditor {
         Ok(())
     }
 
+    /// Replace the application manifest
+    ///
+    /// # Arguments
+    /// * `exe_path` - Path to the executable to modify
+    /// * `manifest` - Manifest XML, see [`application_manifest`]
+    pub fn set_application_manifest(&self, exe_path: &Path, manifest: &str) -> PackResult<()> {
+        let mut file = tempfile::Builder::new()
+            .prefix("auroraview-")
+            .suffix(".manifest")
+            .tempfile()?;
+        std::io::Write::write_all(&mut file, manifest.as_bytes())?;
+
+        // rcedit syntax: rcedit <exe> --application-manifest <file>
+        let output = self
+            .runner
+            .output(
+                Command::new(&self.rcedit_path)
+                    .arg(exe_path)
+                    .arg("--application-manifest")
+                    .arg(file.path()),
+            )
+            .map_err(|e| PackError::ResourceEdit(format!("Failed to run rcedit: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(PackError::ResourceEdit(format!(
+                "rcedit failed to set application manifest: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Set file version
     ///
     /// # Arguments
@@ -323,10 +347,13 @@ impl ResourceEditor {
         tracing::debug!("Setting file version: {}", version);
 
         // rcedit syntax: rcedit <exe> --set-file-version <version>
-        let output = Command::new(&self.rcedit_path)
-            .arg(exe_path)
-            .args(["--set-file-version", version])
-            .output()
+        let output = self
+            .runner
+            .output(
+                Command::new(&self.rcedit_path)
+                    .arg(exe_path)
+                    .args(["--set-file-version", version]),
+            )
             .map_err(|e| PackError::ResourceEdit(format!("Failed to run rcedit: {}", e)))?;
 
         if !output.status.success() {
@@ -348,10 +375,13 @@ impl ResourceEditor {
         tracing::debug!("Setting product version: {}", version);
 
         // rcedit syntax: rcedit <exe> --set-product-version <version>
-        let output = Command::new(&self.rcedit_path)
-            .arg(exe_path)
-            .args(["--set-product-version", version])
-            .output()
+        let output = self
+            .runner
+            .output(
+                Command::new(&self.rcedit_path)
+                    .arg(exe_path)
+                    .args(["--set-product-version", version]),
+            )
             .map_err(|e| PackError::ResourceEdit(format!("Failed to run rcedit: {}", e)))?;
 
         if !output.status.success() {
@@ -399,6 +429,17 @@ impl ResourceEditor {
             self.set_version_string(exe_path, "LegalCopyright", copyright)?;
         }
 
+        // Shortcut creators (installers, deployment tools) read the ID from
+        // here; the runtime sets it on the process
+        if let Some(ref id) = config.app_user_model_id {
+            self.set_version_string(exe_path, "AppUserModelID", id)?;
+        }
+
+        if let Some(awareness) = config.dpi_awareness {
+            tracing::info!("Setting DPI awareness: {:?}", awareness);
+            self.set_application_manifest(exe_path, &application_manifest(awareness))?;
+        }
+
         // Set subsystem LAST (directly modifies PE header, doesn't use rcedit)
         // Only modify if we need to hide console (console=false means GUI subsystem)
         if !config.console {
@@ -435,6 +476,12 @@ pub struct ResourceConfig {
 
     /// Copyright string
     pub copyright: Option<String>,
+
+    /// AppUserModelID recorded in the version info
+    pub app_user_model_id: Option<String>,
+
+    /// DPI awareness declared in the application manifest
+    pub dpi_awareness: Option<DpiAwareness>,
 }
 
 impl ResourceConfig {
@@ -491,6 +538,18 @@ impl ResourceConfig {
         self
     }
 
+    /// Set the AppUserModelID
+    pub fn with_app_user_model_id(mut self, id: impl Into<String>) -> Self {
+        self.app_user_model_id = Some(id.into());
+        self
+    }
+
+    /// Set the DPI awareness
+    pub fn with_dpi_awareness(mut self, awareness: DpiAwareness) -> Self {
+        self.dpi_awareness = Some(awareness);
+        self
+    }
+
     /// Check if any resource modifications are configured
     pub fn has_modifications(&self) -> bool {
         self.icon.is_some()
@@ -501,9 +560,50 @@ impl ResourceConfig {
             || self.product_name.is_some()
             || self.company_name.is_some()
             || self.copyright.is_some()
+            || self.app_user_model_id.is_some()
+            || self.dpi_awareness.is_some()
     }
 }
 
+/// Application manifest declaring DPI awareness
+///
+/// rcedit replaces the launcher's manifest as a whole, so this also carries
+/// what the launcher's own manifest declares: Common Controls v6, Windows
+/// 10/11 compatibility and `asInvoker`.
+pub fn application_manifest(awareness: DpiAwareness) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <dependency>
+    <dependentAssembly>
+      <assemblyIdentity type="win32" name="Microsoft.Windows.Common-Controls" version="6.0.0.0" processorArchitecture="*" publicKeyToken="6595b64144ccf1df" language="*"/>
+    </dependentAssembly>
+  </dependency>
+  <compatibility xmlns="urn:schemas-microsoft-com:compatibility.v1">
+    <application>
+      <supportedOS Id="{{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}}"/>
+    </application>
+  </compatibility>
+  <application xmlns="urn:schemas-microsoft-com:asm.v3">
+    <windowsSettings>
+      <dpiAware xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">{}</dpiAware>
+      <dpiAwareness xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">{}</dpiAwareness>
+    </windowsSettings>
+  </application>
+  <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
+    <security>
+      <requestedPrivileges>
+        <requestedExecutionLevel level="asInvoker" uiAccess="false"/>
+      </requestedPrivileges>
+    </security>
+  </trustInfo>
+</assembly>
+"#,
+        awareness.manifest_dpi_aware(),
+        awareness.manifest_dpi_awareness()
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -534,4 +634,20 @@ mod tests {
         let config = ResourceConfig::new().with_console(true);
         assert!(!config.has_modifications());
     }
+
+    #[test]
+    fn test_application_manifest_dpi_awareness() {
+        let manifest = application_manifest(DpiAwareness::PerMonitorV2);
+        assert!(manifest.contains(">true/pm</dpiAware>"));
+        assert!(manifest.contains(">PerMonitorV2, PerMonitor</dpiAwareness>"));
+
+        let manifest = application_manifest(DpiAwareness::System);
+        assert!(manifest.contains(">true</dpiAware>"));
+        assert!(manifest.contains(">system</dpiAwareness>"));
+
+        let config = ResourceConfig::new()
+            .with_console(true)
+            .with_dpi_awareness(DpiAwareness::PerMonitor);
+        assert!(config.has_modifications());
+    }
 }
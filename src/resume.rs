@@ -0,0 +1,367 @@
+//! Reusing earlier pack runs
+//!
+//! # Resuming failed packs (`[build.resume]`)
+//!
+//! A pack that fails late, e.g. on a missing signing certificate, should not
+//! install frontend dependencies, run hooks and collect Python packages
+//! again. With `[build.resume]`, every finished stage is recorded in a
+//! checkpoint keyed by [`PackConfig::config_hash`]:
+//!
+//! ```text
+//! <cache>/AuroraView/resume/<app>/<config hash>.json
+//! ```
+//!
+//! The next run of the same configuration resumes from the failed stage:
+//!
+//! - `prepare` finished: toolchains are still resolved (from the artifact
+//!   store), but frontend dependencies, `before_collect` / `before_pack`
+//!   hooks and downloads are skipped
+//! - `pack` finished: the output is reused if the executable is unchanged,
+//!   and only `after_pack` (signing, hooks, update feed, provenance) runs
+//!
+//! Checkpoints also record the size and modification time of every planned
+//! input, so editing an asset starts over. A successful run removes its
+//! checkpoint.
+//!
+//! # Skipping unchanged packs (`[build] skip_unchanged`)
+//!
+//! With `skip_unchanged`, a successful run records a build hash over the
+//! resolved manifest (including the settings applied after packing), the
+//! launcher and the content of every input. When the next run computes the
+//! same hash and finds the executable untouched, it returns the recorded
+//! output instead of packing, so `pack` can run in every CI job.
+//! [`Packer::with_force`] packs anyway.
+//!
+//! The check runs after the `prepare` stage, because hooks may generate
+//! inputs (e.g. `npm run build`). Unpinned Python packages are compared by
+//! their requirement strings, not by what the index serves today.
+//!
+//! Records live next to the checkpoints, as `latest-<executable>.json`.
+//!
+//! [`PackConfig::config_hash`]: crate::PackConfig::config_hash
+//! [`Packer::with_force`]: crate::Packer::with_force
+
+use crate::cache_lock::write_atomic;
+use crate::context::unix_nanos;
+use crate::identity::AppIdentity;
+use crate::overlay::{AssetIndexEntry, DedupStats};
+use crate::packer::PackOutput;
+use crate::plan::PackPlan;
+use crate::progress::PackStage;
+use crate::provenance::ResourceDescriptor;
+use crate::{PackConfig, PackResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Resumable pack configuration
+///
+/// Located at `[build.resume]` in TOML. Pack-time only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumeConfig {
+    /// Resume failed packs from the failed stage
+    pub enabled: bool,
+
+    /// Checkpoint directory (default: `<cache>/AuroraView/resume/<app>`)
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for ResumeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: None,
+        }
+    }
+}
+
+impl ResumeConfig {
+    /// Checkpoint directory of an app
+    pub fn checkpoint_dir(&self, identity: &AppIdentity) -> PathBuf {
+        self.dir.clone().unwrap_or_else(|| {
+            dirs::cache_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("AuroraView")
+                .join("resume")
+                .join(identity.cache_key())
+        })
+    }
+}
+
+/// Recorded [`PackOutput`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OutputRecord {
+    executable: PathBuf,
+    /// Size and modification time of the executable when recorded
+    stamp: (u64, u128),
+    size: u64,
+    asset_count: usize,
+    python_file_count: usize,
+    mode: String,
+    update_feed: Option<PathBuf>,
+    provenance: Option<PathBuf>,
+    #[serde(default)]
+    dedup: DedupStats,
+    #[serde(default)]
+    assets: Vec<AssetIndexEntry>,
+    #[serde(default)]
+    stage_timings: BTreeMap<String, u64>,
+    #[serde(default)]
+    tools: BTreeMap<String, String>,
+    #[serde(default)]
+    downloads: Vec<ResourceDescriptor>,
+}
+
+impl OutputRecord {
+    fn new(output: &PackOutput) -> Self {
+        Self {
+            executable: output.executable.clone(),
+            stamp: file_stamp(&output.executable).unwrap_or_default(),
+            size: output.size,
+            asset_count: output.asset_count,
+            python_file_count: output.python_file_count,
+            mode: output.mode.clone(),
+            update_feed: output.update_feed.clone(),
+            provenance: output.provenance.clone(),
+            dedup: output.dedup,
+            assets: output.assets.clone(),
+            stage_timings: output.stage_timings.clone(),
+            tools: output.tools.clone(),
+            downloads: output.downloads.clone(),
+        }
+    }
+
+    /// The recorded output, if its executable is unchanged (same size and
+    /// modification time)
+    fn restore(&self) -> Option<PackOutput> {
+        if file_stamp(&self.executable) != Some(self.stamp) {
+            tracing::info!(
+                "Packing again: {} changed since the last run",
+                self.executable.display()
+            );
+            return None;
+        }
+        Some(PackOutput {
+            executable: self.executable.clone(),
+            size: self.size,
+            asset_count: self.asset_count,
+            python_file_count: self.python_file_count,
+            mode: self.mode.clone(),
+            regressions: Vec::new(),
+            dedup: self.dedup,
+            incremental: None,
+            update_feed: self.update_feed.clone(),
+            provenance: self.provenance.clone(),
+            resumed_from: None,
+            up_to_date: false,
+            assets: self.assets.clone(),
+            stage_timings: self.stage_timings.clone(),
+            duration_ms: 0,
+            tools: self.tools.clone(),
+            downloads: self.downloads.clone(),
+            smoke_test: None,
+        })
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    /// Stamp of the planned inputs (see [`input_stamp`])
+    inputs: String,
+    /// Stages finished, in order
+    completed: Vec<PackStage>,
+    output: Option<OutputRecord>,
+}
+
+/// Checkpoint of one configuration
+#[derive(Debug)]
+pub(crate) struct Checkpoint {
+    path: PathBuf,
+    state: State,
+}
+
+impl Checkpoint {
+    /// Load the checkpoint of `config_hash`; a checkpoint of other inputs is
+    /// discarded
+    pub fn open(dir: &Path, config_hash: &str, inputs: String) -> Self {
+        let path = dir.join(format!("{}.json", config_hash));
+        let state = fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<State>(&data).ok())
+            .filter(|state| state.inputs == inputs)
+            .unwrap_or(State {
+                inputs,
+                ..Default::default()
+            });
+        Self { path, state }
+    }
+
+    /// Stage to resume from, if an earlier run got past `prepare`
+    ///
+    /// `after_pack` still needs [`packed_output`](Self::packed_output) to
+    /// find the executable unchanged.
+    pub fn resume_from(&self) -> Option<PackStage> {
+        if self.is_completed(PackStage::Pack) && self.state.output.is_some() {
+            Some(PackStage::AfterPack)
+        } else if self.is_completed(PackStage::Prepare) {
+            Some(PackStage::Pack)
+        } else {
+            None
+        }
+    }
+
+    fn is_completed(&self, stage: PackStage) -> bool {
+        self.state.completed.contains(&stage)
+    }
+
+    /// Output of the finished `pack` stage, if its executable is unchanged
+    pub fn packed_output(&self) -> Option<PackOutput> {
+        let mut output = self.state.output.as_ref()?.restore()?;
+        output.resumed_from = Some(PackStage::AfterPack);
+        Some(output)
+    }
+
+    /// Record a finished stage (with the output, for `pack`)
+    pub fn complete(&mut self, stage: PackStage, output: Option<&PackOutput>) -> PackResult<()> {
+        if !self.is_completed(stage) {
+            self.state.completed.push(stage);
+        }
+        if let Some(output) = output {
+            self.state.output = Some(OutputRecord::new(output));
+        }
+        write_atomic(&self.path, &serde_json::to_vec_pretty(&self.state)?)
+    }
+
+    /// Remove the checkpoint of a successful run
+    pub fn finish(self) -> PackResult<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Build hash and output of the latest successful pack of an executable
+#[derive(Debug, Serialize, Deserialize)]
+struct Latest {
+    build_hash: String,
+    output: OutputRecord,
+}
+
+/// Up-to-date record of one executable (`[build] skip_unchanged`)
+#[derive(Debug)]
+pub(crate) struct UpToDate {
+    path: PathBuf,
+    build_hash: String,
+}
+
+impl UpToDate {
+    pub fn new(dir: &Path, executable: &Path, build_hash: String) -> Self {
+        let name = executable.file_name().unwrap_or_default().to_string_lossy();
+        Self {
+            path: dir.join(format!("latest-{}.json", name)),
+            build_hash,
+        }
+    }
+
+    /// Output of the previous pack, if it had the same build hash and its
+    /// executable is untouched
+    pub fn previous(&self) -> Option<PackOutput> {
+        let data = fs::read(&self.path).ok()?;
+        let latest: Latest = serde_json::from_slice(&data).ok()?;
+        if latest.build_hash != self.build_hash {
+            return None;
+        }
+        let mut output = latest.output.restore()?;
+        output.up_to_date = true;
+        Some(output)
+    }
+
+    /// Record the output of a successful pack
+    pub fn record(&self, output: &PackOutput) -> PackResult<()> {
+        let latest = Latest {
+            build_hash: self.build_hash.clone(),
+            output: OutputRecord::new(output),
+        };
+        write_atomic(&self.path, &serde_json::to_vec_pretty(&latest)?)
+    }
+}
+
+/// Hash of everything a pack's output depends on
+///
+/// Covers the packed settings ([`PackConfig::config_hash`]), the resolved
+/// manifest and the settings applied after packing, the launcher (size and
+/// modification time) and the content of every planned input.
+///
+/// [`PackConfig::config_hash`]: crate::PackConfig::config_hash
+pub(crate) fn build_hash(
+    config: &PackConfig,
+    plan: &PackPlan,
+    launcher: &Path,
+) -> PackResult<String> {
+    let settings = serde_json::json!({
+        "config": config.config_hash()?,
+        "manifest": config.manifest_sha256,
+        "signing": config.signing,
+        "update_feed": config.update_feed,
+        "provenance": config.provenance,
+        "test_run": config.test_run,
+        "launcher": file_stamp(launcher),
+        "python_packages": plan.python_packages,
+    });
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(&settings)?);
+    for asset in &plan.assets {
+        let (sha256, _) = crate::hashing::sha256_file(&asset.source_path)?;
+        hasher.update(asset.path.as_bytes());
+        hasher.update([0]);
+        hasher.update(sha256.as_bytes());
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Stamp of the planned inputs: path, size and modification time of every
+/// asset source
+pub(crate) fn input_stamp(plan: &PackPlan) -> String {
+    let mut hasher = Sha256::new();
+    for asset in &plan.assets {
+        let (size, modified) = file_stamp(&asset.source_path).unwrap_or_default();
+        hasher.update(asset.path.as_bytes());
+        hasher.update([0]);
+        hasher.update(asset.source_path.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(size.to_le_bytes());
+        hasher.update(modified.to_le_bytes());
+    }
+    hasher.update(plan.python_packages.join("\n").as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Size and modification time (nanoseconds since the epoch) of a file
+fn file_stamp(path: &Path) -> Option<(u64, u128)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.len(), unix_nanos(meta.modified().ok()?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_discards_other_inputs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut checkpoint = Checkpoint::open(temp.path(), "abc", "inputs-1".to_string());
+        assert_eq!(checkpoint.resume_from(), None);
+        checkpoint.complete(PackStage::Prepare, None).unwrap();
+
+        let checkpoint = Checkpoint::open(temp.path(), "abc", "inputs-1".to_string());
+        assert_eq!(checkpoint.resume_from(), Some(PackStage::Pack));
+        let checkpoint = Checkpoint::open(temp.path(), "abc", "inputs-2".to_string());
+        assert_eq!(checkpoint.resume_from(), None);
+        let checkpoint = Checkpoint::open(temp.path(), "other", "inputs-1".to_string());
+        assert_eq!(checkpoint.resume_from(), None);
+    }
+}
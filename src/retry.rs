@@ -0,0 +1,194 @@
+//! Retry-with-backoff for output executable modifications
+//!
+//! Antivirus scanners, indexers and still-running previous builds briefly
+//! lock freshly written executables (mostly on Windows). Copying the
+//! launcher, appending the overlay and running rcedit are wrapped in
+//! [`RetryPolicy::run`], which retries on lock-style errors and, when all
+//! attempts fail, names the processes holding the file where possible.
+
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// Retry policy for file operations on the output executable
+///
+/// Located at `[build.retry]` in TOML.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts (1 = no retry)
+    pub attempts: u32,
+
+    /// Delay before the first retry, in milliseconds (doubled per retry)
+    pub initial_delay_ms: u64,
+
+    /// Maximum delay between retries, in milliseconds
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            initial_delay_ms: 100,
+            max_delay_ms: 2000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Run `op`, retrying with exponential backoff while it fails with a
+    /// file-lock error on `path`
+    pub fn run<T>(
+        &self,
+        what: &str,
+        path: &Path,
+        mut op: impl FnMut() -> PackResult<T>,
+    ) -> PackResult<T> {
+        let attempts = self.attempts.max(1);
+        let mut delay = Duration::from_millis(self.initial_delay_ms);
+        let max_delay = Duration::from_millis(self.max_delay_ms);
+
+        for attempt in 1..=attempts {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < attempts && is_lock_error(&e) => {
+                    tracing::warn!(
+                        "{} failed for {} (attempt {}/{}): {}; retrying in {} ms",
+                        what,
+                        path.display(),
+                        attempt,
+                        attempts,
+                        e,
+                        delay.as_millis()
+                    );
+                    std::thread::sleep(delay);
+                    delay = (delay * 2).min(max_delay);
+                }
+                Err(e) if is_lock_error(&e) => return Err(with_lock_diagnostic(e, path)),
+                Err(e) => return Err(e),
+            }
+        }
+
+        unreachable!("attempts is at least 1")
+    }
+}
+
+/// Check whether an error looks like a transient file lock
+pub fn is_lock_error(error: &PackError) -> bool {
+    match error {
+        PackError::Io(e) => {
+            #[cfg(target_os = "windows")]
+            {
+                // ERROR_ACCESS_DENIED, ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION,
+                // ERROR_USER_MAPPED_FILE
+                matches!(e.raw_os_error(), Some(5 | 32 | 33 | 1224))
+                    || e.kind() == std::io::ErrorKind::PermissionDenied
+            }
+            #[cfg(not(target_os = "windows"))]
+            {
+                // EBUSY, ETXTBSY (executable is running)
+                matches!(e.raw_os_error(), Some(16 | 26))
+            }
+        }
+        // rcedit reports locked files as generic load/commit failures
+        PackError::ResourceEdit(_) => cfg!(target_os = "windows"),
+        _ => false,
+    }
+}
+
+/// Find processes that hold `path` open or are running it
+///
+/// Best effort: returns an empty list if the platform offers no cheap way
+/// to find out.
+pub fn locking_processes(path: &Path) -> Vec<String> {
+    let Ok(path) = path.canonicalize() else {
+        return Vec::new();
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let pid: u32 = e.file_name().to_str()?.parse().ok()?;
+                let proc_dir = e.path();
+                let running = std::fs::read_link(proc_dir.join("exe")).ok() == Some(path.clone());
+                let open = std::fs::read_dir(proc_dir.join("fd"))
+                    .map(|fds| {
+                        fds.filter_map(|fd| fd.ok())
+                            .any(|fd| std::fs::read_link(fd.path()).ok() == Some(path.clone()))
+                    })
+                    .unwrap_or(false);
+                if !running && !open {
+                    return None;
+                }
+                let name = std::fs::read_to_string(proc_dir.join("comm")).unwrap_or_default();
+                Some(format!("{} (pid {})", name.trim(), pid))
+            })
+            .collect()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Processes running the executable (e.g. a previous build left open)
+        let script = format!(
+            "Get-Process | Where-Object {{ $_.Path -eq '{}' }} | \
+             ForEach-Object {{ \"$($_.ProcessName) (pid $($_.Id))\" }}",
+            path.display()
+                .to_string()
+                .trim_start_matches(r"\\?\")
+                .replace('\'', "''")
+        );
+        std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .output()
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let _ = path;
+        Vec::new()
+    }
+}
+
+/// Append the locking process diagnostic to a lock error
+fn with_lock_diagnostic(error: PackError, path: &Path) -> PackError {
+    let processes = locking_processes(path);
+    let note = if processes.is_empty() {
+        format!(
+            "{} is locked by another process (antivirus scanner or a running copy?)",
+            path.display()
+        )
+    } else {
+        format!("{} is locked by: {}", path.display(), processes.join(", "))
+    };
+
+    match error {
+        PackError::Io(e) => {
+            PackError::Io(std::io::Error::new(e.kind(), format!("{}; {}", e, note)))
+        }
+        PackError::ResourceEdit(msg) => PackError::ResourceEdit(format!("{}; {}", msg, note)),
+        other => other,
+    }
+}
@@ -0,0 +1,237 @@
+//! Runtime extraction cache bookkeeping
+//!
+//! Packed apps extract their runtime (Python distribution, process backend
+//! binaries, ...) below the app's cache directory. Every update extracts
+//! into a new directory, so without bookkeeping old versions pile up. A
+//! cache manifest records which directory belongs to which overlay and when
+//! it was last used:
+//!
+//! ```text
+//! <cache>/AuroraView/runtime/<app>/
+//! ├── cache-manifest.json     - Entries keyed by overlay content hash
+//! ├── 3f9a1c2b7d4e5f60/       - Extraction of one overlay
+//! └── v1.2.0/                 - Or a per-version directory (see VersioningConfig)
+//! ```
+//!
+//! The packed runtime records its directory on start, holds
+//! [`RuntimeCache::lock_in_use`] on it while it runs, and then calls
+//! [`RuntimeCache::clean_stale_caches`] with `[runtime.versioning] keep`;
+//! `Packer::clean_stale_caches` does the same from the CLI. Directories of
+//! instances still running (e.g. an older version next to an updated one)
+//! are never removed.
+
+use crate::cache_lock::{write_atomic, CacheLock};
+use crate::clean::{CleanEntry, CleanReport, CleanScope};
+use crate::context::unix_seconds;
+use crate::identity::AppIdentity;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+/// File name of the cache manifest in an app's cache directory
+pub const CACHE_MANIFEST_FILE: &str = "cache-manifest.json";
+
+/// One extraction directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Content hash of the overlay the directory was extracted from
+    pub overlay_hash: String,
+    /// Directory name, relative to the app's cache directory
+    pub dir: String,
+    /// App version, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Unix timestamp (seconds) of the first use
+    pub created: u64,
+    /// Unix timestamp (seconds) of the last use
+    pub last_used: u64,
+}
+
+/// Contents of [`CACHE_MANIFEST_FILE`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheManifest {
+    /// Entries, most recently used last
+    pub entries: Vec<CacheEntry>,
+}
+
+/// Extraction cache of one app
+#[derive(Debug, Clone)]
+pub struct RuntimeCache {
+    root: PathBuf,
+}
+
+impl RuntimeCache {
+    /// Cache rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Cache of an app on this machine
+    pub fn for_app(identity: &AppIdentity) -> Self {
+        Self::new(identity.runtime_cache_dir())
+    }
+
+    /// Cache root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Default extraction directory of an overlay
+    pub fn entry_dir(&self, overlay_hash: &str) -> PathBuf {
+        self.root.join(overlay_hash)
+    }
+
+    /// Read the cache manifest (empty if there is none yet)
+    ///
+    /// A corrupt manifest is treated as empty: directories it listed are
+    /// left alone, not removed.
+    pub fn manifest(&self) -> PackResult<CacheManifest> {
+        let path = self.root.join(CACHE_MANIFEST_FILE);
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(CacheManifest::default())
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_slice(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring corrupt cache manifest {}: {}", path.display(), e);
+            CacheManifest::default()
+        }))
+    }
+
+    /// Record that `dir` holds the extraction of `overlay_hash` and is in use
+    ///
+    /// `dir` must be a direct child of the cache root.
+    pub fn record(&self, overlay_hash: &str, dir: &Path, version: Option<&str>) -> PackResult<()> {
+        let name = dir
+            .strip_prefix(&self.root)
+            .ok()
+            .and_then(|rel| rel.to_str())
+            .filter(|rel| is_entry_name(rel))
+            .ok_or_else(|| {
+                PackError::Config(format!(
+                    "Cache directory {} is not inside {}",
+                    dir.display(),
+                    self.root.display()
+                ))
+            })?;
+
+        let _lock = CacheLock::acquire(&self.manifest_path())?;
+        let mut manifest = self.manifest()?;
+        let now = unix_seconds(SystemTime::now());
+        let created = manifest
+            .entries
+            .iter()
+            .find(|e| e.overlay_hash == overlay_hash)
+            .map_or(now, |e| e.created);
+        // The directory now belongs to this overlay only
+        manifest
+            .entries
+            .retain(|e| e.overlay_hash != overlay_hash && e.dir != name);
+        manifest.entries.push(CacheEntry {
+            overlay_hash: overlay_hash.to_string(),
+            dir: name.to_string(),
+            version: version.map(str::to_string),
+            created,
+            last_used: now,
+        });
+        self.save(&manifest)
+    }
+
+    /// Mark `dir` as used by this process until the returned lock is dropped
+    ///
+    /// Running instances hold it for their lifetime; cleaning skips
+    /// directories locked this way.
+    pub fn lock_in_use(&self, dir: &Path) -> PackResult<CacheLock> {
+        CacheLock::acquire_shared(dir)
+    }
+
+    /// Entries beyond the `keep_latest_n` most recently used
+    pub fn stale_caches(&self, keep_latest_n: usize) -> PackResult<Vec<CacheEntry>> {
+        let mut entries = self.manifest()?.entries;
+        // Most recently recorded first among equal timestamps
+        entries.reverse();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.last_used));
+        Ok(entries.into_iter().skip(keep_latest_n.max(1)).collect())
+    }
+
+    /// Remove all but the `keep_latest_n` most recently used extractions
+    ///
+    /// At least one entry (the most recent) is always kept. Directories
+    /// locked by an extraction in progress or a running instance
+    /// ([`RuntimeCache::lock_in_use`]) are skipped, and directories not in
+    /// the manifest (e.g., from versions before it) are left alone. Entries
+    /// naming anything but a direct child of the cache root are dropped
+    /// without removing anything.
+    pub fn clean_stale_caches(&self, keep_latest_n: usize) -> PackResult<CleanReport> {
+        let _lock = CacheLock::acquire(&self.manifest_path())?;
+        let mut report = CleanReport::default();
+        let mut manifest = self.manifest()?;
+
+        let invalid = manifest.entries.len();
+        manifest.entries.retain(|e| {
+            let valid = is_entry_name(&e.dir);
+            if !valid {
+                tracing::warn!("Ignoring cache manifest entry outside the cache: {}", e.dir);
+            }
+            valid
+        });
+        let invalid = invalid - manifest.entries.len();
+
+        for entry in self.stale_caches(keep_latest_n)? {
+            if !is_entry_name(&entry.dir) {
+                continue;
+            }
+            let path = self.root.join(&entry.dir);
+            let Some(_entry_lock) = CacheLock::try_acquire(&path)? else {
+                tracing::debug!("Skipping cache in use: {}", path.display());
+                continue;
+            };
+            let size = crate::clean::path_size(&path);
+            if path.exists() {
+                fs::remove_dir_all(&path).map_err(|e| {
+                    PackError::Io(std::io::Error::new(
+                        e.kind(),
+                        format!("Failed to remove {}: {}", path.display(), e),
+                    ))
+                })?;
+            }
+            tracing::info!(
+                "Removed stale runtime cache {} ({:.2} MB)",
+                path.display(),
+                size as f64 / (1024.0 * 1024.0)
+            );
+            manifest
+                .entries
+                .retain(|e| e.overlay_hash != entry.overlay_hash);
+            report.entries.push(CleanEntry {
+                scope: CleanScope::PythonRuntime,
+                path,
+                size,
+            });
+        }
+
+        if !report.entries.is_empty() || invalid > 0 {
+            self.save(&manifest)?;
+        }
+        Ok(report)
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.root.join(CACHE_MANIFEST_FILE)
+    }
+
+    fn save(&self, manifest: &CacheManifest) -> PackResult<()> {
+        let content = serde_json::to_vec_pretty(manifest)?;
+        write_atomic(&self.manifest_path(), &content)
+    }
+}
+
+/// Whether `name` names a direct child of the cache root
+fn is_entry_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none()
+}
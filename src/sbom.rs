@@ -0,0 +1,350 @@
+//! Software bill of materials for bundled third-party packages
+//!
+//! Collects name, version and license information from the `*.dist-info`
+//! metadata of installed Python packages. Used to generate third-party
+//! notices for the About screen and to enforce `[build.license_policy]`.
+
+use crate::common::LicensePolicy;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// A third-party package included in the bundle
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SbomPackage {
+    /// Package name
+    pub name: String,
+
+    /// Package version
+    pub version: String,
+
+    /// License (SPDX identifier/expression when known)
+    #[serde(default)]
+    pub license: Option<String>,
+
+    /// Full license text shipped with the package
+    #[serde(skip)]
+    pub license_text: Option<String>,
+}
+
+/// Bill of materials for a bundle
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Sbom {
+    /// Packages sorted by name
+    pub packages: Vec<SbomPackage>,
+}
+
+impl Sbom {
+    /// Collect package metadata from a `site-packages` directory
+    pub fn from_site_packages(dir: &Path) -> PackResult<Self> {
+        let mut packages = Vec::new();
+
+        if dir.is_dir() {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                let is_dist_info = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.ends_with(".dist-info"));
+                if !is_dist_info || !path.is_dir() {
+                    continue;
+                }
+
+                let metadata = match fs::read_to_string(path.join("METADATA")) {
+                    Ok(metadata) => metadata,
+                    Err(_) => continue,
+                };
+                if let Some(mut package) = parse_metadata(&metadata) {
+                    package.license_text = read_license_text(&path);
+                    packages.push(package);
+                }
+            }
+        }
+
+        packages.sort_by_key(|p| p.name.to_lowercase());
+        tracing::debug!("Collected metadata for {} packages", packages.len());
+        Ok(Self { packages })
+    }
+
+    /// Check if there are no packages
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Check all packages against a license policy
+    ///
+    /// Reports every violating package at once.
+    pub fn check_license_policy(&self, policy: &LicensePolicy) -> PackResult<()> {
+        let mut violations = Vec::new();
+
+        for package in &self.packages {
+            if policy
+                .ignore
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&package.name))
+            {
+                continue;
+            }
+            match package.license {
+                Some(ref license) if !policy.permits(license) => {
+                    violations.push(format!(
+                        "{} {} ({})",
+                        package.name, package.version, license
+                    ));
+                }
+                Some(_) => {}
+                None if policy.deny_unknown => {
+                    violations.push(format!(
+                        "{} {} (unknown license)",
+                        package.name, package.version
+                    ));
+                }
+                None => {
+                    tracing::warn!(
+                        "Package {} {} has no license metadata",
+                        package.name,
+                        package.version
+                    );
+                }
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PackError::LicensePolicy(violations.join(", ")))
+        }
+    }
+
+    /// Render a plain-text third-party notices document
+    pub fn third_party_notices(&self) -> String {
+        let mut out = String::from("THIRD-PARTY SOFTWARE NOTICES\n");
+
+        for package in &self.packages {
+            out.push_str(&format!(
+                "\n{}\n{} {} ({})\n{}\n",
+                "=".repeat(72),
+                package.name,
+                package.version,
+                package.license.as_deref().unwrap_or("license unknown"),
+                "=".repeat(72)
+            ));
+            if let Some(ref text) = package.license_text {
+                out.push('\n');
+                out.push_str(text.trim_end());
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+}
+
+/// Parse the header section of a core metadata (`METADATA`) file
+fn parse_metadata(content: &str) -> Option<SbomPackage> {
+    let mut name = None;
+    let mut version = None;
+    let mut license_expression = None;
+    let mut license = None;
+    let mut classifier_license = None;
+
+    for line in content.lines() {
+        // Headers end at the first blank line (the description follows)
+        if line.trim().is_empty() {
+            break;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "Name" => name = Some(value.to_string()),
+            "Version" => version = Some(value.to_string()),
+            "License-Expression" => license_expression = Some(value.to_string()),
+            // Long license texts are sometimes pasted into this field
+            "License" if !value.is_empty() && value.len() <= 64 => {
+                license = Some(value.to_string())
+            }
+            "Classifier" if classifier_license.is_none() => {
+                classifier_license = value
+                    .strip_prefix("License :: ")
+                    .and_then(|c| c.rsplit(" :: ").next())
+                    .and_then(classifier_to_spdx);
+            }
+            _ => {}
+        }
+    }
+
+    Some(SbomPackage {
+        name: name?,
+        version: version.unwrap_or_default(),
+        license: license_expression
+            .or_else(|| {
+                license
+                    .filter(|l| !is_ambiguous_license(l))
+                    .map(|l| classifier_to_spdx(&l).unwrap_or(l))
+            })
+            .or(classifier_license),
+        license_text: None,
+    })
+}
+
+/// Read the first license file shipped in a `.dist-info` directory
+fn read_license_text(dist_info: &Path) -> Option<String> {
+    let candidates = [dist_info.to_path_buf(), dist_info.join("licenses")];
+    for dir in candidates {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut files: Vec<_> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_file()
+                    && p.file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|n| n.to_ascii_uppercase())
+                        .is_some_and(|n| n.starts_with("LICENSE") || n.starts_with("COPYING"))
+            })
+            .collect();
+        files.sort();
+        if let Some(text) = files.first().and_then(|f| fs::read_to_string(f).ok()) {
+            return Some(text);
+        }
+    }
+    None
+}
+
+/// Whether a license name does not identify one license
+///
+/// Bare "BSD" covers the 2-, 3- and 4-clause variants, so such packages are
+/// reported with an unknown license rather than a guessed one.
+fn is_ambiguous_license(name: &str) -> bool {
+    matches!(name.trim(), "BSD" | "BSD License")
+}
+
+/// Map common license names and trove classifiers to SPDX identifiers
+fn classifier_to_spdx(name: &str) -> Option<String> {
+    let spdx = match name.trim() {
+        "MIT" | "MIT License" => "MIT",
+        "Apache 2.0" | "Apache-2.0" | "Apache License 2.0" | "Apache Software License" => {
+            "Apache-2.0"
+        }
+        "BSD-3-Clause" | "BSD 3-Clause" => "BSD-3-Clause",
+        "BSD-2-Clause" | "BSD 2-Clause" => "BSD-2-Clause",
+        "ISC" | "ISC License (ISCL)" => "ISC",
+        "Python Software Foundation License" | "PSF" | "PSF-2.0" => "PSF-2.0",
+        "Mozilla Public License 2.0 (MPL 2.0)" | "MPL-2.0" | "MPL 2.0" => "MPL-2.0",
+        "GNU General Public License v2 (GPLv2)" | "GPLv2" => "GPL-2.0-only",
+        "GNU General Public License v2 or later (GPLv2+)" | "GPLv2+" => "GPL-2.0-or-later",
+        "GNU General Public License v3 (GPLv3)" | "GPLv3" => "GPL-3.0-only",
+        "GNU General Public License v3 or later (GPLv3+)" | "GPLv3+" => "GPL-3.0-or-later",
+        "GNU Lesser General Public License v2 or later (LGPLv2+)" => "LGPL-2.0-or-later",
+        "GNU Lesser General Public License v3 (LGPLv3)" | "LGPLv3" => "LGPL-3.0-only",
+        "GNU Affero General Public License v3" | "AGPLv3" => "AGPL-3.0-only",
+        "The Unlicense (Unlicense)" | "Unlicense" => "Unlicense",
+        _ => return None,
+    };
+    Some(spdx.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata_license_sources() {
+        let expr = "Metadata-Version: 2.4\nName: foo\nVersion: 1.0\nLicense-Expression: MIT OR Apache-2.0\n\nBody";
+        assert_eq!(
+            parse_metadata(expr).unwrap().license.as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+
+        let classifier = "Name: bar\nVersion: 2.0\nClassifier: License :: OSI Approved :: GNU General Public License v3 (GPLv3)\n";
+        let package = parse_metadata(classifier).unwrap();
+        assert_eq!(package.name, "bar");
+        assert_eq!(package.license.as_deref(), Some("GPL-3.0-only"));
+
+        let plain = "Name: baz\nVersion: 0.1\nLicense: BSD 3-Clause\n";
+        assert_eq!(
+            parse_metadata(plain).unwrap().license.as_deref(),
+            Some("BSD-3-Clause")
+        );
+
+        // Bare BSD does not say which clauses apply
+        let bsd = "Name: qux\nVersion: 0.1\nLicense: BSD\nClassifier: License :: OSI Approved :: BSD License\n";
+        assert_eq!(parse_metadata(bsd).unwrap().license, None);
+        let bsd = "Name: qux\nVersion: 0.1\nLicense: BSD\nClassifier: License :: OSI Approved :: MIT License\n";
+        assert_eq!(parse_metadata(bsd).unwrap().license.as_deref(), Some("MIT"));
+
+        assert!(parse_metadata("Version: 1.0\n").is_none());
+    }
+
+    #[test]
+    fn test_from_site_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let dist_info = dir.path().join("requests-2.32.0.dist-info");
+        fs::create_dir_all(dist_info.join("licenses")).unwrap();
+        fs::write(
+            dist_info.join("METADATA"),
+            "Name: requests\nVersion: 2.32.0\nLicense: Apache 2.0\n",
+        )
+        .unwrap();
+        fs::write(dist_info.join("licenses").join("LICENSE"), "Apache text").unwrap();
+        fs::create_dir_all(dir.path().join("requests")).unwrap();
+
+        let sbom = Sbom::from_site_packages(dir.path()).unwrap();
+        assert_eq!(sbom.packages.len(), 1);
+        assert_eq!(sbom.packages[0].license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(
+            sbom.packages[0].license_text.as_deref(),
+            Some("Apache text")
+        );
+        assert!(sbom
+            .third_party_notices()
+            .contains("requests 2.32.0 (Apache-2.0)"));
+    }
+
+    #[test]
+    fn test_check_license_policy() {
+        let package = |name: &str, license: Option<&str>| SbomPackage {
+            name: name.to_string(),
+            version: "1.0".to_string(),
+            license: license.map(|l| l.to_string()),
+            license_text: None,
+        };
+        let sbom = Sbom {
+            packages: vec![
+                package("ok", Some("MIT")),
+                package("dual", Some("GPL-3.0-only OR MIT")),
+                package("copyleft", Some("GPL-3.0-or-later")),
+                package("bare", Some("GPL")),
+                package("lesser", Some("LGPL-3.0-only")),
+                package("mystery", None),
+            ],
+        };
+
+        let policy = LicensePolicy {
+            denied: vec!["GPL-*".to_string()],
+            ..Default::default()
+        };
+        let err = sbom.check_license_policy(&policy).unwrap_err().to_string();
+        assert!(err.contains("copyleft"));
+        // Unnormalized names fail closed
+        assert!(err.contains("bare 1.0 (GPL)"));
+        assert!(!err.contains("lesser"));
+        assert!(!err.contains("dual"));
+        assert!(!err.contains("mystery"));
+
+        let policy = LicensePolicy {
+            denied: vec!["GPL-*".to_string()],
+            deny_unknown: true,
+            ignore: vec!["copyleft".to_string()],
+            ..Default::default()
+        };
+        let err = sbom.check_license_policy(&policy).unwrap_err().to_string();
+        assert!(err.contains("mystery"));
+        assert!(!err.contains("copyleft"));
+    }
+}
@@ -0,0 +1,218 @@
+//! Cron-like schedule specs for periodic reload/restart policies
+//!
+//! Supports the standard five-field syntax:
+//!
+//! ```text
+//! ┌──────── minute (0-59)
+//! │ ┌────── hour (0-23)
+//! │ │ ┌──── day of month (1-31)
+//! │ │ │ ┌── month (1-12)
+//! │ │ │ │ ┌ day of week (0-7, 0 and 7 are Sunday)
+//! * * * * *
+//! ```
+//!
+//! Each field accepts `*`, numbers, ranges (`1-5`), lists (`1,15,30`) and
+//! steps (`*/15`, `0-30/10`); month and weekday fields also accept names
+//! (`JAN`, `MON-FRI`). As in standard cron, when both day fields are
+//! restricted (neither starts with `*`), a time matching either fires:
+//! `0 0 1 * MON` runs on the 1st and on every Monday.
+
+use crate::{PackError, PackResult};
+
+/// Month names, from 1
+const MONTHS: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Weekday names, from 0
+const WEEKDAYS: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A parsed cron-like schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSpec {
+    minutes: Vec<u8>,
+    hours: Vec<u8>,
+    days_of_month: Vec<u8>,
+    months: Vec<u8>,
+    days_of_week: Vec<u8>,
+    /// Whether either day field starts with `*` (the day fields then both
+    /// have to match, otherwise either does)
+    day_wildcard: bool,
+}
+
+impl CronSpec {
+    /// Parse a five-field cron expression
+    pub fn parse(spec: &str) -> PackResult<Self> {
+        let fields: Vec<&str> = spec.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(PackError::Config(format!(
+                "Invalid schedule '{}': expected 5 fields (minute hour day month weekday), got {}",
+                spec,
+                fields.len()
+            )));
+        }
+
+        let parse = |field: &str, name: &str, min: u8, max: u8, names: &[&str]| {
+            parse_field(field, min, max, names).map_err(|e| {
+                PackError::Config(format!("Invalid schedule '{}': {} field {}", spec, name, e))
+            })
+        };
+
+        let mut days_of_week = parse(fields[4], "weekday", 0, 7, WEEKDAYS)?;
+        // 7 is an alias for Sunday
+        if days_of_week.contains(&7) {
+            days_of_week.retain(|d| *d != 7);
+            if !days_of_week.contains(&0) {
+                days_of_week.insert(0, 0);
+            }
+        }
+
+        Ok(Self {
+            minutes: parse(fields[0], "minute", 0, 59, &[])?,
+            hours: parse(fields[1], "hour", 0, 23, &[])?,
+            days_of_month: parse(fields[2], "day", 1, 31, &[])?,
+            months: parse(fields[3], "month", 1, 12, MONTHS)?,
+            days_of_week,
+            day_wildcard: fields[2].starts_with('*') || fields[4].starts_with('*'),
+        })
+    }
+
+    /// Check whether the schedule fires at the given time
+    ///
+    /// `day_of_week` is 0-6 with 0 = Sunday.
+    pub fn matches(&self, minute: u8, hour: u8, day: u8, month: u8, day_of_week: u8) -> bool {
+        let day_of_month = self.days_of_month.contains(&day);
+        let weekday = self.days_of_week.contains(&(day_of_week % 7));
+        let day_matches = match self.day_wildcard {
+            true => day_of_month && weekday,
+            false => day_of_month || weekday,
+        };
+        self.minutes.contains(&minute)
+            && self.hours.contains(&hour)
+            && self.months.contains(&month)
+            && day_matches
+    }
+}
+
+/// Parse a single cron field into a sorted list of values (`names[i]`
+/// stands for `min + i`)
+fn parse_field(field: &str, min: u8, max: u8, names: &[&str]) -> Result<Vec<u8>, String> {
+    let mut values = Vec::new();
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u8 = step
+                    .parse()
+                    .map_err(|_| format!("has invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("has a zero step".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (
+                parse_value(a, min, max, names)?,
+                parse_value(b, min, max, names)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, names)?;
+            // "5/10" means "starting at 5, every 10"
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+
+        if start > end {
+            return Err(format!("has reversed range '{}'", range));
+        }
+
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+/// Parse a single numeric value or name within bounds
+fn parse_value(value: &str, min: u8, max: u8, names: &[&str]) -> Result<u8, String> {
+    if let Some(i) = names.iter().position(|n| n.eq_ignore_ascii_case(value)) {
+        return Ok(min + i as u8);
+    }
+    let parsed: u8 = value
+        .parse()
+        .map_err(|_| format!("has invalid value '{}'", value))?;
+    if parsed < min || parsed > max {
+        return Err(format!(
+            "value {} is out of range ({}-{})",
+            parsed, min, max
+        ));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_every_minute() {
+        let spec = CronSpec::parse("* * * * *").unwrap();
+        assert!(spec.matches(0, 0, 1, 1, 0));
+        assert!(spec.matches(59, 23, 31, 12, 6));
+    }
+
+    #[test]
+    fn test_parse_steps_ranges_lists() {
+        let spec = CronSpec::parse("*/15 1-3 1,15 * *").unwrap();
+        assert!(spec.matches(30, 2, 15, 6, 3));
+        assert!(!spec.matches(31, 2, 15, 6, 3));
+        assert!(!spec.matches(30, 4, 15, 6, 3));
+        assert!(!spec.matches(30, 2, 16, 6, 3));
+
+        let spec = CronSpec::parse("0 * */2 * 1-5").unwrap();
+        assert!(spec.matches(0, 8, 3, 6, 3));
+        assert!(!spec.matches(0, 8, 3, 6, 0));
+        assert!(!spec.matches(0, 8, 4, 6, 3));
+    }
+
+    #[test]
+    fn test_restricted_day_fields_match_either() {
+        let spec = CronSpec::parse("0 0 1 * MON").unwrap();
+        // The 1st (a Wednesday), and a Monday that is not the 1st
+        assert!(spec.matches(0, 0, 1, 5, 3));
+        assert!(spec.matches(0, 0, 13, 5, 1));
+        assert!(!spec.matches(0, 0, 14, 5, 2));
+
+        let spec = CronSpec::parse("0 0 * JAN-mar sun").unwrap();
+        assert!(spec.matches(0, 0, 7, 3, 0));
+        assert!(!spec.matches(0, 0, 8, 3, 1));
+        assert!(!spec.matches(0, 0, 7, 4, 0));
+    }
+
+    #[test]
+    fn test_sunday_alias() {
+        let spec = CronSpec::parse("0 4 * * 7").unwrap();
+        assert!(spec.matches(0, 4, 10, 3, 0));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CronSpec::parse("* * * *").is_err());
+        assert!(CronSpec::parse("60 * * * *").is_err());
+        assert!(CronSpec::parse("* 24 * * *").is_err());
+        assert!(CronSpec::parse("* * 0 * *").is_err());
+        assert!(CronSpec::parse("*/0 * * * *").is_err());
+        assert!(CronSpec::parse("5-1 * * * *").is_err());
+        assert!(CronSpec::parse("a * * * *").is_err());
+        assert!(CronSpec::parse("* * * * MONDAY").is_err());
+    }
+}
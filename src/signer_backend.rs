@@ -0,0 +1,625 @@
+//! External signing backends (HSM / cloud KMS)
+//!
+//! [`SignerBackend`] is what the overlay signer, the update feed and
+//! Windows Authenticode signing call into. Besides local keys
+//! ([`KeyPair`]), `[signing.external]` signs with keys that never leave an
+//! HSM or a cloud KMS, by running the provider's CLI:
+//!
+//! ```toml
+//! [signing.external]
+//! provider = "aws-kms"               # aws-kms, azure-key-vault, pkcs11, command
+//! key = "arn:aws:kms:eu-west-1:123456789012:key/2f1c..."
+//! # Authenticode-sign the Windows executable (requires [bundle] overlay = "sidecar")
+//! authenticode_command = ["AzureSignTool", "sign", "-kvu", "https://vault.vault.azure.net",
+//!                         "-kvc", "release", "-kvm", "-fd", "sha256", "{file}"]
+//! ```
+//!
+//! | Provider          | Signs with                                       |
+//! |-------------------|--------------------------------------------------|
+//! | `aws-kms`         | `aws kms sign` (RSASSA_PKCS1_V1_5_SHA_256)       |
+//! | `azure-key-vault` | `az keyvault key sign` (RS256)                   |
+//! | `pkcs11`          | `pkcs11-tool --sign` (SHA256-RSA-PKCS), `module` |
+//! | `command`         | `sign_command`, any program                      |
+//!
+//! Commands are argument lists with placeholders: `{key}`, `{module}`,
+//! `{pin_env}` (name of the variable holding the PIN), `{input}` (file
+//! with the message, or its SHA-256 digest for KMS providers),
+//! `{digest}`/`{digest_base64}`, `{output}` (file to write the result to;
+//! otherwise it is read from stdout as base64 or `{"signature": ...}` JSON)
+//! and `{file}` (the executable, for `authenticode_command`). The PIN
+//! itself never goes on a command line, where other users can read it: it
+//! is fed to the command on stdin, and the `pkcs11` provider passes
+//! `--pin env:{pin_env}`. Every signature is checked against the public key
+//! before it is used.
+
+use crate::keys::{decode_pem, verify_signature, KeyAlgorithm, KeyPair};
+use crate::{PackError, PackResult};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Signs messages (and optionally executables) with a key held elsewhere
+pub trait SignerBackend: Send + Sync + fmt::Debug {
+    /// Short description for logs (e.g. "aws-kms arn:...")
+    fn describe(&self) -> String;
+
+    /// Signature algorithm
+    fn algorithm(&self) -> PackResult<KeyAlgorithm>;
+
+    /// Public key (raw for Ed25519, DER `RSAPublicKey` for RSA)
+    fn public_key(&self) -> PackResult<Vec<u8>>;
+
+    /// Sign a message
+    fn sign(&self, message: &[u8]) -> PackResult<Vec<u8>>;
+
+    /// Whether [`Self::sign_executable`] is available
+    fn signs_executables(&self) -> bool {
+        false
+    }
+
+    /// Authenticode-sign a Windows executable in place
+    fn sign_executable(&self, file: &Path) -> PackResult<()> {
+        Err(PackError::Signature(format!(
+            "{} cannot sign executables ({})",
+            self.describe(),
+            file.display()
+        )))
+    }
+}
+
+impl SignerBackend for KeyPair {
+    fn describe(&self) -> String {
+        format!("local {} key", self.algorithm().as_str())
+    }
+
+    fn algorithm(&self) -> PackResult<KeyAlgorithm> {
+        Ok(KeyPair::algorithm(self))
+    }
+
+    fn public_key(&self) -> PackResult<Vec<u8>> {
+        STANDARD
+            .decode(self.public_key_base64())
+            .map_err(|e| PackError::Signature(e.to_string()))
+    }
+
+    fn sign(&self, message: &[u8]) -> PackResult<Vec<u8>> {
+        KeyPair::sign(self, message)
+    }
+}
+
+/// External signing provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExternalProvider {
+    /// AWS KMS through the `aws` CLI
+    AwsKms,
+    /// Azure Key Vault through the `az` CLI
+    AzureKeyVault,
+    /// PKCS#11 token (HSM, smart card) through OpenSC's `pkcs11-tool`
+    Pkcs11,
+    /// Custom `sign_command`
+    #[default]
+    Command,
+}
+
+impl ExternalProvider {
+    /// Name used in `[signing.external] provider`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AwsKms => "aws-kms",
+            Self::AzureKeyVault => "azure-key-vault",
+            Self::Pkcs11 => "pkcs11",
+            Self::Command => "command",
+        }
+    }
+
+    /// Whether `{input}` holds the SHA-256 digest instead of the message
+    fn signs_digest(&self) -> bool {
+        matches!(self, Self::AwsKms | Self::AzureKeyVault)
+    }
+
+    fn sign_command(&self) -> Vec<String> {
+        let args: &[&str] = match self {
+            Self::AwsKms => &[
+                "aws",
+                "kms",
+                "sign",
+                "--key-id",
+                "{key}",
+                "--message",
+                "fileb://{input}",
+                "--message-type",
+                "DIGEST",
+                "--signing-algorithm",
+                "RSASSA_PKCS1_V1_5_SHA_256",
+                "--query",
+                "Signature",
+                "--output",
+                "text",
+            ],
+            Self::AzureKeyVault => &[
+                "az",
+                "keyvault",
+                "key",
+                "sign",
+                "--id",
+                "{key}",
+                "--algorithm",
+                "RS256",
+                "--digest",
+                "{digest_base64}",
+                "--query",
+                "signature",
+                "--output",
+                "tsv",
+            ],
+            Self::Pkcs11 => &[
+                "pkcs11-tool",
+                "--module",
+                "{module}",
+                "--login",
+                "--pin",
+                "env:{pin_env}",
+                "--sign",
+                "--mechanism",
+                "SHA256-RSA-PKCS",
+                "--id",
+                "{key}",
+                "--input-file",
+                "{input}",
+                "--output-file",
+                "{output}",
+            ],
+            Self::Command => &[],
+        };
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn public_key_command(&self) -> Vec<String> {
+        let args: &[&str] = match self {
+            Self::AwsKms => &[
+                "aws",
+                "kms",
+                "get-public-key",
+                "--key-id",
+                "{key}",
+                "--query",
+                "PublicKey",
+                "--output",
+                "text",
+            ],
+            Self::AzureKeyVault => &[
+                "az",
+                "keyvault",
+                "key",
+                "download",
+                "--id",
+                "{key}",
+                "--encoding",
+                "DER",
+                "--file",
+                "{output}",
+            ],
+            Self::Pkcs11 => &[
+                "pkcs11-tool",
+                "--module",
+                "{module}",
+                "--read-object",
+                "--type",
+                "pubkey",
+                "--id",
+                "{key}",
+                "--output-file",
+                "{output}",
+            ],
+            Self::Command => &[],
+        };
+        args.iter().map(|a| a.to_string()).collect()
+    }
+}
+
+/// External signer configuration
+///
+/// Located at `[signing.external]` in TOML. Pack-time only.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExternalSignerConfig {
+    /// Provider
+    pub provider: ExternalProvider,
+
+    /// Key id, ARN or URL (`{key}`)
+    pub key: Option<String>,
+
+    /// PKCS#11 module (`{module}`)
+    pub module: Option<PathBuf>,
+
+    /// Environment variable holding the token PIN (`{pin_env}`; the PIN is
+    /// also fed to commands on stdin)
+    pub pin_env: Option<String>,
+
+    /// Public key (base64 or PEM: SubjectPublicKeyInfo, raw Ed25519 or
+    /// `RSAPublicKey`); fetched with `public_key_command` if not set
+    pub public_key: Option<String>,
+
+    /// Command printing or writing the signature (overrides the provider's)
+    pub sign_command: Vec<String>,
+
+    /// Command printing or writing the public key (overrides the provider's)
+    pub public_key_command: Vec<String>,
+
+    /// Command Authenticode-signing `{file}` in place
+    pub authenticode_command: Vec<String>,
+}
+
+impl ExternalSignerConfig {
+    /// Sign with a provider's key
+    pub fn new(provider: ExternalProvider, key: impl Into<String>) -> Self {
+        Self {
+            provider,
+            key: Some(key.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sign by running `command`
+    pub fn command(command: Vec<String>, public_key: impl Into<String>) -> Self {
+        Self {
+            provider: ExternalProvider::Command,
+            sign_command: command,
+            public_key: Some(public_key.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        let provider = self.provider.as_str();
+        match self.provider {
+            ExternalProvider::Command => {
+                if self.sign_command.is_empty() {
+                    return Err(PackError::Config(
+                        "[signing.external] provider \"command\" requires sign_command".to_string(),
+                    ));
+                }
+                if self.public_key.is_none() && self.public_key_command.is_empty() {
+                    return Err(PackError::Config(
+                        "[signing.external] provider \"command\" requires public_key or public_key_command"
+                            .to_string(),
+                    ));
+                }
+            }
+            _ if self.key.is_none() => {
+                return Err(PackError::Config(format!(
+                    "[signing.external] provider \"{}\" requires key",
+                    provider
+                )));
+            }
+            ExternalProvider::Pkcs11 if self.module.is_none() => {
+                return Err(PackError::Config(
+                    "[signing.external] provider \"pkcs11\" requires module".to_string(),
+                ));
+            }
+            ExternalProvider::Pkcs11 if self.pin_env.is_none() && self.sign_command.is_empty() => {
+                return Err(PackError::Config(
+                    "[signing.external] provider \"pkcs11\" requires pin_env".to_string(),
+                ));
+            }
+            _ => {}
+        }
+        let templates = [
+            &self.sign_command,
+            &self.public_key_command,
+            &self.authenticode_command,
+        ];
+        if templates
+            .iter()
+            .any(|t| t.iter().any(|arg| arg.contains("{pin}")))
+        {
+            return Err(PackError::Config(
+                "[signing.external] {pin} would expose the PIN in the process list; \
+                 use env:{pin_env} or read it from stdin"
+                    .to_string(),
+            ));
+        }
+        if let Some(ref public_key) = self.public_key {
+            parse_public_key(public_key.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Signer running a provider CLI, see [`ExternalSignerConfig`]
+#[derive(Debug)]
+pub struct ExternalSigner {
+    config: ExternalSignerConfig,
+    public_key: OnceLock<(KeyAlgorithm, Vec<u8>)>,
+}
+
+impl ExternalSigner {
+    /// Signer of a `[signing.external]` configuration
+    pub fn new(config: ExternalSignerConfig) -> PackResult<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            public_key: OnceLock::new(),
+        })
+    }
+
+    fn key_info(&self) -> PackResult<&(KeyAlgorithm, Vec<u8>)> {
+        if let Some(info) = self.public_key.get() {
+            return Ok(info);
+        }
+        let info = match self.config.public_key {
+            Some(ref public_key) => parse_public_key(public_key.as_bytes())?,
+            None => {
+                let command = match self.config.public_key_command.is_empty() {
+                    true => self.config.provider.public_key_command(),
+                    false => self.config.public_key_command.clone(),
+                };
+                match self.run(&command, None)? {
+                    CommandOutput::File(der) => parse_der_public_key(der),
+                    CommandOutput::Stdout(text) => parse_public_key(&text)?,
+                }
+            }
+        };
+        Ok(self.public_key.get_or_init(|| info))
+    }
+
+    /// Run a command template
+    fn run(&self, template: &[String], message: Option<&[u8]>) -> PackResult<CommandOutput> {
+        let dir = tempfile::tempdir()?;
+        let output = dir.path().join("output");
+        let mut values: HashMap<&str, String> = HashMap::new();
+        values.insert("output", output.to_string_lossy().into_owned());
+        if let Some(ref key) = self.config.key {
+            values.insert("key", key.clone());
+        }
+        if let Some(ref module) = self.config.module {
+            values.insert("module", module.to_string_lossy().into_owned());
+        }
+        let mut pin = None;
+        if let Some(ref pin_env) = self.config.pin_env {
+            pin = Some(std::env::var(pin_env).map_err(|_| {
+                PackError::Config(format!("[signing.external] {} is not set", pin_env))
+            })?);
+            values.insert("pin_env", pin_env.clone());
+        }
+        if let Some(message) = message {
+            let digest = Sha256::digest(message);
+            let input = dir.path().join("input");
+            match self.config.provider.signs_digest() {
+                true => std::fs::write(&input, digest)?,
+                false => std::fs::write(&input, message)?,
+            }
+            values.insert("input", input.to_string_lossy().into_owned());
+            values.insert("digest", format!("{:x}", digest));
+            values.insert("digest_base64", STANDARD.encode(digest));
+        }
+
+        let args = expand(template, &values)?;
+        let stdout = run_command(&args, pin.as_deref())?;
+        match output.is_file() {
+            true => Ok(CommandOutput::File(std::fs::read(&output)?)),
+            false => Ok(CommandOutput::Stdout(stdout)),
+        }
+    }
+}
+
+impl SignerBackend for ExternalSigner {
+    fn describe(&self) -> String {
+        match self.config.key {
+            Some(ref key) => format!("{} {}", self.config.provider.as_str(), key),
+            None => self.config.provider.as_str().to_string(),
+        }
+    }
+
+    fn algorithm(&self) -> PackResult<KeyAlgorithm> {
+        Ok(self.key_info()?.0)
+    }
+
+    fn public_key(&self) -> PackResult<Vec<u8>> {
+        Ok(self.key_info()?.1.clone())
+    }
+
+    fn sign(&self, message: &[u8]) -> PackResult<Vec<u8>> {
+        let (algorithm, ref public_key) = *self.key_info()?;
+        let command = match self.config.sign_command.is_empty() {
+            true => self.config.provider.sign_command(),
+            false => self.config.sign_command.clone(),
+        };
+        let signature = match self.run(&command, Some(message))? {
+            CommandOutput::File(signature) => signature,
+            CommandOutput::Stdout(text) => decode_output(&text, "signature")?,
+        };
+        verify_signature(algorithm, public_key, message, &signature).map_err(|_| {
+            PackError::Signature(format!(
+                "{} returned a signature that does not match its public key",
+                self.describe()
+            ))
+        })?;
+        Ok(signature)
+    }
+
+    fn signs_executables(&self) -> bool {
+        !self.config.authenticode_command.is_empty()
+    }
+
+    fn sign_executable(&self, file: &Path) -> PackResult<()> {
+        if !self.signs_executables() {
+            return Err(PackError::Signature(format!(
+                "[signing.external] authenticode_command is not set; cannot sign {}",
+                file.display()
+            )));
+        }
+        let mut values = HashMap::new();
+        values.insert("file", file.to_string_lossy().into_owned());
+        if let Some(ref key) = self.config.key {
+            values.insert("key", key.clone());
+        }
+        if let Some(ref module) = self.config.module {
+            values.insert("module", module.to_string_lossy().into_owned());
+        }
+        run_command(&expand(&self.config.authenticode_command, &values)?, None)?;
+        tracing::info!("Authenticode-signed {}", file.display());
+        Ok(())
+    }
+}
+
+/// Result of a provider command
+enum CommandOutput {
+    /// Raw bytes written to `{output}`
+    File(Vec<u8>),
+    /// Text printed to stdout
+    Stdout(Vec<u8>),
+}
+
+/// Fill in the placeholders of a command template
+///
+/// Substitutes in one pass over each argument, so values are never
+/// scanned for placeholders themselves.
+fn expand(template: &[String], values: &HashMap<&str, String>) -> PackResult<Vec<String>> {
+    template
+        .iter()
+        .map(|arg| {
+            let mut expanded = String::with_capacity(arg.len());
+            let mut rest = arg.as_str();
+            while let Some(start) = rest.find('{') {
+                let Some(len) = rest[start..].find('}') else {
+                    break;
+                };
+                let name = &rest[start + 1..start + len];
+                let value = values.get(name).ok_or_else(|| {
+                    PackError::Config(format!(
+                        "[signing.external] placeholder {{{}}} is not available here",
+                        name
+                    ))
+                })?;
+                expanded.push_str(&rest[..start]);
+                expanded.push_str(value);
+                rest = &rest[start + len + 1..];
+            }
+            expanded.push_str(rest);
+            Ok(expanded)
+        })
+        .collect()
+}
+
+/// Run a provider command, feeding `pin` (if any) on stdin
+fn run_command(args: &[String], pin: Option<&str>) -> PackResult<Vec<u8>> {
+    let (program, args) = args
+        .split_first()
+        .ok_or_else(|| PackError::Config("[signing.external] empty command".to_string()))?;
+    let spawn_error =
+        |e: std::io::Error| PackError::Signature(format!("Failed to run {}: {}", program, e));
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(match pin {
+            Some(_) => Stdio::piped(),
+            None => Stdio::null(),
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+    if let (Some(pin), Some(mut stdin)) = (pin, child.stdin.take()) {
+        // Commands reading the PIN elsewhere may exit without reading it
+        match stdin.write_all(format!("{}\n", pin).as_bytes()) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(spawn_error(e)),
+            _ => {}
+        }
+    }
+    let output = child.wait_with_output().map_err(spawn_error)?;
+    if !output.status.success() {
+        return Err(PackError::Signature(format!(
+            "{} failed ({}): {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Bytes of a printed command result: base64 or base64url text, or JSON
+/// with a `field` member
+fn decode_output(output: &[u8], field: &str) -> PackResult<Vec<u8>> {
+    let text = String::from_utf8_lossy(output);
+    let text = text.trim();
+    let text = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(serde_json::Value::Object(object)) => object
+            .get(field)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| PackError::Signature(format!("no \"{}\" in the output", field)))?,
+        _ => text.to_string(),
+    };
+    STANDARD
+        .decode(&text)
+        .or_else(|_| URL_SAFE_NO_PAD.decode(text.trim_end_matches('=')))
+        .map_err(|_| PackError::Signature(format!("unreadable {} output", field)))
+}
+
+/// Algorithm and key of a PEM or base64 public key
+fn parse_public_key(text: &[u8]) -> PackResult<(KeyAlgorithm, Vec<u8>)> {
+    let der = match std::str::from_utf8(text) {
+        Ok(text) if text.contains("-----BEGIN") => decode_pem(text)?,
+        _ => decode_output(text, "public_key")?,
+    };
+    Ok(parse_der_public_key(der))
+}
+
+/// Algorithm and key of a DER SubjectPublicKeyInfo, raw Ed25519 key or
+/// `RSAPublicKey`
+fn parse_der_public_key(der: Vec<u8>) -> (KeyAlgorithm, Vec<u8>) {
+    match parse_spki(&der) {
+        Some(parsed) => parsed,
+        None => (KeyAlgorithm::of_public_key(&der), der),
+    }
+}
+
+/// Key of a DER SubjectPublicKeyInfo (RSA or Ed25519)
+fn parse_spki(der: &[u8]) -> Option<(KeyAlgorithm, Vec<u8>)> {
+    const RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+    const ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+
+    let (spki, _) = der_element(der, 0x30)?;
+    let (algorithm, rest) = der_element(spki, 0x30)?;
+    let (oid, _) = der_element(algorithm, 0x06)?;
+    let (bits, _) = der_element(rest, 0x03)?;
+    let key = bits.strip_prefix(&[0])?.to_vec();
+    match oid {
+        RSA_ENCRYPTION => Some((KeyAlgorithm::Rsa, key)),
+        ED25519 => Some((KeyAlgorithm::Ed25519, key)),
+        _ => None,
+    }
+}
+
+/// Content of the DER element with `tag` at the start of `data`, and the
+/// bytes after it
+fn der_element(data: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (&first, rest) = data.split_first()?;
+    if first != tag {
+        return None;
+    }
+    let (&len, rest) = rest.split_first()?;
+    let (len, rest) = if len < 0x80 {
+        (len as usize, rest)
+    } else {
+        let count = (len & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, &rest[count..])
+    };
+    (rest.len() >= len).then(|| rest.split_at(len))
+}
@@ -0,0 +1,404 @@
+//! Ed25519 signing of overlays
+//!
+//! With `[signing]` configured, the packer signs the overlay metadata
+//! (configuration and content hash) together with the asset index, whose
+//! per-asset BLAKE3 hashes bind every asset. The signature and the signer's
+//! public key are stored in the overlay metadata:
+//!
+//! ```toml
+//! [signing]
+//! key = "./keys/overlay.pk8"            # PKCS#8 key (DER or PEM)
+//! # key_env = "AURORAVIEW_SIGNING_KEY"  # or the key itself in an env var (CI)
+//! # key_id = "release"                  # or a key from the keystore
+//! ```
+//!
+//! Keys held in an HSM or cloud KMS sign through `[signing.external]`
+//! instead, see [`SignerBackend`](crate::SignerBackend).
+//!
+//! Ed25519 and RSA keys are accepted; see [`KeyStore`](crate::KeyStore) for
+//! generating keys and keeping them encrypted. The same key signs the
+//! update feed and license tokens.
+//!
+//! Readers verify signed overlays against the key they carry, which catches
+//! corruption and naive edits. To refuse overlays from anyone else, build the
+//! shell with the trusted public key pinned:
+//!
+//! ```bash
+//! AURORAVIEW_OVERLAY_PUBLIC_KEY=<base64 public key> cargo build --release
+//! ```
+//!
+//! A pinned shell rejects unsigned overlays and overlays signed by other
+//! keys in [`OverlayReader::read`](crate::OverlayReader::read), and
+//! [`is_packed`](crate::is_packed) reports `false` for them.
+
+use crate::keys::{verify_signature, KeyAlgorithm, KeyPair, KeyStore, KEY_PASSPHRASE_ENV};
+use crate::overlay::AssetIndexEntry;
+use crate::signer_backend::{ExternalSigner, ExternalSignerConfig, SignerBackend};
+use crate::{PackError, PackResult};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Public key (base64) the shell was built to trust, if any
+pub const TRUSTED_OVERLAY_KEY: Option<&str> = option_env!("AURORAVIEW_OVERLAY_PUBLIC_KEY");
+
+/// Domain separator of the signed message
+const SIGNATURE_CONTEXT: &[u8] = b"auroraview-overlay-signature-v1\0";
+
+/// Overlay signing configuration
+///
+/// Located at `[signing]` in TOML. Pack-time only; the key never reaches
+/// the overlay.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SigningConfig {
+    /// PKCS#8 private key file (DER or PEM)
+    pub key: Option<PathBuf>,
+
+    /// Environment variable holding the key (PEM, or base64 of the DER)
+    pub key_env: Option<String>,
+
+    /// Id of a key in the keystore
+    pub key_id: Option<String>,
+
+    /// Keystore directory (default: `<config>/AuroraView/keys`)
+    pub keystore: Option<PathBuf>,
+
+    /// Environment variable holding the passphrase of a `key_id` key
+    /// (default: `AURORAVIEW_KEY_PASSPHRASE`)
+    pub passphrase_env: Option<String>,
+
+    /// Key in an HSM or cloud KMS (`[signing.external]`)
+    pub external: Option<ExternalSignerConfig>,
+}
+
+impl SigningConfig {
+    /// Sign with a key file
+    pub fn from_key(key: impl Into<PathBuf>) -> Self {
+        Self {
+            key: Some(key.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sign with a key from the keystore
+    pub fn from_key_id(key_id: impl Into<String>) -> Self {
+        Self {
+            key_id: Some(key_id.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sign with a key in an HSM or cloud KMS
+    pub fn from_external(external: ExternalSignerConfig) -> Self {
+        Self {
+            external: Some(external),
+            ..Default::default()
+        }
+    }
+
+    /// Use a keystore other than the default one
+    pub fn with_keystore(mut self, keystore: impl Into<PathBuf>) -> Self {
+        self.keystore = Some(keystore.into());
+        self
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        let sources = [
+            self.key.is_some(),
+            self.key_env.is_some(),
+            self.key_id.is_some(),
+            self.external.is_some(),
+        ];
+        match sources.iter().filter(|set| **set).count() {
+            0 => {
+                return Err(PackError::Config(
+                    "[signing] requires key, key_env, key_id or [signing.external]".to_string(),
+                ))
+            }
+            1 => {}
+            _ => {
+                return Err(PackError::Config(
+                    "[signing] key, key_env, key_id and [signing.external] are mutually exclusive"
+                        .to_string(),
+                ))
+            }
+        }
+        if let Some(ref external) = self.external {
+            external.validate()?;
+        }
+        if let Some(ref key) = self.key {
+            if !key.is_file() {
+                return Err(PackError::AssetNotFound(key.clone()));
+            }
+        }
+        if self.key_id.is_none() && (self.keystore.is_some() || self.passphrase_env.is_some()) {
+            return Err(PackError::Config(
+                "[signing] keystore and passphrase_env require key_id".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Keystore holding `key_id`
+    pub fn keystore(&self) -> KeyStore {
+        KeyStore::open(self.keystore.clone().unwrap_or_else(KeyStore::default_dir))
+    }
+
+    /// Whether the Windows executable is Authenticode-signed as well
+    pub fn signs_executables(&self) -> bool {
+        self.external
+            .as_ref()
+            .is_some_and(|external| !external.authenticode_command.is_empty())
+    }
+
+    /// Load the configured key
+    ///
+    /// Not available for `[signing.external]` keys, which never leave the
+    /// HSM or KMS.
+    pub fn load_key(&self) -> PackResult<KeyPair> {
+        self.validate()?;
+        if self.external.is_some() {
+            return Err(PackError::Config(
+                "[signing.external] keys cannot be loaded; sign through OverlaySigner".to_string(),
+            ));
+        }
+        if let Some(ref key) = self.key {
+            return KeyPair::from_pkcs8(&std::fs::read(key)?);
+        }
+        if let Some(ref key_id) = self.key_id {
+            let store = self.keystore();
+            let stored = store.info(key_id)?;
+            let passphrase = if stored.in_keychain() {
+                None
+            } else {
+                let name = self.passphrase_env.as_deref().unwrap_or(KEY_PASSPHRASE_ENV);
+                Some(std::env::var(name).map_err(|_| {
+                    PackError::Config(format!(
+                        "[signing] key '{}' is encrypted; set {} to its passphrase",
+                        key_id, name
+                    ))
+                })?)
+            };
+            return store.load(key_id, passphrase.as_deref());
+        }
+
+        let name = self.key_env.as_deref().unwrap_or_default();
+        let value = std::env::var(name).map_err(|_| {
+            PackError::Config(format!("[signing] key_env variable {} is not set", name))
+        })?;
+        if value.contains("-----BEGIN") {
+            KeyPair::from_pkcs8(value.as_bytes())
+        } else {
+            let der = STANDARD.decode(value.trim()).map_err(|e| {
+                PackError::Config(format!("[signing] {} is not PEM or base64: {}", name, e))
+            })?;
+            KeyPair::from_pkcs8(&der)
+        }
+    }
+}
+
+/// Signature stored in the overlay metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlaySignature {
+    /// Signature algorithm ("ed25519" or "rsa-pkcs1-sha256")
+    pub algorithm: String,
+    /// Signer's public key (base64)
+    pub public_key: String,
+    /// Signature (base64)
+    pub signature: String,
+}
+
+/// Key that signs overlays
+#[derive(Clone)]
+pub struct OverlaySigner {
+    backend: Arc<dyn SignerBackend>,
+    algorithm: KeyAlgorithm,
+    public_key: String,
+}
+
+impl std::fmt::Debug for OverlaySigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OverlaySigner")
+            .field("backend", &self.backend.describe())
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+impl OverlaySigner {
+    /// Generate a new Ed25519 key, returned as PKCS#8 DER
+    pub fn generate_pkcs8() -> PackResult<Vec<u8>> {
+        Ok(KeyPair::generate(KeyAlgorithm::Ed25519)?
+            .pkcs8_der()
+            .to_vec())
+    }
+
+    /// Load a PKCS#8 key (DER or PEM)
+    pub fn from_pkcs8(key: &[u8]) -> PackResult<Self> {
+        Ok(Self::from_key_pair(KeyPair::from_pkcs8(key)?))
+    }
+
+    /// Sign with a loaded key
+    pub fn from_key_pair(key_pair: KeyPair) -> Self {
+        Self {
+            algorithm: key_pair.algorithm(),
+            public_key: key_pair.public_key_base64(),
+            backend: Arc::new(key_pair),
+        }
+    }
+
+    /// Sign through a backend (HSM, KMS, ...)
+    ///
+    /// Fetches the backend's public key.
+    pub fn from_backend(backend: Arc<dyn SignerBackend>) -> PackResult<Self> {
+        Ok(Self {
+            algorithm: backend.algorithm()?,
+            public_key: STANDARD.encode(backend.public_key()?),
+            backend,
+        })
+    }
+
+    /// Load the key of a `[signing]` configuration
+    pub fn from_config(config: &SigningConfig) -> PackResult<Self> {
+        match config.external {
+            Some(ref external) => {
+                config.validate()?;
+                Self::from_backend(Arc::new(ExternalSigner::new(external.clone())?))
+            }
+            None => config.load_key().map(Self::from_key_pair),
+        }
+    }
+
+    /// Algorithm of the key
+    pub fn algorithm(&self) -> KeyAlgorithm {
+        self.algorithm
+    }
+
+    /// Public key (base64), the value to pin in `AURORAVIEW_OVERLAY_PUBLIC_KEY`
+    pub fn public_key_base64(&self) -> String {
+        self.public_key.clone()
+    }
+
+    /// Backend doing the signing
+    pub fn backend(&self) -> &dyn SignerBackend {
+        self.backend.as_ref()
+    }
+
+    /// Authenticode-sign a Windows executable through the backend
+    pub fn sign_executable(&self, file: &Path) -> PackResult<()> {
+        self.backend.sign_executable(file)
+    }
+
+    /// Sign overlay metadata and its asset index
+    pub(crate) fn sign(
+        &self,
+        metadata: &[u8],
+        index: &[AssetIndexEntry],
+    ) -> PackResult<OverlaySignature> {
+        let signature = self.backend.sign(&signed_message(metadata, index))?;
+        Ok(OverlaySignature {
+            algorithm: self.algorithm().as_str().to_string(),
+            public_key: self.public_key_base64(),
+            signature: STANDARD.encode(signature),
+        })
+    }
+
+    /// Sign a release artifact (base64 signature of its contents; with an
+    /// Ed25519 key, Sparkle's `edSignature`)
+    pub fn sign_artifact(&self, data: &[u8]) -> PackResult<String> {
+        Ok(STANDARD.encode(self.backend.sign(data)?))
+    }
+
+    /// Sign arbitrary data (e.g. license tokens)
+    pub fn sign_bytes(&self, data: &[u8]) -> PackResult<Vec<u8>> {
+        self.backend.sign(data)
+    }
+}
+
+/// Check a release artifact against its signature from an update feed
+pub fn verify_artifact(public_key: &str, data: &[u8], signature: &str) -> PackResult<()> {
+    let decode = |value: &str, what: &str| {
+        STANDARD
+            .decode(value.trim())
+            .map_err(|e| PackError::Signature(format!("invalid {}: {}", what, e)))
+    };
+    let public_key = decode(public_key, "public key")?;
+    verify_signature(
+        KeyAlgorithm::of_public_key(&public_key),
+        &public_key,
+        data,
+        &decode(signature, "signature")?,
+    )
+    .map_err(|_| PackError::Signature("artifact does not match its signature".to_string()))
+}
+
+/// Check an overlay's signature
+///
+/// Signed overlays must verify against their own key; with a `trusted` key,
+/// the overlay must also be signed, by that key.
+pub(crate) fn verify(
+    signature: Option<&OverlaySignature>,
+    metadata: &[u8],
+    index: &[AssetIndexEntry],
+    trusted: Option<&str>,
+) -> PackResult<()> {
+    let Some(signature) = signature else {
+        return match trusted {
+            Some(_) => Err(PackError::Signature("overlay is not signed".to_string())),
+            None => Ok(()),
+        };
+    };
+
+    let Some(algorithm) = KeyAlgorithm::from_name(&signature.algorithm) else {
+        return Err(PackError::Signature(format!(
+            "unsupported algorithm '{}'",
+            signature.algorithm
+        )));
+    };
+    if let Some(trusted) = trusted {
+        if signature.public_key != trusted.trim() {
+            return Err(PackError::Signature(format!(
+                "overlay is signed by an untrusted key ({})",
+                signature.public_key
+            )));
+        }
+    }
+
+    let decode = |value: &str, what: &str| {
+        STANDARD
+            .decode(value)
+            .map_err(|e| PackError::Signature(format!("invalid {}: {}", what, e)))
+    };
+    let public_key = decode(&signature.public_key, "public key")?;
+    let bytes = decode(&signature.signature, "signature")?;
+    verify_signature(
+        algorithm,
+        &public_key,
+        &signed_message(metadata, index),
+        &bytes,
+    )
+    .map_err(|_| {
+        PackError::Signature("signature does not match; the overlay was modified".to_string())
+    })
+}
+
+/// Message covered by the signature
+///
+/// Asset offsets are layout only; the hashes are checked as assets are read.
+fn signed_message(metadata: &[u8], index: &[AssetIndexEntry]) -> Vec<u8> {
+    let mut message = SIGNATURE_CONTEXT.to_vec();
+    message.extend_from_slice(&(metadata.len() as u64).to_le_bytes());
+    message.extend_from_slice(metadata);
+    for entry in index {
+        message.extend_from_slice(entry.path.as_bytes());
+        message.push(0);
+        message.extend_from_slice(&entry.size.to_le_bytes());
+        message.extend_from_slice(entry.hash.as_bytes());
+        message.push(0);
+        message.extend_from_slice(&entry.attributes.digest_bytes());
+    }
+    message
+}
@@ -0,0 +1,403 @@
+//! Blue/green asset slots for safe self-update
+//!
+//! An in-app updater must never leave the app without a working asset set.
+//! Extracted assets live in one of two slots, and a pointer file names the
+//! active one:
+//!
+//! ```text
+//! <cache>/AuroraView/runtime/<app>/slots/
+//! ├── active.json     - SlotPointer: active slot, slot contents, trial state
+//! ├── a/              - One asset set
+//! └── b/              - The other
+//! ```
+//!
+//! The updater extracts the new version into the inactive slot
+//! ([`AssetSlots::begin_stage`]) and switches to it with one atomic pointer
+//! write ([`AssetSlots::commit_stage`]). The new slot is then on trial: it
+//! must pass its first health check ([`AssetSlots::confirm`]) within the
+//! [`RollbackPolicy`], or the next launch switches back to the previous slot
+//! ([`AssetSlots::launch`]):
+//!
+//! ```toml
+//! [runtime.rollback]
+//! health_check_timeout = 30     # Seconds until the new version must be healthy
+//! max_attempts = 1              # Launches without a passed check before rollback
+//! keep_previous = true          # Keep the old slot after a passed check
+//! ```
+
+use crate::cache_lock::{write_atomic, CacheLock};
+use crate::context::unix_seconds;
+use crate::identity::AppIdentity;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// File name of the active-slot pointer
+pub const SLOT_POINTER_FILE: &str = "active.json";
+
+/// One of the two asset slots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Slot {
+    /// Slot `a`
+    #[default]
+    A,
+    /// Slot `b`
+    B,
+}
+
+impl Slot {
+    /// The other slot
+    pub fn other(self) -> Self {
+        match self {
+            Self::A => Self::B,
+            Self::B => Self::A,
+        }
+    }
+
+    /// Directory name of the slot
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::A => "a",
+            Self::B => "b",
+        }
+    }
+}
+
+/// Asset set stored in a slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotContent {
+    /// Content hash of the overlay the slot was extracted from
+    pub overlay_hash: String,
+    /// App version, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// A slot switched to but not yet confirmed healthy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotTrial {
+    /// Launches of the slot so far
+    pub attempts: u32,
+    /// Unix timestamp (seconds) of the switch
+    pub switched_at: u64,
+    /// Unix timestamp (seconds) of the first launch of the slot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launched_at: Option<u64>,
+}
+
+/// Contents of [`SLOT_POINTER_FILE`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotPointer {
+    /// Slot the app runs from
+    pub active: Slot,
+    /// Contents of slot `a`
+    #[serde(default)]
+    pub a: Option<SlotContent>,
+    /// Contents of slot `b`
+    #[serde(default)]
+    pub b: Option<SlotContent>,
+    /// Set while the active slot awaits its first passed health check
+    #[serde(default)]
+    pub trial: Option<SlotTrial>,
+    /// Overlay hash of the last version rolled back (not to be staged again)
+    #[serde(default)]
+    pub rejected: Option<String>,
+}
+
+impl SlotPointer {
+    /// Contents of a slot
+    pub fn content(&self, slot: Slot) -> Option<&SlotContent> {
+        match slot {
+            Slot::A => self.a.as_ref(),
+            Slot::B => self.b.as_ref(),
+        }
+    }
+
+    fn content_mut(&mut self, slot: Slot) -> &mut Option<SlotContent> {
+        match slot {
+            Slot::A => &mut self.a,
+            Slot::B => &mut self.b,
+        }
+    }
+}
+
+/// Rollback policy of self-updates
+///
+/// Located at `[runtime.rollback]` in TOML. Stored in the overlay config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RollbackPolicy {
+    /// Seconds after its first launch within which the new version must
+    /// report healthy
+    pub health_check_timeout: u64,
+
+    /// Launches of a new slot without a passed health check before rolling back
+    pub max_attempts: u32,
+
+    /// Keep the previous slot after the new one passed its health check
+    pub keep_previous: bool,
+}
+
+impl Default for RollbackPolicy {
+    fn default() -> Self {
+        Self {
+            health_check_timeout: 30,
+            max_attempts: 1,
+            keep_previous: true,
+        }
+    }
+}
+
+impl RollbackPolicy {
+    /// Validate the policy
+    pub fn validate(&self) -> PackResult<()> {
+        if self.health_check_timeout == 0 {
+            return Err(PackError::Config(
+                "[runtime.rollback] health_check_timeout must be at least 1 second".to_string(),
+            ));
+        }
+        if self.max_attempts == 0 {
+            return Err(PackError::Config(
+                "[runtime.rollback] max_attempts must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Result of [`AssetSlots::launch`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotLaunch {
+    /// Slot to run from
+    pub slot: Slot,
+    /// Its directory
+    pub dir: PathBuf,
+    /// The slot is on trial: report the health check with
+    /// [`AssetSlots::confirm`] or [`AssetSlots::rollback`]
+    pub on_trial: bool,
+    /// A failed update was rolled back on this launch
+    pub rolled_back: bool,
+}
+
+/// The two asset slots of an app
+#[derive(Debug, Clone)]
+pub struct AssetSlots {
+    root: PathBuf,
+}
+
+impl AssetSlots {
+    /// Slots rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Slots of an app on this machine
+    pub fn for_app(identity: &AppIdentity) -> Self {
+        Self::new(identity.runtime_cache_dir().join("slots"))
+    }
+
+    /// Slots root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Directory of a slot
+    pub fn slot_dir(&self, slot: Slot) -> PathBuf {
+        self.root.join(slot.as_str())
+    }
+
+    /// Read the pointer (slot `a`, empty, if there is none yet)
+    pub fn pointer(&self) -> PackResult<SlotPointer> {
+        match fs::read(self.pointer_path()) {
+            Ok(content) => Ok(serde_json::from_slice(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SlotPointer::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Directory of the active slot
+    pub fn active_dir(&self) -> PackResult<PathBuf> {
+        Ok(self.slot_dir(self.pointer()?.active))
+    }
+
+    /// Empty the inactive slot and return its directory for staging
+    ///
+    /// Fails while the active slot is on trial: the inactive slot is the
+    /// rollback target until the health check passed.
+    pub fn begin_stage(&self) -> PackResult<PathBuf> {
+        let _lock = self.lock()?;
+        let mut pointer = self.pointer()?;
+        if pointer.trial.is_some() {
+            return Err(PackError::Config(
+                "Cannot stage an update while the current one awaits its health check".to_string(),
+            ));
+        }
+        let slot = pointer.active.other();
+        let dir = self.slot_dir(slot);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        // The slot no longer holds what the pointer says
+        *pointer.content_mut(slot) = None;
+        self.save(&pointer)?;
+        Ok(dir)
+    }
+
+    /// Switch to the staged slot, putting it on trial
+    pub fn commit_stage(&self, overlay_hash: &str, version: Option<&str>) -> PackResult<Slot> {
+        let _lock = self.lock()?;
+        let mut pointer = self.pointer()?;
+        if pointer.trial.is_some() {
+            return Err(PackError::Config(
+                "Cannot switch slots while the current one awaits its health check".to_string(),
+            ));
+        }
+        if pointer.rejected.as_deref() == Some(overlay_hash) {
+            return Err(PackError::Config(format!(
+                "Overlay {} was rolled back before; not switching to it again",
+                overlay_hash
+            )));
+        }
+        let slot = pointer.active.other();
+        if !self.slot_dir(slot).is_dir() {
+            return Err(PackError::Config(format!(
+                "Slot '{}' has not been staged",
+                slot.as_str()
+            )));
+        }
+        *pointer.content_mut(slot) = Some(SlotContent {
+            overlay_hash: overlay_hash.to_string(),
+            version: version.map(str::to_string),
+        });
+        pointer.active = slot;
+        pointer.trial = Some(SlotTrial {
+            attempts: 0,
+            switched_at: unix_seconds(SystemTime::now()),
+            launched_at: None,
+        });
+        self.save(&pointer)?;
+        tracing::info!(
+            "Switched to asset slot '{}' ({})",
+            slot.as_str(),
+            overlay_hash
+        );
+        Ok(slot)
+    }
+
+    /// Pick the slot to run from at app start
+    ///
+    /// Counts a launch of a slot on trial; once more than
+    /// `policy.max_attempts` launches, or `policy.health_check_timeout`
+    /// seconds since its first launch, did not confirm it, switches back.
+    /// A slot without a previous version to return to (the first install)
+    /// is kept and leaves its trial.
+    pub fn launch(&self, policy: &RollbackPolicy) -> PackResult<SlotLaunch> {
+        let _lock = self.lock()?;
+        let mut pointer = self.pointer()?;
+        let mut rolled_back = false;
+        if let Some(ref mut trial) = pointer.trial {
+            let now = unix_seconds(SystemTime::now());
+            trial.attempts += 1;
+            let launched_at = *trial.launched_at.get_or_insert(now);
+            let timed_out = now.saturating_sub(launched_at) > policy.health_check_timeout;
+            if trial.attempts > policy.max_attempts || timed_out {
+                if self.has_assets(&pointer, pointer.active.other()) {
+                    Self::switch_back(&mut pointer);
+                    rolled_back = true;
+                } else {
+                    tracing::warn!(
+                        "Asset slot '{}' was not confirmed healthy, but there is no previous \
+                         version to roll back to",
+                        pointer.active.as_str()
+                    );
+                    pointer.trial = None;
+                }
+            }
+            self.save(&pointer)?;
+        }
+        Ok(SlotLaunch {
+            slot: pointer.active,
+            dir: self.slot_dir(pointer.active),
+            on_trial: pointer.trial.is_some(),
+            rolled_back,
+        })
+    }
+
+    /// Report that the slot on trial passed its health check
+    pub fn confirm(&self, policy: &RollbackPolicy) -> PackResult<()> {
+        let _lock = self.lock()?;
+        let mut pointer = self.pointer()?;
+        if pointer.trial.take().is_none() {
+            return Ok(());
+        }
+        if !policy.keep_previous {
+            let previous = pointer.active.other();
+            let dir = self.slot_dir(previous);
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+            *pointer.content_mut(previous) = None;
+        }
+        self.save(&pointer)?;
+        tracing::info!("Asset slot '{}' confirmed", pointer.active.as_str());
+        Ok(())
+    }
+
+    /// Report that the slot on trial failed its health check and switch back
+    ///
+    /// Returns the slot now active. Fails if the other slot holds no
+    /// previous version.
+    pub fn rollback(&self) -> PackResult<Slot> {
+        let _lock = self.lock()?;
+        let mut pointer = self.pointer()?;
+        if pointer.trial.is_none() {
+            return Err(PackError::Config(
+                "No update awaiting its health check to roll back".to_string(),
+            ));
+        }
+        let previous = pointer.active.other();
+        if !self.has_assets(&pointer, previous) {
+            return Err(PackError::Config(format!(
+                "No previous version in asset slot '{}' to roll back to",
+                previous.as_str()
+            )));
+        }
+        Self::switch_back(&mut pointer);
+        self.save(&pointer)?;
+        Ok(pointer.active)
+    }
+
+    fn switch_back(pointer: &mut SlotPointer) {
+        let failed = pointer.active;
+        pointer.rejected = pointer.content(failed).map(|c| c.overlay_hash.clone());
+        pointer.active = failed.other();
+        pointer.trial = None;
+        tracing::warn!(
+            "Rolled back from asset slot '{}' to '{}'",
+            failed.as_str(),
+            pointer.active.as_str()
+        );
+    }
+
+    /// Whether `slot` holds an asset set to run from
+    fn has_assets(&self, pointer: &SlotPointer, slot: Slot) -> bool {
+        pointer.content(slot).is_some()
+            || fs::read_dir(self.slot_dir(slot)).is_ok_and(|mut entries| entries.next().is_some())
+    }
+
+    fn pointer_path(&self) -> PathBuf {
+        self.root.join(SLOT_POINTER_FILE)
+    }
+
+    fn lock(&self) -> PackResult<CacheLock> {
+        CacheLock::acquire(&self.pointer_path())
+    }
+
+    fn save(&self, pointer: &SlotPointer) -> PackResult<()> {
+        write_atomic(&self.pointer_path(), &serde_json::to_vec_pretty(pointer)?)
+    }
+}
@@ -0,0 +1,215 @@
+//! Post-pack smoke test
+//!
+//! With `[debug.smoke_test]`, the after-pack stage checks the finished
+//! executable twice: the packer reads its overlay back and streams every
+//! asset (checking the index hashes), then launches it headless with
+//! [`SELF_CHECK_FLAG`]. The shell answers on standard output with one JSON
+//! line and exits:
+//!
+//! ```json
+//! {"overlay": true, "extraction": true, "backend": true, "errors": []}
+//! ```
+//!
+//! `backend` is `null` for executables without a Python backend. The result
+//! is reported in [`PackOutput::smoke_test`](crate::packer::PackOutput).
+
+use crate::{CommandRunner, OverlayReader, PackResult, SmokeTestConfig};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// Flag that makes a packed executable check itself and exit
+pub const SELF_CHECK_FLAG: &str = "--self-check";
+
+/// Environment variable asking the shell not to open a window
+pub const HEADLESS_ENV: &str = "AURORAVIEW_HEADLESS";
+
+/// Environment variable carrying the smoke test timeout in seconds, so the
+/// shell can give up on the backend health check before it is killed
+pub const SELF_CHECK_TIMEOUT_ENV: &str = "AURORAVIEW_SELF_CHECK_TIMEOUT";
+
+/// Lines of standard error quoted when the self-check fails
+const STDERR_TAIL_LINES: usize = 10;
+
+/// Self-check report printed by the shell
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SelfCheckReport {
+    overlay: bool,
+    extraction: bool,
+    #[serde(default)]
+    backend: Option<bool>,
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+/// Result of the post-pack smoke test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmokeTestReport {
+    /// Every check passed
+    pub passed: bool,
+    /// The overlay read back, in the packer and in the shell
+    pub overlay: bool,
+    /// Every asset extracted with a matching hash
+    pub extraction: bool,
+    /// The backend health check passed (`None`: no backend)
+    pub backend: Option<bool>,
+    /// Exit code of the executable (`None`: not started, killed or timed out)
+    pub exit_code: Option<i32>,
+    /// Duration of the test, in milliseconds
+    pub duration_ms: u64,
+    /// What failed
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for SmokeTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.passed {
+            return write!(f, "passed in {} ms", self.duration_ms);
+        }
+        write!(f, "{}", self.errors.join("; "))
+    }
+}
+
+/// Smoke-test the packed `executable`
+pub(crate) fn run(
+    executable: &Path,
+    config: &SmokeTestConfig,
+    runner: &dyn CommandRunner,
+) -> SmokeTestReport {
+    let started = Instant::now();
+    let mut errors = Vec::new();
+
+    let (overlay, extraction) = match read_back(executable) {
+        Ok(()) => (true, true),
+        Err(ReadBackError::Overlay(e)) => {
+            errors.push(format!("overlay does not read back: {}", e));
+            (false, false)
+        }
+        Err(ReadBackError::Extraction(e)) => {
+            errors.push(format!("asset extraction failed: {}", e));
+            (true, false)
+        }
+    };
+
+    let mut report = SmokeTestReport {
+        passed: false,
+        overlay,
+        extraction,
+        backend: None,
+        exit_code: None,
+        duration_ms: 0,
+        errors,
+    };
+    if overlay {
+        self_check(executable, config, runner, &mut report);
+    }
+    report.passed = report.errors.is_empty()
+        && report.overlay
+        && report.extraction
+        && report.backend != Some(false);
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+enum ReadBackError {
+    Overlay(String),
+    Extraction(String),
+}
+
+/// Open the overlay and stream every asset through its hash check
+fn read_back(executable: &Path) -> Result<(), ReadBackError> {
+    let archive = OverlayReader::open(executable)
+        .map_err(|e| ReadBackError::Overlay(e.to_string()))?
+        .ok_or_else(|| ReadBackError::Overlay("no overlay found".to_string()))?;
+    for entry in archive.entries() {
+        let extract = || -> PackResult<()> {
+            if let Some(mut reader) = archive.asset_reader(&entry.path)? {
+                io::copy(&mut reader, &mut io::sink())?;
+            }
+            Ok(())
+        };
+        extract().map_err(|e| ReadBackError::Extraction(format!("{}: {}", entry.path, e)))?;
+    }
+    Ok(())
+}
+
+/// Launch the executable with [`SELF_CHECK_FLAG`] and merge its report
+fn self_check(
+    executable: &Path,
+    config: &SmokeTestConfig,
+    runner: &dyn CommandRunner,
+    report: &mut SmokeTestReport,
+) {
+    let mut command = Command::new(executable);
+    command
+        .arg(SELF_CHECK_FLAG)
+        .args(&config.args)
+        .env(HEADLESS_ENV, "1")
+        .env(SELF_CHECK_TIMEOUT_ENV, config.timeout.to_string());
+    if let Some(dir) = executable.parent() {
+        command.current_dir(dir);
+    }
+
+    let output = match runner.output_with_timeout(&mut command, Duration::from_secs(config.timeout))
+    {
+        Ok(output) => output,
+        Err(e) => {
+            report
+                .errors
+                .push(format!("failed to run {}: {}", executable.display(), e));
+            return;
+        }
+    };
+    report.exit_code = output.status.code();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let shell_report = stdout
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<SelfCheckReport>(line.trim()).ok());
+    match shell_report {
+        Some(shell_report) => {
+            report.overlay &= shell_report.overlay;
+            report.extraction &= shell_report.extraction;
+            report.backend = shell_report.backend;
+            if !shell_report.overlay {
+                report
+                    .errors
+                    .push("shell could not read its overlay".to_string());
+            }
+            if !shell_report.extraction {
+                report
+                    .errors
+                    .push("shell could not extract its assets".to_string());
+            }
+            if shell_report.backend == Some(false) {
+                report
+                    .errors
+                    .push("backend health check failed".to_string());
+            }
+            report.errors.extend(shell_report.errors);
+        }
+        None => report.errors.push(format!(
+            "{} printed no self-check report",
+            executable.display()
+        )),
+    }
+    if !output.status.success() {
+        report
+            .errors
+            .push(format!("self-check exited with {}", output.status));
+    }
+
+    if !report.errors.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let lines: Vec<&str> = stderr.lines().collect();
+        let tail = &lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..];
+        if !tail.is_empty() {
+            tracing::debug!("Self-check stderr:\n{}", tail.join("\n"));
+            report.errors.push(format!("stderr: {}", tail.join(" | ")));
+        }
+    }
+}
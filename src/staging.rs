@@ -0,0 +1,171 @@
+//! Staging directories and disk space preflight
+//!
+//! Every pack run gets a unique run ID. Intermediate files (dependency
+//! collection, pip installs, protected sources) are written to
+//! `<staging root>/auroraview-pack-<run id>-<purpose>-XXXXXX` directories that
+//! are removed when the step finishes. The staging root defaults to the
+//! system temp directory and can be moved off small `/tmp` mounts with
+//! `[build] staging_dir`.
+
+use crate::clean::path_size;
+use crate::config::{BundleStrategy, PackMode};
+use crate::context::unix_seconds;
+use crate::{PackConfig, PackContext, PackError, PackResult};
+use rand::Rng;
+use std::path::Path;
+use std::time::SystemTime;
+use tempfile::TempDir;
+
+/// Prefix of all staging directories
+pub(crate) const STAGING_PREFIX: &str = "auroraview-pack-";
+
+/// Extra space reserved for a downloaded/extracted Python runtime and packages
+const PYTHON_RUNTIME_ALLOWANCE: u64 = 300 * 1024 * 1024;
+
+/// Extra space reserved for a PyOxidizer build tree
+const PYOXIDIZER_ALLOWANCE: u64 = 1024 * 1024 * 1024;
+
+/// Generate a unique run ID (`<unix seconds>-<random hex>`)
+pub fn new_run_id() -> String {
+    format!(
+        "{}-{:08x}",
+        unix_seconds(SystemTime::now()),
+        rand::thread_rng().gen::<u32>()
+    )
+}
+
+/// Create a unique staging directory for one pack step
+///
+/// The directory is removed when the returned [`TempDir`] is dropped.
+pub(crate) fn create_staging_dir(root: &Path, run_id: &str, purpose: &str) -> PackResult<TempDir> {
+    std::fs::create_dir_all(root)?;
+    tempfile::Builder::new()
+        .prefix(&format!("{}{}-{}-", STAGING_PREFIX, run_id, purpose))
+        .tempdir_in(root)
+        .map_err(|e| {
+            PackError::Io(std::io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to create staging directory in {}: {}",
+                    root.display(),
+                    e
+                ),
+            ))
+        })
+}
+
+/// Estimated disk space needed by a pack run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpaceEstimate {
+    /// Bytes written to the output directory
+    pub output: u64,
+    /// Bytes written to the staging directory
+    pub staging: u64,
+}
+
+/// Estimate the disk space a pack run needs from its inputs
+///
+/// This is a lower bound: hook-collected files and downloads are not known
+/// before they are fetched. The launcher copied is the one of `context`.
+pub fn estimate_required_space(config: &PackConfig, context: &PackContext) -> SpaceEstimate {
+    estimate_space(config, context.launcher())
+}
+
+/// Estimate the disk space of a pack run copying `launcher`
+pub(crate) fn estimate_space(config: &PackConfig, launcher: Option<&Path>) -> SpaceEstimate {
+    let launcher = launcher
+        .and_then(|path| std::fs::metadata(path).ok())
+        .map(|m| m.len())
+        .unwrap_or_default();
+    let frontend = if config.uses_dev_server() {
+        0
+    } else {
+        config
+            .mode
+            .frontend_path()
+            .map(|p| path_size(p))
+            .unwrap_or_default()
+    };
+
+    let mut estimate = SpaceEstimate {
+        output: launcher + frontend,
+        staging: 0,
+    };
+
+    match &config.mode {
+        PackMode::FullStack { python, .. } => {
+            let code: u64 = python.include_paths.iter().map(|p| path_size(p)).sum();
+            estimate.output += code;
+            estimate.staging += code;
+            match python.strategy {
+                BundleStrategy::Standalone | BundleStrategy::Portable => {
+                    estimate.output += PYTHON_RUNTIME_ALLOWANCE;
+                    estimate.staging += PYTHON_RUNTIME_ALLOWANCE;
+                }
+                BundleStrategy::PyOxidizer => {
+                    estimate.output += PYOXIDIZER_ALLOWANCE;
+                }
+                _ => {}
+            }
+        }
+        PackMode::Process { launch, .. } => {
+            estimate.output += path_size(&launch.binary);
+        }
+        _ => {}
+    }
+
+    estimate
+}
+
+/// Fail early if the output or staging volume cannot hold the pack run
+pub(crate) fn preflight_disk_space(
+    config: &PackConfig,
+    staging_root: &Path,
+    launcher: Option<&Path>,
+) -> PackResult<()> {
+    let estimate = estimate_space(config, launcher);
+
+    for (path, required) in [
+        (config.output_dir.as_path(), estimate.output),
+        (staging_root, estimate.staging),
+    ] {
+        if required == 0 {
+            continue;
+        }
+        // Leave 10% headroom on top of the estimate
+        let required = required + required / 10;
+        let Some(available) = available_space(path) else {
+            tracing::debug!(
+                "Could not determine free space for {}, skipping disk check",
+                path.display()
+            );
+            continue;
+        };
+        if available < required {
+            return Err(PackError::DiskSpace(format!(
+                "{} needs ~{:.1} MB but only {:.1} MB is available \
+                 (set [build] staging_dir or free up space)",
+                path.display(),
+                required as f64 / (1024.0 * 1024.0),
+                available as f64 / (1024.0 * 1024.0)
+            )));
+        }
+        tracing::debug!(
+            "Disk space OK for {}: ~{} bytes needed, {} available",
+            path.display(),
+            required,
+            available
+        );
+    }
+
+    Ok(())
+}
+
+/// Query the free space of the volume containing `path`
+///
+/// Returns `None` if it cannot be determined.
+pub fn available_space(path: &Path) -> Option<u64> {
+    // Query the nearest existing ancestor (output dirs may not exist yet)
+    let existing = path.ancestors().find(|p| p.exists())?;
+    fs4::available_space(existing).ok()
+}
@@ -0,0 +1,1028 @@
+//! Content-addressed artifact store
+//!
+//! One store backs the pack caches: vx downloads, python-build-standalone
+//! distributions, rcedit, provisioned toolchains, `node_modules` snapshots
+//! and pip's wheel cache.
+//!
+//! ```text
+//! <store>/                           - [build.store] dir (default: <cache>/AuroraView/store)
+//! ├── blobs/<aa>/<sha256>/<name>     - File contents, keyed by their SHA-256
+//! ├── trees/<id>/                    - Directories, keyed by the SHA-256 of their inputs
+//! ├── refs/<namespace>/<name>        - Named references to blobs and trees
+//! └── tools/<tool>/                  - Caches managed by external tools (pip, uv)
+//! ```
+//!
+//! An object's reference count is the number of refs pointing at it.
+//! [`ArtifactStore::gc`] removes unreferenced objects and evicts the least
+//! recently used ones beyond the `max_size_mb` / `max_age_days` limits.
+//! Writers lock the object (see [`CacheLock`]) and move it into place
+//! complete, so concurrent packs share the store safely.
+//!
+//! With `[build.store.remote]` (see [`crate::RemoteCacheConfig`]), refs
+//! missing locally are pulled from a shared remote cache and new refs are
+//! pushed to it, so ephemeral CI runners reuse what earlier builds
+//! downloaded or built. Blobs are verified against their digest, and tree
+//! archives against the SHA-256 recorded in their ref before being
+//! unpacked with the rules of [`crate::archive`].
+
+use crate::cache_lock::{write_atomic, CacheLock};
+use crate::clean::path_size;
+use crate::http::HttpClient;
+use crate::remote_cache::{RemoteCache, RemoteCacheConfig};
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Environment variable overriding the default store location
+pub const STORE_DIR_ENV: &str = "AURORAVIEW_STORE_DIR";
+
+/// Objects used this recently are never removed, so a concurrent pack
+/// does not lose an artifact it just stored or resolved
+const EVICTION_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// Get the default artifact store directory
+pub fn get_store_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(STORE_DIR_ENV).filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("AuroraView")
+        .join("store")
+}
+
+/// Artifact store configuration
+///
+/// Located at `[build.store]` in TOML.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoreConfig {
+    /// Store directory (default: `$AURORAVIEW_STORE_DIR` or
+    /// `<cache>/AuroraView/store`)
+    pub dir: Option<PathBuf>,
+
+    /// Evict least recently used objects beyond this size
+    pub max_size_mb: Option<u64>,
+
+    /// Evict objects unused for this many days
+    pub max_age_days: Option<u64>,
+
+    /// Shared remote cache (`[build.store.remote]`)
+    pub remote: Option<RemoteCacheConfig>,
+}
+
+impl StoreConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        match self.remote {
+            Some(ref remote) => remote.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Kind of a stored object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    /// A single file, keyed by the SHA-256 of its content
+    Blob,
+    /// A directory, keyed by the SHA-256 of its inputs
+    Tree,
+}
+
+/// Target of a named reference
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreRef {
+    /// Object kind
+    pub kind: ObjectKind,
+    /// Blob digest or tree ID
+    pub id: String,
+}
+
+/// A ref as published to the remote cache
+#[derive(Debug, Serialize, Deserialize)]
+struct RemoteRef {
+    #[serde(flatten)]
+    target: StoreRef,
+    /// File name of a blob (keeps archive extensions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file_name: Option<String>,
+    /// SHA-256 of a tree's archive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    digest: Option<String>,
+}
+
+/// An object in the store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreObject {
+    /// Object kind
+    pub kind: ObjectKind,
+    /// Blob digest or tree ID
+    pub id: String,
+    /// Object directory
+    pub path: PathBuf,
+    /// Size in bytes
+    pub size: u64,
+    /// Last time the object was stored or resolved
+    pub last_used: SystemTime,
+    /// Number of refs pointing at the object
+    pub refs: usize,
+}
+
+/// Result of [`ArtifactStore::gc`]
+#[derive(Debug, Clone, Default)]
+pub struct StoreGcReport {
+    /// Removed objects
+    pub removed: Vec<StoreObject>,
+    /// Refs dropped together with evicted objects
+    pub released_refs: usize,
+}
+
+impl StoreGcReport {
+    /// Total reclaimed bytes
+    pub fn reclaimed(&self) -> u64 {
+        self.removed.iter().map(|o| o.size).sum()
+    }
+}
+
+/// Content-addressed store shared by all pack caches
+#[derive(Debug, Clone)]
+pub struct ArtifactStore {
+    root: PathBuf,
+    max_size: Option<u64>,
+    max_age: Option<Duration>,
+    remote: Option<RemoteCache>,
+    http: HttpClient,
+}
+
+impl ArtifactStore {
+    /// Open the store described by `[build.store]`
+    ///
+    /// An invalid remote cache is reported and ignored.
+    pub fn open(config: &StoreConfig) -> Self {
+        let remote = config
+            .remote
+            .as_ref()
+            .and_then(|remote| match RemoteCache::new(remote) {
+                Ok(remote) => Some(remote),
+                Err(e) => {
+                    tracing::warn!("Remote cache disabled: {}", e);
+                    None
+                }
+            });
+        Self {
+            remote,
+            root: config.dir.clone().unwrap_or_else(get_store_dir),
+            max_size: config.max_size_mb.map(|mb| mb * 1024 * 1024),
+            max_age: config
+                .max_age_days
+                .map(|days| Duration::from_secs(days * 24 * 60 * 60)),
+            http: HttpClient::default(),
+        }
+    }
+
+    /// Open the store at the default location without limits
+    pub fn open_default() -> Self {
+        Self::open(&StoreConfig::default())
+    }
+
+    /// Open a store in a custom directory without limits
+    pub fn in_dir(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_size: None,
+            max_age: None,
+            remote: None,
+            http: HttpClient::default(),
+        }
+    }
+
+    /// Fetch downloads and remote cache objects with `http` (`[build.http]`)
+    pub fn with_http(mut self, http: HttpClient) -> Self {
+        self.remote = self.remote.map(|remote| remote.with_http(&http));
+        self.http = http;
+        self
+    }
+
+    /// HTTP client of the downloads into this store
+    pub fn http(&self) -> &HttpClient {
+        &self.http
+    }
+
+    /// Store directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether a remote cache is configured
+    pub fn has_remote(&self) -> bool {
+        self.remote.is_some()
+    }
+
+    /// Whether size or age limits are configured
+    pub fn has_limits(&self) -> bool {
+        self.max_size.is_some() || self.max_age.is_some()
+    }
+
+    /// Cache directory for an external tool (e.g., pip's wheel cache)
+    pub fn tool_dir(&self, tool: &str) -> PathBuf {
+        self.root.join("tools").join(safe_name(tool))
+    }
+
+    // ------------------------------------------------------------------------
+    // Blobs
+    // ------------------------------------------------------------------------
+
+    /// Store file content under `name`; returns its SHA-256 digest
+    pub fn insert(&self, name: &str, content: &[u8]) -> PackResult<String> {
+        let digest = format!("{:x}", Sha256::digest(content));
+        self.insert_blob(&digest, name, |path| Ok(fs::write(path, content)?))?;
+        Ok(digest)
+    }
+
+    /// Store a file's content under `name`; returns its SHA-256 digest
+    pub fn insert_file(&self, name: &str, source: &Path) -> PackResult<String> {
+        let mut hasher = Sha256::new();
+        io::copy(&mut File::open(source)?, &mut hasher)?;
+        let digest = format!("{:x}", hasher.finalize());
+        self.insert_blob(&digest, name, |path| {
+            fs::copy(source, path)?;
+            Ok(())
+        })?;
+        Ok(digest)
+    }
+
+    fn insert_blob(
+        &self,
+        digest: &str,
+        name: &str,
+        write: impl FnOnce(&Path) -> PackResult<()>,
+    ) -> PackResult<()> {
+        let dir = self.object_dir(ObjectKind::Blob, digest);
+        let _lock = CacheLock::acquire(&dir)?;
+        if blob_file(&dir).is_some() {
+            self.touch(ObjectKind::Blob, digest);
+            return Ok(());
+        }
+
+        let parent = dir.parent().unwrap_or(&self.root);
+        fs::create_dir_all(parent)?;
+        let staging = tempfile::Builder::new()
+            .prefix(".tmp-")
+            .tempdir_in(parent)?;
+        write(&staging.path().join(safe_name(name)))?;
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::rename(staging.path(), &dir)?;
+        self.touch(ObjectKind::Blob, digest);
+        tracing::debug!("Stored blob {} ({})", digest, name);
+        Ok(())
+    }
+
+    /// Path of a stored blob
+    pub fn blob(&self, digest: &str) -> Option<PathBuf> {
+        let path = blob_file(&self.object_dir(ObjectKind::Blob, digest))?;
+        self.touch(ObjectKind::Blob, digest);
+        Some(path)
+    }
+
+    // ------------------------------------------------------------------------
+    // Trees
+    // ------------------------------------------------------------------------
+
+    /// Tree ID derived from the inputs that determine a directory's content
+    pub fn tree_id(inputs: &[&str]) -> String {
+        let mut hasher = Sha256::new();
+        for input in inputs {
+            hasher.update(input.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Path of a stored tree
+    pub fn tree(&self, id: &str) -> Option<PathBuf> {
+        let dir = self.object_dir(ObjectKind::Tree, id);
+        if !dir.is_dir() {
+            return None;
+        }
+        self.touch(ObjectKind::Tree, id);
+        Some(dir)
+    }
+
+    /// Store a directory produced by `populate`, unless it is stored already
+    ///
+    /// `populate` fills an empty staging directory that is moved into place
+    /// once it returns successfully.
+    pub fn insert_tree(
+        &self,
+        id: &str,
+        populate: impl FnOnce(&Path) -> PackResult<()>,
+    ) -> PackResult<PathBuf> {
+        let dir = self.object_dir(ObjectKind::Tree, id);
+        let _lock = CacheLock::acquire(&dir)?;
+        if dir.is_dir() {
+            self.touch(ObjectKind::Tree, id);
+            return Ok(dir);
+        }
+
+        let parent = dir.parent().unwrap_or(&self.root);
+        fs::create_dir_all(parent)?;
+        let staging = tempfile::Builder::new()
+            .prefix(".tmp-")
+            .tempdir_in(parent)?;
+        let staged = staging.path().join("tree");
+        fs::create_dir_all(&staged)?;
+        populate(&staged)?;
+        fs::rename(&staged, &dir)?;
+        self.touch(ObjectKind::Tree, id);
+        tracing::debug!("Stored tree {}", id);
+        Ok(dir)
+    }
+
+    // ------------------------------------------------------------------------
+    // Refs
+    // ------------------------------------------------------------------------
+
+    /// Point `namespace/name` at an object
+    ///
+    /// The object and ref are also pushed to the remote cache (best effort).
+    pub fn link(&self, namespace: &str, name: &str, kind: ObjectKind, id: &str) -> PackResult<()> {
+        let target = StoreRef {
+            kind,
+            id: id.to_string(),
+        };
+        self.write_ref(namespace, name, &target)?;
+
+        if let Some(remote) = self.remote.as_ref().filter(|r| r.push_enabled()) {
+            if let Err(e) = self.push(remote, namespace, name, &target) {
+                tracing::warn!(
+                    "Failed to push {}/{} to remote cache: {}",
+                    namespace,
+                    name,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve `namespace/name` to the path of its object
+    ///
+    /// Local misses are pulled from the remote cache, if configured.
+    pub fn resolve(&self, namespace: &str, name: &str) -> Option<PathBuf> {
+        let local = self
+            .read_ref(&self.ref_path(namespace, name))
+            .and_then(|target| self.object(&target));
+        if local.is_some() {
+            return local;
+        }
+
+        let remote = self.remote.as_ref()?;
+        self.pull(remote, namespace, name).unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to pull {}/{} from remote cache: {}",
+                namespace,
+                name,
+                e
+            );
+            None
+        })
+    }
+
+    /// Remove `namespace/name`; returns whether it existed
+    ///
+    /// The object stays until [`gc`](Self::gc) finds it unreferenced.
+    pub fn unlink(&self, namespace: &str, name: &str) -> PackResult<bool> {
+        match fs::remove_file(self.ref_path(namespace, name)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Number of refs pointing at an object
+    pub fn ref_count(&self, kind: ObjectKind, id: &str) -> usize {
+        self.refs()
+            .iter()
+            .filter(|(_, target)| target.kind == kind && target.id == id)
+            .count()
+    }
+
+    /// Lock `namespace/name` while producing its object
+    pub fn lock(&self, namespace: &str, name: &str) -> PackResult<CacheLock> {
+        CacheLock::acquire(&self.ref_path(namespace, name))
+    }
+
+    /// A temporary file inside the store (same volume as the objects)
+    pub fn temp_file(&self) -> PackResult<tempfile::TempPath> {
+        let dir = self.root.join("tmp");
+        fs::create_dir_all(&dir)?;
+        Ok(tempfile::Builder::new()
+            .prefix(".tmp-")
+            .tempfile_in(dir)?
+            .into_temp_path())
+    }
+
+    // ------------------------------------------------------------------------
+    // Remote cache
+    // ------------------------------------------------------------------------
+
+    fn pull(
+        &self,
+        remote: &RemoteCache,
+        namespace: &str,
+        name: &str,
+    ) -> PackResult<Option<PathBuf>> {
+        let Some(data) = remote.get(&remote_ref_key(namespace, name))? else {
+            return Ok(None);
+        };
+        let RemoteRef {
+            target,
+            file_name,
+            digest,
+        } = serde_json::from_slice(&data)?;
+
+        match target.kind {
+            ObjectKind::Blob if self.blob(&target.id).is_none() => {
+                let Some(content) = remote.get(&remote_blob_key(&target.id))? else {
+                    return Ok(None);
+                };
+                let digest = format!("{:x}", Sha256::digest(&content));
+                if digest != target.id {
+                    return Err(PackError::Download(format!(
+                        "Remote cache blob {} has digest {}",
+                        target.id, digest
+                    )));
+                }
+                self.insert(file_name.as_deref().unwrap_or(name), &content)?;
+            }
+            ObjectKind::Tree if self.tree(&target.id).is_none() => {
+                let Some(expected) = digest else {
+                    tracing::debug!(
+                        "Remote cache tree {} has no recorded digest, ignoring it",
+                        target.id
+                    );
+                    return Ok(None);
+                };
+                let Some(archive) = remote.get(&remote_tree_key(&expected))? else {
+                    return Ok(None);
+                };
+                let digest = format!("{:x}", Sha256::digest(&archive));
+                if digest != expected {
+                    return Err(PackError::Download(format!(
+                        "Remote cache tree {} has digest {}, expected {}",
+                        target.id, digest, expected
+                    )));
+                }
+                self.insert_tree(&target.id, |dir| {
+                    crate::archive::unpack_tar(zstd::stream::Decoder::new(&archive[..])?, dir, 0)?;
+                    Ok(())
+                })?;
+            }
+            _ => {}
+        }
+
+        self.write_ref(namespace, name, &target)?;
+        tracing::info!("Pulled {}/{} from remote cache", namespace, name);
+        Ok(self.object(&target))
+    }
+
+    fn push(
+        &self,
+        remote: &RemoteCache,
+        namespace: &str,
+        name: &str,
+        target: &StoreRef,
+    ) -> PackResult<()> {
+        let dir = self.object_dir(target.kind, &target.id);
+        let (file_name, digest) = match target.kind {
+            ObjectKind::Blob => {
+                let Some(path) = blob_file(&dir) else {
+                    return Ok(());
+                };
+                let key = remote_blob_key(&target.id);
+                if !remote.exists(&key)? {
+                    remote.put(&key, &fs::read(&path)?)?;
+                }
+                let file_name = path.file_name().and_then(|n| n.to_str()).map(String::from);
+                (file_name, None)
+            }
+            ObjectKind::Tree => {
+                if !dir.is_dir() {
+                    return Ok(());
+                }
+                let mut builder = tar::Builder::new(zstd::stream::Encoder::new(Vec::new(), 3)?);
+                builder.follow_symlinks(false);
+                builder.append_dir_all(".", &dir)?;
+                let archive = builder.into_inner()?.finish()?;
+                let digest = format!("{:x}", Sha256::digest(&archive));
+                let key = remote_tree_key(&digest);
+                if !remote.exists(&key)? {
+                    remote.put(&key, &archive)?;
+                }
+                (None, Some(digest))
+            }
+        };
+
+        let remote_ref = RemoteRef {
+            target: target.clone(),
+            file_name,
+            digest,
+        };
+        remote.put(
+            &remote_ref_key(namespace, name),
+            &serde_json::to_vec(&remote_ref)?,
+        )?;
+        tracing::debug!("Pushed {}/{} to remote cache", namespace, name);
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // Maintenance
+    // ------------------------------------------------------------------------
+
+    /// All stored objects with their sizes and reference counts
+    pub fn objects(&self) -> PackResult<Vec<StoreObject>> {
+        let mut counts: HashMap<(ObjectKind, String), usize> = HashMap::new();
+        for (_, target) in self.refs() {
+            *counts.entry((target.kind, target.id)).or_default() += 1;
+        }
+
+        let mut objects = Vec::new();
+        for (kind, dirs) in [
+            (ObjectKind::Blob, self.blob_dirs()),
+            (ObjectKind::Tree, list_dirs(&self.root.join("trees"))),
+        ] {
+            for path in dirs {
+                let Some(id) = path.file_name().and_then(|n| n.to_str()).map(String::from) else {
+                    continue;
+                };
+                if id.starts_with('.') {
+                    continue;
+                }
+                let last_used = fs::metadata(used_marker(&path))
+                    .or_else(|_| fs::metadata(&path))
+                    .and_then(|m| m.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                objects.push(StoreObject {
+                    kind,
+                    refs: counts.get(&(kind, id.clone())).copied().unwrap_or(0),
+                    id,
+                    size: path_size(&path),
+                    last_used,
+                    path,
+                });
+            }
+        }
+        Ok(objects)
+    }
+
+    /// Total size of the stored objects in bytes
+    pub fn usage(&self) -> PackResult<u64> {
+        Ok(self.objects()?.iter().map(|o| o.size).sum())
+    }
+
+    /// Remove unreferenced objects, then evict least recently used objects
+    /// (and their refs) beyond the configured age and size limits
+    pub fn gc(&self) -> PackResult<StoreGcReport> {
+        let mut report = StoreGcReport::default();
+        let now = SystemTime::now();
+        let idle = |o: &StoreObject| now.duration_since(o.last_used).unwrap_or_default();
+
+        let mut objects = self.objects()?;
+        objects.sort_by_key(|o| o.last_used);
+        let mut total: u64 = objects.iter().map(|o| o.size).sum();
+
+        for object in objects {
+            let evict = idle(&object) >= EVICTION_GRACE
+                && (object.refs == 0
+                    || self.max_age.is_some_and(|age| idle(&object) >= age)
+                    || self.max_size.is_some_and(|max| total > max));
+            if !evict {
+                continue;
+            }
+            // Skip objects being written right now
+            let Some(_lock) = CacheLock::try_acquire(&object.path)? else {
+                continue;
+            };
+
+            if object.refs > 0 {
+                report.released_refs += self.release_refs(object.kind, &object.id)?;
+            }
+            fs::remove_dir_all(&object.path)?;
+            let _ = fs::remove_file(used_marker(&object.path));
+            total = total.saturating_sub(object.size);
+            tracing::debug!("Removed {:?} {} from store", object.kind, object.id);
+            report.removed.push(object);
+        }
+
+        if !report.removed.is_empty() {
+            tracing::info!(
+                "Artifact store: removed {} objects ({:.2} MB), {:.2} MB in use",
+                report.removed.len(),
+                report.reclaimed() as f64 / (1024.0 * 1024.0),
+                total as f64 / (1024.0 * 1024.0)
+            );
+        }
+        Ok(report)
+    }
+
+    // ------------------------------------------------------------------------
+    // Internals
+    // ------------------------------------------------------------------------
+
+    fn object_dir(&self, kind: ObjectKind, id: &str) -> PathBuf {
+        let id = safe_name(id);
+        match kind {
+            ObjectKind::Blob => self
+                .root
+                .join("blobs")
+                .join(id.get(..2).unwrap_or("00"))
+                .join(id),
+            ObjectKind::Tree => self.root.join("trees").join(id),
+        }
+    }
+
+    fn object(&self, target: &StoreRef) -> Option<PathBuf> {
+        match target.kind {
+            ObjectKind::Blob => self.blob(&target.id),
+            ObjectKind::Tree => self.tree(&target.id),
+        }
+    }
+
+    fn write_ref(&self, namespace: &str, name: &str, target: &StoreRef) -> PackResult<()> {
+        write_atomic(
+            &self.ref_path(namespace, name),
+            &serde_json::to_vec(target)?,
+        )
+    }
+
+    fn ref_path(&self, namespace: &str, name: &str) -> PathBuf {
+        self.root
+            .join("refs")
+            .join(safe_name(namespace))
+            .join(safe_name(name))
+    }
+
+    fn read_ref(&self, path: &Path) -> Option<StoreRef> {
+        let mut content = Vec::new();
+        File::open(path).ok()?.read_to_end(&mut content).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    /// All refs with their paths
+    fn refs(&self) -> Vec<(PathBuf, StoreRef)> {
+        list_dirs(&self.root.join("refs"))
+            .iter()
+            .flat_map(|namespace| fs::read_dir(namespace).into_iter().flatten())
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_none_or(|ext| ext != "lock"))
+            .filter_map(|p| self.read_ref(&p).map(|target| (p, target)))
+            .collect()
+    }
+
+    fn release_refs(&self, kind: ObjectKind, id: &str) -> PackResult<usize> {
+        let mut released = 0;
+        for (path, target) in self.refs() {
+            if target.kind == kind && target.id == id {
+                fs::remove_file(&path)?;
+                released += 1;
+            }
+        }
+        Ok(released)
+    }
+
+    fn blob_dirs(&self) -> Vec<PathBuf> {
+        list_dirs(&self.root.join("blobs"))
+            .iter()
+            .flat_map(|shard| list_dirs(shard))
+            .collect()
+    }
+
+    /// Record a use of an object (best effort)
+    fn touch(&self, kind: ObjectKind, id: &str) {
+        let marker = used_marker(&self.object_dir(kind, id));
+        let result = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&marker)
+            .and_then(|f| f.set_modified(SystemTime::now()));
+        if let Err(e) = result {
+            tracing::debug!("Failed to touch {}: {}", marker.display(), e);
+        }
+    }
+}
+
+/// Remote key of a ref
+fn remote_ref_key(namespace: &str, name: &str) -> String {
+    format!("refs/{}/{}.json", safe_name(namespace), safe_name(name))
+}
+
+/// Remote key of a blob
+fn remote_blob_key(digest: &str) -> String {
+    format!("blobs/{}", safe_name(digest))
+}
+
+/// Remote key of a tree archive, by the archive's SHA-256
+fn remote_tree_key(digest: &str) -> String {
+    format!("trees/{}.tar.zst", safe_name(digest))
+}
+
+/// Use marker of an object (`<object>.used`)
+fn used_marker(object_dir: &Path) -> PathBuf {
+    let mut name = object_dir.file_name().unwrap_or_default().to_os_string();
+    name.push(".used");
+    object_dir.with_file_name(name)
+}
+
+/// The file of a blob directory
+fn blob_file(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.is_file())
+}
+
+/// Subdirectories of a directory
+fn list_dirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|rd| {
+            rd.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A store key usable as a single file name
+fn safe_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect();
+    match name.trim_start_matches('.') {
+        "" => "_".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Error for an object that vanished between storing and resolving it
+pub(crate) fn missing_object(namespace: &str, name: &str) -> PackError {
+    PackError::Io(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("Artifact store lost {}/{}", namespace, name),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn age(store: &ArtifactStore, kind: ObjectKind, id: &str, secs: u64) {
+        let marker = used_marker(&store.object_dir(kind, id));
+        File::options()
+            .write(true)
+            .open(marker)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_blobs_are_content_addressed() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::in_dir(temp.path());
+
+        let a = store.insert("a.tar.gz", b"same").unwrap();
+        let b = store.insert("b.tar.gz", b"same").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 64);
+        // The first name is kept, so archive extensions survive
+        assert!(store.blob(&a).unwrap().ends_with("a.tar.gz"));
+
+        store.link("downloads", "a", ObjectKind::Blob, &a).unwrap();
+        store.link("downloads", "b", ObjectKind::Blob, &a).unwrap();
+        assert_eq!(store.ref_count(ObjectKind::Blob, &a), 2);
+        assert_eq!(
+            fs::read(store.resolve("downloads", "b").unwrap()).unwrap(),
+            b"same"
+        );
+        assert!(store.resolve("downloads", "missing").is_none());
+    }
+
+    #[test]
+    fn test_trees_are_populated_once() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::in_dir(temp.path());
+        let id = ArtifactStore::tree_id(&["node_modules", "npm-abc"]);
+
+        let dir = store
+            .insert_tree(&id, |dir| Ok(fs::write(dir.join("pkg.js"), "x")?))
+            .unwrap();
+        assert!(dir.join("pkg.js").is_file());
+        let again = store
+            .insert_tree(&id, |_| panic!("already stored"))
+            .unwrap();
+        assert_eq!(dir, again);
+
+        // Failed population leaves nothing behind
+        let other = ArtifactStore::tree_id(&["other"]);
+        assert!(store
+            .insert_tree(&other, |_| Err(PackError::Config("boom".to_string())))
+            .is_err());
+        assert!(store.tree(&other).is_none());
+    }
+
+    #[test]
+    fn test_gc_removes_unreferenced_and_evicts_lru() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::in_dir(temp.path());
+        let orphan = store.insert("orphan", &[1u8; 100]).unwrap();
+        let old = store.insert("old", &[2u8; 100]).unwrap();
+        let new = store.insert("new", &[3u8; 100]).unwrap();
+        store
+            .link("downloads", "old", ObjectKind::Blob, &old)
+            .unwrap();
+        store
+            .link("downloads", "new", ObjectKind::Blob, &new)
+            .unwrap();
+        age(&store, ObjectKind::Blob, &orphan, 7200);
+        age(&store, ObjectKind::Blob, &old, 7200);
+        age(&store, ObjectKind::Blob, &new, 3600);
+
+        // Without limits only unreferenced objects go
+        let report = store.gc().unwrap();
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].id, orphan);
+        assert_eq!(store.usage().unwrap(), 200);
+
+        let limited = ArtifactStore {
+            max_size: Some(150),
+            ..store.clone()
+        };
+        let report = limited.gc().unwrap();
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].id, old);
+        assert_eq!(report.released_refs, 1);
+        assert!(store.resolve("downloads", "old").is_none());
+        assert!(store.resolve("downloads", "new").is_some());
+    }
+
+    type RemoteObjects = std::sync::Arc<std::sync::Mutex<HashMap<String, Vec<u8>>>>;
+
+    /// Minimal HTTP object server (GET/HEAD/PUT) for remote cache tests
+    fn serve_objects() -> (String, RemoteObjects) {
+        use std::io::{BufRead, BufReader, Write};
+        use std::sync::{Arc, Mutex};
+
+        let objects = Arc::new(Mutex::new(HashMap::<String, Vec<u8>>::new()));
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/cache", listener.local_addr().unwrap());
+        let shared = objects.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let mut parts = request_line.split_whitespace();
+                let (method, path) = (parts.next().unwrap(), parts.next().unwrap().to_string());
+                let mut objects = shared.lock().unwrap();
+                let (status, content) = match method {
+                    "PUT" => {
+                        objects.insert(path, body);
+                        ("200 OK", Vec::new())
+                    }
+                    _ => match objects.get(&path) {
+                        Some(data) if method == "GET" => ("200 OK", data.clone()),
+                        Some(_) => ("200 OK", Vec::new()),
+                        None => ("404 Not Found", Vec::new()),
+                    },
+                };
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    content.len()
+                )
+                .unwrap();
+                stream.write_all(&content).unwrap();
+            }
+        });
+        (url, objects)
+    }
+
+    #[test]
+    fn test_remote_cache_push_and_pull() {
+        let (url, objects) = serve_objects();
+        let config = |dir: &Path, push: bool| StoreConfig {
+            dir: Some(dir.to_path_buf()),
+            remote: Some(RemoteCacheConfig {
+                url: url.clone(),
+                push,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // A first runner downloads and builds, pushing to the remote
+        let first = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::open(&config(first.path(), true));
+        let digest = store.insert("python.tar.gz", b"runtime").unwrap();
+        store
+            .link("python", "cpython-3.12", ObjectKind::Blob, &digest)
+            .unwrap();
+        let id = ArtifactStore::tree_id(&["backend", "v1"]);
+        store
+            .insert_tree(&id, |dir| {
+                fs::create_dir_all(dir.join("bin"))?;
+                Ok(fs::write(dir.join("bin").join("server"), "binary")?)
+            })
+            .unwrap();
+        store
+            .link("backends", "app", ObjectKind::Tree, &id)
+            .unwrap();
+        assert!(objects
+            .lock()
+            .unwrap()
+            .contains_key("/cache/refs/python/cpython-3.12.json"));
+
+        // A fresh runner pulls instead of rebuilding
+        let second = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::open(&config(second.path(), false));
+        let blob = store.resolve("python", "cpython-3.12").unwrap();
+        assert!(blob.ends_with("python.tar.gz"));
+        assert_eq!(fs::read(blob).unwrap(), b"runtime");
+        let tree = store.resolve("backends", "app").unwrap();
+        assert_eq!(
+            fs::read_to_string(tree.join("bin").join("server")).unwrap(),
+            "binary"
+        );
+        assert!(store.resolve("python", "missing").is_none());
+
+        // Read-only runners do not push
+        let count = objects.lock().unwrap().len();
+        let digest = store.insert("other", b"other").unwrap();
+        store
+            .link("python", "other", ObjectKind::Blob, &digest)
+            .unwrap();
+        assert_eq!(objects.lock().unwrap().len(), count);
+
+        // Corrupted blobs are rejected
+        objects
+            .lock()
+            .unwrap()
+            .insert(format!("/cache/blobs/{}", digest), b"tampered".to_vec());
+        let body = serde_json::to_vec(&StoreRef {
+            kind: ObjectKind::Blob,
+            id: digest,
+        })
+        .unwrap();
+        objects
+            .lock()
+            .unwrap()
+            .insert("/cache/refs/python/other.json".to_string(), body);
+        let third = tempfile::tempdir().unwrap();
+        let store = ArtifactStore::open(&config(third.path(), false));
+        assert!(store.resolve("python", "other").is_none());
+
+        // So are corrupted tree archives
+        for (key, body) in objects.lock().unwrap().iter_mut() {
+            if key.starts_with("/cache/trees/") {
+                *body = b"tampered".to_vec();
+            }
+        }
+        assert!(store.resolve("backends", "app").is_none());
+    }
+}
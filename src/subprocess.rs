@@ -0,0 +1,337 @@
+//! Subprocess abstraction
+//!
+//! The packer, [`DepsCollector`](crate::DepsCollector) and
+//! [`ResourceEditor`](crate::ResourceEditor) run Python, pip, uv, rcedit
+//! and tool version checks through a [`CommandRunner`]. [`SystemRunner`]
+//! spawns the commands; [`RecordingRunner`] records them and answers with
+//! canned results, so failure paths can be tested without the tools
+//! installed. Downstream apps can implement the trait to sandbox or audit
+//! every subprocess a pack starts.
+//!
+//! Hook commands (`[hooks]`) are not routed through the runner: they are
+//! supervised by their own timeout and output limits.
+//!
+//! ```
+//! use auroraview_pack::{CommandRunner, MockOutcome, RecordingRunner};
+//! use std::process::Command;
+//!
+//! let runner = RecordingRunner::new()
+//!     .on("python -m pip", MockOutcome::failure(1, "no network"));
+//! let output = runner
+//!     .output(Command::new("python").args(["-m", "pip", "install", "requests"]))
+//!     .unwrap();
+//! assert!(!output.status.success());
+//! assert_eq!(runner.command_lines(), ["python -m pip install requests"]);
+//! ```
+
+use std::fmt;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Interval between checks of a process run with a timeout
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs subprocesses on behalf of the packer
+pub trait CommandRunner: Send + Sync + fmt::Debug {
+    /// Run `command` to completion, capturing stdout and stderr
+    fn output(&self, command: &mut Command) -> io::Result<Output>;
+
+    /// Run `command` to completion with its configured (by default
+    /// inherited) stdio
+    fn status(&self, command: &mut Command) -> io::Result<ExitStatus>;
+
+    /// Like [`output`](Self::output), killing the process after `timeout`
+    /// (an `io::ErrorKind::TimedOut` error)
+    ///
+    /// Runners that cannot enforce a timeout run the command to completion.
+    fn output_with_timeout(&self, command: &mut Command, timeout: Duration) -> io::Result<Output> {
+        let _ = timeout;
+        self.output(command)
+    }
+}
+
+/// Runner spawning real processes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        command.output()
+    }
+
+    fn status(&self, command: &mut Command) -> io::Result<ExitStatus> {
+        command.status()
+    }
+
+    fn output_with_timeout(&self, command: &mut Command, timeout: Duration) -> io::Result<Output> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdout = read_to_end(child.stdout.take());
+        let stderr = read_to_end(child.stderr.take());
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("timed out after {} s", timeout.as_secs()),
+                ));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        };
+        let join = |reader: JoinHandle<Vec<u8>>| reader.join().unwrap_or_default();
+        Ok(Output {
+            status,
+            stdout: join(stdout),
+            stderr: join(stderr),
+        })
+    }
+}
+
+/// Read a child's output stream on a thread
+fn read_to_end(stream: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut data = Vec::new();
+        if let Some(mut stream) = stream {
+            let _ = stream.read_to_end(&mut data);
+        }
+        data
+    })
+}
+
+/// Log the captured output of `program` at debug level, line by line
+///
+/// Subprocess output never goes to the caller's terminal.
+pub(crate) fn log_output(program: &str, output: &Output) {
+    for stream in [&output.stdout, &output.stderr] {
+        for line in String::from_utf8_lossy(stream).lines() {
+            if !line.trim().is_empty() {
+                tracing::debug!("[{}] {}", program, line);
+            }
+        }
+    }
+}
+
+/// Last lines of the captured stderr, for error messages
+pub(crate) fn stderr_tail(output: &Output) -> String {
+    const TAIL_LINES: usize = 20;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stderr.lines().collect();
+    lines[lines.len().saturating_sub(TAIL_LINES)..].join("\n")
+}
+
+/// Shared [`SystemRunner`], the default runner of every component
+pub(crate) fn system_runner() -> Arc<dyn CommandRunner> {
+    static RUNNER: OnceLock<Arc<dyn CommandRunner>> = OnceLock::new();
+    RUNNER.get_or_init(|| Arc::new(SystemRunner)).clone()
+}
+
+/// Result a [`RecordingRunner`] answers a command with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockOutcome {
+    /// The command ran and exited with `code`
+    Exit {
+        /// Exit code
+        code: i32,
+        /// Captured standard output
+        stdout: Vec<u8>,
+        /// Captured standard error
+        stderr: Vec<u8>,
+    },
+    /// The program could not be started (`io::ErrorKind::NotFound`)
+    NotFound,
+}
+
+impl MockOutcome {
+    /// Exit code 0 with `stdout`
+    pub fn success(stdout: impl Into<Vec<u8>>) -> Self {
+        Self::Exit {
+            code: 0,
+            stdout: stdout.into(),
+            stderr: Vec::new(),
+        }
+    }
+
+    /// Exit with `code` and `stderr`
+    pub fn failure(code: i32, stderr: impl Into<Vec<u8>>) -> Self {
+        Self::Exit {
+            code,
+            stdout: Vec::new(),
+            stderr: stderr.into(),
+        }
+    }
+}
+
+/// Command run through a [`RecordingRunner`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedCommand {
+    /// Program, as given to `Command::new`
+    pub program: String,
+    /// Arguments
+    pub args: Vec<String>,
+    /// Environment variables set on the command (`None`: removed)
+    pub env: Vec<(String, Option<String>)>,
+    /// Working directory, if set
+    pub cwd: Option<PathBuf>,
+}
+
+impl RecordedCommand {
+    fn new(command: &Command) -> Self {
+        let lossy = |s: &std::ffi::OsStr| s.to_string_lossy().into_owned();
+        Self {
+            program: lossy(command.get_program()),
+            args: command.get_args().map(lossy).collect(),
+            env: command
+                .get_envs()
+                .map(|(name, value)| (lossy(name), value.map(lossy)))
+                .collect(),
+            cwd: command.get_current_dir().map(PathBuf::from),
+        }
+    }
+
+    /// Program and arguments joined by spaces
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Runner recording every command instead of running it
+///
+/// A command is answered by the first rule whose prefix its
+/// [`command_line`](RecordedCommand::command_line) starts with, or by the
+/// default outcome (exit code 0, no output).
+#[derive(Debug)]
+pub struct RecordingRunner {
+    rules: Vec<(String, MockOutcome)>,
+    default: MockOutcome,
+    commands: Mutex<Vec<RecordedCommand>>,
+}
+
+impl Default for RecordingRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordingRunner {
+    /// Runner answering every command with success
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            default: MockOutcome::success(Vec::new()),
+            commands: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Answer commands starting with `prefix` (e.g. `"python -m pip"`)
+    /// with `outcome`
+    pub fn on(mut self, prefix: impl Into<String>, outcome: MockOutcome) -> Self {
+        self.rules.push((prefix.into(), outcome));
+        self
+    }
+
+    /// Answer commands no rule matches with `outcome`
+    pub fn with_default(mut self, outcome: MockOutcome) -> Self {
+        self.default = outcome;
+        self
+    }
+
+    /// Commands run so far, in order
+    pub fn commands(&self) -> Vec<RecordedCommand> {
+        self.lock().clone()
+    }
+
+    /// Command lines run so far, in order
+    pub fn command_lines(&self) -> Vec<String> {
+        self.lock()
+            .iter()
+            .map(RecordedCommand::command_line)
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<RecordedCommand>> {
+        self.commands.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record `command` and look up its outcome
+    fn run(&self, command: &Command) -> io::Result<(ExitStatus, Vec<u8>, Vec<u8>)> {
+        let recorded = RecordedCommand::new(command);
+        let line = recorded.command_line();
+        self.lock().push(recorded);
+        let outcome = self
+            .rules
+            .iter()
+            .find(|(prefix, _)| line.starts_with(prefix.as_str()))
+            .map_or(&self.default, |(_, outcome)| outcome);
+        match outcome {
+            MockOutcome::Exit {
+                code,
+                stdout,
+                stderr,
+            } => Ok((exit_status(*code), stdout.clone(), stderr.clone())),
+            MockOutcome::NotFound => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "program not found: {}",
+                    command.get_program().to_string_lossy()
+                ),
+            )),
+        }
+    }
+}
+
+impl CommandRunner for RecordingRunner {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        let (status, stdout, stderr) = self.run(command)?;
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    fn status(&self, command: &mut Command) -> io::Result<ExitStatus> {
+        self.run(command).map(|(status, _, _)| status)
+    }
+}
+
+impl<T: CommandRunner + ?Sized> CommandRunner for Arc<T> {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        (**self).output(command)
+    }
+
+    fn status(&self, command: &mut Command) -> io::Result<ExitStatus> {
+        (**self).status(command)
+    }
+
+    fn output_with_timeout(&self, command: &mut Command, timeout: Duration) -> io::Result<Output> {
+        (**self).output_with_timeout(command, timeout)
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    // Wait status: the exit code is in the second byte
+    ExitStatus::from_raw((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
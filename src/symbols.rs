@@ -0,0 +1,618 @@
+//! Debug symbol splitting for the launcher and backend binaries
+//!
+//! With `[build.symbols]`, the launcher (the shell) and Process-mode backend
+//! binaries (Rust, Go, ...) are stripped before bundling, and their debug
+//! files are collected into a separate symbols artifact, keyed by build id
+//! in the layouts symbol servers understand:
+//!
+//! ```text
+//! <output_dir>/<name>-symbols/
+//! ├── symbols.json                         - Index (build ids, symbol server)
+//! ├── <pdb name>/<GUID><age>/<pdb name>    - PE/PDB (symstore layout)
+//! ├── buildid/<build id>/debuginfo         - ELF/DWARF (debuginfod layout)
+//! └── <uuid>/<name>.dSYM/                  - Mach-O dSYM bundles
+//! ```
+//!
+//! The index without the debug files is also embedded in the overlay under
+//! [`SYMBOLS_INFO_PATH`], so crash reports can name the exact build ids.
+
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Overlay path of the embedded symbol index
+pub const SYMBOLS_INFO_PATH: &str = "symbols.json";
+
+/// ELF note type of the GNU build id
+const NT_GNU_BUILD_ID: u32 = 3;
+
+/// Mach-O load command carrying the image UUID
+const LC_UUID: u32 = 0x1b;
+
+/// PE debug directory entry type for CodeView records
+const IMAGE_DEBUG_TYPE_CODEVIEW: u32 = 2;
+
+/// Debug symbol configuration
+///
+/// Located at `[build.symbols]` in TOML.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SymbolsConfig {
+    /// Strip debug info from bundled binaries (ELF/Mach-O; PE keeps it in PDBs)
+    pub strip: bool,
+
+    /// Collect PDB/dSYM/DWARF files into the symbols directory
+    pub collect: bool,
+
+    /// Symbols directory (default: `<output_dir>/<name>-symbols`)
+    pub dir: Option<PathBuf>,
+
+    /// Symbol server URL recorded in the index (e.g., for crash reporting)
+    pub symbol_server: Option<String>,
+}
+
+impl Default for SymbolsConfig {
+    fn default() -> Self {
+        Self {
+            strip: true,
+            collect: true,
+            dir: None,
+            symbol_server: None,
+        }
+    }
+}
+
+/// Binary format of a processed binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryFormat {
+    /// Windows PE (symbols in a PDB)
+    Pe,
+    /// Linux ELF (DWARF)
+    Elf,
+    /// macOS Mach-O (dSYM bundle)
+    MachO,
+    /// Unrecognized format (keyed by content hash)
+    Unknown,
+}
+
+/// Identity of a binary for symbol lookup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildId {
+    /// Binary format
+    pub format: BinaryFormat,
+    /// Build id (GNU build id, PDB GUID+age, Mach-O UUID or BLAKE3 hash)
+    pub id: String,
+    /// PDB path recorded in the executable (PE only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdb_path: Option<String>,
+}
+
+/// One processed binary in the symbol index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolEntry {
+    /// Bundled name (e.g., "launcher" or the backend command)
+    pub name: String,
+    /// Build identity
+    pub build_id: BuildId,
+    /// Debug file relative to the symbols directory (if collected)
+    pub debug_file: Option<String>,
+    /// Whether the bundled copy was stripped
+    pub stripped: bool,
+}
+
+/// Symbol index written next to the symbols and into the overlay
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolIndex {
+    /// Application (output) name
+    pub app: String,
+    /// Symbol server URL
+    pub symbol_server: Option<String>,
+    /// Processed binaries
+    pub entries: Vec<SymbolEntry>,
+}
+
+/// Read the build id of a binary
+///
+/// Falls back to a BLAKE3 content hash for formats without a native id.
+pub fn read_build_id(path: &Path) -> PackResult<BuildId> {
+    let data = fs::read(path)?;
+    let native = match detect_format(&data) {
+        BinaryFormat::Elf => elf_build_id(&data).map(|id| (BinaryFormat::Elf, id, None)),
+        BinaryFormat::Pe => pe_codeview(&data).map(|(id, pdb)| (BinaryFormat::Pe, id, Some(pdb))),
+        BinaryFormat::MachO => macho_uuid(&data).map(|id| (BinaryFormat::MachO, id, None)),
+        BinaryFormat::Unknown => None,
+    };
+
+    Ok(match native {
+        Some((format, id, pdb_path)) => BuildId {
+            format,
+            id,
+            pdb_path,
+        },
+        None => BuildId {
+            format: BinaryFormat::Unknown,
+            id: blake3::hash(&data).to_hex().to_string(),
+            pdb_path: None,
+        },
+    })
+}
+
+/// Detect the format of a native binary from its magic bytes
+pub(crate) fn detect_format(data: &[u8]) -> BinaryFormat {
+    if data.starts_with(b"\x7fELF") {
+        BinaryFormat::Elf
+    } else if data.starts_with(b"MZ") {
+        BinaryFormat::Pe
+    } else if data.starts_with(&[0xcf, 0xfa, 0xed, 0xfe])
+        || data.starts_with(&[0xce, 0xfa, 0xed, 0xfe])
+    {
+        BinaryFormat::MachO
+    } else {
+        BinaryFormat::Unknown
+    }
+}
+
+/// Split symbols of one binary
+///
+/// `source` is the binary as built (next to its PDB/dSYM), `bundled` the copy
+/// that ships and is stripped in place.
+pub(crate) fn split_symbols(
+    config: &SymbolsConfig,
+    symbols_dir: &Path,
+    name: &str,
+    source: &Path,
+    bundled: &Path,
+) -> PackResult<SymbolEntry> {
+    let build_id = read_build_id(source)?;
+
+    let debug_file = if config.collect {
+        collect_debug_file(&build_id, symbols_dir, source)?
+    } else {
+        None
+    };
+
+    let stripped = config.strip && strip_binary(build_id.format, bundled)?;
+
+    tracing::info!(
+        "Symbols for {}: {:?} build id {}{}{}",
+        name,
+        build_id.format,
+        build_id.id,
+        debug_file
+            .as_deref()
+            .map(|f| format!(", collected {}", f))
+            .unwrap_or_default(),
+        if stripped { ", stripped" } else { "" }
+    );
+
+    Ok(SymbolEntry {
+        name: name.to_string(),
+        build_id,
+        debug_file,
+        stripped,
+    })
+}
+
+/// Write the symbol index into the symbols directory
+pub(crate) fn write_index(symbols_dir: &Path, index: &SymbolIndex) -> PackResult<()> {
+    fs::create_dir_all(symbols_dir)?;
+    fs::write(
+        symbols_dir.join("symbols.json"),
+        serde_json::to_vec_pretty(index)?,
+    )?;
+    Ok(())
+}
+
+/// Copy the debug file of a binary into its symbol server location
+fn collect_debug_file(
+    build_id: &BuildId,
+    symbols_dir: &Path,
+    source: &Path,
+) -> PackResult<Option<String>> {
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let (found, rel): (PathBuf, PathBuf) = match build_id.format {
+        BinaryFormat::Pe => {
+            // The recorded PDB path is valid on the build machine
+            let recorded = build_id.pdb_path.as_deref().map(PathBuf::from);
+            let sibling = source.with_extension("pdb");
+            let Some(pdb) = recorded.filter(|p| p.is_file()).or_else(|| {
+                let name = build_id
+                    .pdb_path
+                    .as_deref()
+                    .and_then(|p| p.rsplit(['/', '\\']).next())
+                    .map(|n| source.with_file_name(n));
+                name.filter(|p| p.is_file())
+                    .or_else(|| sibling.is_file().then_some(sibling))
+            }) else {
+                tracing::warn!("No PDB found for {}", source.display());
+                return Ok(None);
+            };
+            let pdb_name = pdb
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let rel = Path::new(&pdb_name).join(&build_id.id).join(&pdb_name);
+            (pdb, rel)
+        }
+        BinaryFormat::Elf => {
+            // Separate debug files (objcopy --only-keep-debug) or the
+            // unstripped binary itself, which debuginfod serves as-is
+            let separate = PathBuf::from(format!("{}.debug", source.display()));
+            let found = if separate.is_file() {
+                separate
+            } else if elf_has_debug_info(&fs::read(source)?) {
+                source.to_path_buf()
+            } else {
+                tracing::warn!("No DWARF debug info in {}", source.display());
+                return Ok(None);
+            };
+            let rel = Path::new("buildid").join(&build_id.id).join("debuginfo");
+            (found, rel)
+        }
+        BinaryFormat::MachO => {
+            let dsym = PathBuf::from(format!("{}.dSYM", source.display()));
+            if !dsym.is_dir() {
+                tracing::warn!("No dSYM bundle found for {}", source.display());
+                return Ok(None);
+            }
+            let rel = Path::new(&build_id.id).join(format!("{}.dSYM", file_name));
+            (dsym, rel)
+        }
+        BinaryFormat::Unknown => return Ok(None),
+    };
+
+    let dest = symbols_dir.join(&rel);
+    if found.is_dir() {
+        copy_dir(&found, &dest)?;
+    } else {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&found, &dest)?;
+    }
+    Ok(Some(rel.to_string_lossy().replace('\\', "/")))
+}
+
+/// Strip debug info from a binary in place; returns whether it was stripped
+fn strip_binary(format: BinaryFormat, path: &Path) -> PackResult<bool> {
+    let args: &[&str] = match format {
+        BinaryFormat::Elf => &["--strip-debug"],
+        BinaryFormat::MachO => &["-S"],
+        // PE debug info lives in the PDB, not the executable
+        BinaryFormat::Pe | BinaryFormat::Unknown => return Ok(false),
+    };
+
+    let output = Command::new("strip")
+        .args(args)
+        .arg(path)
+        .output()
+        .map_err(|e| {
+            PackError::Config(format!(
+                "Failed to run `strip` for {} (install binutils/Xcode tools or set \
+                 [build.symbols] strip = false): {}",
+                path.display(),
+                e
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(PackError::Config(format!(
+            "strip failed for {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(true)
+}
+
+/// Recursively copy a directory (dSYM bundles)
+fn copy_dir(src: &Path, dest: &Path) -> PackResult<()> {
+    for entry in walkdir::WalkDir::new(src) {
+        let entry = entry.map_err(|e| PackError::Io(e.into()))?;
+        let rel = entry.path().strip_prefix(src).unwrap_or(entry.path());
+        let target = dest.join(rel);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Binary Parsing
+// ============================================================================
+
+/// Little/big-endian integer reader over a byte slice
+#[derive(Clone, Copy)]
+struct Reader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn bytes(&self, offset: usize, len: usize) -> Option<&[u8]> {
+        self.data.get(offset..offset.checked_add(len)?)
+    }
+
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let b: [u8; 2] = self.bytes(offset, 2)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let b: [u8; 4] = self.bytes(offset, 4)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        })
+    }
+
+    fn u64(&self, offset: usize) -> Option<u64> {
+        let b: [u8; 8] = self.bytes(offset, 8)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u64::from_be_bytes(b)
+        } else {
+            u64::from_le_bytes(b)
+        })
+    }
+}
+
+/// ELF section header fields needed here
+struct ElfSection {
+    name: u32,
+    kind: u32,
+    offset: usize,
+    size: usize,
+}
+
+/// Parse ELF section headers
+fn elf_sections(data: &[u8]) -> Option<(Reader<'_>, Vec<ElfSection>, usize)> {
+    let is_64 = *data.get(4)? == 2;
+    let r = Reader {
+        data,
+        big_endian: *data.get(5)? == 2,
+    };
+
+    let (shoff, shentsize, shnum, shstrndx) = if is_64 {
+        (
+            r.u64(0x28)? as usize,
+            r.u16(0x3a)?,
+            r.u16(0x3c)?,
+            r.u16(0x3e)?,
+        )
+    } else {
+        (
+            r.u32(0x20)? as usize,
+            r.u16(0x2e)?,
+            r.u16(0x30)?,
+            r.u16(0x32)?,
+        )
+    };
+
+    let sections = (0..shnum as usize)
+        .filter_map(|i| {
+            let base = shoff.checked_add(i.checked_mul(shentsize as usize)?)?;
+            Some(if is_64 {
+                ElfSection {
+                    name: r.u32(base)?,
+                    kind: r.u32(base + 4)?,
+                    offset: r.u64(base + 0x18)? as usize,
+                    size: r.u64(base + 0x20)? as usize,
+                }
+            } else {
+                ElfSection {
+                    name: r.u32(base)?,
+                    kind: r.u32(base + 4)?,
+                    offset: r.u32(base + 0x10)? as usize,
+                    size: r.u32(base + 0x14)? as usize,
+                }
+            })
+        })
+        .collect();
+    Some((r, sections, shstrndx as usize))
+}
+
+/// Read the GNU build id note of an ELF file
+fn elf_build_id(data: &[u8]) -> Option<String> {
+    let (r, sections, _) = elf_sections(data)?;
+    // SHT_NOTE
+    for section in sections.iter().filter(|s| s.kind == 7) {
+        let mut pos = section.offset;
+        let end = section.offset.checked_add(section.size)?;
+        while pos + 12 <= end {
+            let namesz = r.u32(pos)? as usize;
+            let descsz = r.u32(pos + 4)? as usize;
+            let kind = r.u32(pos + 8)?;
+            let name_off = pos + 12;
+            let desc_off = name_off + namesz.div_ceil(4) * 4;
+            if kind == NT_GNU_BUILD_ID && r.bytes(name_off, namesz)? == b"GNU\0" {
+                return Some(hex(r.bytes(desc_off, descsz)?));
+            }
+            pos = desc_off + descsz.div_ceil(4) * 4;
+        }
+    }
+    None
+}
+
+/// Check whether an ELF file carries DWARF debug info
+fn elf_has_debug_info(data: &[u8]) -> bool {
+    let Some((r, sections, shstrndx)) = elf_sections(data) else {
+        return false;
+    };
+    let Some(strtab) = sections.get(shstrndx) else {
+        return false;
+    };
+    sections.iter().any(|s| {
+        let start = strtab.offset + s.name as usize;
+        r.bytes(start, ".debug_info".len() + 1)
+            .is_some_and(|name| name == b".debug_info\0")
+    })
+}
+
+/// Read the CodeView (RSDS) record of a PE file: symstore key and PDB path
+fn pe_codeview(data: &[u8]) -> Option<(String, String)> {
+    let r = Reader {
+        data,
+        big_endian: false,
+    };
+    let pe = r.u32(0x3c)? as usize;
+    if r.bytes(pe, 4)? != b"PE\0\0" {
+        return None;
+    }
+    let coff = pe + 4;
+    let num_sections = r.u16(coff + 2)? as usize;
+    let opt_size = r.u16(coff + 16)? as usize;
+    let opt = coff + 20;
+    let dirs = match r.u16(opt)? {
+        0x10b => opt + 96,
+        0x20b => opt + 112,
+        _ => return None,
+    };
+    // Data directory 6: debug
+    let debug_rva = r.u32(dirs + 6 * 8)?;
+    let debug_size = r.u32(dirs + 6 * 8 + 4)? as usize;
+
+    let section_table = opt + opt_size;
+    let rva_to_offset = |rva: u32| -> Option<usize> {
+        (0..num_sections).find_map(|i| {
+            let s = section_table + i * 40;
+            let va = r.u32(s + 12)?;
+            let raw_size = r.u32(s + 16)?;
+            let raw_ptr = r.u32(s + 20)?;
+            let delta = rva.checked_sub(va).filter(|&delta| delta < raw_size)?;
+            raw_ptr.checked_add(delta).map(|offset| offset as usize)
+        })
+    };
+
+    let debug_dir = rva_to_offset(debug_rva)?;
+    for i in 0..debug_size / 28 {
+        let entry = debug_dir + i * 28;
+        if r.u32(entry + 12)? != IMAGE_DEBUG_TYPE_CODEVIEW {
+            continue;
+        }
+        let ptr = r.u32(entry + 24)? as usize;
+        let size = r.u32(entry + 16)? as usize;
+        let record = r.bytes(ptr, size)?;
+        if record.len() < 24 || &record[..4] != b"RSDS" {
+            continue;
+        }
+        let g = &record[4..20];
+        let guid = format!(
+            "{:08X}{:04X}{:04X}{}",
+            u32::from_le_bytes(g[0..4].try_into().ok()?),
+            u16::from_le_bytes(g[4..6].try_into().ok()?),
+            u16::from_le_bytes(g[6..8].try_into().ok()?),
+            hex(&g[8..16]).to_uppercase()
+        );
+        let age = u32::from_le_bytes(record[20..24].try_into().ok()?);
+        let path = record[24..].split(|&b| b == 0).next().unwrap_or_default();
+        return Some((
+            format!("{}{:X}", guid, age),
+            String::from_utf8_lossy(path).to_string(),
+        ));
+    }
+    None
+}
+
+/// Read the LC_UUID of a (thin) Mach-O file
+fn macho_uuid(data: &[u8]) -> Option<String> {
+    let r = Reader {
+        data,
+        big_endian: false,
+    };
+    let header_size = if data.starts_with(&[0xcf, 0xfa, 0xed, 0xfe]) {
+        32
+    } else {
+        28
+    };
+    let ncmds = r.u32(16)? as usize;
+    let mut pos = header_size;
+    for _ in 0..ncmds {
+        let cmd = r.u32(pos)?;
+        let cmdsize = r.u32(pos + 4)? as usize;
+        if cmd == LC_UUID {
+            return Some(hex(r.bytes(pos + 8, 16)?).to_uppercase());
+        }
+        if cmdsize == 0 {
+            return None;
+        }
+        pos += cmdsize;
+    }
+    None
+}
+
+/// Lowercase hex encoding
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_format_uses_content_hash() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("backend.bin");
+        fs::write(&path, b"not a binary").unwrap();
+
+        let id = read_build_id(&path).unwrap();
+        assert_eq!(id.format, BinaryFormat::Unknown);
+        assert_eq!(id.id, blake3::hash(b"not a binary").to_hex().to_string());
+    }
+
+    #[test]
+    fn test_macho_uuid() {
+        let mut data = vec![0u8; 32 + 24];
+        data[..4].copy_from_slice(&[0xcf, 0xfa, 0xed, 0xfe]);
+        data[16..20].copy_from_slice(&1u32.to_le_bytes());
+        data[32..36].copy_from_slice(&LC_UUID.to_le_bytes());
+        data[36..40].copy_from_slice(&24u32.to_le_bytes());
+        data[40..56].copy_from_slice(&[0xab; 16]);
+        assert_eq!(macho_uuid(&data).unwrap(), "AB".repeat(16));
+    }
+
+    #[test]
+    fn test_pe_codeview_rejects_overflowing_sections() {
+        let mut data = vec![0u8; 0x200];
+        data[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        data[0x40..0x44].copy_from_slice(b"PE\0\0");
+        data[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+        data[0x54..0x56].copy_from_slice(&0xf0u16.to_le_bytes());
+        data[0x58..0x5a].copy_from_slice(&0x20bu16.to_le_bytes());
+        // Debug directory and a section whose ranges overflow u32
+        data[0xf8..0xfc].copy_from_slice(&0xffff_fff0u32.to_le_bytes());
+        data[0xfc..0x100].copy_from_slice(&28u32.to_le_bytes());
+        data[0x154..0x158].copy_from_slice(&0xffff_ff00u32.to_le_bytes());
+        data[0x158..0x15c].copy_from_slice(&0x1000u32.to_le_bytes());
+        data[0x15c..0x160].copy_from_slice(&0xffff_ffffu32.to_le_bytes());
+        assert!(pe_codeview(&data).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_elf_build_id_of_current_exe() {
+        // Test binaries are linked by the system linker, which usually
+        // emits a GNU build id; the parser must not fail either way
+        let exe = std::env::current_exe().unwrap();
+        let id = read_build_id(&exe).unwrap();
+        assert!(matches!(
+            id.format,
+            BinaryFormat::Elf | BinaryFormat::Unknown
+        ));
+        assert!(!id.id.is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+//! Launcher scripts of the system Python strategy
+//!
+//! `strategy = "system"` packs rely on the user's Python. Next to the
+//! launcher, the pack writes `launch.sh` / `launch.cmd`, which:
+//!
+//! 1. Look for a Python matching `[backend.python] version` (major.minor)
+//! 2. Print install instructions for the platform and exit if none is found
+//! 3. With `[backend.python.system] venv = true`, create a virtual
+//!    environment from `requirements.txt` on first run and use it
+//! 4. Start the launcher with the chosen interpreter in
+//!    [`SYSTEM_PYTHON_ENV`]
+//!
+//! ```toml
+//! [backend.python]
+//! strategy = "system"
+//! version = "3.11"
+//!
+//! [backend.python.system]
+//! venv = true          # Create .venv from requirements.txt on first run
+//! venv_dir = ".venv"   # Relative to the app directory
+//! ```
+
+use crate::config::PythonBundleConfig;
+use crate::python_abi::major_minor;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path};
+
+/// Interpreter the launcher scripts hand to the launcher
+pub const SYSTEM_PYTHON_ENV: &str = "AURORAVIEW_PYTHON";
+
+/// Launcher script for Linux/macOS
+pub const LAUNCH_SCRIPT_SH: &str = "launch.sh";
+
+/// Launcher script for Windows
+pub const LAUNCH_SCRIPT_CMD: &str = "launch.cmd";
+
+/// System Python configuration
+///
+/// Located at `[backend.python.system]` in TOML.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SystemPythonConfig {
+    /// Create a virtual environment from `requirements.txt` on first run
+    pub venv: bool,
+
+    /// Virtual environment directory, relative to the app directory
+    pub venv_dir: String,
+}
+
+impl Default for SystemPythonConfig {
+    fn default() -> Self {
+        Self {
+            venv: false,
+            venv_dir: ".venv".to_string(),
+        }
+    }
+}
+
+impl SystemPythonConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        let dir = Path::new(&self.venv_dir);
+        let inside = !self.venv_dir.trim().is_empty()
+            && dir
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if !inside {
+            return Err(PackError::Config(format!(
+                "[backend.python.system] venv_dir '{}' must be a relative path inside the app directory",
+                self.venv_dir
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Write the launcher scripts into an app directory
+pub(crate) fn write_scripts(
+    app_dir: &Path,
+    exe_name: &str,
+    python: &PythonBundleConfig,
+) -> PackResult<()> {
+    let system = python.system.clone().unwrap_or_default();
+    let (major, minor) = major_minor(&python.version)?;
+    let has_requirements = app_dir.join("requirements.txt").is_file();
+    let script = LaunchScript {
+        exe_name,
+        version: format!("{}.{}", major, minor),
+        version_tuple: format!("({}, {})", major, minor),
+        venv: system.venv.then_some(system.venv_dir.as_str()),
+        has_requirements,
+    };
+
+    let sh_path = app_dir.join(LAUNCH_SCRIPT_SH);
+    fs::write(&sh_path, script.shell())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&sh_path, fs::Permissions::from_mode(0o755))?;
+    }
+    fs::write(app_dir.join(LAUNCH_SCRIPT_CMD), script.cmd())?;
+
+    tracing::info!(
+        "Generated system Python launcher scripts (Python {}{})",
+        script.version,
+        if system.venv { ", venv" } else { "" }
+    );
+    Ok(())
+}
+
+struct LaunchScript<'a> {
+    exe_name: &'a str,
+    version: String,
+    version_tuple: String,
+    venv: Option<&'a str>,
+    has_requirements: bool,
+}
+
+impl LaunchScript<'_> {
+    fn shell(&self) -> String {
+        let version = &self.version;
+        let mut script = format!(
+            r#"#!/bin/sh
+# Generated by auroraview-pack: checks the system Python, then starts the app
+APP_DIR="$(cd "$(dirname "$0")" && pwd)"
+
+PYTHON=""
+for candidate in python{version} python3 python; do
+    if command -v "$candidate" >/dev/null 2>&1 &&
+        "$candidate" -c "import sys; sys.exit(sys.version_info[:2] != {tuple})" 2>/dev/null; then
+        PYTHON="$(command -v "$candidate")"
+        break
+    fi
+done
+
+if [ -z "$PYTHON" ]; then
+    echo "error: Python {version} is required but was not found in PATH" >&2
+    echo "Install it with one of:" >&2
+    echo "  macOS:          brew install python@{version}" >&2
+    echo "  Debian/Ubuntu:  sudo apt install python{version} python{version}-venv" >&2
+    echo "  Fedora:         sudo dnf install python{version}" >&2
+    echo "  Other:          https://www.python.org/downloads/" >&2
+    exit 1
+fi
+"#,
+            tuple = self.version_tuple
+        );
+
+        if let Some(venv_dir) = self.venv {
+            script.push_str(&format!(
+                r#"
+VENV="$APP_DIR/{venv_dir}"
+if [ ! -x "$VENV/bin/python" ]; then
+    echo "Creating virtual environment in $VENV..."
+    "$PYTHON" -m venv "$VENV" || {{ rm -rf "$VENV"; exit 1; }}
+"#
+            ));
+            if self.has_requirements {
+                script.push_str(
+                    r#"    "$VENV/bin/python" -m pip install -r "$APP_DIR/requirements.txt" || { rm -rf "$VENV"; exit 1; }
+"#,
+                );
+            }
+            script.push_str(
+                r#"fi
+PYTHON="$VENV/bin/python"
+"#,
+            );
+        }
+
+        script.push_str(&format!(
+            r#"
+export {env}="$PYTHON"
+exec "$APP_DIR/{exe}" "$@"
+"#,
+            env = SYSTEM_PYTHON_ENV,
+            exe = self.exe_name
+        ));
+        script
+    }
+
+    fn cmd(&self) -> String {
+        let version = &self.version;
+        let mut script = format!(
+            r#"@echo off
+rem Generated by auroraview-pack: checks the system Python, then starts the app
+setlocal
+set "APP_DIR=%~dp0"
+set "APP_DIR=%APP_DIR:~0,-1%"
+
+set "PYTHON="
+call :find_python py -{version}
+if not defined PYTHON call :find_python python
+if not defined PYTHON call :find_python python3
+if not defined PYTHON goto :missing_python
+"#
+        );
+
+        if let Some(venv_dir) = self.venv {
+            let venv_dir = venv_dir.replace('/', "\\");
+            script.push_str(&format!(
+                r#"
+set "VENV=%APP_DIR%\{venv_dir}"
+if not exist "%VENV%\Scripts\python.exe" (
+    echo Creating virtual environment in %VENV%...
+    "%PYTHON%" -m venv "%VENV%" || goto :venv_failed
+"#
+            ));
+            if self.has_requirements {
+                script.push_str(
+                    r#"    "%VENV%\Scripts\python.exe" -m pip install -r "%APP_DIR%\requirements.txt" || goto :venv_failed
+"#,
+                );
+            }
+            script.push_str(
+                r#")
+set "PYTHON=%VENV%\Scripts\python.exe"
+"#,
+            );
+        }
+
+        script.push_str(&format!(
+            r#"
+set "{env}=%PYTHON%"
+"%APP_DIR%\{exe}" %*
+exit /b %ERRORLEVEL%
+
+:find_python
+%* -c "import sys; sys.exit(sys.version_info[:2] != {tuple})" >nul 2>&1 || exit /b 0
+for /f "delims=" %%I in ('%* -c "import sys; print(sys.executable)"') do set "PYTHON=%%I"
+exit /b 0
+
+:missing_python
+echo error: Python {version} is required but was not found 1>&2
+echo Install it with: winget install Python.Python.{version} 1>&2
+echo or download it from https://www.python.org/downloads/ 1>&2
+exit /b 1
+"#,
+            env = SYSTEM_PYTHON_ENV,
+            exe = self.exe_name,
+            tuple = self.version_tuple
+        ));
+
+        if self.venv.is_some() {
+            script.push_str(
+                r#"
+:venv_failed
+if exist "%VENV%" rmdir /s /q "%VENV%"
+exit /b 1
+"#,
+            );
+        }
+        script.replace('\n', "\r\n")
+    }
+}
@@ -0,0 +1,148 @@
+//! Build targets (`[build] targets`)
+//!
+//! One manifest can pack for several platforms. A target names an
+//! architecture and an OS, either short (`x86_64-windows`, `aarch64-macos`)
+//! or as a Rust triple (`aarch64-apple-darwin`). Each target is packed into
+//! its own executable, `<name>-<arch>-<os>[.exe]`, from that target's
+//! launcher stub (`[build.stubs]`) and Python distribution.
+
+use crate::{PackError, PackResult, PythonTarget, TargetPlatform};
+use std::fmt;
+use std::str::FromStr;
+
+/// CPU architecture of a build target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetArch {
+    /// x86-64 (`x86_64`, `amd64`, `x64`)
+    X86_64,
+    /// 64-bit ARM (`aarch64`, `arm64`)
+    Aarch64,
+}
+
+impl TargetArch {
+    /// Architecture of the running packer
+    pub fn current() -> PackResult<Self> {
+        if cfg!(target_arch = "x86_64") {
+            Ok(Self::X86_64)
+        } else if cfg!(target_arch = "aarch64") {
+            Ok(Self::Aarch64)
+        } else {
+            Err(PackError::Config(format!(
+                "Unsupported build architecture: {}",
+                std::env::consts::ARCH
+            )))
+        }
+    }
+
+    /// Name used in target strings and output names
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::X86_64 => "x86_64",
+            Self::Aarch64 => "aarch64",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "x86_64" | "amd64" | "x64" => Some(Self::X86_64),
+            "aarch64" | "arm64" => Some(Self::Aarch64),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of the build matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildTarget {
+    /// CPU architecture
+    pub arch: TargetArch,
+    /// Operating system (never `TargetPlatform::Current`)
+    pub platform: TargetPlatform,
+}
+
+impl BuildTarget {
+    /// Create a target
+    pub fn new(arch: TargetArch, platform: TargetPlatform) -> Self {
+        let platform = match platform {
+            TargetPlatform::Current => TargetPlatform::current(),
+            platform => platform,
+        };
+        Self { arch, platform }
+    }
+
+    /// Target of the running packer
+    pub fn current() -> PackResult<Self> {
+        let platform = TargetPlatform::current();
+        if platform == TargetPlatform::Current {
+            return Err(PackError::Config(format!(
+                "Unsupported build platform: {}",
+                std::env::consts::OS
+            )));
+        }
+        Ok(Self::new(TargetArch::current()?, platform))
+    }
+
+    /// Parse `x86_64-windows`, `arm64-macos`, `x86_64-unknown-linux-gnu`, ...
+    pub fn parse(target: &str) -> PackResult<Self> {
+        let lower = target.trim().to_ascii_lowercase();
+        let mut parts = lower.split('-');
+        let arch = parts.next().and_then(TargetArch::from_name);
+        let platform = parts.find_map(|part| match part {
+            "windows" | "win" | "win32" => Some(TargetPlatform::Windows),
+            "macos" | "darwin" | "apple" => Some(TargetPlatform::MacOS),
+            "linux" => Some(TargetPlatform::Linux),
+            _ => None,
+        });
+        match (arch, platform) {
+            (Some(arch), Some(platform)) => Ok(Self { arch, platform }),
+            _ => Err(PackError::Config(format!(
+                "Invalid build target '{}' (expected <arch>-<os>, e.g. x86_64-windows or aarch64-macos)",
+                target
+            ))),
+        }
+    }
+
+    /// Whether this is the platform the packer runs on
+    pub fn is_current(&self) -> bool {
+        Self::current().is_ok_and(|current| current == *self)
+    }
+
+    /// Output executable name for `output_name` built for this target
+    pub fn exe_name(&self, output_name: &str) -> String {
+        format!("{}-{}{}", output_name, self, self.platform.exe_extension())
+    }
+
+    /// Python standalone distribution of this target
+    pub fn python_target(&self) -> PackResult<PythonTarget> {
+        match (self.arch, self.platform) {
+            (TargetArch::X86_64, TargetPlatform::Windows) => Ok(PythonTarget::WindowsX64),
+            (TargetArch::X86_64, TargetPlatform::Linux) => Ok(PythonTarget::LinuxX64),
+            (TargetArch::X86_64, TargetPlatform::MacOS) => Ok(PythonTarget::MacOSX64),
+            (TargetArch::Aarch64, TargetPlatform::MacOS) => Ok(PythonTarget::MacOSArm64),
+            _ => Err(PackError::Config(format!(
+                "No Python standalone distribution for target {}",
+                self
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for BuildTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let os = match self.platform {
+            TargetPlatform::Windows => "windows",
+            TargetPlatform::MacOS => "macos",
+            TargetPlatform::Linux => "linux",
+            TargetPlatform::Current => std::env::consts::OS,
+        };
+        write!(f, "{}-{}", self.arch.as_str(), os)
+    }
+}
+
+impl FromStr for BuildTarget {
+    type Err = PackError;
+
+    fn from_str(s: &str) -> PackResult<Self> {
+        Self::parse(s)
+    }
+}
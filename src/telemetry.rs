@@ -0,0 +1,525 @@
+//! OpenTelemetry export of pack and startup timings
+//!
+//! With `OTEL_EXPORTER_OTLP_ENDPOINT` set (or the per-signal
+//! `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` / `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT`),
+//! every pack exports a trace, one span for the run with a child span per
+//! stage, and gauges of its duration, stage durations and sizes. Packed
+//! applications can export their [`PackedMetrics`] the same way. Data is
+//! sent as OTLP/HTTP JSON; the standard variables are honoured:
+//!
+//! | Variable | Use |
+//! |----------|-----|
+//! | `OTEL_EXPORTER_OTLP_ENDPOINT` | Base URL (`/v1/traces`, `/v1/metrics` appended) |
+//! | `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` | Trace URL, as is |
+//! | `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT` | Metrics URL, as is |
+//! | `OTEL_EXPORTER_OTLP_HEADERS` | `key=value,...` sent with every request |
+//! | `OTEL_EXPORTER_OTLP_PROTOCOL` | `grpc` is not supported and disables export |
+//! | `OTEL_SERVICE_NAME` | `service.name` (default `auroraview-pack`) |
+//! | `OTEL_RESOURCE_ATTRIBUTES` | `key=value,...` resource attributes |
+//! | `OTEL_SDK_DISABLED` | `true` disables export |
+//!
+//! A failed export is logged; it never fails the pack.
+
+use crate::packer::PackOutput;
+use crate::{HttpClient, PackError, PackResult, PackedMetrics};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+/// Default `service.name`
+const DEFAULT_SERVICE_NAME: &str = "auroraview-pack";
+
+/// Timeout of each export request
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pack stages in the order they run
+const STAGE_ORDER: &[&str] = &["prepare", "pack", "after_pack"];
+
+/// Where to export and what to attach (the `OTEL_*` variables)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtlpConfig {
+    /// URL traces are posted to
+    pub traces_endpoint: Option<String>,
+    /// URL metrics are posted to
+    pub metrics_endpoint: Option<String>,
+    /// Headers sent with every request (e.g. an API key)
+    pub headers: Vec<(String, String)>,
+    /// `service.name` resource attribute
+    pub service_name: String,
+    /// Further resource attributes
+    pub resource_attributes: BTreeMap<String, String>,
+}
+
+impl OtlpConfig {
+    /// Export traces and metrics to the collector at `endpoint`
+    /// (e.g. `http://localhost:4318`)
+    pub fn new(endpoint: &str) -> Self {
+        let endpoint = endpoint.trim_end_matches('/');
+        Self {
+            traces_endpoint: Some(format!("{}/v1/traces", endpoint)),
+            metrics_endpoint: Some(format!("{}/v1/metrics", endpoint)),
+            headers: Vec::new(),
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+            resource_attributes: BTreeMap::new(),
+        }
+    }
+
+    /// Configuration from the `OTEL_*` environment variables (`None`: no
+    /// endpoint set, or export disabled)
+    pub fn from_env() -> Option<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Configuration from variables looked up by `lookup`
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let var = |name: &str| lookup(name).filter(|value| !value.trim().is_empty());
+        if var("OTEL_SDK_DISABLED").is_some_and(|v| v.trim().eq_ignore_ascii_case("true")) {
+            return None;
+        }
+        if let Some(protocol) = var("OTEL_EXPORTER_OTLP_PROTOCOL") {
+            if protocol.trim() == "grpc" {
+                tracing::warn!(
+                    "OTEL_EXPORTER_OTLP_PROTOCOL=grpc is not supported (telemetry is sent \
+                     as http/json); not exporting telemetry"
+                );
+                return None;
+            }
+        }
+
+        let mut config = match var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            Some(endpoint) => Self::new(endpoint.trim()),
+            None => Self {
+                traces_endpoint: None,
+                metrics_endpoint: None,
+                ..Self::new("")
+            },
+        };
+        if let Some(endpoint) = var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT") {
+            config.traces_endpoint = Some(endpoint.trim().to_string());
+        }
+        if let Some(endpoint) = var("OTEL_EXPORTER_OTLP_METRICS_ENDPOINT") {
+            config.metrics_endpoint = Some(endpoint.trim().to_string());
+        }
+        if config.traces_endpoint.is_none() && config.metrics_endpoint.is_none() {
+            return None;
+        }
+
+        if let Some(headers) = var("OTEL_EXPORTER_OTLP_HEADERS") {
+            config.headers = parse_pairs(&headers).into_iter().collect();
+        }
+        if let Some(attributes) = var("OTEL_RESOURCE_ATTRIBUTES") {
+            config.resource_attributes = parse_pairs(&attributes);
+        }
+        if let Some(name) = var("OTEL_SERVICE_NAME") {
+            config.service_name = name.trim().to_string();
+        } else if let Some(name) = config.resource_attributes.remove("service.name") {
+            config.service_name = name;
+        }
+        Some(config)
+    }
+
+    /// Send `name: value` with every request
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set a resource attribute
+    pub fn with_resource_attribute(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.resource_attributes.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Exports pack and startup timings over OTLP/HTTP JSON
+#[derive(Debug, Clone)]
+pub struct OtlpExporter {
+    config: OtlpConfig,
+    http: HttpClient,
+}
+
+impl OtlpExporter {
+    /// Exporter for `config`
+    pub fn new(config: OtlpConfig) -> Self {
+        Self {
+            config,
+            http: HttpClient::default().with_timeout(EXPORT_TIMEOUT),
+        }
+    }
+
+    /// Exporter configured by the `OTEL_*` environment variables
+    pub fn from_env() -> Option<Self> {
+        OtlpConfig::from_env().map(Self::new)
+    }
+
+    /// Send requests through `http` (proxy and certificate pins of
+    /// `[build.http]`)
+    pub fn with_http(mut self, http: HttpClient) -> Self {
+        self.http = http.with_timeout(EXPORT_TIMEOUT);
+        self
+    }
+
+    /// The configuration
+    pub fn config(&self) -> &OtlpConfig {
+        &self.config
+    }
+
+    /// Export the trace and metrics of a finished pack
+    pub fn export_pack(&self, output: &PackOutput) -> PackResult<()> {
+        let now = SystemTime::now();
+        self.post(
+            self.config.traces_endpoint.as_deref(),
+            &self.pack_traces(output, now),
+        )?;
+        self.post(
+            self.config.metrics_endpoint.as_deref(),
+            &self.pack_metrics(output, now),
+        )
+    }
+
+    /// Export the startup timings of a packed application
+    pub fn export_startup(&self, metrics: &PackedMetrics) -> PackResult<()> {
+        let now = SystemTime::now();
+        self.post(
+            self.config.traces_endpoint.as_deref(),
+            &self.startup_traces(metrics, now),
+        )?;
+        self.post(
+            self.config.metrics_endpoint.as_deref(),
+            &self.startup_metrics(metrics, now),
+        )
+    }
+
+    /// OTLP trace request of a pack that finished at `end`
+    ///
+    /// The root span `pack` covers the run; stages follow each other in
+    /// the order they run.
+    pub fn pack_traces(&self, output: &PackOutput, end: SystemTime) -> Value {
+        let trace_id = random_hex(16);
+        let root_id = random_hex(8);
+        let start = end
+            .checked_sub(Duration::from_millis(output.duration_ms))
+            .unwrap_or(end);
+
+        let mut spans = vec![span(
+            &trace_id,
+            &root_id,
+            None,
+            "pack",
+            start,
+            end,
+            pack_attributes(output),
+        )];
+        let mut stage_start = start;
+        for (stage, ms) in ordered_stages(&output.stage_timings) {
+            let stage_end = stage_start + Duration::from_millis(ms);
+            spans.push(span(
+                &trace_id,
+                &random_hex(8),
+                Some(&root_id),
+                &format!("pack.{}", stage),
+                stage_start,
+                stage_end,
+                vec![attribute("auroraview.pack.stage", stage)],
+            ));
+            stage_start = stage_end;
+        }
+        self.traces_request(spans)
+    }
+
+    /// OTLP metrics request of a pack that finished at `time`
+    pub fn pack_metrics(&self, output: &PackOutput, time: SystemTime) -> Value {
+        let attributes = pack_attributes(output);
+        let compressed: u64 = output.assets.iter().map(|a| a.length).sum();
+        let uncompressed: u64 = output.assets.iter().map(|a| a.size).sum();
+        let stages = ordered_stages(&output.stage_timings)
+            .into_iter()
+            .map(|(stage, ms)| {
+                let mut attributes = attributes.clone();
+                attributes.push(attribute("auroraview.pack.stage", stage));
+                (ms, attributes)
+            })
+            .collect();
+        self.metrics_request(vec![
+            gauge(
+                "auroraview.pack.duration",
+                "ms",
+                time,
+                vec![(output.duration_ms, attributes.clone())],
+            ),
+            gauge("auroraview.pack.stage.duration", "ms", time, stages),
+            gauge(
+                "auroraview.pack.size",
+                "By",
+                time,
+                vec![(output.size, attributes.clone())],
+            ),
+            gauge(
+                "auroraview.pack.assets",
+                "{asset}",
+                time,
+                vec![(output.asset_count as u64, attributes.clone())],
+            ),
+            gauge(
+                "auroraview.pack.assets.compressed_size",
+                "By",
+                time,
+                vec![(compressed, attributes.clone())],
+            ),
+            gauge(
+                "auroraview.pack.assets.size",
+                "By",
+                time,
+                vec![(uncompressed, attributes)],
+            ),
+        ])
+    }
+
+    /// OTLP trace request of a startup that was measured until `end`
+    ///
+    /// The root span `startup` has a child span per phase marked, from the
+    /// previous mark to its own.
+    pub fn startup_traces(&self, metrics: &PackedMetrics, end: SystemTime) -> Value {
+        let trace_id = random_hex(16);
+        let root_id = random_hex(8);
+        let total = metrics.total.unwrap_or_else(|| metrics.elapsed());
+        let start = end.checked_sub(total).unwrap_or(end);
+
+        let mut spans = vec![span(
+            &trace_id,
+            &root_id,
+            None,
+            "startup",
+            start,
+            start + total,
+            Vec::new(),
+        )];
+        let mut previous = Duration::ZERO;
+        for (name, mark) in metrics.marks() {
+            spans.push(span(
+                &trace_id,
+                &random_hex(8),
+                Some(&root_id),
+                &format!("startup.{}", name),
+                start + previous,
+                start + mark.max(previous),
+                Vec::new(),
+            ));
+            previous = previous.max(mark);
+        }
+        self.traces_request(spans)
+    }
+
+    /// OTLP metrics request of a startup measured until `time`
+    pub fn startup_metrics(&self, metrics: &PackedMetrics, time: SystemTime) -> Value {
+        let total = metrics.total.unwrap_or_else(|| metrics.elapsed());
+        let phase = |name: &str, duration: Duration| {
+            (
+                duration.as_millis() as u64,
+                vec![attribute("auroraview.startup.phase", name)],
+            )
+        };
+        let marks = metrics
+            .marks()
+            .into_iter()
+            .map(|(name, mark)| phase(name, mark))
+            .collect();
+        let phases = metrics
+            .phases()
+            .iter()
+            .map(|(name, duration)| phase(name, *duration))
+            .collect();
+        self.metrics_request(vec![
+            gauge(
+                "auroraview.startup.duration",
+                "ms",
+                time,
+                vec![(total.as_millis() as u64, Vec::new())],
+            ),
+            gauge("auroraview.startup.mark", "ms", time, marks),
+            gauge("auroraview.startup.phase.duration", "ms", time, phases),
+        ])
+    }
+
+    fn resource(&self) -> Value {
+        let attributes: Vec<Value> =
+            std::iter::once(attribute("service.name", &self.config.service_name))
+                .chain(std::iter::once(attribute(
+                    "service.version",
+                    crate::PACKER_VERSION,
+                )))
+                .chain(
+                    self.config
+                        .resource_attributes
+                        .iter()
+                        .map(|(key, value)| attribute(key, value)),
+                )
+                .collect();
+        json!({ "attributes": attributes })
+    }
+
+    fn traces_request(&self, spans: Vec<Value>) -> Value {
+        json!({
+            "resourceSpans": [{
+                "resource": self.resource(),
+                "scopeSpans": [{ "scope": scope(), "spans": spans }],
+            }]
+        })
+    }
+
+    fn metrics_request(&self, metrics: Vec<Value>) -> Value {
+        json!({
+            "resourceMetrics": [{
+                "resource": self.resource(),
+                "scopeMetrics": [{ "scope": scope(), "metrics": metrics }],
+            }]
+        })
+    }
+
+    /// Post `body` to `endpoint` (skipped without one)
+    fn post(&self, endpoint: Option<&str>, body: &Value) -> PackResult<()> {
+        let Some(endpoint) = endpoint else {
+            return Ok(());
+        };
+        let body = serde_json::to_vec(body)?;
+        let mut headers: Vec<(&str, &str)> = vec![("Content-Type", "application/json")];
+        headers.extend(
+            self.config
+                .headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        self.http
+            .send("POST", endpoint, &headers, Some(&body))
+            .map_err(|e| PackError::Telemetry(format!("POST {}: {}", endpoint, e)))?;
+        tracing::debug!("Exported telemetry to {}", endpoint);
+        Ok(())
+    }
+}
+
+/// `key=value,...` as in `OTEL_RESOURCE_ATTRIBUTES` (values may be
+/// percent-encoded)
+fn parse_pairs(list: &str) -> BTreeMap<String, String> {
+    list.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), percent_decode(value.trim())))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// Decode `%XX` escapes (invalid escapes are kept as is)
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Stages in the order they run, then any other timing by name
+fn ordered_stages(timings: &BTreeMap<String, u64>) -> Vec<(&str, u64)> {
+    let rank = |stage: &str| {
+        STAGE_ORDER
+            .iter()
+            .position(|s| *s == stage)
+            .unwrap_or(STAGE_ORDER.len())
+    };
+    let mut stages: Vec<_> = timings.iter().map(|(s, ms)| (s.as_str(), *ms)).collect();
+    stages.sort_by_key(|(stage, _)| rank(stage));
+    stages
+}
+
+fn pack_attributes(output: &PackOutput) -> Vec<Value> {
+    let app = output
+        .executable
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    vec![
+        attribute("auroraview.pack.app", &app),
+        attribute("auroraview.pack.mode", &output.mode),
+        json!({ "key": "auroraview.pack.up_to_date", "value": { "boolValue": output.up_to_date } }),
+    ]
+}
+
+fn scope() -> Value {
+    json!({ "name": "auroraview-pack", "version": crate::PACKER_VERSION })
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn span(
+    trace_id: &str,
+    span_id: &str,
+    parent: Option<&str>,
+    name: &str,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<Value>,
+) -> Value {
+    json!({
+        "traceId": trace_id,
+        "spanId": span_id,
+        "parentSpanId": parent.unwrap_or_default(),
+        "name": name,
+        // SPAN_KIND_INTERNAL
+        "kind": 1,
+        "startTimeUnixNano": unix_nanos(start),
+        "endTimeUnixNano": unix_nanos(end),
+        "attributes": attributes,
+        // STATUS_CODE_OK
+        "status": { "code": 1 },
+    })
+}
+
+/// Gauge with one integer data point per `(value, attributes)`
+fn gauge(name: &str, unit: &str, time: SystemTime, points: Vec<(u64, Vec<Value>)>) -> Value {
+    let time = unix_nanos(time);
+    let points: Vec<Value> = points
+        .into_iter()
+        .map(|(value, attributes)| {
+            json!({
+                "timeUnixNano": time,
+                "asInt": value.to_string(),
+                "attributes": attributes,
+            })
+        })
+        .collect();
+    json!({ "name": name, "unit": unit, "gauge": { "dataPoints": points } })
+}
+
+/// Nanoseconds since the Unix epoch, as a string (64-bit JSON integers)
+fn unix_nanos(time: SystemTime) -> String {
+    crate::context::unix_nanos(time).to_string()
+}
+
+/// `bytes` random bytes as lowercase hex (trace and span IDs)
+fn random_hex(bytes: usize) -> String {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    (0..bytes)
+        .map(|_| format!("{:02x}", rng.gen::<u8>()))
+        .collect()
+}
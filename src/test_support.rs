@@ -0,0 +1,406 @@
+//! Fixtures for integration tests (`test-support` feature)
+//!
+//! Downstream crates (and this crate's own tests) run full pack → read
+//! overlay → verify cycles without network access or a real launcher:
+//!
+//! - [`write_stub_exe`]: a tiny launcher stub, so a pack does not copy the
+//!   test binary
+//! - [`fake_frontend`]: a minimal frontend (`index.html`, a script and a
+//!   stylesheet)
+//! - [`fake_python`]: a Python project and a fake standalone distribution,
+//!   seeded into an artifact store so nothing is downloaded
+//! - [`MockServer`]: a local HTTP server for `[[downloads]]`, update feeds
+//!   and the like
+//! - [`PackFixture`]: a temporary directory tying these together
+//!
+//! ```no_run
+//! use auroraview_pack::test_support::PackFixture;
+//! use auroraview_pack::PackConfig;
+//!
+//! let fixture = PackFixture::new()?;
+//! let frontend = fixture.frontend()?;
+//! let output = fixture.pack(PackConfig::frontend(&frontend).with_output("app"))?;
+//! let overlay = PackFixture::read_overlay(&output.executable)?;
+//! assert!(overlay.assets.iter().any(|(path, _)| path == "index.html"));
+//! # Ok::<(), auroraview_pack::PackError>(())
+//! ```
+
+use crate::packer::PackOutput;
+use crate::{
+    ArtifactStore, BuildTarget, ObjectKind, OverlayData, OverlayReader, PackConfig, PackError,
+    PackResult, Packer, PythonTarget, StoreConfig,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Content of the stub written by [`write_stub_exe`]
+pub const STUB_EXE_CONTENT: &[u8] = b"#!/bin/sh\n# auroraview-pack test stub\nexit 0\n";
+
+/// Python version of the distribution seeded by [`fake_python`]
+pub const FAKE_PYTHON_VERSION: &str = "3.11";
+
+/// Write a tiny launcher stub (executable on Unix)
+///
+/// Use it as the launcher with [`PackFixture::configure`] or
+/// `PackConfig::with_stub`.
+pub fn write_stub_exe(path: &Path) -> PackResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, STUB_EXE_CONTENT)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// Write a minimal frontend to `dir` and return it
+///
+/// Contains `index.html`, `assets/app.js` and `assets/style.css`.
+pub fn fake_frontend(dir: &Path) -> PackResult<PathBuf> {
+    fs::create_dir_all(dir.join("assets"))?;
+    fs::write(
+        dir.join("index.html"),
+        "<!DOCTYPE html>\n<html><head><link rel=\"stylesheet\" href=\"assets/style.css\"></head>\n\
+         <body><h1>Test App</h1><script src=\"assets/app.js\"></script></body></html>\n",
+    )?;
+    fs::write(dir.join("assets/app.js"), "console.log('test app');\n")?;
+    fs::write(dir.join("assets/style.css"), "body { margin: 0; }\n")?;
+    Ok(dir.to_path_buf())
+}
+
+/// Python project and distribution written by [`fake_python`]
+#[derive(Debug, Clone)]
+pub struct FakePython {
+    /// Source directory, with `main.py` (`main:run`)
+    pub source_dir: PathBuf,
+    /// Entry point of the project
+    pub entry_point: String,
+    /// Store holding the fake distribution of the current platform
+    pub store: StoreConfig,
+}
+
+/// Write a Python project to `dir/app` and seed a fake standalone
+/// distribution of the current platform into the store at `dir/store`
+///
+/// Pack with `PythonBundleConfig::new(&python.entry_point)`, the source
+/// directory as include path and `PackConfig::with_store(python.store)`.
+/// The distribution's interpreter is a shell script; it is packed, not run.
+pub fn fake_python(dir: &Path) -> PackResult<FakePython> {
+    let source_dir = dir.join("app");
+    fs::create_dir_all(&source_dir)?;
+    fs::write(
+        source_dir.join("main.py"),
+        "def run():\n    print(\"test app\")\n\n\nif __name__ == \"__main__\":\n    run()\n",
+    )?;
+
+    let store = StoreConfig {
+        dir: Some(dir.join("store")),
+        ..Default::default()
+    };
+    let target = PythonTarget::current()?;
+    let name = format!("cpython-{}-{}.tar.gz", FAKE_PYTHON_VERSION, target.triple());
+    let artifacts = ArtifactStore::open(&store);
+    let digest = artifacts.insert(&name, &python_distribution(target)?)?;
+    artifacts.link("python", &name, ObjectKind::Blob, &digest)?;
+
+    Ok(FakePython {
+        source_dir,
+        entry_point: "main:run".to_string(),
+        store,
+    })
+}
+
+/// Fake `install_only` standalone distribution (`.tar.gz`) for `target`
+pub fn python_distribution(target: PythonTarget) -> PackResult<Vec<u8>> {
+    let lib = match target {
+        PythonTarget::WindowsX64 => "python/Lib/os.py".to_string(),
+        _ => format!("python/lib/python{}/os.py", FAKE_PYTHON_VERSION),
+    };
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::fast(),
+    ));
+    for (path, content, mode) in [
+        (target.python_path().to_string(), STUB_EXE_CONTENT, 0o755),
+        (lib, &b"# fake standard library\n"[..], 0o644),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content)?;
+    }
+    Ok(builder.into_inner()?.finish()?)
+}
+
+/// Files served by a [`MockServer`], by path
+type Routes = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+/// Requests with a body received by a [`MockServer`]
+type Uploads = Arc<Mutex<Vec<(String, Vec<u8>)>>>;
+
+/// Local HTTP server serving fixed files
+///
+/// Answers `GET <path>` (the query string is ignored) with the file added
+/// for the path, `POST` and `PUT` with `200` (see [`uploads`](Self::uploads)),
+/// anything else with `404`. Every request path is recorded. The server
+/// stops when dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    url: String,
+    routes: Routes,
+    requests: Arc<Mutex<Vec<String>>>,
+    uploads: Uploads,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Start a server on a free local port
+    pub fn start() -> PackResult<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let routes = Routes::default();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let uploads = Uploads::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let (routes, requests, uploads, stop) = (
+                routes.clone(),
+                requests.clone(),
+                uploads.clone(),
+                stop.clone(),
+            );
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        // A client hanging up is not the server's problem
+                        let _ = serve(stream, &routes, &requests, &uploads);
+                    }
+                }
+            })
+        };
+        Ok(Self {
+            url,
+            routes,
+            requests,
+            uploads,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Serve `content` at `path` (e.g. `/python.tar.gz`)
+    pub fn with_file(self, path: &str, content: impl Into<Vec<u8>>) -> Self {
+        self.add_file(path, content);
+        self
+    }
+
+    /// Serve `content` at `path`, replacing an earlier file
+    pub fn add_file(&self, path: &str, content: impl Into<Vec<u8>>) {
+        self.routes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(normalize(path), content.into());
+    }
+
+    /// URL of `path` on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.url, normalize(path))
+    }
+
+    /// Paths requested so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Paths and bodies of the `POST` and `PUT` requests so far, in order
+    pub fn uploads(&self) -> Vec<(String, Vec<u8>)> {
+        self.uploads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the accept loop
+        let _ = TcpStream::connect(self.url.trim_start_matches("http://"));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn normalize(path: &str) -> String {
+    format!("/{}", path.trim_start_matches('/'))
+}
+
+/// Answer one request
+fn serve(
+    stream: TcpStream,
+    routes: &Routes,
+    requests: &Mutex<Vec<String>>,
+    uploads: &Uploads,
+) -> PackResult<()> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut words = request_line.split_whitespace();
+    let method = words.next().unwrap_or("GET").to_string();
+    let target = words.next().unwrap_or("/");
+    let path = target.split('?').next().unwrap_or(target).to_string();
+    requests
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(path.clone());
+
+    let (status, body) = if method == "POST" || method == "PUT" {
+        let mut upload = vec![0; content_length];
+        reader.read_exact(&mut upload)?;
+        uploads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((path, upload));
+        ("200 OK", Vec::new())
+    } else {
+        match routes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&path)
+            .cloned()
+        {
+            Some(body) => ("200 OK", body),
+            None => ("404 Not Found", Vec::new()),
+        }
+    };
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    Ok(stream.flush()?)
+}
+
+/// Temporary directory for pack tests
+///
+/// Holds a launcher stub, and the fixtures written by
+/// [`frontend`](Self::frontend) and [`python`](Self::python).
+/// [`configure`](Self::configure) points a configuration at the stub and
+/// keeps the output, artifact store and resume checkpoints inside the
+/// directory, which is removed on drop.
+#[derive(Debug)]
+pub struct PackFixture {
+    dir: tempfile::TempDir,
+    stub: PathBuf,
+}
+
+impl PackFixture {
+    /// Create a fixture directory with a launcher stub
+    pub fn new() -> PackResult<Self> {
+        let dir = tempfile::TempDir::new()?;
+        let stub = dir
+            .path()
+            .join(format!("stub{}", std::env::consts::EXE_SUFFIX));
+        write_stub_exe(&stub)?;
+        Ok(Self { dir, stub })
+    }
+
+    /// Fixture directory
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Launcher stub
+    pub fn stub(&self) -> &Path {
+        &self.stub
+    }
+
+    /// Output directory of [`configure`](Self::configure)d packs
+    pub fn output_dir(&self) -> PathBuf {
+        self.path().join("out")
+    }
+
+    /// Artifact store of [`configure`](Self::configure)d packs
+    pub fn store(&self) -> StoreConfig {
+        StoreConfig {
+            dir: Some(self.path().join("store")),
+            ..Default::default()
+        }
+    }
+
+    /// Write a [`fake_frontend`] to `dist`
+    pub fn frontend(&self) -> PackResult<PathBuf> {
+        fake_frontend(&self.path().join("dist"))
+    }
+
+    /// Write a [`fake_python`] project and distribution (into
+    /// [`store`](Self::store))
+    pub fn python(&self) -> PackResult<FakePython> {
+        fake_python(self.path())
+    }
+
+    /// Pack `config` with the stub as launcher, into
+    /// [`output_dir`](Self::output_dir)
+    ///
+    /// Unless set, the artifact store is [`store`](Self::store) and resume
+    /// checkpoints are kept in the fixture directory.
+    pub fn configure(&self, config: PackConfig) -> PackResult<PackConfig> {
+        let target = BuildTarget::current()?;
+        let mut config = config
+            .with_output_dir(self.output_dir())
+            .with_stub(target, &self.stub);
+        config.build_target = Some(target);
+        if config.store.dir.is_none() {
+            config.store = self.store();
+        }
+        if let Some(resume) = config.resume.as_mut().filter(|r| r.dir.is_none()) {
+            resume.dir = Some(self.path().join("resume"));
+        }
+        Ok(config)
+    }
+
+    /// Pack a [`configure`](Self::configure)d `config`
+    pub fn pack(&self, config: PackConfig) -> PackResult<PackOutput> {
+        Packer::new(self.configure(config)?).pack()
+    }
+
+    /// Read the overlay of a packed executable (an error if it has none)
+    pub fn read_overlay(executable: &Path) -> PackResult<OverlayData> {
+        OverlayReader::read(executable)?.ok_or_else(|| {
+            PackError::InvalidOverlay(format!("{} has no overlay", executable.display()))
+        })
+    }
+}
@@ -0,0 +1,412 @@
+//! Go / Rust toolchain provisioning
+//!
+//! `[backend.go.toolchain]` and `[backend.rust.toolchain]` pin a toolchain
+//! version. When the machine has no matching toolchain on PATH, the packer
+//! downloads it instead of failing. Archives, rustup-init and extracted Go
+//! distributions live in the [artifact store](crate::ArtifactStore); rustup
+//! manages its installation in the pack cache:
+//!
+//! ```text
+//! <cache>/AuroraView/toolchains/
+//! ├── rustup/             - RUSTUP_HOME
+//! └── cargo/              - CARGO_HOME (bin/ holds cargo, rustc, ...)
+//! ```
+//!
+//! Every download is verified against a SHA-256: the configured `sha256`, or
+//! the checksum published next to the official download.
+
+use crate::cache_lock::CacheLock;
+use crate::downloader::Downloader;
+use crate::store::{ArtifactStore, ObjectKind};
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Official Go download index (JSON, includes checksums)
+const GO_DOWNLOAD_INDEX: &str = "https://go.dev/dl/?mode=json&include=all";
+
+/// Official rustup-init download base
+const RUSTUP_DIST: &str = "https://static.rust-lang.org/rustup/dist";
+
+/// Artifact store namespace of extracted toolchains
+const TOOLCHAINS_NAMESPACE: &str = "toolchains";
+
+/// Pinned toolchain configuration
+///
+/// Located at `[backend.go.toolchain]` / `[backend.rust.toolchain]` in TOML.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolchainConfig {
+    /// Toolchain version (Go: "1.22.5", Rust: "1.79.0" or a channel like "stable")
+    pub version: String,
+
+    /// Expected SHA-256 of the downloaded archive (Go) or rustup-init (Rust);
+    /// defaults to the officially published checksum
+    #[serde(default)]
+    pub sha256: Option<String>,
+
+    /// Override the download URL (e.g., an internal mirror)
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl ToolchainConfig {
+    /// Create a toolchain pin for a version
+    pub fn new(version: impl Into<String>) -> Self {
+        Self {
+            version: version.into(),
+            sha256: None,
+            url: None,
+        }
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self, section: &str) -> PackResult<()> {
+        if self.version.trim().is_empty() {
+            return Err(PackError::Config(format!(
+                "[{}] requires a non-empty 'version'",
+                section
+            )));
+        }
+        if let Some(ref hash) = self.sha256 {
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(PackError::Config(format!(
+                    "Invalid 'sha256' in [{}]: {}",
+                    section, hash
+                )));
+            }
+        }
+        if self.url.is_some() && self.sha256.is_none() {
+            return Err(PackError::Config(format!(
+                "[{}] requires 'sha256' when 'url' is overridden",
+                section
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A toolchain ready to use
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProvisionedToolchain {
+    /// Directory to prepend to PATH (`None` when the system toolchain is used)
+    pub bin_dir: Option<PathBuf>,
+
+    /// Extra environment variables (e.g., RUSTUP_HOME, CARGO_HOME)
+    pub env: Vec<(String, OsString)>,
+}
+
+/// Get the cache directory for provisioned toolchains
+pub fn get_toolchains_cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("AuroraView")
+        .join("toolchains")
+}
+
+/// Use the system Go if it matches the pinned version, otherwise download it
+pub fn ensure_go(
+    config: &ToolchainConfig,
+    store: &ArtifactStore,
+) -> PackResult<ProvisionedToolchain> {
+    let version = config.version.trim_start_matches("go");
+    if let Some(found) = command_output("go", &["version"]) {
+        // "go version go1.22.5 linux/amd64"
+        if found
+            .split_whitespace()
+            .any(|w| w == format!("go{}", version))
+        {
+            tracing::debug!("Using system Go: {}", found.trim());
+            return Ok(ProvisionedToolchain::default());
+        }
+        tracing::info!(
+            "System Go ({}) does not match pinned go{}",
+            found.trim(),
+            version
+        );
+    }
+
+    let ref_name = format!("go-{}", version);
+    let go_root = match store
+        .resolve(TOOLCHAINS_NAMESPACE, &ref_name)
+        .filter(|root| root.join("bin").join(exe("go")).is_file())
+    {
+        Some(root) => root,
+        None => {
+            let file_name = go_archive_name(version)?;
+            let sha256 = match config.sha256 {
+                Some(ref hash) => hash.clone(),
+                None => fetch_go_checksum(store, &file_name)?,
+            };
+            let url = config
+                .url
+                .clone()
+                .unwrap_or_else(|| format!("https://go.dev/dl/{}", file_name));
+
+            tracing::info!("Provisioning Go {} from {}", version, url);
+            let downloader = Downloader::new(store.root())
+                .with_store(store.clone())
+                .require_checksum(true);
+            let archive = downloader.download(&file_name, &url, Some(&sha256))?;
+
+            // The extracted tree is keyed by the verified archive checksum
+            let id = ArtifactStore::tree_id(&["go", &sha256.to_ascii_lowercase()]);
+            let root = store.insert_tree(&id, |dir| downloader.extract(&archive, dir, 1))?;
+            store.link(TOOLCHAINS_NAMESPACE, &ref_name, ObjectKind::Tree, &id)?;
+            root
+        }
+    };
+
+    Ok(ProvisionedToolchain {
+        bin_dir: Some(go_root.join("bin")),
+        env: Vec::new(),
+    })
+}
+
+/// Use the system Rust if it matches the pinned version, otherwise install it
+/// with rustup into the pack cache
+pub fn ensure_rust(
+    config: &ToolchainConfig,
+    store: &ArtifactStore,
+) -> PackResult<ProvisionedToolchain> {
+    if let Some(found) = command_output("rustc", &["--version"]) {
+        // "rustc 1.79.0 (129f3b996 2024-06-10)"; channels accept any version
+        let is_channel = matches!(config.version.as_str(), "stable" | "beta" | "nightly");
+        if is_channel || found.split_whitespace().nth(1) == Some(config.version.as_str()) {
+            tracing::debug!("Using system Rust: {}", found.trim());
+            return Ok(ProvisionedToolchain::default());
+        }
+        tracing::info!(
+            "System Rust ({}) does not match pinned {}",
+            found.trim(),
+            config.version
+        );
+    }
+
+    let root = get_toolchains_cache_dir();
+    let rustup_home = root.join("rustup");
+    // rustup is not safe against concurrent installs into one RUSTUP_HOME
+    let _lock = CacheLock::acquire(&rustup_home)?;
+    let cargo_home = root.join("cargo");
+    let bin_dir = cargo_home.join("bin");
+    let env = vec![
+        ("RUSTUP_HOME".to_string(), rustup_home.into_os_string()),
+        ("CARGO_HOME".to_string(), cargo_home.into_os_string()),
+        (
+            "RUSTUP_TOOLCHAIN".to_string(),
+            OsString::from(&config.version),
+        ),
+    ];
+
+    let rustup = bin_dir.join(exe("rustup"));
+    let installed = rustup.is_file()
+        && Command::new(&rustup)
+            .args(["run", &config.version, "rustc", "--version"])
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .output()
+            .is_ok_and(|o| o.status.success());
+
+    if !installed {
+        let triple = host_triple()?;
+        let url = config
+            .url
+            .clone()
+            .unwrap_or_else(|| format!("{}/{}/{}", RUSTUP_DIST, triple, exe("rustup-init")));
+        let sha256 = match config.sha256 {
+            Some(ref hash) => hash.clone(),
+            None => store
+                .http()
+                .get_text(&format!("{}.sha256", url))?
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        };
+
+        tracing::info!("Provisioning Rust {} with rustup", config.version);
+        let downloader = Downloader::new(store.root())
+            .with_store(store.clone())
+            .require_checksum(true);
+        let rustup_init = downloader.download(
+            &format!("{}-{}", triple, exe("rustup-init")),
+            &url,
+            Some(&sha256),
+        )?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&rustup_init, fs::Permissions::from_mode(0o755))?;
+        }
+
+        let output = Command::new(&rustup_init)
+            .args([
+                "-y",
+                "--no-modify-path",
+                "--profile",
+                "minimal",
+                "--default-toolchain",
+                &config.version,
+            ])
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .output()
+            .map_err(|e| PackError::Config(format!("Failed to run rustup-init: {}", e)))?;
+        crate::subprocess::log_output("rustup-init", &output);
+        if !output.status.success() {
+            return Err(PackError::Config(format!(
+                "rustup-init failed (exit code {:?}): {}",
+                output.status.code(),
+                crate::subprocess::stderr_tail(&output)
+            )));
+        }
+    }
+
+    Ok(ProvisionedToolchain {
+        bin_dir: Some(bin_dir),
+        env,
+    })
+}
+
+/// Go archive file name for the host platform
+fn go_archive_name(version: &str) -> PackResult<String> {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os @ ("linux" | "windows" | "freebsd") => os,
+        os => {
+            return Err(PackError::Config(format!(
+                "Go provisioning is not supported on {}",
+                os
+            )))
+        }
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "386",
+        arch => {
+            return Err(PackError::Config(format!(
+                "Go provisioning is not supported on {}",
+                arch
+            )))
+        }
+    };
+    let ext = if os == "windows" { "zip" } else { "tar.gz" };
+    Ok(format!("go{}.{}-{}.{}", version, os, arch, ext))
+}
+
+/// Rust host triple for rustup-init
+fn host_triple() -> PackResult<String> {
+    let arch = std::env::consts::ARCH;
+    match std::env::consts::OS {
+        "linux" => Ok(format!("{}-unknown-linux-gnu", arch)),
+        "macos" => Ok(format!("{}-apple-darwin", arch)),
+        "windows" => Ok(format!("{}-pc-windows-msvc", arch)),
+        os => Err(PackError::Config(format!(
+            "Rust provisioning is not supported on {}",
+            os
+        ))),
+    }
+}
+
+/// Look up the published checksum of a Go archive
+fn fetch_go_checksum(store: &ArtifactStore, file_name: &str) -> PackResult<String> {
+    let index: serde_json::Value = serde_json::from_str(&store.http().get_text(GO_DOWNLOAD_INDEX)?)
+        .map_err(|e| PackError::Config(format!("Invalid Go download index: {}", e)))?;
+    find_go_checksum(&index, file_name).ok_or_else(|| {
+        PackError::Config(format!(
+            "No published checksum for {} (set [backend.go.toolchain] sha256)",
+            file_name
+        ))
+    })
+}
+
+/// Find a file's checksum in the Go download index
+fn find_go_checksum(index: &serde_json::Value, file_name: &str) -> Option<String> {
+    index
+        .as_array()?
+        .iter()
+        .filter_map(|release| release.get("files")?.as_array())
+        .flatten()
+        .find(|file| file.get("filename").and_then(|f| f.as_str()) == Some(file_name))?
+        .get("sha256")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Run a command and return its stdout if it succeeds
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+}
+
+/// Platform executable name
+fn exe(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Build the PATH with toolchain bin directories prepended
+pub(crate) fn path_with(bin_dirs: &[&Path]) -> PackResult<OsString> {
+    let current = std::env::var_os("PATH").unwrap_or_default();
+    let paths = bin_dirs
+        .iter()
+        .map(|p| p.to_path_buf())
+        .chain(std::env::split_paths(&current));
+    std::env::join_paths(paths).map_err(|e| PackError::Config(format!("Invalid PATH: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_go_archive_name() {
+        let name = go_archive_name("1.22.5").unwrap();
+        assert!(name.starts_with("go1.22.5."));
+        if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+            assert_eq!(name, "go1.22.5.linux-amd64.tar.gz");
+        }
+    }
+
+    #[test]
+    fn test_find_go_checksum() {
+        let index = serde_json::json!([
+            {"version": "go1.22.5", "files": [
+                {"filename": "go1.22.5.linux-amd64.tar.gz", "sha256": "abc"},
+                {"filename": "go1.22.5.windows-amd64.zip", "sha256": "def"}
+            ]}
+        ]);
+        assert_eq!(
+            find_go_checksum(&index, "go1.22.5.windows-amd64.zip").as_deref(),
+            Some("def")
+        );
+        assert_eq!(
+            find_go_checksum(&index, "go1.21.0.linux-amd64.tar.gz"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_toolchain_validate() {
+        assert!(ToolchainConfig::new("1.22.5")
+            .validate("backend.go.toolchain")
+            .is_ok());
+        assert!(ToolchainConfig::new("")
+            .validate("backend.go.toolchain")
+            .is_err());
+
+        let mirror = ToolchainConfig {
+            url: Some("https://mirror.example.com/go.tar.gz".to_string()),
+            ..ToolchainConfig::new("1.22.5")
+        };
+        assert!(mirror.validate("backend.go.toolchain").is_err());
+    }
+}
@@ -0,0 +1,527 @@
+//! Asset transforms during bundling
+//!
+//! `[frontend.transform]` shrinks frontend assets as they are bundled,
+//! without a separate frontend build step. Steps are configured per file
+//! extension:
+//!
+//! ```toml
+//! [frontend.transform]
+//! jpeg_quality = 85
+//!
+//! [frontend.transform.extensions]
+//! js = ["minify"]
+//! css = ["minify"]
+//! html = ["strip_comments"]
+//! png = ["optimize"]
+//! ```
+//!
+//! - `strip_comments`: drop comments from JS, CSS and HTML (`/*! ... */`
+//!   license comments and IE conditional comments are kept)
+//! - `minify`: strip comments and squeeze whitespace of JS and CSS; JS
+//!   keeps its line breaks, so automatic semicolon insertion is unaffected
+//! - `optimize`: re-encode PNG (lossless, best compression) and JPEG (at
+//!   `jpeg_quality`, lossy)
+//!
+//! A transform that does not shrink an asset keeps the original. Other
+//! transforms plug in through [`AssetTransform`] and
+//! [`BundleBuilder::with_transform`](crate::BundleBuilder::with_transform).
+
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+/// Transforms the content of bundled assets
+pub trait AssetTransform: Send + Sync {
+    /// New content of the asset at `path` (`None`: keep it unchanged)
+    fn transform(&self, path: &str, content: &[u8]) -> PackResult<Option<Vec<u8>>>;
+}
+
+/// Transform step applied to an extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformStep {
+    /// Drop comments (JS, CSS, HTML)
+    StripComments,
+    /// Drop comments and squeeze whitespace (JS, CSS)
+    Minify,
+    /// Re-encode images (PNG, JPEG)
+    Optimize,
+}
+
+impl TransformStep {
+    /// Name as used in TOML
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::StripComments => "strip_comments",
+            Self::Minify => "minify",
+            Self::Optimize => "optimize",
+        }
+    }
+}
+
+/// Kind of content, from the file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentKind {
+    Js,
+    Css,
+    Html,
+    Png,
+    Jpeg,
+}
+
+impl ContentKind {
+    fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "js" | "mjs" | "cjs" => Some(Self::Js),
+            "css" => Some(Self::Css),
+            "html" | "htm" => Some(Self::Html),
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            _ => None,
+        }
+    }
+
+    fn supports(&self, step: TransformStep) -> bool {
+        match step {
+            TransformStep::StripComments => matches!(self, Self::Js | Self::Css | Self::Html),
+            TransformStep::Minify => matches!(self, Self::Js | Self::Css),
+            TransformStep::Optimize => matches!(self, Self::Png | Self::Jpeg),
+        }
+    }
+}
+
+/// Asset transform settings (`[frontend.transform]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformConfig {
+    /// Steps per file extension (without the dot)
+    #[serde(default)]
+    pub extensions: BTreeMap<String, Vec<TransformStep>>,
+
+    /// JPEG quality of `optimize` (1-100)
+    #[serde(default = "default_jpeg_quality")]
+    pub jpeg_quality: u8,
+}
+
+fn default_jpeg_quality() -> u8 {
+    85
+}
+
+impl Default for TransformConfig {
+    fn default() -> Self {
+        Self {
+            extensions: BTreeMap::new(),
+            jpeg_quality: default_jpeg_quality(),
+        }
+    }
+}
+
+impl TransformConfig {
+    /// Minify JS and CSS, strip HTML comments and optimize PNG; JPEG is
+    /// left alone (its optimization is lossy)
+    pub fn recommended() -> Self {
+        let mut config = Self::default();
+        for extension in ["js", "mjs", "css"] {
+            config = config.with(extension, &[TransformStep::Minify]);
+        }
+        config
+            .with("html", &[TransformStep::StripComments])
+            .with("png", &[TransformStep::Optimize])
+    }
+
+    /// Apply `steps` to files with `extension`
+    pub fn with(mut self, extension: &str, steps: &[TransformStep]) -> Self {
+        self.extensions.insert(
+            extension.trim_start_matches('.').to_lowercase(),
+            steps.to_vec(),
+        );
+        self
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        if !(1..=100).contains(&self.jpeg_quality) {
+            return Err(PackError::Config(format!(
+                "[frontend.transform] jpeg_quality must be 1-100 (got {})",
+                self.jpeg_quality
+            )));
+        }
+        for (extension, steps) in &self.extensions {
+            let kind = ContentKind::from_extension(extension).ok_or_else(|| {
+                PackError::Config(format!(
+                    "[frontend.transform] has no transforms for .{} files",
+                    extension
+                ))
+            })?;
+            if let Some(step) = steps.iter().find(|step| !kind.supports(**step)) {
+                return Err(PackError::Config(format!(
+                    "[frontend.transform] step '{}' does not apply to .{} files",
+                    step.as_str(),
+                    extension
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn steps(&self, path: &str) -> Option<(ContentKind, &[TransformStep])> {
+        let (_, extension) = path.rsplit_once('.')?;
+        let steps = self.extensions.get(&extension.to_lowercase())?;
+        Some((ContentKind::from_extension(extension)?, steps))
+    }
+}
+
+impl AssetTransform for TransformConfig {
+    fn transform(&self, path: &str, content: &[u8]) -> PackResult<Option<Vec<u8>>> {
+        let Some((kind, steps)) = self.steps(path) else {
+            return Ok(None);
+        };
+        let mut current: Option<Vec<u8>> = None;
+        for step in steps {
+            let input = current.as_deref().unwrap_or(content);
+            let output = match (kind, step) {
+                (ContentKind::Png | ContentKind::Jpeg, _) => {
+                    let encoded = if kind == ContentKind::Png {
+                        optimize_png(input)
+                    } else {
+                        optimize_jpeg(input, self.jpeg_quality)
+                    };
+                    // An undecodable image is bundled as it is
+                    encoded
+                        .map_err(|e| tracing::warn!("Not optimizing {}: {}", path, e))
+                        .ok()
+                }
+                (_, step) => {
+                    let Ok(text) = std::str::from_utf8(input) else {
+                        tracing::debug!("Not transforming {}: not UTF-8", path);
+                        return Ok(None);
+                    };
+                    let minify = *step == TransformStep::Minify;
+                    let output = match kind {
+                        ContentKind::Js => strip_js(text, minify),
+                        ContentKind::Css => strip_css(text, minify),
+                        _ => strip_html_comments(text),
+                    };
+                    Some(output.into_bytes())
+                }
+            };
+            if let Some(output) = output.filter(|o| o.len() < input.len()) {
+                current = Some(output);
+            }
+        }
+        Ok(current)
+    }
+}
+
+/// Re-encode a PNG losslessly with the best compression
+fn optimize_png(content: &[u8]) -> Result<Vec<u8>, image::ImageError> {
+    use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+    let image = image::load_from_memory_with_format(content, image::ImageFormat::Png)?;
+    let mut output = Vec::new();
+    image.write_with_encoder(PngEncoder::new_with_quality(
+        &mut output,
+        CompressionType::Best,
+        FilterType::Adaptive,
+    ))?;
+    Ok(output)
+}
+
+/// Re-encode a JPEG at `quality`
+fn optimize_jpeg(content: &[u8], quality: u8) -> Result<Vec<u8>, image::ImageError> {
+    use image::codecs::jpeg::JpegEncoder;
+    let image = image::load_from_memory_with_format(content, image::ImageFormat::Jpeg)?;
+    let mut output = Cursor::new(Vec::new());
+    image.write_with_encoder(JpegEncoder::new_with_quality(&mut output, quality))?;
+    Ok(output.into_inner())
+}
+
+/// JavaScript keywords after which `/` starts a regular expression
+const REGEX_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+
+/// Strip JavaScript comments; with `minify`, also indentation, blank
+/// lines and runs of spaces outside literals (line breaks are kept for
+/// automatic semicolon insertion)
+fn strip_js(source: &str, minify: bool) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    // Open template literals: brace depth of their `${` substitutions
+    let mut templates: Vec<usize> = Vec::new();
+    let mut in_template = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if in_template {
+            out.push(c);
+            match c {
+                '\\' => {
+                    if let Some(next) = next {
+                        out.push(next);
+                        i += 1;
+                    }
+                }
+                '`' => in_template = false,
+                '$' if next == Some('{') => {
+                    out.push('{');
+                    i += 1;
+                    templates.push(0);
+                    in_template = false;
+                }
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '\'' | '"' => {
+                i = copy_quoted(&chars, i, c, &mut out);
+                continue;
+            }
+            '`' => {
+                out.push(c);
+                in_template = true;
+            }
+            '{' => {
+                if let Some(depth) = templates.last_mut() {
+                    *depth += 1;
+                }
+                out.push(c);
+            }
+            '}' => match templates.last_mut() {
+                Some(0) => {
+                    templates.pop();
+                    out.push(c);
+                    in_template = true;
+                }
+                Some(depth) => {
+                    *depth -= 1;
+                    out.push(c);
+                }
+                None => out.push(c),
+            },
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if next == Some('*') => {
+                let end = find(&chars, i + 2, &['*', '/']).unwrap_or(chars.len());
+                let comment: String = chars[i..(end + 2).min(chars.len())].iter().collect();
+                if comment.starts_with("/*!") {
+                    out.push_str(&comment);
+                } else if comment.contains('\n') {
+                    push_newline(&mut out, minify);
+                } else {
+                    push_space(&mut out, minify);
+                }
+                i = end + 2;
+                continue;
+            }
+            ' ' | '\t' => push_space(&mut out, minify),
+            '\n' => push_newline(&mut out, minify),
+            '/' if regex_allowed(&out) => {
+                i = copy_regex(&chars, i, &mut out);
+                continue;
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+
+    out
+}
+
+/// Separate tokens by a space (with `minify`: one, not at line start)
+fn push_space(out: &mut String, minify: bool) {
+    if !minify || !(out.is_empty() || out.ends_with([' ', '\n'])) {
+        out.push(' ');
+    }
+}
+
+/// End a line (with `minify`: without trailing spaces or blank lines)
+fn push_newline(out: &mut String, minify: bool) {
+    if minify {
+        out.truncate(out.trim_end_matches([' ', '\t']).len());
+        if out.is_empty() || out.ends_with('\n') {
+            return;
+        }
+    }
+    out.push('\n');
+}
+
+/// Copy a quoted string starting at `start`, returning the index after it
+fn copy_quoted(chars: &[char], start: usize, quote: char, out: &mut String) -> usize {
+    out.push(quote);
+    let mut i = start + 1;
+    while i < chars.len() {
+        let c = chars[i];
+        out.push(c);
+        if c == '\\' {
+            if let Some(&next) = chars.get(i + 1) {
+                out.push(next);
+                i += 1;
+            }
+        } else if c == quote || c == '\n' {
+            return i + 1;
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Copy a regular expression literal starting at `start`
+fn copy_regex(chars: &[char], start: usize, out: &mut String) -> usize {
+    out.push('/');
+    let mut i = start + 1;
+    let mut in_class = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            return i;
+        }
+        out.push(c);
+        match c {
+            '\\' => {
+                if let Some(&next) = chars.get(i + 1) {
+                    out.push(next);
+                    i += 1;
+                }
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => return i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// A `/` after the code in `out` starts a regular expression
+fn regex_allowed(out: &str) -> bool {
+    let code = out.trim_end();
+    let Some(last) = code.chars().last() else {
+        return true;
+    };
+    if "(,=:[!&|?{};+-*%<>~^".contains(last) {
+        return true;
+    }
+    if last.is_alphanumeric() || last == '_' || last == '$' {
+        let word_start = code
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .map_or(0, |i| i + 1);
+        return REGEX_KEYWORDS.contains(&&code[word_start..]);
+    }
+    false
+}
+
+/// Index of `pattern` in `chars` from `start`
+fn find(chars: &[char], start: usize, pattern: &[char]) -> Option<usize> {
+    (start..chars.len()).find(|&i| chars[i..].starts_with(pattern))
+}
+
+/// Strip CSS comments; with `minify`, also whitespace that does not
+/// separate tokens
+fn strip_css(source: &str, minify: bool) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' => {
+                i = copy_quoted(&chars, i, c, &mut out);
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let end = find(&chars, i + 2, &['*', '/']).unwrap_or(chars.len());
+                if chars.get(i + 2) == Some(&'!') {
+                    out.extend(&chars[i..(end + 2).min(chars.len())]);
+                }
+                i = end + 2;
+                continue;
+            }
+            c if minify && c.is_whitespace() => {
+                let next = chars[i..].iter().find(|c| !c.is_whitespace());
+                let separates = !out.is_empty()
+                    && !out.ends_with(|c: char| "{};,>".contains(c) || c.is_whitespace())
+                    && next.is_some_and(|c| !"{};,>".contains(*c));
+                if separates {
+                    out.push(' ');
+                }
+                while i < chars.len() && chars[i].is_whitespace() {
+                    i += 1;
+                }
+                continue;
+            }
+            '}' if minify && out.ends_with(';') => {
+                out.pop();
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Elements whose content is not markup
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Strip HTML comments, keeping IE conditional comments and the content
+/// of `<script>`, `<style>`, `<textarea>` and `<title>`
+fn strip_html_comments(source: &str) -> String {
+    let lower = source.to_ascii_lowercase();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+    while i < source.len() {
+        let rest = &lower[i..];
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(source.len(), |e| i + e + 3);
+            let comment = &source[i..end];
+            if comment.starts_with("<!--[if") || comment.starts_with("<!--<![") {
+                out.push_str(comment);
+            }
+            i = end;
+            continue;
+        }
+        if let Some(element) = RAW_TEXT_ELEMENTS.iter().find(|e| {
+            rest.strip_prefix('<')
+                .and_then(|r| r.strip_prefix(**e))
+                .is_some_and(|r| r.starts_with(|c: char| c == '>' || c.is_whitespace()))
+        }) {
+            let close = format!("</{}", element);
+            let end = rest.find(&close).map_or(source.len(), |e| i + e);
+            out.push_str(&source[i..end]);
+            i = end;
+            if i < source.len() {
+                out.push_str(&source[i..i + close.len()]);
+                i += close.len();
+            }
+            continue;
+        }
+        let c = source[i..].chars().next().expect("index is in bounds");
+        out.push(c);
+        i += c.len_utf8();
+    }
+    out
+}
@@ -0,0 +1,283 @@
+//! Uninstall helpers for bare-executable distributions
+//!
+//! Apps shipped as a single executable leave traces the user cannot see: the
+//! extraction cache, the WebView profile and registered URL schemes or file
+//! associations. `[uninstall]` lists what the app registers; the packer then
+//! writes `<name>-uninstall.sh` / `<name>-uninstall.cmd` next to the
+//! executable, and the runtime accepts `--uninstall` to do the same:
+//!
+//! ```toml
+//! [uninstall]
+//! script = true                     # Write the uninstall scripts
+//! flag = true                       # `myapp --uninstall`
+//! remove_user_data = true           # Also delete the WebView profile
+//! protocols = ["myapp"]             # myapp:// handlers
+//! file_associations = [".myx"]
+//! ```
+//!
+//! All names are derived from the app identity (see [`crate::AppIdentity`]).
+
+use crate::config::PackConfig;
+use crate::identity::AppIdentity;
+use crate::{PackError, PackResult, StorageLocation};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Command-line flag that makes the runtime uninstall the app
+pub const UNINSTALL_FLAG: &str = "--uninstall";
+
+/// Uninstall helper configuration
+///
+/// Located at `[uninstall]` in TOML. Stored in the overlay config for the
+/// runtime's [`UNINSTALL_FLAG`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UninstallConfig {
+    /// Write uninstall scripts next to the executable
+    pub script: bool,
+
+    /// Accept [`UNINSTALL_FLAG`] at runtime
+    pub flag: bool,
+
+    /// Delete the WebView profile and user data too
+    pub remove_user_data: bool,
+
+    /// URL schemes the app registers as handler (without `://`)
+    pub protocols: Vec<String>,
+
+    /// File extensions the app registers (e.g., ".myx")
+    pub file_associations: Vec<String>,
+}
+
+impl Default for UninstallConfig {
+    fn default() -> Self {
+        Self {
+            script: true,
+            flag: true,
+            remove_user_data: true,
+            protocols: Vec::new(),
+            file_associations: Vec::new(),
+        }
+    }
+}
+
+impl UninstallConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        for protocol in &self.protocols {
+            let valid = protocol
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic())
+                && protocol
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+            if !valid {
+                return Err(PackError::Config(format!(
+                    "[uninstall] protocol '{}' must be a URL scheme like \"myapp\"",
+                    protocol
+                )));
+            }
+        }
+        for extension in &self.file_associations {
+            let bare = extension.strip_prefix('.').unwrap_or(extension);
+            if bare.is_empty() || !bare.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(PackError::Config(format!(
+                    "[uninstall] file association '{}' must be an extension like \".myx\"",
+                    extension
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// User data directory (WebView profile) of an app on this machine
+pub fn user_data_dir(identity: &AppIdentity) -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("AuroraView")
+        .join("data")
+        .join(identity.cache_key())
+}
+
+/// Write the uninstall scripts next to an executable
+///
+/// Returns the paths of the written scripts.
+pub(crate) fn write_scripts(
+    executable: &Path,
+    config: &PackConfig,
+    uninstall: &UninstallConfig,
+) -> PackResult<Vec<PathBuf>> {
+    let identity = config.identity();
+    let key = identity.cache_key();
+    if !key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ' '))
+    {
+        return Err(PackError::Config(format!(
+            "[uninstall] cannot script removal of '{}': use an identifier of letters, \
+             digits, '.', '-' and '_'",
+            key
+        )));
+    }
+
+    let script = UninstallScript {
+        title: &config.window.title,
+        identity: &identity,
+        uninstall,
+        portable_data: config
+            .storage
+            .as_ref()
+            .is_some_and(|s| s.location == StorageLocation::Portable),
+    };
+    let dir = executable.parent().unwrap_or_else(|| Path::new("."));
+    let sh_path = dir.join(format!("{}-uninstall.sh", config.output_name));
+    fs::write(&sh_path, script.shell())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&sh_path, fs::Permissions::from_mode(0o755))?;
+    }
+    let cmd_path = dir.join(format!("{}-uninstall.cmd", config.output_name));
+    fs::write(&cmd_path, script.cmd())?;
+
+    tracing::info!("Generated uninstall scripts for {}", identity.identifier());
+    Ok(vec![sh_path, cmd_path])
+}
+
+struct UninstallScript<'a> {
+    title: &'a str,
+    identity: &'a AppIdentity,
+    uninstall: &'a UninstallConfig,
+    portable_data: bool,
+}
+
+impl UninstallScript<'_> {
+    fn shell(&self) -> String {
+        let key = self.identity.cache_key();
+        let desktop = self.identity.linux_desktop_file();
+        let mut script = format!(
+            r#"#!/bin/sh
+# Generated by auroraview-pack: removes the traces of {title} ({id})
+APP_DIR="$(cd "$(dirname "$0")" && pwd)"
+
+case "$(uname -s)" in
+    Darwin)
+        CACHE_ROOT="$HOME/Library/Caches"
+        DATA_ROOT="$HOME/Library/Application Support"
+        ;;
+    *)
+        CACHE_ROOT="${{XDG_CACHE_HOME:-$HOME/.cache}}"
+        DATA_ROOT="${{XDG_DATA_HOME:-$HOME/.local/share}}"
+        ;;
+esac
+
+rm -rf "$CACHE_ROOT/AuroraView/runtime/{key}"
+"#,
+            title = shell_text(self.title),
+            id = key,
+        );
+        if self.uninstall.remove_user_data {
+            script.push_str(&format!("rm -rf \"$DATA_ROOT/AuroraView/data/{}\"\n", key));
+            if self.portable_data {
+                script.push_str("rm -rf \"$APP_DIR/data\"\n");
+            }
+        }
+        script.push_str(&format!(
+            r#"
+# Desktop entry and handlers (Linux)
+APPS_DIR="${{XDG_DATA_HOME:-$HOME/.local/share}}/applications"
+rm -f "$APPS_DIR/{desktop}"
+MIMEAPPS="${{XDG_CONFIG_HOME:-$HOME/.config}}/mimeapps.list"
+if [ -f "$MIMEAPPS" ]; then
+    sed -i.bak "/={desktop}/d" "$MIMEAPPS" && rm -f "$MIMEAPPS.bak"
+fi
+if command -v update-desktop-database >/dev/null 2>&1; then
+    update-desktop-database "$APPS_DIR" >/dev/null 2>&1
+fi
+
+echo "Removed {title} data. Delete the executable to finish."
+"#,
+            title = shell_text(self.title),
+        ));
+        script
+    }
+
+    fn cmd(&self) -> String {
+        let key = self.identity.cache_key();
+        let prog_id = self.identity.app_user_model_id();
+        let mut script = format!(
+            "@echo off\r\n\
+             rem Generated by auroraview-pack: removes the traces of {key}\r\n\
+             rmdir /s /q \"%LOCALAPPDATA%\\AuroraView\\runtime\\{key}\" 2>nul\r\n",
+        );
+        if self.uninstall.remove_user_data {
+            script.push_str(&format!(
+                "rmdir /s /q \"%LOCALAPPDATA%\\AuroraView\\data\\{}\" 2>nul\r\n",
+                key
+            ));
+            if self.portable_data {
+                script.push_str("rmdir /s /q \"%~dp0data\" 2>nul\r\n");
+            }
+        }
+        for protocol in &self.uninstall.protocols {
+            script.push_str(&format!(
+                "reg delete \"HKCU\\Software\\Classes\\{}\" /f >nul 2>&1\r\n",
+                protocol
+            ));
+        }
+        for extension in &self.uninstall.file_associations {
+            let extension = extension.strip_prefix('.').unwrap_or(extension);
+            script.push_str(&format!(
+                "reg delete \"HKCU\\Software\\Classes\\.{}\\OpenWithProgids\" /v \"{}\" /f >nul 2>&1\r\n",
+                extension, prog_id
+            ));
+        }
+        script.push_str(&format!(
+            "reg delete \"HKCU\\Software\\Classes\\{id}\" /f >nul 2>&1\r\n\
+             reg delete \"HKCU\\Software\\Classes\\AppUserModelId\\{id}\" /f >nul 2>&1\r\n\
+             echo Removed app data. Delete the executable to finish.\r\n",
+            id = prog_id
+        ));
+        script
+    }
+}
+
+/// Text safe inside a double-quoted shell string or comment
+fn shell_text(text: &str) -> String {
+    text.replace(['\n', '\r', '"', '$', '`', '\\'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uninstall_scripts() {
+        let identity = AppIdentity::new(Some("com.acme.viewer"), "viewer");
+        let uninstall = UninstallConfig {
+            protocols: vec!["acme-viewer".to_string()],
+            file_associations: vec![".acv".to_string()],
+            ..Default::default()
+        };
+        let script = UninstallScript {
+            title: "Acme Viewer",
+            identity: &identity,
+            uninstall: &uninstall,
+            portable_data: false,
+        };
+
+        let sh = script.shell();
+        assert!(sh.contains("rm -rf \"$CACHE_ROOT/AuroraView/runtime/com.acme.viewer\""));
+        assert!(sh.contains("rm -rf \"$DATA_ROOT/AuroraView/data/com.acme.viewer\""));
+        assert!(sh.contains("rm -f \"$APPS_DIR/com.acme.viewer.desktop\""));
+        assert!(!sh.contains("$APP_DIR/data"));
+
+        let cmd = script.cmd();
+        assert!(cmd.contains("HKCU\\Software\\Classes\\acme-viewer\""));
+        assert!(cmd.contains(".acv\\OpenWithProgids\" /v \"com.acme.viewer\""));
+        assert!(cmd.contains("AppUserModelId\\com.acme.viewer"));
+    }
+}
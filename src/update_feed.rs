@@ -0,0 +1,425 @@
+//! Update feed generation
+//!
+//! `[bundle.update_feed]` makes the packer write an update feed next to the
+//! packed executable, so the in-app updater (and Sparkle-like external
+//! tools) can consume what the build produced without a separate release
+//! script:
+//!
+//! ```toml
+//! [bundle.update_feed]
+//! base_url = "https://downloads.acme.example/{version}/"
+//! release_notes_url = "https://acme.example/releases/{version}"
+//! minimum_version = "1.2.0"       # Older versions must reinstall
+//! # file = "latest.json"          # Relative to the output directory
+//! # appcast = "appcast.xml"       # Also write a Sparkle appcast
+//! ```
+//!
+//! Each pack adds its platform's artifact (URL, size, SHA-256 and, with
+//! `[signing]`, a signature) to the feed, tagged with the
+//! `[runtime.update]` channel. Packs of the same version and channel for
+//! other platforms are merged into one document:
+//!
+//! ```json
+//! {
+//!   "name": "my-app",
+//!   "version": "1.4.0",
+//!   "channel": "stable",
+//!   "pub_date": "2026-10-16T09:30:00Z",
+//!   "notes_url": "https://acme.example/releases/1.4.0",
+//!   "minimum_version": "1.2.0",
+//!   "platforms": {
+//!     "windows-x86_64": {
+//!       "url": "https://downloads.acme.example/1.4.0/my-app.exe",
+//!       "size": 48211968,
+//!       "sha256": "9f2c...",
+//!       "signature": "Zm9v..."
+//!     }
+//!   }
+//! }
+//! ```
+
+use crate::common::TargetPlatform;
+use crate::signing::OverlaySigner;
+use crate::{PackConfig, PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default feed file name
+const DEFAULT_FEED_FILE: &str = "latest.json";
+
+/// Update feed configuration
+///
+/// Located at `[bundle.update_feed]` in TOML. Pack-time only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateFeedConfig {
+    /// Feed file, relative to the output directory
+    pub file: PathBuf,
+
+    /// Sparkle appcast to write as well, relative to the output directory
+    pub appcast: Option<PathBuf>,
+
+    /// URL the artifacts are published under; `{version}` and `{platform}`
+    /// are replaced (default: artifact URLs are the bare file names)
+    pub base_url: Option<String>,
+
+    /// Release notes URL; `{version}` is replaced
+    pub release_notes_url: Option<String>,
+
+    /// Oldest version that may update to this one
+    pub minimum_version: Option<String>,
+}
+
+impl Default for UpdateFeedConfig {
+    fn default() -> Self {
+        Self {
+            file: PathBuf::from(DEFAULT_FEED_FILE),
+            appcast: None,
+            base_url: None,
+            release_notes_url: None,
+            minimum_version: None,
+        }
+    }
+}
+
+impl UpdateFeedConfig {
+    /// Validate the configuration
+    pub fn validate(&self) -> PackResult<()> {
+        if self.file.as_os_str().is_empty() {
+            return Err(PackError::Config(
+                "[bundle.update_feed] file must not be empty".to_string(),
+            ));
+        }
+        if let Some(ref base_url) = self.base_url {
+            check_url("base_url", &expand(base_url, "1.0.0", "linux-x86_64"))?;
+        }
+        if let Some(ref notes) = self.release_notes_url {
+            check_url("release_notes_url", &expand(notes, "1.0.0", ""))?;
+        }
+        if self
+            .minimum_version
+            .as_deref()
+            .is_some_and(|v| v.trim().is_empty())
+        {
+            return Err(PackError::Config(
+                "[bundle.update_feed] minimum_version must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Download URL of an artifact
+    pub fn artifact_url(&self, file_name: &str, version: &str, platform: &str) -> String {
+        match self.base_url {
+            Some(ref base_url) => {
+                let base = expand(base_url, version, platform);
+                format!("{}/{}", base.trim_end_matches('/'), file_name)
+            }
+            None => file_name.to_string(),
+        }
+    }
+}
+
+/// Artifact of one platform in an update feed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedArtifact {
+    /// Download URL
+    pub url: String,
+    /// Size in bytes
+    pub size: u64,
+    /// SHA-256 (hex) of the artifact
+    pub sha256: String,
+    /// Signature (base64) of the artifact, if signed (Ed25519 for Sparkle)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// Update feed document (`latest.json`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateFeed {
+    /// App name
+    pub name: String,
+    /// Released version
+    pub version: String,
+    /// Update channel (`[runtime.update]`; none means stable)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Publication time (RFC 3339, UTC)
+    pub pub_date: String,
+    /// Release notes URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_url: Option<String>,
+    /// Oldest version that may update to this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum_version: Option<String>,
+    /// Artifacts by platform key (e.g. `windows-x86_64`)
+    pub platforms: BTreeMap<String, FeedArtifact>,
+}
+
+impl UpdateFeed {
+    /// Read a feed file
+    pub fn load(path: &Path) -> PackResult<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Keep the other platforms of an earlier feed of the same version
+    ///
+    /// Returns `false` (and keeps nothing) if `previous` is another version
+    /// or channel.
+    pub fn merge(&mut self, previous: UpdateFeed) -> bool {
+        if previous.version != self.version || previous.channel != self.channel {
+            return false;
+        }
+        for (platform, artifact) in previous.platforms {
+            self.platforms.entry(platform).or_insert(artifact);
+        }
+        true
+    }
+
+    /// Render the feed as a Sparkle appcast
+    pub fn to_appcast(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <rss version=\"2.0\" xmlns:sparkle=\"http://www.andymatuschak.org/xml-namespaces/sparkle\">\n\
+             \x20 <channel>\n",
+        );
+        xml.push_str(&format!("    <title>{}</title>\n", xml_escape(&self.name)));
+        for (platform, artifact) in &self.platforms {
+            xml.push_str("    <item>\n");
+            xml.push_str(&format!(
+                "      <title>Version {}</title>\n",
+                xml_escape(&self.version)
+            ));
+            if let Some(date) = rfc2822(&self.pub_date) {
+                xml.push_str(&format!("      <pubDate>{}</pubDate>\n", date));
+            }
+            xml.push_str(&format!(
+                "      <sparkle:version>{0}</sparkle:version>\n      \
+                 <sparkle:shortVersionString>{0}</sparkle:shortVersionString>\n",
+                xml_escape(&self.version)
+            ));
+            if let Some(ref minimum) = self.minimum_version {
+                xml.push_str(&format!(
+                    "      <sparkle:minimumAutoupdateVersion>{}</sparkle:minimumAutoupdateVersion>\n",
+                    xml_escape(minimum)
+                ));
+            }
+            if let Some(ref notes) = self.notes_url {
+                xml.push_str(&format!(
+                    "      <sparkle:releaseNotesLink>{}</sparkle:releaseNotesLink>\n",
+                    xml_escape(notes)
+                ));
+            }
+            let os = platform.split('-').next().unwrap_or(platform);
+            xml.push_str(&format!(
+                "      <enclosure url=\"{}\" sparkle:os=\"{}\" length=\"{}\" type=\"application/octet-stream\"",
+                xml_escape(&artifact.url),
+                xml_escape(os),
+                artifact.size
+            ));
+            if let Some(ref signature) = artifact.signature {
+                xml.push_str(&format!(
+                    " sparkle:edSignature=\"{}\"",
+                    xml_escape(signature)
+                ));
+            }
+            xml.push_str("/>\n    </item>\n");
+        }
+        xml.push_str("  </channel>\n</rss>\n");
+        xml
+    }
+}
+
+/// Platform key of a target, as used in update feeds (e.g. `windows-x86_64`)
+pub fn platform_key(target: TargetPlatform) -> String {
+    let os = match target {
+        TargetPlatform::Current => return platform_key(TargetPlatform::current()),
+        TargetPlatform::Windows => "windows",
+        TargetPlatform::MacOS => "darwin",
+        TargetPlatform::Linux => "linux",
+    };
+    format!("{}-{}", os, std::env::consts::ARCH)
+}
+
+/// Add a packed executable to the update feed in `output_dir`, published
+/// at `published` (Unix seconds)
+///
+/// Returns the feed path.
+pub(crate) fn write(
+    config: &PackConfig,
+    feed_config: &UpdateFeedConfig,
+    executable: &Path,
+    signer: Option<&OverlaySigner>,
+    published: u64,
+) -> PackResult<PathBuf> {
+    let version = config.app_version.clone().ok_or_else(|| {
+        PackError::Config("[bundle.update_feed] requires a package version".to_string())
+    })?;
+    if !executable.is_file() {
+        return Err(PackError::Config(format!(
+            "[bundle.update_feed] needs a single-file artifact, got {}",
+            executable.display()
+        )));
+    }
+
+    let platform = platform_key(config.target_platform);
+    let file_name = executable
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (sha256, size) = crate::hashing::sha256_file(executable)?;
+    let signature = match signer {
+        Some(signer) => Some(signer.sign_artifact(&fs::read(executable)?)?),
+        None => None,
+    };
+
+    let mut feed = UpdateFeed {
+        name: config.output_name.clone(),
+        version: version.clone(),
+        channel: config.update.as_ref().map(|u| u.channel.clone()),
+        pub_date: rfc3339(published),
+        notes_url: feed_config
+            .release_notes_url
+            .as_deref()
+            .map(|notes| expand(notes, &version, "")),
+        minimum_version: feed_config.minimum_version.clone(),
+        platforms: BTreeMap::from([(
+            platform.clone(),
+            FeedArtifact {
+                url: feed_config.artifact_url(&file_name, &version, &platform),
+                size,
+                sha256,
+                signature,
+            },
+        )]),
+    };
+
+    let path = config.output_dir.join(&feed_config.file);
+    if path.exists() {
+        match UpdateFeed::load(&path) {
+            Ok(previous) => {
+                if !feed.merge(previous) {
+                    tracing::info!("Replacing update feed of another version or channel");
+                }
+            }
+            Err(e) => tracing::warn!("Replacing unreadable update feed: {}", e),
+        }
+    }
+    crate::cache_lock::write_atomic(&path, &serde_json::to_vec_pretty(&feed)?)?;
+    if let Some(ref appcast) = feed_config.appcast {
+        crate::cache_lock::write_atomic(
+            &config.output_dir.join(appcast),
+            feed.to_appcast().as_bytes(),
+        )?;
+    }
+    tracing::info!(
+        "Update feed: {} {} ({}) -> {}",
+        feed.name,
+        feed.version,
+        platform,
+        path.display()
+    );
+    Ok(path)
+}
+
+fn expand(template: &str, version: &str, platform: &str) -> String {
+    template
+        .replace("{version}", version)
+        .replace("{platform}", platform)
+}
+
+fn check_url(field: &str, value: &str) -> PackResult<()> {
+    match url::Url::parse(value) {
+        Ok(url) if url.scheme() == "https" || url.scheme() == "http" => Ok(()),
+        _ => Err(PackError::Config(format!(
+            "[bundle.update_feed] {} '{}' must be an http(s) URL",
+            field, value
+        ))),
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// RFC 3339 UTC timestamp of Unix seconds
+pub(crate) fn rfc3339(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let time = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// RFC 2822 date (for RSS) of an RFC 3339 UTC timestamp
+fn rfc2822(timestamp: &str) -> Option<String> {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+    let (year, month, day) = crate::license::parse_expiry_date(date)?;
+    let days = days_from_civil(year as i64, month, day);
+    Some(format!(
+        "{}, {:02} {} {:04} {} +0000",
+        DAYS[days.rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        time
+    ))
+}
+
+/// Date of days since the Unix epoch
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Days since the Unix epoch of a date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(1_792_143_000), "2026-10-16T09:30:00Z");
+        assert_eq!(
+            rfc2822("2026-10-16T09:30:00Z").as_deref(),
+            Some("Fri, 16 Oct 2026 09:30:00 +0000")
+        );
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+    }
+}
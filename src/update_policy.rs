@@ -0,0 +1,259 @@
+//! Update channels and auto-update constraints
+//!
+//! `[runtime.update]` records which update channel a build follows and which
+//! versions it may auto-update to, so a stable build never picks up a
+//! nightly feed and forced upgrades are decided at pack time:
+//!
+//! ```toml
+//! [runtime.update]
+//! channel = "stable"                 # stable, beta, nightly, ...
+//! feed_url = "https://downloads.acme.example/{channel}/latest.json"
+//! minimum_version = "1.2.0"          # Never auto-update to older versions
+//! maximum_version = "1.99.99"        # Never auto-update past this (pin a major)
+//! mandatory = false                  # Install updates without asking
+//! ```
+//!
+//! The runtime checks every feed it fetches with [`UpdatePolicy::decide`].
+//! Feeds written by `[bundle.update_feed]` carry the channel of the build
+//! that produced them.
+
+use crate::update_feed::UpdateFeed;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Default update channel
+pub const DEFAULT_UPDATE_CHANNEL: &str = "stable";
+
+/// Update channel and auto-update constraints
+///
+/// Located at `[runtime.update]` in TOML. Stored in the overlay config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdatePolicy {
+    /// Channel this build follows
+    pub channel: String,
+
+    /// Feed to check for updates; `{channel}` is replaced
+    pub feed_url: Option<String>,
+
+    /// Oldest version to auto-update to (prevents downgrades)
+    pub minimum_version: Option<String>,
+
+    /// Newest version to auto-update to
+    pub maximum_version: Option<String>,
+
+    /// Install updates without asking the user
+    pub mandatory: bool,
+}
+
+impl Default for UpdatePolicy {
+    fn default() -> Self {
+        Self {
+            channel: DEFAULT_UPDATE_CHANNEL.to_string(),
+            feed_url: None,
+            minimum_version: None,
+            maximum_version: None,
+            mandatory: false,
+        }
+    }
+}
+
+/// What to do with an update feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateDecision {
+    /// Offer (or, if `mandatory`, install) the feed's version
+    Install {
+        /// Install without asking
+        mandatory: bool,
+    },
+    /// The feed's version is not newer than the running one
+    UpToDate,
+    /// The feed belongs to another channel
+    WrongChannel {
+        /// Channel of the feed
+        channel: String,
+    },
+    /// The feed's version is outside `minimum_version..=maximum_version`
+    OutOfRange,
+    /// The running version is older than the feed's `minimum_version` and
+    /// has to be reinstalled
+    ReinstallRequired,
+}
+
+impl UpdatePolicy {
+    /// Policy following a channel
+    pub fn channel(channel: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Validate the policy against the package version
+    pub fn validate(&self, version: Option<&str>) -> PackResult<()> {
+        let valid_channel = !self.channel.is_empty()
+            && self
+                .channel
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.');
+        if !valid_channel {
+            return Err(PackError::Config(format!(
+                "[runtime.update] channel '{}' must be lowercase letters, digits, '-' or '.'",
+                self.channel
+            )));
+        }
+        if let Some(ref feed_url) = self.feed_url {
+            let url = self.feed_url_for_channel(feed_url);
+            if !url::Url::parse(&url).is_ok_and(|u| u.scheme() == "https" || u.scheme() == "http") {
+                return Err(PackError::Config(format!(
+                    "[runtime.update] feed_url '{}' must be an http(s) URL",
+                    feed_url
+                )));
+            }
+        }
+        for (field, value) in [
+            ("minimum_version", &self.minimum_version),
+            ("maximum_version", &self.maximum_version),
+        ] {
+            if let Some(value) = value {
+                if parse_version(value).is_none() {
+                    return Err(PackError::Config(format!(
+                        "[runtime.update] {} '{}' is not a version like \"1.2.0\"",
+                        field, value
+                    )));
+                }
+            }
+        }
+        if let (Some(min), Some(max)) = (&self.minimum_version, &self.maximum_version) {
+            if compare_versions(min, max) == Some(Ordering::Greater) {
+                return Err(PackError::Config(format!(
+                    "[runtime.update] minimum_version {} is above maximum_version {}",
+                    min, max
+                )));
+            }
+        }
+        if let (Some(version), Some(max)) = (version, &self.maximum_version) {
+            if compare_versions(version, max) == Some(Ordering::Greater) {
+                return Err(PackError::Config(format!(
+                    "Package version {} is above [runtime.update] maximum_version {}",
+                    version, max
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Feed URL with the channel filled in
+    pub fn resolved_feed_url(&self) -> Option<String> {
+        self.feed_url
+            .as_deref()
+            .map(|url| self.feed_url_for_channel(url))
+    }
+
+    /// Decide whether a build running `current_version` takes the update
+    /// offered by `feed`
+    pub fn decide(&self, feed: &UpdateFeed, current_version: &str) -> UpdateDecision {
+        let channel = feed.channel.as_deref().unwrap_or(DEFAULT_UPDATE_CHANNEL);
+        if channel != self.channel {
+            return UpdateDecision::WrongChannel {
+                channel: channel.to_string(),
+            };
+        }
+        if compare_versions(&feed.version, current_version) != Some(Ordering::Greater) {
+            return UpdateDecision::UpToDate;
+        }
+        let below_min = self
+            .minimum_version
+            .as_deref()
+            .is_some_and(|min| compare_versions(&feed.version, min) == Some(Ordering::Less));
+        let above_max = self
+            .maximum_version
+            .as_deref()
+            .is_some_and(|max| compare_versions(&feed.version, max) == Some(Ordering::Greater));
+        if below_min || above_max {
+            return UpdateDecision::OutOfRange;
+        }
+        if feed
+            .minimum_version
+            .as_deref()
+            .is_some_and(|min| compare_versions(current_version, min) == Some(Ordering::Less))
+        {
+            return UpdateDecision::ReinstallRequired;
+        }
+        UpdateDecision::Install {
+            mandatory: self.mandatory,
+        }
+    }
+
+    fn feed_url_for_channel(&self, url: &str) -> String {
+        url.replace("{channel}", &self.channel)
+    }
+}
+
+/// Compare two versions like `1.2.0` or `2.0.0-beta.1`
+///
+/// Numeric parts are compared numerically (missing parts count as 0), and a
+/// pre-release sorts before its release. Returns `None` for versions that
+/// cannot be parsed.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a_parts, a_pre) = parse_version(a)?;
+    let (b_parts, b_pre) = parse_version(b)?;
+    let len = a_parts.len().max(b_parts.len());
+    for i in 0..len {
+        let ordering = a_parts
+            .get(i)
+            .unwrap_or(&0)
+            .cmp(b_parts.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return Some(ordering);
+        }
+    }
+    Some(match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(b),
+    })
+}
+
+/// Numeric parts and pre-release tag of a version
+fn parse_version(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+    let version = version.trim().trim_start_matches('v');
+    // Build metadata does not take part in comparisons
+    let version = version.split('+').next()?;
+    let (release, pre) = match version.split_once('-') {
+        Some((release, pre)) => (release, Some(pre)),
+        None => (version, None),
+    };
+    let parts = release
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    Some((parts, pre))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.2.0", "1.10.0"), Some(Ordering::Less));
+        assert_eq!(compare_versions("1.2", "1.2.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("v2.0.0", "1.9.9"), Some(Ordering::Greater));
+        assert_eq!(
+            compare_versions("2.0.0-beta.1", "2.0.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_versions("2.0.0-beta.2", "2.0.0-beta.1"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_versions("1.0.0+abc", "1.0.0"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare_versions("nightly", "1.0.0"), None);
+    }
+}
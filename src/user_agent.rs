@@ -0,0 +1,142 @@
+//! User-agent templates
+//!
+//! `[package] user_agent` may contain tokens that are expanded at pack time,
+//! so backends can identify packed clients and their versions:
+//!
+//! ```toml
+//! [package]
+//! name = "viewer"
+//! version = "2.3.0"
+//!
+//! # Appended to the WebView's default user agent
+//! user_agent = "{default} {app_name}/{version} ({os})"
+//!
+//! # Or replacing it
+//! # user_agent = "{app_name}/{version} ({os}; {arch})"
+//! ```
+//!
+//! `{default}` is left in the overlay and replaced by the runtime with the
+//! WebView's own user agent; a template without it replaces the default.
+
+use crate::common::TargetPlatform;
+use crate::{PackError, PackResult};
+
+/// Token the runtime replaces with the WebView's default user agent
+pub const DEFAULT_USER_AGENT_TOKEN: &str = "{default}";
+
+/// Tokens expanded at pack time
+const PACK_TIME_TOKENS: &[&str] = &["app_name", "version", "os", "arch"];
+
+/// Values of the pack-time tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAgentVars {
+    /// `{app_name}`: the package name
+    pub app_name: String,
+    /// `{version}`: the package version
+    pub version: String,
+    /// `{os}`: the target platform (`Windows`, `macOS`, `Linux`)
+    pub os: String,
+    /// `{arch}`: the target architecture (e.g., `x86_64`)
+    pub arch: String,
+}
+
+impl UserAgentVars {
+    /// Values for an app packed for `platform` on this architecture
+    pub fn new(
+        app_name: impl Into<String>,
+        version: impl Into<String>,
+        platform: TargetPlatform,
+    ) -> Self {
+        let platform = match platform {
+            TargetPlatform::Current => TargetPlatform::current(),
+            other => other,
+        };
+        let os = match platform {
+            TargetPlatform::Windows => "Windows",
+            TargetPlatform::MacOS => "macOS",
+            TargetPlatform::Linux => "Linux",
+            TargetPlatform::Current => std::env::consts::OS,
+        };
+        Self {
+            app_name: app_name.into(),
+            version: version.into(),
+            os: os.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+        }
+    }
+}
+
+/// Expand the pack-time tokens of a user-agent template
+///
+/// `{default}` is kept for the runtime. Unknown tokens are an error.
+pub fn expand_user_agent(template: &str, vars: &UserAgentVars) -> PackResult<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+            return Err(PackError::Config(format!(
+                "Unclosed '{{' in user_agent '{}'",
+                template
+            )));
+        };
+        let value = match &rest[open + 1..close] {
+            "app_name" => vars.app_name.as_str(),
+            "version" => vars.version.as_str(),
+            "os" => vars.os.as_str(),
+            "arch" => vars.arch.as_str(),
+            "default" => DEFAULT_USER_AGENT_TOKEN,
+            name => {
+                return Err(PackError::Config(format!(
+                    "Unknown token {{{}}} in user_agent '{}' (expected one of: default, {})",
+                    name,
+                    template,
+                    PACK_TIME_TOKENS.join(", ")
+                )))
+            }
+        };
+        out.push_str(value);
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Check that a packed user agent has no unexpanded pack-time tokens
+pub fn validate_user_agent(user_agent: &str) -> PackResult<()> {
+    if user_agent.trim().is_empty() {
+        return Err(PackError::Config(
+            "user_agent must not be empty".to_string(),
+        ));
+    }
+    if let Some(token) = PACK_TIME_TOKENS
+        .iter()
+        .find(|token| user_agent.contains(&format!("{{{}}}", token)))
+    {
+        return Err(PackError::Config(format!(
+            "user_agent '{}' has an unexpanded {{{}}} token (see expand_user_agent)",
+            user_agent, token
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_user_agent() {
+        let vars = UserAgentVars::new("viewer", "2.3.0", TargetPlatform::Windows);
+        assert_eq!(
+            expand_user_agent("{default} {app_name}/{version} ({os})", &vars).unwrap(),
+            "{default} viewer/2.3.0 (Windows)"
+        );
+        assert_eq!(
+            expand_user_agent("Static/1.0", &vars).unwrap(),
+            "Static/1.0"
+        );
+        assert!(expand_user_agent("{app}/{version}", &vars).is_err());
+        assert!(expand_user_agent("{app_name", &vars).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Multi-version coexistence policy
+//!
+//! `[runtime.versioning]` decides what two installed versions of the same
+//! app (same identifier) share when they run on one machine:
+//!
+//! ```toml
+//! [runtime.versioning]
+//! cache = "per_version"     # Extraction cache: "per_version" or "shared"
+//! data = "shared"           # WebView profile and user data
+//! concurrent = true         # Both versions may run at the same time
+//! keep = 2                  # Version caches kept before old ones are removed
+//! migrate = "myapp.migrate:run"   # Called as run(from_version, to_version)
+//! ```
+//!
+//! The migration hook runs in the Python backend the first time a version
+//! starts on data last used by an older version, before the window opens.
+//! The policy is stored in the overlay config together with the app version.
+
+use crate::identity::AppIdentity;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Whether a resource is separate per version or shared by all versions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionIsolation {
+    /// One copy per version
+    #[default]
+    PerVersion,
+    /// One copy for all versions
+    Shared,
+}
+
+/// Multi-version coexistence policy
+///
+/// Located at `[runtime.versioning]` in TOML.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersioningConfig {
+    /// Extraction cache isolation
+    pub cache: VersionIsolation,
+
+    /// WebView profile and user data isolation
+    pub data: VersionIsolation,
+
+    /// Allow different versions to run at the same time (the single-instance
+    /// lock is per version); `false` shares one lock across versions
+    pub concurrent: bool,
+
+    /// Number of version caches kept (including the running one)
+    pub keep: u32,
+
+    /// Python entry point (`module:function`) migrating shared data from an
+    /// older version
+    pub migrate: Option<String>,
+}
+
+impl Default for VersioningConfig {
+    fn default() -> Self {
+        Self {
+            cache: VersionIsolation::PerVersion,
+            data: VersionIsolation::Shared,
+            concurrent: true,
+            keep: 2,
+            migrate: None,
+        }
+    }
+}
+
+impl VersioningConfig {
+    /// Validate the policy for an app version
+    ///
+    /// `python_backend` tells whether the app has a Python backend to run
+    /// the migration hook in.
+    pub fn validate(&self, version: Option<&str>, python_backend: bool) -> PackResult<()> {
+        let invalid = |msg: String| PackError::Config(format!("[runtime.versioning] {}", msg));
+
+        if self.keep == 0 {
+            return Err(invalid("keep must be at least 1".to_string()));
+        }
+        let version = version.map(str::trim).unwrap_or_default();
+        if self.cache == VersionIsolation::PerVersion || self.migrate.is_some() {
+            let valid = !version.is_empty()
+                && version
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'));
+            if !valid {
+                return Err(invalid(format!(
+                    "needs a package version like \"1.2.0\", got '{}'",
+                    version
+                )));
+            }
+        }
+        if let Some(ref migrate) = self.migrate {
+            if self.data != VersionIsolation::Shared {
+                return Err(invalid("migrate requires data = \"shared\"".to_string()));
+            }
+            if !python_backend {
+                return Err(invalid(
+                    "migrate requires a Python backend to run in".to_string(),
+                ));
+            }
+            let well_formed = migrate.split_once(':').is_some_and(|(module, function)| {
+                !module.is_empty()
+                    && !function.is_empty()
+                    && module
+                        .split('.')
+                        .chain([function])
+                        .all(is_python_identifier)
+            });
+            if !well_formed {
+                return Err(invalid(format!(
+                    "migrate '{}' must be a 'module:function' entry point",
+                    migrate
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Extraction cache directory of an app version
+    pub fn cache_dir(&self, identity: &AppIdentity, version: &str) -> PathBuf {
+        let dir = identity.runtime_cache_dir();
+        match self.cache {
+            VersionIsolation::PerVersion => dir.join(format!("v{}", version)),
+            VersionIsolation::Shared => dir,
+        }
+    }
+
+    /// Name of the single-instance lock of an app version
+    pub fn instance_name(&self, identity: &AppIdentity, version: &str) -> String {
+        let name = identity.single_instance_name();
+        if self.concurrent {
+            format!("{}.v{}", name, version)
+        } else {
+            name
+        }
+    }
+}
+
+/// Whether `name` is a Python identifier (ASCII subset)
+fn is_python_identifier(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_dirs() {
+        let identity = AppIdentity::new(Some("com.acme.viewer"), "viewer");
+        let policy = VersioningConfig::default();
+        assert!(policy
+            .cache_dir(&identity, "1.2.0")
+            .ends_with("com.acme.viewer/v1.2.0"));
+        assert_eq!(
+            policy.instance_name(&identity, "1.2.0"),
+            "AuroraView.com.acme.viewer.v1.2.0"
+        );
+
+        let shared = VersioningConfig {
+            cache: VersionIsolation::Shared,
+            concurrent: false,
+            ..Default::default()
+        };
+        assert!(shared
+            .cache_dir(&identity, "1.2.0")
+            .ends_with("com.acme.viewer"));
+        assert_eq!(
+            shared.instance_name(&identity, "1.2.0"),
+            "AuroraView.com.acme.viewer"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Watch mode: repack when sources change
+//!
+//! [`Packer::watch`](crate::Packer::watch) packs once, then polls the
+//! frontend directory and the Python `include_paths` and repacks
+//! (incrementally, through the build cache) whenever a file is added,
+//! removed or modified. Every pack is reported as a [`WatchEvent`], so a dev
+//! CLI can print "repacked in 1.2s".
+//!
+//! Polling needs no platform watcher and works on network drives; a change
+//! is packed once the tree has been quiet for [`WatchOptions::debounce`],
+//! so an editor saving many files triggers one pack.
+
+use crate::packer::PackOutput;
+use crate::PackError;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Watch mode settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// How often the watched directories are scanned
+    pub poll_interval: Duration,
+    /// Quiet period after the last change before repacking
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(500),
+            debounce: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Event of a watch session
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// Watched files changed; a pack starts
+    Changed {
+        /// Added, removed or modified files
+        paths: Vec<PathBuf>,
+    },
+    /// A pack finished (`changed` is empty for the initial pack)
+    Packed {
+        /// Output of the pack
+        output: Box<PackOutput>,
+        /// Files whose change triggered the pack
+        changed: Vec<PathBuf>,
+        /// Duration of the pack
+        elapsed: Duration,
+    },
+    /// A pack failed; watching continues
+    Failed {
+        /// Why the pack failed
+        error: PackError,
+        /// Files whose change triggered the pack
+        changed: Vec<PathBuf>,
+        /// Time until the pack failed
+        elapsed: Duration,
+    },
+}
+
+/// Size and modification time of every watched file
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Snapshot(BTreeMap<PathBuf, (u64, Option<SystemTime>)>);
+
+impl Snapshot {
+    /// Scan `roots`, leaving out everything below `ignore` (the output
+    /// directory, which a pack rewrites)
+    pub fn scan(roots: &[PathBuf], ignore: &Path) -> Self {
+        let mut files = BTreeMap::new();
+        for root in roots {
+            for entry in walkdir::WalkDir::new(root)
+                .into_iter()
+                .filter_entry(|e| !e.path().starts_with(ignore))
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+            {
+                if let Ok(meta) = entry.metadata() {
+                    files.insert(entry.into_path(), (meta.len(), meta.modified().ok()));
+                }
+            }
+        }
+        Self(files)
+    }
+
+    /// Files added, removed or modified since `previous`
+    pub fn changes(&self, previous: &Snapshot) -> Vec<PathBuf> {
+        let modified = self
+            .0
+            .iter()
+            .filter(|(path, stamp)| previous.0.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone());
+        let removed = previous
+            .0
+            .keys()
+            .filter(|path| !self.0.contains_key(*path))
+            .cloned();
+        let mut changes: Vec<PathBuf> = modified.chain(removed).collect();
+        changes.sort();
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_changes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().join("src");
+        let out = root.join("out");
+        std::fs::create_dir_all(&out).unwrap();
+        std::fs::write(root.join("a.py"), "a").unwrap();
+        std::fs::write(root.join("b.py"), "b").unwrap();
+        let roots = vec![root.clone()];
+        let before = Snapshot::scan(&roots, &out);
+
+        std::fs::write(root.join("a.py"), "a2").unwrap();
+        std::fs::remove_file(root.join("b.py")).unwrap();
+        std::fs::write(root.join("c.py"), "c").unwrap();
+        std::fs::write(out.join("app.exe"), "ignored").unwrap();
+        let after = Snapshot::scan(&roots, &out);
+
+        assert_eq!(
+            after.changes(&before),
+            [root.join("a.py"), root.join("b.py"), root.join("c.py")]
+        );
+        assert!(after.changes(&after).is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+//! Watermarked, expiring preview builds
+//!
+//! `[license.preview]` marks a time-boxed beta build: the packer injects a
+//! small banner noting the expiry date into every page, and once the
+//! license has expired (after the grace period) the page is replaced by a
+//! polite notice instead of the app failing at random:
+//!
+//! ```toml
+//! [license]
+//! enabled = true
+//! expires_at = "2026-12-31"
+//! grace_period_days = 3
+//! expiration_message = "This preview has ended. Get the release at https://acme.example/"
+//!
+//! [license.preview]
+//! label = "Preview · expires {expires}"
+//! position = "bottom_right"
+//! ```
+//!
+//! The runtime's license check stays authoritative; the injected script
+//! only covers pages already open when the date passes.
+
+use crate::common::LicenseConfig;
+use crate::license::expiry_cutoff;
+use crate::{PackError, PackResult};
+use serde::{Deserialize, Serialize};
+
+/// Default banner text
+const DEFAULT_LABEL: &str = "Preview build · expires {expires}";
+
+/// Default notice shown once the preview has expired
+const DEFAULT_EXPIRED_MESSAGE: &str = "This preview build has expired.";
+
+/// Where the watermark is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkPosition {
+    /// Top-left corner
+    TopLeft,
+    /// Top-right corner
+    TopRight,
+    /// Bottom-left corner
+    BottomLeft,
+    /// Bottom-right corner
+    #[default]
+    BottomRight,
+    /// Full-width banner along the top edge
+    Banner,
+}
+
+impl WatermarkPosition {
+    fn css(self) -> &'static str {
+        match self {
+            Self::TopLeft => "top:8px;left:8px;",
+            Self::TopRight => "top:8px;right:8px;",
+            Self::BottomLeft => "bottom:8px;left:8px;",
+            Self::BottomRight => "bottom:8px;right:8px;",
+            Self::Banner => "top:0;left:0;right:0;text-align:center;border-radius:0;",
+        }
+    }
+}
+
+/// Preview build configuration
+///
+/// Located at `[license.preview]` in TOML. Requires `[license] expires_at`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewConfig {
+    /// Watermark text; `{expires}` is replaced with the expiry date
+    pub label: String,
+
+    /// Watermark position
+    pub position: WatermarkPosition,
+
+    /// Replace the page with the expiration message once expired
+    pub block_after_expiry: bool,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            label: DEFAULT_LABEL.to_string(),
+            position: WatermarkPosition::default(),
+            block_after_expiry: true,
+        }
+    }
+}
+
+impl PreviewConfig {
+    /// Validate against the license it belongs to
+    pub fn validate(&self, license: &LicenseConfig) -> PackResult<()> {
+        let Some(ref expires_at) = license.expires_at else {
+            return Err(PackError::Config(
+                "[license.preview] requires [license] expires_at".to_string(),
+            ));
+        };
+        if !license.enabled {
+            return Err(PackError::Config(
+                "[license.preview] requires [license] enabled = true".to_string(),
+            ));
+        }
+        if expiry_cutoff(expires_at, 0).is_none() {
+            return Err(PackError::Config(format!(
+                "[license] expires_at '{}' must be a date like \"2026-12-31\"",
+                expires_at
+            )));
+        }
+        if self.label.trim().is_empty() {
+            return Err(PackError::Config(
+                "[license.preview] label must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Watermark text for an expiry date
+    pub fn label_for(&self, expires_at: &str) -> String {
+        self.label.replace("{expires}", expires_at)
+    }
+
+    /// Script injected into every page
+    ///
+    /// Returns `None` if the license has no valid expiry date.
+    pub fn script(&self, license: &LicenseConfig) -> Option<String> {
+        let expires_at = license.expires_at.as_deref()?;
+        let cutoff_ms = expiry_cutoff(expires_at, license.grace_period_days)? * 1000;
+        let label = json_string(&self.label_for(expires_at));
+        let message = json_string(
+            license
+                .expiration_message
+                .as_deref()
+                .unwrap_or(DEFAULT_EXPIRED_MESSAGE),
+        );
+        Some(format!(
+            r#"(function () {{
+  var cutoff = {cutoff_ms}, block = {block};
+  function show() {{
+    if (!document.body) return;
+    var expired = Date.now() >= cutoff;
+    if (expired && block) {{
+      document.documentElement.innerHTML = "";
+      var notice = document.createElement("div");
+      notice.className = "auroraview-preview-expired";
+      notice.textContent = {message};
+      document.documentElement.appendChild(notice);
+      return;
+    }}
+    if (document.querySelector(".auroraview-preview-watermark")) return;
+    var mark = document.createElement("div");
+    mark.className = "auroraview-preview-watermark";
+    mark.textContent = {label};
+    document.body.appendChild(mark);
+  }}
+  if (document.readyState === "loading") {{
+    document.addEventListener("DOMContentLoaded", show);
+  }} else {{
+    show();
+  }}
+  setInterval(show, 60000);
+}})();"#,
+            block = self.block_after_expiry,
+        ))
+    }
+
+    /// Stylesheet injected into every page
+    pub fn style(&self) -> String {
+        format!(
+            ".auroraview-preview-watermark{{position:fixed;{}z-index:2147483647;\
+             pointer-events:none;padding:4px 10px;border-radius:4px;\
+             background:rgba(0,0,0,.55);color:#fff;font:12px/1.4 system-ui,sans-serif;\
+             opacity:.85}}\
+             .auroraview-preview-expired{{position:fixed;inset:0;display:flex;\
+             align-items:center;justify-content:center;padding:32px;text-align:center;\
+             background:#fff;color:#222;font:16px/1.5 system-ui,sans-serif}}",
+            self.position.css()
+        )
+    }
+}
+
+/// JavaScript string literal of `text`
+fn json_string(text: &str) -> String {
+    // JSON strings are JS string literals; escape `<` so the text cannot
+    // close a surrounding <script> element
+    serde_json::to_string(text)
+        .unwrap_or_default()
+        .replace('<', "\\u003c")
+}
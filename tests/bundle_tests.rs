@@ -46,3 +46,82 @@ fn test_bundle_excludes() {
     assert_eq!(bundle.len(), 1);
     assert_eq!(bundle.assets()[0].0, "index.html");
 }
+
+#[test]
+fn test_bundle_builder_threads_keep_order() {
+    let temp = TempDir::new().unwrap();
+    for name in ["d.js", "a.html", "c.css", "b.js"] {
+        fs::write(temp.path().join(name), name).unwrap();
+    }
+    fs::create_dir(temp.path().join("assets")).unwrap();
+    fs::write(temp.path().join("assets/logo.svg"), "<svg/>").unwrap();
+
+    let parallel = BundleBuilder::new(temp.path())
+        .with_threads(3)
+        .build()
+        .unwrap();
+    let paths: Vec<&str> = parallel.assets().iter().map(|(p, _)| p.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec!["a.html", "assets/logo.svg", "b.js", "c.css", "d.js"]
+    );
+    assert_eq!(parallel.get("b.js"), Some(&b"b.js"[..]));
+    assert_eq!(
+        parallel.assets(),
+        BundleBuilder::new(temp.path()).build().unwrap().assets()
+    );
+}
+
+#[test]
+fn test_bundle_include_exclude_globs() {
+    let temp = TempDir::new().unwrap();
+    fs::create_dir_all(temp.path().join("assets/video")).unwrap();
+    fs::create_dir_all(temp.path().join("js")).unwrap();
+    fs::write(temp.path().join("index.html"), "<html></html>").unwrap();
+    fs::write(temp.path().join("js/app.js"), "app").unwrap();
+    fs::write(temp.path().join("js/app.js.map"), "sourcemap").unwrap();
+    fs::write(temp.path().join("assets/logo.png"), "png").unwrap();
+    fs::write(temp.path().join("assets/video/intro.mp4"), "video").unwrap();
+    fs::write(temp.path().join(".DS_Store"), "").unwrap();
+
+    let paths = |builder: BundleBuilder| -> Vec<String> {
+        builder
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect()
+    };
+
+    // Exclude by name at any depth, or by path from the root
+    let bundled = paths(
+        BundleBuilder::new(temp.path()).exclude_globs(&["*.png".into(), "assets/video/**".into()]),
+    );
+    assert_eq!(bundled, ["index.html", "js/app.js"]);
+    let bundled = paths(BundleBuilder::new(temp.path()).exclude_globs(&["video".into()]));
+    assert_eq!(bundled, ["assets/logo.png", "index.html", "js/app.js"]);
+
+    // Included source maps bypass the built-in excludes
+    let bundled = paths(BundleBuilder::new(temp.path()).include_globs(&["**".into()]));
+    assert!(bundled.contains(&"js/app.js.map".to_string()));
+    let bundled = paths(
+        BundleBuilder::new(temp.path())
+            .include_globs(&["js/*".into(), "*.html".into()])
+            .exclude_globs(&["*.js".into()]),
+    );
+    assert_eq!(bundled, ["index.html", "js/app.js.map"]);
+
+    // `*` does not cross directories
+    let bundled = paths(BundleBuilder::new(temp.path()).include_globs(&["assets/*".into()]));
+    assert_eq!(bundled, ["assets/logo.png"]);
+
+    let err = BundleBuilder::new(temp.path())
+        .include_globs(&["[".into()])
+        .list()
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid include pattern '['"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,107 @@
+//! Tests for auroraview-pack CDP test-run support
+
+use auroraview_pack::{wait_for_cdp, CdpTestConfig, PackConfig, Packer, TestRunDescriptor};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn test_descriptor_path_for() {
+    let path = TestRunDescriptor::path_for(Path::new("/out/my-app"));
+    assert_eq!(path, Path::new("/out/my-app.test.json"));
+
+    let path = TestRunDescriptor::path_for(Path::new("/out/my-app.exe"));
+    assert_eq!(path, Path::new("/out/my-app.exe.test.json"));
+}
+
+#[test]
+fn test_descriptor_roundtrip() {
+    let temp = tempdir().unwrap();
+    let descriptor = TestRunDescriptor {
+        executable: temp.path().join("my-app"),
+        port: 9222,
+        startup_timeout: 45,
+        health_url: Some("http://127.0.0.1:8080/health".to_string()),
+    };
+    assert_eq!(
+        descriptor.version_url(),
+        "http://127.0.0.1:9222/json/version"
+    );
+
+    let path = temp.path().join("my-app.test.json");
+    descriptor.save(&path).unwrap();
+    let loaded = TestRunDescriptor::load(&path).unwrap();
+    assert_eq!(loaded.port, 9222);
+    assert_eq!(loaded.startup_timeout, 45);
+    assert_eq!(loaded.health_url, descriptor.health_url);
+}
+
+#[test]
+fn test_wait_for_cdp_returns_websocket_url() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            let body = r#"{"webSocketDebuggerUrl":"ws://127.0.0.1/devtools/browser/abc"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    let ws = wait_for_cdp(port, Duration::from_secs(5)).unwrap();
+    assert_eq!(ws, "ws://127.0.0.1/devtools/browser/abc");
+}
+
+#[test]
+fn test_wait_for_cdp_timeout() {
+    // Bind and drop to get a port that is very likely unused
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let result = wait_for_cdp(port, Duration::from_millis(300));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_test_run_requires_debugging_port() {
+    let output = tempdir().unwrap();
+    let config = PackConfig::url("https://example.com")
+        .with_output("test-app")
+        .with_output_dir(output.path())
+        .with_test_run(CdpTestConfig::default());
+
+    let result = Packer::new(config).pack();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_pack_writes_descriptor() {
+    let output = tempdir().unwrap();
+    let config = PackConfig::url("https://example.com")
+        .with_output("test-app")
+        .with_output_dir(output.path())
+        .with_remote_debugging_port(9333)
+        .with_test_run(CdpTestConfig {
+            startup_timeout: 10,
+            ..Default::default()
+        });
+
+    let result = Packer::new(config).pack().unwrap();
+    let descriptor = TestRunDescriptor::load(TestRunDescriptor::path_for(&result.executable))
+        .expect("descriptor should be written");
+    assert_eq!(descriptor.executable, result.executable);
+    assert_eq!(descriptor.port, 9333);
+    assert_eq!(descriptor.startup_timeout, 10);
+}
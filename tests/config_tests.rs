@@ -1,8 +1,8 @@
 //! Tests for auroraview-pack config module
 
 use auroraview_pack::{
-    BundleStrategy, LicenseConfig, PackConfig, PackMode, PythonBundleConfig, TargetPlatform,
-    WindowConfig, WindowStartPosition,
+    BundleStrategy, KioskConfig, LaunchSpec, LicenseConfig, PackConfig, PackMode,
+    PythonBundleConfig, TargetPlatform, WindowConfig, WindowStartPosition,
 };
 use std::path::PathBuf;
 
@@ -135,6 +135,41 @@ fn test_fullstack_with_config() {
     }
 }
 
+#[test]
+fn test_process_mode() {
+    let launch = LaunchSpec::new("./bin/server").with_args(vec!["--port".to_string()]);
+    let config = PackConfig::process("./dist", launch);
+    assert_eq!(config.mode.name(), "process");
+    assert!(config.mode.embeds_assets());
+    assert!(!config.mode.has_python());
+    assert_eq!(config.mode.frontend_path(), Some(&PathBuf::from("./dist")));
+
+    let launch = config.mode.launch_spec().expect("launch spec");
+    assert_eq!(launch.command, "bin/server");
+    assert_eq!(launch.args, vec!["--port".to_string()]);
+
+    // The launch spec is part of the serialized overlay config
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["mode"]["type"], "process");
+    assert_eq!(json["mode"]["launch"]["command"], "bin/server");
+}
+
+#[test]
+fn test_pack_config_with_kiosk() {
+    let config = PackConfig::url("https://example.com")
+        .with_debug(true)
+        .with_kiosk(KioskConfig::default());
+
+    assert!(config.window.fullscreen);
+    assert!(!config.window.resizable);
+    assert!(config.kiosk.is_some());
+
+    // Kiosk disables devtools even in debug mode
+    let debug = config.debug_config();
+    assert!(debug.enabled);
+    assert!(!debug.devtools);
+}
+
 #[test]
 fn test_pack_mode_properties() {
     let url_mode = PackMode::Url {
@@ -262,3 +297,20 @@ fn test_collect_pattern() {
     assert!(json.contains("../examples/*.py"));
     assert!(json.contains("examples"));
 }
+
+#[test]
+fn test_pack_config_with_header() {
+    use auroraview_pack::HeaderRule;
+
+    let config = PackConfig::url("https://internal.example.com").with_header(HeaderRule::new(
+        "X-Tenant-Id",
+        "acme",
+        vec!["https://internal.example.com".to_string()],
+    ));
+    assert_eq!(config.network.headers.len(), 1);
+    assert!(config.network.validate().is_ok());
+
+    // Headers are part of the serialized overlay config
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["network"]["headers"][0]["name"], "X-Tenant-Id");
+}
@@ -1,7 +1,8 @@
 //! Tests for auroraview-pack deps_collector module
 
-use auroraview_pack::DepsCollector;
+use auroraview_pack::{DepsCollector, MockOutcome, RecordingRunner};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 // Note: is_stdlib and default_excludes are private functions,
 // so we test through the public DepsCollector API
@@ -50,3 +51,40 @@ fn test_collector_chained_config() {
         .include(["mypackage"]);
     let _ = collector;
 }
+
+#[test]
+fn test_collector_with_recording_runner() {
+    let temp = tempfile::tempdir().unwrap();
+    let runner = Arc::new(
+        RecordingRunner::new()
+            .on("python-test --version", MockOutcome::NotFound)
+            .on(
+                "python-test -c",
+                MockOutcome::success("/site-packages/requests\n"),
+            )
+            .on("python-test -m pip", MockOutcome::failure(1, "no network")),
+    );
+    let collector = DepsCollector::new()
+        .python_exe("python-test")
+        .with_runner(runner.clone());
+
+    assert!(!collector.is_python_available());
+    assert_eq!(
+        collector.get_package_path("requests").unwrap(),
+        Some(PathBuf::from("/site-packages/requests"))
+    );
+    // A failed pip install still reports what was collected (nothing)
+    let deps = collector
+        .collect_with_pip(&["requests".to_string()], &temp.path().join("deps"))
+        .unwrap();
+    assert_eq!(deps.file_count, 0);
+
+    let commands = runner.commands();
+    assert_eq!(commands.len(), 3);
+    assert_eq!(commands[0].command_line(), "python-test --version");
+    assert_eq!(
+        commands[2].args[..4],
+        ["-m", "pip", "install", "--target"].map(String::from)
+    );
+    assert_eq!(commands[2].args.last().unwrap(), "requests");
+}
@@ -0,0 +1,85 @@
+//! Tests for the C ABI
+
+use auroraview_pack::ffi::{
+    avpack_config_free, avpack_config_from_toml, avpack_pack, avpack_string_free, avpack_version,
+};
+use auroraview_pack::test_support::PackFixture;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+/// Take a string returned by the library
+unsafe fn take_string(s: *mut c_char) -> String {
+    assert!(!s.is_null());
+    let string = CStr::from_ptr(s).to_str().unwrap().to_string();
+    avpack_string_free(s);
+    string
+}
+
+#[test]
+fn test_ffi_pack_from_toml() {
+    let fixture = PackFixture::new().unwrap();
+    fixture.frontend().unwrap();
+    let toml = CString::new(format!(
+        r#"
+[package]
+name = "ffi-app"
+
+[frontend]
+path = "dist"
+
+[build]
+out_dir = "out"
+
+[build.store]
+dir = "{}"
+"#,
+        fixture
+            .path()
+            .join("store")
+            .display()
+            .to_string()
+            .replace('\\', "/")
+    ))
+    .unwrap();
+    let base_dir = CString::new(fixture.path().to_str().unwrap()).unwrap();
+
+    unsafe {
+        let mut error = ptr::null_mut();
+        let config = avpack_config_from_toml(toml.as_ptr(), base_dir.as_ptr(), &mut error);
+        assert!(!config.is_null());
+        assert!(error.is_null());
+
+        let result: serde_json::Value =
+            serde_json::from_str(&take_string(avpack_pack(config))).unwrap();
+        avpack_config_free(config);
+
+        assert_eq!(result["ok"], true, "{}", result);
+        let executable = result["output"]["executable"].as_str().unwrap();
+        assert!(executable.contains("ffi-app"));
+        assert!(fixture.output_dir().join(executable).is_file());
+        assert_eq!(result["output"]["asset_count"], 3);
+
+        let version = CStr::from_ptr(avpack_version()).to_str().unwrap();
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+    }
+}
+
+#[test]
+fn test_ffi_reports_errors() {
+    unsafe {
+        let mut error = ptr::null_mut();
+        let toml = CString::new("[package\nname =").unwrap();
+        let config = avpack_config_from_toml(toml.as_ptr(), ptr::null(), &mut error);
+        assert!(config.is_null());
+        assert!(take_string(error).contains("Failed to parse manifest"));
+
+        // NULL arguments are errors, not crashes
+        assert!(avpack_config_from_toml(ptr::null(), ptr::null(), ptr::null_mut()).is_null());
+        let result: serde_json::Value =
+            serde_json::from_str(&take_string(avpack_pack(ptr::null()))).unwrap();
+        assert_eq!(result["ok"], false);
+        assert_eq!(result["error"], "Configuration error: config is NULL");
+        avpack_config_free(ptr::null_mut());
+        avpack_string_free(ptr::null_mut());
+    }
+}
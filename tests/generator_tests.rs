@@ -166,6 +166,34 @@ fn test_packer_frontend_mode_no_index_html() {
     assert!(result.is_err(), "Frontend without index.html should fail");
 }
 
+#[test]
+fn test_packer_deduplicates_identical_assets() {
+    use auroraview_pack::OverlayReader;
+
+    let input_temp = tempdir().expect("Failed to create input temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+
+    let logo = vec![42u8; 64 * 1024];
+    fs::write(input_temp.path().join("index.html"), "<html></html>").unwrap();
+    fs::create_dir_all(input_temp.path().join("a")).unwrap();
+    fs::create_dir_all(input_temp.path().join("b")).unwrap();
+    fs::write(input_temp.path().join("a/logo.png"), &logo).unwrap();
+    fs::write(input_temp.path().join("b/logo.png"), &logo).unwrap();
+
+    let config = PackConfig::frontend(input_temp.path())
+        .with_output("test-app")
+        .with_output_dir(output_temp.path());
+    let output = Packer::new(config).pack().expect("pack should succeed");
+
+    assert_eq!(output.dedup.duplicate_assets, 1);
+    assert_eq!(output.dedup.saved_bytes, logo.len() as u64);
+
+    let archive = OverlayReader::open(&output.executable).unwrap().unwrap();
+    let a = archive.entry("a/logo.png").unwrap();
+    let b = archive.entry("b/logo.png").unwrap();
+    assert_eq!((a.offset, a.length), (b.offset, b.length));
+}
+
 // ============================================================================
 // Bundle Builder Tests
 // ============================================================================
@@ -475,6 +503,323 @@ fn test_packer_fullstack_validation_empty_entry_point() {
     assert!(result.is_err(), "Empty entry point should fail validation");
 }
 
+#[test]
+fn test_entry_point_parse() {
+    use auroraview_pack::EntryPoint;
+
+    assert_eq!(
+        EntryPoint::parse("myapp.main:run").unwrap(),
+        EntryPoint::Module {
+            module: "myapp.main".to_string(),
+            function: Some("run".to_string()),
+        }
+    );
+    assert_eq!(
+        EntryPoint::parse("myapp.main").unwrap().top_level(),
+        Some("myapp")
+    );
+    assert_eq!(
+        EntryPoint::parse("app/main.py").unwrap(),
+        EntryPoint::Script("app/main.py".into())
+    );
+    assert_eq!(
+        EntryPoint::parse("myapp.main:App.run").unwrap().to_string(),
+        "myapp.main:App.run"
+    );
+    assert!(EntryPoint::parse("my-app:run").is_err());
+    assert!(EntryPoint::parse("main:").is_err());
+    assert!(EntryPoint::parse("1main:run").is_err());
+}
+
+#[test]
+fn test_packer_fullstack_validation_entry_point_not_importable() {
+    use auroraview_pack::LocalArchive;
+
+    let temp = tempdir().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+    let source = temp.path().join("src");
+    fs::create_dir_all(source.join("myapp")).unwrap();
+    fs::write(source.join("myapp/__init__.py"), "").unwrap();
+    fs::write(source.join("myapp/app.py"), "def run(): pass").unwrap();
+    fs::write(source.join("tools.py"), "").unwrap();
+
+    let pack = |entry_point: &str, packages: Vec<String>| {
+        let python = PythonBundleConfig {
+            include_paths: vec![source.clone()],
+            packages,
+            ..PythonBundleConfig::new(entry_point)
+        };
+        let config = PackConfig::fullstack_with_config(&frontend, python)
+            .with_output("test-app")
+            .with_output_dir(temp.path().join("out"));
+        // Past validation, packing stops at the missing distribution
+        Packer::new(config)
+            .with_python_provider(LocalArchive::new(temp.path().join("missing.tar.gz")))
+            .pack()
+    };
+
+    // Fails before any runtime is downloaded, listing the candidates
+    let err = pack("myapp.main:run", Vec::new()).unwrap_err().to_string();
+    assert!(err.contains("module 'myapp.main' not found"), "{}", err);
+    assert!(
+        err.contains("candidates: myapp, myapp.app, tools"),
+        "{}",
+        err
+    );
+
+    let err = pack("main.py", Vec::new()).unwrap_err().to_string();
+    assert!(err.contains("script 'main.py' not found"), "{}", err);
+
+    let err = pack("myapp.main:", Vec::new()).unwrap_err().to_string();
+    assert!(err.contains("Invalid Python entry_point"), "{}", err);
+
+    // Modules of the listed packages are resolved once installed
+    let err = pack("flask.cli:main", vec!["Flask>=3".to_string()])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("archive not found"), "{}", err);
+    let err = pack("myapp.app:run", Vec::new()).unwrap_err().to_string();
+    assert!(err.contains("archive not found"), "{}", err);
+}
+
+#[test]
+fn test_packer_process_missing_binary() {
+    use auroraview_pack::LaunchSpec;
+
+    let frontend_temp = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+
+    fs::write(frontend_temp.path().join("index.html"), "<html></html>").unwrap();
+
+    let launch = LaunchSpec::new(frontend_temp.path().join("missing-server"));
+    let config = PackConfig::process(frontend_temp.path(), launch)
+        .with_output("test-app")
+        .with_output_dir(output_temp.path());
+
+    let packer = Packer::new(config);
+    let result = packer.pack();
+
+    assert!(
+        result.is_err(),
+        "Missing backend binary should fail validation"
+    );
+}
+
+#[test]
+fn test_packer_dev_server_skips_frontend_assets() {
+    use auroraview_pack::{BuildProfile, OverlayReader, PackError};
+
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+
+    // Frontend path does not exist: the dev server provides the frontend
+    let config = PackConfig::frontend("/nonexistent/dist")
+        .with_dev_server("http://localhost:5173")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path());
+
+    let packer = Packer::new(config);
+    let output = packer.pack().expect("dev pack should succeed");
+    assert_eq!(output.asset_count, 0);
+
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+    assert_eq!(overlay.config.profile, BuildProfile::Dev);
+    assert_eq!(
+        overlay.config.dev_server_url.as_deref(),
+        Some("http://localhost:5173")
+    );
+    assert!(overlay.assets.is_empty());
+
+    // The dev profile without a dev server is a configuration error
+    let mut config = PackConfig::frontend("/nonexistent/dist")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path());
+    config.profile = BuildProfile::Dev;
+    let err = Packer::new(config).pack().unwrap_err();
+    assert!(matches!(err, PackError::Config(_)), "{}", err);
+}
+
+#[test]
+fn test_packer_rewrites_html_branding() {
+    use auroraview_pack::{HtmlBranding, OverlayReader};
+
+    let frontend_temp = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    fs::write(
+        frontend_temp.path().join("index.html"),
+        "<html><head><title>Vite App</title></head><body></body></html>",
+    )
+    .unwrap();
+
+    let config = PackConfig::frontend(frontend_temp.path())
+        .with_html_branding(HtmlBranding {
+            title: Some("Branded App".to_string()),
+            theme_color: Some("#1e88e5".to_string()),
+            description: None,
+        })
+        .with_output("test-app")
+        .with_output_dir(output_temp.path());
+
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+    let (_, index) = overlay
+        .assets
+        .iter()
+        .find(|(path, _)| path == "index.html")
+        .expect("index.html");
+    let index = String::from_utf8(index.clone()).unwrap();
+    assert!(index.contains("<title>Branded App</title>"));
+    assert!(index.contains(r##"<meta name="theme-color" content="#1e88e5">"##));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pyoxidizer_pack_stages_branding_and_certificates() {
+    use auroraview_pack::HtmlBranding;
+    use std::collections::HashMap;
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().expect("Failed to create temp directory");
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(
+        frontend.join("index.html"),
+        "<html><head><title>Vite App</title></head><body></body></html>",
+    )
+    .unwrap();
+    let ca_path = temp.path().join("internal-ca.pem");
+    fs::write(
+        &ca_path,
+        "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+    )
+    .unwrap();
+
+    // Stand-in for PyOxidizer copying the bundled resources out
+    let pyoxidizer = temp.path().join("pyoxidizer");
+    fs::write(
+        &pyoxidizer,
+        r#"#!/bin/sh
+[ "$1" = "--version" ] && { echo "PyOxidizer 0.24.0"; exit 0; }
+src=$(sed -n 's/.*strip_prefix = "\([^"]*resources-[^"]*\)".*/\1/p' pyoxidizer.bzl)
+cp -R "$src" "$CAPTURE"
+mkdir -p build/install && echo app > build/install/oxidized
+"#,
+    )
+    .unwrap();
+    fs::set_permissions(&pyoxidizer, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let backend = temp.path().join("backend");
+    fs::create_dir_all(&backend).unwrap();
+    fs::write(backend.join("main.py"), "def run(): pass\n").unwrap();
+
+    let capture = temp.path().join("capture");
+    let python = PythonBundleConfig {
+        strategy: BundleStrategy::PyOxidizer,
+        pyoxidizer_path: Some(pyoxidizer),
+        include_paths: vec![backend],
+        ..PythonBundleConfig::new("main:run")
+    };
+    let config = PackConfig::fullstack_with_config(&frontend, python)
+        .with_output("oxidized")
+        .with_output_dir(temp.path().join("out"))
+        .with_env(HashMap::from([(
+            "CAPTURE".to_string(),
+            capture.display().to_string(),
+        )]))
+        .with_html_branding(HtmlBranding {
+            title: Some("Branded App".to_string()),
+            theme_color: None,
+            description: None,
+        })
+        .with_ca_certificate(&ca_path);
+
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    assert_eq!(output.asset_count, 1);
+
+    let index = fs::read_to_string(capture.join("frontend/index.html")).unwrap();
+    assert!(index.contains("<title>Branded App</title>"));
+    assert!(capture.join("certs/ca/0/internal-ca.pem").is_file());
+    let network: serde_json::Value =
+        serde_json::from_slice(&fs::read(capture.join("network.json")).unwrap()).unwrap();
+    assert_eq!(
+        network["ca_certificates"],
+        serde_json::json!(["certs/ca/0/internal-ca.pem"])
+    );
+}
+
+#[test]
+fn test_packer_embeds_about_data() {
+    use auroraview_pack::{
+        AboutConfig, AboutData, AboutInfo, OverlayReader, ABOUT_JSON_PATH, ABOUT_PREFIX,
+    };
+
+    let frontend_temp = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    fs::write(frontend_temp.path().join("index.html"), "<html></html>").unwrap();
+    let changelog = frontend_temp.path().join("CHANGELOG.md");
+    fs::write(&changelog, "## 1.2.0\n- Initial release\n").unwrap();
+
+    let about = AboutConfig {
+        changelog: Some(changelog),
+        info: AboutInfo {
+            name: "About App".to_string(),
+            version: Some("1.2.0".to_string()),
+            license: Some("MIT".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let config = PackConfig::frontend(frontend_temp.path().join("index.html"))
+        .with_about(about)
+        .with_output("test-app")
+        .with_output_dir(output_temp.path());
+
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+    let asset = |name: &str| {
+        overlay
+            .assets
+            .iter()
+            .find(|(path, _)| path == name)
+            .map(|(_, content)| content.clone())
+    };
+
+    let data: AboutData = serde_json::from_slice(&asset(ABOUT_JSON_PATH).unwrap()).unwrap();
+    assert_eq!(data.info.name, "About App");
+    assert_eq!(data.info.version.as_deref(), Some("1.2.0"));
+    let changelog_path = data.changelog.expect("changelog embedded");
+    assert_eq!(changelog_path, format!("{}CHANGELOG.md", ABOUT_PREFIX));
+    assert!(asset(&changelog_path).is_some());
+    // No bundled packages in frontend mode
+    assert!(data.third_party_notices.is_none());
+}
+
+#[test]
+fn test_packer_about_missing_changelog() {
+    use auroraview_pack::AboutConfig;
+
+    let frontend_temp = tempdir().expect("Failed to create frontend temp directory");
+    fs::write(frontend_temp.path().join("index.html"), "<html></html>").unwrap();
+
+    let config = PackConfig::frontend(frontend_temp.path()).with_about(AboutConfig {
+        changelog: Some(frontend_temp.path().join("MISSING.md")),
+        ..Default::default()
+    });
+
+    let result = Packer::new(config).pack();
+    assert!(matches!(
+        result,
+        Err(auroraview_pack::PackError::AssetNotFound(_))
+    ));
+}
+
 #[test]
 fn test_overlay_fullstack_roundtrip() {
     use auroraview_pack::{OverlayData, OverlayReader, OverlayWriter};
@@ -724,3 +1069,755 @@ fn test_python_standalone_cache_dir() {
         .to_string_lossy()
         .contains("x86_64-unknown-linux-gnu"));
 }
+
+// ============================================================================
+// Network Certificate Tests
+// ============================================================================
+
+#[test]
+fn test_packer_embeds_network_certificates() {
+    use auroraview_pack::{ClientCertificateConfig, OverlayReader};
+    use std::path::PathBuf;
+
+    let certs = tempdir().expect("Failed to create certs temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+
+    let ca_path = certs.path().join("internal-ca.pem");
+    fs::write(
+        &ca_path,
+        "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+    )
+    .unwrap();
+    let client_path = certs.path().join("client.p12");
+    fs::write(&client_path, b"pkcs12-bytes").unwrap();
+
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_ca_certificate(&ca_path)
+        .with_client_certificate(ClientCertificateConfig {
+            path: client_path,
+            key: None,
+            password_env: Some("CLIENT_CERT_PASSWORD".to_string()),
+            origins: vec!["https://internal.example.com".to_string()],
+        });
+
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+
+    let network = &overlay.config.network;
+    assert_eq!(
+        network.ca_certificates,
+        vec![PathBuf::from("certs/ca/0/internal-ca.pem")]
+    );
+    assert_eq!(
+        network.client_certificates[0].path,
+        PathBuf::from("certs/client/0/client.p12")
+    );
+    assert_eq!(
+        network.client_certificates[0].password_env.as_deref(),
+        Some("CLIENT_CERT_PASSWORD")
+    );
+
+    let names: Vec<&str> = overlay.assets.iter().map(|(n, _)| n.as_str()).collect();
+    assert!(names.contains(&"certs/ca/0/internal-ca.pem"));
+    assert!(names.contains(&"certs/client/0/client.p12"));
+}
+
+#[test]
+fn test_packer_embeds_shortcut_task_icons() {
+    use auroraview_pack::{OverlayReader, ShortcutTask};
+    use std::path::PathBuf;
+
+    let icons = tempdir().expect("Failed to create icons temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+
+    let icon_path = icons.path().join("logs.ico");
+    fs::write(&icon_path, b"ico-bytes").unwrap();
+
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_shortcut_task(
+            ShortcutTask::new("Open logs", vec!["--open-logs".to_string()]).with_icon(&icon_path),
+        )
+        .with_shortcut_task(ShortcutTask::new(
+            "New window",
+            vec!["--new-window".to_string()],
+        ));
+
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+
+    let tasks = &overlay.config.shortcuts.as_ref().expect("shortcuts").tasks;
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].args, vec!["--open-logs"]);
+    assert_eq!(tasks[0].icon, Some(PathBuf::from("shortcuts/0/logs.ico")));
+    assert_eq!(tasks[1].icon, None);
+
+    let names: Vec<&str> = overlay.assets.iter().map(|(n, _)| n.as_str()).collect();
+    assert!(names.contains(&"shortcuts/0/logs.ico"));
+}
+
+#[test]
+fn test_packer_writes_sidecar_overlay() {
+    use auroraview_pack::{sidecar_path, OverlayReader};
+
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_sidecar_overlay();
+
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let sidecar = sidecar_path(&output.executable);
+    assert!(sidecar.is_file());
+    assert_eq!(
+        OverlayReader::get_original_size(&output.executable).unwrap(),
+        None
+    );
+    assert_eq!(
+        output.size,
+        fs::metadata(std::env::current_exe().unwrap())
+            .unwrap()
+            .len()
+    );
+
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay from the sidecar");
+    assert_eq!(overlay.config.mode.name(), "url",);
+
+    // Repacking embedded removes the sidecar
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path());
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    assert!(!sidecar.exists());
+    assert!(OverlayReader::get_original_size(&output.executable)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_packer_embeds_integrity_manifest() {
+    use auroraview_pack::{IntegrityManifest, OverlayReader, INTEGRITY_MANIFEST_PATH};
+
+    let frontend = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    fs::write(frontend.path().join("index.html"), "<html></html>").unwrap();
+    fs::create_dir(frontend.path().join("js")).unwrap();
+    fs::write(frontend.path().join("js/app.js"), "console.log(1)").unwrap();
+
+    let config = PackConfig::frontend(frontend.path())
+        .with_output("test-app")
+        .with_output_dir(output_temp.path());
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+
+    let (_, json) = overlay
+        .assets
+        .iter()
+        .find(|(path, _)| path == INTEGRITY_MANIFEST_PATH)
+        .expect("integrity manifest");
+    let manifest: IntegrityManifest = serde_json::from_slice(json).unwrap();
+    assert_eq!(manifest.files.len(), overlay.assets.len() - 1);
+    let entry = manifest.entry("js/app.js").expect("app.js entry");
+    assert_eq!(entry.size, 14);
+    assert_eq!(
+        entry.sha256,
+        "0a286891c11c056e1ab5bfc25bf5d6b2f5b06d38eac10944f678fd8a2e70c393"
+    );
+    assert_eq!(
+        entry.blake3.as_deref(),
+        Some(blake3::hash(b"console.log(1)").to_hex().as_str())
+    );
+
+    // Extracted copies are checked against it
+    let extracted = tempdir().unwrap();
+    for (path, content) in &overlay.assets {
+        let dest = extracted.path().join(path);
+        fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        fs::write(dest, content).unwrap();
+    }
+    assert!(manifest.verify_dir(extracted.path()).is_ok());
+
+    fs::write(extracted.path().join("js/app.js"), "console.log(2)").unwrap();
+    fs::remove_file(extracted.path().join("index.html")).unwrap();
+    let report = manifest.verify_dir(extracted.path());
+    assert_eq!(report.corrupted, vec!["js/app.js"]);
+    assert_eq!(report.missing, vec!["index.html"]);
+    assert_eq!(report.damaged().count(), 2);
+}
+
+#[test]
+fn test_packer_embeds_eula() {
+    use auroraview_pack::{
+        is_eula_accepted, record_eula_acceptance, EulaConfig, LicenseConfig, OverlayReader,
+    };
+
+    let frontend = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    fs::write(frontend.path().join("index.html"), "<html></html>").unwrap();
+    let eula_path = frontend.path().join("EULA.html");
+    fs::write(&eula_path, "<p>Terms</p>").unwrap();
+
+    let license = LicenseConfig {
+        eula: Some(EulaConfig {
+            path: eula_path,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_license(license);
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+
+    // The overlay config points at the embedded text and pins its revision
+    let eula = overlay.config.license.unwrap().eula.unwrap();
+    assert_eq!(eula.path.to_str(), Some("__eula__/EULA.html"));
+    assert!(eula.is_html());
+    assert!(eula.require_acceptance);
+    let revision = eula.revision.expect("revision from the EULA hash");
+    assert_eq!(revision.len(), 16);
+    assert!(overlay
+        .assets
+        .iter()
+        .any(|(path, content)| path == "__eula__/EULA.html" && content == b"<p>Terms</p>"));
+
+    let record = output_temp.path().join("data").join("eula-accepted.json");
+    assert!(!is_eula_accepted(&record, &revision));
+    record_eula_acceptance(&record, &revision).unwrap();
+    assert!(is_eula_accepted(&record, &revision));
+    assert!(!is_eula_accepted(&record, "2027-01"));
+}
+
+#[test]
+fn test_packer_inspect_and_unpack() {
+    use auroraview_pack::{InspectReport, UNPACK_ASSETS_DIR, UNPACK_REPORT_FILE};
+
+    let frontend = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    fs::write(frontend.path().join("index.html"), "<html></html>").unwrap();
+    fs::create_dir(frontend.path().join("js")).unwrap();
+    fs::write(frontend.path().join("js/app.js"), "console.log(1)").unwrap();
+
+    let config = PackConfig::frontend(frontend.path())
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_build_metadata("build_id", "42");
+    let output = Packer::new(config).pack().expect("pack should succeed");
+
+    let report = Packer::inspect(&output.executable).unwrap();
+    assert_eq!(report.overlay_file, output.executable);
+    assert!(report.original_size.is_some());
+    assert_eq!(report.metadata["build_id"], "42");
+    assert!(report.python_runtime.is_none());
+    assert_eq!(report.config.mode.name(), "frontend");
+    assert_eq!(report.asset("js/app.js").unwrap().size, 14);
+    assert!(report.total_size() >= 27);
+    let text = report.to_string();
+    assert!(text.contains("js/app.js"));
+    assert!(text.contains("build_id = 42"));
+
+    let unpacked = tempdir().unwrap();
+    Packer::unpack(&output.executable, unpacked.path()).unwrap();
+    let assets = unpacked.path().join(UNPACK_ASSETS_DIR);
+    assert_eq!(
+        fs::read_to_string(assets.join("js/app.js")).unwrap(),
+        "console.log(1)"
+    );
+    let written: InspectReport =
+        serde_json::from_slice(&fs::read(unpacked.path().join(UNPACK_REPORT_FILE)).unwrap())
+            .unwrap();
+    assert_eq!(written.content_hash, report.content_hash);
+    assert_eq!(written.assets.len(), report.assets.len());
+
+    // Files without an overlay are reported as such
+    let plain = output_temp.path().join("plain.bin");
+    fs::write(&plain, vec![0u8; 64]).unwrap();
+    assert!(Packer::inspect(&plain).is_err());
+}
+
+#[test]
+fn test_packer_diff() {
+    use auroraview_pack::test_support::PackFixture;
+    use auroraview_pack::ChangeKind;
+
+    let fixture = PackFixture::new().unwrap();
+    let frontend = fixture.frontend().unwrap();
+    let old = fixture
+        .pack(
+            PackConfig::frontend(&frontend)
+                .with_output("app-v1")
+                .with_title("App")
+                .with_build_metadata("build_id", "1"),
+        )
+        .unwrap();
+
+    fs::write(
+        frontend.join("assets/app.js"),
+        "console.log('test app v2');\n",
+    )
+    .unwrap();
+    fs::remove_file(frontend.join("assets/style.css")).unwrap();
+    fs::write(frontend.join("about.html"), "<p>About</p>").unwrap();
+    let new = fixture
+        .pack(
+            PackConfig::frontend(&frontend)
+                .with_output("app-v2")
+                .with_title("App v2")
+                .with_build_metadata("build_id", "2"),
+        )
+        .unwrap();
+
+    let diff = Packer::diff(&old.executable, &new.executable).unwrap();
+    assert_eq!(diff.asset("about.html").unwrap().kind, ChangeKind::Added);
+    assert_eq!(diff.asset("about.html").unwrap().size_delta(), 12);
+    assert_eq!(
+        diff.asset("assets/style.css").unwrap().kind,
+        ChangeKind::Removed
+    );
+    let script = diff.asset("assets/app.js").unwrap();
+    assert_eq!(script.kind, ChangeKind::Changed);
+    assert_eq!(script.size_delta(), 3);
+    assert!(diff.asset("index.html").is_none());
+    assert_eq!(
+        diff.size_delta(),
+        diff.assets.iter().map(|a| a.size_delta()).sum::<i64>()
+    );
+
+    let title = diff.config_change("window.title").unwrap();
+    assert_eq!(title.old, Some(serde_json::json!("App")));
+    assert_eq!(title.new, Some(serde_json::json!("App v2")));
+    assert!(diff.metadata.iter().any(|c| c.key == "build_id"));
+    assert!(diff.python_runtime.is_empty());
+    let text = diff.to_string();
+    assert!(
+        text.contains("+ ") && text.contains("about.html"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("window.title: \"App\" -> \"App v2\""),
+        "{}",
+        text
+    );
+
+    let same = Packer::diff(&old.executable, &old.executable).unwrap();
+    assert!(same.is_empty());
+    assert!(same.to_string().contains("No changes"));
+}
+
+#[test]
+fn test_packer_rejects_duplicate_shortcut_tasks() {
+    use auroraview_pack::ShortcutTask;
+
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_shortcut_task(ShortcutTask::new("Open logs", Vec::new()))
+        .with_shortcut_task(ShortcutTask::new("open logs", vec!["--x".to_string()]));
+
+    let err = Packer::new(config).pack().unwrap_err();
+    assert!(err.to_string().contains("Duplicate [[shortcuts.tasks]]"));
+}
+
+#[test]
+fn test_packer_signs_overlay() {
+    use auroraview_pack::{OverlayReader, OverlaySigner, SigningConfig};
+
+    let keys = tempdir().expect("Failed to create keys temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+
+    let pkcs8 = OverlaySigner::generate_pkcs8().unwrap();
+    let key_path = keys.path().join("overlay.pk8");
+    fs::write(&key_path, &pkcs8).unwrap();
+    let public_key = OverlaySigner::from_pkcs8(&pkcs8)
+        .unwrap()
+        .public_key_base64();
+
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_signing(SigningConfig::from_key(&key_path));
+
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let signature = OverlayReader::verify(&output.executable, Some(&public_key))
+        .unwrap()
+        .expect("signed overlay");
+    assert_eq!(signature.public_key, public_key);
+
+    // The key itself never reaches the overlay
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+    assert!(overlay.config.signing.is_none());
+}
+
+#[test]
+fn test_packer_writes_signed_provenance() {
+    use auroraview_pack::{
+        provenance_path, verify_provenance, OverlaySigner, ProvenanceConfig, SigningConfig,
+        SLSA_PROVENANCE_V1,
+    };
+    use sha2::{Digest, Sha256};
+
+    let keys = tempdir().expect("Failed to create keys temp directory");
+    let frontend = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    fs::write(
+        frontend.path().join("index.html"),
+        "<html>provenance</html>",
+    )
+    .unwrap();
+
+    let pkcs8 = OverlaySigner::generate_pkcs8().unwrap();
+    let key_path = keys.path().join("overlay.pk8");
+    fs::write(&key_path, &pkcs8).unwrap();
+    let public_key = OverlaySigner::from_pkcs8(&pkcs8)
+        .unwrap()
+        .public_key_base64();
+
+    let config = PackConfig::frontend(frontend.path())
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_signing(SigningConfig::from_key(&key_path))
+        .with_provenance(ProvenanceConfig {
+            builder_id: Some("https://ci.example.com/release".to_string()),
+            ..Default::default()
+        });
+
+    let output = Packer::new(config).pack().expect("pack should succeed");
+    let path = output.provenance.expect("provenance written");
+    assert_eq!(path, provenance_path(&output.executable));
+
+    let envelope = fs::read_to_string(&path).unwrap();
+    let statement = verify_provenance(&envelope, &public_key).unwrap();
+    assert_eq!(statement.predicate_type, SLSA_PROVENANCE_V1);
+    let digest = format!(
+        "{:x}",
+        Sha256::digest(fs::read(&output.executable).unwrap())
+    );
+    assert_eq!(statement.subject[0].sha256(), Some(digest.as_str()));
+
+    let definition = &statement.predicate.build_definition;
+    assert_eq!(definition.external_parameters.mode, "frontend");
+    let index = format!("{:x}", Sha256::digest("<html>provenance</html>".as_bytes()));
+    assert!(definition
+        .resolved_dependencies
+        .iter()
+        .any(|d| d.name == "index.html" && d.sha256() == Some(index.as_str())));
+    assert!(definition
+        .resolved_dependencies
+        .iter()
+        .any(|d| d.name == "launcher"));
+    assert_eq!(
+        statement.predicate.run_details.builder.id,
+        "https://ci.example.com/release"
+    );
+
+    // Another key does not verify
+    let other = OverlaySigner::from_pkcs8(&OverlaySigner::generate_pkcs8().unwrap())
+        .unwrap()
+        .public_key_base64();
+    assert!(verify_provenance(&envelope, &other).is_err());
+}
+
+#[test]
+fn test_packer_rejects_invalid_ca_bundle() {
+    let certs = tempdir().expect("Failed to create certs temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+
+    let ca_path = certs.path().join("not-a-cert.pem");
+    fs::write(&ca_path, "hello").unwrap();
+
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_ca_certificate(&ca_path);
+    assert!(Packer::new(config).pack().is_err());
+
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_ca_certificate(certs.path().join("missing.pem"));
+    assert!(Packer::new(config).pack().is_err());
+}
+
+#[test]
+fn test_packer_embedded_records_python_requirement() {
+    use auroraview_pack::{
+        AbiMismatchAction, EmbeddedPythonConfig, HostPython, OverlayReader, PythonRequirement,
+        PYTHON_REQUIREMENT_PATH,
+    };
+
+    let frontend_temp = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    fs::write(frontend_temp.path().join("index.html"), "<html></html>").unwrap();
+    let code_temp = tempdir().expect("Failed to create code temp directory");
+    fs::write(code_temp.path().join("main.py"), "def run(): pass\n").unwrap();
+
+    let python = PythonBundleConfig::new("main:run")
+        .with_strategy(BundleStrategy::Embedded)
+        .with_version("3.12")
+        .with_include_paths(vec![code_temp.path().join("main.py")])
+        .with_embedded(EmbeddedPythonConfig {
+            on_mismatch: AbiMismatchAction::Warn,
+            message: Some("Contact IT".to_string()),
+            ..Default::default()
+        });
+    let config = PackConfig::fullstack_with_config(frontend_temp.path(), python)
+        .with_output("embedded-app")
+        .with_output_dir(output_temp.path());
+    let output = Packer::new(config)
+        .pack()
+        .expect("embedded pack should succeed");
+
+    let overlay = OverlayReader::read(&output.executable)
+        .unwrap()
+        .expect("overlay");
+    let (_, content) = overlay
+        .assets
+        .iter()
+        .find(|(path, _)| path == PYTHON_REQUIREMENT_PATH)
+        .expect("python requirement");
+    let requirement: PythonRequirement = serde_json::from_slice(content).unwrap();
+    assert_eq!(requirement.version, "3.12");
+    assert_eq!(requirement.abi, "cp312");
+    assert!(requirement.native_extensions.is_empty());
+    assert_eq!(requirement.runtime.on_mismatch, AbiMismatchAction::Warn);
+
+    // The launcher's check explains mismatches
+    let matching = HostPython {
+        program: "python3".to_string(),
+        version: "3.12".to_string(),
+    };
+    assert!(requirement.check(Some(&matching)).is_ok());
+    let older = HostPython {
+        version: "3.10".to_string(),
+        ..matching
+    };
+    let message = requirement.check(Some(&older)).unwrap_err();
+    assert!(message.contains("`python3` is Python 3.10"), "{}", message);
+    assert!(message.contains("https://www.python.org/downloads/"));
+    assert!(message.ends_with("Contact IT"));
+    assert!(requirement.check(None).is_err());
+}
+
+#[test]
+fn test_incremental_pack_reuses_compressed_assets() {
+    use auroraview_pack::IncrementalConfig;
+
+    let frontend = tempdir().expect("Failed to create frontend temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    let cache = tempdir().expect("Failed to create cache temp directory");
+    fs::write(frontend.path().join("index.html"), "<html></html>").unwrap();
+    fs::write(frontend.path().join("app.js"), "console.log(1)").unwrap();
+
+    let pack = || {
+        let config = PackConfig::frontend(frontend.path())
+            .with_output("test-app")
+            .with_output_dir(output_temp.path())
+            .with_incremental(IncrementalConfig {
+                dir: Some(cache.path().to_path_buf()),
+                ..Default::default()
+            });
+        let output = Packer::new(config).pack().expect("pack should succeed");
+        let report = Packer::inspect(&output.executable).unwrap();
+        (output.incremental.expect("incremental stats"), report)
+    };
+
+    let (first, first_report) = pack();
+    assert_eq!(first.reused, 0);
+    assert!(first.compressed >= 2);
+    assert!(first.changed >= 2);
+
+    // Nothing changed: every asset comes from the cache, same overlay
+    let (second, second_report) = pack();
+    assert_eq!(second.compressed, 0);
+    assert_eq!(second.reused, first.compressed);
+    assert_eq!(second.changed, 0);
+    assert_eq!(second_report.content_hash, first_report.content_hash);
+
+    // One changed file is the only one compressed again
+    fs::write(frontend.path().join("app.js"), "console.log(2)").unwrap();
+    let (third, third_report) = pack();
+    assert_eq!(third.compressed, 1);
+    assert!(third.changed >= 1);
+    assert_eq!(
+        third_report.asset("index.html").unwrap().hash,
+        first_report.asset("index.html").unwrap().hash
+    );
+    assert_ne!(third_report.content_hash, first_report.content_hash);
+
+    // A corrupted blob is discarded and recompressed
+    for blob in fs::read_dir(cache.path().join("blobs")).unwrap() {
+        fs::write(blob.unwrap().path(), b"garbage").unwrap();
+    }
+    let (fourth, fourth_report) = pack();
+    assert_eq!(fourth.reused, 0);
+    assert_eq!(fourth_report.content_hash, third_report.content_hash);
+}
+
+#[test]
+fn test_incremental_cache_is_shared_by_profiles() {
+    use auroraview_pack::IncrementalConfig;
+
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    let cache = tempdir().expect("Failed to create cache temp directory");
+    let profiles: Vec<_> = ["debug", "release"]
+        .iter()
+        .map(|profile| {
+            let frontend = tempdir().expect("Failed to create frontend temp directory");
+            fs::write(
+                frontend.path().join("index.html"),
+                format!("<p>{}</p>", profile),
+            )
+            .unwrap();
+            frontend
+        })
+        .collect();
+
+    let pack = |profile: usize, max_size_mb: Option<u64>| {
+        let config = PackConfig::frontend(profiles[profile].path())
+            .with_output("test-app")
+            .with_output_dir(output_temp.path())
+            .with_incremental(IncrementalConfig {
+                dir: Some(cache.path().to_path_buf()),
+                max_size_mb,
+                ..Default::default()
+            });
+        let output = Packer::new(config).pack().expect("pack should succeed");
+        output.incremental.expect("incremental stats")
+    };
+    let blobs = || fs::read_dir(cache.path().join("blobs")).unwrap().count();
+
+    // Alternating profiles keep each other's blobs
+    let debug = pack(0, Some(1024));
+    pack(1, Some(1024));
+    let again = pack(0, Some(1024));
+    assert_eq!(again.compressed, 0);
+    assert_eq!(again.reused, debug.compressed);
+
+    // Beyond the size limit, blobs of other packs are evicted first
+    let all = blobs();
+    pack(1, Some(0));
+    let kept = blobs();
+    assert!(kept < all, "{} < {}", kept, all);
+    let debug = pack(0, Some(0));
+    assert!(debug.compressed > 0);
+}
+
+#[test]
+fn test_packer_writes_update_feed() {
+    use auroraview_pack::{
+        platform_key, verify_artifact, FeedArtifact, OverlaySigner, SigningConfig, TargetPlatform,
+        UpdateFeed, UpdateFeedConfig,
+    };
+
+    let keys = tempdir().expect("Failed to create keys temp directory");
+    let output_temp = tempdir().expect("Failed to create output temp directory");
+    let pkcs8 = OverlaySigner::generate_pkcs8().unwrap();
+    let key_path = keys.path().join("overlay.pk8");
+    fs::write(&key_path, &pkcs8).unwrap();
+    let public_key = OverlaySigner::from_pkcs8(&pkcs8)
+        .unwrap()
+        .public_key_base64();
+
+    // A feed of the same version from another platform's pack is kept
+    let other = FeedArtifact {
+        url: "https://dl.example.com/1.4.0/test-app.AppImage".to_string(),
+        size: 1,
+        sha256: "00".to_string(),
+        signature: None,
+    };
+    let previous = serde_json::json!({
+        "name": "test-app",
+        "version": "1.4.0",
+        "pub_date": "2026-10-01T00:00:00Z",
+        "platforms": { "other-arch": other },
+    });
+    fs::write(
+        output_temp.path().join("latest.json"),
+        serde_json::to_vec(&previous).unwrap(),
+    )
+    .unwrap();
+
+    let feed_config = UpdateFeedConfig {
+        base_url: Some("https://dl.example.com/{version}/".to_string()),
+        release_notes_url: Some("https://example.com/notes/{version}".to_string()),
+        minimum_version: Some("1.2.0".to_string()),
+        appcast: Some("appcast.xml".into()),
+        ..Default::default()
+    };
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_app_version("1.4.0")
+        .with_signing(SigningConfig::from_key(&key_path))
+        .with_update_feed(feed_config.clone());
+    let output = Packer::new(config).pack().expect("pack should succeed");
+
+    let feed_path = output.update_feed.expect("update feed");
+    let feed = UpdateFeed::load(&feed_path).unwrap();
+    assert_eq!(feed.version, "1.4.0");
+    assert_eq!(feed.minimum_version.as_deref(), Some("1.2.0"));
+    assert_eq!(
+        feed.notes_url.as_deref(),
+        Some("https://example.com/notes/1.4.0")
+    );
+    assert_eq!(feed.platforms["other-arch"], other);
+
+    let artifact = &feed.platforms[&platform_key(TargetPlatform::Current)];
+    let file_name = output.executable.file_name().unwrap().to_str().unwrap();
+    assert_eq!(
+        artifact.url,
+        format!("https://dl.example.com/1.4.0/{}", file_name)
+    );
+    let data = fs::read(&output.executable).unwrap();
+    assert_eq!(artifact.size, data.len() as u64);
+    verify_artifact(&public_key, &data, artifact.signature.as_ref().unwrap()).unwrap();
+    assert!(verify_artifact(
+        &public_key,
+        b"tampered",
+        artifact.signature.as_ref().unwrap()
+    )
+    .is_err());
+
+    let appcast = fs::read_to_string(output_temp.path().join("appcast.xml")).unwrap();
+    assert!(appcast.contains("<sparkle:version>1.4.0</sparkle:version>"));
+    assert!(appcast.contains("sparkle:edSignature="));
+    assert!(appcast.contains("<sparkle:minimumAutoupdateVersion>1.2.0"));
+
+    // A new version replaces the feed
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_app_version("1.5.0")
+        .with_update_feed(feed_config);
+    Packer::new(config).pack().expect("pack should succeed");
+    let feed = UpdateFeed::load(&feed_path).unwrap();
+    assert_eq!(feed.version, "1.5.0");
+    assert_eq!(feed.platforms.len(), 1);
+    assert!(feed.platforms.values().all(|a| a.signature.is_none()));
+
+    // Without a version there is nothing to publish
+    let config = PackConfig::url("https://internal.example.com")
+        .with_output("test-app")
+        .with_output_dir(output_temp.path())
+        .with_update_feed(UpdateFeedConfig::default());
+    assert!(Packer::new(config).pack().is_err());
+}
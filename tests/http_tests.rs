@@ -0,0 +1,207 @@
+//! Tests for the shared HTTP client (`[build.http]`)
+
+use auroraview_pack::{
+    ByteProgress, Downloader, HttpClient, HttpConfig, Manifest, PackConfig, PackProgressObserver,
+    HTTP_USER_AGENT,
+};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use tempfile::tempdir;
+
+/// Serve `responses` in order, one per connection; returns the base URL and
+/// the User-Agent of each request
+fn serve(responses: Vec<String>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let agents = Arc::new(Mutex::new(Vec::new()));
+    let seen = agents.clone();
+    std::thread::spawn(move || {
+        for (stream, response) in listener.incoming().flatten().zip(responses) {
+            let mut reader = BufReader::new(stream);
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("user-agent") {
+                        seen.lock().unwrap().push(value.trim().to_string());
+                    }
+                }
+            }
+            reader.into_inner().write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (url, agents)
+}
+
+fn response(status: &str, headers: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+        status,
+        body.len(),
+        headers,
+        body
+    )
+}
+
+/// Client that never goes through a proxy from the environment
+fn local_client(config: HttpConfig) -> HttpClient {
+    HttpClient::new(&HttpConfig {
+        no_proxy: vec!["127.0.0.1".to_string()],
+        ..config
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_rate_limited_requests_are_retried() {
+    let (url, agents) = serve(vec![
+        response("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+        response("503 Service Unavailable", "Retry-After: 0\r\n", ""),
+        response("200 OK", "", "payload"),
+    ]);
+    let client = local_client(HttpConfig::default());
+
+    let text = client.get_text(&format!("{}/file.txt", url)).unwrap();
+    assert_eq!(text, "payload");
+    let agents = agents.lock().unwrap();
+    assert_eq!(agents.len(), 3);
+    assert!(agents.iter().all(|agent| agent == HTTP_USER_AGENT));
+    assert!(HTTP_USER_AGENT.starts_with("auroraview-pack/"));
+}
+
+#[test]
+fn test_rate_limit_retries_are_bounded() {
+    let (url, _) = serve(vec![
+        response("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+        response("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+    ]);
+    let client = local_client(HttpConfig {
+        rate_limit_retries: 1,
+        ..Default::default()
+    });
+
+    let err = client.get_bytes(&format!("{}/file", url)).unwrap_err();
+    assert!(err.to_string().contains("429"), "{}", err);
+}
+
+#[test]
+fn test_download_to_file() {
+    let temp = tempdir().unwrap();
+    let (url, _) = serve(vec![response("200 OK", "", "archive bytes")]);
+    let client = local_client(HttpConfig::default());
+
+    let dest = temp.path().join("archive.tar.gz");
+    let size = client.download_to(&format!("{}/a", url), &dest).unwrap();
+    assert_eq!(size, 13);
+    assert_eq!(std::fs::read_to_string(&dest).unwrap(), "archive bytes");
+}
+
+/// Observer keeping every byte event
+#[derive(Default)]
+struct ByteEvents(Mutex<Vec<ByteProgress>>);
+
+impl PackProgressObserver for ByteEvents {
+    fn bytes(&self, progress: &ByteProgress) {
+        self.0.lock().unwrap().push(progress.clone());
+    }
+}
+
+#[test]
+fn test_download_reports_byte_progress() {
+    let temp = tempdir().unwrap();
+    let body = "x".repeat(64 * 1024);
+    let (url, _) = serve(vec![response("200 OK", "", &body)]);
+    let events = Arc::new(ByteEvents::default());
+    let client = local_client(HttpConfig::default()).with_progress(events.clone());
+
+    let dest = temp.path().join("runtime.tar.gz");
+    let url = format!("{}/releases/runtime.tar.gz?raw=1", url);
+    client.download_to(&url, &dest).unwrap();
+
+    let events = events.0.lock().unwrap();
+    assert!(!events.is_empty());
+    assert!(events.iter().all(|e| e.item == "download runtime.tar.gz"));
+    assert!(events.iter().all(|e| e.total == Some(body.len() as u64)));
+    assert!(events.windows(2).all(|w| w[0].done < w[1].done));
+    let last = events.last().unwrap();
+    assert_eq!(last.done, body.len() as u64);
+    assert_eq!(last.fraction(), Some(1.0));
+    assert_eq!(last.eta, Some(std::time::Duration::ZERO));
+}
+
+#[test]
+fn test_extraction_reports_byte_progress() {
+    let temp = tempdir().unwrap();
+    let archive = temp.path().join("tool.tar.gz");
+    let encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(&archive).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    let mut header = tar::Header::new_gnu();
+    header.set_size(4);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "tool/bin/tool", &b"tool"[..])
+        .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    let events = Arc::new(ByteEvents::default());
+    let client = HttpClient::default().with_progress(events.clone());
+    let dest = temp.path().join("out");
+    Downloader::new(temp.path().join("cache"))
+        .with_http(client)
+        .extract(&archive, &dest, 1)
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(dest.join("bin/tool")).unwrap(),
+        "tool"
+    );
+    let size = std::fs::metadata(&archive).unwrap().len();
+    let events = events.0.lock().unwrap();
+    let last = events.last().unwrap();
+    assert_eq!(last.item, "extract tool.tar.gz");
+    assert_eq!(last.total, Some(size));
+    assert_eq!(last.done, size);
+}
+
+#[test]
+fn test_build_http_from_manifest() {
+    let toml = r#"
+[package]
+name = "test-app"
+
+[frontend]
+url = "https://example.com"
+
+[build.http]
+proxy = "http://proxy.corp.example:3128"
+no_proxy = ["artifacts.corp.example"]
+read_timeout_secs = 120
+
+[build.http.pins]
+"artifacts.corp.example" = [
+    "9F:86:D0:81:88:4C:7D:65:9A:2F:EA:A0:C5:5A:D0:15:A3:BF:4F:1B:2B:0B:82:2C:D1:5D:6C:15:B0:F0:0A:08",
+]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    manifest.validate().unwrap();
+    let config = PackConfig::from_manifest(&manifest, std::path::Path::new("/project")).unwrap();
+    assert_eq!(
+        config.http.proxy.as_deref(),
+        Some("http://proxy.corp.example:3128")
+    );
+    assert_eq!(config.http.read_timeout_secs, 120);
+    assert_eq!(config.http.rate_limit_retries, 3);
+    assert_eq!(config.http.pins["artifacts.corp.example"].len(), 1);
+
+    let bad_pin = toml.replace("9F:86", "ZZ:86");
+    let manifest = Manifest::parse(&bad_pin).unwrap();
+    assert!(manifest.validate().is_err());
+}
@@ -1,6 +1,9 @@
 //! Tests for auroraview-pack lib module
 
-use auroraview_pack::VERSION;
+use auroraview_pack::{
+    is_protection_available, probe_capabilities, BundleStrategy, CapabilityKind, HostCapabilities,
+    VERSION,
+};
 
 #[test]
 fn test_version() {
@@ -12,3 +15,30 @@ fn test_is_packed() {
     // In test environment, should not be packed
     assert!(!auroraview_pack::is_packed());
 }
+
+#[test]
+fn test_probe_capabilities() {
+    let caps = probe_capabilities();
+    assert_eq!(caps.os, std::env::consts::OS);
+    assert_eq!(caps.of_kind(CapabilityKind::Strategy).count(), 5);
+    for strategy in [
+        BundleStrategy::Standalone,
+        BundleStrategy::PyOxidizer,
+        BundleStrategy::Embedded,
+        BundleStrategy::Portable,
+        BundleStrategy::System,
+    ] {
+        let cap = caps.strategy(strategy).unwrap();
+        // Unavailable options always say why
+        assert!(cap.available || cap.reason.is_some(), "{:?}", cap);
+    }
+    assert_eq!(
+        caps.is_available(CapabilityKind::Protection, "bytecode"),
+        is_protection_available()
+    );
+    assert!(!caps.is_available(CapabilityKind::Installer, "unknown"));
+
+    let json = serde_json::to_string(&caps).unwrap();
+    let parsed: HostCapabilities = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, caps);
+}
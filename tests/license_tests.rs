@@ -63,3 +63,43 @@ fn test_machine_id() {
     let id = get_machine_id();
     assert!(!id.is_empty());
 }
+
+#[test]
+fn test_preview_watermark() {
+    use auroraview_pack::{PreviewConfig, WatermarkPosition};
+
+    let mut license = LicenseConfig::preview("2099-12-31");
+    license.expiration_message = Some("Preview ended </script>".to_string());
+    let preview = license.preview.clone().unwrap();
+    assert!(preview.validate(&license).is_ok());
+    assert_eq!(
+        preview.label_for("2099-12-31"),
+        "Preview build · expires 2099-12-31"
+    );
+
+    let script = preview.script(&license).unwrap();
+    assert!(script.contains("Preview build · expires 2099-12-31"));
+    // End of 2099-12-31 UTC
+    assert!(script.contains("var cutoff = 4102444800000, block = true;"));
+    assert!(!script.contains("</script>"));
+    assert!(preview.style().contains("bottom:8px;right:8px;"));
+
+    // The grace period moves the cutoff
+    license.grace_period_days = 1;
+    assert!(preview
+        .script(&license)
+        .unwrap()
+        .contains("var cutoff = 4102531200000,"));
+
+    let banner = PreviewConfig {
+        position: WatermarkPosition::Banner,
+        ..Default::default()
+    };
+    assert!(banner.style().contains("top:0;left:0;right:0;"));
+
+    // An expiry date is required
+    assert!(preview.validate(&LicenseConfig::default()).is_err());
+    assert!(preview
+        .validate(&LicenseConfig::time_limited("31.12.2099"))
+        .is_err());
+}
@@ -0,0 +1,107 @@
+//! Tests for log levels and quiet mode
+
+use auroraview_pack::test_support::PackFixture;
+use auroraview_pack::{LogLevel, PackConfig, PackOptions, Packer, LOG_LEVEL_ENV, QUIET_ENV};
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Subscriber recording the level and target of every event
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<(Level, String)>>>);
+
+impl Recorder {
+    fn events(&self) -> Vec<(Level, String)> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn crate_events(&self, level: Level) -> usize {
+        self.events()
+            .iter()
+            .filter(|(l, target)| *l == level && target.starts_with("auroraview_pack"))
+            .count()
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        self.0
+            .lock()
+            .unwrap()
+            .push((*metadata.level(), metadata.target().to_string()));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn test_pack_options_from_env() {
+    let options = PackOptions::from_lookup(|name| match name {
+        LOG_LEVEL_ENV => Some("Debug".to_string()),
+        QUIET_ENV => Some("true".to_string()),
+        _ => None,
+    });
+    assert_eq!(options.log_level, Some(LogLevel::Debug));
+    assert!(options.quiet);
+    assert_eq!(options.effective_level(), Some(LogLevel::Warn));
+    assert_eq!(options.filter_directive(), "auroraview_pack=warn");
+    assert!(options.enabled(&Level::ERROR));
+    assert!(!options.enabled(&Level::INFO));
+
+    // Unset or invalid: no cap
+    let options = PackOptions::from_lookup(|name| (name == LOG_LEVEL_ENV).then(|| "loud".into()));
+    assert_eq!(options, PackOptions::default());
+    assert_eq!(options.effective_level(), None);
+    assert!(options.enabled(&Level::TRACE));
+
+    assert_eq!("warning".parse::<LogLevel>().unwrap(), LogLevel::Warn);
+    assert!("verbose".parse::<LogLevel>().is_err());
+}
+
+#[test]
+fn test_quiet_pack_drops_info_events() {
+    let fixture = PackFixture::new().unwrap();
+    let pack = |name: &str, options: PackOptions| {
+        let recorder = Recorder::default();
+        let config = fixture
+            .configure(PackConfig::frontend(fixture.frontend().unwrap()).with_output(name))
+            .unwrap()
+            .with_threads(2);
+        tracing::subscriber::with_default(recorder.clone(), || {
+            Packer::new(config).with_options(options).pack().unwrap();
+            // Events of other crates pass through
+            tracing::info!(target: "app", "packed");
+        });
+        recorder
+    };
+
+    let verbose = pack("verbose-app", PackOptions::default());
+    assert!(verbose.crate_events(Level::INFO) > 0);
+
+    let quiet = pack("quiet-app", PackOptions::default().with_quiet(true));
+    assert_eq!(quiet.crate_events(Level::INFO), 0);
+    assert_eq!(quiet.crate_events(Level::DEBUG), 0);
+    assert!(quiet.events().contains(&(Level::INFO, "app".to_string())));
+
+    let info = pack(
+        "info-app",
+        PackOptions::default().with_log_level(LogLevel::Info),
+    );
+    assert!(info.crate_events(Level::INFO) > 0);
+    assert_eq!(info.crate_events(Level::DEBUG), 0);
+}
@@ -1,6 +1,9 @@
 //! Tests for auroraview-pack manifest module
 
-use auroraview_pack::{Manifest, StartPosition};
+use auroraview_pack::{
+    BackendType, BuildProfile, CudaProvision, Manifest, ScheduleAction, StartPosition,
+    StorageConfig, StorageLocation,
+};
 
 // ============================================================================
 // Basic Parsing Tests
@@ -270,6 +273,152 @@ binary = "server"
     assert!(manifest.is_fullstack());
 }
 
+#[test]
+fn test_backend_toolchains() {
+    let toml = r#"
+[package]
+name = "test"
+
+[frontend]
+path = "./dist"
+
+[backend]
+type = "go"
+
+[backend.go]
+module = "github.com/user/app"
+
+[backend.go.toolchain]
+version = "1.22.5"
+sha256 = "904b924d435eaea086515bc63235b192ea441bd8c9b198c507e85009e6e4c7f0"
+
+[backend.rust.toolchain]
+version = "1.79.0"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+    let backend = manifest.backend.as_ref().unwrap();
+    let go = backend.go.as_ref().unwrap().toolchain.as_ref().unwrap();
+    assert_eq!(go.version, "1.22.5");
+    assert!(go.sha256.is_some());
+    let rust = backend.rust.as_ref().unwrap().toolchain.as_ref().unwrap();
+    assert_eq!(rust.version, "1.79.0");
+
+    let mirror = toml.replace(
+        "version = \"1.22.5\"\n",
+        "version = \"1.22.5\"\nurl = \"https://mirror.example.com/go.tar.gz\"\n",
+    );
+    let mirror = mirror.replace(
+        "sha256 = \"904b924d435eaea086515bc63235b192ea441bd8c9b198c507e85009e6e4c7f0\"\n",
+        "",
+    );
+    assert!(Manifest::parse(&mirror).unwrap().validate().is_err());
+}
+
+#[test]
+fn test_python_env_archive() {
+    let toml = r#"
+[package]
+name = "test"
+
+[frontend]
+path = "./dist"
+
+[backend]
+type = "python"
+
+[backend.python]
+entry_point = "main:run"
+env_archive = "./build/env.tar.gz"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let python = manifest.backend.as_ref().unwrap().python.as_ref().unwrap();
+    assert_eq!(
+        python.env_archive,
+        Some(std::path::PathBuf::from("./build/env.tar.gz"))
+    );
+
+    let bundle = python.to_bundle_config(std::path::Path::new("/project"));
+    assert_eq!(
+        bundle.env_archive,
+        Some(std::path::PathBuf::from("/project/build/env.tar.gz"))
+    );
+}
+
+#[test]
+fn test_python_cuda_config() {
+    let toml = r#"
+[package]
+name = "test"
+
+[frontend]
+path = "./dist"
+
+[backend]
+type = "python"
+
+[backend.python]
+entry_point = "main:run"
+packages = ["torch"]
+
+[backend.python.cuda]
+provide = "download"
+version = "12.1"
+url = "https://example.com/cuda-libs.tar.gz"
+sha256 = "904b924d435eaea086515bc63235b192ea441bd8c9b198c507e85009e6e4c7f0"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+    let python = manifest.backend.as_ref().unwrap().python.as_ref().unwrap();
+    let cuda = python.cuda.as_ref().unwrap();
+    assert!(cuda.exclude);
+    assert_eq!(cuda.provide, CudaProvision::Download);
+
+    let bundle = python.to_bundle_config(std::path::Path::new("/project"));
+    assert_eq!(bundle.cuda.as_ref(), Some(cuda));
+
+    // Downloads must be verifiable
+    let unverified = toml.replace(
+        "sha256 = \"904b924d435eaea086515bc63235b192ea441bd8c9b198c507e85009e6e4c7f0\"\n",
+        "",
+    );
+    assert!(Manifest::parse(&unverified).unwrap().validate().is_err());
+}
+
+#[test]
+fn test_python_system_launcher_config() {
+    let toml = r#"
+[package]
+name = "test"
+
+[frontend]
+path = "./dist"
+
+[backend]
+type = "python"
+
+[backend.python]
+entry_point = "main:run"
+strategy = "system"
+
+[backend.python.system]
+venv = true
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let python = manifest.backend.as_ref().unwrap().python.as_ref().unwrap();
+    let bundle = python.to_bundle_config(std::path::Path::new("/project"));
+    let system = bundle.system.unwrap();
+    assert!(system.venv);
+    assert_eq!(system.venv_dir, ".venv");
+    assert!(system.validate().is_ok());
+
+    let escaping = auroraview_pack::SystemPythonConfig {
+        venv_dir: "../shared-venv".to_string(),
+        ..system
+    };
+    assert!(escaping.validate().is_err());
+}
+
 #[test]
 fn test_backend_type_node() {
     let toml = r#"
@@ -290,3 +439,1469 @@ entry_point = "./server/index.js"
     let manifest = Manifest::parse(toml).unwrap();
     assert!(manifest.is_fullstack());
 }
+
+#[test]
+fn test_backend_type_process() {
+    let toml = r#"
+[package]
+name = "test"
+title = "Test"
+
+[frontend]
+path = "./dist"
+
+[backend]
+type = "process"
+
+[backend.process]
+command = "./bin/server"
+args = ["--port", "8080"]
+health_check = { url = "http://localhost:8080/health" }
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert_eq!(manifest.get_backend_type(), BackendType::Process);
+    assert!(manifest.is_fullstack());
+    assert!(manifest.validate().is_ok());
+
+    let launch = manifest
+        .get_launch_spec(std::path::Path::new("/project"))
+        .expect("launch spec");
+    assert_eq!(launch.command, "bin/server");
+    assert_eq!(launch.args, vec!["--port", "8080"]);
+    assert!(launch.binary.ends_with("bin/server"));
+    assert!(launch.health_check.is_some());
+}
+
+#[test]
+fn test_backend_type_process_requires_command() {
+    let toml = r#"
+[package]
+name = "test"
+
+[frontend]
+path = "./dist"
+
+[backend]
+type = "process"
+
+[backend.process]
+args = ["--port", "8080"]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_err());
+    assert!(manifest
+        .get_launch_spec(std::path::Path::new("/project"))
+        .is_none());
+}
+
+#[test]
+fn test_frontend_dev_url_profile() {
+    let toml = r#"
+[package]
+name = "test"
+
+[frontend]
+path = "./dist"
+dev_url = "http://localhost:5173"
+
+[build]
+profile = "dev"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+    assert_eq!(manifest.build.profile, BuildProfile::Dev);
+    assert_eq!(
+        manifest.get_dev_server_url(),
+        Some("http://localhost:5173".to_string())
+    );
+
+    // Release profile ignores dev_url
+    let release = toml.replace("profile = \"dev\"", "profile = \"release\"");
+    let manifest = Manifest::parse(&release).unwrap();
+    assert_eq!(manifest.build.profile, BuildProfile::Release);
+    assert!(manifest.get_dev_server_url().is_none());
+
+    // The dev profile needs a dev server
+    let missing = toml.replace("dev_url = \"http://localhost:5173\"\n", "");
+    let err = Manifest::parse(&missing).unwrap().validate().unwrap_err();
+    assert!(err.to_string().contains("requires 'dev_url'"), "{}", err);
+}
+
+#[test]
+fn test_frontend_dev_url_invalid() {
+    let toml = r#"
+[package]
+name = "test"
+
+[frontend]
+path = "./dist"
+dev_url = "ftp://localhost:5173"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_runtime_kiosk() {
+    let toml = r#"
+[package]
+name = "signage"
+
+[frontend]
+url = "https://dashboard.example.com"
+
+[runtime.kiosk]
+restart_on_crash = true
+idle_reload_minutes = 15
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let kiosk = manifest.runtime.as_ref().unwrap().kiosk.as_ref().unwrap();
+    // Lock-down options default to enabled
+    assert!(kiosk.fullscreen_locked);
+    assert!(kiosk.disable_context_menu);
+    assert!(kiosk.disable_devtools);
+    assert!(kiosk.restart_on_crash);
+    assert_eq!(kiosk.idle_reload_minutes, Some(15));
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert!(config.window.fullscreen);
+    assert_eq!(config.kiosk.as_ref(), Some(kiosk));
+}
+
+#[test]
+fn test_runtime_kiosk_invalid_idle_reload() {
+    let toml = r#"
+[package]
+name = "signage"
+
+[frontend]
+url = "https://dashboard.example.com"
+
+[runtime.kiosk]
+idle_reload_minutes = 0
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_runtime_schedule() {
+    let toml = r#"
+[package]
+name = "dashboard"
+
+[frontend]
+url = "https://dashboard.example.com"
+
+[[runtime.schedule]]
+action = "reload"
+cron = "*/30 * * * *"
+
+[[runtime.schedule]]
+action = "restart"
+cron = "0 4 * * 0"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert_eq!(config.schedule.len(), 2);
+    assert_eq!(config.schedule[0].action, ScheduleAction::Reload);
+    assert_eq!(config.schedule[1].action, ScheduleAction::Restart);
+}
+
+#[test]
+fn test_windows_app_user_model() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[bundle.windows.app_user_model]
+id = "Acme.PhotoTool.Main"
+relaunch_command = "photo-tool.exe --restore"
+relaunch_display_name = "Photo Tool"
+toast_activator_clsid = "{2A7B9C4D-1E3F-4A5B-8C6D-7E8F9A0B1C2D}"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let app_user_model = config.app_user_model.clone().unwrap();
+    assert_eq!(app_user_model.id.as_deref(), Some("Acme.PhotoTool.Main"));
+    assert!(!app_user_model.prevent_pinning);
+    assert!(app_user_model.validate().is_ok());
+    assert!(config.windows_resource.has_modifications());
+
+    // Carried in the overlay config for the runtime
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["app_user_model"]["id"], "Acme.PhotoTool.Main");
+
+    for (invalid, expected) in [
+        (
+            toml.replace("Acme.PhotoTool.Main", "Acme Photo Tool"),
+            "without spaces",
+        ),
+        (
+            toml.replace("relaunch_command = \"photo-tool.exe --restore\"\n", ""),
+            "requires relaunch_command",
+        ),
+        (
+            toml.replace("{2A7B9C4D-1E3F-4A5B-8C6D-7E8F9A0B1C2D}", "not-a-clsid"),
+            "is not a CLSID",
+        ),
+    ] {
+        let manifest = Manifest::parse(&invalid).unwrap();
+        let config =
+            auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new("."))
+                .unwrap();
+        let err = config.app_user_model.unwrap().validate().unwrap_err();
+        assert!(err.to_string().contains(expected), "{}", err);
+    }
+}
+
+#[test]
+fn test_runtime_schedule_invalid_cron() {
+    let toml = r#"
+[package]
+name = "dashboard"
+
+[frontend]
+url = "https://dashboard.example.com"
+
+[[runtime.schedule]]
+action = "restart"
+cron = "0 25 * * *"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_network_runtime_certificates() {
+    let toml = r#"
+[package]
+name = "internal-tool"
+
+[frontend]
+url = "https://internal.example.com"
+
+[network.runtime]
+ca_certificates = ["./certs/ca.pem"]
+
+[[network.runtime.client_certificates]]
+path = "./certs/client.pem"
+key = "./certs/client.key"
+origins = ["https://internal.example.com"]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let base = std::path::Path::new("/project");
+    let config = auroraview_pack::PackConfig::from_manifest(&manifest, base).unwrap();
+    assert_eq!(
+        config.network.ca_certificates,
+        vec![base.join("certs/ca.pem")]
+    );
+    let cert = &config.network.client_certificates[0];
+    assert_eq!(cert.path, base.join("certs/client.pem"));
+    assert_eq!(cert.key, Some(base.join("certs/client.key")));
+    assert!(!cert.is_pkcs12());
+}
+
+#[test]
+fn test_network_invalid_origin() {
+    let toml = r#"
+[package]
+name = "internal-tool"
+
+[frontend]
+url = "https://internal.example.com"
+
+[[network.runtime.client_certificates]]
+path = "./certs/client.p12"
+origins = ["not a url"]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_network_runtime_headers() {
+    let toml = r#"
+[package]
+name = "internal-tool"
+
+[frontend]
+url = "https://internal.example.com"
+
+[[network.runtime.headers]]
+name = "X-Tenant-Id"
+value = "acme"
+origins = ["https://internal.example.com"]
+
+[[network.runtime.headers]]
+name = "Authorization"
+value_env = "INTERNAL_AUTH"
+origins = ["https://api.internal.example.com"]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert_eq!(config.network.headers.len(), 2);
+    assert_eq!(config.network.headers[0].value.as_deref(), Some("acme"));
+    assert_eq!(
+        config.network.headers[1].value_env.as_deref(),
+        Some("INTERNAL_AUTH")
+    );
+}
+
+#[test]
+fn test_network_runtime_headers_invalid() {
+    let cases = [
+        // No origins
+        r#"
+[[network.runtime.headers]]
+name = "X-Tenant-Id"
+value = "acme"
+origins = []
+"#,
+        // Both value and value_env
+        r#"
+[[network.runtime.headers]]
+name = "X-Tenant-Id"
+value = "acme"
+value_env = "TENANT"
+origins = ["https://internal.example.com"]
+"#,
+        // Invalid header name
+        r#"
+[[network.runtime.headers]]
+name = "X Tenant"
+value = "acme"
+origins = ["https://internal.example.com"]
+"#,
+        // Header injection via line break
+        r#"
+[[network.runtime.headers]]
+name = "X-Tenant-Id"
+value = "acme\r\nX-Admin: 1"
+origins = ["https://internal.example.com"]
+"#,
+    ];
+
+    for case in cases {
+        let toml = format!(
+            "[package]\nname = \"t\"\n\n[frontend]\nurl = \"https://internal.example.com\"\n{}",
+            case
+        );
+        let manifest = Manifest::parse(&toml).unwrap();
+        assert!(manifest.validate().is_err(), "should reject: {}", case);
+    }
+}
+
+#[test]
+fn test_runtime_storage() {
+    let toml = r#"
+[package]
+name = "shared-terminal"
+
+[frontend]
+url = "https://portal.example.com"
+
+[runtime.storage]
+persist = false
+location = "temp"
+clear_on_exit = true
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let storage = manifest.runtime.as_ref().unwrap().storage.as_ref().unwrap();
+    assert_eq!(storage, &StorageConfig::ephemeral());
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert_eq!(config.storage.as_ref(), Some(storage));
+}
+
+#[test]
+fn test_runtime_print() {
+    let toml = r#"
+[package]
+name = "locked-portal"
+
+[frontend]
+url = "https://portal.example.com"
+
+[runtime.print]
+allowed = false
+pdf = false
+strip_print_css = true
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let print = config.print.as_ref().unwrap();
+    assert!(!print.allowed);
+    assert!(print.strip_print_css);
+
+    // Runtime settings reach the overlay; stripping is pack-time only
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["print"]["allowed"], false);
+    assert!(json["print"].get("strip_print_css").is_none());
+
+    // Stripping print styles needs printing disabled
+    let manifest = Manifest::parse(&toml.replace("allowed = false", "allowed = true")).unwrap();
+    assert!(manifest.validate().is_err());
+
+    let locked_without_dir = toml.replace("strip_print_css = true", "pdf_dir_locked = true");
+    let manifest = Manifest::parse(&locked_without_dir).unwrap();
+    assert!(manifest.validate().is_err());
+    let manifest = Manifest::parse(&locked_without_dir.replace(
+        "pdf_dir_locked",
+        "pdf_dir = \"$HOME/Reports\"\npdf_dir_locked",
+    ))
+    .unwrap();
+    assert!(manifest.validate().is_ok());
+}
+
+#[test]
+fn test_runtime_versioning() {
+    let toml = r#"
+[package]
+name = "viewer"
+version = "1.4.0"
+identifier = "com.acme.viewer"
+
+[frontend]
+path = "./dist"
+
+[backend]
+type = "python"
+
+[backend.python]
+entry_point = "viewer.main:run"
+
+[runtime.versioning]
+cache = "per_version"
+data = "shared"
+keep = 3
+migrate = "viewer.migrate:run"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert_eq!(config.app_version.as_deref(), Some("1.4.0"));
+    let versioning = config.versioning.clone().unwrap();
+    assert_eq!(
+        versioning.cache,
+        auroraview_pack::VersionIsolation::PerVersion
+    );
+    assert!(versioning.concurrent);
+    assert!(versioning
+        .cache_dir(&config.identity(), "1.4.0")
+        .ends_with("v1.4.0"));
+
+    // Stored in the overlay with the app version
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["app_version"], "1.4.0");
+    assert_eq!(json["versioning"]["migrate"], "viewer.migrate:run");
+
+    // Migrating per-version data makes no sense
+    let manifest =
+        Manifest::parse(&toml.replace("data = \"shared\"", "data = \"per_version\"")).unwrap();
+    assert!(manifest.validate().is_err());
+
+    let manifest =
+        Manifest::parse(&toml.replace("viewer.migrate:run", "viewer/migrate.py")).unwrap();
+    assert!(manifest.validate().is_err());
+
+    let manifest = Manifest::parse(&toml.replace("keep = 3", "keep = 0")).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_permissions() {
+    let toml = r#"
+[package]
+name = "kiosk-portal"
+
+[frontend]
+url = "https://portal.example.com"
+
+[permissions.downloads]
+directory = "{downloads}/{app}"
+prompt = false
+extensions = ["pdf", "csv"]
+
+[permissions.file_dialogs]
+roots = ["{documents}/Reports"]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let permissions = config.permissions.as_ref().unwrap();
+    let downloads = permissions.downloads.as_ref().unwrap();
+    assert!(downloads.allows("export.CSV"));
+    assert!(!downloads.allows("tool.exe"));
+    assert!(!auroraview_pack::DownloadsPermission::denied().allows("export.csv"));
+    let dialogs = permissions.file_dialogs.as_ref().unwrap();
+    assert!(dialogs.allowed);
+    assert!(dialogs.allows("anything.bin"));
+
+    // Carried in the overlay config for the runtime
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(
+        json["permissions"]["downloads"]["directory"],
+        "{downloads}/{app}"
+    );
+    assert_eq!(
+        json["permissions"]["file_dialogs"]["roots"][0],
+        "{documents}/Reports"
+    );
+
+    for (from, to) in [
+        ("{downloads}/{app}", "{appdata}/{app}"),
+        ("\"pdf\"", "\".pdf\""),
+        ("directory = \"{downloads}/{app}\"\n", ""),
+    ] {
+        let manifest = Manifest::parse(&toml.replace(from, to)).unwrap();
+        assert!(manifest.validate().is_err(), "should reject: {}", to);
+    }
+}
+
+#[test]
+fn test_clipboard_and_drag_drop_permissions() {
+    let toml = r#"
+[package]
+name = "vendor-portal"
+
+[frontend]
+url = "https://vendor.example.com"
+
+[permissions.clipboard]
+read = false
+
+[permissions.drag_drop]
+files = false
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let permissions = config.permissions.as_ref().unwrap();
+    let clipboard = permissions.clipboard.as_ref().unwrap();
+    assert!(!clipboard.read);
+    assert!(clipboard.write);
+    let drag_drop = permissions.drag_drop.as_ref().unwrap();
+    assert!(!drag_drop.allows("notes.txt"));
+    assert!(drag_drop.text);
+    assert!(permissions.downloads.is_none());
+
+    // Carried in the overlay config for the runtime
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["permissions"]["clipboard"]["read"], false);
+    assert_eq!(json["permissions"]["drag_drop"]["files"], false);
+
+    let manifest =
+        Manifest::parse(&toml.replace("files = false", "extensions = [\"*.png\"]")).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_runtime_storage_location_validation() {
+    let custom_without_path = r#"
+[package]
+name = "app"
+
+[frontend]
+url = "https://portal.example.com"
+
+[runtime.storage]
+location = "custom"
+"#;
+    let manifest = Manifest::parse(custom_without_path).unwrap();
+    assert!(manifest.validate().is_err());
+
+    let path_without_custom = r#"
+[package]
+name = "app"
+
+[frontend]
+url = "https://portal.example.com"
+
+[runtime.storage]
+location = "portable"
+path = "./profile"
+"#;
+    let manifest = Manifest::parse(path_without_custom).unwrap();
+    assert!(manifest.validate().is_err());
+
+    let custom = r#"
+[package]
+name = "app"
+
+[frontend]
+url = "https://portal.example.com"
+
+[runtime.storage]
+location = "custom"
+path = "%LOCALAPPDATA%/MyApp/profile"
+"#;
+    let manifest = Manifest::parse(custom).unwrap();
+    assert!(manifest.validate().is_ok());
+    let storage = manifest.runtime.unwrap().storage.unwrap();
+    assert_eq!(storage.location, StorageLocation::Custom);
+    assert!(storage.persist);
+    assert!(!storage.clear_on_exit);
+}
+
+#[test]
+fn test_frontend_rewrite_html_branding() {
+    let toml = r##"
+[package]
+name = "branded"
+title = "Branded App"
+description = "Internal dashboard"
+brand_color = "#1e88e5"
+
+[frontend]
+path = "./dist"
+rewrite_html = true
+"##;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let branding = manifest.get_html_branding().expect("branding enabled");
+    assert_eq!(branding.title.as_deref(), Some("Branded App"));
+    assert_eq!(branding.theme_color.as_deref(), Some("#1e88e5"));
+    assert_eq!(branding.description.as_deref(), Some("Internal dashboard"));
+
+    // Without rewrite_html the document is left untouched
+    let manifest = Manifest::parse(&toml.replace("rewrite_html = true", "")).unwrap();
+    assert!(manifest.get_html_branding().is_none());
+
+    // Brand color must be a hex color
+    let manifest = Manifest::parse(&toml.replace("#1e88e5", "blue")).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_about_config() {
+    let toml = r#"
+[package]
+name = "about-app"
+title = "About App"
+version = "2.0.0"
+license = "MIT"
+authors = ["Jane Doe"]
+
+[frontend]
+path = "./dist"
+
+[about]
+changelog = "CHANGELOG.md"
+license_file = "LICENSE"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let about = manifest
+        .get_about_config(std::path::Path::new("/project"))
+        .expect("about config");
+
+    assert_eq!(
+        about.changelog.as_deref(),
+        Some(std::path::Path::new("/project/CHANGELOG.md"))
+    );
+    assert!(about.third_party_notices);
+    assert_eq!(about.info.name, "About App");
+    assert_eq!(about.info.version.as_deref(), Some("2.0.0"));
+    assert_eq!(about.info.license.as_deref(), Some("MIT"));
+    assert_eq!(about.info.authors, vec!["Jane Doe".to_string()]);
+}
+
+#[test]
+fn test_build_license_policy() {
+    let toml = r#"
+[package]
+name = "proprietary-app"
+
+[frontend]
+path = "./dist"
+
+[build.license_policy]
+allowed = ["MIT", "BSD-*", "Apache-2.0"]
+denied = ["GPL-*"]
+deny_unknown = true
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let policy = manifest.build.license_policy.as_ref().unwrap();
+    assert!(policy.deny_unknown);
+    assert!(policy.permits("MIT"));
+    assert!(policy.permits("bsd-3-clause"));
+    assert!(policy.permits("(MIT OR GPL-3.0-only)"));
+    assert!(policy.permits("Apache-2.0 AND MIT"));
+    assert!(!policy.permits("GPL-3.0-only"));
+    assert!(!policy.permits("MIT AND GPL-2.0-only WITH Classpath-exception-2.0"));
+    assert!(!policy.permits("MPL-2.0"));
+
+    // Contradictory policy is rejected
+    let manifest =
+        Manifest::parse(&toml.replace(r#"denied = ["GPL-*"]"#, r#"denied = ["mit"]"#)).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_build_optimize_and_symbols() {
+    let toml = r#"
+[package]
+name = "app"
+
+[frontend]
+path = "./dist"
+
+[build.optimize]
+upx = true
+exclude = ["lib/torch/**"]
+
+[build.symbols]
+strip = false
+symbol_server = "https://symbols.example.com"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let optimize = manifest.build.optimize.as_ref().unwrap();
+    assert!(optimize.strip);
+    assert!(optimize.upx);
+    assert_eq!(optimize.upx_args, vec!["--best".to_string()]);
+    assert_eq!(optimize.exclude, vec!["lib/torch/**".to_string()]);
+
+    let symbols = manifest.build.symbols.as_ref().unwrap();
+    assert!(!symbols.strip);
+    assert!(symbols.collect);
+    assert_eq!(
+        symbols.symbol_server.as_deref(),
+        Some("https://symbols.example.com")
+    );
+}
+
+#[test]
+fn test_build_store_remote() {
+    let toml = r#"
+[package]
+name = "app"
+
+[frontend]
+path = "./dist"
+
+[build.store]
+max_size_mb = 2048
+
+[build.store.remote]
+url = "s3://ci-cache/auroraview"
+region = "eu-west-1"
+push = false
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let store = &manifest.build.store;
+    assert_eq!(store.max_size_mb, Some(2048));
+    let remote = store.remote.as_ref().unwrap();
+    assert_eq!(remote.url, "s3://ci-cache/auroraview");
+    assert_eq!(remote.region.as_deref(), Some("eu-west-1"));
+    assert!(!remote.push);
+    assert_eq!(remote.timeout_secs, 60);
+    assert!(store.validate().is_ok());
+}
+
+#[test]
+fn test_hooks_isolation_resolves_extra_path() {
+    let toml = r#"
+[package]
+name = "app"
+
+[frontend]
+path = "./dist"
+
+[hooks.isolation]
+extra_path = ["./tools/bin", "/opt/go/bin"]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let hooks = manifest
+        .hooks
+        .as_ref()
+        .unwrap()
+        .to_hooks_config(std::path::Path::new("/project"));
+    let isolation = hooks.isolation.unwrap();
+    assert!(isolation.path);
+    assert!(!isolation.system_path.is_empty());
+    assert_eq!(
+        std::path::PathBuf::from(&isolation.extra_path[0]),
+        std::path::Path::new("/project").join("tools").join("bin")
+    );
+    assert_eq!(isolation.extra_path[1], "/opt/go/bin");
+}
+
+#[test]
+fn test_shortcut_tasks() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[[shortcuts.tasks]]
+name = "New window"
+args = ["--new-window"]
+
+[[shortcuts.tasks]]
+name = "Open logs folder"
+args = ["--open-logs"]
+icon = "./icons/logs.ico"
+description = "Show the log directory"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let tasks = &manifest.shortcuts.as_ref().unwrap().tasks;
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].name, "New window");
+    assert_eq!(tasks[0].icon, None);
+
+    let base = std::path::Path::new("/project");
+    let config = auroraview_pack::PackConfig::from_manifest(&manifest, base).unwrap();
+    let tasks = &config.shortcuts.as_ref().unwrap().tasks;
+    assert_eq!(tasks[1].args, vec!["--open-logs"]);
+    assert_eq!(tasks[1].icon, Some(base.join("icons/logs.ico")));
+    assert_eq!(
+        tasks[1].description.as_deref(),
+        Some("Show the log directory")
+    );
+}
+
+#[test]
+fn test_signing_section() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[signing]
+key = "./keys/overlay.pk8"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let base = std::path::Path::new("/project");
+    let config = auroraview_pack::PackConfig::from_manifest(&manifest, base).unwrap();
+    let signing = config.signing.clone().unwrap();
+    assert_eq!(signing.key, Some(base.join("keys/overlay.pk8")));
+
+    // Pack-time only
+    let json = serde_json::to_value(&config).unwrap();
+    assert!(json.get("signing").is_none());
+
+    let both = auroraview_pack::SigningConfig {
+        key_env: Some("AURORAVIEW_SIGNING_KEY".to_string()),
+        ..signing
+    };
+    assert!(both
+        .validate()
+        .unwrap_err()
+        .to_string()
+        .contains("mutually exclusive"));
+    assert!(auroraview_pack::SigningConfig::default()
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn test_signing_key_id() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[signing]
+key_id = "release"
+keystore = "./keys"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let base = std::path::Path::new("/project");
+    let config = auroraview_pack::PackConfig::from_manifest(&manifest, base).unwrap();
+    let signing = config.signing.unwrap();
+    assert_eq!(signing.key_id.as_deref(), Some("release"));
+    assert_eq!(signing.keystore, Some(base.join("keys")));
+    assert_eq!(signing.keystore().dir(), base.join("keys"));
+    signing.validate().unwrap();
+}
+
+#[test]
+fn test_build_threads() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[build]
+threads = 4
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new("/project"))
+            .unwrap();
+    assert_eq!(config.threads, Some(4));
+
+    let zero = Manifest::parse(&toml.replace("threads = 4", "threads = 0")).unwrap();
+    assert!(zero.validate().unwrap_err().to_string().contains("threads"));
+}
+
+#[test]
+fn test_build_provenance() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+version = "1.2.0"
+
+[frontend]
+url = "https://photos.example.com"
+
+[signing]
+key = "keys/overlay.pk8"
+
+[build.provenance]
+builder_id = "https://ci.example.com/release"
+environment = ["RELEASE_TRAIN"]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    manifest.validate().unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new("/project"))
+            .unwrap();
+    let provenance = config.provenance.unwrap();
+    assert_eq!(provenance.builder_id(), "https://ci.example.com/release");
+    assert_eq!(provenance.environment, ["RELEASE_TRAIN"]);
+    assert_eq!(config.manifest_sha256.map(|h| h.len()), Some(64));
+
+    let unsigned =
+        Manifest::parse(&toml.replace("[signing]\nkey = \"keys/overlay.pk8\"\n", "")).unwrap();
+    assert!(unsigned
+        .validate()
+        .unwrap_err()
+        .to_string()
+        .contains("[signing]"));
+}
+
+#[test]
+fn test_build_layout() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[build]
+layout = "onedir"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new("/project"))
+            .unwrap();
+    assert_eq!(config.layout, Some(auroraview_pack::OutputLayout::Onedir));
+
+    let default = Manifest::parse(&toml.replace("layout = \"onedir\"", "")).unwrap();
+    assert_eq!(default.build.layout, None);
+    assert!(Manifest::parse(&toml.replace("onedir", "onetree")).is_err());
+}
+
+#[test]
+fn test_build_targets() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[build]
+targets = ["x86_64-windows", "aarch64-apple-darwin"]
+
+[build.stubs]
+"x86_64-pc-windows-msvc" = "stubs/auroraview.exe"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    manifest.validate().unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new("/project"))
+            .unwrap();
+    let names: Vec<String> = config.targets.iter().map(|t| t.to_string()).collect();
+    assert_eq!(names, ["x86_64-windows", "aarch64-macos"]);
+    assert_eq!(
+        config.stub(&config.targets[0]).unwrap(),
+        &std::path::Path::new("/project").join("stubs/auroraview.exe")
+    );
+    assert!(config.stub(&config.targets[1]).is_none());
+
+    let windows = config.for_target(config.targets[0]);
+    assert_eq!(windows.output_name, "photo-tool-x86_64-windows");
+    assert!(windows.targets.is_empty());
+
+    let invalid = Manifest::parse(&toml.replace("aarch64-apple-darwin", "sparc-solaris")).unwrap();
+    assert!(invalid
+        .validate()
+        .unwrap_err()
+        .to_string()
+        .contains("sparc-solaris"));
+}
+
+#[test]
+fn test_window_dpi() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[window.dpi]
+awareness = "per_monitor"
+min_scale_factor = 1.25
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let dpi = config.window.dpi.clone().unwrap();
+    assert_eq!(dpi.awareness, auroraview_pack::DpiAwareness::PerMonitor);
+    assert_eq!(dpi.scale_factor, None);
+    assert_eq!(dpi.min_scale_factor, Some(1.25));
+    assert!(dpi.validate().is_ok());
+
+    // Carried in the overlay config for the runtime
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["window"]["dpi"]["awareness"], "per_monitor");
+
+    // Defaults to per-monitor V2
+    let manifest = Manifest::parse(&toml.replace("awareness = \"per_monitor\"\n", "")).unwrap();
+    assert_eq!(
+        manifest.get_window_config().dpi.unwrap().awareness,
+        auroraview_pack::DpiAwareness::PerMonitorV2
+    );
+
+    let invalid = auroraview_pack::DpiConfig {
+        scale_factor: Some(1.0),
+        ..dpi
+    };
+    assert!(invalid
+        .validate()
+        .unwrap_err()
+        .to_string()
+        .contains("above scale_factor"));
+}
+
+#[test]
+fn test_window_theme() {
+    let toml = r##"
+[package]
+name = "photo-tool"
+
+[frontend]
+url = "https://photos.example.com"
+
+[window]
+frameless = true
+
+[window.theme]
+mode = "dark"
+titlebar_color = "#1e1e1e"
+background_color = "#121212"
+"##;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let theme = config.window.theme.clone().unwrap();
+    assert_eq!(theme.mode, auroraview_pack::ThemeMode::Dark);
+    assert_eq!(theme.titlebar_color.as_deref(), Some("#1e1e1e"));
+    assert_eq!(theme.titlebar_text_color, None);
+    assert!(theme.validate().is_ok());
+
+    // Carried in the overlay config for the runtime
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["window"]["theme"]["mode"], "dark");
+    assert_eq!(json["window"]["theme"]["background_color"], "#121212");
+
+    let manifest = Manifest::parse(&toml.replace("#121212", "charcoal")).unwrap();
+    let err = manifest
+        .get_window_config()
+        .theme
+        .unwrap()
+        .validate()
+        .unwrap_err();
+    assert!(err.to_string().contains("background_color"));
+}
+
+#[test]
+fn test_window_content() {
+    let toml = r#"
+[package]
+name = "kiosk"
+
+[frontend]
+url = "https://kiosk.example.com"
+
+[window.content]
+zoom = 1.5
+user_zoom = false
+pinch_zoom = false
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let content = config.window.content.clone().unwrap();
+    assert_eq!(content.zoom, 1.5);
+    assert!(!content.user_zoom);
+    assert!(!content.pinch_zoom);
+    assert!(content.validate().is_ok());
+
+    // Carried in the overlay config for the runtime
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["window"]["content"]["zoom"], 1.5);
+    assert_eq!(json["window"]["content"]["pinch_zoom"], false);
+
+    // Defaults leave zooming to the user
+    let manifest = Manifest::parse(&toml.replace("zoom = 1.5\n", "")).unwrap();
+    let content = manifest.get_window_config().content.unwrap();
+    assert_eq!(content.zoom, 1.0);
+
+    let manifest =
+        Manifest::parse(&toml.replace("zoom = 1.5", "zoom = 1.5\nmax_zoom = 1.25")).unwrap();
+    let err = manifest
+        .get_window_config()
+        .content
+        .unwrap()
+        .validate()
+        .unwrap_err();
+    assert!(err.to_string().contains("max_zoom"));
+}
+
+#[test]
+fn test_build_codecs() {
+    let toml = r#"
+[package]
+name = "codecs"
+
+[frontend]
+path = "./dist"
+
+[build.codecs]
+frontend = "brotli"
+python_runtime = "store"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let codecs = manifest.build.codecs;
+    assert_eq!(
+        codecs.codec_for("frontend/app.js"),
+        auroraview_pack::Codec::Brotli
+    );
+    assert_eq!(
+        codecs.codec_for("python_runtime.tar.gz"),
+        auroraview_pack::Codec::Store
+    );
+    assert_eq!(
+        codecs.codec_for("python/main.py"),
+        auroraview_pack::Codec::Zstd
+    );
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert_eq!(config.codecs, codecs);
+
+    // Pack-time only: the overlay index records each asset's codec
+    let json = serde_json::to_value(&config).unwrap();
+    assert!(json.get("codecs").is_none());
+
+    assert!(Manifest::parse(&toml.replace("\"store\"", "\"xz\"")).is_err());
+}
+
+#[test]
+fn test_user_agent_template() {
+    let toml = r#"
+[package]
+name = "viewer"
+version = "2.3.0"
+user_agent = "{default} {app_name}/{version} ({os})"
+
+[frontend]
+url = "https://viewer.example.com"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let vars = auroraview_pack::UserAgentVars::new(
+        "viewer",
+        "2.3.0",
+        auroraview_pack::TargetPlatform::Current,
+    );
+    assert_eq!(
+        config.user_agent.as_deref(),
+        Some(format!("{{default}} viewer/2.3.0 ({})", vars.os).as_str())
+    );
+    assert!(auroraview_pack::validate_user_agent(config.user_agent.as_deref().unwrap()).is_ok());
+
+    // Recorded in the overlay config
+    let json = serde_json::to_value(&config).unwrap();
+    assert!(json["user_agent"]
+        .as_str()
+        .unwrap()
+        .contains("viewer/2.3.0"));
+
+    // Static user agents are kept as is
+    let manifest = Manifest::parse(&toml.replace(
+        "{default} {app_name}/{version} ({os})",
+        "Mozilla/5.0 Custom",
+    ))
+    .unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert_eq!(config.user_agent.as_deref(), Some("Mozilla/5.0 Custom"));
+
+    let manifest = Manifest::parse(&toml.replace("{os}", "{platform}")).unwrap();
+    let err = auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new("."))
+        .unwrap_err();
+    assert!(err.to_string().contains("{platform}"));
+
+    // Builder configs must be expanded before packing
+    assert!(auroraview_pack::validate_user_agent("{app_name}/1.0").is_err());
+}
+
+#[test]
+fn test_identifier_threading() {
+    let toml = r#"
+[package]
+name = "viewer"
+identifier = "com.acme.viewer"
+
+[frontend]
+url = "https://viewer.acme.com"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert_eq!(config.identifier.as_deref(), Some("com.acme.viewer"));
+
+    let identity = config.identity();
+    assert!(identity.runtime_cache_dir().ends_with("com.acme.viewer"));
+    assert_eq!(identity.app_user_model_id(), "com.acme.viewer");
+    assert_eq!(identity.macos_bundle_id(), "com.acme.viewer");
+    assert_eq!(identity.linux_desktop_file(), "com.acme.viewer.desktop");
+    assert_eq!(
+        identity.single_instance_name(),
+        "AuroraView.com.acme.viewer"
+    );
+
+    // Two apps of the same vendor with the same output name never collide
+    let other = config.clone().with_identifier("com.acme.editor").identity();
+    assert_ne!(other.runtime_cache_dir(), identity.runtime_cache_dir());
+    assert_ne!(
+        other.single_instance_name(),
+        identity.single_instance_name()
+    );
+
+    // Recorded in the overlay for the runtime
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["identifier"], "com.acme.viewer");
+
+    // Falls back to the macOS bundle identifier, then the output name
+    let manifest = Manifest::parse(&toml.replace(
+        "identifier = \"com.acme.viewer\"",
+        "\n[bundle.macos]\nbundle_identifier = \"com.acme.mac\"",
+    ))
+    .unwrap();
+    assert_eq!(manifest.get_identifier().as_deref(), Some("com.acme.mac"));
+    let config = auroraview_pack::PackConfig::url("https://viewer.acme.com");
+    assert!(config.identity().runtime_cache_dir().ends_with("viewer"));
+
+    assert!(auroraview_pack::validate_identifier("com.acme.viewer").is_ok());
+    assert!(auroraview_pack::validate_identifier("acme viewer").is_err());
+}
+
+#[test]
+fn test_build_metadata() {
+    let toml = r#"
+[package]
+name = "meta"
+
+[frontend]
+url = "https://example.com"
+
+[build.metadata]
+channel = "beta"
+build_id = "2026.10.16-3"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    assert_eq!(config.build_metadata["channel"], "beta");
+    assert_eq!(config.build_metadata["build_id"], "2026.10.16-3");
+
+    let config = config.with_build_metadata("git_commit", "4f2a9c1");
+    assert_eq!(config.build_metadata.len(), 3);
+}
+
+#[test]
+fn test_uninstall() {
+    let toml = r#"
+[package]
+name = "viewer"
+identifier = "com.acme.viewer"
+
+[frontend]
+path = "./dist"
+
+[uninstall]
+remove_user_data = false
+protocols = ["acme-viewer"]
+file_associations = [".acv"]
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    assert!(manifest.validate().is_ok());
+
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new(".")).unwrap();
+    let uninstall = config.uninstall.clone().unwrap();
+    assert!(uninstall.script);
+    assert!(uninstall.flag);
+    assert!(!uninstall.remove_user_data);
+    assert!(auroraview_pack::user_data_dir(&config.identity()).ends_with("com.acme.viewer"));
+
+    // The runtime reads the policy for --uninstall
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["uninstall"]["protocols"][0], "acme-viewer");
+
+    let manifest = Manifest::parse(&toml.replace("acme-viewer", "1bad")).unwrap();
+    assert!(manifest.validate().is_err());
+
+    let manifest = Manifest::parse(&toml.replace(".acv", ".a/b")).unwrap();
+    assert!(manifest.validate().is_err());
+}
+
+#[test]
+fn test_update_feed_section() {
+    let toml = r#"
+[package]
+name = "photo-tool"
+version = "1.4.0"
+
+[frontend]
+url = "https://photos.example.com"
+
+[bundle.update_feed]
+base_url = "https://downloads.example.com/{version}/"
+minimum_version = "1.2.0"
+appcast = "appcast.xml"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let feed = manifest.bundle.update_feed.clone().unwrap();
+    assert_eq!(feed.file, std::path::PathBuf::from("latest.json"));
+    assert_eq!(
+        feed.artifact_url("photo-tool.exe", "1.4.0", "windows-x86_64"),
+        "https://downloads.example.com/1.4.0/photo-tool.exe"
+    );
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new("/p")).unwrap();
+    assert_eq!(config.update_feed, Some(feed));
+
+    let invalid = toml.replace("https://downloads", "ftp://downloads");
+    assert!(Manifest::parse(&invalid).unwrap().validate().is_err());
+}
+
+#[test]
+fn test_runtime_update_section() {
+    use auroraview_pack::{FeedArtifact, UpdateDecision, UpdateFeed};
+    use std::collections::BTreeMap;
+
+    let toml = r#"
+[package]
+name = "photo-tool"
+version = "1.4.0"
+
+[frontend]
+url = "https://photos.example.com"
+
+[runtime.update]
+channel = "beta"
+feed_url = "https://downloads.example.com/{channel}/latest.json"
+minimum_version = "1.2.0"
+maximum_version = "1.99.0"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    manifest.validate().unwrap();
+    let config =
+        auroraview_pack::PackConfig::from_manifest(&manifest, std::path::Path::new("/p")).unwrap();
+    let update = config.update.clone().unwrap();
+    assert_eq!(
+        update.resolved_feed_url().as_deref(),
+        Some("https://downloads.example.com/beta/latest.json")
+    );
+
+    // Runtime setting: stored in the overlay config
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["update"]["channel"], "beta");
+
+    let feed = |version: &str, channel: Option<&str>| UpdateFeed {
+        name: "photo-tool".to_string(),
+        version: version.to_string(),
+        channel: channel.map(str::to_string),
+        pub_date: "2026-10-16T00:00:00Z".to_string(),
+        notes_url: None,
+        minimum_version: None,
+        platforms: BTreeMap::<String, FeedArtifact>::new(),
+    };
+    assert_eq!(
+        update.decide(&feed("1.5.0", Some("beta")), "1.4.0"),
+        UpdateDecision::Install { mandatory: false }
+    );
+    assert_eq!(
+        update.decide(&feed("1.5.0", None), "1.4.0"),
+        UpdateDecision::WrongChannel {
+            channel: "stable".to_string()
+        }
+    );
+    assert_eq!(
+        update.decide(&feed("1.4.0", Some("beta")), "1.4.0"),
+        UpdateDecision::UpToDate
+    );
+    assert_eq!(
+        update.decide(&feed("2.0.0", Some("beta")), "1.4.0"),
+        UpdateDecision::OutOfRange
+    );
+    let mut forced = feed("1.6.0", Some("beta"));
+    forced.minimum_version = Some("1.5.0".to_string());
+    assert_eq!(
+        update.decide(&forced, "1.4.0"),
+        UpdateDecision::ReinstallRequired
+    );
+
+    // The package itself must be within the constraints
+    let invalid = toml.replace("version = \"1.4.0\"", "version = \"2.1.0\"");
+    assert!(Manifest::parse(&invalid).unwrap().validate().is_err());
+    let invalid = toml.replace("channel = \"beta\"", "channel = \"Beta Channel\"");
+    assert!(Manifest::parse(&invalid).unwrap().validate().is_err());
+}
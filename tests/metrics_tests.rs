@@ -43,3 +43,159 @@ fn test_report_format() {
     assert!(report.contains("Packed App Startup Performance"));
     assert!(report.contains("Overlay read"));
 }
+
+#[test]
+fn test_otlp_config_from_env_vars() {
+    use auroraview_pack::OtlpConfig;
+
+    let lookup = |vars: &[(&str, &str)]| {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name: &str| {
+            vars.iter()
+                .find(|(var, _)| var == name)
+                .map(|(_, value)| value.clone())
+        }
+    };
+    assert_eq!(OtlpConfig::from_lookup(lookup(&[])), None);
+
+    let config = OtlpConfig::from_lookup(lookup(&[
+        ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318/"),
+        (
+            "OTEL_EXPORTER_OTLP_METRICS_ENDPOINT",
+            "http://metrics/ingest",
+        ),
+        (
+            "OTEL_EXPORTER_OTLP_HEADERS",
+            "x-api-key=abc%3D%3D, x-team=build",
+        ),
+        (
+            "OTEL_RESOURCE_ATTRIBUTES",
+            "service.name=ci-packs,deployment.environment=ci",
+        ),
+    ]))
+    .unwrap();
+    assert_eq!(
+        config.traces_endpoint.as_deref(),
+        Some("http://collector:4318/v1/traces")
+    );
+    assert_eq!(
+        config.metrics_endpoint.as_deref(),
+        Some("http://metrics/ingest")
+    );
+    assert_eq!(
+        config.headers,
+        [
+            ("x-api-key".to_string(), "abc==".to_string()),
+            ("x-team".to_string(), "build".to_string())
+        ]
+    );
+    assert_eq!(config.service_name, "ci-packs");
+    assert_eq!(config.resource_attributes["deployment.environment"], "ci");
+
+    // Disabled, or over an unsupported protocol
+    for disabled in [
+        ("OTEL_SDK_DISABLED", "true"),
+        ("OTEL_EXPORTER_OTLP_PROTOCOL", "grpc"),
+    ] {
+        let vars = [
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318"),
+            disabled,
+        ];
+        assert_eq!(OtlpConfig::from_lookup(lookup(&vars)), None);
+    }
+}
+
+#[test]
+fn test_startup_telemetry() {
+    use auroraview_pack::{OtlpConfig, OtlpExporter};
+    use std::time::SystemTime;
+
+    let mut metrics = PackedMetrics::new();
+    metrics.mark_overlay_read();
+    metrics.add_phase("extract_python", Duration::from_millis(12));
+    metrics.mark_webview_created();
+    metrics.mark_total();
+
+    let exporter = OtlpExporter::new(
+        OtlpConfig::new("http://localhost:4318").with_resource_attribute("host.name", "ci-1"),
+    );
+    let traces = exporter.startup_traces(&metrics, SystemTime::now());
+    let resource = &traces["resourceSpans"][0]["resource"]["attributes"];
+    assert!(resource
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|a| a["key"] == "host.name" && a["value"]["stringValue"] == "ci-1"));
+    let spans = traces["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .unwrap();
+    let names: Vec<_> = spans.iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(
+        names,
+        ["startup", "startup.overlay_read", "startup.webview_created"]
+    );
+    let root = spans[0]["spanId"].as_str().unwrap();
+    assert_eq!(root.len(), 16);
+    assert_eq!(spans[0]["traceId"].as_str().unwrap().len(), 32);
+    assert!(spans[1..].iter().all(|s| s["parentSpanId"] == root));
+
+    let exported = exporter.startup_metrics(&metrics, SystemTime::now());
+    let metrics = exported["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+        .as_array()
+        .unwrap();
+    let phases = metrics
+        .iter()
+        .find(|m| m["name"] == "auroraview.startup.phase.duration")
+        .unwrap();
+    let point = &phases["gauge"]["dataPoints"][0];
+    assert_eq!(point["asInt"], "12");
+    assert_eq!(
+        point["attributes"][0]["value"]["stringValue"],
+        "extract_python"
+    );
+}
+
+#[test]
+fn test_pack_exports_telemetry() {
+    use auroraview_pack::test_support::{MockServer, PackFixture};
+    use auroraview_pack::{OtlpConfig, OtlpExporter, PackConfig, Packer};
+
+    let server = MockServer::start().unwrap();
+    let fixture = PackFixture::new().unwrap();
+    let config = fixture
+        .configure(PackConfig::frontend(fixture.frontend().unwrap()).with_output("otel-app"))
+        .unwrap();
+    let exporter =
+        OtlpExporter::new(OtlpConfig::new(&server.url("/")).with_header("x-api-key", "secret"));
+    let output = Packer::new(config).with_telemetry(exporter).pack().unwrap();
+
+    let uploads = server.uploads();
+    let paths: Vec<_> = uploads.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(paths, ["/v1/traces", "/v1/metrics"]);
+
+    let traces: serde_json::Value = serde_json::from_slice(&uploads[0].1).unwrap();
+    let spans = traces["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .unwrap();
+    assert_eq!(spans[0]["name"], "pack");
+    let stages: Vec<_> = spans[1..]
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(stages, ["pack.prepare", "pack.pack", "pack.after_pack"]);
+
+    let metrics: serde_json::Value = serde_json::from_slice(&uploads[1].1).unwrap();
+    let size = metrics["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["name"] == "auroraview.pack.size")
+        .unwrap();
+    assert_eq!(
+        size["gauge"]["dataPoints"][0]["asInt"],
+        output.size.to_string()
+    );
+}
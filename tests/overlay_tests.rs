@@ -1,6 +1,10 @@
 //! Tests for auroraview-pack overlay module
 
-use auroraview_pack::{OverlayData, OverlayReader, OverlayWriter, PackConfig};
+use auroraview_pack::{
+    migrate, sidecar_path, Codec, CodecConfig, Compatibility, OverlayData, OverlayReader,
+    OverlaySigner, OverlayVersion, OverlayWriter, PackConfig, PackError, MIN_READER_VERSION,
+    OVERLAY_MAGIC, OVERLAY_VERSION, PACKER_VERSION,
+};
 use tempfile::NamedTempFile;
 
 #[test]
@@ -41,3 +45,1020 @@ fn test_no_overlay() {
     assert!(!OverlayReader::has_overlay(temp.path()).unwrap());
     assert!(OverlayReader::read(temp.path()).unwrap().is_none());
 }
+
+#[test]
+fn test_overlay_random_access() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("index.html", b"<html></html>".to_vec());
+    data.add_asset("big.bin", vec![7u8; 256 * 1024]);
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    let mut archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(archive.version, OVERLAY_VERSION);
+    assert_eq!(archive.entries().len(), 2);
+    let entry = archive.entry("big.bin").unwrap();
+    assert_eq!(entry.size, 256 * 1024);
+    assert!(entry.length < entry.size);
+
+    assert_eq!(
+        archive.read_asset("index.html").unwrap().unwrap(),
+        b"<html></html>"
+    );
+    assert_eq!(
+        archive.read_asset("big.bin").unwrap().unwrap().len(),
+        256 * 1024
+    );
+    assert!(archive.read_asset("missing.txt").unwrap().is_none());
+}
+
+#[test]
+fn test_overlay_detects_corrupted_asset() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"exe").unwrap();
+
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("a.txt", b"first".to_vec());
+    data.add_asset("b.txt", b"second".to_vec());
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    // Swap the stored bytes of the two assets
+    let archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    let a = archive.entry("a.txt").unwrap().clone();
+    let b = archive.entry("b.txt").unwrap().clone();
+    drop(archive);
+    let mut bytes = std::fs::read(temp.path()).unwrap();
+    let footer = bytes.len() - 12;
+    let data_start = footer - (a.length + b.length) as usize;
+    let stored: Vec<u8> = bytes[data_start..footer].to_vec();
+    let (first, second) = stored.split_at(a.length as usize);
+    bytes[data_start..footer].copy_from_slice(&[second, first].concat());
+    std::fs::write(temp.path(), &bytes).unwrap();
+
+    let mut archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    let mut content = Vec::new();
+    assert!(std::io::Read::read_to_end(
+        &mut archive.asset_reader("a.txt").unwrap().unwrap(),
+        &mut content,
+    )
+    .is_err());
+    assert!(archive.read_asset("a.txt").is_err());
+    assert!(OverlayReader::read(temp.path()).is_err());
+}
+
+/// Write a version 2 overlay whose asset index is `index`, with no asset data
+fn write_indexed_overlay(path: &std::path::Path, index: serde_json::Value) {
+    use std::io::Write;
+
+    let mut metadata = serde_json::to_value(PackConfig::url("https://example.com")).unwrap();
+    metadata["content_hash"] = "0123456789abcdef".into();
+    let config_compressed =
+        zstd::encode_all(&serde_json::to_vec(&metadata).unwrap()[..], 3).unwrap();
+    let index_compressed = zstd::encode_all(&serde_json::to_vec(&index).unwrap()[..], 3).unwrap();
+
+    let mut file = std::fs::File::create(path).unwrap();
+    file.write_all(b"exe").unwrap();
+    file.write_all(OVERLAY_MAGIC).unwrap();
+    file.write_all(&2u32.to_le_bytes()).unwrap();
+    file.write_all(&(config_compressed.len() as u64).to_le_bytes())
+        .unwrap();
+    file.write_all(&(index_compressed.len() as u64).to_le_bytes())
+        .unwrap();
+    file.write_all(&config_compressed).unwrap();
+    file.write_all(&index_compressed).unwrap();
+    file.write_all(&3u64.to_le_bytes()).unwrap();
+    file.write_all(OVERLAY_MAGIC).unwrap();
+}
+
+#[test]
+fn test_overlay_rejects_index_outside_file() {
+    let temp = NamedTempFile::new().unwrap();
+    let entry = |offset: u64, length: u64| {
+        serde_json::json!([{
+            "path": "index.html",
+            "offset": offset,
+            "length": length,
+            "size": u64::MAX,
+            "hash": "00",
+        }])
+    };
+    // Overflowing and huge ranges fail before anything is allocated
+    for (offset, length) in [(u64::MAX, 2), (0, 1 << 40), (1, 0)] {
+        write_indexed_overlay(temp.path(), entry(offset, length));
+        let err = OverlayReader::read(temp.path()).unwrap_err();
+        assert!(
+            err.to_string().contains("lies outside the overlay"),
+            "{}",
+            err
+        );
+        assert!(OverlayReader::open(temp.path()).is_err());
+    }
+
+    // Section lengths in the header are bounded by the file too
+    let mut bytes = std::fs::read(temp.path()).unwrap();
+    bytes[3 + 8..3 + 16].copy_from_slice(&(1u64 << 40).to_le_bytes());
+    std::fs::write(temp.path(), &bytes).unwrap();
+    assert!(matches!(
+        OverlayReader::read(temp.path()),
+        Err(PackError::InvalidOverlay(_))
+    ));
+}
+
+/// Write a version 1 overlay (config, then one tar.zstd of all assets)
+fn write_v1_overlay(path: &std::path::Path, exe: &[u8]) {
+    write_legacy_overlay(path, exe, 1, serde_json::json!({}));
+}
+
+/// Write an overlay laid out like version 1, with format version `format`
+/// and `fields` set in the config (`null` removes a field)
+fn write_legacy_overlay(
+    path: &std::path::Path,
+    exe: &[u8],
+    format: u32,
+    fields: serde_json::Value,
+) {
+    use std::io::Write;
+
+    let config = PackConfig::url("https://example.com").with_title("Legacy");
+    let mut metadata = serde_json::to_value(&config).unwrap();
+    metadata["content_hash"] = "0123456789abcdef".into();
+    for (key, value) in fields.as_object().unwrap() {
+        match value {
+            serde_json::Value::Null => {
+                metadata.as_object_mut().unwrap().remove(key);
+            }
+            _ => metadata[key] = value.clone(),
+        }
+    }
+    let config_compressed =
+        zstd::encode_all(&serde_json::to_vec(&metadata).unwrap()[..], 3).unwrap();
+    let mut tar = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(13);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, "index.html", &b"<html></html>"[..])
+        .unwrap();
+    let assets_compressed = zstd::encode_all(&tar.into_inner().unwrap()[..], 3).unwrap();
+
+    let mut file = std::fs::File::create(path).unwrap();
+    file.write_all(exe).unwrap();
+    file.write_all(OVERLAY_MAGIC).unwrap();
+    file.write_all(&format.to_le_bytes()).unwrap();
+    file.write_all(&(config_compressed.len() as u64).to_le_bytes())
+        .unwrap();
+    file.write_all(&(assets_compressed.len() as u64).to_le_bytes())
+        .unwrap();
+    file.write_all(&config_compressed).unwrap();
+    file.write_all(&assets_compressed).unwrap();
+    file.write_all(&(exe.len() as u64).to_le_bytes()).unwrap();
+    file.write_all(OVERLAY_MAGIC).unwrap();
+}
+
+#[test]
+fn test_overlay_reads_v1_format() {
+    let temp = NamedTempFile::new().unwrap();
+    write_v1_overlay(temp.path(), b"fake executable content");
+
+    let read_data = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert_eq!(read_data.config.window.title, "Legacy");
+    assert_eq!(read_data.content_hash, "0123456789abcdef");
+    assert_eq!(read_data.assets.len(), 1);
+
+    let mut archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(archive.version, 1);
+    let mut content = String::new();
+    std::io::Read::read_to_string(
+        &mut archive.asset_reader("index.html").unwrap().unwrap(),
+        &mut content,
+    )
+    .unwrap();
+    assert_eq!(content, "<html></html>");
+    assert_eq!(
+        archive.read_asset("index.html").unwrap().unwrap(),
+        b"<html></html>"
+    );
+}
+
+#[test]
+fn test_overlay_version() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+    assert!(OverlayReader::version(temp.path()).unwrap().is_none());
+
+    let data = OverlayData::new(PackConfig::url("https://example.com"));
+    OverlayWriter::write(temp.path(), &data).unwrap();
+    let version = OverlayReader::version(temp.path()).unwrap().unwrap();
+    assert_eq!(version.format, OVERLAY_VERSION);
+    assert_eq!(version.packer_version.as_deref(), Some(PACKER_VERSION));
+    assert_eq!(
+        version.min_reader_version.as_deref(),
+        Some(MIN_READER_VERSION)
+    );
+    assert_eq!(version.compatibility(), Compatibility::Compatible);
+
+    // Written before releases were recorded
+    write_v1_overlay(temp.path(), b"fake executable content");
+    let version = OverlayReader::version(temp.path()).unwrap().unwrap();
+    assert_eq!(version.format, 1);
+    assert_eq!(version.packer_version, None);
+    assert!(version.check().is_ok());
+
+    // Compatibility matrix against other readers
+    let version = OverlayVersion {
+        format: 3,
+        packer_version: Some("0.5.0".to_string()),
+        min_reader_version: Some("0.4.0".to_string()),
+    };
+    assert_eq!(
+        version.compatibility_with("0.3.9", 1..=3),
+        Compatibility::ReaderTooOld {
+            required: "0.4.0".to_string()
+        }
+    );
+    assert_eq!(
+        version.compatibility_with("0.4.0", 1..=3),
+        Compatibility::Compatible
+    );
+    assert_eq!(
+        version.compatibility_with("1.0.0", 1..=2),
+        Compatibility::UnsupportedFormat { format: 3 }
+    );
+}
+
+#[test]
+fn test_overlay_rejects_newer_overlay() {
+    let temp = NamedTempFile::new().unwrap();
+
+    // Needs a newer reader
+    write_legacy_overlay(
+        temp.path(),
+        b"fake executable content",
+        1,
+        serde_json::json!({ "packer_version": "99.1.0", "min_reader_version": "99.0.0" }),
+    );
+    let err = OverlayReader::read(temp.path()).unwrap_err();
+    assert!(matches!(err, PackError::IncompatibleOverlay(_)));
+    let message = err.to_string();
+    assert!(
+        message.contains("built with auroraview-pack 99.1.0"),
+        "{}",
+        message
+    );
+    assert!(message.contains(">= 99.0.0"), "{}", message);
+    assert!(message.contains(PACKER_VERSION), "{}", message);
+
+    // Unknown format: the version is still reported
+    write_legacy_overlay(
+        temp.path(),
+        b"fake executable content",
+        OVERLAY_VERSION + 1,
+        serde_json::json!({ "packer_version": "99.1.0" }),
+    );
+    let version = OverlayReader::version(temp.path()).unwrap().unwrap();
+    assert_eq!(version.format, OVERLAY_VERSION + 1);
+    assert_eq!(version.packer_version.as_deref(), Some("99.1.0"));
+    assert_eq!(
+        version.compatibility(),
+        Compatibility::UnsupportedFormat {
+            format: OVERLAY_VERSION + 1
+        }
+    );
+    let err = OverlayReader::read(temp.path()).unwrap_err();
+    assert!(matches!(err, PackError::IncompatibleOverlay(_)));
+    assert!(err
+        .to_string()
+        .contains("built with auroraview-pack 99.1.0"));
+}
+
+#[test]
+fn test_overlay_schema_report() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+    let data = OverlayData::new(PackConfig::url("https://example.com"));
+    OverlayWriter::write(temp.path(), &data).unwrap();
+    let read = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert!(read.schema.is_exact(), "{:?}", read.schema);
+    assert_eq!(read.schema.describe(), None);
+    assert_eq!(
+        read.schema.version.unwrap().packer_version.as_deref(),
+        Some(PACKER_VERSION)
+    );
+
+    // Written by a newer release (a new field) or an older one (a field
+    // missing)
+    write_legacy_overlay(
+        temp.path(),
+        b"fake executable content",
+        1,
+        serde_json::json!({
+            "packer_version": "9.0.0",
+            "future_option": { "enabled": true },
+            "compression_level": null,
+        }),
+    );
+    let read = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert_eq!(read.schema.unknown_fields, ["future_option"]);
+    assert_eq!(read.schema.defaulted_fields, ["compression_level"]);
+    assert_eq!(
+        read.config.unknown_fields["future_option"],
+        serde_json::json!({ "enabled": true })
+    );
+    let message = read.schema.describe().unwrap();
+    assert!(
+        message.contains("built with auroraview-pack 9.0.0"),
+        "{}",
+        message
+    );
+    assert!(message.contains("future_option"), "{}", message);
+    let archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(archive.schema, read.schema);
+
+    // Unknown fields survive a rewrite
+    let rewritten = NamedTempFile::new().unwrap();
+    std::fs::write(rewritten.path(), b"fake executable content").unwrap();
+    OverlayWriter::write(rewritten.path(), &read).unwrap();
+    let reread = OverlayReader::read(rewritten.path()).unwrap().unwrap();
+    assert_eq!(reread.schema.unknown_fields, ["future_option"]);
+    assert!(reread.schema.defaulted_fields.is_empty());
+}
+
+#[test]
+fn test_overlay_migrate() {
+    let temp = NamedTempFile::new().unwrap();
+    write_v1_overlay(temp.path(), b"fake executable content");
+    assert_eq!(OverlayReader::detect_version(temp.path()).unwrap(), Some(1));
+
+    assert!(migrate(temp.path(), OVERLAY_VERSION + 1).is_err());
+    assert_eq!(migrate(temp.path(), OVERLAY_VERSION).unwrap(), 1);
+    assert_eq!(
+        OverlayReader::detect_version(temp.path()).unwrap(),
+        Some(OVERLAY_VERSION)
+    );
+    assert!(std::fs::read(temp.path())
+        .unwrap()
+        .starts_with(b"fake executable content"));
+
+    // Same config, assets and cache key, now with an index
+    let mut archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(archive.version, OVERLAY_VERSION);
+    assert_eq!(archive.config.window.title, "Legacy");
+    assert_eq!(archive.content_hash, "0123456789abcdef");
+    assert_eq!(
+        archive.read_asset("index.html").unwrap().unwrap(),
+        b"<html></html>"
+    );
+
+    // Already current: nothing to do
+    let before = std::fs::read(temp.path()).unwrap();
+    assert_eq!(
+        migrate(temp.path(), OVERLAY_VERSION).unwrap(),
+        OVERLAY_VERSION
+    );
+    assert_eq!(std::fs::read(temp.path()).unwrap(), before);
+
+    let plain = NamedTempFile::new().unwrap();
+    std::fs::write(plain.path(), b"no overlay").unwrap();
+    assert_eq!(OverlayReader::detect_version(plain.path()).unwrap(), None);
+    assert!(migrate(plain.path(), OVERLAY_VERSION).is_err());
+}
+
+#[test]
+fn test_overlay_sidecar_fallback() {
+    let dir = tempfile::tempdir().unwrap();
+    let exe = dir.path().join("app.exe");
+    std::fs::write(&exe, b"small launcher").unwrap();
+    assert!(!OverlayReader::has_overlay(&exe).unwrap());
+
+    let sidecar = sidecar_path(&exe);
+    assert_eq!(sidecar, dir.path().join("app.avpk"));
+    std::fs::write(&sidecar, []).unwrap();
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("index.html", b"<html></html>".to_vec());
+    OverlayWriter::write(&sidecar, &data).unwrap();
+
+    assert!(OverlayReader::has_overlay(&exe).unwrap());
+    assert_eq!(
+        OverlayReader::overlay_file(&exe).unwrap(),
+        Some(sidecar.clone())
+    );
+    assert_eq!(OverlayReader::get_original_size(&exe).unwrap(), None);
+    let read_data = OverlayReader::read(&exe).unwrap().unwrap();
+    assert_eq!(read_data.assets, data.assets);
+
+    // Patching goes to the sidecar; the launcher stays as it is
+    OverlayWriter::patch(
+        &exe,
+        vec![("index.html".to_string(), b"<html>v2</html>".to_vec())],
+        Vec::new(),
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&exe).unwrap(), b"small launcher");
+    let mut archive = OverlayReader::open(&exe).unwrap().unwrap();
+    assert_eq!(
+        archive.read_asset("index.html").unwrap().unwrap(),
+        b"<html>v2</html>"
+    );
+}
+
+#[test]
+fn test_overlay_streaming_writer() {
+    let buffered = NamedTempFile::new().unwrap();
+    let streamed = NamedTempFile::new().unwrap();
+    std::fs::write(buffered.path(), b"fake executable content").unwrap();
+    std::fs::write(streamed.path(), b"fake executable content").unwrap();
+
+    let runtime = NamedTempFile::new().unwrap();
+    let runtime_content: Vec<u8> = (0..512 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::write(runtime.path(), &runtime_content).unwrap();
+
+    let config = PackConfig::url("https://example.com").with_title("Streamed");
+    let mut data = OverlayData::new(config.clone());
+    data.add_asset("index.html", b"<html></html>".to_vec());
+    data.add_asset("python_runtime.tar.gz", runtime_content.clone());
+    OverlayWriter::write(buffered.path(), &data).unwrap();
+
+    let mut stream = OverlayWriter::begin(streamed.path(), 3).unwrap();
+    stream.append_asset("index.html", b"<html></html>").unwrap();
+    let size = stream
+        .append_asset_from_file("python_runtime.tar.gz", runtime.path())
+        .unwrap();
+    assert_eq!(size, runtime_content.len() as u64);
+    let hash = stream.finish(&config).unwrap();
+
+    // Same content hash as the buffered writer, same assets on read
+    let expected = OverlayReader::read(buffered.path()).unwrap().unwrap();
+    assert_eq!(hash, expected.content_hash);
+
+    let read_data = OverlayReader::read(streamed.path()).unwrap().unwrap();
+    assert_eq!(read_data.config.window.title, "Streamed");
+    assert_eq!(read_data.content_hash, hash);
+    assert_eq!(read_data.assets, expected.assets);
+
+    let mut archive = OverlayReader::open(streamed.path()).unwrap().unwrap();
+    assert_eq!(
+        archive
+            .read_asset("python_runtime.tar.gz")
+            .unwrap()
+            .unwrap(),
+        runtime_content
+    );
+    assert_eq!(
+        OverlayReader::get_original_size(streamed.path())
+            .unwrap()
+            .unwrap(),
+        b"fake executable content".len() as u64
+    );
+}
+
+#[test]
+fn test_overlay_codecs() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+
+    let codecs = CodecConfig {
+        frontend: Some(Codec::Brotli),
+        python: Some(Codec::Lz4),
+        python_runtime: Some(Codec::Store),
+        ..Default::default()
+    };
+    let runtime: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+    let assets = vec![
+        (
+            "frontend/index.html".to_string(),
+            b"<html><body>hello</body></html>".repeat(50),
+        ),
+        ("python/main.py".to_string(), b"print('hi')\n".repeat(50)),
+        ("python_runtime.tar.gz".to_string(), runtime.clone()),
+        ("data/model.bin".to_string(), vec![7u8; 4096]),
+    ];
+
+    let config = PackConfig::url("https://example.com");
+    let mut stream = OverlayWriter::begin(temp.path(), 3)
+        .unwrap()
+        .with_codecs(codecs);
+    stream.append_assets(&assets).unwrap();
+    stream.finish(&config).unwrap();
+
+    let mut archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(archive.version, OVERLAY_VERSION);
+    let codec_of =
+        |archive: &auroraview_pack::OverlayArchive, path: &str| archive.entry(path).unwrap().codec;
+    assert_eq!(codec_of(&archive, "frontend/index.html"), Codec::Brotli);
+    assert_eq!(codec_of(&archive, "python/main.py"), Codec::Lz4);
+    assert_eq!(codec_of(&archive, "python_runtime.tar.gz"), Codec::Store);
+    assert_eq!(codec_of(&archive, "data/model.bin"), Codec::Zstd);
+    assert_eq!(
+        archive.entry("python_runtime.tar.gz").unwrap().length,
+        runtime.len() as u64
+    );
+
+    for (path, content) in &assets {
+        assert_eq!(&archive.read_asset(path).unwrap().unwrap(), content);
+        let mut streamed = Vec::new();
+        std::io::Read::read_to_end(
+            &mut archive.asset_reader(path).unwrap().unwrap(),
+            &mut streamed,
+        )
+        .unwrap();
+        assert_eq!(&streamed, content);
+    }
+
+    let read_data = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert_eq!(read_data.assets, assets);
+}
+
+#[test]
+fn test_overlay_metadata() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("index.html", b"<html></html>".to_vec());
+    data.set_metadata("git_commit", "4f2a9c1");
+    data.set_metadata("channel", "beta");
+    data.set_metadata("channel", "stable");
+    assert_eq!(data.metadata("channel"), Some("stable"));
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    let read_data = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert_eq!(read_data.metadata, data.metadata);
+    let archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(archive.metadata["git_commit"], "4f2a9c1");
+    drop(archive);
+
+    // Kept when the assets are patched
+    OverlayWriter::patch(
+        temp.path(),
+        vec![("app.js".to_string(), b"run()".to_vec())],
+        vec![],
+    )
+    .unwrap();
+    let read_data = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert_eq!(read_data.metadata("channel"), Some("stable"));
+
+    // Set on the streaming writer
+    let plain = NamedTempFile::new().unwrap();
+    std::fs::write(plain.path(), b"exe").unwrap();
+    let mut stream = OverlayWriter::begin(plain.path(), 3).unwrap();
+    stream.set_metadata("ci_run", "1234");
+    stream
+        .finish(&PackConfig::url("https://example.com"))
+        .unwrap();
+    let read_data = OverlayReader::read(plain.path()).unwrap().unwrap();
+    assert_eq!(read_data.metadata("ci_run"), Some("1234"));
+    assert_eq!(read_data.metadata("channel"), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_overlay_unix_attributes() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let src = tempfile::tempdir().unwrap();
+    let bin = src.path().join("tool");
+    std::fs::write(&bin, b"#!/bin/sh\necho hi\n").unwrap();
+    std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o750)).unwrap();
+    std::os::unix::fs::symlink("tool", src.path().join("tool-link")).unwrap();
+
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_file_asset("bin/tool", &bin).unwrap();
+    data.add_file_asset("bin/tool-link", &src.path().join("tool-link"))
+        .unwrap();
+    data.add_symlink("lib/libpython.so", "libpython3.11.so.1.0")
+        .unwrap();
+    data.add_asset("lib/libpython3.11.so.1.0", b"ELF".to_vec());
+    assert!(data.add_symlink("lib/escape", "../../etc/passwd").is_err());
+    assert!(data.add_symlink("lib/abs", "/etc/passwd").is_err());
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    let read_data = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert_eq!(read_data.attributes, data.attributes);
+    assert_eq!(read_data.content_hash, data.clone().get_content_hash());
+
+    let mut archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(
+        archive.entry("bin/tool").unwrap().attributes,
+        auroraview_pack::AssetAttributes::with_mode(0o750)
+    );
+    assert!(archive.entry("bin/tool-link").unwrap().attributes.symlink);
+
+    let out = tempfile::tempdir().unwrap();
+    assert_eq!(archive.extract_to(out.path()).unwrap(), 4);
+    let mode = std::fs::metadata(out.path().join("bin/tool"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o750);
+    assert_eq!(
+        std::fs::read_link(out.path().join("lib/libpython.so")).unwrap(),
+        std::path::PathBuf::from("libpython3.11.so.1.0")
+    );
+    assert_eq!(
+        std::fs::read(out.path().join("bin/tool-link")).unwrap(),
+        b"#!/bin/sh\necho hi\n"
+    );
+
+    // Changing only the mode changes the cache key
+    let mut changed = data.clone();
+    changed.set_mode("bin/tool", 0o755).unwrap();
+    assert_ne!(
+        changed.compute_content_hash(),
+        data.clone().get_content_hash()
+    );
+}
+
+#[test]
+fn test_overlay_streaming_writer_leaves_exe_untouched_on_error() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+
+    let mut stream = OverlayWriter::begin(temp.path(), 3).unwrap();
+    stream.append_asset("index.html", b"<html></html>").unwrap();
+    let missing = temp.path().with_extension("missing");
+    assert!(stream
+        .append_asset_from_file("python_runtime.tar.gz", &missing)
+        .is_err());
+    drop(stream);
+
+    assert!(!OverlayReader::has_overlay(temp.path()).unwrap());
+    assert_eq!(
+        std::fs::read(temp.path()).unwrap(),
+        b"fake executable content"
+    );
+}
+
+#[test]
+fn test_overlay_signature() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+
+    let signer = OverlaySigner::from_pkcs8(&OverlaySigner::generate_pkcs8().unwrap()).unwrap();
+    let mut data = OverlayData::new(PackConfig::url("https://example.com").with_title("Signed"));
+    data.add_asset("index.html", b"<html></html>".to_vec());
+    OverlayWriter::write_signed(temp.path(), &data, &signer).unwrap();
+
+    let public_key = signer.public_key_base64();
+    let signature = OverlayReader::verify(temp.path(), Some(&public_key))
+        .unwrap()
+        .expect("signed");
+    assert_eq!(signature.algorithm, "ed25519");
+    assert_eq!(signature.public_key, public_key);
+    assert_eq!(
+        OverlayReader::read(temp.path())
+            .unwrap()
+            .unwrap()
+            .config
+            .window
+            .title,
+        "Signed"
+    );
+    let archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(archive.signature, Some(signature));
+
+    // Another key is not trusted
+    let other = OverlaySigner::from_pkcs8(&OverlaySigner::generate_pkcs8().unwrap()).unwrap();
+    let err = OverlayReader::verify(temp.path(), Some(&other.public_key_base64())).unwrap_err();
+    assert!(matches!(err, PackError::Signature(_)), "{}", err);
+
+    // Unsigned overlays only pass without a trusted key
+    let unsigned = NamedTempFile::new().unwrap();
+    std::fs::write(unsigned.path(), b"fake executable content").unwrap();
+    OverlayWriter::write(unsigned.path(), &data).unwrap();
+    assert_eq!(OverlayReader::verify(unsigned.path(), None).unwrap(), None);
+    assert!(OverlayReader::verify(unsigned.path(), Some(&public_key)).is_err());
+}
+
+#[test]
+fn test_overlay_signature_detects_tampered_config() {
+    let temp = NamedTempFile::new().unwrap();
+    let exe = b"fake executable content";
+    std::fs::write(temp.path(), exe).unwrap();
+
+    let signer = OverlaySigner::from_pkcs8(&OverlaySigner::generate_pkcs8().unwrap()).unwrap();
+    let mut data = OverlayData::new(PackConfig::url("https://example.com").with_title("Signed"));
+    data.add_asset("index.html", b"<html></html>".to_vec());
+    OverlayWriter::write_signed(temp.path(), &data, &signer).unwrap();
+
+    // Point the app somewhere else, keeping the signature
+    let bytes = std::fs::read(temp.path()).unwrap();
+    let start = exe.len();
+    let config_len = u64::from_le_bytes(bytes[start + 8..start + 16].try_into().unwrap()) as usize;
+    let config_start = start + 24;
+    let config = zstd::decode_all(&bytes[config_start..config_start + config_len]).unwrap();
+    let config = String::from_utf8(config)
+        .unwrap()
+        .replace("https://example.com", "https://evil.example.com");
+    let config = zstd::encode_all(config.as_bytes(), 3).unwrap();
+
+    let mut tampered = bytes[..start + 8].to_vec();
+    tampered.extend_from_slice(&(config.len() as u64).to_le_bytes());
+    tampered.extend_from_slice(&bytes[start + 16..config_start]);
+    tampered.extend_from_slice(&config);
+    tampered.extend_from_slice(&bytes[config_start + config_len..]);
+    std::fs::write(temp.path(), &tampered).unwrap();
+
+    let err = OverlayReader::read(temp.path()).unwrap_err();
+    assert!(matches!(err, PackError::Signature(_)), "{}", err);
+    assert!(OverlayReader::open(temp.path()).is_err());
+    assert!(OverlayReader::verify(temp.path(), None).is_err());
+}
+
+#[test]
+fn test_overlay_deduplicates_identical_assets() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+
+    let dll = vec![9u8; 128 * 1024];
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("python/pkg_a/__init__.py", Vec::new());
+    data.add_asset("python/pkg_b/__init__.py", Vec::new());
+    data.add_asset("python/pkg_a/vcruntime140.dll", dll.clone());
+    data.add_asset("python/pkg_b/vcruntime140.dll", dll.clone());
+    let stats = data.dedup_stats();
+    assert_eq!(stats.duplicate_assets, 2);
+    assert_eq!(stats.saved_bytes, dll.len() as u64);
+
+    let mut unique = OverlayData::new(PackConfig::url("https://example.com"));
+    unique.add_asset("python/pkg_a/__init__.py", Vec::new());
+    unique.add_asset("python/pkg_a/vcruntime140.dll", dll.clone());
+    let unique_file = NamedTempFile::new().unwrap();
+    std::fs::write(unique_file.path(), b"fake executable content").unwrap();
+    OverlayWriter::write(unique_file.path(), &unique).unwrap();
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    // Duplicates only add index entries
+    let size = std::fs::metadata(temp.path()).unwrap().len();
+    let unique_size = std::fs::metadata(unique_file.path()).unwrap().len();
+    assert!(size < unique_size + 1024, "{} vs {}", size, unique_size);
+
+    let read_data = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert_eq!(read_data.assets, data.assets);
+    assert_eq!(read_data.content_hash, data.clone().compute_content_hash());
+
+    // Streamed assets are deduplicated against earlier ones too
+    let streamed = NamedTempFile::new().unwrap();
+    std::fs::write(streamed.path(), b"fake executable content").unwrap();
+    let mut stream = OverlayWriter::begin(streamed.path(), 3).unwrap();
+    stream.append_assets(&data.assets[..3]).unwrap();
+    stream
+        .append_asset_from_reader("python/pkg_b/vcruntime140.dll", &dll[..])
+        .unwrap();
+    stream.append_asset("python/extra.txt", b"extra").unwrap();
+    assert_eq!(stream.dedup_stats(), stats);
+    stream.finish(&data.config).unwrap();
+
+    let mut archive = OverlayReader::open(streamed.path()).unwrap().unwrap();
+    let a = archive
+        .entry("python/pkg_a/vcruntime140.dll")
+        .unwrap()
+        .clone();
+    let b = archive
+        .entry("python/pkg_b/vcruntime140.dll")
+        .unwrap()
+        .clone();
+    assert_eq!((a.offset, a.length), (b.offset, b.length));
+    assert_eq!(
+        archive.read_asset("python/extra.txt").unwrap().unwrap(),
+        b"extra"
+    );
+    assert_eq!(
+        archive
+            .read_asset("python/pkg_b/vcruntime140.dll")
+            .unwrap()
+            .unwrap(),
+        dll
+    );
+}
+
+#[test]
+fn test_overlay_patch_refuses_mapped_file() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("index.html", b"<html>old</html>".to_vec());
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    // Truncating the file would pull the pages from under the open archive
+    let archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    let patch = || {
+        OverlayWriter::patch(
+            temp.path(),
+            vec![("index.html".to_string(), b"<html>new</html>".to_vec())],
+            Vec::new(),
+        )
+    };
+    let err = patch().unwrap_err();
+    assert!(err.to_string().contains("in use"), "{}", err);
+    let mut content = Vec::new();
+    std::io::Read::read_to_end(
+        &mut archive.asset_reader("index.html").unwrap().unwrap(),
+        &mut content,
+    )
+    .unwrap();
+    assert_eq!(content, b"<html>old</html>");
+
+    drop(archive);
+    patch().unwrap();
+    let mut archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert_eq!(
+        archive.read_asset("index.html").unwrap().unwrap(),
+        b"<html>new</html>"
+    );
+}
+
+#[test]
+fn test_overlay_patch() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+
+    let runtime = vec![7u8; 256 * 1024];
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("index.html", b"<html>old</html>".to_vec());
+    data.add_asset("app.js", b"console.log('old')".to_vec());
+    data.add_asset("python_runtime.tar.gz", runtime.clone());
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    let hash = OverlayWriter::patch(
+        temp.path(),
+        vec![
+            ("index.html".to_string(), b"<html>new</html>".to_vec()),
+            ("style.css".to_string(), b"body {}".to_vec()),
+        ],
+        vec!["app.js".to_string()],
+    )
+    .unwrap();
+
+    // The executable is kept and the overlay is replaced, not appended
+    let bytes = std::fs::read(temp.path()).unwrap();
+    assert!(bytes.starts_with(b"fake executable content"));
+    assert_eq!(
+        OverlayReader::get_original_size(temp.path()).unwrap(),
+        Some(23)
+    );
+
+    let read_data = OverlayReader::read(temp.path()).unwrap().unwrap();
+    let mut expected = OverlayData::new(PackConfig::url("https://example.com"));
+    expected.add_asset("python_runtime.tar.gz", runtime);
+    expected.add_asset("index.html", b"<html>new</html>".to_vec());
+    expected.add_asset("style.css", b"body {}".to_vec());
+    assert_eq!(read_data.assets, expected.assets);
+    assert_eq!(hash, expected.compute_content_hash());
+    assert_eq!(read_data.content_hash, hash);
+
+    // Removing an asset the overlay does not have is an error
+    let result = OverlayWriter::patch(temp.path(), Vec::new(), vec!["missing.js".to_string()]);
+    assert!(matches!(result, Err(PackError::Config(_))));
+    assert_eq!(std::fs::read(temp.path()).unwrap(), bytes);
+}
+
+#[test]
+fn test_overlay_patch_signed() {
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"exe").unwrap();
+    let signer = OverlaySigner::from_pkcs8(&OverlaySigner::generate_pkcs8().unwrap()).unwrap();
+
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("index.html", b"old".to_vec());
+    OverlayWriter::write_signed(temp.path(), &data, &signer).unwrap();
+
+    let patch = || vec![("index.html".to_string(), b"new".to_vec())];
+    let result = OverlayWriter::patch(temp.path(), patch(), Vec::new());
+    assert!(matches!(result, Err(PackError::Signature(_))));
+
+    OverlayWriter::patch_signed(temp.path(), patch(), Vec::new(), &signer).unwrap();
+    let signature = OverlayReader::verify(temp.path(), Some(&signer.public_key_base64()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(signature.public_key, signer.public_key_base64());
+    let read_data = OverlayReader::read(temp.path()).unwrap().unwrap();
+    assert_eq!(
+        read_data.assets,
+        vec![("index.html".to_string(), b"new".to_vec())]
+    );
+}
+
+#[test]
+fn test_overlay_asset_reader() {
+    use std::io::Read;
+
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"fake executable content").unwrap();
+
+    let runtime: Vec<u8> = (0..2 * 1024 * 1024).map(|i| (i % 253) as u8).collect();
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("index.html", b"<html></html>".to_vec());
+    data.add_asset("python_runtime.tar.gz", runtime.clone());
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    let archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    assert!(archive.asset_reader("missing.txt").unwrap().is_none());
+
+    // Several readers at once, read in small chunks
+    let mut html = archive.asset_reader("index.html").unwrap().unwrap();
+    let mut reader = archive
+        .asset_reader("python_runtime.tar.gz")
+        .unwrap()
+        .unwrap();
+    assert_eq!(reader.size(), runtime.len() as u64);
+    let mut content = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = reader.read(&mut chunk).unwrap();
+        if n == 0 {
+            break;
+        }
+        content.extend_from_slice(&chunk[..n]);
+    }
+    assert_eq!(content, runtime);
+
+    let mut text = String::new();
+    html.read_to_string(&mut text).unwrap();
+    assert_eq!(text, "<html></html>");
+
+    // Content swapped for other valid data fails the hash check at the end
+    let temp = NamedTempFile::new().unwrap();
+    std::fs::write(temp.path(), b"exe").unwrap();
+    let mut data = OverlayData::new(PackConfig::url("https://example.com"));
+    data.add_asset("a.txt", b"first".to_vec());
+    data.add_asset("b.txt", b"other".to_vec());
+    OverlayWriter::write(temp.path(), &data).unwrap();
+
+    let archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    let (a, b) = (
+        archive.entry("a.txt").unwrap(),
+        archive.entry("b.txt").unwrap(),
+    );
+    assert_eq!(a.length, b.length);
+    let length = a.length as usize;
+    drop(archive);
+    let mut bytes = std::fs::read(temp.path()).unwrap();
+    let data_start = bytes.len() - 12 - 2 * length;
+    let (first, second) = bytes[data_start..data_start + 2 * length].split_at_mut(length);
+    first.swap_with_slice(second);
+    std::fs::write(temp.path(), &bytes).unwrap();
+
+    let archive = OverlayReader::open(temp.path()).unwrap().unwrap();
+    let err = archive
+        .asset_reader("a.txt")
+        .unwrap()
+        .unwrap()
+        .read_to_end(&mut Vec::new())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_overlay_parallel_file_assets_match_sequential() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let mut files = Vec::new();
+    for i in 0..12 {
+        let path = dir.path().join(format!("lib_{}.py", i));
+        // Every third file repeats an earlier content
+        let content: Vec<u8> = (0..16 * 1024).map(|j| ((j + i % 3) % 251) as u8).collect();
+        std::fs::write(&path, content).unwrap();
+        files.push((format!("python/lib_{}.py", i), path));
+    }
+    let files: Vec<(&str, &std::path::Path)> = files
+        .iter()
+        .map(|(path, file)| (path.as_str(), file.as_path()))
+        .collect();
+    let config = PackConfig::url("https://example.com");
+
+    let sequential = NamedTempFile::new().unwrap();
+    std::fs::write(sequential.path(), b"fake executable content").unwrap();
+    let mut stream = OverlayWriter::begin(sequential.path(), 3).unwrap();
+    for (path, file) in &files {
+        stream.append_asset_from_file(*path, file).unwrap();
+    }
+    let expected_hash = stream.finish(&config).unwrap();
+
+    let parallel = NamedTempFile::new().unwrap();
+    std::fs::write(parallel.path(), b"fake executable content").unwrap();
+    let mut stream = OverlayWriter::begin(parallel.path(), 3)
+        .unwrap()
+        .with_threads(4)
+        .unwrap();
+    let size = stream.append_assets_from_files(&files).unwrap();
+    assert_eq!(size, 12 * 16 * 1024);
+    assert_eq!(stream.dedup_stats().duplicate_assets, 9);
+    let hash = stream.finish(&config).unwrap();
+
+    // Same order, same stored data, regardless of threads
+    assert_eq!(hash, expected_hash);
+    assert_eq!(
+        std::fs::read(parallel.path()).unwrap(),
+        std::fs::read(sequential.path()).unwrap()
+    );
+
+    let err = OverlayWriter::begin(parallel.path(), 3)
+        .unwrap()
+        .append_assets_from_files(&[("missing.py", &dir.path().join("missing.py"))])
+        .err()
+        .unwrap();
+    assert!(matches!(err, PackError::AssetNotFound(_)), "{}", err);
+}
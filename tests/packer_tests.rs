@@ -1,6 +1,16 @@
 //! Tests for auroraview-pack packer module
 
-use auroraview_pack::{Manifest, PackConfig, Packer, VxConfig};
+use auroraview_pack::{
+    available_space, estimate_required_space, is_lock_error, lockfile_hash, locking_processes,
+    repack, AssetSource, BuildTarget, BundleStrategy, ByteProgress, CheckStatus, CleanScope,
+    CompareTo, DownloadEntry, FixedClock, FrontendDependencies, HistoryConfig, HookCommand,
+    HooksConfig, IncrementalConfig, IsolationConfig, IsolationEnv, LicensePolicy, Manifest,
+    OutputLayout, OverlayData, OverlayReader, OverlaySigner, OverlayWriter, PackConfig,
+    PackContext, PackError, PackHistory, PackProgressObserver, PackStage, PackStats,
+    PackageManager, Packer, PythonBundleConfig, ResumeConfig, RetryPolicy, RuntimeCache,
+    SymbolIndex, SymbolsConfig, SystemPythonConfig, TargetArch, TargetPlatform, ToolchainConfig,
+    VxConfig, WatchEvent, WatchOptions, LAUNCH_SCRIPT_CMD, LAUNCH_SCRIPT_SH, SYSTEM_PYTHON_ENV,
+};
 use std::fs;
 use tempfile::TempDir;
 
@@ -149,6 +159,36 @@ fn test_vx_ensure_missing_tool() {
     assert!(result.is_ok() || result.is_err());
 }
 
+#[test]
+fn test_vx_ensure_with_command_runner() {
+    use auroraview_pack::{MockOutcome, RecordingRunner};
+    use std::sync::Arc;
+
+    let mut config = PackConfig::url("https://example.com");
+    config.vx = Some(VxConfig {
+        enabled: true,
+        ensure: vec!["node@20".to_string(), "go".to_string()],
+        ..Default::default()
+    });
+
+    let runner = Arc::new(
+        RecordingRunner::new()
+            .on("node --version", MockOutcome::success("v20.11.1\n"))
+            .on("go", MockOutcome::NotFound),
+    );
+    let packer = Packer::new(config.clone()).with_command_runner(runner.clone());
+    let err = packer.validate_vx_ensure_requirements().unwrap_err();
+    assert!(err.to_string().contains("go tool required"), "{}", err);
+    assert_eq!(runner.command_lines(), ["node --version", "go version"]);
+
+    let runner = RecordingRunner::new().on("node --version", MockOutcome::success("v18.2.0\n"));
+    let err = Packer::new(config)
+        .with_command_runner(runner)
+        .validate_vx_ensure_requirements()
+        .unwrap_err();
+    assert!(err.to_string().contains("found 18.2.0"), "{}", err);
+}
+
 #[test]
 fn test_vx_runtime_injection() {
     let _temp = TempDir::new().unwrap();
@@ -200,3 +240,2345 @@ fn test_offline_mode() {
     // Clean up
     env::remove_var("AURORAVIEW_OFFLINE");
 }
+
+// ============================================================================
+// Clean Tests
+// ============================================================================
+
+#[test]
+fn test_clean_scope_parse() {
+    assert_eq!(CleanScope::parse("all"), Some(CleanScope::All));
+    assert_eq!(
+        CleanScope::parse("python_runtime"),
+        Some(CleanScope::PythonRuntime)
+    );
+    assert_eq!(CleanScope::parse("deps"), Some(CleanScope::Staging));
+    assert_eq!(CleanScope::parse("unknown"), None);
+    assert_eq!(CleanScope::All.expand().len(), CleanScope::ALL.len());
+    for scope in CleanScope::ALL {
+        assert_eq!(CleanScope::parse(scope.as_str()), Some(scope));
+    }
+}
+
+#[test]
+fn test_clean_dry_run_then_remove() {
+    let temp = TempDir::new().unwrap();
+    let vx_cache = temp.path().join("vx-cache");
+    fs::create_dir_all(&vx_cache).unwrap();
+    fs::write(vx_cache.join("artifact.zip"), vec![0u8; 1024]).unwrap();
+    let work_dir = temp.path().join(".pyoxidizer-build");
+    fs::create_dir_all(&work_dir).unwrap();
+    fs::write(work_dir.join("build.log"), vec![0u8; 512]).unwrap();
+
+    let mut config = PackConfig::url("https://example.com").with_output_dir(temp.path());
+    config.vx = Some(VxConfig {
+        cache_dir: vx_cache.clone(),
+        ..Default::default()
+    });
+    let packer = Packer::new(config);
+
+    // Dry run reports without deleting
+    let report = packer.clean(CleanScope::Vx, true).unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].scope, CleanScope::Vx);
+    assert_eq!(report.reclaimed(), 1024);
+    assert!(vx_cache.exists());
+
+    // Scopes are selective
+    let report = packer.clean(CleanScope::PyOxidizer, false).unwrap();
+    assert_eq!(report.reclaimed(), 512);
+    assert!(!work_dir.exists());
+    assert!(vx_cache.exists());
+
+    let report = packer.clean(CleanScope::Vx, false).unwrap();
+    assert!(!report.dry_run);
+    assert!(!vx_cache.exists());
+}
+
+#[test]
+fn test_vx_cache_dir_follows_context() {
+    let temp = TempDir::new().unwrap();
+    let mut config = PackConfig::url("https://example.com").with_output_dir(temp.path());
+    config.vx = Some(VxConfig::default());
+    let clean = |context: PackContext| {
+        Packer::new(config.clone())
+            .with_context(context)
+            .clean(CleanScope::Vx, true)
+            .unwrap()
+    };
+
+    // The default cache goes under the cache root, not the process cwd
+    let cache_root = temp.path().join("cache");
+    fs::create_dir_all(cache_root.join("vx")).unwrap();
+    let report = clean(PackContext::new(temp.path()).with_cache_root(&cache_root));
+    assert_eq!(report.entries[0].path, cache_root.join("vx"));
+
+    // Without a cache root it resolves against the base directory
+    fs::create_dir_all(temp.path().join(".pack-cache/vx")).unwrap();
+    let report = clean(PackContext::new(temp.path()));
+    assert_eq!(report.entries[0].path, temp.path().join("./.pack-cache/vx"));
+}
+
+#[test]
+fn test_clean_stale_runtime_caches() {
+    let temp = TempDir::new().unwrap();
+    let cache = RuntimeCache::new(temp.path().join("com.acme.viewer"));
+    for (hash, version) in [("aaaa", "1.0.0"), ("bbbb", "1.1.0"), ("cccc", "1.2.0")] {
+        let dir = cache.entry_dir(hash);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("python.bin"), vec![0u8; 256]).unwrap();
+        cache.record(hash, &dir, Some(version)).unwrap();
+    }
+    // Relaunching the oldest version makes it the most recently used
+    cache
+        .record("aaaa", &cache.entry_dir("aaaa"), Some("1.0.0"))
+        .unwrap();
+    // Directories from before the manifest are not touched
+    fs::create_dir_all(cache.root().join("python")).unwrap();
+
+    let stale = cache.stale_caches(2).unwrap();
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].overlay_hash, "bbbb");
+
+    let report = cache.clean_stale_caches(2).unwrap();
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.reclaimed(), 256);
+    assert!(!cache.entry_dir("bbbb").exists());
+    assert!(cache.entry_dir("aaaa").exists());
+    assert!(cache.root().join("python").exists());
+
+    let manifest = cache.manifest().unwrap();
+    let hashes: Vec<_> = manifest
+        .entries
+        .iter()
+        .map(|e| e.overlay_hash.as_str())
+        .collect();
+    assert_eq!(hashes, ["cccc", "aaaa"]);
+
+    // The most recent extraction is always kept
+    cache.clean_stale_caches(0).unwrap();
+    assert_eq!(cache.manifest().unwrap().entries.len(), 1);
+    assert!(cache.entry_dir("aaaa").exists());
+
+    // Only direct children of the cache root can be recorded
+    assert!(cache
+        .record("dddd", &temp.path().join("elsewhere"), None)
+        .is_err());
+}
+
+#[test]
+fn test_clean_stale_runtime_caches_skips_unsafe_entries() {
+    use auroraview_pack::{CacheEntry, CACHE_MANIFEST_FILE};
+
+    let temp = TempDir::new().unwrap();
+    let cache = RuntimeCache::new(temp.path().join("com.acme.viewer"));
+    for hash in ["aaaa", "bbbb", "cccc"] {
+        let dir = cache.entry_dir(hash);
+        fs::create_dir_all(&dir).unwrap();
+        cache.record(hash, &dir, None).unwrap();
+    }
+
+    // A tampered manifest pointing outside the cache removes nothing
+    let outside = temp.path().join("outside");
+    fs::create_dir_all(&outside).unwrap();
+    let mut manifest = cache.manifest().unwrap();
+    manifest.entries.insert(
+        0,
+        CacheEntry {
+            overlay_hash: "evil".to_string(),
+            dir: "../outside".to_string(),
+            version: None,
+            created: 0,
+            last_used: 0,
+        },
+    );
+    fs::write(
+        cache.root().join(CACHE_MANIFEST_FILE),
+        serde_json::to_vec(&manifest).unwrap(),
+    )
+    .unwrap();
+
+    // The cache of a running older instance is kept
+    let in_use = cache.lock_in_use(&cache.entry_dir("aaaa")).unwrap();
+    let report = cache.clean_stale_caches(1).unwrap();
+    assert_eq!(report.entries.len(), 1);
+    assert!(outside.exists());
+    assert!(cache.entry_dir("aaaa").exists());
+    assert!(!cache.entry_dir("bbbb").exists());
+    let hashes: Vec<_> = cache
+        .manifest()
+        .unwrap()
+        .entries
+        .into_iter()
+        .map(|e| e.overlay_hash)
+        .collect();
+    assert_eq!(hashes, ["aaaa", "cccc"]);
+
+    drop(in_use);
+    cache.clean_stale_caches(1).unwrap();
+    assert!(!cache.entry_dir("aaaa").exists());
+}
+
+#[test]
+fn test_asset_slots_rollback() {
+    use auroraview_pack::{AssetSlots, RollbackPolicy, Slot};
+
+    let temp = TempDir::new().unwrap();
+    let slots = AssetSlots::new(temp.path().join("slots"));
+    let policy = RollbackPolicy::default();
+    assert!(policy.validate().is_ok());
+
+    // First install runs from slot a
+    let launch = slots.launch(&policy).unwrap();
+    assert_eq!(launch.slot, Slot::A);
+    assert!(!launch.on_trial);
+    fs::create_dir_all(&launch.dir).unwrap();
+    fs::write(launch.dir.join("version.txt"), "1.0").unwrap();
+
+    // Stage and switch to an update
+    let staged = slots.begin_stage().unwrap();
+    assert_eq!(staged, slots.slot_dir(Slot::B));
+    fs::write(staged.join("version.txt"), "1.1").unwrap();
+    assert_eq!(
+        slots.commit_stage("hash-1.1", Some("1.1")).unwrap(),
+        Slot::B
+    );
+    assert!(slots.begin_stage().is_err());
+
+    // The first launch tries it; without a passed health check the next
+    // launch rolls back
+    let launch = slots.launch(&policy).unwrap();
+    assert_eq!(launch.slot, Slot::B);
+    assert!(launch.on_trial);
+    let launch = slots.launch(&policy).unwrap();
+    assert_eq!(launch.slot, Slot::A);
+    assert!(launch.rolled_back);
+    assert_eq!(
+        fs::read_to_string(launch.dir.join("version.txt")).unwrap(),
+        "1.0"
+    );
+    let pointer = slots.pointer().unwrap();
+    assert_eq!(pointer.rejected.as_deref(), Some("hash-1.1"));
+
+    // The rejected version is not switched to again; a fixed one is
+    let staged = slots.begin_stage().unwrap();
+    fs::write(staged.join("version.txt"), "1.1").unwrap();
+    assert!(slots.commit_stage("hash-1.1", Some("1.1")).is_err());
+    assert_eq!(
+        slots.commit_stage("hash-1.2", Some("1.2")).unwrap(),
+        Slot::B
+    );
+    assert!(slots.launch(&policy).unwrap().on_trial);
+
+    // A passed health check ends the trial and drops the old slot
+    let policy = RollbackPolicy {
+        keep_previous: false,
+        ..Default::default()
+    };
+    slots.confirm(&policy).unwrap();
+    let launch = slots.launch(&policy).unwrap();
+    assert_eq!(launch.slot, Slot::B);
+    assert!(!launch.on_trial);
+    assert!(!slots.slot_dir(Slot::A).exists());
+    assert!(slots.rollback().is_err());
+
+    let invalid = RollbackPolicy {
+        max_attempts: 0,
+        ..Default::default()
+    };
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn test_asset_slots_health_check_timeout() {
+    use auroraview_pack::{AssetSlots, RollbackPolicy, Slot, SlotPointer};
+
+    let temp = TempDir::new().unwrap();
+    let slots = AssetSlots::new(temp.path().join("slots"));
+    let policy = RollbackPolicy {
+        max_attempts: 5,
+        ..Default::default()
+    };
+
+    // An update on first install has nothing to roll back to
+    fs::write(slots.begin_stage().unwrap().join("version.txt"), "1.0").unwrap();
+    slots.commit_stage("hash-1.0", None).unwrap();
+    assert!(slots.rollback().is_err());
+    let launch = slots.launch(&policy).unwrap();
+    assert_eq!(launch.slot, Slot::B);
+    assert!(launch.on_trial);
+    let expired = |slots: &AssetSlots| {
+        let mut pointer = slots.pointer().unwrap();
+        let trial = pointer.trial.as_mut().unwrap();
+        trial.launched_at = Some(trial.launched_at.unwrap() - 60);
+        let path = slots.root().join(auroraview_pack::SLOT_POINTER_FILE);
+        fs::write(path, serde_json::to_vec(&pointer).unwrap()).unwrap();
+    };
+    expired(&slots);
+    let launch = slots.launch(&policy).unwrap();
+    assert_eq!(launch.slot, Slot::B);
+    assert!(!launch.on_trial && !launch.rolled_back);
+
+    // An update not confirmed within the timeout is rolled back, however
+    // few launches it had
+    fs::write(slots.begin_stage().unwrap().join("version.txt"), "1.1").unwrap();
+    slots.commit_stage("hash-1.1", None).unwrap();
+    assert!(slots.launch(&policy).unwrap().on_trial);
+    expired(&slots);
+    let launch = slots.launch(&policy).unwrap();
+    assert!(launch.rolled_back);
+    assert_eq!(launch.slot, Slot::B);
+    let pointer: SlotPointer = slots.pointer().unwrap();
+    assert_eq!(pointer.rejected.as_deref(), Some("hash-1.1"));
+}
+
+#[test]
+fn test_clean_staging_scope_uses_staging_dir() {
+    let staging = TempDir::new().unwrap();
+    let leftover = staging.path().join("auroraview-pack-123-abc-deps-XYZ");
+    fs::create_dir_all(&leftover).unwrap();
+    fs::write(leftover.join("file.py"), "x = 1").unwrap();
+    let unrelated = staging.path().join("other-tool");
+    fs::create_dir_all(&unrelated).unwrap();
+
+    let config = PackConfig::url("https://example.com").with_staging_dir(staging.path());
+    let report = Packer::new(config)
+        .clean(CleanScope::Staging, false)
+        .unwrap();
+
+    assert_eq!(report.entries.len(), 1);
+    assert!(!leftover.exists());
+    assert!(unrelated.exists());
+}
+
+#[test]
+fn test_clean_staging_scope_defaults_to_context_temp_root() {
+    let temp = TempDir::new().unwrap();
+    let leftover = temp.path().join("auroraview-pack-123-abc-deps-XYZ");
+    fs::create_dir_all(&leftover).unwrap();
+
+    let context = PackContext::new(temp.path()).with_temp_root(temp.path());
+    let report = Packer::new(PackConfig::url("https://example.com"))
+        .with_context(context)
+        .clean(CleanScope::Staging, true)
+        .unwrap();
+
+    assert_eq!(report.entries.len(), 1);
+    assert!(leftover.exists());
+}
+
+// ============================================================================
+// Staging & Disk Space Tests
+// ============================================================================
+
+#[test]
+fn test_packer_run_ids_are_unique() {
+    let a = Packer::new(PackConfig::url("https://example.com"));
+    let b = Packer::new(PackConfig::url("https://example.com"));
+    assert!(!a.run_id().is_empty());
+    assert_ne!(a.run_id(), b.run_id());
+}
+
+#[test]
+fn test_estimate_required_space_counts_inputs() {
+    let temp = TempDir::new().unwrap();
+    fs::write(temp.path().join("index.html"), vec![b'a'; 4096]).unwrap();
+
+    let context = PackContext::new(temp.path());
+    let url = estimate_required_space(&PackConfig::url("https://example.com"), &context);
+    let frontend = estimate_required_space(&PackConfig::frontend(temp.path()), &context);
+    assert_eq!(frontend.output, url.output + 4096);
+    assert_eq!(frontend.staging, 0);
+}
+
+#[test]
+fn test_available_space_for_missing_dir() {
+    let temp = TempDir::new().unwrap();
+    // Falls back to the nearest existing ancestor
+    let missing = temp.path().join("not").join("yet").join("created");
+    if let Some(space) = available_space(&missing) {
+        assert!(space > 0);
+    }
+}
+
+#[test]
+fn test_staging_dir_from_manifest() {
+    let toml = r#"
+[package]
+name = "test-app"
+
+[frontend]
+url = "https://example.com"
+
+[build]
+staging_dir = "build/staging"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config = PackConfig::from_manifest(&manifest, std::path::Path::new("/project")).unwrap();
+    assert_eq!(
+        config.staging_dir.as_deref(),
+        Some(std::path::Path::new("/project/build/staging"))
+    );
+}
+
+// ============================================================================
+// File Lock Retry Tests
+// ============================================================================
+
+#[cfg(unix)]
+#[test]
+fn test_retry_policy_retries_lock_errors() {
+    let policy = RetryPolicy {
+        attempts: 3,
+        initial_delay_ms: 1,
+        max_delay_ms: 1,
+    };
+    let mut calls = 0;
+    let result = policy.run("Writing", std::path::Path::new("app"), || {
+        calls += 1;
+        if calls < 3 {
+            // ETXTBSY
+            Err(PackError::Io(std::io::Error::from_raw_os_error(26)))
+        } else {
+            Ok(calls)
+        }
+    });
+    assert_eq!(result.unwrap(), 3);
+
+    calls = 0;
+    let result: Result<(), _> = policy.run("Writing", std::path::Path::new("app"), || {
+        calls += 1;
+        Err(PackError::Io(std::io::Error::from_raw_os_error(26)))
+    });
+    assert!(result.unwrap_err().to_string().contains("locked"));
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn test_retry_policy_skips_other_errors() {
+    let mut calls = 0;
+    let result: Result<(), _> =
+        RetryPolicy::default().run("Writing", std::path::Path::new("app"), || {
+            calls += 1;
+            Err(PackError::Config("bad".to_string()))
+        });
+    assert!(result.is_err());
+    assert_eq!(calls, 1);
+    assert!(!is_lock_error(&PackError::Config("bad".to_string())));
+}
+
+#[test]
+fn test_build_retry_from_manifest() {
+    let toml = r#"
+[package]
+name = "test-app"
+
+[frontend]
+url = "https://example.com"
+
+[build.retry]
+attempts = 10
+initial_delay_ms = 50
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config = PackConfig::from_manifest(&manifest, std::path::Path::new("/project")).unwrap();
+    assert_eq!(config.file_retry.attempts, 10);
+    assert_eq!(config.file_retry.initial_delay_ms, 50);
+    assert_eq!(config.file_retry.max_delay_ms, 2000);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_locking_processes_finds_running_exe() {
+    let exe = std::env::current_exe().unwrap();
+    let processes = locking_processes(&exe);
+    let pid = format!("pid {}", std::process::id());
+    assert!(processes.iter().any(|p| p.contains(&pid)));
+}
+
+// ============================================================================
+// Output Name & Path Validation Tests
+// ============================================================================
+
+#[test]
+fn test_pack_rejects_invalid_output_name() {
+    let temp = TempDir::new().unwrap();
+    let config = PackConfig::url("https://example.com")
+        .with_output("my:app")
+        .with_output_dir(temp.path());
+    let err = Packer::new(config).pack().unwrap_err().to_string();
+    assert!(err.contains("my-app"), "{}", err);
+}
+
+#[test]
+fn test_derived_output_name_is_sanitized() {
+    let config = PackConfig::url("http://localhost:3000");
+    assert_eq!(config.output_name, "localhost-3000");
+}
+
+#[test]
+fn test_pack_rejects_long_output_path() {
+    let temp = TempDir::new().unwrap();
+    let mut config = PackConfig::url("https://example.com")
+        .with_output("app")
+        .with_output_dir(temp.path().join("x".repeat(250)));
+    config.target_platform = TargetPlatform::Windows;
+    let err = Packer::new(config.clone()).pack().unwrap_err().to_string();
+    assert!(err.contains("Windows limit"), "{}", err);
+
+    // Only Windows has the limit
+    config.target_platform = TargetPlatform::Linux;
+    let err = Packer::new(config).pack().err().map(|e| e.to_string());
+    assert!(!err.is_some_and(|e| e.contains("Windows limit")));
+}
+
+#[test]
+fn test_sanitize_name_from_manifest() {
+    let toml = r#"
+[package]
+name = "My App: Pro"
+
+[frontend]
+url = "https://example.com"
+
+[build]
+sanitize_name = true
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config = PackConfig::from_manifest(&manifest, std::path::Path::new("/project")).unwrap();
+    assert_eq!(config.output_name, "My App- Pro");
+}
+
+// ============================================================================
+// Hook Command Tests
+// ============================================================================
+
+#[test]
+fn test_hooks_run_from_manifest() {
+    let toml = r#"
+[package]
+name = "test-app"
+
+[frontend]
+url = "https://example.com"
+
+[hooks]
+before_pack = ["python gen_version.py"]
+
+[[hooks.run]]
+program = "npm"
+args = ["run", "build", "--", "--out-dir", "My Output"]
+cwd = "web"
+
+[[hooks.run]]
+program = "echo"
+stage = "after_pack"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config = PackConfig::from_manifest(&manifest, std::path::Path::new("/project")).unwrap();
+    let hooks = config.hooks.unwrap();
+    assert_eq!(hooks.before_pack, vec!["python gen_version.py".to_string()]);
+    assert_eq!(hooks.run.len(), 2);
+    assert_eq!(hooks.run[0].args[4], "My Output");
+    assert_eq!(
+        hooks.run[0].cwd.as_deref(),
+        Some(std::path::Path::new("/project/web"))
+    );
+    assert_eq!(
+        hooks.run[1].stage,
+        auroraview_pack::DownloadStage::AfterPack
+    );
+    assert_eq!(
+        hooks.run[0].display(),
+        r#"npm run build -- --out-dir "My Output""#
+    );
+}
+
+#[test]
+fn test_hooks_run_accepts_before_pack_stage() {
+    let hook =
+        HookCommand::new("echo", ["hi"]).with_stage(auroraview_pack::DownloadStage::BeforePack);
+    assert!(hook.validate().is_ok());
+    assert!(HookCommand::new(" ", Vec::<String>::new())
+        .validate()
+        .is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_pass_arguments_verbatim() {
+    let temp = TempDir::new().unwrap();
+    let out_dir = temp.path().join("out dir");
+    let argv_file = temp.path().join("argv ü.txt");
+    let shell_file = temp.path().join("shell 'q'.txt");
+
+    let hooks = HooksConfig {
+        before_collect: vec![format!(
+            "printf '%s' \"a  b\" > \"{}\"",
+            shell_file.display()
+        )],
+        run: vec![HookCommand::new(
+            "sh",
+            [
+                "-c".to_string(),
+                "printf '%s' \"$1\" > \"$2\"".to_string(),
+                "sh".to_string(),
+                "it's \"quoted\" ü".to_string(),
+                argv_file.display().to_string(),
+            ],
+        )],
+        ..Default::default()
+    };
+    let mut config = PackConfig::url("https://example.com")
+        .with_output("hook-app")
+        .with_output_dir(&out_dir);
+    config.hooks = Some(hooks);
+
+    Packer::new(config).pack().unwrap();
+    assert_eq!(fs::read_to_string(&argv_file).unwrap(), "it's \"quoted\" ü");
+    assert_eq!(fs::read_to_string(&shell_file).unwrap(), "a  b");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_before_pack_hooks_run_after_before_collect() {
+    let temp = TempDir::new().unwrap();
+    let log = temp.path().join("stages.log");
+    let hooks = HooksConfig {
+        before_collect: vec![format!("echo collect >> \"{}\"", log.display())],
+        before_pack: vec![format!("echo pack >> \"{}\"", log.display())],
+        after_pack: vec![format!("echo after >> \"{}\"", log.display())],
+        ..Default::default()
+    };
+    pack_with_hooks(hooks).unwrap();
+    assert_eq!(fs::read_to_string(&log).unwrap(), "collect\npack\nafter\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_substitute_build_variables() {
+    let temp = TempDir::new().unwrap();
+    let out_dir = temp.path().join("out dir");
+    let shell_file = temp.path().join("shell.txt");
+    let argv_file = temp.path().join("argv.txt");
+    let mut argv_hook = HookCommand::new(
+        "sh",
+        [
+            "-c".to_string(),
+            "printf '%s|%s' \"$1\" \"$OUT\" > \"$2\"".to_string(),
+            "sh".to_string(),
+            "${OUTPUT_DIR}".to_string(),
+            argv_file.display().to_string(),
+        ],
+    )
+    .with_stage(auroraview_pack::DownloadStage::AfterPack);
+    argv_hook
+        .env
+        .insert("OUT".to_string(), "${OUTPUT_EXE}".to_string());
+    let hooks = HooksConfig {
+        after_pack: vec![format!(
+            "test -f \"${{OUTPUT_EXE}}\" && echo \"${{PACKAGE_NAME}} ${{VERSION}} ${{TARGET}}\" > \"{}\"",
+            shell_file.display()
+        )],
+        run: vec![argv_hook],
+        ..Default::default()
+    };
+    let config = PackConfig::url("https://example.com")
+        .with_output("hook-app")
+        .with_output_dir(&out_dir)
+        .with_app_version("1.2.3")
+        .with_hooks(hooks);
+
+    let output = Packer::new(config).pack().unwrap();
+    let target = BuildTarget::current().unwrap().to_string();
+    assert_eq!(
+        fs::read_to_string(&shell_file).unwrap().trim(),
+        format!("hook-app 1.2.3 {}", target)
+    );
+    assert_eq!(
+        fs::read_to_string(&argv_file).unwrap(),
+        format!("{}|{}", out_dir.display(), output.executable.display())
+    );
+}
+
+#[cfg(unix)]
+#[cfg(unix)]
+#[test]
+fn test_failed_pack_resumes_from_failed_stage() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+    let log = temp.path().join("stages.log");
+    let ready = temp.path().join("signing-ready");
+    let hooks = HooksConfig {
+        before_collect: vec![format!("echo collect >> \"{}\"", log.display())],
+        after_pack: vec![format!("test -f \"{}\"", ready.display())],
+        ..Default::default()
+    };
+    let config = PackConfig::frontend(&frontend)
+        .with_output("resume-app")
+        .with_output_dir(temp.path().join("out"))
+        .with_hooks(hooks)
+        .with_resume(ResumeConfig {
+            dir: Some(temp.path().join("resume")),
+            ..Default::default()
+        });
+    let runs = || fs::read_to_string(&log).unwrap().lines().count();
+
+    // after_pack fails: prepare and pack are checkpointed
+    assert!(Packer::new(config.clone()).pack().is_err());
+    assert_eq!(runs(), 1);
+
+    fs::write(&ready, "").unwrap();
+    let observer = RecordingObserver::default();
+    let events = observer.0.clone();
+    let output = Packer::new(config.clone())
+        .with_progress(observer)
+        .pack()
+        .unwrap();
+    assert_eq!(output.resumed_from, Some(PackStage::AfterPack));
+    assert_eq!(output.asset_count, 1);
+    assert!(output.stage_timings.contains_key("pack"));
+    assert!(!output.assets.is_empty());
+    assert_eq!(runs(), 1, "prepare must not run again");
+    let events = events.lock().unwrap();
+    assert!(
+        events.iter().any(|e| e == "resumed prepare"),
+        "{:?}",
+        events
+    );
+    assert!(events.iter().any(|e| e == "resumed pack"), "{:?}", events);
+    assert!(!events.iter().any(|e| e == "start pack"), "{:?}", events);
+    assert!(
+        !events.iter().any(|e| e.starts_with("file ")),
+        "{:?}",
+        events
+    );
+
+    // A successful run removes its checkpoint
+    let output = Packer::new(config.clone()).pack().unwrap();
+    assert_eq!(output.resumed_from, None);
+    assert_eq!(runs(), 2);
+
+    // Changed inputs start over
+    fs::remove_file(&ready).unwrap();
+    assert!(Packer::new(config.clone()).pack().is_err());
+    fs::write(frontend.join("index.html"), "<html>v2</html>").unwrap();
+    fs::write(&ready, "").unwrap();
+    let output = Packer::new(config).pack().unwrap();
+    assert_eq!(output.resumed_from, None);
+    assert_eq!(runs(), 4);
+}
+
+#[test]
+fn test_unchanged_pack_returns_previous_output() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html>v1</html>").unwrap();
+    let config = PackConfig::frontend(&frontend)
+        .with_output("unchanged-app")
+        .with_output_dir(temp.path().join("out"))
+        .with_skip_unchanged(true)
+        .with_resume(ResumeConfig {
+            dir: Some(temp.path().join("state")),
+            ..Default::default()
+        });
+    let pack = |force: bool| {
+        Packer::new(config.clone())
+            .with_force(force)
+            .pack()
+            .unwrap()
+    };
+
+    let first = pack(false);
+    assert!(!first.up_to_date);
+
+    let observer = RecordingObserver::default();
+    let events = observer.0.clone();
+    let second = Packer::new(config.clone())
+        .with_progress(observer)
+        .pack()
+        .unwrap();
+    assert!(second.up_to_date);
+    assert_eq!(second.executable, first.executable);
+    assert_eq!(second.size, first.size);
+    assert_eq!(second.asset_count, 1);
+    // The build report of the recorded run comes back with it
+    assert_eq!(second.assets, first.assets);
+    assert_eq!(second.dedup, first.dedup);
+    assert_eq!(second.tools, first.tools);
+    assert_eq!(second.downloads, first.downloads);
+    assert_eq!(second.stage_timings["pack"], first.stage_timings["pack"]);
+    assert!(!second.assets.is_empty());
+    let events = events.lock().unwrap();
+    assert!(!events.iter().any(|e| e == "start pack"), "{:?}", events);
+    assert!(
+        events.iter().any(|e| e == "resumed after_pack"),
+        "{:?}",
+        events
+    );
+
+    // Forced, or with a changed input (same size), everything is packed
+    assert!(!pack(true).up_to_date);
+    assert!(pack(false).up_to_date);
+    fs::write(frontend.join("index.html"), "<html>v2</html>").unwrap();
+    assert!(!pack(false).up_to_date);
+
+    // A touched executable is packed again
+    assert!(pack(false).up_to_date);
+    fs::write(&first.executable, "damaged").unwrap();
+    let repacked = pack(false);
+    assert!(!repacked.up_to_date);
+    assert!(fs::metadata(&repacked.executable).unwrap().len() > 1024);
+}
+
+#[test]
+fn test_watch_repacks_on_change() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html>v1</html>").unwrap();
+    let config = PackConfig::frontend(&frontend)
+        .with_output("watch-app")
+        .with_output_dir(temp.path().join("out"))
+        .with_incremental(IncrementalConfig {
+            dir: Some(temp.path().join("build-cache")),
+            ..Default::default()
+        });
+    let options = WatchOptions {
+        poll_interval: std::time::Duration::from_millis(20),
+        debounce: std::time::Duration::from_millis(20),
+    };
+
+    let mut events = Vec::new();
+    Packer::new(config)
+        .watch(&options, |event| {
+            let (label, done) = match event {
+                WatchEvent::Packed { ref changed, .. } if changed.is_empty() => {
+                    let page = frontend.join("app.js");
+                    std::thread::spawn(move || {
+                        std::thread::sleep(std::time::Duration::from_millis(100));
+                        fs::write(page, "console.log(2)").unwrap();
+                    });
+                    ("initial".to_string(), false)
+                }
+                WatchEvent::Changed { ref paths } => (format!("changed {:?}", paths), false),
+                WatchEvent::Packed {
+                    output, changed, ..
+                } => (
+                    format!("repacked {} {:?}", output.asset_count, changed),
+                    true,
+                ),
+                WatchEvent::Failed { error, .. } => panic!("pack failed: {}", error),
+            };
+            events.push(label);
+            match done {
+                true => std::ops::ControlFlow::Break(()),
+                false => std::ops::ControlFlow::Continue(()),
+            }
+        })
+        .unwrap();
+
+    let changed = vec![frontend.join("app.js")];
+    assert_eq!(
+        events,
+        [
+            "initial".to_string(),
+            format!("changed {:?}", changed),
+            format!("repacked 2 {:?}", changed),
+        ]
+    );
+}
+
+#[test]
+fn test_repack_applies_window_env_and_license() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html>app</html>").unwrap();
+    fs::write(temp.path().join("EULA.txt"), "Terms v1").unwrap();
+    let output = Packer::new(
+        PackConfig::frontend(&frontend)
+            .with_output("repack-app")
+            .with_output_dir(temp.path().join("out"))
+            .with_title("Before"),
+    )
+    .pack()
+    .unwrap();
+    let before = OverlayReader::read(&output.executable).unwrap().unwrap();
+
+    let manifest = Manifest::parse(
+        r#"
+[package]
+name = "repack-app"
+title = "After"
+
+[frontend]
+path = "./dist"
+
+[window]
+width = 800
+height = 600
+
+[runtime.env]
+APP_MODE = "release"
+
+[license.eula]
+path = "./EULA.txt"
+"#,
+    )
+    .unwrap();
+    let report = repack(&output.executable, &manifest, temp.path(), None).unwrap();
+    assert_eq!(report.changed, ["window", "env", "license"]);
+
+    let after = OverlayReader::read(&output.executable).unwrap().unwrap();
+    assert_eq!(after.config.window.title, "After");
+    assert_eq!(after.config.window.width, 800);
+    assert_eq!(after.config.env["APP_MODE"], "release");
+    assert_eq!(after.content_hash, report.content_hash);
+    assert_eq!(after.assets.len(), before.assets.len() + 1);
+    let eula = after
+        .config
+        .license
+        .as_ref()
+        .unwrap()
+        .eula
+        .as_ref()
+        .unwrap();
+    let text = after
+        .assets
+        .iter()
+        .find(|(path, _)| path.as_str() == eula.path.to_string_lossy());
+    assert_eq!(text.unwrap().1, b"Terms v1");
+    assert!(before
+        .assets
+        .iter()
+        .all(|asset| after.assets.contains(asset)));
+
+    // Unchanged settings are left alone; a new EULA replaces the old one
+    let again = repack(&output.executable, &manifest, temp.path(), None).unwrap();
+    assert!(again.changed.is_empty());
+    assert_eq!(again.content_hash, report.content_hash);
+    fs::write(temp.path().join("EULA.txt"), "Terms v2").unwrap();
+    let updated = repack(&output.executable, &manifest, temp.path(), None).unwrap();
+    assert_eq!(updated.changed, ["license"]);
+    let after = OverlayReader::read(&output.executable).unwrap().unwrap();
+    assert_eq!(after.assets.len(), before.assets.len() + 1);
+}
+
+#[test]
+fn test_repack_signed_overlay_requires_signer() {
+    let temp = TempDir::new().unwrap();
+    let exe = temp.path().join("app.exe");
+    fs::write(&exe, b"fake executable content").unwrap();
+    let signer = OverlaySigner::from_pkcs8(&OverlaySigner::generate_pkcs8().unwrap()).unwrap();
+    let data = OverlayData::new(PackConfig::url("https://example.com"));
+    OverlayWriter::write_signed(&exe, &data, &signer).unwrap();
+    let manifest = Manifest::parse(
+        r#"
+[package]
+name = "signed-app"
+title = "Signed"
+
+[frontend]
+url = "https://example.com"
+"#,
+    )
+    .unwrap();
+
+    let err = repack(&exe, &manifest, temp.path(), None).unwrap_err();
+    assert!(matches!(err, PackError::Signature(_)), "{}", err);
+    let report = repack(&exe, &manifest, temp.path(), Some(&signer)).unwrap();
+    assert_eq!(report.changed, ["window"]);
+    let public_key = signer.public_key_base64();
+    assert!(OverlayReader::verify(&exe, Some(&public_key))
+        .unwrap()
+        .is_some());
+    let archive = OverlayReader::read(&exe).unwrap().unwrap();
+    assert_eq!(archive.config.window.title, "Signed");
+}
+
+fn pack_with_hooks(hooks: HooksConfig) -> Result<(), PackError> {
+    let temp = TempDir::new().unwrap();
+    let mut config = PackConfig::url("https://example.com")
+        .with_output("hook-app")
+        .with_output_dir(temp.path());
+    config.hooks = Some(hooks);
+    Packer::new(config).pack().map(|_| ())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hook_idle_timeout_kills_silent_hook() {
+    let started = std::time::Instant::now();
+    let err = pack_with_hooks(HooksConfig {
+        before_collect: vec!["echo started; sleep 30".to_string()],
+        idle_timeout_secs: Some(1),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert!(matches!(err, PackError::HookTimeout(_)));
+    let message = err.to_string();
+    assert!(message.contains("no output for 1s"), "{}", message);
+    assert!(message.contains("started"), "{}", message);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hook_timeout_overrides_per_command() {
+    let started = std::time::Instant::now();
+    let err = pack_with_hooks(HooksConfig {
+        run: vec![
+            HookCommand::new("sh", ["-c", "while true; do echo tick; sleep 0.2; done"])
+                .with_timeout(1),
+        ],
+        idle_timeout_secs: Some(60),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert!(
+        err.to_string().contains("exceeded timeout of 1s"),
+        "{}",
+        err
+    );
+    assert!(err.to_string().contains("tick"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hook_timeout_kills_hook_printing_without_pause() {
+    let started = std::time::Instant::now();
+    let err = pack_with_hooks(HooksConfig {
+        before_collect: vec!["while true; do echo spam; done".to_string()],
+        timeout_secs: Some(1),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert!(matches!(err, PackError::HookTimeout(_)));
+    assert!(
+        err.to_string().contains("exceeded timeout of 1s"),
+        "{}",
+        err
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hook_failure_includes_output() {
+    let err = pack_with_hooks(HooksConfig {
+        before_collect: vec!["echo 'npm ERR! missing script' >&2; exit 3".to_string()],
+        ..Default::default()
+    })
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("exit code Some(3)"), "{}", err);
+    assert!(err.contains("npm ERR! missing script"), "{}", err);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_isolated_hooks_get_controlled_environment() {
+    let temp = TempDir::new().unwrap();
+    let env_file = temp.path().join("env.txt");
+    let tools = temp.path().join("tools");
+    fs::create_dir_all(&tools).unwrap();
+
+    let isolation = IsolationConfig {
+        extra_path: vec![tools.display().to_string()],
+        clear_env: vec!["LANG".to_string()],
+        ..IsolationConfig::full()
+    };
+    pack_with_hooks(HooksConfig {
+        before_collect: vec![format!("env > \"{}\"", env_file.display())],
+        isolation: Some(isolation.clone()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let env = fs::read_to_string(&env_file).unwrap();
+    let path = env
+        .lines()
+        .find_map(|l| l.strip_prefix("PATH="))
+        .unwrap()
+        .to_string();
+    let expected: Vec<String> = isolation
+        .extra_path
+        .iter()
+        .chain(&isolation.system_path)
+        .cloned()
+        .collect();
+    assert_eq!(path, expected.join(":"));
+    // Variables outside inherit_env (cargo's own) do not leak into hooks
+    assert!(!env.contains("CARGO_PKG_NAME="), "{}", env);
+    assert!(!env.lines().any(|l| l.starts_with("LANG=")), "{}", env);
+}
+
+#[test]
+fn test_hook_timeouts_from_manifest() {
+    let toml = r#"
+[package]
+name = "test-app"
+
+[frontend]
+url = "https://example.com"
+
+[hooks]
+timeout_secs = 1800
+idle_timeout_secs = 300
+heartbeat_secs = 0
+
+[[hooks.run]]
+program = "npm"
+args = ["run", "build"]
+timeout_secs = 600
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config = PackConfig::from_manifest(&manifest, std::path::Path::new("/project")).unwrap();
+    let hooks = config.hooks.unwrap();
+    assert_eq!(hooks.timeout_secs, Some(1800));
+    assert_eq!(hooks.idle_timeout_secs, Some(300));
+    assert_eq!(hooks.heartbeat_secs, Some(0));
+    assert_eq!(hooks.run[0].timeout_secs, Some(600));
+    assert_eq!(hooks.run[0].idle_timeout_secs, None);
+}
+
+// ============================================================================
+// Frontend Dependencies Tests
+// ============================================================================
+
+fn frontend_project(lockfile: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    fs::write(temp.path().join("package.json"), r#"{"name": "web"}"#).unwrap();
+    fs::write(temp.path().join("package-lock.json"), lockfile).unwrap();
+    temp
+}
+
+fn pack_with_dependencies(deps: FrontendDependencies) -> Result<(), PackError> {
+    let out = TempDir::new().unwrap();
+    let config = PackConfig::url("https://example.com")
+        .with_output("deps-app")
+        .with_output_dir(out.path())
+        .with_frontend_dependencies(deps);
+    Packer::new(config).pack().map(|_| ())
+}
+
+#[test]
+fn test_frontend_dependencies_from_manifest() {
+    let toml = r#"
+[package]
+name = "test-app"
+
+[frontend]
+url = "https://example.com"
+
+[frontend.dependencies]
+dir = "web"
+manager = "pnpm"
+script = "build"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config = PackConfig::from_manifest(&manifest, std::path::Path::new("/project")).unwrap();
+    let deps = config.frontend_dependencies.unwrap();
+    assert_eq!(
+        deps.dir.as_deref(),
+        Some(std::path::Path::new("/project/web"))
+    );
+    assert_eq!(deps.manager, Some(PackageManager::Pnpm));
+    assert!(deps.frozen_lockfile);
+    assert!(deps.cache);
+    assert_eq!(deps.script.as_deref(), Some("build"));
+}
+
+#[test]
+fn test_frontend_dependencies_skip_when_stamp_matches() {
+    let project = frontend_project("{}");
+    let hash = lockfile_hash(&project.path().join("package-lock.json")).unwrap();
+    fs::create_dir_all(project.path().join("node_modules")).unwrap();
+    fs::write(
+        project.path().join("node_modules/.auroraview-lock-hash"),
+        format!("npm-{}", hash),
+    )
+    .unwrap();
+
+    // No package manager is run, so this succeeds without npm installed
+    pack_with_dependencies(FrontendDependencies {
+        dir: Some(project.path().to_path_buf()),
+        lockfile_sha256: Some(hash),
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_frontend_dependencies_lockfile_hash_mismatch() {
+    let project = frontend_project("{\"lockfileVersion\": 3}");
+    let err = pack_with_dependencies(FrontendDependencies {
+        dir: Some(project.path().to_path_buf()),
+        lockfile_sha256: Some("0".repeat(64)),
+        ..Default::default()
+    })
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("has changed"), "{}", err);
+}
+
+#[test]
+fn test_frontend_dependencies_requires_lockfile() {
+    let project = frontend_project("{}");
+    fs::remove_file(project.path().join("package-lock.json")).unwrap();
+    let err = pack_with_dependencies(FrontendDependencies {
+        dir: Some(project.path().to_path_buf()),
+        ..Default::default()
+    })
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("package-lock.json"), "{}", err);
+
+    assert_eq!(
+        PackageManager::detect(project.path()),
+        None,
+        "no lockfile, nothing detected"
+    );
+    fs::write(project.path().join("yarn.lock"), "").unwrap();
+    assert_eq!(
+        PackageManager::detect(project.path()),
+        Some(PackageManager::Yarn)
+    );
+}
+
+#[test]
+fn test_frontend_dependencies_validate() {
+    let deps = FrontendDependencies {
+        lockfile_sha256: Some("abc".to_string()),
+        ..Default::default()
+    };
+    assert!(deps.validate().is_err());
+}
+
+// ============================================================================
+// Toolchain Provisioning Tests
+// ============================================================================
+
+#[test]
+fn test_toolchains_from_manifest() {
+    let toml = r#"
+[package]
+name = "test-app"
+
+[frontend]
+url = "https://example.com"
+
+[backend.go.toolchain]
+version = "1.22.5"
+
+[backend.rust.toolchain]
+version = "stable"
+"#;
+    let manifest = Manifest::parse(toml).unwrap();
+    let config = PackConfig::from_manifest(&manifest, std::path::Path::new("/project")).unwrap();
+    assert_eq!(config.go_toolchain, Some(ToolchainConfig::new("1.22.5")));
+    assert_eq!(config.rust_toolchain, Some(ToolchainConfig::new("stable")));
+}
+
+#[test]
+fn test_pack_rejects_invalid_toolchain() {
+    let temp = TempDir::new().unwrap();
+    let config = PackConfig::url("https://example.com")
+        .with_output_dir(temp.path())
+        .with_go_toolchain(ToolchainConfig {
+            sha256: Some("not-a-hash".to_string()),
+            ..ToolchainConfig::new("1.22.5")
+        });
+    let err = Packer::new(config).pack().unwrap_err().to_string();
+    assert!(err.contains("backend.go.toolchain"), "{}", err);
+}
+
+// ============================================================================
+// Environment Archive Tests
+// ============================================================================
+
+#[test]
+fn test_env_archive_requires_standalone_strategy() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+    let archive = temp.path().join("env.tar.gz");
+    fs::write(&archive, b"").unwrap();
+
+    let python = PythonBundleConfig {
+        strategy: BundleStrategy::Embedded,
+        ..PythonBundleConfig::new("main:run").with_env_archive(&archive)
+    };
+    let config = PackConfig::fullstack_with_config(&frontend, python).with_output_dir(temp.path());
+    let err = Packer::new(config).pack().unwrap_err().to_string();
+    assert!(err.contains("env_archive requires strategy"), "{}", err);
+
+    let missing = PythonBundleConfig::new("main:run").with_env_archive(temp.path().join("nope"));
+    let config = PackConfig::fullstack_with_config(&frontend, missing).with_output_dir(temp.path());
+    let err = Packer::new(config).pack().unwrap_err();
+    assert!(matches!(err, PackError::AssetNotFound(_)), "{}", err);
+}
+
+#[test]
+fn test_license_policy_rejects_strategies_without_package_metadata() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+
+    let policy = LicensePolicy {
+        denied: vec!["GPL-*".to_string()],
+        ..Default::default()
+    };
+    fs::write(temp.path().join("main.py"), "def run(): pass\n").unwrap();
+    let python = PythonBundleConfig {
+        strategy: BundleStrategy::PyOxidizer,
+        packages: vec!["requests".to_string()],
+        include_paths: vec![temp.path().to_path_buf()],
+        ..PythonBundleConfig::new("main:run")
+    };
+    let packer = |python: PythonBundleConfig| {
+        Packer::new(
+            PackConfig::fullstack_with_config(&frontend, python)
+                .with_output_dir(temp.path())
+                .with_license_policy(policy.clone()),
+        )
+    };
+    let err = packer(python.clone()).plan().unwrap_err();
+    assert!(matches!(err, PackError::LicensePolicy(_)), "{}", err);
+    assert!(
+        err.to_string().contains("strategy = \"pyoxidizer\""),
+        "{}",
+        err
+    );
+
+    // Nothing to check without third-party packages
+    let config = packer(PythonBundleConfig {
+        packages: Vec::new(),
+        ..python
+    });
+    config.plan().unwrap();
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[test]
+fn test_portable_strategy_embeds_python_runtime() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+
+    // A distribution archive whose interpreter lost its exec bits
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::fast(),
+    ));
+    for (path, content) in [
+        ("python/bin/python3", &b"#!/bin/sh\n"[..]),
+        ("python/lib/python3.11/os.py", &b""[..]),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content).unwrap();
+    }
+    let archive = builder.into_inner().unwrap().finish().unwrap();
+
+    // Seed the artifact store so nothing is downloaded
+    let store_dir = temp.path().join("store");
+    let store = auroraview_pack::ArtifactStore::in_dir(&store_dir);
+    let name = "cpython-3.11-x86_64-unknown-linux-gnu.tar.gz";
+    let digest = store.insert(name, &archive).unwrap();
+    store
+        .link("python", name, auroraview_pack::ObjectKind::Blob, &digest)
+        .unwrap();
+
+    let source = temp.path().join("app");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("main.py"), "def run():\n    pass\n").unwrap();
+    let python = PythonBundleConfig {
+        strategy: BundleStrategy::Portable,
+        include_paths: vec![source],
+        ..PythonBundleConfig::new("main:run")
+    };
+    let config = PackConfig::fullstack_with_config(&frontend, python)
+        .with_output("portable-app")
+        .with_output_dir(temp.path())
+        .with_store(auroraview_pack::StoreConfig {
+            dir: Some(store_dir),
+            ..Default::default()
+        });
+    Packer::new(config).pack().unwrap();
+
+    let app_dir = temp.path().join("portable-app");
+    let interpreter = app_dir.join("python/bin/python3");
+    assert!(app_dir.join("python/lib/python3.11/os.py").is_file());
+    assert_eq!(
+        fs::metadata(&interpreter).unwrap().permissions().mode() & 0o111,
+        0o111
+    );
+    assert!(app_dir.join("lib").is_dir());
+
+    let contract: IsolationEnv = serde_json::from_slice(
+        &fs::read(app_dir.join(auroraview_pack::ISOLATION_ENV_FILE)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        contract.path.first().map(String::as_str),
+        Some("python/bin")
+    );
+}
+
+#[test]
+fn test_system_strategy_writes_isolation_contract() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+
+    let python = PythonBundleConfig {
+        strategy: BundleStrategy::System,
+        isolation: IsolationConfig {
+            extra_path: vec!["tools/bin".to_string()],
+            extra_pythonpath: vec!["plugins".to_string()],
+            system_path: vec!["/usr/bin".to_string()],
+            clear_env: vec!["PYTHONHOME".to_string()],
+            ..IsolationConfig::full()
+        },
+        ..PythonBundleConfig::new("main:run")
+    };
+    let config = PackConfig::fullstack_with_config(&frontend, python)
+        .with_output("iso-app")
+        .with_output_dir(temp.path());
+    Packer::new(config).pack().unwrap();
+
+    let app_dir = temp.path().join("iso-app");
+    let contract: IsolationEnv = serde_json::from_slice(
+        &fs::read(app_dir.join(auroraview_pack::ISOLATION_ENV_FILE)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(contract.strategy, BundleStrategy::System);
+    assert_eq!(contract.pythonpath, ["backend", "plugins"]);
+    assert_eq!(contract.path, ["tools/bin", "/usr/bin"]);
+
+    let env_sh = fs::read_to_string(app_dir.join("env.sh")).unwrap();
+    assert!(
+        env_sh.contains("export PYTHONPATH=\"$APP_DIR/backend:$APP_DIR/plugins\""),
+        "{}",
+        env_sh
+    );
+    assert!(env_sh.contains("unset \"PYTHONHOME\""));
+    let env_cmd = fs::read_to_string(app_dir.join("env.cmd")).unwrap();
+    assert!(env_cmd.contains("%APP_DIR%\\tools\\bin"), "{}", env_cmd);
+}
+
+#[test]
+fn test_isolation_scripts_escape_entries() {
+    let temp = TempDir::new().unwrap();
+    let isolation = IsolationConfig {
+        extra_path: vec!["tools/$HOME".to_string()],
+        system_path: vec!["/opt/100%/bin".to_string()],
+        ..IsolationConfig::full()
+    };
+    let contract = IsolationEnv::new(BundleStrategy::System, &isolation);
+    contract.write(temp.path()).unwrap();
+
+    let env_sh = fs::read_to_string(temp.path().join("env.sh")).unwrap();
+    assert!(
+        env_sh.contains("export PATH=\"$APP_DIR/tools/\\$HOME:/opt/100%/bin\""),
+        "{}",
+        env_sh
+    );
+    let env_cmd = fs::read_to_string(temp.path().join("env.cmd")).unwrap();
+    assert!(
+        env_cmd.contains("@set \"PATH=%APP_DIR%\\tools\\$HOME;/opt/100%%/bin\""),
+        "{}",
+        env_cmd
+    );
+
+    // Quotes cannot be escaped in env.cmd
+    let isolation = IsolationConfig {
+        extra_path: vec!["a\"&calc".to_string()],
+        ..isolation
+    };
+    let err = IsolationEnv::new(BundleStrategy::System, &isolation)
+        .write(temp.path())
+        .unwrap_err();
+    assert!(matches!(err, PackError::Config(_)), "{}", err);
+}
+
+#[test]
+fn test_system_strategy_writes_launch_scripts() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+
+    let python = PythonBundleConfig {
+        strategy: BundleStrategy::System,
+        packages: vec!["requests".to_string()],
+        ..PythonBundleConfig::new("main:run")
+            .with_version("3.12")
+            .with_system(SystemPythonConfig {
+                venv: true,
+                ..Default::default()
+            })
+    };
+    let config = PackConfig::fullstack_with_config(&frontend, python.clone())
+        .with_output("sys-app")
+        .with_output_dir(temp.path());
+    Packer::new(config).pack().unwrap();
+
+    let app_dir = temp.path().join("sys-app");
+    let sh = fs::read_to_string(app_dir.join(LAUNCH_SCRIPT_SH)).unwrap();
+    assert!(sh.contains("sys.version_info[:2] != (3, 12)"), "{}", sh);
+    assert!(sh.contains("brew install python@3.12"));
+    assert!(sh.contains("VENV=\"$APP_DIR/.venv\""));
+    assert!(sh.contains("-m pip install -r \"$APP_DIR/requirements.txt\""));
+    assert!(sh.contains(&format!("export {}=\"$PYTHON\"", SYSTEM_PYTHON_ENV)));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(app_dir.join(LAUNCH_SCRIPT_SH))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+    let cmd = fs::read_to_string(app_dir.join(LAUNCH_SCRIPT_CMD)).unwrap();
+    assert!(cmd.contains("call :find_python py -3.12\r\n"), "{}", cmd);
+    assert!(cmd.contains("winget install Python.Python.3.12"));
+    assert!(cmd.contains("set \"VENV=%APP_DIR%\\.venv\""));
+
+    // The launcher configuration only applies to the system strategy
+    let embedded = PythonBundleConfig {
+        strategy: BundleStrategy::Embedded,
+        ..python
+    };
+    let config =
+        PackConfig::fullstack_with_config(&frontend, embedded).with_output_dir(temp.path());
+    let err = Packer::new(config).pack().unwrap_err().to_string();
+    assert!(
+        err.contains("[backend.python.system] requires strategy"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_isolation_extra_path_validation() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+
+    for (entry, expected) in [
+        ("../outside", "inside the app directory"),
+        ("a;b", "path list separator"),
+        ("lib:bin", "path list separator"),
+        ("  ", "must not be empty"),
+    ] {
+        let python = PythonBundleConfig {
+            strategy: BundleStrategy::System,
+            isolation: IsolationConfig {
+                extra_path: vec![entry.to_string()],
+                ..IsolationConfig::full()
+            },
+            ..PythonBundleConfig::new("main:run")
+        };
+        let config =
+            PackConfig::fullstack_with_config(&frontend, python).with_output_dir(temp.path());
+        let err = Packer::new(config).pack().unwrap_err().to_string();
+        assert!(err.contains("[python.isolation]"), "{}", err);
+        assert!(err.contains(expected), "{}: {}", entry, err);
+    }
+
+    // Drive-qualified and nested relative entries are fine
+    let isolation = IsolationConfig {
+        extra_path: vec!["C:\\tools".to_string(), "bin/../lib".to_string()],
+        ..IsolationConfig::full()
+    };
+    isolation.validate("python.isolation").unwrap();
+}
+
+// ============================================================================
+// Debug Symbol Tests
+// ============================================================================
+
+#[test]
+fn test_pack_writes_symbol_index() {
+    let temp = TempDir::new().unwrap();
+    let config = PackConfig::url("https://example.com")
+        .with_output("sym-app")
+        .with_output_dir(temp.path())
+        .with_symbols(SymbolsConfig {
+            strip: false,
+            symbol_server: Some("https://symbols.example.com".to_string()),
+            ..Default::default()
+        });
+    Packer::new(config).pack().unwrap();
+
+    let index_path = temp.path().join("sym-app-symbols").join("symbols.json");
+    let index: SymbolIndex = serde_json::from_slice(&fs::read(index_path).unwrap()).unwrap();
+    assert_eq!(index.app, "sym-app");
+    assert_eq!(
+        index.symbol_server.as_deref(),
+        Some("https://symbols.example.com")
+    );
+    assert_eq!(index.entries.len(), 1);
+    assert_eq!(index.entries[0].name, "launcher");
+    assert!(!index.entries[0].stripped);
+    assert!(!index.entries[0].build_id.id.is_empty());
+}
+
+#[test]
+fn test_pack_records_history_and_fails_on_regression() {
+    let temp = TempDir::new().unwrap();
+    let history_dir = temp.path().join("history");
+    let history = HistoryConfig {
+        dir: Some(history_dir.clone()),
+        ..Default::default()
+    };
+    let config = PackConfig::url("https://example.com")
+        .with_output("hist-app")
+        .with_output_dir(temp.path())
+        .with_history(history.clone());
+    let output = Packer::new(config.clone()).pack().unwrap();
+    assert!(output.regressions.is_empty());
+
+    let recorded = PackHistory::in_dir(&history_dir, "hist-app");
+    let entries = recorded.entries().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].size, output.size);
+    assert_eq!(entries[0].mode, output.mode);
+    assert!(entries[0].phases.contains_key("pack"));
+
+    // A much smaller baseline turns the same pack into a size regression
+    recorded
+        .set_baseline(&PackStats {
+            size: output.size / 2,
+            ..entries[0].clone()
+        })
+        .unwrap();
+    let gated = config.with_history(HistoryConfig {
+        compare_to: CompareTo::Baseline,
+        fail_on_regression: true,
+        ..history
+    });
+    let err = Packer::new(gated).pack().unwrap_err();
+    assert!(matches!(err, PackError::Regression(_)));
+    // The failing run is not recorded
+    assert_eq!(recorded.entries().unwrap().len(), 1);
+}
+
+#[test]
+fn test_doctor_reports_checklist() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+
+    let config = PackConfig::frontend(&frontend).with_output_dir(temp.path().join("out"));
+    let report = Packer::new(config.clone()).doctor();
+    assert_eq!(report.checks[0].name, "config");
+    assert_eq!(report.checks[0].status, CheckStatus::Pass);
+    assert!(report.get("disk: output").is_some());
+    // Frontend-only apps need no tools or network
+    assert!(report.get("python").is_none());
+    assert!(!report.checks.iter().any(|c| c.name.starts_with("network")));
+
+    // All problems are reported at once
+    let mut broken = PackConfig::frontend(temp.path().join("missing"));
+    broken.windows_resource.certificate = Some(temp.path().join("cert.pfx"));
+    let report = Packer::new(broken).doctor();
+    assert!(!report.is_ok());
+    let failures: Vec<&str> = report.failures().map(|c| c.name.as_str()).collect();
+    assert_eq!(failures, vec!["config", "signing certificate"]);
+    assert!(report.to_string().contains("cert.pfx not found"));
+}
+
+#[test]
+fn test_pack_plan_writes_nothing() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(frontend.join("js")).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+    fs::write(frontend.join("js/app.js"), "console.log(1)").unwrap();
+    fs::write(frontend.join("js/app.js.map"), "{}").unwrap();
+    let output_dir = temp.path().join("out");
+
+    let mut config = PackConfig::frontend(&frontend)
+        .with_output("planned")
+        .with_output_dir(&output_dir)
+        .with_hooks(HooksConfig {
+            before_collect: vec!["npm run build".to_string()],
+            before_pack: vec!["python gen_version.py".to_string()],
+            ..Default::default()
+        });
+    config.vx = Some(VxConfig {
+        cache_dir: temp.path().join("vx-cache"),
+        ensure: vec!["node@20".to_string()],
+        ..Default::default()
+    });
+    config.downloads = vec![DownloadEntry {
+        name: "tool".to_string(),
+        url: "https://example.com/tool.tar.gz".to_string(),
+        checksum: Some("abc".to_string()),
+        strip_components: 0,
+        extract: true,
+        stage: Default::default(),
+        dest: "tools".to_string(),
+        executable: Vec::new(),
+    }];
+
+    let plan = Packer::new(config.clone()).plan().expect("plan");
+    assert_eq!(plan.mode, "frontend");
+    assert!(plan.executable.starts_with(&output_dir));
+    assert_eq!(plan.assets.len(), 2);
+    let script = plan.asset("js/app.js").unwrap();
+    assert_eq!(script.size, 14);
+    assert_eq!(script.source, AssetSource::Frontend);
+    assert!(plan.asset("js/app.js.map").is_none());
+    assert_eq!(plan.asset_size(), 27);
+    assert_eq!(plan.downloads.len(), 1);
+    assert_eq!(plan.pending_downloads().count(), 1);
+    assert!(plan.downloads[0].verified);
+    assert_eq!(
+        plan.hooks,
+        vec![
+            "npm run build".to_string(),
+            "python gen_version.py".to_string()
+        ]
+    );
+    assert!(plan.tools.iter().any(|t| t.name == "node@20"));
+    assert!(plan.estimated_output_size >= 27);
+    assert!(plan.to_string().contains("js/app.js"));
+    let json = serde_json::to_value(&plan).unwrap();
+    assert_eq!(json["downloads"][0]["stage"], "before_collect");
+
+    // Nothing was written or fetched
+    assert!(!output_dir.exists());
+    assert!(!temp.path().join("vx-cache").exists());
+
+    // Download policy violations fail the plan
+    config.vx.as_mut().unwrap().require_checksum = true;
+    config.downloads[0].checksum = None;
+    assert!(Packer::new(config).plan().is_err());
+}
+
+#[test]
+fn test_build_target_parse() {
+    let windows = BuildTarget::parse("x86_64-windows").unwrap();
+    assert_eq!(windows.arch, TargetArch::X86_64);
+    assert_eq!(windows.platform, TargetPlatform::Windows);
+    assert_eq!(windows.exe_name("myapp"), "myapp-x86_64-windows.exe");
+    assert_eq!(
+        BuildTarget::parse("x86_64-pc-windows-msvc").unwrap(),
+        windows
+    );
+
+    let mac = BuildTarget::parse("arm64-apple-darwin").unwrap();
+    assert_eq!(mac.to_string(), "aarch64-macos");
+    assert_eq!(mac.exe_name("myapp"), "myapp-aarch64-macos");
+    assert_eq!(
+        mac.python_target().unwrap().triple(),
+        "aarch64-apple-darwin"
+    );
+
+    assert!(BuildTarget::parse("aarch64-linux")
+        .unwrap()
+        .python_target()
+        .is_err());
+    assert!(BuildTarget::parse("riscv64-linux").is_err());
+    assert!(BuildTarget::parse("x86_64").is_err());
+}
+
+#[test]
+fn test_pack_targets_uses_stubs() {
+    let temp = TempDir::new().unwrap();
+    let windows = BuildTarget::parse("x86_64-windows").unwrap();
+    let mac = BuildTarget::parse("aarch64-macos").unwrap();
+    let windows_stub = temp.path().join("stub-windows.exe");
+    let mac_stub = temp.path().join("stub-macos");
+    fs::write(&windows_stub, b"MZ windows stub").unwrap();
+    fs::write(&mac_stub, b"macos stub").unwrap();
+
+    let config = PackConfig::url("https://example.com")
+        .with_output("myapp")
+        .with_output_dir(temp.path().join("out"))
+        .with_targets(vec![windows, mac])
+        .with_stub(windows, &windows_stub)
+        .with_stub(mac, &mac_stub);
+    let outputs = Packer::new(config).pack_targets().unwrap();
+
+    assert_eq!(outputs.len(), 2);
+    for ((target, output), (name, stub)) in outputs.iter().zip([
+        ("myapp-x86_64-windows.exe", &b"MZ windows stub"[..]),
+        ("myapp-aarch64-macos", &b"macos stub"[..]),
+    ]) {
+        assert_eq!(output.executable.file_name().unwrap(), name);
+        assert!(
+            fs::read(&output.executable).unwrap().starts_with(stub),
+            "{}",
+            target
+        );
+    }
+}
+
+#[test]
+fn test_pack_targets_uses_command_runner() {
+    use auroraview_pack::{MockOutcome, RecordingRunner};
+    use std::sync::Arc;
+
+    let temp = TempDir::new().unwrap();
+    let targets: Vec<BuildTarget> = ["x86_64-windows", "aarch64-macos"]
+        .into_iter()
+        .map(|t| BuildTarget::parse(t).unwrap())
+        .collect();
+    let mut config = PackConfig::url("https://example.com")
+        .with_output("myapp")
+        .with_output_dir(temp.path().join("out"))
+        .with_targets(targets.clone());
+    for target in &targets {
+        let stub = temp.path().join(format!("stub-{}", target));
+        fs::write(&stub, b"stub").unwrap();
+        config = config.with_stub(*target, stub);
+    }
+    config.vx = Some(VxConfig {
+        enabled: true,
+        ensure: vec!["node@20".to_string()],
+        ..Default::default()
+    });
+
+    let runner =
+        Arc::new(RecordingRunner::new().on("node --version", MockOutcome::success("v20.11.1\n")));
+    let outputs = Packer::new(config)
+        .with_command_runner(runner.clone())
+        .pack_targets()
+        .unwrap();
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(runner.command_lines(), ["node --version", "node --version"]);
+}
+
+#[test]
+fn test_pack_targets_uses_python_provider() {
+    use auroraview_pack::test_support::{fake_python, python_distribution};
+    use auroraview_pack::{DistributionProvider, PackResult, PythonTarget};
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    /// Provider writing a fake distribution per requested target
+    #[derive(Debug, Clone)]
+    struct Offline {
+        dir: PathBuf,
+        requested: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl DistributionProvider for Offline {
+        fn describe(&self) -> String {
+            "offline test provider".to_string()
+        }
+
+        fn archive(&self, _version: &str, target: PythonTarget) -> PackResult<PathBuf> {
+            self.requested
+                .lock()
+                .unwrap()
+                .push(target.triple().to_string());
+            let path = self.dir.join(format!("{}.tar.gz", target.triple()));
+            fs::write(&path, python_distribution(target)?)?;
+            Ok(path)
+        }
+    }
+
+    let temp = TempDir::new().unwrap();
+    let python = fake_python(temp.path()).unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(&frontend).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+
+    let targets: Vec<BuildTarget> = ["x86_64-windows", "aarch64-macos"]
+        .into_iter()
+        .map(|t| BuildTarget::parse(t).unwrap())
+        .collect();
+    let bundle = PythonBundleConfig {
+        include_paths: vec![python.source_dir.clone()],
+        ..PythonBundleConfig::new(&python.entry_point)
+    };
+    let mut config = PackConfig::fullstack_with_config(&frontend, bundle)
+        .with_output("myapp")
+        .with_output_dir(temp.path().join("out"))
+        .with_targets(targets.clone());
+    for target in &targets {
+        let stub = temp.path().join(format!("stub-{}", target));
+        fs::write(&stub, b"stub").unwrap();
+        config = config.with_stub(*target, stub);
+    }
+
+    let provider = Offline {
+        dir: temp.path().to_path_buf(),
+        requested: Arc::default(),
+    };
+    let outputs = Packer::new(config)
+        .with_python_provider(provider.clone())
+        .pack_targets()
+        .unwrap();
+    assert_eq!(outputs.len(), 2);
+    let expected: Vec<String> = targets
+        .iter()
+        .map(|t| t.python_target().unwrap().triple().to_string())
+        .collect();
+    assert_eq!(*provider.requested.lock().unwrap(), expected);
+}
+
+#[test]
+fn test_pack_targets_requires_stub_for_foreign_target() {
+    let temp = TempDir::new().unwrap();
+    let current = BuildTarget::current().unwrap();
+    let foreign = ["x86_64-windows", "aarch64-macos"]
+        .into_iter()
+        .map(|t| BuildTarget::parse(t).unwrap())
+        .find(|t| *t != current)
+        .unwrap();
+
+    let config = PackConfig::url("https://example.com")
+        .with_output("myapp")
+        .with_output_dir(temp.path())
+        .with_targets(vec![current, foreign]);
+    let err = Packer::new(config).pack_targets().unwrap_err().to_string();
+    assert!(err.contains("[build.stubs]"), "{}", err);
+    assert!(!temp.path().join(current.exe_name("myapp")).exists());
+}
+
+#[test]
+fn test_onedir_layout_writes_asset_files() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(frontend.join("js")).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+    fs::write(frontend.join("js/app.js"), "console.log(1)").unwrap();
+
+    let out = temp.path().join("out");
+    let config = PackConfig::frontend(&frontend)
+        .with_output("myapp")
+        .with_output_dir(&out)
+        .with_layout(OutputLayout::Onedir);
+    assert_eq!(config.layout(), OutputLayout::Onedir);
+    let output = Packer::new(config).pack().unwrap();
+
+    let dir = out.join("myapp");
+    assert_eq!(output.executable.parent().unwrap(), dir);
+    assert_eq!(
+        fs::read_to_string(dir.join("js/app.js")).unwrap(),
+        "console.log(1)"
+    );
+    assert!(dir.join("index.html").is_file());
+    assert!(output.size > fs::metadata(&output.executable).unwrap().len());
+
+    // The executable only carries the configuration
+    let overlay = OverlayReader::read(&output.executable).unwrap().unwrap();
+    assert_eq!(overlay.config.layout(), OutputLayout::Onedir);
+    assert!(!overlay.assets.iter().any(|(path, _)| path == "index.html"));
+}
+
+/// Progress observer recording every event
+#[derive(Default)]
+struct RecordingObserver(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+impl PackProgressObserver for RecordingObserver {
+    fn stage_started(&self, stage: PackStage) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("start {}", stage.as_str()));
+    }
+
+    fn stage_finished(&self, stage: PackStage, _elapsed: std::time::Duration) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("end {}", stage.as_str()));
+    }
+
+    fn stage_resumed(&self, stage: PackStage) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("resumed {}", stage.as_str()));
+    }
+
+    fn file_written(&self, path: &str, size: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("file {} {}", path, size));
+    }
+
+    fn bytes(&self, progress: &ByteProgress) {
+        let total = progress.total.unwrap();
+        assert!(progress.done <= total);
+        if progress.done == total {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("bytes {} {}", progress.item, total));
+        }
+    }
+}
+
+#[test]
+fn test_packer_reports_progress() {
+    let temp = TempDir::new().unwrap();
+    let frontend = temp.path().join("dist");
+    fs::create_dir_all(frontend.join("js")).unwrap();
+    fs::write(frontend.join("index.html"), "<html></html>").unwrap();
+    fs::write(frontend.join("js/app.js"), "console.log(1)").unwrap();
+
+    for layout in [OutputLayout::Onefile, OutputLayout::Onedir] {
+        let observer = RecordingObserver::default();
+        let events = observer.0.clone();
+        let config = PackConfig::frontend(&frontend)
+            .with_output("progress-app")
+            .with_output_dir(temp.path().join(layout.as_str()))
+            .with_layout(layout);
+        Packer::new(config).with_progress(observer).pack().unwrap();
+
+        let events = events.lock().unwrap();
+        let stages: Vec<&str> = events
+            .iter()
+            .filter(|e| e.starts_with("start ") || e.starts_with("end "))
+            .map(String::as_str)
+            .collect();
+        assert_eq!(
+            stages,
+            [
+                "start prepare",
+                "end prepare",
+                "start pack",
+                "end pack",
+                "start after_pack",
+                "end after_pack"
+            ]
+        );
+        assert!(
+            events.iter().any(|e| e == "file index.html 13"),
+            "{:?}",
+            events
+        );
+        assert!(
+            events.iter().any(|e| e == "file js/app.js 14"),
+            "{:?}",
+            events
+        );
+        assert!(events.iter().any(|e| e.starts_with("bytes assets ")));
+        // Files are reported while the pack stage runs
+        let pack_end = events.iter().position(|e| e == "end pack").unwrap();
+        assert!(events.iter().rposition(|e| e.starts_with("file ")).unwrap() < pack_end);
+    }
+}
+
+#[test]
+fn test_layout_defaults_follow_the_mode() {
+    let temp = TempDir::new().unwrap();
+    assert_eq!(
+        PackConfig::url("https://example.com").layout(),
+        OutputLayout::Onefile
+    );
+
+    let portable = PythonBundleConfig {
+        strategy: BundleStrategy::Portable,
+        ..PythonBundleConfig::new("main:run")
+    };
+    let config = PackConfig::fullstack_with_config(temp.path(), portable);
+    assert_eq!(config.layout(), OutputLayout::Onedir);
+
+    let err = Packer::new(
+        config
+            .with_layout(OutputLayout::Onefile)
+            .with_output_dir(temp.path()),
+    )
+    .pack()
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("always writes a directory"), "{}", err);
+}
+
+#[test]
+fn test_pack_output_build_report() {
+    use auroraview_pack::test_support::{PackFixture, FAKE_PYTHON_VERSION};
+
+    let fixture = PackFixture::new().unwrap();
+    let frontend = fixture.frontend().unwrap();
+    let python = fixture.python().unwrap();
+    let bundle = PythonBundleConfig::new(&python.entry_point)
+        .with_include_paths(vec![python.source_dir.clone()]);
+    let output = fixture
+        .pack(PackConfig::fullstack_with_config(&frontend, bundle).with_output("report-app"))
+        .unwrap();
+
+    let script = output
+        .assets
+        .iter()
+        .find(|a| a.path == "frontend/assets/app.js")
+        .unwrap();
+    assert_eq!(script.size, "console.log('test app');\n".len() as u64);
+    assert!(script.length > 0);
+    for stage in ["prepare", "pack", "after_pack"] {
+        assert!(output.stage_timings.contains_key(stage), "{}", stage);
+    }
+    assert!(output.duration_ms >= output.stage_timings["pack"]);
+    assert_eq!(output.tools["python"], FAKE_PYTHON_VERSION);
+    assert_eq!(output.tools["auroraview-pack"], env!("CARGO_PKG_VERSION"));
+
+    let download = output
+        .downloads
+        .iter()
+        .find(|d| d.name.starts_with("python-"))
+        .unwrap();
+    assert!(download
+        .uri
+        .as_deref()
+        .unwrap()
+        .contains("python-build-standalone"));
+    assert_eq!(download.sha256().unwrap().len(), 64);
+
+    let json: serde_json::Value = serde_json::from_str(&output.to_json().unwrap()).unwrap();
+    assert_eq!(json["mode"], output.mode.as_str());
+    assert_eq!(json["tools"]["python"], FAKE_PYTHON_VERSION);
+    assert_eq!(
+        json["assets"].as_array().unwrap().len(),
+        output.assets.len()
+    );
+    assert!(json["downloads"][0]["digest"]["sha256"].is_string());
+}
+
+#[test]
+fn test_smoke_test_passes() {
+    use auroraview_pack::test_support::PackFixture;
+    use auroraview_pack::{
+        MockOutcome, RecordingRunner, SmokeTestConfig, HEADLESS_ENV, SELF_CHECK_FLAG,
+    };
+    use std::sync::Arc;
+
+    let fixture = PackFixture::new().unwrap();
+    let config = fixture
+        .configure(
+            PackConfig::frontend(fixture.frontend().unwrap())
+                .with_output("smoke-app")
+                .with_smoke_test(SmokeTestConfig::default()),
+        )
+        .unwrap();
+    let runner = Arc::new(RecordingRunner::new().with_default(MockOutcome::success(
+        "starting\n{\"overlay\":true,\"extraction\":true,\"backend\":null,\"errors\":[]}\n",
+    )));
+    let output = Packer::new(config)
+        .with_command_runner(runner.clone())
+        .pack()
+        .unwrap();
+
+    let report = output.smoke_test.unwrap();
+    assert!(report.passed, "{}", report);
+    assert!(report.overlay && report.extraction);
+    assert_eq!(report.backend, None);
+    assert_eq!(report.exit_code, Some(0));
+
+    let commands = runner.commands();
+    let check = commands
+        .iter()
+        .find(|c| c.args.first().map(String::as_str) == Some(SELF_CHECK_FLAG))
+        .unwrap();
+    assert_eq!(check.program, output.executable.to_string_lossy());
+    assert!(check
+        .env
+        .contains(&(HEADLESS_ENV.to_string(), Some("1".to_string()))));
+}
+
+#[test]
+fn test_smoke_test_backend_failure() {
+    use auroraview_pack::test_support::PackFixture;
+    use auroraview_pack::{MockOutcome, RecordingRunner, SmokeTestConfig};
+
+    let fixture = PackFixture::new().unwrap();
+    let report = "{\"overlay\":true,\"extraction\":true,\"backend\":false,\"errors\":[\"no response on /health\"]}";
+    let runner = || RecordingRunner::new().with_default(MockOutcome::success(report));
+
+    let config = fixture
+        .configure(
+            PackConfig::frontend(fixture.frontend().unwrap())
+                .with_output("smoke-required")
+                .with_smoke_test(SmokeTestConfig::default()),
+        )
+        .unwrap();
+    let err = Packer::new(config)
+        .with_command_runner(runner())
+        .pack()
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("backend health check failed"),
+        "{}",
+        err
+    );
+    assert!(
+        err.to_string().contains("no response on /health"),
+        "{}",
+        err
+    );
+
+    // Not required: reported, the pack succeeds
+    let config = fixture
+        .configure(
+            PackConfig::frontend(fixture.frontend().unwrap())
+                .with_output("smoke-optional")
+                .with_smoke_test(SmokeTestConfig {
+                    required: false,
+                    ..Default::default()
+                }),
+        )
+        .unwrap();
+    let output = Packer::new(config)
+        .with_command_runner(runner())
+        .pack()
+        .unwrap();
+    let report = output.smoke_test.unwrap();
+    assert!(!report.passed);
+    assert_eq!(report.backend, Some(false));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_smoke_test_runs_executable() {
+    use auroraview_pack::test_support::PackFixture;
+    use auroraview_pack::SmokeTestConfig;
+
+    // The test stub exits without a self-check report
+    let fixture = PackFixture::new().unwrap();
+    let config = fixture
+        .configure(
+            PackConfig::frontend(fixture.frontend().unwrap())
+                .with_output("smoke-stub")
+                .with_smoke_test(SmokeTestConfig {
+                    required: false,
+                    timeout: 10,
+                    ..Default::default()
+                }),
+        )
+        .unwrap();
+    let report = Packer::new(config).pack().unwrap().smoke_test.unwrap();
+    assert!(report.overlay && report.extraction);
+    assert!(!report.passed);
+    assert_eq!(report.exit_code, Some(0));
+    assert!(report.errors[0].contains("printed no self-check report"));
+}
+
+#[test]
+fn test_frontend_globs_from_manifest() {
+    use auroraview_pack::test_support::PackFixture;
+    use auroraview_pack::Manifest;
+
+    let fixture = PackFixture::new().unwrap();
+    let frontend = fixture.frontend().unwrap();
+    fs::write(frontend.join("assets/app.js.map"), "sourcemap").unwrap();
+    fs::write(frontend.join("assets/intro.mp4"), "video").unwrap();
+
+    let manifest = Manifest::parse(&format!(
+        r#"
+[package]
+name = "globs-app"
+
+[frontend]
+path = "{}"
+include = ["**"]
+exclude = ["*.mp4"]
+"#,
+        frontend.display().to_string().replace('\\', "/")
+    ))
+    .unwrap();
+    let config = PackConfig::from_manifest(&manifest, fixture.path()).unwrap();
+    assert_eq!(config.frontend_exclude, ["*.mp4"]);
+    let output = fixture.pack(config).unwrap();
+
+    let paths: Vec<_> = output.assets.iter().map(|a| a.path.as_str()).collect();
+    assert!(paths.contains(&"assets/app.js.map"), "{:?}", paths);
+    assert!(!paths.contains(&"assets/intro.mp4"), "{:?}", paths);
+
+    let err = Packer::new(
+        fixture
+            .configure(
+                PackConfig::frontend(&frontend)
+                    .with_output("bad-globs")
+                    .with_frontend_exclude(vec!["[".to_string()]),
+            )
+            .unwrap(),
+    )
+    .pack()
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid exclude pattern"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_pack_context_isolates_concurrent_packs() {
+    use auroraview_pack::test_support::PackFixture;
+
+    let fixture = PackFixture::new().unwrap();
+    let jobs: Vec<_> = ["job-a", "job-b"]
+        .iter()
+        .map(|name| {
+            let job = fixture.path().join(name);
+            fs::create_dir_all(job.join("dist")).unwrap();
+            fs::write(job.join("dist/index.html"), format!("<h1>{}</h1>", name)).unwrap();
+            job
+        })
+        .collect();
+
+    let outputs: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .iter()
+            .map(|job| {
+                // Relative paths resolve against the job, not the process
+                let config = PackConfig::frontend("dist")
+                    .with_output("app")
+                    .with_output_dir("out")
+                    .with_history(HistoryConfig::default());
+                let context = PackContext::new(job)
+                    .with_cache_root(job.join("cache"))
+                    .with_temp_root(job.join("tmp"))
+                    .with_launcher(fixture.stub())
+                    .with_clock(FixedClock::from_unix(1_700_000_000));
+                let packer = Packer::new(config).with_context(context);
+                scope.spawn(move || packer.pack().unwrap())
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    for (job, output) in jobs.iter().zip(&outputs) {
+        assert!(output.executable.starts_with(job.join("out")));
+        let mut archive = OverlayReader::open(&output.executable).unwrap().unwrap();
+        let index = archive.read_asset("index.html").unwrap().unwrap();
+        assert!(String::from_utf8(index)
+            .unwrap()
+            .contains(job.file_name().unwrap().to_str().unwrap()));
+
+        // History under the cache root, timestamps from the clock
+        let runs = PackHistory::in_dir(job.join("cache/pack-history"), "app")
+            .entries()
+            .unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].timestamp, 1_700_000_000);
+        assert!(runs[0].run_id.starts_with("1700000000-"));
+    }
+    assert!(!fixture.path().join("out").exists());
+}
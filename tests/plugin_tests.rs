@@ -0,0 +1,124 @@
+//! Tests for packer plugins
+
+use auroraview_pack::test_support::PackFixture;
+use auroraview_pack::{
+    OverlayData, PackConfig, PackError, PackOutput, PackPlugin, PackResult, Packer, PluginContext,
+};
+use std::sync::{Arc, Mutex};
+
+/// Plugin recording its callbacks and rewriting `.css` assets
+#[derive(Clone, Default)]
+struct Recorder {
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl Recorder {
+    fn record(&self, call: impl Into<String>) {
+        self.calls.lock().unwrap().push(call.into());
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl PackPlugin for Recorder {
+    fn name(&self) -> &str {
+        "recorder"
+    }
+
+    fn after_collect(
+        &self,
+        cx: &PluginContext<'_>,
+        assets: &mut Vec<(String, Vec<u8>)>,
+    ) -> PackResult<()> {
+        self.record(format!("after_collect {}", cx.config.output_name));
+        assets.push(("plugin/notice.txt".to_string(), b"injected".to_vec()));
+        Ok(())
+    }
+
+    fn filter_asset(&self, path: &str) -> bool {
+        !path.ends_with(".js")
+    }
+
+    fn transform_asset(&self, path: &str, content: &[u8]) -> PackResult<Option<Vec<u8>>> {
+        Ok(path.ends_with(".css").then(|| content.to_ascii_uppercase()))
+    }
+
+    fn before_overlay_write(
+        &self,
+        cx: &PluginContext<'_>,
+        overlay: &mut OverlayData,
+    ) -> PackResult<()> {
+        self.record("before_overlay_write");
+        overlay.set_metadata("plugin.run", cx.run_id);
+        Ok(())
+    }
+
+    fn after_pack(&self, cx: &PluginContext<'_>, output: &mut PackOutput) -> PackResult<()> {
+        assert_eq!(cx.executable, output.executable);
+        self.record("after_pack");
+        Ok(())
+    }
+}
+
+/// Plugin failing after the executable is packed
+struct Failing;
+
+impl PackPlugin for Failing {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    fn after_pack(&self, _cx: &PluginContext<'_>, _output: &mut PackOutput) -> PackResult<()> {
+        Err(PackError::Config("no license server".to_string()))
+    }
+}
+
+#[test]
+fn test_plugin_callbacks_shape_the_overlay() {
+    let fixture = PackFixture::new().unwrap();
+    let config = fixture
+        .configure(PackConfig::frontend(fixture.frontend().unwrap()).with_output("plugged"))
+        .unwrap();
+    let recorder = Recorder::default();
+    let packer = Packer::new(config).with_plugin(recorder.clone());
+    assert_eq!(packer.plugins(), ["recorder"]);
+
+    let output = packer.pack().unwrap();
+    assert_eq!(
+        recorder.calls(),
+        [
+            "after_collect plugged",
+            "before_overlay_write",
+            "after_pack"
+        ]
+    );
+
+    let overlay = PackFixture::read_overlay(&output.executable).unwrap();
+    let asset = |path: &str| {
+        overlay
+            .assets
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, c)| c.clone())
+    };
+    assert_eq!(asset("plugin/notice.txt").unwrap(), b"injected");
+    assert_eq!(asset("assets/style.css").unwrap(), b"BODY { MARGIN: 0; }\n");
+    assert!(asset("assets/app.js").is_none());
+    assert!(asset("index.html").is_some());
+    assert!(overlay.metadata("plugin.run").is_some());
+}
+
+#[test]
+fn test_plugin_error_fails_pack() {
+    let fixture = PackFixture::new().unwrap();
+    let config = fixture
+        .configure(PackConfig::frontend(fixture.frontend().unwrap()))
+        .unwrap();
+    let err = Packer::new(config).with_plugin(Failing).pack().unwrap_err();
+    assert!(matches!(err, PackError::Plugin(_)));
+    assert!(err
+        .to_string()
+        .contains("failing (after_pack): Configuration error: no license server"));
+}
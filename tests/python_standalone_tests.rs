@@ -1,9 +1,11 @@
 //! Tests for auroraview-pack python_standalone module
 
+use auroraview_pack::test_support::python_distribution;
 use auroraview_pack::{
-    get_runtime_cache_dir, PythonRuntimeMeta, PythonStandalone, PythonStandaloneConfig,
-    PythonTarget,
+    get_runtime_cache_dir, DistributionProvider, LocalArchive, PythonRuntimeMeta, PythonStandalone,
+    PythonStandaloneConfig, PythonTarget,
 };
+use std::sync::Arc;
 
 #[test]
 fn test_target_detection() {
@@ -184,3 +186,44 @@ fn test_cache_dir_custom() {
     let standalone = PythonStandalone::new(config).unwrap();
     assert_eq!(standalone.cache_dir(), temp_dir.path());
 }
+
+#[test]
+fn test_local_archive_provider_skips_download() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let archive = temp_dir.path().join("python.tar.gz");
+    std::fs::write(
+        &archive,
+        python_distribution(PythonTarget::LinuxX64).unwrap(),
+    )
+    .unwrap();
+    let cache_dir = temp_dir.path().join("cache");
+    let config = PythonStandaloneConfig {
+        version: "3.11".to_string(),
+        release: None,
+        target: Some("x86_64-unknown-linux-gnu".to_string()),
+        cache_dir: Some(cache_dir.clone()),
+    };
+
+    let standalone = PythonStandalone::new(config)
+        .unwrap()
+        .with_provider(Arc::new(LocalArchive::new(&archive)));
+    assert_eq!(standalone.download().unwrap(), archive);
+    assert!(!cache_dir.exists());
+
+    let python = standalone
+        .extract(&temp_dir.path().join("runtime"))
+        .unwrap();
+    assert!(python.ends_with("python/bin/python3"));
+    assert!(python.is_file());
+}
+
+#[test]
+fn test_local_archive_provider_missing_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let provider = LocalArchive::new(temp_dir.path().join("missing.tar.gz"));
+    assert!(provider.describe().contains("missing.tar.gz"));
+    let err = provider
+        .archive("3.11", PythonTarget::LinuxX64)
+        .unwrap_err();
+    assert!(err.to_string().contains("not found"), "{}", err);
+}
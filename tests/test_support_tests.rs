@@ -0,0 +1,88 @@
+//! Tests for the integration test fixtures (`test-support` feature)
+
+use auroraview_pack::test_support::{
+    python_distribution, MockServer, PackFixture, STUB_EXE_CONTENT,
+};
+use auroraview_pack::{
+    HttpClient, HttpConfig, OverlayReader, PackConfig, PythonBundleConfig, PythonTarget,
+};
+
+#[test]
+fn test_fixture_packs_frontend_with_stub() {
+    let fixture = PackFixture::new().unwrap();
+    let frontend = fixture.frontend().unwrap();
+    let output = fixture
+        .pack(PackConfig::frontend(&frontend).with_output("fixture-app"))
+        .unwrap();
+
+    assert!(output.executable.starts_with(fixture.output_dir()));
+    let exe = std::fs::read(&output.executable).unwrap();
+    assert!(exe.starts_with(STUB_EXE_CONTENT));
+    assert_eq!(
+        OverlayReader::get_original_size(&output.executable)
+            .unwrap()
+            .unwrap(),
+        STUB_EXE_CONTENT.len() as u64
+    );
+
+    let overlay = PackFixture::read_overlay(&output.executable).unwrap();
+    for path in ["index.html", "assets/app.js", "assets/style.css"] {
+        assert!(overlay.assets.iter().any(|(p, _)| p == path), "{}", path);
+    }
+    assert_eq!(
+        OverlayReader::verify(&output.executable, None).unwrap(),
+        None
+    );
+    assert!(PackFixture::read_overlay(fixture.stub()).is_err());
+}
+
+#[test]
+fn test_fixture_packs_fake_python() {
+    let fixture = PackFixture::new().unwrap();
+    let frontend = fixture.frontend().unwrap();
+    let python = fixture.python().unwrap();
+    assert_eq!(python.store, fixture.store());
+
+    let bundle = PythonBundleConfig::new(&python.entry_point)
+        .with_include_paths(vec![python.source_dir.clone()]);
+    let output = fixture
+        .pack(PackConfig::fullstack_with_config(&frontend, bundle).with_output("fixture-py"))
+        .unwrap();
+
+    let overlay = PackFixture::read_overlay(&output.executable).unwrap();
+    assert!(overlay
+        .assets
+        .iter()
+        .any(|(path, _)| path.ends_with("main.py")));
+    assert!(output.python_file_count >= 1);
+}
+
+#[test]
+fn test_mock_server_serves_files() {
+    let temp = tempfile::tempdir().unwrap();
+    let archive = python_distribution(PythonTarget::LinuxX64).unwrap();
+    let server = MockServer::start()
+        .unwrap()
+        .with_file("/python.tar.gz", archive.clone());
+    server.add_file("notes.txt", "v1");
+    let client = HttpClient::new(&HttpConfig {
+        no_proxy: vec!["127.0.0.1".to_string()],
+        rate_limit_retries: 0,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let dest = temp.path().join("python.tar.gz");
+    let size = client
+        .download_to(&server.url("python.tar.gz?raw=1"), &dest)
+        .unwrap();
+    assert_eq!(size, archive.len() as u64);
+    assert_eq!(std::fs::read(&dest).unwrap(), archive);
+    assert_eq!(client.get_text(&server.url("/notes.txt")).unwrap(), "v1");
+    let err = client.get_bytes(&server.url("/missing")).unwrap_err();
+    assert!(err.to_string().contains("404"), "{}", err);
+    assert_eq!(
+        server.requests(),
+        ["/python.tar.gz", "/notes.txt", "/missing"]
+    );
+}
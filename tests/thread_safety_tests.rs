@@ -0,0 +1,86 @@
+//! Tests for sharing packers and their types between threads
+
+use auroraview_pack::test_support::PackFixture;
+use auroraview_pack::{
+    ArtifactStore, AssetBundle, BundleBuilder, DoctorReport, Downloader, HttpClient, Manifest,
+    OtlpExporter, OverlayData, OverlayReader, OverlaySigner, PackConfig, PackContext, PackError,
+    PackOptions, PackOutput, PackPlan, Packer, TransformConfig,
+};
+use std::sync::Arc;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_public_types_are_send_sync() {
+    assert_send_sync::<Packer>();
+    assert_send_sync::<PackConfig>();
+    assert_send_sync::<Manifest>();
+    assert_send_sync::<PackContext>();
+    assert_send_sync::<PackOptions>();
+    assert_send_sync::<PackOutput>();
+    assert_send_sync::<PackPlan>();
+    assert_send_sync::<PackError>();
+    assert_send_sync::<DoctorReport>();
+    assert_send_sync::<OverlayData>();
+    assert_send_sync::<OverlaySigner>();
+    assert_send_sync::<BundleBuilder>();
+    assert_send_sync::<AssetBundle>();
+    assert_send_sync::<TransformConfig>();
+    assert_send_sync::<ArtifactStore>();
+    assert_send_sync::<Downloader>();
+    assert_send_sync::<HttpClient>();
+    assert_send_sync::<OtlpExporter>();
+}
+
+#[test]
+fn test_pack_two_configs_concurrently() {
+    let fixture = PackFixture::new().unwrap();
+    let frontend = fixture.frontend().unwrap();
+    let packers: Vec<_> = ["left", "right"]
+        .iter()
+        .map(|name| {
+            let config = fixture
+                .configure(PackConfig::frontend(&frontend).with_output(*name))
+                .unwrap()
+                .with_threads(2);
+            Packer::new(config)
+        })
+        .collect();
+
+    let outputs: Vec<PackOutput> = std::thread::scope(|scope| {
+        let handles: Vec<_> = packers
+            .iter()
+            .map(|packer| scope.spawn(|| packer.pack().unwrap()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_ne!(outputs[0].executable, outputs[1].executable);
+    for output in &outputs {
+        let archive = OverlayReader::open(&output.executable).unwrap().unwrap();
+        assert_eq!(archive.entries().len(), output.asset_count + 1);
+    }
+}
+
+#[test]
+fn test_shared_packer_packs_from_threads() {
+    let fixture = PackFixture::new().unwrap();
+    let config = fixture
+        .configure(PackConfig::frontend(fixture.frontend().unwrap()).with_output("shared"))
+        .unwrap();
+    let packer = Arc::new(Packer::new(config));
+
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let packer = Arc::clone(&packer);
+            std::thread::spawn(move || packer.pack().unwrap())
+        })
+        .collect();
+    let outputs: Vec<PackOutput> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Packs of one packer run one at a time and produce the same output
+    assert_eq!(outputs[0].executable, outputs[1].executable);
+    assert_eq!(outputs[0].size, outputs[1].size);
+    let overlay = PackFixture::read_overlay(&outputs[0].executable).unwrap();
+    assert_eq!(overlay.assets.len(), outputs[0].asset_count + 1);
+}
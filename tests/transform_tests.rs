@@ -0,0 +1,141 @@
+//! Tests for asset transforms during bundling
+
+use auroraview_pack::{AssetTransform, BundleBuilder, TransformConfig, TransformStep};
+use std::fs;
+use tempfile::TempDir;
+
+fn apply(config: &TransformConfig, path: &str, content: &str) -> String {
+    match config.transform(path, content.as_bytes()).unwrap() {
+        Some(output) => String::from_utf8(output).unwrap(),
+        None => content.to_string(),
+    }
+}
+
+#[test]
+fn test_minify_js_keeps_literals() {
+    let config = TransformConfig::default().with("js", &[TransformStep::Minify]);
+    let source = r#"/*! MIT License */
+// Greets the user
+function greet(name) {
+    /* the URL is not a comment */
+    const url = "https://example.com//path";
+    const re = /\/\/[a-z/]+/g;   // regex with slashes
+    const ratio = a / b / c;
+    const html = `<div>
+        ${name /* inline */}   //not a comment
+    </div>`;
+
+
+    return url + re.source + html + '\'//';
+}
+"#;
+    let output = apply(&config, "app.js", source);
+    assert_eq!(
+        output,
+        r#"/*! MIT License */
+function greet(name) {
+const url = "https://example.com//path";
+const re = /\/\/[a-z/]+/g;
+const ratio = a / b / c;
+const html = `<div>
+        ${name }   //not a comment
+    </div>`;
+return url + re.source + html + '\'//';
+}
+"#
+    );
+    assert!(output.len() < source.len());
+
+    // strip_comments keeps the layout
+    let config = TransformConfig::default().with("js", &[TransformStep::StripComments]);
+    assert_eq!(
+        apply(&config, "a.js", "let a = 1; // one\n  let b = 2;\n"),
+        "let a = 1; \n  let b = 2;\n"
+    );
+}
+
+#[test]
+fn test_minify_css_and_strip_html_comments() {
+    let config = TransformConfig::recommended();
+    let css = "/* theme */\nbody  >  .app {\n  margin : 0 ;\n  font: 12px \"A  B\";\n}\na :hover { width: calc(1px + 2px); }\n";
+    assert_eq!(
+        apply(&config, "style.css", css),
+        "body>.app{margin : 0;font: 12px \"A  B\"}a :hover{width: calc(1px + 2px)}"
+    );
+
+    let html = "<!DOCTYPE html>\n<!-- build 42 -->\n<html><!--[if IE]><p>IE</p><![endif]-->\n\
+                <script>var s = '<!-- kept -->';</script>\n<textarea><!-- text --></textarea></html>\n";
+    assert_eq!(
+        apply(&config, "index.html", html),
+        "<!DOCTYPE html>\n\n<html><!--[if IE]><p>IE</p><![endif]-->\n\
+         <script>var s = '<!-- kept -->';</script>\n<textarea><!-- text --></textarea></html>\n"
+    );
+
+    // Untransformed extensions are kept
+    assert!(config.transform("data.json", b"{ }").unwrap().is_none());
+}
+
+#[test]
+fn test_optimize_png_in_bundle() {
+    use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+
+    let temp = TempDir::new().unwrap();
+    let image = image::RgbaImage::from_fn(64, 64, |x, _| image::Rgba([x as u8, 0, 0, 255]));
+    let mut png = Vec::new();
+    image
+        .write_with_encoder(PngEncoder::new_with_quality(
+            &mut png,
+            CompressionType::Fast,
+            FilterType::NoFilter,
+        ))
+        .unwrap();
+    fs::write(temp.path().join("logo.png"), &png).unwrap();
+    fs::write(temp.path().join("broken.png"), "not a png").unwrap();
+    fs::write(temp.path().join("app.js"), "let a  =  1;   // one\n").unwrap();
+
+    let bundle = BundleBuilder::new(temp.path())
+        .with_transform(TransformConfig::recommended())
+        .build()
+        .unwrap();
+
+    let optimized = bundle.get("logo.png").unwrap();
+    assert!(optimized.len() < png.len());
+    let decoded = image::load_from_memory(optimized).unwrap().to_rgba8();
+    assert_eq!(decoded, image);
+    assert_eq!(bundle.get("broken.png").unwrap(), b"not a png");
+    assert_eq!(bundle.get("app.js").unwrap(), b"let a = 1;\n");
+}
+
+#[test]
+fn test_transform_config_validation() {
+    let config: TransformConfig = toml::from_str(
+        r#"
+jpeg_quality = 80
+
+[extensions]
+js = ["minify"]
+jpg = ["optimize"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.extensions["js"], [TransformStep::Minify]);
+    config.validate().unwrap();
+
+    let err = TransformConfig::default()
+        .with("html", &[TransformStep::Minify])
+        .validate()
+        .unwrap_err();
+    assert!(err.to_string().contains("'minify' does not apply to .html"));
+    let err = TransformConfig::default()
+        .with("wasm", &[TransformStep::Optimize])
+        .validate()
+        .unwrap_err();
+    assert!(err.to_string().contains("no transforms for .wasm"));
+    let err = TransformConfig {
+        jpeg_quality: 0,
+        ..Default::default()
+    }
+    .validate()
+    .unwrap_err();
+    assert!(err.to_string().contains("jpeg_quality"));
+}